@@ -0,0 +1,93 @@
+use serde::Serialize;
+use tauri::AppHandle;
+
+use crate::commands::export;
+use crate::commands::settings;
+use crate::error::AppError;
+
+// ─── Alt text ───────────────────────────────────────────────────
+//
+// Images without alt text are invisible to screen readers and show as
+// blank boxes in mail clients that block images. Publishing and scheduling
+// check for them: by default they only warn, with the
+// `publishing.alt_text_mode` setting at "block" they refuse to send, and
+// "off" skips the check.
+
+pub const ALT_TEXT_MODE_SETTING: &str = "publishing.alt_text_mode";
+
+#[derive(Debug, Serialize, Clone)]
+pub struct MissingAltText {
+    /// Position among the document's images, from 0
+    pub index: usize,
+    /// Image source; data URIs are cut down to their media type
+    pub src: String,
+    /// "missing" (no alt attribute), "empty" or "file_name"
+    pub reason: String,
+}
+
+/// "warn" (default), "block" or "off"
+pub(crate) fn alt_text_mode(app: &AppHandle) -> String {
+    settings::get_setting(app, ALT_TEXT_MODE_SETTING).unwrap_or_else(|| "warn".to_string())
+}
+
+/// Alt text that is just the uploaded file's name, e.g. "IMG_2041.jpg".
+fn looks_like_file_name(alt: &str) -> bool {
+    let lower = alt.trim().to_lowercase();
+    !lower.contains(' ')
+        && [".png", ".jpg", ".jpeg", ".gif", ".webp", ".svg", ".bmp"]
+            .iter()
+            .any(|ext| lower.ends_with(ext))
+}
+
+/// Images in `html` whose alt text is absent, blank or a file name.
+pub(crate) fn missing_alt_text(html: &str) -> Vec<MissingAltText> {
+    export::image_tags(html)
+        .into_iter()
+        .enumerate()
+        .filter_map(|(index, (src, alt))| {
+            let reason = match alt.as_deref().map(str::trim) {
+                None => "missing",
+                Some("") => "empty",
+                Some(alt) if looks_like_file_name(alt) => "file_name",
+                Some(_) => return None,
+            };
+            let src = match src.strip_prefix("data:") {
+                Some(data) => format!("data:{}", data.split([';', ',']).next().unwrap_or("")),
+                None => src,
+            };
+            Some(MissingAltText { index, src, reason: reason.to_string() })
+        })
+        .collect()
+}
+
+/// One-line summary for warnings and errors.
+pub(crate) fn describe(missing: &[MissingAltText]) -> String {
+    match missing.len() {
+        1 => "1 image has no alt text".to_string(),
+        n => format!("{} images have no alt text", n),
+    }
+}
+
+/// The pre-publish check: images missing alt text under the current mode,
+/// or an error when the mode blocks and `force` isn't set.
+pub(crate) fn enforce(app: &AppHandle, html: &str, force: bool) -> Result<Vec<MissingAltText>, AppError> {
+    let mode = alt_text_mode(app);
+    if mode == "off" {
+        return Ok(Vec::new());
+    }
+    let missing = missing_alt_text(html);
+    if !missing.is_empty() && mode == "block" && !force {
+        return Err(AppError::validation(format!(
+            "{}. Add alt text before publishing, or turn off the alt text check.",
+            describe(&missing)
+        ))
+        .with_details(serde_json::json!({ "missing_alt_text": missing })));
+    }
+    Ok(missing)
+}
+
+/// Images in `html_content` that need alt text, regardless of the mode.
+#[tauri::command]
+pub async fn check_alt_text(html_content: String) -> Result<Vec<MissingAltText>, AppError> {
+    Ok(missing_alt_text(&html_content))
+}
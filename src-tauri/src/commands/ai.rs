@@ -2,6 +2,11 @@ use serde::{Deserialize, Serialize};
 use tauri::{AppHandle, Emitter};
 use futures_util::StreamExt;
 
+use crate::commands::audit;
+use crate::db;
+use crate::error::AppError;
+use crate::permissions::{self, Permission};
+
 // ─── Types ───
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -47,7 +52,8 @@ pub struct AiUsage {
 // Store/retrieve AI provider configs from tauri-plugin-store
 
 #[tauri::command]
-pub async fn save_ai_provider(app: AppHandle, provider: AiProvider) -> Result<(), String> {
+pub async fn save_ai_provider(app: AppHandle, provider: AiProvider) -> Result<(), AppError> {
+    permissions::require(&app, Permission::ManageCredentials)?;
     // Use tauri-plugin-store to save provider config
     // Store key: "ai_provider:{id}"
     // Save all fields including api_key
@@ -56,11 +62,13 @@ pub async fn save_ai_provider(app: AppHandle, provider: AiProvider) -> Result<()
     let key = format!("provider:{}", provider.id);
     store.set(&key, serde_json::to_value(&provider).map_err(|e| e.to_string())?);
     store.save().map_err(|e| e.to_string())?;
+    let conn = db::get_db(&app)?;
+    audit::record(&app, &conn, "credential.stored", "ai_provider", Some(&provider.id), None)?;
     Ok(())
 }
 
 #[tauri::command]
-pub async fn get_ai_providers(app: AppHandle) -> Result<Vec<AiProvider>, String> {
+pub async fn get_ai_providers(app: AppHandle) -> Result<Vec<AiProvider>, AppError> {
     use tauri_plugin_store::StoreExt;
     let store = app.store("ai_providers.json").map_err(|e| e.to_string())?;
     let mut providers = Vec::new();
@@ -75,29 +83,38 @@ pub async fn get_ai_providers(app: AppHandle) -> Result<Vec<AiProvider>, String>
 }
 
 #[tauri::command]
-pub async fn delete_ai_provider(app: AppHandle, provider_id: String) -> Result<(), String> {
+pub async fn delete_ai_provider(app: AppHandle, provider_id: String) -> Result<(), AppError> {
+    permissions::require(&app, Permission::ManageCredentials)?;
     use tauri_plugin_store::StoreExt;
     let store = app.store("ai_providers.json").map_err(|e| e.to_string())?;
     let key = format!("provider:{}", provider_id);
     store.delete(&key);
     store.save().map_err(|e| e.to_string())?;
+    let conn = db::get_db(&app)?;
+    audit::record(&app, &conn, "credential.deleted", "ai_provider", Some(&provider_id), None)?;
     Ok(())
 }
 
 // ─── AI Chat Completion ───
 // Routes to the correct API based on provider_id
 
-#[tauri::command]
-pub async fn ai_chat(app: AppHandle, request: AiRequest) -> Result<AiResponse, String> {
-    // 1. Load provider config from store
+/// A saved provider config; transcription reuses the OpenAI key.
+pub(crate) fn load_provider(app: &AppHandle, provider_id: &str) -> Result<AiProvider, AppError> {
     use tauri_plugin_store::StoreExt;
     let store = app.store("ai_providers.json").map_err(|e| e.to_string())?;
-    let key = format!("provider:{}", request.provider_id);
-    let provider_value = store.get(&key).ok_or_else(|| format!("Provider '{}' not found", request.provider_id))?;
-    let provider: AiProvider = serde_json::from_value(provider_value.clone()).map_err(|e| e.to_string())?;
+    let key = format!("provider:{}", provider_id);
+    let provider_value =
+        store.get(&key).ok_or_else(|| AppError::not_found(format!("Provider '{}' not found", provider_id)))?;
+    Ok(serde_json::from_value(provider_value.clone()).map_err(|e| e.to_string())?)
+}
+
+#[tauri::command]
+pub async fn ai_chat(app: AppHandle, request: AiRequest) -> Result<AiResponse, AppError> {
+    // 1. Load provider config from store
+    let provider = load_provider(&app, &request.provider_id)?;
 
     // 2. Route to correct API
-    let client = reqwest::Client::new();
+    let client = crate::http::client("ai")?;
     let max_tokens = request.max_tokens.unwrap_or(2048);
     let temperature = request.temperature.unwrap_or(0.7);
 
@@ -106,7 +123,7 @@ pub async fn ai_chat(app: AppHandle, request: AiRequest) -> Result<AiResponse, S
         "openai" => call_openai(&client, &provider, &request, max_tokens, temperature).await,
         "gemini" => call_gemini(&client, &provider, &request, max_tokens, temperature).await,
         "openrouter" => call_openrouter(&client, &provider, &request, max_tokens, temperature).await,
-        _ => Err(format!("Unknown provider: {}", provider.id)),
+        _ => Err(AppError::validation(format!("Unknown provider: {}", provider.id))),
     }
 }
 
@@ -117,7 +134,7 @@ async fn call_anthropic(
     request: &AiRequest,
     max_tokens: u32,
     temperature: f32,
-) -> Result<AiResponse, String> {
+) -> Result<AiResponse, AppError> {
     let url = if provider.base_url.is_empty() {
         "https://api.anthropic.com/v1/messages".to_string()
     } else {
@@ -149,13 +166,17 @@ async fn call_anthropic(
         .json(&body)
         .send()
         .await
-        .map_err(|e| format!("Anthropic request failed: {}", e))?;
+        .map_err(|e| AppError::network("anthropic", "Anthropic request failed", e))?;
 
     let status = resp.status();
     let text = resp.text().await.map_err(|e| e.to_string())?;
 
     if !status.is_success() {
-        return Err(format!("Anthropic API error ({}): {}", status, text));
+        return Err(AppError::from_status(
+            "anthropic",
+            status,
+            format!("Anthropic API error ({}): {}", status, text),
+        ));
     }
 
     let json: serde_json::Value = serde_json::from_str(&text).map_err(|e| e.to_string())?;
@@ -181,7 +202,7 @@ async fn call_openai(
     request: &AiRequest,
     max_tokens: u32,
     temperature: f32,
-) -> Result<AiResponse, String> {
+) -> Result<AiResponse, AppError> {
     let url = if provider.base_url.is_empty() {
         "https://api.openai.com/v1/chat/completions".to_string()
     } else {
@@ -213,13 +234,17 @@ async fn call_openai(
         .json(&body)
         .send()
         .await
-        .map_err(|e| format!("OpenAI request failed: {}", e))?;
+        .map_err(|e| AppError::network("openai", "OpenAI request failed", e))?;
 
     let status = resp.status();
     let text = resp.text().await.map_err(|e| e.to_string())?;
 
     if !status.is_success() {
-        return Err(format!("OpenAI API error ({}): {}", status, text));
+        return Err(AppError::from_status(
+            "openai",
+            status,
+            format!("OpenAI API error ({}): {}", status, text),
+        ));
     }
 
     let json: serde_json::Value = serde_json::from_str(&text).map_err(|e| e.to_string())?;
@@ -245,7 +270,7 @@ async fn call_gemini(
     request: &AiRequest,
     max_tokens: u32,
     temperature: f32,
-) -> Result<AiResponse, String> {
+) -> Result<AiResponse, AppError> {
     let url = format!(
         "https://generativelanguage.googleapis.com/v1beta/models/{}:generateContent?key={}",
         provider.model, provider.api_key
@@ -285,13 +310,17 @@ async fn call_gemini(
         .json(&body)
         .send()
         .await
-        .map_err(|e| format!("Gemini request failed: {}", e))?;
+        .map_err(|e| AppError::network("gemini", "Gemini request failed", e))?;
 
     let status = resp.status();
     let text = resp.text().await.map_err(|e| e.to_string())?;
 
     if !status.is_success() {
-        return Err(format!("Gemini API error ({}): {}", status, text));
+        return Err(AppError::from_status(
+            "gemini",
+            status,
+            format!("Gemini API error ({}): {}", status, text),
+        ));
     }
 
     let json: serde_json::Value = serde_json::from_str(&text).map_err(|e| e.to_string())?;
@@ -319,7 +348,7 @@ async fn call_openrouter(
     request: &AiRequest,
     max_tokens: u32,
     temperature: f32,
-) -> Result<AiResponse, String> {
+) -> Result<AiResponse, AppError> {
     let url = if provider.base_url.is_empty() {
         "https://openrouter.ai/api/v1/chat/completions".to_string()
     } else {
@@ -351,13 +380,17 @@ async fn call_openrouter(
         .json(&body)
         .send()
         .await
-        .map_err(|e| format!("OpenRouter request failed: {}", e))?;
+        .map_err(|e| AppError::network("openrouter", "OpenRouter request failed", e))?;
 
     let status = resp.status();
     let text = resp.text().await.map_err(|e| e.to_string())?;
 
     if !status.is_success() {
-        return Err(format!("OpenRouter API error ({}): {}", status, text));
+        return Err(AppError::from_status(
+            "openrouter",
+            status,
+            format!("OpenRouter API error ({}): {}", status, text),
+        ));
     }
 
     let json: serde_json::Value = serde_json::from_str(&text).map_err(|e| e.to_string())?;
@@ -376,6 +409,161 @@ async fn call_openrouter(
     })
 }
 
+// ─── Alt text from images ───────────────────────────────────────
+
+const ALT_TEXT_PROMPT: &str = "Write alt text for this image in a newsletter. One plain sentence under 125 characters describing what matters in the image. No \"image of\" or \"picture of\", no quotes, no trailing commentary.";
+/// Images past this are scaled down before upload; providers cap the size
+const VISION_MAX_BYTES: usize = 3_500_000;
+const VISION_MAX_SIDE: u32 = 1568;
+
+/// (media type, base64 data) for `image`: a data URI, a local or
+/// uploaded image, or a remote URL, which is downloaded.
+async fn vision_image(app: &AppHandle, image: &str) -> Result<(String, String), AppError> {
+    let images_dir = crate::commands::images::images_dir(app).ok();
+    let mut bytes = match crate::commands::export::image_bytes(image, images_dir.as_deref()) {
+        Some(bytes) => bytes,
+        None if image.starts_with("http://") || image.starts_with("https://") => {
+            let resp = crate::http::client("ai")?
+                .get(image)
+                .send()
+                .await
+                .map_err(|e| AppError::network("ai", "Couldn't download the image", e))?;
+            if !resp.status().is_success() {
+                return Err(AppError::validation(format!("Couldn't download the image ({})", resp.status())));
+            }
+            resp.bytes().await.map_err(|e| e.to_string())?.to_vec()
+        }
+        None => return Err(AppError::validation("That image can't be read")),
+    };
+    let mut format = ::image::guess_format(&bytes).map_err(|_| AppError::validation("That file isn't a supported image"))?;
+    if bytes.len() > VISION_MAX_BYTES {
+        let decoded = ::image::load_from_memory(&bytes).map_err(|e| format!("Failed to decode image: {}", e))?;
+        let mut out = std::io::Cursor::new(Vec::new());
+        decoded
+            .thumbnail(VISION_MAX_SIDE, VISION_MAX_SIDE)
+            .to_rgb8()
+            .write_to(&mut out, ::image::ImageFormat::Jpeg)
+            .map_err(|e| format!("Failed to resize image: {}", e))?;
+        bytes = out.into_inner();
+        format = ::image::ImageFormat::Jpeg;
+    }
+    use base64::Engine;
+    Ok((format.to_mime_type().to_string(), base64::engine::general_purpose::STANDARD.encode(&bytes)))
+}
+
+/// Alt text for one image from a vision-capable model on `provider_id`.
+/// `context` (e.g. the surrounding paragraph) helps the model pick out
+/// what the image is there for.
+#[tauri::command]
+pub async fn ai_generate_alt_text(
+    app: AppHandle,
+    provider_id: String,
+    image: String,
+    context: Option<String>,
+) -> Result<String, AppError> {
+    let provider = load_provider(&app, &provider_id)?;
+    let (media_type, data) = vision_image(&app, &image).await?;
+    let prompt = match context.as_deref().map(str::trim).filter(|c| !c.is_empty()) {
+        Some(context) => format!("{}\n\nIt appears next to this text:\n{}", ALT_TEXT_PROMPT, context),
+        None => ALT_TEXT_PROMPT.to_string(),
+    };
+    let data_uri = format!("data:{};base64,{}", media_type, data);
+    let openai_content = serde_json::json!([
+        { "type": "text", "text": prompt },
+        { "type": "image_url", "image_url": { "url": data_uri } },
+    ]);
+
+    let client = crate::http::client("ai")?;
+    let base = provider.base_url.trim_end_matches('/');
+    let (label, request) = match provider.id.as_str() {
+        "claude" => {
+            let url = if base.is_empty() { "https://api.anthropic.com".to_string() } else { base.to_string() };
+            let body = serde_json::json!({
+                "model": provider.model,
+                "max_tokens": 200,
+                "messages": [{ "role": "user", "content": [
+                    { "type": "image", "source": { "type": "base64", "media_type": media_type, "data": data } },
+                    { "type": "text", "text": prompt },
+                ]}],
+            });
+            let request = client
+                .post(format!("{}/v1/messages", url))
+                .header("x-api-key", &provider.api_key)
+                .header("anthropic-version", "2023-06-01")
+                .json(&body);
+            ("anthropic", request)
+        }
+        "openai" => {
+            let url = if base.is_empty() { "https://api.openai.com".to_string() } else { base.to_string() };
+            let body = serde_json::json!({
+                "model": provider.model,
+                "max_tokens": 200,
+                "messages": [{ "role": "user", "content": openai_content }],
+            });
+            ("openai", client.post(format!("{}/v1/chat/completions", url)).bearer_auth(&provider.api_key).json(&body))
+        }
+        "openrouter" => {
+            let url = if base.is_empty() { "https://openrouter.ai".to_string() } else { base.to_string() };
+            let body = serde_json::json!({
+                "model": provider.model,
+                "max_tokens": 200,
+                "messages": [{ "role": "user", "content": openai_content }],
+            });
+            let request = client
+                .post(format!("{}/api/v1/chat/completions", url))
+                .bearer_auth(&provider.api_key)
+                .header("HTTP-Referer", "https://station.app")
+                .header("X-Title", "Station")
+                .json(&body);
+            ("openrouter", request)
+        }
+        "gemini" => {
+            let url = format!(
+                "https://generativelanguage.googleapis.com/v1beta/models/{}:generateContent?key={}",
+                provider.model, provider.api_key
+            );
+            let body = serde_json::json!({
+                "contents": [{ "role": "user", "parts": [
+                    { "inline_data": { "mime_type": media_type, "data": data } },
+                    { "text": prompt },
+                ]}],
+                "generationConfig": { "maxOutputTokens": 200 },
+            });
+            ("gemini", client.post(url).json(&body))
+        }
+        _ => return Err(AppError::validation(format!("Unknown provider: {}", provider.id))),
+    };
+
+    let resp = request
+        .send()
+        .await
+        .map_err(|e| AppError::network(label, "Alt text request failed", e))?;
+    let status = resp.status();
+    let text = resp.text().await.map_err(|e| e.to_string())?;
+    if !status.is_success() {
+        return Err(AppError::from_status(label, status, format!("Alt text request failed ({}): {}", status, text)));
+    }
+    let json: serde_json::Value = serde_json::from_str(&text).map_err(|e| e.to_string())?;
+    let alt = match label {
+        "anthropic" => json["content"][0]["text"].as_str(),
+        "gemini" => json["candidates"][0]["content"]["parts"][0]["text"].as_str(),
+        _ => json["choices"][0]["message"]["content"].as_str(),
+    }
+    .unwrap_or("")
+    .trim()
+    .trim_matches(['"', '\'', '\u{201c}', '\u{201d}'])
+    .trim()
+    .to_string();
+    if alt.is_empty() {
+        return Err(AppError::new(
+            crate::error::ErrorKind::Platform,
+            "The model didn't describe the image; check that it supports images",
+        )
+        .with_platform(label));
+    }
+    Ok(alt)
+}
+
 // ─── Streaming AI Chat ──────────────────────────────────────────
 
 #[derive(Debug, Clone, Serialize)]
@@ -396,18 +584,20 @@ pub async fn ai_chat_stream(
     app: AppHandle,
     request: AiRequest,
     request_id: String,
-) -> Result<(), String> {
+) -> Result<(), AppError> {
     use tauri_plugin_store::StoreExt;
     let store = app.store("ai_providers.json").map_err(|e| e.to_string())?;
     let key = format!("provider:{}", request.provider_id);
     let provider_value = store
         .get(&key)
-        .ok_or_else(|| format!("Provider '{}' not found", request.provider_id))?;
+        .ok_or_else(|| AppError::not_found(format!("Provider '{}' not found", request.provider_id)))?;
     let provider: AiProvider =
         serde_json::from_value(provider_value.clone()).map_err(|e| e.to_string())?;
 
     let max_tokens = request.max_tokens.unwrap_or(2048);
     let temperature = request.temperature.unwrap_or(0.7);
+    // Only the Gemini fallback uses this; the streaming paths build their own
+    let client = crate::http::client("ai")?;
 
     tokio::spawn(async move {
         let result = match provider.id.as_str() {
@@ -441,7 +631,6 @@ pub async fn ai_chat_stream(
             }
             "gemini" => {
                 // Gemini doesn't have simple SSE streaming — fall back to non-streaming
-                let client = reqwest::Client::new();
                 match call_gemini(&client, &provider, &request, max_tokens, temperature).await {
                     Ok(resp) => {
                         let _ = app.emit(
@@ -457,7 +646,7 @@ pub async fn ai_chat_stream(
                     Err(e) => Err(e),
                 }
             }
-            _ => Err(format!("Unknown provider: {}", provider.id)),
+            _ => Err(AppError::validation(format!("Unknown provider: {}", provider.id))),
         };
 
         if let Err(e) = result {
@@ -465,7 +654,7 @@ pub async fn ai_chat_stream(
                 "ai-stream-error",
                 StreamError {
                     request_id,
-                    error: e,
+                    error: e.to_string(),
                 },
             );
         }
@@ -481,7 +670,7 @@ async fn stream_anthropic(
     max_tokens: u32,
     temperature: f32,
     request_id: &str,
-) -> Result<(), String> {
+) -> Result<(), AppError> {
     let url = if provider.base_url.is_empty() {
         "https://api.anthropic.com/v1/messages".to_string()
     } else {
@@ -507,7 +696,7 @@ async fn stream_anthropic(
         body["system"] = serde_json::json!(system);
     }
 
-    let client = reqwest::Client::new();
+    let client = crate::http::client("ai")?;
     let resp = client
         .post(&url)
         .header("x-api-key", &provider.api_key)
@@ -516,11 +705,10 @@ async fn stream_anthropic(
         .json(&body)
         .send()
         .await
-        .map_err(|e| format!("Anthropic stream request failed: {}", e))?;
+        .map_err(|e| AppError::network("anthropic", "Anthropic stream request failed", e))?;
 
     if !resp.status().is_success() {
-        let text = resp.text().await.unwrap_or_default();
-        return Err(format!("Anthropic API error: {}", text));
+        return Err(AppError::from_response("anthropic", "Anthropic API error", resp).await);
     }
 
     let mut stream = resp.bytes_stream();
@@ -595,7 +783,7 @@ async fn stream_openai_compatible(
     temperature: f32,
     request_id: &str,
     is_openrouter: bool,
-) -> Result<(), String> {
+) -> Result<(), AppError> {
     let url = if provider.base_url.is_empty() {
         if is_openrouter {
             "https://openrouter.ai/api/v1/chat/completions".to_string()
@@ -627,7 +815,7 @@ async fn stream_openai_compatible(
         "stream": true,
     });
 
-    let client = reqwest::Client::new();
+    let client = crate::http::client("ai")?;
     let mut req = client
         .post(&url)
         .header("Authorization", format!("Bearer {}", provider.api_key))
@@ -643,11 +831,10 @@ async fn stream_openai_compatible(
         .json(&body)
         .send()
         .await
-        .map_err(|e| format!("Stream request failed: {}", e))?;
+        .map_err(|e| AppError::network(&provider.id, "Stream request failed", e))?;
 
     if !resp.status().is_success() {
-        let text = resp.text().await.unwrap_or_default();
-        return Err(format!("API error: {}", text));
+        return Err(AppError::from_response(&provider.id, "API error", resp).await);
     }
 
     let mut stream = resp.bytes_stream();
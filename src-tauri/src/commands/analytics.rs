@@ -0,0 +1,956 @@
+use chrono::{DateTime, Datelike, Utc};
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+use crate::commands::charts::{self, ChartSpec};
+use crate::commands::export::{build_pdf, html_to_plain_text, PdfExportOptions};
+use crate::commands::platform::{AnalyticsData, PostPerformance};
+use crate::commands::workspaces::{self, Scope};
+use crate::commands::windows;
+use crate::db;
+use crate::error::AppError;
+use crate::permissions::{self, Permission};
+use crate::query_cache::{self, Key};
+use crate::text::{self, CountMode};
+
+// ─── Ranges ─────────────────────────────────────────────────────
+
+/// Inclusive bounds, as RFC 3339 timestamps or plain `YYYY-MM-DD` dates
+/// (a bare `to` date covers the whole day).
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DateRange {
+    pub from: String,
+    pub to: String,
+}
+
+impl DateRange {
+    pub(crate) fn bounds(&self) -> Result<(DateTime<Utc>, DateTime<Utc>), AppError> {
+        let parse = |s: &str, end_of_day: bool| {
+            if let Ok(d) = DateTime::parse_from_rfc3339(s) {
+                return Ok(d.with_timezone(&Utc));
+            }
+            chrono::NaiveDate::parse_from_str(s, "%Y-%m-%d")
+                .ok()
+                .and_then(|day| {
+                    if end_of_day {
+                        day.and_hms_opt(23, 59, 59)
+                    } else {
+                        day.and_hms_opt(0, 0, 0)
+                    }
+                })
+                .map(|t| t.and_utc())
+                .ok_or_else(|| AppError::validation(format!("Invalid date: {}", s)))
+        };
+        let (from, to) = (parse(&self.from, false)?, parse(&self.to, true)?);
+        if to < from {
+            return Err(AppError::validation("Range ends before it starts"));
+        }
+        Ok((from, to))
+    }
+
+    /// Both bounds as RFC 3339, comparable with stored timestamps.
+    fn normalized(&self) -> Result<DateRange, AppError> {
+        let (from, to) = self.bounds()?;
+        Ok(DateRange {
+            from: from.to_rfc3339(),
+            to: to.to_rfc3339(),
+        })
+    }
+
+    /// The same-length period ending just before this one.
+    fn preceding(&self) -> Result<DateRange, AppError> {
+        let (from, to) = self.bounds()?;
+        let prev_to = from - chrono::Duration::seconds(1);
+        Ok(DateRange {
+            from: (prev_to - (to - from)).to_rfc3339(),
+            to: prev_to.to_rfc3339(),
+        })
+    }
+}
+
+// ─── Period comparison ──────────────────────────────────────────
+
+#[derive(Debug, Serialize, Clone)]
+pub struct MetricDelta {
+    pub current: f64,
+    pub previous: f64,
+    pub change: f64,
+    /// `None` when the previous value is zero
+    pub change_pct: Option<f64>,
+}
+
+impl MetricDelta {
+    fn new(current: f64, previous: f64) -> Self {
+        MetricDelta {
+            current,
+            previous,
+            change: current - previous,
+            change_pct: (previous != 0.0).then(|| (current - previous) / previous.abs() * 100.0),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct AnalyticsComparison {
+    pub range: DateRange,
+    pub previous_range: DateRange,
+    pub subscribers: MetricDelta,
+    pub open_rate: MetricDelta,
+    pub click_rate: MetricDelta,
+    pub revenue_cents: MetricDelta,
+    pub posts_published: MetricDelta,
+}
+
+struct PeriodTotals {
+    subscribers: f64,
+    open_rate: f64,
+    click_rate: f64,
+    revenue_cents: f64,
+    posts_published: f64,
+}
+
+/// Subscribers at the end of the range: the latest snapshot per account of
+/// the workspace, summed. Falls back to the unified subscriber table when
+/// no platform has been snapshotted yet.
+pub(crate) fn subscribers_at(conn: &Connection, scope: &Scope, to: &str, platform: Option<&str>) -> Result<f64, AppError> {
+    let from_snapshots: Option<i64> = conn.query_row(
+        "SELECT SUM(total_subscribers) FROM analytics_snapshots s
+         WHERE captured_at = (
+             SELECT MAX(captured_at) FROM analytics_snapshots
+             WHERE platform = s.platform AND account_id = s.account_id AND captured_at <= ?1
+         )
+         AND (?2 IS NULL OR platform = ?2) AND (platform || ':' || account_id) IN (SELECT value FROM json_each(?3))",
+        rusqlite::params![to, platform, scope.accounts],
+        |row| row.get(0),
+    )?;
+    if let Some(total) = from_snapshots {
+        return Ok(total as f64);
+    }
+
+    let local: i64 = conn.query_row(
+        "SELECT COUNT(DISTINCT s.id) FROM subscribers s
+         LEFT JOIN subscriber_platforms sp ON sp.subscriber_id = s.id
+         WHERE s.first_seen_at <= ?1 AND (?2 IS NULL OR sp.platform = ?2) AND s.workspace_id = ?3",
+        rusqlite::params![to, platform, scope.workspace_id],
+        |row| row.get(0),
+    )?;
+    Ok(local as f64)
+}
+
+fn period_totals(conn: &Connection, scope: &Scope, range: &DateRange, platform: Option<&str>) -> Result<PeriodTotals, AppError> {
+    let (open_rate, click_rate): (f64, f64) = conn.query_row(
+        "SELECT COALESCE(AVG(open_rate), 0), COALESCE(AVG(click_rate), 0) FROM analytics_snapshots
+         WHERE captured_at >= ?1 AND captured_at <= ?2 AND (?3 IS NULL OR platform = ?3)
+           AND (platform || ':' || account_id) IN (SELECT value FROM json_each(?4))",
+        rusqlite::params![range.from, range.to, platform, scope.accounts],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    )?;
+
+    // Revenue isn't tied to a platform account, so it ignores the filter
+    let revenue_cents: i64 = conn.query_row(
+        "SELECT COALESCE(SUM(CASE WHEN type != 'refund' THEN amount_cents ELSE -amount_cents END), 0)
+         FROM revenue_entries WHERE recorded_at >= ?1 AND recorded_at <= ?2 AND workspace_id = ?3",
+        rusqlite::params![range.from, range.to, scope.workspace_id],
+        |row| row.get(0),
+    )?;
+
+    let posts_published: i64 = match platform {
+        Some(platform) => conn.query_row(
+            "SELECT COUNT(DISTINCT sp.document_id) FROM scheduled_posts sp JOIN documents d ON d.id = sp.document_id
+             WHERE sp.status = 'published' AND sp.platform = ?3 AND sp.updated_at >= ?1 AND sp.updated_at <= ?2
+               AND d.workspace_id = ?4",
+            rusqlite::params![range.from, range.to, platform, scope.workspace_id],
+            |row| row.get(0),
+        )?,
+        None => conn.query_row(
+            "SELECT COUNT(*) FROM documents
+             WHERE status = 'published' AND published_at >= ?1 AND published_at <= ?2 AND workspace_id = ?3",
+            rusqlite::params![range.from, range.to, scope.workspace_id],
+            |row| row.get(0),
+        )?,
+    };
+
+    Ok(PeriodTotals {
+        subscribers: subscribers_at(conn, scope, &range.to, platform)?,
+        open_rate,
+        click_rate,
+        revenue_cents: revenue_cents as f64,
+        posts_published: posts_published as f64,
+    })
+}
+
+/// Headline numbers for `range` against `previous_range` (by default the
+/// same-length period just before it), so each dashboard figure can show
+/// its change without a second round of queries. Includes revenue, so it
+/// needs the view_revenue permission.
+#[tauri::command]
+pub async fn get_analytics_comparison(
+    app: AppHandle,
+    range: DateRange,
+    previous_range: Option<DateRange>,
+    platform: Option<String>,
+) -> Result<AnalyticsComparison, AppError> {
+    permissions::require(&app, Permission::ViewRevenue)?;
+    let range = range.normalized()?;
+    let previous_range = match previous_range {
+        Some(prev) => prev.normalized()?,
+        None => range.preceding()?,
+    };
+
+    let scope = workspaces::scope(&app);
+    let key = Key::new("get_analytics_comparison", &(&range, &previous_range, &platform, &scope));
+    query_cache::cached(key, COMPARISON_TABLES, || {
+        let conn = db::get_db(&app)?;
+        let current = period_totals(&conn, &scope, &range, platform.as_deref())?;
+        let previous = period_totals(&conn, &scope, &previous_range, platform.as_deref())?;
+
+        Ok(AnalyticsComparison {
+            subscribers: MetricDelta::new(current.subscribers, previous.subscribers),
+            open_rate: MetricDelta::new(current.open_rate, previous.open_rate),
+            click_rate: MetricDelta::new(current.click_rate, previous.click_rate),
+            revenue_cents: MetricDelta::new(current.revenue_cents, previous.revenue_cents),
+            posts_published: MetricDelta::new(current.posts_published, previous.posts_published),
+            range,
+            previous_range,
+        })
+    })
+}
+
+/// What `period_totals` reads
+const COMPARISON_TABLES: &[&str] =
+    &["analytics_snapshots", "subscribers", "subscriber_platforms", "revenue_entries", "scheduled_posts", "documents"];
+
+// ─── Publishing heatmap ─────────────────────────────────────────
+
+#[derive(Debug, Serialize, Clone)]
+pub struct HeatmapDay {
+    /// YYYY-MM-DD
+    pub date: String,
+    pub posts_published: i64,
+    pub words_written: i64,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct PublishingHeatmap {
+    pub year: i32,
+    /// Every day of the year, in order
+    pub days: Vec<HeatmapDay>,
+    pub total_posts: i64,
+    pub total_words: i64,
+    pub max_posts: i64,
+    pub max_words: i64,
+    /// Longest run of consecutive days with any activity
+    pub longest_streak: i64,
+}
+
+/// Words added per day: each document's saved versions (then its current
+/// state) in order, counting only increases so edits that cut text don't
+/// go negative.
+fn words_by_day(
+    conn: &Connection,
+    workspace_id: &str,
+    year_start: &str,
+    year_end: &str,
+    mode: CountMode,
+) -> Result<std::collections::HashMap<String, i64>, AppError> {
+    let mut stmt = conn.prepare(
+        "SELECT document_id, html_content, created_at, 0 AS current FROM document_versions
+         WHERE document_id IN (SELECT id FROM documents WHERE updated_at >= ?1 AND workspace_id = ?2)
+         UNION ALL
+         SELECT id, html_content, updated_at, 1 FROM documents WHERE updated_at >= ?1 AND workspace_id = ?2
+         ORDER BY 1, 4, 3",
+    )?;
+    let rows = stmt.query_map(rusqlite::params![year_start, workspace_id], |row| {
+        Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get::<_, String>(2)?))
+    })?;
+
+    let mut by_day = std::collections::HashMap::new();
+    let mut last: Option<(String, i64)> = None;
+    for (doc_id, html, at) in rows.filter_map(|r| r.ok()) {
+        let words = text::count_text(&html_to_plain_text(&html), mode) as i64;
+        let previous = match &last {
+            Some((id, n)) if *id == doc_id => *n,
+            _ => 0,
+        };
+        if words > previous && at.as_str() >= year_start && at.as_str() <= year_end {
+            *by_day.entry(at.chars().take(10).collect()).or_insert(0) += words - previous;
+        }
+        last = Some((doc_id, words));
+    }
+    Ok(by_day)
+}
+
+/// Per-day posts published and words written for a year, for the
+/// contribution-graph view. A post published to three platforms counts
+/// three times; documents marked published outside the scheduler count
+/// once.
+#[tauri::command]
+pub async fn get_publishing_heatmap(app: AppHandle, year: i32) -> Result<PublishingHeatmap, AppError> {
+    let mode = text::count_mode(&app);
+    let workspace_id = workspaces::current(&app);
+    let key = Key::new("get_publishing_heatmap", &(year, mode, &workspace_id));
+    query_cache::cached(key, &["scheduled_posts", "documents", "document_versions"], || {
+        let conn = db::get_db(&app)?;
+        build_heatmap(&conn, &workspace_id, year, mode)
+    })
+}
+
+fn year_bounds(year: i32) -> Result<(chrono::NaiveDate, chrono::NaiveDate), AppError> {
+    let first = chrono::NaiveDate::from_ymd_opt(year, 1, 1)
+        .ok_or_else(|| AppError::validation(format!("Invalid year: {}", year)))?;
+    let last = chrono::NaiveDate::from_ymd_opt(year, 12, 31)
+        .ok_or_else(|| AppError::validation(format!("Invalid year: {}", year)))?;
+    Ok((first, last))
+}
+
+/// Timestamps bracketing `first..=last`, comparable with stored RFC 3339 strings.
+fn year_span(first: chrono::NaiveDate, last: chrono::NaiveDate) -> (String, String) {
+    (format!("{}T00:00:00", first), format!("{}T23:59:59~", last))
+}
+
+fn build_heatmap(conn: &Connection, workspace_id: &str, year: i32, mode: CountMode) -> Result<PublishingHeatmap, AppError> {
+    let (first, last) = year_bounds(year)?;
+    let (year_start, year_end) = year_span(first, last);
+
+    let mut posts: std::collections::HashMap<String, i64> = std::collections::HashMap::new();
+    {
+        let mut stmt = conn.prepare(
+            "SELECT substr(sp.updated_at, 1, 10), COUNT(*) FROM scheduled_posts sp
+             JOIN documents d ON d.id = sp.document_id
+             WHERE sp.status = 'published' AND sp.updated_at >= ?1 AND sp.updated_at <= ?2 AND d.workspace_id = ?3
+             GROUP BY 1
+             UNION ALL
+             SELECT substr(published_at, 1, 10), COUNT(*) FROM documents d
+             WHERE status = 'published' AND published_at >= ?1 AND published_at <= ?2 AND d.workspace_id = ?3
+               AND NOT EXISTS (
+                   SELECT 1 FROM scheduled_posts sp WHERE sp.document_id = d.id AND sp.status = 'published'
+               )
+             GROUP BY 1",
+        )?;
+        let rows = stmt.query_map(rusqlite::params![year_start, year_end, workspace_id], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?))
+        })?;
+        for (day, count) in rows.filter_map(|r| r.ok()) {
+            *posts.entry(day).or_insert(0) += count;
+        }
+    }
+    let words = words_by_day(conn, workspace_id, &year_start, &year_end, mode)?;
+
+    let mut days = Vec::new();
+    let (mut streak, mut longest_streak) = (0i64, 0i64);
+    for date in first.iter_days().take_while(|d| *d <= last) {
+        let key = date.format("%Y-%m-%d").to_string();
+        let day = HeatmapDay {
+            posts_published: posts.get(&key).copied().unwrap_or(0),
+            words_written: words.get(&key).copied().unwrap_or(0),
+            date: key,
+        };
+        if day.posts_published > 0 || day.words_written > 0 {
+            streak += 1;
+            longest_streak = longest_streak.max(streak);
+        } else {
+            streak = 0;
+        }
+        days.push(day);
+    }
+
+    Ok(PublishingHeatmap {
+        year,
+        total_posts: days.iter().map(|d| d.posts_published).sum(),
+        total_words: days.iter().map(|d| d.words_written).sum(),
+        max_posts: days.iter().map(|d| d.posts_published).max().unwrap_or(0),
+        max_words: days.iter().map(|d| d.words_written).max().unwrap_or(0),
+        longest_streak,
+        days,
+    })
+}
+
+// ─── Writing stats ──────────────────────────────────────────────
+//
+// Every save records the change in the document's word count against the
+// day, so output can be tracked without replaying version history the way
+// the heatmap does. Days are UTC, like the stored timestamps.
+
+/// Longest range `get_writing_stats` covers
+const MAX_WRITING_STATS_DAYS: i64 = 3660;
+const WEEKDAYS: [&str; 7] = ["Monday", "Tuesday", "Wednesday", "Thursday", "Friday", "Saturday", "Sunday"];
+
+#[derive(Debug, Serialize, Clone)]
+pub struct WritingDay {
+    /// YYYY-MM-DD
+    pub date: String,
+    /// Net words; negative on a day spent cutting
+    pub words: i64,
+    pub documents: i64,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct WritingStats {
+    /// Every day of the range, in order
+    pub days: Vec<WritingDay>,
+    pub total_words: i64,
+    /// Over every day of the range, idle ones included
+    pub daily_average: f64,
+    /// Consecutive days with words written, ending at the range's end
+    /// (or yesterday, while today is still empty)
+    pub current_streak: i64,
+    pub longest_streak: i64,
+    /// Up to five most productive days, best first
+    pub busiest_days: Vec<WritingDay>,
+    /// Weekday with the most words in total, e.g. "Tuesday"
+    pub busiest_weekday: Option<String>,
+}
+
+/// Add a save's word count change to today's tally for the document.
+pub(crate) fn record_words(conn: &Connection, document_id: &str, delta: i64) {
+    if delta == 0 {
+        return;
+    }
+    let now = Utc::now();
+    conn.execute(
+        "INSERT INTO writing_stats (date, document_id, words_delta, updated_at) VALUES (?1, ?2, ?3, ?4)
+         ON CONFLICT(date, document_id) DO UPDATE SET
+             words_delta = words_delta + excluded.words_delta, updated_at = excluded.updated_at",
+        rusqlite::params![now.format("%Y-%m-%d").to_string(), document_id, delta, now.to_rfc3339()],
+    )
+    .ok();
+}
+
+fn build_writing_stats(conn: &Connection, first: chrono::NaiveDate, last: chrono::NaiveDate) -> Result<WritingStats, AppError> {
+    let mut by_day: std::collections::HashMap<String, (i64, i64)> = std::collections::HashMap::new();
+    {
+        let mut stmt = conn.prepare(
+            "SELECT date, SUM(words_delta), COUNT(DISTINCT document_id) FROM writing_stats
+             WHERE date >= ?1 AND date <= ?2 GROUP BY date",
+        )?;
+        let rows = stmt.query_map(rusqlite::params![first.to_string(), last.to_string()], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?, row.get::<_, i64>(2)?))
+        })?;
+        for (date, words, documents) in rows.filter_map(|r| r.ok()) {
+            by_day.insert(date, (words, documents));
+        }
+    }
+
+    let mut days = Vec::new();
+    let mut weekdays = [0i64; 7];
+    let (mut streak, mut longest_streak) = (0i64, 0i64);
+    for date in first.iter_days().take_while(|d| *d <= last) {
+        let key = date.format("%Y-%m-%d").to_string();
+        let (words, documents) = by_day.get(&key).copied().unwrap_or((0, 0));
+        if words > 0 {
+            streak += 1;
+            longest_streak = longest_streak.max(streak);
+        } else {
+            streak = 0;
+        }
+        weekdays[date.weekday().num_days_from_monday() as usize] += words;
+        days.push(WritingDay { date: key, words, documents });
+    }
+
+    let today = Utc::now().format("%Y-%m-%d").to_string();
+    let current_streak = days
+        .iter()
+        .rev()
+        .skip_while(|d| d.date == today && d.words <= 0)
+        .take_while(|d| d.words > 0)
+        .count() as i64;
+    let mut busiest_days: Vec<WritingDay> = days.iter().filter(|d| d.words > 0).cloned().collect();
+    busiest_days.sort_by(|a, b| b.words.cmp(&a.words).then_with(|| a.date.cmp(&b.date)));
+    busiest_days.truncate(5);
+    let busiest_weekday = (0..7)
+        .filter(|i| weekdays[*i] > 0)
+        .max_by_key(|i| (weekdays[*i], std::cmp::Reverse(*i)))
+        .map(|i| WEEKDAYS[i].to_string());
+
+    let total_words = days.iter().map(|d| d.words).sum();
+    Ok(WritingStats {
+        daily_average: total_words as f64 / days.len().max(1) as f64,
+        total_words,
+        current_streak,
+        longest_streak,
+        busiest_days,
+        busiest_weekday,
+        days,
+    })
+}
+
+/// Daily word counts, streaks and busiest days over `range`.
+#[tauri::command]
+pub async fn get_writing_stats(app: AppHandle, range: DateRange) -> Result<WritingStats, AppError> {
+    let (from, to) = range.bounds()?;
+    let (first, last) = (from.date_naive(), to.date_naive());
+    if (last - first).num_days() > MAX_WRITING_STATS_DAYS {
+        return Err(AppError::validation("Pick a range of ten years or less"));
+    }
+    let conn = db::get_db(&app)?;
+    build_writing_stats(&conn, first, last)
+}
+
+// ─── Year in review ─────────────────────────────────────────────
+
+#[derive(Debug, Serialize, Clone)]
+pub struct YearReview {
+    pub year: i32,
+    pub total_words: i64,
+    /// Distinct documents published during the year
+    pub issues_published: i64,
+    pub longest_streak: i64,
+    pub busiest_day: Option<String>,
+    pub top_posts: Vec<PostPerformance>,
+    pub subscribers_start: i64,
+    pub subscribers_end: i64,
+    pub revenue_cents: i64,
+    /// "YYYY-MM" with the highest net revenue
+    pub best_revenue_month: Option<String>,
+    /// Subscribers at the end of each month so far, January first
+    pub monthly_subscribers: Vec<i64>,
+    /// Net revenue of each month so far, January first
+    pub monthly_revenue_cents: Vec<i64>,
+    /// Standalone, shareable HTML page
+    pub html: String,
+    /// Present when requested with `format: "pdf"`
+    pub pdf: Option<Vec<u8>>,
+    /// 1200×630 SVG summary card, when requested
+    pub social_card_svg: Option<String>,
+}
+
+pub(crate) fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+pub(crate) fn thousands(n: i64) -> String {
+    let digits = n.abs().to_string();
+    let mut out = String::new();
+    for (i, ch) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i) % 3 == 0 {
+            out.push(',');
+        }
+        out.push(ch);
+    }
+    if n < 0 {
+        format!("-{}", out)
+    } else {
+        out
+    }
+}
+
+fn dollars(cents: i64) -> String {
+    format!("${}", thousands(cents / 100))
+}
+
+/// Best-performing posts published in `year`, from the most recent
+/// analytics snapshot of each account (later snapshots have the most
+/// settled open/click counts).
+/// Per-post numbers from the latest snapshot of each of the workspace's
+/// accounts, one per post.
+fn latest_post_performance(conn: &Connection, scope: &Scope) -> Result<Vec<PostPerformance>, AppError> {
+    let mut stmt = conn.prepare(
+        "SELECT data_json FROM analytics_snapshots s
+         WHERE captured_at = (
+             SELECT MAX(captured_at) FROM analytics_snapshots
+             WHERE platform = s.platform AND account_id = s.account_id
+         )
+         AND (platform || ':' || account_id) IN (SELECT value FROM json_each(?1))",
+    )?;
+    let rows = stmt.query_map(rusqlite::params![scope.accounts], |row| row.get::<_, String>(0))?;
+
+    let mut seen = std::collections::HashSet::new();
+    Ok(rows
+        .filter_map(|r| r.ok())
+        .filter_map(|json| serde_json::from_str::<AnalyticsData>(&json).ok())
+        .flat_map(|data| data.recent_posts)
+        .filter(|p| seen.insert((p.platform.clone(), p.id.clone())))
+        .collect())
+}
+
+fn top_posts(conn: &Connection, scope: &Scope, year: i32, limit: usize) -> Result<Vec<PostPerformance>, AppError> {
+    let prefix = year.to_string();
+    let mut posts: Vec<PostPerformance> = latest_post_performance(conn, scope)?
+        .into_iter()
+        .filter(|p| p.published_at.starts_with(&prefix))
+        .collect();
+    posts.sort_by_key(|p| std::cmp::Reverse(p.opens + p.clicks));
+    posts.truncate(limit);
+    Ok(posts)
+}
+
+/// Month names for the months `values` covers, January first.
+fn month_labels(values: &[i64]) -> Vec<String> {
+    (1..=values.len() as u32)
+        .filter_map(|m| chrono::NaiveDate::from_ymd_opt(2000, m, 1))
+        .map(|d| d.format("%b").to_string())
+        .collect()
+}
+
+fn review_body(r: &YearReview, chart_text: bool) -> Result<String, AppError> {
+    let mut html = String::new();
+    html.push_str(&format!(
+        "<p>{} words written across {} published issues.</p>",
+        thousands(r.total_words),
+        r.issues_published
+    ));
+
+    html.push_str("<h2>Highlights</h2><ul>");
+    html.push_str(&format!("<li>Longest writing streak: {} days</li>", r.longest_streak));
+    if let Some(day) = &r.busiest_day {
+        html.push_str(&format!("<li>Busiest day: {}</li>", escape(day)));
+    }
+    html.push_str(&format!(
+        "<li>Audience: {} to {} subscribers ({:+})</li>",
+        thousands(r.subscribers_start),
+        thousands(r.subscribers_end),
+        r.subscribers_end - r.subscribers_start
+    ));
+    html.push_str(&format!("<li>Revenue: {}</li>", dollars(r.revenue_cents)));
+    if let Some(month) = &r.best_revenue_month {
+        html.push_str(&format!("<li>Best revenue month: {}</li>", escape(month)));
+    }
+    html.push_str("</ul>");
+
+    if !r.monthly_subscribers.is_empty() {
+        let values = r.monthly_subscribers.iter().map(|n| *n as f64).collect();
+        let chart = ChartSpec::single("line", month_labels(&r.monthly_subscribers), "Subscribers", values, "number");
+        html.push_str(&format!(
+            "<h2>Subscribers</h2><p><img src=\"{}\" alt=\"Subscribers by month\"></p>",
+            charts::png_data_uri(&chart, chart_text)?
+        ));
+    }
+    if r.monthly_revenue_cents.iter().any(|c| *c != 0) {
+        let values = r.monthly_revenue_cents.iter().map(|c| *c as f64).collect();
+        let chart = ChartSpec::single("bar", month_labels(&r.monthly_revenue_cents), "Revenue", values, "dollars");
+        html.push_str(&format!(
+            "<h2>Revenue</h2><p><img src=\"{}\" alt=\"Revenue by month\"></p>",
+            charts::png_data_uri(&chart, chart_text)?
+        ));
+    }
+
+    if !r.top_posts.is_empty() {
+        html.push_str("<h2>Top posts</h2><table><tr><th>Post</th><th>Platform</th><th>Opens</th><th>Clicks</th></tr>");
+        for post in &r.top_posts {
+            html.push_str(&format!(
+                "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+                escape(&post.title),
+                escape(&post.platform),
+                thousands(post.opens as i64),
+                thousands(post.clicks as i64)
+            ));
+        }
+        html.push_str("</table>");
+    }
+    Ok(html)
+}
+
+fn social_card(r: &YearReview) -> String {
+    let stats = [
+        (thousands(r.total_words), "words"),
+        (r.issues_published.to_string(), "issues"),
+        (format!("{:+}", r.subscribers_end - r.subscribers_start), "subscribers"),
+        (dollars(r.revenue_cents), "revenue"),
+    ];
+    let mut cells = String::new();
+    for (i, (value, label)) in stats.iter().enumerate() {
+        let x = 90 + i as i64 * 270;
+        cells.push_str(&format!(
+            r##"<text x="{x}" y="380" font-size="56" font-weight="700" fill="#ffffff">{}</text><text x="{x}" y="430" font-size="26" fill="#c4b5fd">{}</text>"##,
+            escape(value),
+            label
+        ));
+    }
+    format!(
+        r##"<svg xmlns="http://www.w3.org/2000/svg" width="1200" height="630" viewBox="0 0 1200 630" font-family="Inter, Helvetica, Arial, sans-serif"><rect width="1200" height="630" fill="#1e1b4b"/><rect x="0" y="600" width="1200" height="30" fill="#7c3aed"/><text x="90" y="200" font-size="72" font-weight="700" fill="#ffffff">My {} in writing</text>{}</svg>"##,
+        r.year, cells
+    )
+}
+
+/// Compile the year's writing, audience and revenue numbers into a
+/// shareable report. `format` is "html" (default) or "pdf". Needs the
+/// view_revenue permission.
+#[tauri::command]
+pub async fn generate_year_review(
+    app: AppHandle,
+    year: i32,
+    format: Option<String>,
+    include_social_card: Option<bool>,
+) -> Result<YearReview, AppError> {
+    permissions::require(&app, Permission::ViewRevenue)?;
+    let mut review = {
+        let scope = workspaces::scope(&app);
+        let conn = db::get_db(&app)?;
+        let (first, last) = year_bounds(year)?;
+        let (year_start, year_end) = year_span(first, last);
+        let heatmap = build_heatmap(&conn, &scope.workspace_id, year, text::count_mode(&app))?;
+
+        let issues_published: i64 = conn.query_row(
+            "SELECT COUNT(*) FROM documents
+             WHERE status = 'published' AND published_at >= ?1 AND published_at <= ?2 AND workspace_id = ?3",
+            rusqlite::params![year_start, year_end, scope.workspace_id],
+            |row| row.get(0),
+        )?;
+        let revenue_cents: i64 = conn.query_row(
+            "SELECT COALESCE(SUM(CASE WHEN type != 'refund' THEN amount_cents ELSE -amount_cents END), 0)
+             FROM revenue_entries WHERE recorded_at >= ?1 AND recorded_at <= ?2 AND workspace_id = ?3",
+            rusqlite::params![year_start, year_end, scope.workspace_id],
+            |row| row.get(0),
+        )?;
+        let best_revenue_month: Option<String> = conn
+            .query_row(
+                "SELECT strftime('%Y-%m', recorded_at) AS month,
+                        SUM(CASE WHEN type != 'refund' THEN amount_cents ELSE -amount_cents END) AS net
+                 FROM revenue_entries WHERE recorded_at >= ?1 AND recorded_at <= ?2 AND workspace_id = ?3
+                 GROUP BY month ORDER BY net DESC LIMIT 1",
+                rusqlite::params![year_start, year_end, scope.workspace_id],
+                |row| row.get(0),
+            )
+            .ok();
+        let before_year = (first - chrono::Duration::days(1)).format("%Y-%m-%dT23:59:59~").to_string();
+
+        // Months up to the current one, for the charts
+        let today = Utc::now().date_naive();
+        let months = if year == today.year() { today.month() } else if year < today.year() { 12 } else { 0 };
+        let mut monthly_subscribers = Vec::new();
+        for month in 1..=months {
+            let next = chrono::NaiveDate::from_ymd_opt(year, month, 1)
+                .and_then(|d| d.checked_add_months(chrono::Months::new(1)))
+                .ok_or_else(|| AppError::validation(format!("Invalid year: {}", year)))?;
+            let month_end = (next - chrono::Duration::days(1)).format("%Y-%m-%dT23:59:59~").to_string();
+            monthly_subscribers.push(subscribers_at(&conn, &scope, &month_end, None)? as i64);
+        }
+        let mut monthly_revenue_cents = vec![0; months as usize];
+        let mut stmt = conn.prepare(
+            "SELECT CAST(strftime('%m', recorded_at) AS INTEGER) AS month,
+                    SUM(CASE WHEN type != 'refund' THEN amount_cents ELSE -amount_cents END)
+             FROM revenue_entries WHERE recorded_at >= ?1 AND recorded_at <= ?2 AND workspace_id = ?3
+             GROUP BY month",
+        )?;
+        let rows = stmt.query_map(rusqlite::params![year_start, year_end, scope.workspace_id], |row| {
+            Ok((row.get::<_, i64>(0)?, row.get(1)?))
+        })?;
+        for (month, net) in rows.filter_map(|r| r.ok()) {
+            if let Some(slot) = monthly_revenue_cents.get_mut(month as usize - 1) {
+                *slot = net;
+            }
+        }
+
+        YearReview {
+            year,
+            total_words: heatmap.total_words,
+            issues_published,
+            longest_streak: heatmap.longest_streak,
+            busiest_day: heatmap
+                .days
+                .iter()
+                .filter(|d| d.posts_published + d.words_written > 0)
+                .max_by_key(|d| (d.posts_published, d.words_written))
+                .map(|d| d.date.clone()),
+            top_posts: top_posts(&conn, &scope, year, 5)?,
+            subscribers_start: subscribers_at(&conn, &scope, &before_year, None)? as i64,
+            subscribers_end: subscribers_at(&conn, &scope, &year_end, None)? as i64,
+            revenue_cents,
+            best_revenue_month,
+            monthly_subscribers,
+            monthly_revenue_cents,
+            html: String::new(),
+            pdf: None,
+            social_card_svg: None,
+        }
+    };
+
+    let title = format!("{} Year in Review", year);
+    let body = review_body(&review, charts::chart_text(&app))?;
+    review.html = format!(
+        "<!DOCTYPE html><html><head><meta charset=\"utf-8\"><title>{title}</title><style>body{{font-family:Georgia,serif;max-width:720px;margin:48px auto;padding:0 24px;color:#1f2937;line-height:1.6}}h1,h2{{font-family:Helvetica,Arial,sans-serif}}table{{width:100%;border-collapse:collapse}}th,td{{text-align:left;padding:6px 8px;border-bottom:1px solid #e5e7eb}}</style></head><body><h1>{title}</h1>{body}</body></html>"
+    );
+
+    if format.as_deref() == Some("pdf") {
+        let options = PdfExportOptions { page_numbers: true, ..Default::default() };
+        let pdf = tokio::task::spawn_blocking(move || build_pdf(&title, &body, None, None, options))
+            .await
+            .map_err(|e| format!("Export task failed: {}", e))??;
+        review.pdf = Some(pdf);
+    }
+    if include_social_card.unwrap_or(false) {
+        review.social_card_svg = Some(social_card(&review));
+    }
+
+    if let Ok(conn) = db::get_db(&app) {
+        db::log_activity(&conn, "report.year_review", "report", None, Some(&format!("Generated {} year in review", year)));
+    }
+    Ok(review)
+}
+
+// ─── Digest ─────────────────────────────────────────────────────
+
+const DIGEST_EXCERPT_CHARS: usize = 220;
+
+#[derive(Debug, Serialize, Clone)]
+pub struct DigestItem {
+    pub document_id: String,
+    pub title: String,
+    pub published_at: String,
+    pub url: Option<String>,
+    pub excerpt: String,
+    /// Opens + clicks from the latest analytics snapshot, when matched
+    pub engagement: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct Digest {
+    /// The new draft document
+    pub document_id: String,
+    pub title: String,
+    /// First and last day covered, "YYYY-MM-DD"
+    pub from: String,
+    pub to: String,
+    pub items: Vec<DigestItem>,
+}
+
+/// First and last day of `period`: "week" (last full Monday-Sunday week),
+/// "month" (last full calendar month) or a "YYYY-MM" month, with a label.
+fn digest_period(period: &str) -> Result<(chrono::NaiveDate, chrono::NaiveDate, String), AppError> {
+    use chrono::Datelike;
+    let today = Utc::now().date_naive();
+    let month_bounds = |first: chrono::NaiveDate| {
+        let next = first
+            .checked_add_months(chrono::Months::new(1))
+            .ok_or_else(|| AppError::validation("Invalid month"))?;
+        Ok::<_, AppError>((first, next - chrono::Duration::days(1), first.format("%B %Y").to_string()))
+    };
+    match period {
+        "week" => {
+            let this_monday = today - chrono::Duration::days(today.weekday().num_days_from_monday() as i64);
+            let first = this_monday - chrono::Duration::days(7);
+            let last = this_monday - chrono::Duration::days(1);
+            Ok((first, last, format!("the week of {}", first.format("%B %-d, %Y"))))
+        }
+        "month" => {
+            let this_month = today.with_day(1).unwrap_or(today);
+            let first = this_month
+                .checked_sub_months(chrono::Months::new(1))
+                .ok_or_else(|| AppError::validation("Invalid month"))?;
+            month_bounds(first)
+        }
+        other => {
+            let first = chrono::NaiveDate::parse_from_str(&format!("{}-01", other), "%Y-%m-%d").map_err(|_| {
+                AppError::validation(format!("Invalid period '{}', expected week, month or YYYY-MM", other))
+            })?;
+            month_bounds(first)
+        }
+    }
+}
+
+fn digest_excerpt(html: &str) -> String {
+    let text = html_to_plain_text(html);
+    let first = text.split("\n\n").map(str::trim).find(|p| !p.is_empty()).unwrap_or("");
+    if first.chars().count() <= DIGEST_EXCERPT_CHARS {
+        return first.to_string();
+    }
+    let cut: String = first.chars().take(DIGEST_EXCERPT_CHARS).collect();
+    format!("{}…", cut.trim_end())
+}
+
+fn digest_body(label: &str, items: &[DigestItem]) -> String {
+    let mut html = format!(
+        "<p>In case you missed it, here's everything published in {}.</p>",
+        escape(label)
+    );
+    for item in items {
+        let heading = match &item.url {
+            Some(url) => format!("<a href=\"{}\">{}</a>", escape(url), escape(&item.title)),
+            None => escape(&item.title),
+        };
+        html.push_str(&format!("<h2>{}</h2><p>{}</p>", heading, escape(&item.excerpt)));
+    }
+    html
+}
+
+/// Assemble a recap of the period's published issues (top performers
+/// first, then newest) into a new draft document ready to schedule.
+/// `project` limits it to one project and files the digest there.
+#[tauri::command]
+pub async fn build_digest(app: AppHandle, period: String, project: Option<String>) -> Result<Digest, AppError> {
+    let (first, last, label) = digest_period(&period)?;
+    let (start, end) = year_span(first, last);
+    let scope = workspaces::scope(&app);
+    let conn = db::get_db(&app)?;
+
+    let mut stmt = conn.prepare(
+        "SELECT d.id, d.title, d.published_at, d.html_content,
+                COALESCE(
+                    (SELECT sp.published_url FROM scheduled_posts sp
+                     WHERE sp.document_id = d.id AND sp.published_url IS NOT NULL
+                     ORDER BY sp.updated_at DESC LIMIT 1),
+                    (SELECT pp.url FROM platform_posts pp
+                     WHERE pp.document_id = d.id AND pp.url IS NOT NULL
+                     ORDER BY pp.published_at DESC LIMIT 1)
+                )
+         FROM documents d
+         WHERE d.status = 'published' AND d.published_at >= ?1 AND d.published_at <= ?2
+           AND (?3 IS NULL OR d.project_id = ?3) AND d.workspace_id = ?4
+         ORDER BY d.published_at DESC",
+    )?;
+    let issues: Vec<(String, String, String, String, Option<String>)> = stmt
+        .query_map(rusqlite::params![start, end, project, scope.workspace_id], |row| {
+            Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?))
+        })?
+        .filter_map(|r| r.ok())
+        .collect();
+    if issues.is_empty() {
+        return Err(AppError::validation(format!("No issues were published in {}", label)));
+    }
+
+    // Snapshot posts are keyed by platform id; imports link those to
+    // documents, otherwise fall back to matching the title
+    let mut by_document: std::collections::HashMap<String, u64> = std::collections::HashMap::new();
+    let mut by_title: std::collections::HashMap<String, u64> = std::collections::HashMap::new();
+    for post in latest_post_performance(&conn, &scope)? {
+        let engagement = post.opens + post.clicks;
+        let linked: Option<String> = conn
+            .query_row(
+                "SELECT document_id FROM platform_posts WHERE platform = ?1 AND platform_post_id = ?2",
+                rusqlite::params![post.platform, post.id],
+                |row| row.get(0),
+            )
+            .ok();
+        match linked {
+            Some(document_id) => *by_document.entry(document_id).or_insert(0) += engagement,
+            None => *by_title.entry(post.title.to_lowercase()).or_insert(0) += engagement,
+        }
+    }
+
+    let mut items: Vec<DigestItem> = issues
+        .into_iter()
+        .map(|(document_id, title, published_at, html, url)| DigestItem {
+            engagement: by_document
+                .get(&document_id)
+                .or_else(|| by_title.get(&title.to_lowercase()))
+                .copied(),
+            excerpt: digest_excerpt(&html),
+            document_id,
+            title,
+            published_at,
+            url,
+        })
+        .collect();
+    // Stable: unmatched issues keep newest-first order after the ranked ones
+    items.sort_by_key(|item| std::cmp::Reverse(item.engagement));
+
+    let title = format!("In case you missed it: {}", label);
+    let html = digest_body(&label, &items);
+    let document_id = uuid::Uuid::new_v4().to_string();
+    let word_count = text::count_html(&html, text::count_mode(&app)) as i64;
+    let now = Utc::now().to_rfc3339();
+    conn.execute(
+        "INSERT INTO documents (id, title, content, html_content, project_id, status, word_count, character_count, version, workspace_id, created_at, updated_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, 'draft', ?6, 0, 1, ?8, ?7, ?7)",
+        rusqlite::params![document_id, title, html_to_plain_text(&html), html, project, word_count, now, scope.workspace_id],
+    )?;
+    crate::commands::links::index_document(&conn, &document_id, &html);
+    db::log_activity(&conn, "digest.built", "document", Some(&document_id), Some(&title));
+    windows::document_changed(&app, &document_id, "saved", Some(1), None);
+
+    Ok(Digest { document_id, title, from: first.to_string(), to: last.to_string(), items })
+}
@@ -0,0 +1,396 @@
+use chrono::{NaiveDate, NaiveDateTime, Utc};
+use rusqlite::Connection;
+use serde::Serialize;
+use std::io::Read;
+use tauri::AppHandle;
+
+use crate::commands::platform::{AnalyticsData, ImportedPost, PostPerformance, Subscriber};
+use crate::commands::{audience, backfill, deliverability, milestones, snapshots, workspaces};
+use crate::db;
+use crate::error::AppError;
+
+// ─── ESP export archives ────────────────────────────────────────
+//
+// Offline counterpart to account backfill, for platforms the user has
+// already left: reads the audience and campaign exports Mailchimp, Beehiiv
+// and Kit hand out (a zip of CSVs or a single CSV) and merges them into the
+// unified subscriber table, subscriber tags and the post archive. Columns
+// are found by header name, since each platform (and each export vintage)
+// names them differently.
+
+const PLATFORMS: &[&str] = &["mailchimp", "beehiiv", "kit"];
+
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct ArchiveImportResult {
+    pub platform: String,
+    pub account_id: String,
+    /// CSV files read, with what each was recognised as
+    pub files: Vec<ArchiveFile>,
+    pub subscribers_new: i64,
+    pub subscribers_updated: i64,
+    pub tags_applied: i64,
+    pub campaigns_imported: i64,
+    /// Rows without an email (subscribers) or title (campaigns)
+    pub rows_skipped: i64,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ArchiveFile {
+    pub name: String,
+    /// "subscribers" | "campaigns" | "ignored"
+    pub kind: String,
+    pub rows: i64,
+}
+
+/// One subscriber row, before merging
+struct ArchivedSubscriber {
+    subscriber: Subscriber,
+    name: Option<String>,
+    tags: Vec<String>,
+}
+
+/// One campaign row: the post plus whatever stats the export carried
+struct ArchivedCampaign {
+    post: ImportedPost,
+    opens: u64,
+    clicks: u64,
+    unsubscribes: u64,
+}
+
+/// Export dates come as RFC 3339, "YYYY-MM-DD HH:MM:SS", US "MM/DD/YYYY"
+/// or a bare date; all are read as UTC.
+pub(crate) fn normalize_date(value: &str) -> Option<String> {
+    let value = value.trim();
+    if value.is_empty() {
+        return None;
+    }
+    if let Ok(dt) = chrono::DateTime::parse_from_rfc3339(value) {
+        return Some(dt.with_timezone(&Utc).to_rfc3339());
+    }
+    for format in ["%Y-%m-%d %H:%M:%S", "%Y-%m-%d %H:%M", "%m/%d/%Y %H:%M:%S", "%m/%d/%Y %H:%M", "%m/%d/%Y %I:%M %p"] {
+        if let Ok(dt) = NaiveDateTime::parse_from_str(value, format) {
+            return Some(dt.and_utc().to_rfc3339());
+        }
+    }
+    // "2023-04-01 10:00:00 UTC" and similar trailing zones
+    if let Some((head, _)) = value.rsplit_once(' ') {
+        if let Ok(dt) = NaiveDateTime::parse_from_str(head, "%Y-%m-%d %H:%M:%S") {
+            return Some(dt.and_utc().to_rfc3339());
+        }
+    }
+    for format in ["%Y-%m-%d", "%m/%d/%Y", "%B %d, %Y", "%b %d, %Y"] {
+        if let Ok(date) = NaiveDate::parse_from_str(value, format) {
+            return date.and_hms_opt(0, 0, 0).map(|dt| dt.and_utc().to_rfc3339());
+        }
+    }
+    None
+}
+
+/// Subscribed / active in any platform's wording becomes "active"; other
+/// states (unsubscribed, cleaned, bounced, ...) are kept as given.
+fn normalize_status(value: &str) -> String {
+    let value = value.trim().to_lowercase();
+    match value.as_str() {
+        "" | "active" | "subscribed" | "confirmed" => "active".to_string(),
+        _ => value,
+    }
+}
+
+/// A two-letter country code; full country names aren't mapped.
+fn country_code(value: &str) -> Option<String> {
+    (value.len() == 2 && value.chars().all(|c| c.is_ascii_alphabetic())).then(|| value.to_ascii_uppercase())
+}
+
+/// Tags as exported: `"a","b"` (Mailchimp), `a, b` or `a;b`.
+fn split_tags(value: &str) -> Vec<String> {
+    value
+        .split([',', ';'])
+        .map(|t| t.trim().trim_matches('"').trim().to_string())
+        .filter(|t| !t.is_empty())
+        .collect()
+}
+
+fn parse_count(value: &str) -> u64 {
+    value.trim().replace(',', "").parse::<f64>().map(|n| n.max(0.0) as u64).unwrap_or(0)
+}
+
+pub(crate) struct Columns {
+    headers: Vec<String>,
+}
+
+impl Columns {
+    /// Headers lowercased, with underscores as spaces and any BOM dropped
+    pub(crate) fn from_headers(headers: &csv::StringRecord) -> Self {
+        Columns {
+            headers: headers
+                .iter()
+                .map(|h| h.trim_start_matches('\u{feff}').to_lowercase().replace('_', " "))
+                .collect(),
+        }
+    }
+
+    /// First column whose header equals one of `names`, else the first that
+    /// contains one, so "Email Address" and "email" both match "email".
+    pub(crate) fn find(&self, names: &[&str]) -> Option<usize> {
+        names
+            .iter()
+            .find_map(|n| self.headers.iter().position(|h| h == n))
+            .or_else(|| names.iter().find_map(|n| self.headers.iter().position(|h| h.contains(n))))
+    }
+}
+
+pub(crate) fn cell(record: &csv::StringRecord, col: Option<usize>) -> &str {
+    col.and_then(|c| record.get(c)).map(str::trim).unwrap_or("")
+}
+
+/// Read one CSV, recognising it as a subscriber list (it has an email
+/// column) or a campaign report (a subject or title plus a send date).
+fn read_csv(
+    name: &str,
+    data: &[u8],
+    platform: &str,
+    subscribers: &mut Vec<ArchivedSubscriber>,
+    campaigns: &mut Vec<ArchivedCampaign>,
+    skipped: &mut i64,
+) -> Result<ArchiveFile, AppError> {
+    let mut reader = csv::ReaderBuilder::new().flexible(true).trim(csv::Trim::All).from_reader(data);
+    let columns = Columns::from_headers(
+        reader
+            .headers()
+            .map_err(|e| AppError::validation(format!("{}: unreadable CSV header: {}", name, e)))?,
+    );
+    let lower_name = name.to_lowercase();
+    let mut rows = 0;
+
+    if let Some(email_col) = columns.find(&["email address", "email"]) {
+        let first = columns.find(&["first name", "fname"]);
+        let last = columns.find(&["last name", "lname"]);
+        let full = columns.find(&["name", "full name"]).filter(|c| Some(*c) != first && Some(*c) != last);
+        let status = columns.find(&["status", "subscription status", "state"]);
+        let created = columns.find(&["optin time", "confirm time", "created at", "subscribed at", "subscribe date", "signup date", "date added", "created"]);
+        let tags = columns.find(&["tags", "tag"]);
+        let id = columns.find(&["id", "subscriber id", "leid", "euid"]);
+        // Mailchimp's "CC" is the subscriber's country code
+        let country = columns.find(&["cc", "country code", "country"]);
+        // Mailchimp splits its audience export by state into separate files
+        let file_status = ["unsubscribed", "cleaned", "pending"]
+            .into_iter()
+            .find(|s| lower_name.contains(s));
+
+        for record in reader.records() {
+            let Ok(record) = record else {
+                *skipped += 1;
+                continue;
+            };
+            let email = cell(&record, Some(email_col)).to_lowercase();
+            if !email.contains('@') {
+                *skipped += 1;
+                continue;
+            }
+            rows += 1;
+            let name = match (cell(&record, first), cell(&record, last), cell(&record, full)) {
+                ("", "", "") => None,
+                ("", "", full) => Some(full.to_string()),
+                (first, last, _) => Some(format!("{} {}", first, last).trim().to_string()),
+            };
+            let status = match file_status {
+                Some(s) => s.to_string(),
+                None => normalize_status(cell(&record, status)),
+            };
+            subscribers.push(ArchivedSubscriber {
+                subscriber: Subscriber {
+                    id: Some(cell(&record, id)).filter(|v| !v.is_empty()).unwrap_or(&email).to_string(),
+                    email,
+                    status,
+                    created_at: normalize_date(cell(&record, created)).unwrap_or_default(),
+                    platform: platform.to_string(),
+                    country: country_code(cell(&record, country)),
+                },
+                name,
+                tags: split_tags(cell(&record, tags)),
+            });
+        }
+        return Ok(ArchiveFile { name: name.to_string(), kind: "subscribers".to_string(), rows });
+    }
+
+    let title = columns.find(&["subject", "subject line", "title", "campaign name", "name"]);
+    let sent = columns.find(&["send date", "sent at", "send time", "published at", "publish date", "date"]);
+    let (Some(title_col), Some(sent_col)) = (title, sent) else {
+        return Ok(ArchiveFile { name: name.to_string(), kind: "ignored".to_string(), rows: 0 });
+    };
+    let id = columns.find(&["campaign id", "id"]);
+    let html = columns.find(&["content html", "html", "content", "body"]);
+    let url = columns.find(&["web url", "archive url", "url", "link"]);
+    let opens = columns.find(&["unique opens", "opens", "total opens"]);
+    let clicks = columns.find(&["unique clicks", "clicks", "total clicks"]);
+    let unsubscribes = columns.find(&["unsubscribes", "unsubscribed"]);
+
+    for record in reader.records() {
+        let Ok(record) = record else {
+            *skipped += 1;
+            continue;
+        };
+        let title = cell(&record, Some(title_col));
+        if title.is_empty() {
+            *skipped += 1;
+            continue;
+        }
+        rows += 1;
+        let published_at = normalize_date(cell(&record, Some(sent_col)));
+        let post_id = match cell(&record, id) {
+            "" => format!("{}@{}", title, published_at.as_deref().unwrap_or("")),
+            id => id.to_string(),
+        };
+        campaigns.push(ArchivedCampaign {
+            post: ImportedPost {
+                id: post_id,
+                title: title.to_string(),
+                html_content: cell(&record, html).to_string(),
+                published_at,
+                url: Some(cell(&record, url).to_string()).filter(|u| !u.is_empty()),
+                platform: platform.to_string(),
+            },
+            opens: parse_count(cell(&record, opens)),
+            clicks: parse_count(cell(&record, clicks)),
+            unsubscribes: parse_count(cell(&record, unsubscribes)),
+        });
+    }
+    Ok(ArchiveFile { name: name.to_string(), kind: "campaigns".to_string(), rows })
+}
+
+/// Names and tags aren't part of the platform subscriber shape; set them
+/// once the rows are merged. Returns how many tags were newly applied.
+fn apply_names_and_tags(conn: &Connection, workspace_id: &str, subscribers: &[ArchivedSubscriber]) -> i64 {
+    let mut tags_applied = 0;
+    for sub in subscribers {
+        let Ok(id) = conn.query_row(
+            "SELECT id FROM subscribers WHERE workspace_id = ?1 AND email = ?2",
+            rusqlite::params![workspace_id, sub.subscriber.email],
+            |row| row.get::<_, String>(0),
+        ) else {
+            continue;
+        };
+        if let Some(name) = &sub.name {
+            conn.execute(
+                "UPDATE subscribers SET name = ?1 WHERE id = ?2 AND (name IS NULL OR name = '')",
+                rusqlite::params![name, id],
+            )
+            .ok();
+        }
+        for tag in &sub.tags {
+            tags_applied += conn
+                .execute(
+                    "INSERT OR IGNORE INTO subscriber_tags (subscriber_id, tag) VALUES (?1, ?2)",
+                    rusqlite::params![id, tag],
+                )
+                .unwrap_or(0) as i64;
+        }
+    }
+    tags_applied
+}
+
+/// Import a Mailchimp, Beehiiv or Kit export (zip or CSV) into the
+/// unified schema. `account_id` keys the rows like a connected account
+/// would; it defaults to "archive".
+#[tauri::command]
+pub async fn import_esp_archive(
+    app: AppHandle,
+    platform: String,
+    file_path: String,
+    account_id: Option<String>,
+) -> Result<ArchiveImportResult, AppError> {
+    if !PLATFORMS.contains(&platform.as_str()) {
+        return Err(AppError::validation(format!("Archive import not supported for {}", platform)));
+    }
+    let account_id = account_id.filter(|a| !a.trim().is_empty()).unwrap_or_else(|| "archive".to_string());
+    let data = std::fs::read(&file_path).map_err(|e| AppError::not_found(format!("Can't read {}: {}", file_path, e)))?;
+
+    let mut result = ArchiveImportResult {
+        platform: platform.clone(),
+        account_id: account_id.clone(),
+        ..Default::default()
+    };
+    let mut subscribers = Vec::new();
+    let mut campaigns = Vec::new();
+
+    if data.starts_with(b"PK") {
+        let mut archive = zip::ZipArchive::new(std::io::Cursor::new(&data))
+            .map_err(|e| AppError::validation(format!("Not a readable zip archive: {}", e)))?;
+        for i in 0..archive.len() {
+            let mut entry = archive.by_index(i).map_err(|e| format!("Failed to read archive: {}", e))?;
+            let name = entry.name().to_string();
+            // macOS zips carry "__MACOSX/._file.csv" resource forks
+            if !name.to_lowercase().ends_with(".csv") || name.contains("__MACOSX") {
+                continue;
+            }
+            let mut bytes = Vec::new();
+            entry.read_to_end(&mut bytes).map_err(|e| format!("Failed to read {}: {}", name, e))?;
+            let file = read_csv(&name, &bytes, &platform, &mut subscribers, &mut campaigns, &mut result.rows_skipped)?;
+            result.files.push(file);
+        }
+    } else {
+        let name = std::path::Path::new(&file_path)
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or(file_path.clone());
+        let file = read_csv(&name, &data, &platform, &mut subscribers, &mut campaigns, &mut result.rows_skipped)?;
+        result.files.push(file);
+    }
+    if subscribers.is_empty() && campaigns.is_empty() {
+        return Err(AppError::validation("No subscriber or campaign CSVs found in the export"));
+    }
+
+    let workspace_id = workspaces::of_account(&app, &platform, &account_id);
+    {
+        let conn = db::get_db(&app)?;
+        snapshots::take_snapshot(&app, &conn, "archive_import")?;
+        let platform_subs: Vec<Subscriber> = subscribers.iter().map(|s| s.subscriber.clone()).collect();
+        let (new, updated) =
+            audience::upsert_platform_subscribers(&conn, &workspace_id, &platform, &account_id, &platform_subs);
+        result.subscribers_new = new;
+        result.subscribers_updated = updated;
+        deliverability::record_platform_statuses(&conn, &workspace_id, &platform, &platform_subs);
+        result.tags_applied = apply_names_and_tags(&conn, &workspace_id, &subscribers);
+    }
+
+    let posts: Vec<ImportedPost> = campaigns.iter().map(|c| c.post.clone()).collect();
+    result.campaigns_imported = backfill::store_imported_posts(&app, &platform, &account_id, &posts)?;
+
+    let conn = db::get_db(&app)?;
+    // Campaign stats become a snapshot, so post rankings include the archive
+    if campaigns.iter().any(|c| c.opens + c.clicks > 0) {
+        let active = subscribers.iter().filter(|s| s.subscriber.status == "active").count() as u64;
+        let data = AnalyticsData {
+            total_subscribers: active,
+            open_rate: 0.0,
+            click_rate: 0.0,
+            subscriber_growth: Vec::new(),
+            recent_posts: campaigns
+                .iter()
+                .map(|c| PostPerformance {
+                    id: c.post.id.clone(),
+                    title: c.post.title.clone(),
+                    published_at: c.post.published_at.clone().unwrap_or_default(),
+                    opens: c.opens,
+                    clicks: c.clicks,
+                    unsubscribes: c.unsubscribes,
+                    platform: platform.clone(),
+                })
+                .collect(),
+        };
+        backfill::record_analytics_snapshot(&conn, &platform, &account_id, None, &data)?;
+    }
+
+    db::log_activity(
+        &conn,
+        "audience.archive_imported",
+        "subscribers",
+        Some(&account_id),
+        Some(&format!(
+            "{} export: {} new and {} updated subscribers, {} campaigns",
+            platform, result.subscribers_new, result.subscribers_updated, result.campaigns_imported
+        )),
+    );
+    milestones::evaluate(&app, &conn);
+    Ok(result)
+}
@@ -0,0 +1,217 @@
+use chrono::Utc;
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+use uuid::Uuid;
+
+use crate::commands::platform::PublishAttachment;
+use crate::db;
+use crate::error::AppError;
+use crate::permissions::{self, Permission};
+
+// ─── Attachments ────────────────────────────────────────────────
+//
+// Files attached to a document (PDFs, audio, anything else) are copied
+// into app data/attachments as "<id>.<ext>" and listed in the
+// attachments table. Platforms that host files upload them at publish
+// time; the rest ignore them.
+
+/// Larger files are refused; platform upload limits are lower still
+const MAX_ATTACHMENT_BYTES: u64 = 500 * 1024 * 1024;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Attachment {
+    pub id: String,
+    pub document_id: String,
+    /// Original file name, shown to readers
+    pub filename: String,
+    pub mime_type: String,
+    pub size: i64,
+    pub path: String,
+    pub created_at: String,
+    /// Document holding this file's transcript, once transcribed
+    pub transcript_document_id: Option<String>,
+}
+
+pub(crate) fn attachments_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    let data_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data dir: {}", e))?;
+    let path = data_dir.join("attachments");
+    if !path.exists() {
+        fs::create_dir_all(&path).map_err(|e| format!("Failed to create attachments dir: {}", e))?;
+    }
+    Ok(path)
+}
+
+/// MIME type from the extension; platforms use it to pick an upload endpoint.
+pub(crate) fn mime_type(ext: &str) -> &'static str {
+    match ext {
+        "pdf" => "application/pdf",
+        "mp3" => "audio/mpeg",
+        "m4a" => "audio/mp4",
+        "wav" => "audio/wav",
+        "ogg" | "oga" => "audio/ogg",
+        "flac" => "audio/flac",
+        "aac" => "audio/aac",
+        "mp4" => "video/mp4",
+        "mov" => "video/quicktime",
+        "webm" => "video/webm",
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "zip" => "application/zip",
+        "epub" => "application/epub+zip",
+        "txt" => "text/plain",
+        "csv" => "text/csv",
+        "docx" => "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+        _ => "application/octet-stream",
+    }
+}
+
+fn from_row(dir: &Path, row: &rusqlite::Row) -> rusqlite::Result<Attachment> {
+    let stored_name: String = row.get(3)?;
+    Ok(Attachment {
+        id: row.get(0)?,
+        document_id: row.get(1)?,
+        filename: row.get(2)?,
+        mime_type: row.get(4)?,
+        size: row.get(5)?,
+        path: dir.join(stored_name).to_string_lossy().to_string(),
+        created_at: row.get(6)?,
+        transcript_document_id: row.get(7)?,
+    })
+}
+
+fn list(app: &AppHandle, conn: &Connection, document_id: &str) -> Result<Vec<Attachment>, AppError> {
+    let dir = attachments_dir(app)?;
+    let mut stmt = conn.prepare(
+        "SELECT id, document_id, filename, stored_name, mime_type, size, created_at, transcript_document_id
+         FROM attachments WHERE document_id = ?1 ORDER BY created_at ASC",
+    )?;
+    let rows = stmt.query_map(rusqlite::params![document_id], |row| from_row(&dir, row))?;
+    Ok(rows.filter_map(|r| r.ok()).collect())
+}
+
+pub(crate) fn get(app: &AppHandle, conn: &Connection, attachment_id: &str) -> Result<Attachment, AppError> {
+    let dir = attachments_dir(app)?;
+    conn.query_row(
+        "SELECT id, document_id, filename, stored_name, mime_type, size, created_at, transcript_document_id
+         FROM attachments WHERE id = ?1",
+        rusqlite::params![attachment_id],
+        |row| from_row(&dir, row),
+    )
+    .map_err(|_| AppError::not_found(format!("Attachment '{}' not found", attachment_id)))
+}
+
+/// The document's attachments as publish uploads. Files missing on disk
+/// are skipped so one lost file doesn't block the post.
+pub(crate) fn for_publish(app: &AppHandle, conn: &Connection, document_id: &str) -> Vec<PublishAttachment> {
+    list(app, conn, document_id)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|a| {
+            let exists = Path::new(&a.path).is_file();
+            if !exists {
+                crate::trace::log("Attachments", &format!("Skipping missing file for attachment {}", a.id));
+            }
+            exists
+        })
+        .map(|a| PublishAttachment { filename: a.filename, mime_type: a.mime_type, path: a.path })
+        .collect()
+}
+
+/// Remove every attachment of a deleted document, files included.
+pub(crate) fn delete_for_document(app: &AppHandle, conn: &Connection, document_id: &str) {
+    for attachment in list(app, conn, document_id).unwrap_or_default() {
+        fs::remove_file(&attachment.path).ok();
+    }
+    conn.execute("DELETE FROM attachments WHERE document_id = ?1", rusqlite::params![document_id]).ok();
+}
+
+#[tauri::command]
+pub async fn attach_file(app: AppHandle, document_id: String, file_path: String) -> Result<Attachment, AppError> {
+    let source = PathBuf::from(&file_path);
+    if !source.is_file() {
+        return Err(AppError::not_found("File not found"));
+    }
+    let size = fs::metadata(&source).map_err(|e| format!("Failed to read metadata: {}", e))?.len();
+    if size > MAX_ATTACHMENT_BYTES {
+        return Err(AppError::validation(format!(
+            "Attachments are limited to {} MB",
+            MAX_ATTACHMENT_BYTES / (1024 * 1024)
+        )));
+    }
+
+    let conn = db::get_db(&app)?;
+    let exists: bool = conn.query_row(
+        "SELECT EXISTS (SELECT 1 FROM documents WHERE id = ?1)",
+        rusqlite::params![document_id],
+        |row| row.get(0),
+    )?;
+    if !exists {
+        return Err(AppError::not_found(format!("Document '{}' not found", document_id)));
+    }
+
+    let filename = source
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or("attachment")
+        .to_string();
+    let ext = source
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("")
+        .to_lowercase();
+    let id = Uuid::new_v4().to_string();
+    let stored_name = if ext.is_empty() { id.clone() } else { format!("{}.{}", id, ext) };
+    let dest = attachments_dir(&app)?.join(&stored_name);
+    fs::copy(&source, &dest).map_err(|e| format!("Failed to copy attachment: {}", e))?;
+
+    let now = Utc::now().to_rfc3339();
+    let mime = mime_type(&ext);
+    conn.execute(
+        "INSERT INTO attachments (id, document_id, filename, stored_name, mime_type, size, created_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        rusqlite::params![id, document_id, filename, stored_name, mime, size as i64, now],
+    )?;
+    db::log_activity(&conn, "attachment.added", "document", Some(&document_id), Some(&filename));
+
+    Ok(Attachment {
+        id,
+        document_id,
+        filename,
+        mime_type: mime.to_string(),
+        size: size as i64,
+        path: dest.to_string_lossy().to_string(),
+        created_at: now,
+        transcript_document_id: None,
+    })
+}
+
+#[tauri::command]
+pub async fn list_attachments(app: AppHandle, document_id: String) -> Result<Vec<Attachment>, AppError> {
+    let conn = db::get_db(&app)?;
+    list(&app, &conn, &document_id)
+}
+
+#[tauri::command]
+pub async fn delete_attachment(app: AppHandle, attachment_id: String) -> Result<(), AppError> {
+    permissions::require(&app, Permission::DeleteContent)?;
+    let conn = db::get_db(&app)?;
+    let attachment = get(&app, &conn, &attachment_id)?;
+    fs::remove_file(&attachment.path).ok();
+    conn.execute("DELETE FROM attachments WHERE id = ?1", rusqlite::params![attachment_id])?;
+    db::log_activity(
+        &conn,
+        "attachment.deleted",
+        "document",
+        Some(&attachment.document_id),
+        Some(&attachment.filename),
+    );
+    Ok(())
+}
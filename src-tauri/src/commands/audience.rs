@@ -1,4 +1,9 @@
+use crate::commands::offline::{self, QueuedOperation};
+use crate::commands::{snapshots, workspaces};
+use crate::connectivity;
 use crate::db;
+use crate::error::AppError;
+use crate::query_cache::{self, Key};
 use chrono::Utc;
 use serde::{Deserialize, Serialize};
 use tauri::AppHandle;
@@ -67,6 +72,35 @@ pub struct Segment {
     pub color: String,
 }
 
+/// Aggregate breakdowns of the audience, for picking send times and for
+/// sponsor conversations. Nothing here identifies a subscriber.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AudienceDemographics {
+    pub total: i64,
+    /// "personal" | "corporate" | "education" | "government"
+    pub domain_types: Vec<BreakdownCount>,
+    pub top_domains: Vec<DomainCount>,
+    /// ISO country codes, largest first
+    pub countries: Vec<BreakdownCount>,
+    /// Subscribers whose platform didn't report a country
+    pub unknown_country: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BreakdownCount {
+    pub key: String,
+    pub count: i64,
+    /// Fraction of `total`
+    pub share: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DomainCount {
+    pub domain: String,
+    pub kind: String,
+    pub count: i64,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct SyncResult {
     pub synced: i64,
@@ -78,43 +112,21 @@ pub struct SyncResult {
 // Commands
 // ---------------------------------------------------------------------------
 
-#[tauri::command]
-pub async fn sync_subscribers(
-    app: AppHandle,
-    platform: String,
-    account_id: String,
-    publication_id: Option<String>,
-) -> Result<SyncResult, String> {
-    // Get API key
-    let api_key = {
-        let store = app.store("credentials.json").map_err(|e| format!("Store error: {}", e))?;
-        let key = format!("{}:{}", platform, account_id);
-        match store.get(&key) {
-            Some(val) => {
-                let cred: crate::commands::credentials::StoredCredential =
-                    serde_json::from_value(val.clone()).map_err(|e| format!("Parse error: {}", e))?;
-                cred.api_key
-            }
-            None => return Err("No credentials found".to_string()),
-        }
-    };
-
-    // Fetch subscribers using the existing PlatformService trait
-    use crate::services::PlatformService;
-    let platform_subs = match platform.as_str() {
-        "beehiiv" => crate::services::beehiiv::BeehiivService::get_subscribers(&api_key, publication_id.as_deref()).await?,
-        "kit" => crate::services::kit::KitService::get_subscribers(&api_key, publication_id.as_deref()).await?,
-        "ghost" => crate::services::ghost::GhostService::get_subscribers(&api_key, publication_id.as_deref()).await?,
-        "substack" => crate::services::substack::SubstackService::get_subscribers(&api_key, publication_id.as_deref()).await?,
-        _ => return Err(format!("Subscriber sync not supported for {}", platform)),
-    };
-
-    let conn = db::get_db(&app)?;
+/// Merge one platform's subscriber list into the workspace's unified
+/// table, returning (new, updated) counts. Shared by manual sync and
+/// account backfill.
+pub(crate) fn upsert_platform_subscribers(
+    conn: &rusqlite::Connection,
+    workspace_id: &str,
+    platform: &str,
+    account_id: &str,
+    platform_subs: &[crate::commands::platform::Subscriber],
+) -> (i64, i64) {
     let now = Utc::now().to_rfc3339();
     let mut new_count = 0i64;
     let mut updated_count = 0i64;
 
-    for sub in &platform_subs {
+    for sub in platform_subs {
         let email = sub.email.trim().to_lowercase();
         if email.is_empty() {
             continue;
@@ -123,27 +135,31 @@ pub async fn sync_subscribers(
         // Check if subscriber exists
         let existing_id: Option<String> = conn
             .query_row(
-                "SELECT id FROM subscribers WHERE email = ?1",
-                rusqlite::params![email],
+                "SELECT id FROM subscribers WHERE workspace_id = ?1 AND email = ?2",
+                rusqlite::params![workspace_id, email],
                 |row| row.get(0),
             )
             .ok();
 
         let sub_id = if let Some(id) = existing_id {
-            // Update last_seen_at
+            // Update last_seen_at, and the country when this platform knows it
             conn.execute(
-                "UPDATE subscribers SET last_seen_at = ?1, updated_at = ?1 WHERE id = ?2",
-                rusqlite::params![now, id],
+                "UPDATE subscribers SET last_seen_at = ?1, updated_at = ?1, country = COALESCE(?3, country) WHERE id = ?2",
+                rusqlite::params![now, id, sub.country],
             ).ok();
             updated_count += 1;
             id
         } else {
-            // Insert new subscriber
+            // Insert new subscriber, dated from when they joined the platform
+            // so historical cohorts line up after a backfill
             let id = uuid::Uuid::new_v4().to_string();
+            let first_seen = chrono::DateTime::parse_from_rfc3339(&sub.created_at)
+                .map(|dt| dt.with_timezone(&Utc).to_rfc3339())
+                .unwrap_or_else(|_| now.clone());
             conn.execute(
-                "INSERT INTO subscribers (id, email, name, first_seen_at, last_seen_at, engagement_score, total_opens, total_clicks, created_at, updated_at)
-                 VALUES (?1, ?2, NULL, ?3, ?3, 0.0, 0, 0, ?3, ?3)",
-                rusqlite::params![id, email, now],
+                "INSERT INTO subscribers (id, workspace_id, email, name, first_seen_at, last_seen_at, engagement_score, total_opens, total_clicks, country, created_at, updated_at)
+                 VALUES (?1, ?6, ?2, NULL, ?3, ?4, 0.0, 0, 0, ?5, ?4, ?4)",
+                rusqlite::params![id, email, first_seen, now, sub.country, workspace_id],
             ).ok();
             new_count += 1;
             id
@@ -164,6 +180,71 @@ pub async fn sync_subscribers(
         ).ok();
     }
 
+    (new_count, updated_count)
+}
+
+#[tauri::command]
+pub async fn sync_subscribers(
+    app: AppHandle,
+    platform: String,
+    account_id: String,
+    publication_id: Option<String>,
+) -> Result<SyncResult, AppError> {
+    let queued = || QueuedOperation::SyncSubscribers {
+        platform: platform.clone(),
+        account_id: account_id.clone(),
+        publication_id: publication_id.clone(),
+    };
+    if !connectivity::is_online() {
+        return Err(offline::enqueue(&app, queued()));
+    }
+    match sync_now(&app, &platform, &account_id, publication_id.as_deref()).await {
+        Err(e) if offline::should_queue(&e, &queued()) => {
+            connectivity::set_online(&app, false);
+            Err(offline::enqueue(&app, queued()))
+        }
+        other => other,
+    }
+}
+
+/// Sync without the offline check; the offline queue replays through here.
+pub(crate) async fn sync_now(
+    app: &AppHandle,
+    platform: &str,
+    account_id: &str,
+    publication_id: Option<&str>,
+) -> Result<SyncResult, AppError> {
+    // Get API key; subscribers land in the account's workspace
+    let (api_key, workspace_id) = {
+        let store = app.store("credentials.json").map_err(|e| format!("Store error: {}", e))?;
+        let key = format!("{}:{}", platform, account_id);
+        match store.get(&key) {
+            Some(val) => {
+                let cred: crate::commands::credentials::StoredCredential =
+                    serde_json::from_value(val.clone()).map_err(|e| format!("Parse error: {}", e))?;
+                (cred.api_key, cred.workspace_id)
+            }
+            None => return Err(AppError::auth("No credentials found")),
+        }
+    };
+
+    // Fetch subscribers using the existing PlatformService trait
+    use crate::services::PlatformService;
+    let platform_subs = match platform {
+        "beehiiv" => crate::services::beehiiv::BeehiivService::get_subscribers(&api_key, publication_id).await?,
+        "kit" => crate::services::kit::KitService::get_subscribers(&api_key, publication_id).await?,
+        "ghost" => crate::services::ghost::GhostService::get_subscribers(&api_key, publication_id).await?,
+        "substack" => crate::services::substack::SubstackService::get_subscribers(&api_key, publication_id).await?,
+        _ => return Err(AppError::validation(format!("Subscriber sync not supported for {}", platform))),
+    };
+
+    let conn = db::get_db(app)?;
+    snapshots::take_snapshot(app, &conn, "subscriber_sync")?;
+    let (new_count, updated_count) =
+        upsert_platform_subscribers(&conn, &workspace_id, platform, account_id, &platform_subs);
+    // Bounced / complained states become deliverability events
+    crate::commands::deliverability::record_platform_statuses(&conn, &workspace_id, platform, &platform_subs);
+
     db::log_activity(
         &conn,
         "audience.synced",
@@ -171,6 +252,7 @@ pub async fn sync_subscribers(
         None,
         Some(&format!("Synced {} from {}: {} new, {} updated", platform_subs.len(), platform, new_count, updated_count)),
     );
+    crate::commands::milestones::evaluate(app, &conn);
 
     Ok(SyncResult {
         synced: platform_subs.len() as i64,
@@ -188,7 +270,7 @@ pub async fn get_unified_subscribers(
     tag: Option<String>,
     sort_by: Option<String>,
     sort_dir: Option<String>,
-) -> Result<PaginatedSubscribers, String> {
+) -> Result<PaginatedSubscribers, AppError> {
     let conn = db::get_db(&app)?;
     let page = page.unwrap_or(1).max(1);
     let per_page = per_page.unwrap_or(50).min(200);
@@ -203,8 +285,8 @@ pub async fn get_unified_subscribers(
     };
     let direction = if sort_dir.as_deref() == Some("asc") { "ASC" } else { "DESC" };
 
-    let mut where_clauses = Vec::new();
-    let mut params: Vec<Box<dyn rusqlite::types::ToSql>> = Vec::new();
+    let mut where_clauses = vec!["s.workspace_id = ?1".to_string()];
+    let mut params: Vec<Box<dyn rusqlite::types::ToSql>> = vec![Box::new(workspaces::current(&app))];
 
     if let Some(ref q) = search {
         let param_idx = params.len() + 1;
@@ -221,11 +303,7 @@ pub async fn get_unified_subscribers(
         params.push(Box::new(t.clone()));
     }
 
-    let where_sql = if where_clauses.is_empty() {
-        String::new()
-    } else {
-        format!("WHERE {}", where_clauses.join(" AND "))
-    };
+    let where_sql = format!("WHERE {}", where_clauses.join(" AND "));
 
     // Get total
     let count_sql = format!("SELECT COUNT(*) FROM subscribers s {}", where_sql);
@@ -330,7 +408,7 @@ fn get_subscriber_tags(conn: &rusqlite::Connection, subscriber_id: &str) -> Vec<
 pub async fn get_subscriber_detail(
     app: AppHandle,
     id: String,
-) -> Result<UnifiedSubscriber, String> {
+) -> Result<UnifiedSubscriber, AppError> {
     let conn = db::get_db(&app)?;
 
     let sub = conn
@@ -376,7 +454,7 @@ pub async fn tag_subscribers(
     app: AppHandle,
     ids: Vec<String>,
     tag: String,
-) -> Result<(), String> {
+) -> Result<(), AppError> {
     let conn = db::get_db(&app)?;
     for id in &ids {
         conn.execute(
@@ -392,7 +470,7 @@ pub async fn untag_subscribers(
     app: AppHandle,
     ids: Vec<String>,
     tag: String,
-) -> Result<(), String> {
+) -> Result<(), AppError> {
     let conn = db::get_db(&app)?;
     for id in &ids {
         conn.execute(
@@ -404,36 +482,49 @@ pub async fn untag_subscribers(
 }
 
 #[tauri::command]
-pub async fn get_audience_stats(app: AppHandle) -> Result<AudienceStats, String> {
-    let conn = db::get_db(&app)?;
+pub async fn get_audience_stats(app: AppHandle) -> Result<AudienceStats, AppError> {
+    let workspace_id = workspaces::current(&app);
+    query_cache::cached(Key::new("get_audience_stats", &workspace_id), &["subscribers", "subscriber_platforms"], || {
+        audience_stats(&app, &workspace_id)
+    })
+}
+
+fn audience_stats(app: &AppHandle, workspace_id: &str) -> Result<AudienceStats, AppError> {
+    let conn = db::get_db(app)?;
 
     let total_unique: i64 = conn
-        .query_row("SELECT COUNT(*) FROM subscribers", [], |row| row.get(0))
+        .query_row("SELECT COUNT(*) FROM subscribers WHERE workspace_id = ?1", rusqlite::params![workspace_id], |row| {
+            row.get(0)
+        })
         .unwrap_or(0);
 
     let thirty_days_ago = (Utc::now() - chrono::Duration::days(30)).to_rfc3339();
     let new_last_30d: i64 = conn
         .query_row(
-            "SELECT COUNT(*) FROM subscribers WHERE first_seen_at >= ?1",
-            rusqlite::params![thirty_days_ago],
+            "SELECT COUNT(*) FROM subscribers WHERE first_seen_at >= ?1 AND workspace_id = ?2",
+            rusqlite::params![thirty_days_ago, workspace_id],
             |row| row.get(0),
         )
         .unwrap_or(0);
 
     let avg_engagement: f64 = conn
         .query_row(
-            "SELECT COALESCE(AVG(engagement_score), 0.0) FROM subscribers",
-            [],
+            "SELECT COALESCE(AVG(engagement_score), 0.0) FROM subscribers WHERE workspace_id = ?1",
+            rusqlite::params![workspace_id],
             |row| row.get(0),
         )
         .unwrap_or(0.0);
 
     // Platform breakdown
     let mut platform_stmt = conn
-        .prepare("SELECT platform, COUNT(DISTINCT subscriber_id) FROM subscriber_platforms GROUP BY platform")
+        .prepare(
+            "SELECT sp.platform, COUNT(DISTINCT sp.subscriber_id) FROM subscriber_platforms sp
+             JOIN subscribers s ON s.id = sp.subscriber_id
+             WHERE s.workspace_id = ?1 GROUP BY sp.platform",
+        )
         .map_err(|e| format!("Query failed: {}", e))?;
     let platform_breakdown: Vec<PlatformCount> = platform_stmt
-        .query_map([], |row| {
+        .query_map(rusqlite::params![workspace_id], |row| {
             Ok(PlatformCount {
                 platform: row.get(0)?,
                 count: row.get(1)?,
@@ -448,12 +539,12 @@ pub async fn get_audience_stats(app: AppHandle) -> Result<AudienceStats, String>
     let mut growth_stmt = conn
         .prepare(
             "SELECT strftime('%Y-%m', first_seen_at) as month, COUNT(*)
-             FROM subscribers WHERE first_seen_at >= ?1
+             FROM subscribers WHERE first_seen_at >= ?1 AND workspace_id = ?2
              GROUP BY month ORDER BY month ASC",
         )
         .map_err(|e| format!("Query failed: {}", e))?;
     let growth_data: Vec<GrowthPoint> = growth_stmt
-        .query_map(rusqlite::params![six_months_ago], |row| {
+        .query_map(rusqlite::params![six_months_ago, workspace_id], |row| {
             Ok(GrowthPoint {
                 date: row.get(0)?,
                 count: row.get(1)?,
@@ -473,40 +564,47 @@ pub async fn get_audience_stats(app: AppHandle) -> Result<AudienceStats, String>
 }
 
 #[tauri::command]
-pub async fn get_audience_segments(app: AppHandle) -> Result<Vec<Segment>, String> {
-    let conn = db::get_db(&app)?;
+pub async fn get_audience_segments(app: AppHandle) -> Result<Vec<Segment>, AppError> {
+    let workspace_id = workspaces::current(&app);
+    query_cache::cached(Key::new("get_audience_segments", &workspace_id), &["subscribers"], || {
+        audience_segments(&app, &workspace_id)
+    })
+}
+
+fn audience_segments(app: &AppHandle, workspace_id: &str) -> Result<Vec<Segment>, AppError> {
+    let conn = db::get_db(app)?;
 
     let thirty_days_ago = (Utc::now() - chrono::Duration::days(30)).to_rfc3339();
     let ninety_days_ago = (Utc::now() - chrono::Duration::days(90)).to_rfc3339();
 
     let new_count: i64 = conn
         .query_row(
-            "SELECT COUNT(*) FROM subscribers WHERE first_seen_at >= ?1",
-            rusqlite::params![thirty_days_ago],
+            "SELECT COUNT(*) FROM subscribers WHERE first_seen_at >= ?1 AND workspace_id = ?2",
+            rusqlite::params![thirty_days_ago, workspace_id],
             |row| row.get(0),
         )
         .unwrap_or(0);
 
     let engaged_count: i64 = conn
         .query_row(
-            "SELECT COUNT(*) FROM subscribers WHERE engagement_score >= 0.7",
-            [],
+            "SELECT COUNT(*) FROM subscribers WHERE engagement_score >= 0.7 AND workspace_id = ?1",
+            rusqlite::params![workspace_id],
             |row| row.get(0),
         )
         .unwrap_or(0);
 
     let at_risk_count: i64 = conn
         .query_row(
-            "SELECT COUNT(*) FROM subscribers WHERE last_seen_at < ?1 AND last_seen_at >= ?2",
-            rusqlite::params![thirty_days_ago, ninety_days_ago],
+            "SELECT COUNT(*) FROM subscribers WHERE last_seen_at < ?1 AND last_seen_at >= ?2 AND workspace_id = ?3",
+            rusqlite::params![thirty_days_ago, ninety_days_ago, workspace_id],
             |row| row.get(0),
         )
         .unwrap_or(0);
 
     let inactive_count: i64 = conn
         .query_row(
-            "SELECT COUNT(*) FROM subscribers WHERE last_seen_at < ?1",
-            rusqlite::params![ninety_days_ago],
+            "SELECT COUNT(*) FROM subscribers WHERE last_seen_at < ?1 AND workspace_id = ?2",
+            rusqlite::params![ninety_days_ago, workspace_id],
             |row| row.get(0),
         )
         .unwrap_or(0);
@@ -538,3 +636,107 @@ pub async fn get_audience_segments(app: AppHandle) -> Result<Vec<Segment>, Strin
         },
     ])
 }
+
+/// Mailbox providers anyone can sign up to; any other domain counts as
+/// corporate.
+const PERSONAL_DOMAINS: &[&str] = &[
+    "gmail.com", "googlemail.com", "outlook.com", "hotmail.com", "live.com", "msn.com", "yahoo.com",
+    "ymail.com", "icloud.com", "me.com", "mac.com", "aol.com", "proton.me", "protonmail.com", "pm.me",
+    "gmx.com", "gmx.de", "gmx.net", "web.de", "mail.com", "yandex.com", "yandex.ru", "zoho.com",
+    "fastmail.com", "hey.com", "qq.com", "163.com", "naver.com",
+];
+
+/// "personal", "education", "government" or "corporate".
+fn domain_kind(domain: &str) -> &'static str {
+    let labels: Vec<&str> = domain.split('.').collect();
+    // Country variants of the big providers: yahoo.co.uk, hotmail.fr, ...
+    let provider = labels.first().copied().unwrap_or("");
+    let tld = labels.last().copied().unwrap_or("");
+    // Under a country code: ox.ac.uk, unimelb.edu.au, service.gov.uk
+    let second_level = if labels.len() > 2 { labels[labels.len() - 2] } else { "" };
+    let regional_provider = labels.len() <= 3 && matches!(provider, "yahoo" | "hotmail" | "outlook" | "live");
+    if PERSONAL_DOMAINS.contains(&domain) || regional_provider {
+        "personal"
+    } else if tld == "edu" || matches!(second_level, "edu" | "ac") {
+        "education"
+    } else if matches!(tld, "gov" | "mil") || matches!(second_level, "gov" | "gouv") {
+        "government"
+    } else {
+        "corporate"
+    }
+}
+
+fn breakdown(counts: Vec<(String, i64)>, total: i64) -> Vec<BreakdownCount> {
+    counts
+        .into_iter()
+        .map(|(key, count)| BreakdownCount {
+            key,
+            count,
+            share: if total > 0 { count as f64 / total as f64 } else { 0.0 },
+        })
+        .collect()
+}
+
+/// Email domain and country breakdowns of the unified audience. Countries
+/// come from platforms that report them (Ghost, Mailchimp archives);
+/// `top_domains` lists the `limit` (default 20) most common domains.
+#[tauri::command]
+pub async fn get_audience_demographics(app: AppHandle, limit: Option<usize>) -> Result<AudienceDemographics, AppError> {
+    let workspace_id = workspaces::current(&app);
+    query_cache::cached(Key::new("get_audience_demographics", &(limit, &workspace_id)), &["subscribers"], || {
+        audience_demographics(&app, &workspace_id, limit)
+    })
+}
+
+fn audience_demographics(
+    app: &AppHandle,
+    workspace_id: &str,
+    limit: Option<usize>,
+) -> Result<AudienceDemographics, AppError> {
+    let conn = db::get_db(app)?;
+    let total: i64 = conn.query_row(
+        "SELECT COUNT(*) FROM subscribers WHERE workspace_id = ?1",
+        rusqlite::params![workspace_id],
+        |row| row.get(0),
+    )?;
+
+    let mut domain_stmt = conn.prepare(
+        "SELECT lower(substr(email, instr(email, '@') + 1)) AS domain, COUNT(*) AS n
+         FROM subscribers WHERE instr(email, '@') > 0 AND workspace_id = ?1
+         GROUP BY domain ORDER BY n DESC, domain ASC",
+    )?;
+    let domains: Vec<DomainCount> = domain_stmt
+        .query_map(rusqlite::params![workspace_id], |row| {
+            let domain: String = row.get(0)?;
+            Ok(DomainCount { kind: domain_kind(&domain).to_string(), domain, count: row.get(1)? })
+        })?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    let mut kinds: Vec<(String, i64)> = Vec::new();
+    for domain in &domains {
+        match kinds.iter_mut().find(|(kind, _)| *kind == domain.kind) {
+            Some((_, count)) => *count += domain.count,
+            None => kinds.push((domain.kind.clone(), domain.count)),
+        }
+    }
+    kinds.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+
+    let mut country_stmt = conn.prepare(
+        "SELECT country, COUNT(*) AS n FROM subscribers WHERE country IS NOT NULL AND workspace_id = ?1
+         GROUP BY country ORDER BY n DESC, country ASC",
+    )?;
+    let countries: Vec<(String, i64)> = country_stmt
+        .query_map(rusqlite::params![workspace_id], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .filter_map(|r| r.ok())
+        .collect();
+    let known: i64 = countries.iter().map(|(_, n)| n).sum();
+
+    Ok(AudienceDemographics {
+        total,
+        domain_types: breakdown(kinds, total),
+        top_domains: domains.into_iter().take(limit.unwrap_or(20)).collect(),
+        countries: breakdown(countries, total),
+        unknown_country: total - known,
+    })
+}
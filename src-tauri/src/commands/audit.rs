@@ -0,0 +1,127 @@
+use rusqlite::Connection;
+use serde::Serialize;
+use tauri::AppHandle;
+
+use crate::db;
+use crate::error::AppError;
+use crate::permissions::{self, Permission};
+
+// ─── Audit log ──────────────────────────────────────────────────
+//
+// Credential access, data exports and bulk deletes are appended to
+// audit_log, separate from the activity feed (which storage pruning trims).
+// Each row carries the hash of the one before it; `verify_audit_log`
+// recomputes the chain, so a row edited, inserted or removed in the middle
+// shows up. Dropping rows off the end can only be caught by comparing the
+// head hash with one noted earlier.
+
+#[derive(Debug, Serialize, Clone)]
+pub struct AuditEntry {
+    pub seq: i64,
+    pub action: String,
+    pub entity_type: String,
+    pub entity_id: Option<String>,
+    pub details: Option<String>,
+    pub actor_role: String,
+    pub created_at: String,
+    pub trace_id: Option<String>,
+    pub hash: String,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct AuditVerification {
+    pub valid: bool,
+    pub rows_checked: i64,
+    /// First row whose hash or link doesn't match
+    pub broken_at_seq: Option<i64>,
+    /// Hash of the newest row that checks out; note it to detect later
+    /// truncation
+    pub head_hash: String,
+}
+
+/// Record a sensitive operation under the active role.
+pub(crate) fn record(
+    app: &AppHandle,
+    conn: &Connection,
+    action: &str,
+    entity_type: &str,
+    entity_id: Option<&str>,
+    details: Option<&str>,
+) -> Result<(), AppError> {
+    let role = permissions::current_role(app);
+    db::log_audit(conn, action, entity_type, entity_id, details, role.as_str())
+        .map_err(|e| AppError::from(format!("Failed to write audit log: {}", e)))
+}
+
+#[tauri::command]
+pub async fn list_audit_log(
+    app: AppHandle,
+    limit: Option<i64>,
+    before_seq: Option<i64>,
+) -> Result<Vec<AuditEntry>, AppError> {
+    permissions::require(&app, Permission::ViewAuditLog)?;
+    let conn = db::get_db(&app)?;
+    let mut stmt = conn.prepare(
+        "SELECT seq, action, entity_type, entity_id, details, actor_role, created_at, trace_id, hash
+         FROM audit_log WHERE (?1 IS NULL OR seq < ?1) ORDER BY seq DESC LIMIT ?2",
+    )?;
+    let rows = stmt.query_map(rusqlite::params![before_seq, limit.unwrap_or(100)], |row| {
+        Ok(AuditEntry {
+            seq: row.get(0)?,
+            action: row.get(1)?,
+            entity_type: row.get(2)?,
+            entity_id: row.get(3)?,
+            details: row.get(4)?,
+            actor_role: row.get(5)?,
+            created_at: row.get(6)?,
+            trace_id: row.get(7)?,
+            hash: row.get(8)?,
+        })
+    })?;
+    Ok(rows.filter_map(|r| r.ok()).collect())
+}
+
+/// Recompute the hash chain from the first row.
+#[tauri::command]
+pub async fn verify_audit_log(app: AppHandle) -> Result<AuditVerification, AppError> {
+    permissions::require(&app, Permission::ViewAuditLog)?;
+    let conn = db::get_db(&app)?;
+    let mut stmt = conn.prepare(
+        "SELECT seq, action, entity_type, entity_id, details, actor_role, created_at, prev_hash, hash
+         FROM audit_log ORDER BY seq ASC",
+    )?;
+    let mut rows = stmt.query([])?;
+
+    let mut expected_prev = db::AUDIT_GENESIS.to_string();
+    let mut expected_seq = 1;
+    let mut rows_checked = 0;
+    while let Some(row) = rows.next()? {
+        let seq: i64 = row.get(0)?;
+        let entity_id: Option<String> = row.get(3)?;
+        let details: Option<String> = row.get(4)?;
+        let prev_hash: String = row.get(7)?;
+        let hash: String = row.get(8)?;
+        let recomputed = db::audit_hash(
+            &prev_hash,
+            seq,
+            &row.get::<_, String>(1)?,
+            &row.get::<_, String>(2)?,
+            entity_id.as_deref(),
+            details.as_deref(),
+            &row.get::<_, String>(5)?,
+            &row.get::<_, String>(6)?,
+        );
+        rows_checked += 1;
+        if seq != expected_seq || prev_hash != expected_prev || recomputed != hash {
+            return Ok(AuditVerification {
+                valid: false,
+                rows_checked,
+                broken_at_seq: Some(seq),
+                head_hash: expected_prev,
+            });
+        }
+        expected_prev = hash;
+        expected_seq = seq + 1;
+    }
+    Ok(AuditVerification { valid: true, rows_checked, broken_at_seq: None, head_hash: expected_prev })
+}
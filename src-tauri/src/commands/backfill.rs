@@ -0,0 +1,207 @@
+use crate::commands::audience;
+use crate::commands::export::html_to_plain_text;
+use crate::commands::milestones;
+use crate::commands::platform::{self, AnalyticsData, ImportedPost};
+use crate::commands::snapshots;
+use crate::commands::workspaces;
+use crate::db;
+use crate::error::AppError;
+use crate::sanitize::sanitize_html;
+use chrono::Utc;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+
+// ─── Account backfill ───────────────────────────────────────────
+//
+// Runs once after a platform is connected: pulls the post archive,
+// subscriber list and a first analytics snapshot so dashboards have data
+// straight away. Progress is reported on the "backfill:progress" event.
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BackfillProgress {
+    pub job_id: String,
+    pub platform: String,
+    pub account_id: String,
+    /// "posts" | "subscribers" | "analytics" | "done"
+    pub stage: String,
+    pub message: String,
+    pub posts_imported: i64,
+    pub subscribers_new: i64,
+    pub subscribers_updated: i64,
+    pub snapshot_recorded: bool,
+    /// Per-stage failures; a failed stage doesn't stop the others
+    pub errors: Vec<String>,
+    pub trace_id: String,
+}
+
+/// Start a background backfill. Returns immediately; listen for
+/// "backfill:progress" events carrying `job_id`.
+#[tauri::command]
+pub async fn backfill_account(
+    app: AppHandle,
+    platform: String,
+    account_id: String,
+    publication_id: Option<String>,
+    job_id: String,
+) -> Result<(), AppError> {
+    // Fail fast on missing credentials rather than inside the task
+    platform::get_api_key(&app, &platform, &account_id)?;
+    snapshots::take_snapshot(&app, &*db::get_db(&app)?, "backfill")?;
+
+    let trace_id = crate::trace::new_id();
+    tokio::spawn(crate::trace::scope(trace_id.clone(), async move {
+        let mut progress = BackfillProgress {
+            job_id,
+            platform: platform.clone(),
+            account_id: account_id.clone(),
+            stage: "posts".to_string(),
+            message: "Importing posts".to_string(),
+            posts_imported: 0,
+            subscribers_new: 0,
+            subscribers_updated: 0,
+            snapshot_recorded: false,
+            errors: Vec::new(),
+            trace_id,
+        };
+        let _ = app.emit("backfill:progress", &progress);
+
+        match platform::import_posts(app.clone(), platform.clone(), account_id.clone(), publication_id.clone()).await {
+            Ok(posts) => match store_imported_posts(&app, &platform, &account_id, &posts) {
+                Ok(n) => progress.posts_imported = n,
+                Err(e) => progress.errors.push(format!("posts: {}", e)),
+            },
+            Err(e) => progress.errors.push(format!("posts: {}", e)),
+        }
+
+        progress.stage = "subscribers".to_string();
+        progress.message = format!("Imported {} posts; syncing subscribers", progress.posts_imported);
+        let _ = app.emit("backfill:progress", &progress);
+
+        match platform::get_subscribers(app.clone(), platform.clone(), account_id.clone(), publication_id.clone()).await {
+            Ok(subs) => match db::get_db(&app) {
+                Ok(conn) => {
+                    let workspace_id = workspaces::of_account(&app, &platform, &account_id);
+                    let (new, updated) =
+                        audience::upsert_platform_subscribers(&conn, &workspace_id, &platform, &account_id, &subs);
+                    progress.subscribers_new = new;
+                    progress.subscribers_updated = updated;
+                }
+                Err(e) => progress.errors.push(format!("subscribers: {}", e)),
+            },
+            Err(e) => progress.errors.push(format!("subscribers: {}", e)),
+        }
+
+        progress.stage = "analytics".to_string();
+        progress.message = format!(
+            "Synced {} subscribers; capturing analytics",
+            progress.subscribers_new + progress.subscribers_updated
+        );
+        let _ = app.emit("backfill:progress", &progress);
+
+        match platform::get_analytics(app.clone(), platform.clone(), account_id.clone(), publication_id.clone()).await {
+            Ok(data) => match db::get_db(&app).and_then(|conn| {
+                record_analytics_snapshot(&conn, &platform, &account_id, publication_id.as_deref(), &data)
+            }) {
+                Ok(()) => progress.snapshot_recorded = true,
+                Err(e) => progress.errors.push(format!("analytics: {}", e)),
+            },
+            Err(e) => progress.errors.push(format!("analytics: {}", e)),
+        }
+
+        progress.stage = "done".to_string();
+        progress.message = format!(
+            "Backfill complete: {} posts, {} new subscribers",
+            progress.posts_imported, progress.subscribers_new
+        );
+        if let Ok(conn) = db::get_db(&app) {
+            db::log_activity(&conn, "account.backfilled", "account", Some(&account_id), Some(&format!("{}: {}", platform, progress.message)));
+            milestones::evaluate(&app, &conn);
+        }
+        let _ = app.emit("backfill:progress", &progress);
+    }));
+
+    Ok(())
+}
+
+/// Save imported posts as published documents in the account's workspace,
+/// skipping any already linked to a document. Returns how many were newly
+/// created.
+pub(crate) fn store_imported_posts(
+    app: &AppHandle,
+    platform: &str,
+    account_id: &str,
+    posts: &[ImportedPost],
+) -> Result<i64, String> {
+    let mode = crate::text::count_mode(app);
+    let workspace_id = workspaces::of_account(app, platform, account_id);
+    let conn = db::get_db(app)?;
+    let now = Utc::now().to_rfc3339();
+    let mut created = 0i64;
+
+    for post in posts {
+        let exists: bool = conn
+            .query_row(
+                "SELECT 1 FROM platform_posts WHERE platform = ?1 AND account_id = ?2 AND platform_post_id = ?3",
+                rusqlite::params![platform, account_id, post.id],
+                |_| Ok(true),
+            )
+            .unwrap_or(false);
+        if exists {
+            continue;
+        }
+
+        let doc_id = uuid::Uuid::new_v4().to_string();
+        let html = sanitize_html(&post.html_content, post.url.as_deref());
+        let text = html_to_plain_text(&html);
+        let words = crate::text::count_text(&text, mode) as i64;
+        let chars = text.chars().count() as i64;
+        let published_at = post.published_at.clone().unwrap_or_else(|| now.clone());
+
+        conn.execute(
+            "INSERT INTO documents (id, title, content, html_content, status, published_at, word_count, character_count, version, workspace_id, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, 'published', ?5, ?6, ?7, 1, ?9, ?5, ?8)",
+            rusqlite::params![doc_id, post.title, text, html, published_at, words, chars, now, workspace_id],
+        )
+        .map_err(|e| format!("Failed to save imported post: {}", e))?;
+        crate::commands::links::index_document(&conn, &doc_id, &html);
+
+        conn.execute(
+            "INSERT INTO platform_posts (platform, account_id, platform_post_id, document_id, url, published_at, imported_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            rusqlite::params![platform, account_id, post.id, doc_id, post.url, post.published_at, now],
+        )
+        .map_err(|e| format!("Failed to link imported post: {}", e))?;
+
+        created += 1;
+    }
+
+    Ok(created)
+}
+
+/// Persist a point-in-time analytics reading for trend charts.
+pub(crate) fn record_analytics_snapshot(
+    conn: &rusqlite::Connection,
+    platform: &str,
+    account_id: &str,
+    publication_id: Option<&str>,
+    data: &AnalyticsData,
+) -> Result<(), String> {
+    let data_json = serde_json::to_string(data).map_err(|e| e.to_string())?;
+    conn.execute(
+        "INSERT INTO analytics_snapshots (id, platform, account_id, publication_id, total_subscribers, open_rate, click_rate, data_json, captured_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+        rusqlite::params![
+            uuid::Uuid::new_v4().to_string(),
+            platform,
+            account_id,
+            publication_id,
+            data.total_subscribers as i64,
+            data.open_rate,
+            data.click_rate,
+            data_json,
+            Utc::now().to_rfc3339(),
+        ],
+    )
+    .map_err(|e| format!("Failed to record analytics snapshot: {}", e))?;
+    Ok(())
+}
@@ -0,0 +1,307 @@
+use chrono::Utc;
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tauri::AppHandle;
+
+use crate::commands::export;
+use crate::db;
+use crate::error::AppError;
+use crate::permissions::{self, Permission};
+
+// ─── Content blocks ─────────────────────────────────────────────
+//
+// Recurring sections of an issue (sponsor slot, footer, about-the-author)
+// are saved once as HTML with `{{placeholder}}` fields. They are merged
+// into the outgoing HTML when publishing, so the draft itself never holds
+// a copy that goes stale.
+
+const KINDS: &[&str] = &["sponsor", "footer", "about", "custom"];
+/// Where a block lands: before the content, after its first paragraph,
+/// or at the end
+const POSITIONS: &[&str] = &["top", "after_intro", "bottom"];
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ContentBlock {
+    pub id: String,
+    pub name: String,
+    pub kind: String, // "sponsor" | "footer" | "about" | "custom"
+    pub html: String,
+    pub position: String, // "top" | "after_intro" | "bottom"
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct AssembledIssue {
+    pub html: String,
+    /// Placeholders with no value; they are left out of the HTML
+    pub unresolved: Vec<String>,
+}
+
+const COLUMNS: &str = "id, name, kind, html, position, created_at, updated_at";
+
+fn block_from_row(row: &rusqlite::Row) -> rusqlite::Result<ContentBlock> {
+    Ok(ContentBlock {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        kind: row.get(2)?,
+        html: row.get(3)?,
+        position: row.get(4)?,
+        created_at: row.get(5)?,
+        updated_at: row.get(6)?,
+    })
+}
+
+fn validate(kind: &str, position: &str) -> Result<(), AppError> {
+    if !KINDS.contains(&kind) {
+        return Err(AppError::validation(format!("Unknown block kind '{}'", kind)));
+    }
+    if !POSITIONS.contains(&position) {
+        return Err(AppError::validation(format!("Unknown block position '{}'", position)));
+    }
+    Ok(())
+}
+
+fn load_block(conn: &Connection, id: &str) -> Result<ContentBlock, AppError> {
+    conn.query_row(
+        &format!("SELECT {} FROM content_blocks WHERE id = ?1", COLUMNS),
+        rusqlite::params![id],
+        block_from_row,
+    )
+    .map_err(|_| AppError::not_found(format!("Content block '{}' not found", id)))
+}
+
+fn html_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Built-in placeholder values for a document: `title`, `date`, `year`,
+/// `word_count`, `brand.*` from its project's brand kit, plus `sponsor.*`
+/// from the deal booked on this issue.
+pub(crate) fn document_values(conn: &Connection, document_id: &str) -> Result<HashMap<String, String>, AppError> {
+    let (title, word_count): (String, i64) = conn
+        .query_row(
+            "SELECT title, word_count FROM documents WHERE id = ?1",
+            rusqlite::params![document_id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .map_err(|_| AppError::not_found(format!("Document '{}' not found", document_id)))?;
+    let today = Utc::now();
+
+    let mut values = HashMap::new();
+    values.insert("title".to_string(), title);
+    values.insert("date".to_string(), today.format("%B %-d, %Y").to_string());
+    values.insert("year".to_string(), today.format("%Y").to_string());
+    values.insert("word_count".to_string(), word_count.to_string());
+
+    if let Some(defaults) = export::defaults_for_document(conn, document_id) {
+        let brand = defaults.brand;
+        for (key, value) in [
+            ("brand.logo_url", brand.logo_url),
+            ("brand.accent_color", brand.accent_color),
+            ("brand.font_family", brand.font_family),
+        ] {
+            if let Some(value) = value {
+                values.insert(key.to_string(), value);
+            }
+        }
+    }
+
+    let sponsor: Option<(String, Option<String>, Option<String>, String)> = conn
+        .query_row(
+            "SELECT c.name, c.company, c.email, d.title FROM sponsorship_deals d
+             JOIN contacts c ON c.id = d.contact_id
+             WHERE d.document_id = ?1 ORDER BY d.updated_at DESC LIMIT 1",
+            rusqlite::params![document_id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+        )
+        .ok();
+    if let Some((name, company, email, deal_title)) = sponsor {
+        values.insert("sponsor.name".to_string(), company.unwrap_or_else(|| name.clone()));
+        values.insert("sponsor.contact".to_string(), name);
+        values.insert("sponsor.title".to_string(), deal_title);
+        if let Some(email) = email {
+            values.insert("sponsor.email".to_string(), email);
+        }
+    }
+    Ok(values)
+}
+
+/// Replace `{{key}}` fields with escaped values. Unknown keys are dropped
+/// and recorded in `unresolved`.
+pub(crate) fn fill_placeholders(html: &str, values: &HashMap<String, String>, unresolved: &mut Vec<String>) -> String {
+    let mut out = String::with_capacity(html.len());
+    let mut rest = html;
+    while let Some(start) = rest.find("{{") {
+        let Some(len) = rest[start + 2..].find("}}") else {
+            break;
+        };
+        out.push_str(&rest[..start]);
+        let key = rest[start + 2..start + 2 + len].trim();
+        match values.get(key) {
+            Some(value) => out.push_str(&html_escape(value)),
+            None => {
+                if !unresolved.iter().any(|k| k == key) {
+                    unresolved.push(key.to_string());
+                }
+            }
+        }
+        rest = &rest[start + 2 + len + 2..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Merge `block_ids` into `html`, in the given order within each position.
+/// `values` override the built-in placeholders.
+pub(crate) fn assemble(
+    conn: &Connection,
+    document_id: &str,
+    html: &str,
+    block_ids: &[String],
+    values: &HashMap<String, String>,
+) -> Result<AssembledIssue, AppError> {
+    let mut all_values = document_values(conn, document_id)?;
+    all_values.extend(values.iter().map(|(k, v)| (k.clone(), v.clone())));
+
+    let mut unresolved = Vec::new();
+    let mut top = String::new();
+    let mut intro = String::new();
+    let mut bottom = String::new();
+    for id in block_ids {
+        let block = load_block(conn, id)?;
+        let filled = fill_placeholders(&block.html, &all_values, &mut unresolved);
+        match block.position.as_str() {
+            "top" => top.push_str(&filled),
+            "after_intro" => intro.push_str(&filled),
+            _ => bottom.push_str(&filled),
+        }
+    }
+
+    let body = match html.find("</p>") {
+        Some(i) if !intro.is_empty() => format!("{}{}{}", &html[..i + 4], intro, &html[i + 4..]),
+        // No paragraph to follow: the intro blocks open the issue
+        _ => format!("{}{}", intro, html),
+    };
+    Ok(AssembledIssue { html: format!("{}{}{}", top, body, bottom), unresolved })
+}
+
+#[tauri::command]
+pub async fn create_content_block(
+    app: AppHandle,
+    name: String,
+    html: String,
+    kind: Option<String>,
+    position: Option<String>,
+) -> Result<ContentBlock, AppError> {
+    let kind = kind.unwrap_or_else(|| "custom".to_string());
+    let position = position.unwrap_or_else(|| "bottom".to_string());
+    validate(&kind, &position)?;
+    if name.trim().is_empty() {
+        return Err(AppError::validation("Block name is required"));
+    }
+    let conn = db::get_db(&app)?;
+    let id = uuid::Uuid::new_v4().to_string();
+    let now = Utc::now().to_rfc3339();
+
+    conn.execute(
+        "INSERT INTO content_blocks (id, name, kind, html, position, created_at, updated_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?6)",
+        rusqlite::params![id, name, kind, html, position, now],
+    )
+    .map_err(|e| format!("Failed to create content block: {}", e))?;
+
+    Ok(ContentBlock {
+        id,
+        name,
+        kind,
+        html,
+        position,
+        created_at: now.clone(),
+        updated_at: now,
+    })
+}
+
+#[tauri::command]
+pub async fn list_content_blocks(app: AppHandle, kind: Option<String>) -> Result<Vec<ContentBlock>, AppError> {
+    let conn = db::get_db(&app)?;
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM content_blocks WHERE (?1 IS NULL OR kind = ?1) ORDER BY name COLLATE NOCASE ASC",
+        COLUMNS
+    ))?;
+    let rows = stmt.query_map(rusqlite::params![kind], block_from_row)?;
+    Ok(rows.filter_map(|r| r.ok()).collect())
+}
+
+#[tauri::command]
+pub async fn update_content_block(
+    app: AppHandle,
+    id: String,
+    name: Option<String>,
+    html: Option<String>,
+    kind: Option<String>,
+    position: Option<String>,
+) -> Result<ContentBlock, AppError> {
+    let conn = db::get_db(&app)?;
+    let mut block = load_block(&conn, &id)?;
+
+    if let Some(n) = name {
+        if n.trim().is_empty() {
+            return Err(AppError::validation("Block name is required"));
+        }
+        block.name = n;
+    }
+    if let Some(h) = html {
+        block.html = h;
+    }
+    if let Some(k) = kind {
+        block.kind = k;
+    }
+    if let Some(p) = position {
+        block.position = p;
+    }
+    validate(&block.kind, &block.position)?;
+    block.updated_at = Utc::now().to_rfc3339();
+
+    conn.execute(
+        "UPDATE content_blocks SET name = ?1, html = ?2, kind = ?3, position = ?4, updated_at = ?5 WHERE id = ?6",
+        rusqlite::params![block.name, block.html, block.kind, block.position, block.updated_at, id],
+    )
+    .map_err(|e| format!("Failed to update content block: {}", e))?;
+
+    Ok(block)
+}
+
+#[tauri::command]
+pub async fn delete_content_block(app: AppHandle, id: String) -> Result<(), AppError> {
+    permissions::require(&app, Permission::DeleteContent)?;
+    let conn = db::get_db(&app)?;
+    conn.execute("DELETE FROM content_blocks WHERE id = ?1", rusqlite::params![id])
+        .map_err(|e| format!("Failed to delete content block: {}", e))?;
+    Ok(())
+}
+
+/// The document's HTML with `block_ids` merged in, as it would be sent.
+/// The stored draft is left untouched; publishing does the same merge from
+/// `PublishOptions.block_ids`.
+#[tauri::command]
+pub async fn assemble_issue(
+    app: AppHandle,
+    document_id: String,
+    block_ids: Vec<String>,
+    values: Option<HashMap<String, String>>,
+) -> Result<AssembledIssue, AppError> {
+    let conn = db::get_db(&app)?;
+    let html: String = conn
+        .query_row(
+            "SELECT html_content FROM documents WHERE id = ?1",
+            rusqlite::params![document_id],
+            |row| row.get(0),
+        )
+        .map_err(|_| AppError::not_found(format!("Document '{}' not found", document_id)))?;
+    assemble(&conn, &document_id, &html, &block_ids, &values.unwrap_or_default())
+}
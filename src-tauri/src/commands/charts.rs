@@ -0,0 +1,250 @@
+use base64::Engine;
+use plotters::coord::Shift;
+use plotters::prelude::*;
+use serde::Deserialize;
+use std::sync::OnceLock;
+use tauri::AppHandle;
+
+use crate::commands::analytics::thousands;
+use crate::commands::export;
+use crate::error::AppError;
+
+// ─── Charts ─────────────────────────────────────────────────────
+//
+// Bar and line charts of monthly series (growth, revenue, open rates)
+// for reports and exports, drawn with plotters. PNG is what the PDF and
+// Word exporters can embed; SVG stays sharp in HTML. Labels need a system
+// sans-serif font; without one the charts are drawn without text.
+
+const DEFAULT_WIDTH: u32 = 1200;
+const DEFAULT_HEIGHT: u32 = 480;
+/// Font family the chart text is registered under
+const CHART_FONT: &str = "sans-serif";
+const PALETTE: [RGBColor; 5] = [
+    RGBColor(124, 58, 237),
+    RGBColor(13, 148, 136),
+    RGBColor(217, 119, 6),
+    RGBColor(225, 29, 72),
+    RGBColor(2, 132, 199),
+];
+const GRID: RGBColor = RGBColor(229, 231, 235);
+const TEXT: RGBColor = RGBColor(55, 65, 81);
+
+/// Whether a font for chart text was found; registered once per run.
+static CHART_TEXT: OnceLock<bool> = OnceLock::new();
+
+#[derive(Debug, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct ChartSeries {
+    pub name: String,
+    /// One per label; missing values are drawn as zero
+    pub values: Vec<f64>,
+    /// "#rrggbb"; from the palette when unset
+    pub color: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct ChartSpec {
+    pub title: Option<String>,
+    /// "bar" (default) | "line"
+    pub kind: Option<String>,
+    /// One per point along the x axis, e.g. "Jan 2026"
+    pub labels: Vec<String>,
+    pub series: Vec<ChartSeries>,
+    /// How values read on the y axis: "number" (default) | "percent" |
+    /// "dollars" (values in cents)
+    pub value_format: Option<String>,
+    /// In px; 1200×480 by default
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+}
+
+impl ChartSpec {
+    /// A single-series chart, as the reports draw them.
+    pub(crate) fn single(kind: &str, labels: Vec<String>, name: &str, values: Vec<f64>, value_format: &str) -> Self {
+        ChartSpec {
+            kind: Some(kind.to_string()),
+            labels,
+            series: vec![ChartSeries { name: name.to_string(), values, color: None }],
+            value_format: Some(value_format.to_string()),
+            ..Default::default()
+        }
+    }
+
+    fn size(&self) -> (u32, u32) {
+        (
+            self.width.unwrap_or(DEFAULT_WIDTH).clamp(200, 3000),
+            self.height.unwrap_or(DEFAULT_HEIGHT).clamp(150, 2000),
+        )
+    }
+}
+
+/// Register the system sans-serif for chart text, once. Plotters keeps
+/// fonts for the life of the process, so the font data is leaked.
+pub(crate) fn chart_text(app: &AppHandle) -> bool {
+    *CHART_TEXT.get_or_init(|| {
+        let Some(face) = export::system_sans_face(app) else {
+            return false;
+        };
+        let bytes: &'static [u8] = Box::leak(face.as_slice().to_vec().into_boxed_slice());
+        plotters::style::register_font(CHART_FONT, FontStyle::Normal, bytes).is_ok()
+    })
+}
+
+fn parse_color(hex: &str) -> Option<RGBColor> {
+    let hex = hex.trim().strip_prefix('#')?;
+    if hex.len() != 6 {
+        return None;
+    }
+    let channel = |i: usize| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok();
+    Some(RGBColor(channel(0)?, channel(2)?, channel(4)?))
+}
+
+fn format_value(value: f64, format: &str) -> String {
+    match format {
+        "percent" => format!("{:.0}%", value),
+        "dollars" => format!("${}", thousands((value / 100.0).round() as i64)),
+        _ if value.abs() >= 10_000.0 => format!("{}k", thousands((value / 1000.0).round() as i64)),
+        _ => thousands(value.round() as i64),
+    }
+}
+
+fn validate(spec: &ChartSpec) -> Result<(), AppError> {
+    if spec.labels.is_empty() {
+        return Err(AppError::validation("A chart needs at least one label"));
+    }
+    if spec.series.is_empty() {
+        return Err(AppError::validation("A chart needs at least one series"));
+    }
+    if let Some(kind) = spec.kind.as_deref().filter(|k| !["bar", "line"].contains(k)) {
+        return Err(AppError::validation(format!("Unknown chart kind '{}'", kind)));
+    }
+    Ok(())
+}
+
+fn draw<DB: DrawingBackend>(root: DrawingArea<DB, Shift>, spec: &ChartSpec, text: bool) -> Result<(), String>
+where
+    DB::ErrorType: 'static,
+{
+    let err = |e: DrawingAreaErrorKind<DB::ErrorType>| format!("Failed to draw chart: {}", e);
+    root.fill(&WHITE).map_err(err)?;
+
+    let count = spec.labels.len();
+    let value = |series: &ChartSeries, i: usize| series.values.get(i).copied().filter(|v| v.is_finite()).unwrap_or(0.0);
+    let values = || spec.series.iter().flat_map(|s| (0..count).map(move |i| value(s, i)));
+    let low = values().fold(0.0, f64::min);
+    let high = values().fold(0.0, f64::max);
+    let (low, high) = if high - low <= 0.0 { (0.0, 1.0) } else { (low * 1.1, high * 1.1) };
+
+    let x_range = -0.5..count as f64 - 0.5;
+
+    let mut builder = ChartBuilder::on(&root);
+    builder.margin(16);
+    if text {
+        if let Some(title) = spec.title.as_deref().filter(|t| !t.trim().is_empty()) {
+            builder.caption(title.trim(), (CHART_FONT, 24).into_font().color(&TEXT));
+        }
+        builder.x_label_area_size(32).y_label_area_size(64);
+    }
+    let mut chart = builder.build_cartesian_2d(x_range, low..high).map_err(err)?;
+
+    let value_format = spec.value_format.as_deref().unwrap_or("number");
+    // Labels sit on whole x values; plotters may pick steps of 2 or 5 on long series
+    let label_for = |x: &f64| match spec.labels.get(x.round().max(0.0) as usize) {
+        Some(label) if (x - x.round()).abs() < 0.01 => label.clone(),
+        _ => String::new(),
+    };
+    let format_y = |y: &f64| format_value(*y, value_format);
+    let mut mesh = chart.configure_mesh();
+    mesh.disable_x_mesh().light_line_style(WHITE).bold_line_style(GRID).axis_style(GRID);
+    if text {
+        mesh.x_labels(count.min(12))
+            .x_label_formatter(&label_for)
+            .y_label_formatter(&format_y)
+            .y_labels(5)
+            .label_style((CHART_FONT, 14).into_font().color(&TEXT));
+    } else {
+        mesh.x_labels(0).y_labels(5).y_label_formatter(&|_| String::new());
+    }
+    mesh.draw().map_err(err)?;
+
+    let bar = spec.kind.as_deref() != Some("line");
+    let slot = 0.7 / spec.series.len() as f64;
+    for (n, series) in spec.series.iter().enumerate() {
+        let color = series.color.as_deref().and_then(parse_color).unwrap_or(PALETTE[n % PALETTE.len()]);
+        let points = (0..count).map(|i| (i as f64, value(series, i)));
+        let drawn = if bar {
+            let left = -0.35 + slot * n as f64;
+            chart.draw_series(points.map(|(x, y)| {
+                Rectangle::new([(x + left, 0.0), (x + left + slot * 0.9, y)], color.filled())
+            }))
+        } else {
+            chart.draw_series(LineSeries::new(points, color.stroke_width(3)))
+        }
+        .map_err(err)?;
+        if text && spec.series.len() > 1 {
+            drawn.label(series.name.clone()).legend(move |(x, y)| {
+                Rectangle::new([(x, y - 5), (x + 14, y + 5)], color.filled())
+            });
+        }
+    }
+    if text && spec.series.len() > 1 {
+        chart
+            .configure_series_labels()
+            .background_style(WHITE.mix(0.9))
+            .border_style(GRID)
+            .label_font((CHART_FONT, 14).into_font().color(&TEXT))
+            .draw()
+            .map_err(err)?;
+    }
+    root.present().map_err(err)?;
+    Ok(())
+}
+
+/// `spec` as PNG bytes. `text` is the result of `chart_text`.
+pub(crate) fn render_png(spec: &ChartSpec, text: bool) -> Result<Vec<u8>, AppError> {
+    validate(spec)?;
+    let (width, height) = spec.size();
+    let mut pixels = vec![0u8; width as usize * height as usize * 3];
+    draw(BitMapBackend::with_buffer(&mut pixels, (width, height)).into_drawing_area(), spec, text)?;
+    let canvas = ::image::RgbImage::from_raw(width, height, pixels).ok_or("Chart buffer has the wrong size")?;
+    let mut png = Vec::new();
+    canvas
+        .write_to(&mut std::io::Cursor::new(&mut png), ::image::ImageFormat::Png)
+        .map_err(|e| format!("Failed to encode chart: {}", e))?;
+    Ok(png)
+}
+
+/// `spec` as an SVG document. `text` is the result of `chart_text`.
+pub(crate) fn render_svg(spec: &ChartSpec, text: bool) -> Result<String, AppError> {
+    validate(spec)?;
+    let mut svg = String::new();
+    draw(SVGBackend::with_string(&mut svg, spec.size()).into_drawing_area(), spec, text)?;
+    Ok(svg)
+}
+
+/// `spec` as a PNG data URI, for an `<img>` in report HTML that is also
+/// turned into a PDF.
+pub(crate) fn png_data_uri(spec: &ChartSpec, text: bool) -> Result<String, AppError> {
+    let png = render_png(spec, text)?;
+    Ok(format!("data:image/png;base64,{}", base64::engine::general_purpose::STANDARD.encode(png)))
+}
+
+/// Render a chart as a data URI to embed in a document or export.
+/// `format` is "png" (default, embeddable in PDF and Word exports) or "svg".
+#[tauri::command]
+pub async fn render_chart(app: AppHandle, spec: ChartSpec, format: Option<String>) -> Result<String, AppError> {
+    let text = chart_text(&app);
+    let format = format.unwrap_or_else(|| "png".to_string());
+    tokio::task::spawn_blocking(move || match format.as_str() {
+        "png" => png_data_uri(&spec, text),
+        "svg" => {
+            let svg = render_svg(&spec, text)?;
+            Ok(format!("data:image/svg+xml;base64,{}", base64::engine::general_purpose::STANDARD.encode(svg)))
+        }
+        other => Err(AppError::validation(format!("Unknown chart format '{}'", other))),
+    })
+    .await
+    .map_err(|e| format!("Chart task failed: {}", e))?
+}
@@ -0,0 +1,333 @@
+use chrono::Utc;
+use rusqlite::{Connection, OptionalExtension};
+use serde::Serialize;
+use tauri::AppHandle;
+
+use crate::commands::export::html_to_markdown;
+use crate::commands::platform::{self, ImportedPost};
+use crate::commands::usage;
+use crate::db;
+use crate::error::AppError;
+use crate::services::beehiiv::BeehiivService;
+use crate::services::ghost::GhostService;
+use crate::services::wordpress::WordPressService;
+
+// ─── Published copy comparison ──────────────────────────────────
+//
+// Fetch the live copy of a document from the platform and diff it against
+// the draft, to catch what a platform's importer dropped or rewrote. Both
+// bodies are normalized through the Markdown exporter, so markup that
+// renders the same (attributes, wrappers, whitespace) compares equal, and
+// images compare by file name since platforms re-host them. Blocks are
+// diffed first; a removed block facing an added one is reported as a
+// change, with a word diff inside it.
+
+/// Past this many blocks a side, blocks are compared position by position
+/// rather than aligned (the alignment is quadratic)
+const MAX_BLOCKS: usize = 2000;
+
+#[derive(Debug, Serialize, Clone)]
+pub struct DiffSpan {
+    /// "equal" | "removed" | "added"
+    pub kind: String,
+    pub text: String,
+}
+
+/// One block that differs. Indexes are block positions, from 0.
+#[derive(Debug, Serialize, Clone)]
+pub struct BlockChange {
+    /// "removed" (only in the draft) | "added" (only on the platform) | "changed"
+    pub kind: String,
+    pub local_index: Option<usize>,
+    pub published_index: Option<usize>,
+    /// Normalized Markdown of the block on each side
+    pub local: Option<String>,
+    pub published: Option<String>,
+    /// Word diff of a changed block
+    pub spans: Vec<DiffSpan>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct PublishedComparison {
+    pub document_id: String,
+    pub platform: String,
+    pub post_id: String,
+    pub url: Option<String>,
+    pub fetched_at: String,
+    pub identical: bool,
+    pub unchanged_blocks: usize,
+    pub changes: Vec<BlockChange>,
+}
+
+/// Where a document went out on a platform: (account_id, publication_id,
+/// platform post id, url), from the scheduler or an import.
+type PublishedRecord = (String, Option<String>, String, Option<String>);
+
+fn published_record(
+    conn: &Connection,
+    document_id: &str,
+    platform: &str,
+    account_id: Option<&str>,
+) -> Result<Option<PublishedRecord>, AppError> {
+    // Scheduled posts keep the platform's id on their publish attempt
+    // (older ones in published_url); imports in platform_posts
+    Ok(conn
+        .query_row(
+            "SELECT account_id, publication_id, post_id, url FROM (
+                 SELECT p.account_id, p.publication_id, p.updated_at AS at, NULL AS url,
+                     COALESCE((SELECT a.post_id FROM publish_attempts a
+                               WHERE a.scheduled_post_id = p.id AND a.status = 'published'
+                               ORDER BY a.started_at DESC LIMIT 1), p.published_url) AS post_id
+                 FROM scheduled_posts p
+                 WHERE p.document_id = ?1 AND p.platform = ?2 AND p.status = 'published'
+                 UNION ALL
+                 SELECT account_id, NULL, COALESCE(published_at, imported_at), url, platform_post_id
+                 FROM platform_posts WHERE document_id = ?1 AND platform = ?2
+             )
+             WHERE post_id IS NOT NULL AND post_id != '' AND (?3 IS NULL OR account_id = ?3)
+             ORDER BY at DESC LIMIT 1",
+            rusqlite::params![document_id, platform, account_id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+        )
+        .optional()?)
+}
+
+/// Editor or platform HTML as comparable blocks: Markdown split at blank
+/// lines, whitespace collapsed, image URLs cut to their file names.
+fn normalize(html: &str) -> Vec<String> {
+    html_to_markdown(&html.replace('\u{a0}', " "))
+        .split("\n\n")
+        .map(|block| {
+            let lines: Vec<String> = block
+                .lines()
+                .map(|line| image_names(&line.split_whitespace().collect::<Vec<_>>().join(" ")))
+                .filter(|line| !line.is_empty())
+                .collect();
+            lines.join("\n")
+        })
+        .filter(|block| !block.is_empty())
+        .collect()
+}
+
+/// `![alt](https://cdn.example.com/a/b/photo.jpg?w=600)` as `![alt](photo.jpg)`.
+fn image_names(line: &str) -> String {
+    let mut out = String::new();
+    let mut rest = line;
+    while let Some(start) = rest.find("![") {
+        let Some(open) = rest[start..].find("](").map(|i| start + i + 2) else {
+            break;
+        };
+        let Some(close) = rest[open..].find(')').map(|i| open + i) else {
+            break;
+        };
+        let url = rest[open..close].split(['?', '#']).next().unwrap_or_default();
+        let name = url.trim_end_matches('/').rsplit('/').next().unwrap_or(url);
+        out.push_str(&rest[..open]);
+        out.push_str(name);
+        rest = &rest[close..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Longest-common-subsequence alignment of `a` and `b`, as (a index,
+/// b index) pairs with None on the side an element is missing from.
+fn align<T: PartialEq>(a: &[T], b: &[T]) -> Vec<(Option<usize>, Option<usize>)> {
+    let (n, m) = (a.len(), b.len());
+    // lengths[i][j]: LCS of a[i..] and b[j..]
+    let mut lengths = vec![vec![0u32; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lengths[i][j] = if a[i] == b[j] {
+                lengths[i + 1][j + 1] + 1
+            } else {
+                lengths[i + 1][j].max(lengths[i][j + 1])
+            };
+        }
+    }
+    let (mut i, mut j) = (0, 0);
+    let mut pairs = Vec::new();
+    while i < n || j < m {
+        if i < n && j < m && a[i] == b[j] {
+            pairs.push((Some(i), Some(j)));
+            i += 1;
+            j += 1;
+        } else if j == m || (i < n && lengths[i + 1][j] >= lengths[i][j + 1]) {
+            pairs.push((Some(i), None));
+            i += 1;
+        } else {
+            pairs.push((None, Some(j)));
+            j += 1;
+        }
+    }
+    pairs
+}
+
+fn word_diff(local: &str, published: &str) -> Vec<DiffSpan> {
+    let a: Vec<&str> = local.split_whitespace().collect();
+    let b: Vec<&str> = published.split_whitespace().collect();
+    let mut spans: Vec<DiffSpan> = Vec::new();
+    for pair in align(&a, &b) {
+        let (kind, word) = match pair {
+            (Some(i), Some(_)) => ("equal", a[i]),
+            (Some(i), None) => ("removed", a[i]),
+            (None, Some(j)) => ("added", b[j]),
+            (None, None) => continue,
+        };
+        match spans.last_mut() {
+            Some(last) if last.kind == kind => {
+                last.text.push(' ');
+                last.text.push_str(word);
+            }
+            _ => spans.push(DiffSpan { kind: kind.to_string(), text: word.to_string() }),
+        }
+    }
+    spans
+}
+
+/// The blocks that differ, and how many matched.
+fn diff_blocks(local: &[String], published: &[String]) -> (usize, Vec<BlockChange>) {
+    let pairs = if local.len().max(published.len()) > MAX_BLOCKS {
+        // Too long to align: compare position by position
+        (0..local.len().max(published.len()))
+            .map(|i| (Some(i).filter(|i| *i < local.len()), Some(i).filter(|i| *i < published.len())))
+            .collect()
+    } else {
+        align(local, published)
+    };
+
+    let mut unchanged = 0;
+    let mut changes = Vec::new();
+    let mut removed: Vec<usize> = Vec::new();
+    let mut added: Vec<usize> = Vec::new();
+    // Pair each run of removals with the additions beside it
+    let flush = |removed: &mut Vec<usize>, added: &mut Vec<usize>, changes: &mut Vec<BlockChange>| {
+        for k in 0..removed.len().max(added.len()) {
+            let (l, p) = (removed.get(k).copied(), added.get(k).copied());
+            let local_text = l.map(|i| local[i].clone());
+            let published_text = p.map(|j| published[j].clone());
+            let (kind, spans) = match (&local_text, &published_text) {
+                (Some(a), Some(b)) => ("changed", word_diff(a, b)),
+                (Some(_), None) => ("removed", Vec::new()),
+                _ => ("added", Vec::new()),
+            };
+            changes.push(BlockChange {
+                kind: kind.to_string(),
+                local_index: l,
+                published_index: p,
+                local: local_text,
+                published: published_text,
+                spans,
+            });
+        }
+        removed.clear();
+        added.clear();
+    };
+    for pair in pairs {
+        match pair {
+            (Some(i), Some(j)) if local[i] == published[j] => {
+                flush(&mut removed, &mut added, &mut changes);
+                unchanged += 1;
+            }
+            (l, p) => {
+                removed.extend(l);
+                added.extend(p);
+            }
+        }
+    }
+    flush(&mut removed, &mut added, &mut changes);
+    (unchanged, changes)
+}
+
+/// The live copy of the post on the platform.
+async fn fetch_published(
+    api_key: &str,
+    platform: &str,
+    publication_ids: &[String],
+    post_id: &str,
+) -> Result<Option<ImportedPost>, AppError> {
+    match platform {
+        "ghost" => GhostService::get_post(api_key, post_id).await,
+        "beehiiv" => {
+            for publication_id in publication_ids {
+                if let Some(post) = BeehiivService::get_post(api_key, publication_id, post_id).await? {
+                    return Ok(Some(post));
+                }
+            }
+            Ok(None)
+        }
+        "wordpress" => WordPressService::get_post(api_key, post_id).await,
+        _ => Err(AppError::validation(format!("Can't compare with posts on {}", platform))),
+    }
+}
+
+/// Diff a document against its published copy on Ghost, Beehiiv or
+/// WordPress. The copy is the one the scheduler published or an import
+/// came from, on `account_id` when given. WordPress posts that weren't
+/// recorded are looked up by title, which needs `account_id`.
+#[tauri::command]
+pub async fn compare_with_published(
+    app: AppHandle,
+    document_id: String,
+    platform: String,
+    account_id: Option<String>,
+) -> Result<PublishedComparison, AppError> {
+    if !matches!(platform.as_str(), "ghost" | "beehiiv" | "wordpress") {
+        return Err(AppError::validation(format!("Can't compare with posts on {}", platform)));
+    }
+    let (title, html, record, publications) = {
+        let conn = db::get_db(&app)?;
+        let (title, html): (String, String) = conn
+            .query_row(
+                "SELECT title, html_content FROM documents WHERE id = ?1",
+                rusqlite::params![document_id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .map_err(|_| AppError::not_found(format!("Document '{}' not found", document_id)))?;
+        let record = published_record(&conn, &document_id, &platform, account_id.as_deref())?;
+        let account = record.as_ref().map(|r| r.0.clone()).or_else(|| account_id.clone());
+        let mut stmt =
+            conn.prepare("SELECT publication_id FROM publications WHERE platform = ?1 AND account_id = ?2")?;
+        let publications: Vec<String> = stmt
+            .query_map(rusqlite::params![platform, account], |row| row.get(0))?
+            .filter_map(|r| r.ok())
+            .collect();
+        (title, html, record, publications)
+    };
+
+    let post = match &record {
+        Some((account, publication_id, post_id, _)) => {
+            let api_key = platform::get_api_key(&app, &platform, account)?;
+            usage::record_api_call(&app, &platform, account);
+            let candidates: Vec<String> = publication_id.iter().cloned().chain(publications).collect();
+            fetch_published(&api_key, &platform, &candidates, post_id).await?.ok_or_else(|| {
+                AppError::not_found(format!("The post is no longer on {}", platform)).with_platform(&platform)
+            })?
+        }
+        None if platform == "wordpress" => {
+            let account = account_id
+                .as_deref()
+                .ok_or_else(|| AppError::validation("Choose the WordPress account to look the post up on"))?;
+            let api_key = platform::get_api_key(&app, &platform, account)?;
+            usage::record_api_call(&app, &platform, account);
+            WordPressService::find_post_by_title(&api_key, &title).await?.ok_or_else(|| {
+                AppError::not_found(format!("No WordPress post titled '{}'", title)).with_platform("wordpress")
+            })?
+        }
+        None => {
+            return Err(AppError::not_found(format!("This document hasn't been published to {} from here", platform)));
+        }
+    };
+
+    let (unchanged_blocks, changes) = diff_blocks(&normalize(&html), &normalize(&post.html_content));
+    Ok(PublishedComparison {
+        document_id,
+        platform,
+        post_id: post.id,
+        url: post.url.or_else(|| record.and_then(|r| r.3)),
+        fetched_at: Utc::now().to_rfc3339(),
+        identical: changes.is_empty(),
+        unchanged_blocks,
+        changes,
+    })
+}
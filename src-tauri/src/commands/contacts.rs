@@ -0,0 +1,425 @@
+use crate::db;
+use crate::error::AppError;
+use crate::permissions::{self, Permission};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+const ROLES: &[&str] = &["sponsor", "editor", "guest"];
+/// Same pipeline stages as the Sponsors tab (SPONSOR_STAGES)
+const DEAL_STATUSES: &[&str] = &["prospect", "outreach", "negotiation", "booked", "live", "invoiced", "paid"];
+
+/// Someone we work with: a sponsor, an editor, or a guest writer or
+/// interviewee.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Contact {
+    pub id: String,
+    pub name: String,
+    pub company: Option<String>,
+    pub email: Option<String>,
+    pub role: String, // "sponsor" | "editor" | "guest"
+    pub notes: String,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// A sponsorship deal with a contact, at some pipeline stage. `document_id`
+/// is the issue carrying the slot, once known; `run_date` is "YYYY-MM-DD".
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SponsorshipDeal {
+    pub id: String,
+    pub contact_id: String,
+    pub title: String,
+    pub amount_cents: i64,
+    pub currency: String,
+    pub status: String, // "prospect" | "outreach" | "negotiation" | "booked" | "live" | "invoiced" | "paid"
+    pub document_id: Option<String>,
+    pub run_date: Option<String>,
+    pub notes: String,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ContactDetail {
+    pub contact: Contact,
+    pub deals: Vec<SponsorshipDeal>,
+    /// Sum of paid deals, in cents per currency
+    pub paid_cents: Vec<(String, i64)>,
+}
+
+const CONTACT_COLUMNS: &str = "id, name, company, email, role, notes, created_at, updated_at";
+const DEAL_COLUMNS: &str =
+    "id, contact_id, title, amount_cents, currency, status, document_id, run_date, notes, created_at, updated_at";
+
+fn contact_from_row(row: &rusqlite::Row) -> rusqlite::Result<Contact> {
+    Ok(Contact {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        company: row.get(2)?,
+        email: row.get(3)?,
+        role: row.get(4)?,
+        notes: row.get(5)?,
+        created_at: row.get(6)?,
+        updated_at: row.get(7)?,
+    })
+}
+
+fn deal_from_row(row: &rusqlite::Row) -> rusqlite::Result<SponsorshipDeal> {
+    Ok(SponsorshipDeal {
+        id: row.get(0)?,
+        contact_id: row.get(1)?,
+        title: row.get(2)?,
+        amount_cents: row.get(3)?,
+        currency: row.get(4)?,
+        status: row.get(5)?,
+        document_id: row.get(6)?,
+        run_date: row.get(7)?,
+        notes: row.get(8)?,
+        created_at: row.get(9)?,
+        updated_at: row.get(10)?,
+    })
+}
+
+fn validate_role(role: &str) -> Result<(), AppError> {
+    if !ROLES.contains(&role) {
+        return Err(AppError::validation(format!("Unknown contact role '{}'", role)));
+    }
+    Ok(())
+}
+
+fn validate_email(email: &Option<String>) -> Result<(), AppError> {
+    if let Some(e) = email {
+        if !e.is_empty() && !e.contains('@') {
+            return Err(AppError::validation(format!("Invalid email '{}'", e)));
+        }
+    }
+    Ok(())
+}
+
+fn validate_deal(status: &str, amount_cents: i64, run_date: &Option<String>) -> Result<(), AppError> {
+    if !DEAL_STATUSES.contains(&status) {
+        return Err(AppError::validation(format!("Unknown deal status '{}'", status)));
+    }
+    if amount_cents < 0 {
+        return Err(AppError::validation("Deal amount can't be negative"));
+    }
+    if let Some(d) = run_date {
+        chrono::NaiveDate::parse_from_str(d, "%Y-%m-%d")
+            .map_err(|_| AppError::validation(format!("Invalid run date '{}', expected YYYY-MM-DD", d)))?;
+    }
+    Ok(())
+}
+
+fn load_contact(conn: &rusqlite::Connection, id: &str) -> Result<Contact, AppError> {
+    conn.query_row(
+        &format!("SELECT {} FROM contacts WHERE id = ?1", CONTACT_COLUMNS),
+        rusqlite::params![id],
+        contact_from_row,
+    )
+    .map_err(|_| AppError::not_found(format!("Contact '{}' not found", id)))
+}
+
+fn load_deal(conn: &rusqlite::Connection, id: &str) -> Result<SponsorshipDeal, AppError> {
+    conn.query_row(
+        &format!("SELECT {} FROM sponsorship_deals WHERE id = ?1", DEAL_COLUMNS),
+        rusqlite::params![id],
+        deal_from_row,
+    )
+    .map_err(|_| AppError::not_found(format!("Deal '{}' not found", id)))
+}
+
+// ─── Contacts ───────────────────────────────────────────────────
+
+#[tauri::command]
+pub async fn create_contact(
+    app: AppHandle,
+    name: String,
+    company: Option<String>,
+    email: Option<String>,
+    role: Option<String>,
+    notes: Option<String>,
+) -> Result<Contact, AppError> {
+    let role = role.unwrap_or_else(|| "sponsor".to_string());
+    validate_role(&role)?;
+    validate_email(&email)?;
+    if name.trim().is_empty() {
+        return Err(AppError::validation("Contact name is required"));
+    }
+    let conn = db::get_db(&app)?;
+    let id = uuid::Uuid::new_v4().to_string();
+    let now = Utc::now().to_rfc3339();
+    let notes = notes.unwrap_or_default();
+
+    conn.execute(
+        "INSERT INTO contacts (id, name, company, email, role, notes, created_at, updated_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?7)",
+        rusqlite::params![id, name, company, email, role, notes, now],
+    )
+    .map_err(|e| format!("Failed to create contact: {}", e))?;
+
+    db::log_activity(&conn, "contact.created", "contact", Some(&id), Some(&name));
+
+    Ok(Contact {
+        id,
+        name,
+        company,
+        email,
+        role,
+        notes,
+        created_at: now.clone(),
+        updated_at: now,
+    })
+}
+
+/// Contacts by name, optionally filtered by role and a search `query`
+/// matched against name, company, email and notes.
+#[tauri::command]
+pub async fn list_contacts(
+    app: AppHandle,
+    role: Option<String>,
+    query: Option<String>,
+) -> Result<Vec<Contact>, AppError> {
+    let conn = db::get_db(&app)?;
+
+    let mut sql = format!("SELECT {} FROM contacts WHERE 1=1", CONTACT_COLUMNS);
+    let mut params: Vec<Box<dyn rusqlite::types::ToSql>> = Vec::new();
+
+    if let Some(ref r) = role {
+        sql.push_str(&format!(" AND role = ?{}", params.len() + 1));
+        params.push(Box::new(r.clone()));
+    }
+    if let Some(q) = query.as_deref().map(str::trim).filter(|q| !q.is_empty()) {
+        let n = params.len() + 1;
+        sql.push_str(&format!(
+            " AND (name LIKE ?{n} OR company LIKE ?{n} OR email LIKE ?{n} OR notes LIKE ?{n})"
+        ));
+        params.push(Box::new(format!("%{}%", q)));
+    }
+    sql.push_str(" ORDER BY name COLLATE NOCASE ASC");
+
+    let param_refs: Vec<&dyn rusqlite::types::ToSql> = params.iter().map(|p| p.as_ref()).collect();
+
+    let mut stmt = conn.prepare(&sql).map_err(|e| format!("Query failed: {}", e))?;
+    let rows = stmt
+        .query_map(param_refs.as_slice(), contact_from_row)
+        .map_err(|e| format!("Query map failed: {}", e))?;
+
+    Ok(rows.filter_map(|r| r.ok()).collect())
+}
+
+#[tauri::command]
+pub async fn get_contact(app: AppHandle, id: String) -> Result<ContactDetail, AppError> {
+    let conn = db::get_db(&app)?;
+    let contact = load_contact(&conn, &id)?;
+
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM sponsorship_deals WHERE contact_id = ?1 ORDER BY run_date IS NULL, run_date DESC, created_at DESC",
+        DEAL_COLUMNS
+    ))?;
+    let deals: Vec<SponsorshipDeal> = stmt
+        .query_map(rusqlite::params![id], deal_from_row)?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    let mut paid: Vec<(String, i64)> = Vec::new();
+    for deal in deals.iter().filter(|d| d.status == "paid") {
+        match paid.iter_mut().find(|(c, _)| *c == deal.currency) {
+            Some((_, total)) => *total += deal.amount_cents,
+            None => paid.push((deal.currency.clone(), deal.amount_cents)),
+        }
+    }
+
+    Ok(ContactDetail { contact, deals, paid_cents: paid })
+}
+
+#[tauri::command]
+pub async fn update_contact(
+    app: AppHandle,
+    id: String,
+    name: Option<String>,
+    company: Option<String>,
+    email: Option<String>,
+    role: Option<String>,
+    notes: Option<String>,
+) -> Result<Contact, AppError> {
+    validate_email(&email)?;
+    let conn = db::get_db(&app)?;
+    let mut contact = load_contact(&conn, &id)?;
+
+    if let Some(n) = name {
+        if n.trim().is_empty() {
+            return Err(AppError::validation("Contact name is required"));
+        }
+        contact.name = n;
+    }
+    if company.is_some() {
+        contact.company = company;
+    }
+    if email.is_some() {
+        contact.email = email;
+    }
+    if let Some(r) = role {
+        validate_role(&r)?;
+        contact.role = r;
+    }
+    if let Some(n) = notes {
+        contact.notes = n;
+    }
+    contact.updated_at = Utc::now().to_rfc3339();
+
+    conn.execute(
+        "UPDATE contacts SET name = ?1, company = ?2, email = ?3, role = ?4, notes = ?5, updated_at = ?6 WHERE id = ?7",
+        rusqlite::params![contact.name, contact.company, contact.email, contact.role, contact.notes, contact.updated_at, id],
+    )
+    .map_err(|e| format!("Failed to update contact: {}", e))?;
+
+    Ok(contact)
+}
+
+/// Delete a contact and their deals.
+#[tauri::command]
+pub async fn delete_contact(app: AppHandle, id: String) -> Result<(), AppError> {
+    permissions::require(&app, Permission::DeleteContent)?;
+    let conn = db::get_db(&app)?;
+    conn.execute("DELETE FROM sponsorship_deals WHERE contact_id = ?1", rusqlite::params![id])
+        .map_err(|e| format!("Failed to delete deals: {}", e))?;
+    conn.execute("DELETE FROM contacts WHERE id = ?1", rusqlite::params![id])
+        .map_err(|e| format!("Failed to delete contact: {}", e))?;
+    db::log_activity(&conn, "contact.deleted", "contact", Some(&id), None);
+    Ok(())
+}
+
+// ─── Sponsorship deals ──────────────────────────────────────────
+
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn create_sponsorship_deal(
+    app: AppHandle,
+    contact_id: String,
+    title: String,
+    amount_cents: i64,
+    currency: Option<String>,
+    status: Option<String>,
+    document_id: Option<String>,
+    run_date: Option<String>,
+    notes: Option<String>,
+) -> Result<SponsorshipDeal, AppError> {
+    let status = status.unwrap_or_else(|| "prospect".to_string());
+    validate_deal(&status, amount_cents, &run_date)?;
+    let conn = db::get_db(&app)?;
+    load_contact(&conn, &contact_id)?;
+
+    let id = uuid::Uuid::new_v4().to_string();
+    let now = Utc::now().to_rfc3339();
+    let currency = currency.unwrap_or_else(|| "USD".to_string()).to_uppercase();
+    let notes = notes.unwrap_or_default();
+
+    conn.execute(
+        "INSERT INTO sponsorship_deals (id, contact_id, title, amount_cents, currency, status, document_id, run_date, notes, created_at, updated_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?10)",
+        rusqlite::params![id, contact_id, title, amount_cents, currency, status, document_id, run_date, notes, now],
+    )
+    .map_err(|e| format!("Failed to create deal: {}", e))?;
+
+    db::log_activity(&conn, "deal.created", "contact", Some(&contact_id), Some(&title));
+
+    Ok(SponsorshipDeal {
+        id,
+        contact_id,
+        title,
+        amount_cents,
+        currency,
+        status,
+        document_id,
+        run_date,
+        notes,
+        created_at: now.clone(),
+        updated_at: now,
+    })
+}
+
+/// Deals across all contacts, soonest run date first; `status` filters.
+#[tauri::command]
+pub async fn list_sponsorship_deals(
+    app: AppHandle,
+    status: Option<String>,
+) -> Result<Vec<SponsorshipDeal>, AppError> {
+    let conn = db::get_db(&app)?;
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM sponsorship_deals WHERE (?1 IS NULL OR status = ?1)
+         ORDER BY run_date IS NULL, run_date ASC, created_at DESC",
+        DEAL_COLUMNS
+    ))?;
+    let rows = stmt.query_map(rusqlite::params![status], deal_from_row)?;
+    Ok(rows.filter_map(|r| r.ok()).collect())
+}
+
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn update_sponsorship_deal(
+    app: AppHandle,
+    id: String,
+    title: Option<String>,
+    amount_cents: Option<i64>,
+    currency: Option<String>,
+    status: Option<String>,
+    document_id: Option<String>,
+    run_date: Option<String>,
+    notes: Option<String>,
+) -> Result<SponsorshipDeal, AppError> {
+    let conn = db::get_db(&app)?;
+    let mut deal = load_deal(&conn, &id)?;
+
+    if let Some(t) = title {
+        deal.title = t;
+    }
+    if let Some(a) = amount_cents {
+        deal.amount_cents = a;
+    }
+    if let Some(c) = currency {
+        deal.currency = c.to_uppercase();
+    }
+    if let Some(s) = status {
+        deal.status = s;
+    }
+    if document_id.is_some() {
+        deal.document_id = document_id;
+    }
+    if run_date.is_some() {
+        deal.run_date = run_date;
+    }
+    if let Some(n) = notes {
+        deal.notes = n;
+    }
+    validate_deal(&deal.status, deal.amount_cents, &deal.run_date)?;
+    deal.updated_at = Utc::now().to_rfc3339();
+
+    conn.execute(
+        "UPDATE sponsorship_deals SET title = ?1, amount_cents = ?2, currency = ?3, status = ?4, document_id = ?5,
+                run_date = ?6, notes = ?7, updated_at = ?8 WHERE id = ?9",
+        rusqlite::params![
+            deal.title,
+            deal.amount_cents,
+            deal.currency,
+            deal.status,
+            deal.document_id,
+            deal.run_date,
+            deal.notes,
+            deal.updated_at,
+            id
+        ],
+    )
+    .map_err(|e| format!("Failed to update deal: {}", e))?;
+
+    Ok(deal)
+}
+
+#[tauri::command]
+pub async fn delete_sponsorship_deal(app: AppHandle, id: String) -> Result<(), AppError> {
+    permissions::require(&app, Permission::DeleteContent)?;
+    let conn = db::get_db(&app)?;
+    conn.execute("DELETE FROM sponsorship_deals WHERE id = ?1", rusqlite::params![id])
+        .map_err(|e| format!("Failed to delete deal: {}", e))?;
+    Ok(())
+}
@@ -2,6 +2,11 @@ use serde::{Deserialize, Serialize};
 use tauri::AppHandle;
 use tauri_plugin_store::StoreExt;
 
+use crate::commands::{audit, workspaces};
+use crate::db;
+use crate::error::AppError;
+use crate::permissions::{self, Permission};
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct StoredCredential {
     pub platform: String,
@@ -9,6 +14,13 @@ pub struct StoredCredential {
     pub api_key: String,
     pub account_name: String,
     pub email: String,
+    /// Credentials stored before workspaces belong to the default one
+    #[serde(default = "default_workspace")]
+    pub workspace_id: String,
+}
+
+fn default_workspace() -> String {
+    workspaces::DEFAULT_WORKSPACE.to_string()
 }
 
 #[tauri::command]
@@ -19,7 +31,8 @@ pub async fn store_credential(
     api_key: String,
     account_name: String,
     email: String,
-) -> Result<(), String> {
+) -> Result<(), AppError> {
+    permissions::require(&app, Permission::ManageCredentials)?;
     let store = app.store("credentials.json").map_err(|e| e.to_string())?;
     let key = format!("{}:{}", platform, account_id);
     let cred = StoredCredential {
@@ -28,12 +41,15 @@ pub async fn store_credential(
         api_key,
         account_name,
         email,
+        workspace_id: workspaces::current(&app),
     };
     store.set(
         &key,
         serde_json::to_value(&cred).map_err(|e| e.to_string())?,
     );
     store.save().map_err(|e| e.to_string())?;
+    let conn = db::get_db(&app)?;
+    audit::record(&app, &conn, "credential.stored", "credential", Some(&key), None)?;
     Ok(())
 }
 
@@ -42,9 +58,12 @@ pub async fn get_credential(
     app: AppHandle,
     platform: String,
     account_id: String,
-) -> Result<Option<StoredCredential>, String> {
+) -> Result<Option<StoredCredential>, AppError> {
+    permissions::require(&app, Permission::ManageCredentials)?;
     let store = app.store("credentials.json").map_err(|e| e.to_string())?;
     let key = format!("{}:{}", platform, account_id);
+    let conn = db::get_db(&app)?;
+    audit::record(&app, &conn, "credential.read", "credential", Some(&key), None)?;
     match store.get(&key) {
         Some(val) => {
             let cred: StoredCredential =
@@ -60,20 +79,33 @@ pub async fn delete_credential(
     app: AppHandle,
     platform: String,
     account_id: String,
-) -> Result<(), String> {
+) -> Result<(), AppError> {
+    permissions::require(&app, Permission::ManageCredentials)?;
     let store = app.store("credentials.json").map_err(|e| e.to_string())?;
     let key = format!("{}:{}", platform, account_id);
     store.delete(&key);
     store.save().map_err(|e| e.to_string())?;
+    let conn = db::get_db(&app)?;
+    audit::record(&app, &conn, "credential.deleted", "credential", Some(&key), None)?;
     Ok(())
 }
 
+/// The active workspace's connected accounts. Roles without
+/// `ManageCredentials` see them with the API key blanked.
 #[tauri::command]
-pub async fn list_credentials(app: AppHandle) -> Result<Vec<StoredCredential>, String> {
+pub async fn list_credentials(app: AppHandle) -> Result<Vec<StoredCredential>, AppError> {
     let store = app.store("credentials.json").map_err(|e| e.to_string())?;
+    let show_keys = permissions::current_role(&app).allows(Permission::ManageCredentials);
+    let workspace = workspaces::current(&app);
     let mut creds = Vec::new();
     for (_, value) in store.entries() {
-        if let Ok(cred) = serde_json::from_value::<StoredCredential>(value.clone()) {
+        if let Ok(mut cred) = serde_json::from_value::<StoredCredential>(value.clone()) {
+            if cred.workspace_id != workspace {
+                continue;
+            }
+            if !show_keys {
+                cred.api_key.clear();
+            }
             creds.push(cred);
         }
     }
@@ -0,0 +1,390 @@
+use chrono::Utc;
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+use crate::commands::archives::{self, Columns};
+use crate::commands::platform::Subscriber;
+use crate::commands::workspaces;
+use crate::db;
+use crate::error::AppError;
+
+// ─── Bounces and complaints ─────────────────────────────────────
+//
+// Delivery failures arrive two ways: as subscriber states in a platform
+// sync or archive import ("bounced", "cleaned", "complained"), or as an
+// exported bounce/complaint report. Both become subscriber_events rows.
+// A hard bounce puts the address on the suppression list; every event
+// lowers the subscriber's engagement score.
+
+const EVENT_TYPES: &[&str] = &["hard_bounce", "soft_bounce", "complaint"];
+
+/// Engagement score lost per event; a hard bounce or complaint means the
+/// address is effectively gone
+fn score_penalty(event_type: &str) -> f64 {
+    match event_type {
+        "soft_bounce" => 0.1,
+        _ => 1.0,
+    }
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct BounceEventInput {
+    pub email: String,
+    /// "hard_bounce" | "soft_bounce" | "complaint"
+    pub event_type: String,
+    pub reason: Option<String>,
+    /// RFC 3339; defaults to now
+    pub occurred_at: Option<String>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct SubscriberEvent {
+    pub id: String,
+    pub subscriber_id: Option<String>,
+    pub email: String,
+    pub event_type: String,
+    pub platform: String,
+    pub reason: Option<String>,
+    pub source: String,
+    pub occurred_at: String,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct Suppression {
+    pub email: String,
+    pub reason: String,
+    pub platform: Option<String>,
+    pub created_at: String,
+}
+
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct IngestResult {
+    pub recorded: i64,
+    /// Already recorded by an earlier ingestion
+    pub duplicates: i64,
+    pub suppressed: i64,
+    /// Events for addresses not in the unified audience; still recorded
+    pub unknown_subscribers: i64,
+    /// CSV rows without an email or a recognisable event type
+    pub rows_skipped: i64,
+}
+
+/// Event type for a bounce/complaint wording from any platform's report,
+/// or None when it isn't a delivery failure.
+fn classify(value: &str) -> Option<&'static str> {
+    let value = value.trim().to_lowercase();
+    if value.is_empty() {
+        return None;
+    }
+    if ["complain", "spam", "abuse"].iter().any(|w| value.contains(w)) {
+        Some("complaint")
+    } else if ["soft", "transient", "temporary"].iter().any(|w| value.contains(w)) {
+        Some("soft_bounce")
+    } else if ["hard", "permanent", "bounce", "cleaned", "invalid", "undeliverable"]
+        .iter()
+        .any(|w| value.contains(w))
+    {
+        Some("hard_bounce")
+    } else {
+        None
+    }
+}
+
+/// Record one event against the subscriber of that address in
+/// `workspace_id`, then suppress and re-score. Returns (inserted,
+/// suppressed, known subscriber).
+#[allow(clippy::too_many_arguments)]
+fn record_event(
+    conn: &Connection,
+    workspace_id: &str,
+    platform: &str,
+    source: &str,
+    email: &str,
+    event_type: &str,
+    reason: Option<&str>,
+    occurred_at: &str,
+) -> (bool, bool, bool) {
+    let email = email.trim().to_lowercase();
+    let now = Utc::now().to_rfc3339();
+    let subscriber_id: Option<String> = conn
+        .query_row(
+            "SELECT id FROM subscribers WHERE workspace_id = ?1 AND email = ?2",
+            rusqlite::params![workspace_id, email],
+            |row| row.get(0),
+        )
+        .ok();
+
+    let inserted = conn
+        .execute(
+            "INSERT OR IGNORE INTO subscriber_events (id, subscriber_id, email, event_type, platform, reason, source, occurred_at, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            rusqlite::params![
+                uuid::Uuid::new_v4().to_string(),
+                subscriber_id,
+                email,
+                event_type,
+                platform,
+                reason,
+                source,
+                occurred_at,
+                now
+            ],
+        )
+        .unwrap_or(0)
+        > 0;
+    if !inserted {
+        return (false, false, subscriber_id.is_some());
+    }
+
+    let suppressed = event_type == "hard_bounce"
+        && conn
+            .execute(
+                "INSERT OR IGNORE INTO suppression_list (email, reason, platform, created_at) VALUES (?1, ?2, ?3, ?4)",
+                rusqlite::params![email, reason.unwrap_or("Hard bounce"), platform, now],
+            )
+            .unwrap_or(0)
+            > 0;
+
+    // The mailbox is the same in every workspace it subscribed in
+    if subscriber_id.is_some() {
+        conn.execute(
+            "UPDATE subscribers SET engagement_score = MAX(0.0, engagement_score - ?1), updated_at = ?2 WHERE email = ?3",
+            rusqlite::params![score_penalty(event_type), now, email],
+        )
+        .ok();
+    }
+    (true, suppressed, subscriber_id.is_some())
+}
+
+fn ingest(
+    conn: &Connection,
+    workspace_id: &str,
+    platform: &str,
+    source: &str,
+    events: &[BounceEventInput],
+) -> Result<IngestResult, AppError> {
+    let mut result = IngestResult::default();
+    let now = Utc::now().to_rfc3339();
+    for event in events {
+        if !EVENT_TYPES.contains(&event.event_type.as_str()) {
+            return Err(AppError::validation(format!("Unknown event type '{}'", event.event_type)));
+        }
+        if !event.email.contains('@') {
+            result.rows_skipped += 1;
+            continue;
+        }
+        let occurred_at = event.occurred_at.as_deref().and_then(archives::normalize_date).unwrap_or_else(|| now.clone());
+        let (inserted, suppressed, known) = record_event(
+            conn,
+            workspace_id,
+            platform,
+            source,
+            &event.email,
+            &event.event_type,
+            event.reason.as_deref(),
+            &occurred_at,
+        );
+        if !inserted {
+            result.duplicates += 1;
+            continue;
+        }
+        result.recorded += 1;
+        result.suppressed += suppressed as i64;
+        result.unknown_subscribers += (!known) as i64;
+    }
+    Ok(result)
+}
+
+/// Events implied by subscriber states from a sync or archive import.
+/// A state carries no date, so each address gets at most one event of a
+/// type per platform however often it is re-synced.
+pub(crate) fn record_platform_statuses(
+    conn: &Connection,
+    workspace_id: &str,
+    platform: &str,
+    subscribers: &[Subscriber],
+) -> IngestResult {
+    let mut result = IngestResult::default();
+    let now = Utc::now().to_rfc3339();
+    for sub in subscribers {
+        let Some(event_type) = classify(&sub.status) else {
+            continue;
+        };
+        let email = sub.email.trim().to_lowercase();
+        let seen: bool = conn
+            .query_row(
+                "SELECT EXISTS(SELECT 1 FROM subscriber_events WHERE email = ?1 AND event_type = ?2 AND platform = ?3)",
+                rusqlite::params![email, event_type, platform],
+                |row| row.get(0),
+            )
+            .unwrap_or(false);
+        if seen {
+            result.duplicates += 1;
+            continue;
+        }
+        let (inserted, suppressed, known) =
+            record_event(conn, workspace_id, platform, "sync", &email, event_type, Some(&sub.status), &now);
+        result.recorded += inserted as i64;
+        result.suppressed += suppressed as i64;
+        result.unknown_subscribers += (inserted && !known) as i64;
+    }
+    result
+}
+
+/// Record bounce/complaint events reported by a platform (webhook payloads,
+/// API responses relayed by the UI).
+#[tauri::command]
+pub async fn ingest_bounce_events(
+    app: AppHandle,
+    platform: String,
+    events: Vec<BounceEventInput>,
+) -> Result<IngestResult, AppError> {
+    let workspace_id = workspaces::current(&app);
+    let conn = db::get_db(&app)?;
+    let result = ingest(&conn, &workspace_id, &platform, "api", &events)?;
+    log_ingest(&conn, &platform, &result);
+    Ok(result)
+}
+
+/// Import an exported bounce or complaint report. The event type comes from
+/// a type/status column, or from the file name ("hard-bounces.csv",
+/// "complaints.csv") when there is none.
+#[tauri::command]
+pub async fn import_bounce_csv(app: AppHandle, platform: String, file_path: String) -> Result<IngestResult, AppError> {
+    let data = std::fs::read(&file_path).map_err(|e| AppError::not_found(format!("Can't read {}: {}", file_path, e)))?;
+    let mut reader = csv::ReaderBuilder::new()
+        .flexible(true)
+        .trim(csv::Trim::All)
+        .from_reader(data.as_slice());
+    let columns = Columns::from_headers(
+        reader
+            .headers()
+            .map_err(|e| AppError::validation(format!("Unreadable CSV header: {}", e)))?,
+    );
+    let email_col = columns
+        .find(&["email address", "email", "recipient"])
+        .ok_or_else(|| AppError::validation("No email column in the report"))?;
+    let type_col = columns.find(&["bounce type", "event type", "type", "event", "status", "reason"]);
+    let reason_col = columns.find(&["diagnostic", "description", "bounce reason", "reason", "message"]);
+    let date_col = columns.find(&["timestamp", "bounced at", "occurred at", "created at", "date"]);
+    let file_type = classify(
+        &std::path::Path::new(&file_path)
+            .file_stem()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default(),
+    );
+
+    let mut events = Vec::new();
+    let mut skipped = 0;
+    for record in reader.records() {
+        let Ok(record) = record else {
+            skipped += 1;
+            continue;
+        };
+        let event_type = classify(archives::cell(&record, type_col)).or(file_type);
+        let email = archives::cell(&record, Some(email_col));
+        let Some(event_type) = event_type.filter(|_| email.contains('@')) else {
+            skipped += 1;
+            continue;
+        };
+        events.push(BounceEventInput {
+            email: email.to_string(),
+            event_type: event_type.to_string(),
+            reason: Some(archives::cell(&record, reason_col).to_string()).filter(|r| !r.is_empty()),
+            occurred_at: Some(archives::cell(&record, date_col).to_string()).filter(|d| !d.is_empty()),
+        });
+    }
+
+    let workspace_id = workspaces::current(&app);
+    let conn = db::get_db(&app)?;
+    let mut result = ingest(&conn, &workspace_id, &platform, "csv", &events)?;
+    result.rows_skipped += skipped;
+    log_ingest(&conn, &platform, &result);
+    Ok(result)
+}
+
+fn log_ingest(conn: &Connection, platform: &str, result: &IngestResult) {
+    db::log_activity(
+        conn,
+        "audience.bounces_ingested",
+        "subscribers",
+        None,
+        Some(&format!(
+            "{}: {} events recorded, {} addresses suppressed",
+            platform, result.recorded, result.suppressed
+        )),
+    );
+}
+
+#[tauri::command]
+pub async fn list_subscriber_events(
+    app: AppHandle,
+    subscriber_id: Option<String>,
+    event_type: Option<String>,
+    limit: Option<i64>,
+) -> Result<Vec<SubscriberEvent>, AppError> {
+    let conn = db::get_db(&app)?;
+    let mut stmt = conn.prepare(
+        "SELECT id, subscriber_id, email, event_type, platform, reason, source, occurred_at FROM subscriber_events
+         WHERE (?1 IS NULL OR subscriber_id = ?1) AND (?2 IS NULL OR event_type = ?2)
+         ORDER BY occurred_at DESC LIMIT ?3",
+    )?;
+    let rows = stmt.query_map(
+        rusqlite::params![subscriber_id, event_type, limit.unwrap_or(200).clamp(1, 1000)],
+        |row| {
+            Ok(SubscriberEvent {
+                id: row.get(0)?,
+                subscriber_id: row.get(1)?,
+                email: row.get(2)?,
+                event_type: row.get(3)?,
+                platform: row.get(4)?,
+                reason: row.get(5)?,
+                source: row.get(6)?,
+                occurred_at: row.get(7)?,
+            })
+        },
+    )?;
+    Ok(rows.filter_map(|r| r.ok()).collect())
+}
+
+#[tauri::command]
+pub async fn list_suppressions(app: AppHandle) -> Result<Vec<Suppression>, AppError> {
+    let conn = db::get_db(&app)?;
+    let mut stmt =
+        conn.prepare("SELECT email, reason, platform, created_at FROM suppression_list ORDER BY created_at DESC")?;
+    let rows = stmt.query_map([], |row| {
+        Ok(Suppression { email: row.get(0)?, reason: row.get(1)?, platform: row.get(2)?, created_at: row.get(3)? })
+    })?;
+    Ok(rows.filter_map(|r| r.ok()).collect())
+}
+
+/// Suppress an address by hand, e.g. after a removal request.
+#[tauri::command]
+pub async fn add_suppression(app: AppHandle, email: String, reason: Option<String>) -> Result<(), AppError> {
+    let email = email.trim().to_lowercase();
+    if !email.contains('@') {
+        return Err(AppError::validation("A valid email address is required"));
+    }
+    let conn = db::get_db(&app)?;
+    conn.execute(
+        "INSERT OR REPLACE INTO suppression_list (email, reason, platform, created_at) VALUES (?1, ?2, NULL, ?3)",
+        rusqlite::params![email, reason.unwrap_or_else(|| "Manual".to_string()), Utc::now().to_rfc3339()],
+    )
+    .map_err(|e| format!("Failed to add suppression: {}", e))?;
+    Ok(())
+}
+
+/// Lift a suppression, e.g. once a bounced address is confirmed to work.
+/// Its recorded events stay.
+#[tauri::command]
+pub async fn remove_suppression(app: AppHandle, email: String) -> Result<(), AppError> {
+    let conn = db::get_db(&app)?;
+    conn.execute(
+        "DELETE FROM suppression_list WHERE email = ?1",
+        rusqlite::params![email.trim().to_lowercase()],
+    )
+    .map_err(|e| format!("Failed to remove suppression: {}", e))?;
+    db::log_activity(&conn, "audience.unsuppressed", "subscribers", None, Some(&email));
+    Ok(())
+}
@@ -4,8 +4,17 @@ use docx_rs::*;
 use printpdf::*;
 use serde::{Deserialize, Serialize};
 use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+use tauri::Emitter;
 
+use crate::commands::scheduler::PresetTarget;
+use crate::commands::export_jobs::{self, JobContext};
+use crate::commands::{analytics, attachments, audit, export_history, focus, images, links, snapshots, storage, windows, workspaces};
 use crate::db;
+use crate::text;
+use crate::error::{AppError, ErrorKind};
+use crate::permissions::{self, Permission};
+use crate::sanitize::sanitize_html;
 
 // ---------------------------------------------------------------------------
 // Types
@@ -21,6 +30,21 @@ pub struct DocumentMeta {
     pub project_id: Option<String>,
     pub status: String,
     pub character_count: i64,
+    /// Set while the document is archived
+    #[serde(default)]
+    pub archived_at: Option<String>,
+    /// Word count goal set with `set_document_goal`
+    #[serde(default)]
+    pub target_words: Option<i64>,
+    /// Percent of `target_words` written, past 100 once exceeded
+    #[serde(default)]
+    pub goal_progress: Option<f64>,
+    /// Set while the document is pinned
+    #[serde(default)]
+    pub pinned_at: Option<String>,
+    /// Position within its project, from `reorder_documents`
+    #[serde(default)]
+    pub sort_index: Option<i64>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -32,6 +56,9 @@ struct StationDocument {
     html_content: String,
     created_at: String,
     updated_at: String,
+    /// Edit version to send back as `expected_version` on the next save
+    #[serde(default)]
+    document_version: i64,
 }
 
 // ---------------------------------------------------------------------------
@@ -42,15 +69,29 @@ struct StationDocument {
 enum HtmlNode {
     Heading { level: u8, children: Vec<InlineNode> },
     Paragraph { children: Vec<InlineNode> },
-    UnorderedList { items: Vec<Vec<InlineNode>> },
-    OrderedList { items: Vec<Vec<InlineNode>> },
+    UnorderedList { items: Vec<ListItem> },
+    OrderedList { items: Vec<ListItem> },
+    /// Tiptap's `<ul data-type="taskList">`; every item has `checked` set
+    TaskList { items: Vec<ListItem> },
     Blockquote { children: Vec<InlineNode> },
-    CodeBlock { text: String },
+    CodeBlock { text: String, language: Option<String> },
     HorizontalRule,
-    Table { rows: Vec<Vec<Vec<InlineNode>>> },
+    /// The first `header_rows` rows are headings: the `<thead>`, or rows of
+    /// only `<th>` cells
+    Table { rows: Vec<Vec<Vec<InlineNode>>>, header_rows: usize },
     Image { src: String, alt: String },
 }
 
+/// One `<li>`: its own text, then any lists nested in it.
+#[derive(Debug, Clone)]
+struct ListItem {
+    children: Vec<InlineNode>,
+    /// `UnorderedList`, `OrderedList` and `TaskList` nodes
+    sublists: Vec<HtmlNode>,
+    /// Whether a task list item is ticked; `None` outside task lists
+    checked: Option<bool>,
+}
+
 #[derive(Debug, Clone)]
 struct InlineNode {
     text: String,
@@ -58,11 +99,168 @@ struct InlineNode {
     italic: bool,
     underline: bool,
     code: bool,
+    /// Target of the enclosing link, if any
+    href: Option<String>,
+    /// Set on a footnote reference, whose `text` is then its number
+    footnote: Option<FootnoteRef>,
+}
+
+#[derive(Debug, Clone)]
+struct FootnoteRef {
+    /// Counting from 1 in reading order
+    number: usize,
+    /// Id of the note in the document's footnotes list, when the note
+    /// isn't kept inline with the reference
+    id: Option<String>,
+    note: Vec<InlineNode>,
+}
+
+impl InlineNode {
+    /// `text`, with a footnote reference as "[n]" for exports that have no
+    /// superscript to set it in.
+    fn bracketed_text(&self) -> std::borrow::Cow<'_, str> {
+        match &self.footnote {
+            Some(footnote) => format!("[{}]", footnote.number).into(),
+            None => self.text.as_str().into(),
+        }
+    }
+}
+
+/// The inline runs of a block that has text, in reading order.
+fn block_inlines(node: &HtmlNode) -> Vec<&Vec<InlineNode>> {
+    match node {
+        HtmlNode::Heading { children, .. } | HtmlNode::Paragraph { children } | HtmlNode::Blockquote { children } => {
+            vec![children]
+        }
+        HtmlNode::UnorderedList { items } | HtmlNode::OrderedList { items } | HtmlNode::TaskList { items } => items
+            .iter()
+            .flat_map(|item| std::iter::once(&item.children).chain(item.sublists.iter().flat_map(block_inlines)))
+            .collect(),
+        HtmlNode::Table { rows, .. } => rows.iter().flatten().collect(),
+        HtmlNode::CodeBlock { .. } | HtmlNode::HorizontalRule | HtmlNode::Image { .. } => Vec::new(),
+    }
+}
+
+fn block_inlines_mut(node: &mut HtmlNode) -> Vec<&mut Vec<InlineNode>> {
+    match node {
+        HtmlNode::Heading { children, .. } | HtmlNode::Paragraph { children } | HtmlNode::Blockquote { children } => {
+            vec![children]
+        }
+        HtmlNode::UnorderedList { items } | HtmlNode::OrderedList { items } | HtmlNode::TaskList { items } => items
+            .iter_mut()
+            .flat_map(|ListItem { children, sublists, .. }| {
+                std::iter::once(children).chain(sublists.iter_mut().flat_map(block_inlines_mut))
+            })
+            .collect(),
+        HtmlNode::Table { rows, .. } => rows.iter_mut().flatten().collect(),
+        HtmlNode::CodeBlock { .. } | HtmlNode::HorizontalRule | HtmlNode::Image { .. } => Vec::new(),
+    }
+}
+
+/// A list item placed for exporters that lay lists out line by line.
+struct ListLine<'a> {
+    /// 0 for the outermost list
+    depth: usize,
+    /// Position among its siblings, from 1, in an ordered list
+    number: Option<usize>,
+    /// Set for task list items
+    checked: Option<bool>,
+    children: &'a [InlineNode],
+}
+
+/// The items of a list and of the lists nested in it, in reading order.
+fn list_lines(node: &HtmlNode) -> Vec<ListLine<'_>> {
+    fn walk<'a>(node: &'a HtmlNode, depth: usize, lines: &mut Vec<ListLine<'a>>) {
+        let (items, ordered) = match node {
+            HtmlNode::UnorderedList { items } | HtmlNode::TaskList { items } => (items, false),
+            HtmlNode::OrderedList { items } => (items, true),
+            _ => return,
+        };
+        for (i, item) in items.iter().enumerate() {
+            lines.push(ListLine {
+                depth,
+                number: ordered.then_some(i + 1),
+                checked: item.checked,
+                children: &item.children,
+            });
+            for sublist in &item.sublists {
+                walk(sublist, depth + 1, lines);
+            }
+        }
+    }
+    let mut lines = Vec::new();
+    walk(node, 0, &mut lines);
+    lines
+}
+
+/// "1." / "a." / "i." for numbered items by depth, as Word and most
+/// browsers' outlines go. `None` for a bullet.
+fn ordered_marker(number: Option<usize>, depth: usize) -> Option<String> {
+    let n = number?;
+    Some(match depth % 3 {
+        0 => format!("{}.", n),
+        1 => {
+            let mut letters = String::new();
+            let mut rest = n;
+            while rest > 0 {
+                rest -= 1;
+                letters.insert(0, char::from(b'a' + (rest % 26) as u8));
+                rest /= 26;
+            }
+            format!("{}.", letters)
+        }
+        _ => {
+            let mut roman = String::new();
+            let mut rest = n;
+            for (value, numeral) in [
+                (1000, "m"), (900, "cm"), (500, "d"), (400, "cd"), (100, "c"), (90, "xc"),
+                (50, "l"), (40, "xl"), (10, "x"), (9, "ix"), (5, "v"), (4, "iv"), (1, "i"),
+            ] {
+                while rest >= value {
+                    roman.push_str(numeral);
+                    rest -= value;
+                }
+            }
+            format!("{}.", roman)
+        }
+    })
+}
+
+/// Every footnote reference in `nodes`, in order of their numbers.
+fn footnotes(nodes: &[HtmlNode]) -> Vec<&FootnoteRef> {
+    nodes
+        .iter()
+        .flat_map(block_inlines)
+        .flatten()
+        .filter_map(|inline| inline.footnote.as_ref())
+        .collect()
+}
+
+/// Parse editor HTML into blocks. Footnotes are numbered in reading order
+/// and each reference carries its note, whether that was written inline
+/// or in the footnotes list at the end of the document.
+fn parse_html(html: &str) -> Vec<HtmlNode> {
+    let (html, notes) = take_footnote_list(html);
+    let mut nodes = parse_blocks(&html);
+    let mut number = 0;
+    for inlines in nodes.iter_mut().flat_map(block_inlines_mut) {
+        for inline in inlines.iter_mut() {
+            let Some(footnote) = &mut inline.footnote else { continue };
+            number += 1;
+            footnote.number = number;
+            if let Some(note) = footnote.id.as_ref().and_then(|id| notes.get(id)) {
+                footnote.note = parse_inline(note);
+            }
+            inline.text = number.to_string();
+        }
+    }
+    nodes
 }
 
 /// Very small, purpose-built HTML parser.  It handles the subset produced by
 /// Tiptap / ProseMirror (well-formed, no nesting surprises).
-fn parse_html(html: &str) -> Vec<HtmlNode> {
+fn parse_blocks(html: &str) -> Vec<HtmlNode> {
+
     let mut nodes: Vec<HtmlNode> = Vec::new();
     let html = html.trim();
 
@@ -107,19 +305,9 @@ fn parse_html(html: &str) -> Vec<HtmlNode> {
                             pos = end;
                         }
                     }
-                    "ul" => {
-                        if let Some((inner, end)) = read_until_closing(&chars, pos, "ul") {
-                            nodes.push(HtmlNode::UnorderedList {
-                                items: parse_list_items(&inner),
-                            });
-                            pos = end;
-                        }
-                    }
-                    "ol" => {
-                        if let Some((inner, end)) = read_until_closing(&chars, pos, "ol") {
-                            nodes.push(HtmlNode::OrderedList {
-                                items: parse_list_items(&inner),
-                            });
+                    "ul" | "ol" => {
+                        if let Some((inner, end)) = read_until_closing(&chars, pos, &tag_name) {
+                            nodes.push(list_node(&tag_info, &inner));
                             pos = end;
                         }
                     }
@@ -137,8 +325,16 @@ fn parse_html(html: &str) -> Vec<HtmlNode> {
                         if let Some((inner, end)) = read_until_closing(&chars, pos, "pre") {
                             // Strip inner <code> tag if present
                             let code_text = strip_tags_simple(&inner, "code");
+                            // Tiptap marks the language as class="language-xyz"
+                            let language = inner
+                                .split("language-")
+                                .nth(1)
+                                .and_then(|rest| rest.split(['"', '\'', ' ']).next())
+                                .filter(|lang| !lang.is_empty())
+                                .map(str::to_string);
                             nodes.push(HtmlNode::CodeBlock {
                                 text: decode_html_entities(&code_text),
+                                language,
                             });
                             pos = end;
                         }
@@ -171,7 +367,7 @@ fn parse_html(html: &str) -> Vec<HtmlNode> {
                     "div" | "section" | "article" | "main" | "header" | "footer" => {
                         // Wrapper tags – recurse into children
                         if let Some((inner, end)) = read_until_closing(&chars, pos, &tag_name) {
-                            let inner_nodes = parse_html(&inner);
+                            let inner_nodes = parse_blocks(&inner);
                             nodes.extend(inner_nodes);
                             pos = end;
                         }
@@ -206,6 +402,8 @@ fn parse_html(html: &str) -> Vec<HtmlNode> {
                         italic: false,
                         underline: false,
                         code: false,
+                        href: None,
+                        footnote: None,
                     }],
                 });
             }
@@ -363,7 +561,40 @@ fn read_until_closing(chars: &[char], start: usize, tag: &str) -> Option<(String
     Some((inner, chars.len()))
 }
 
-fn parse_list_items(html: &str) -> Vec<Vec<InlineNode>> {
+/// `html` with its `<ul>` and `<ol>` blocks cut out, and those blocks
+/// parsed as lists.
+fn split_sublists(html: &str) -> (String, Vec<HtmlNode>) {
+    let chars: Vec<char> = html.chars().collect();
+    let mut rest = String::new();
+    let mut sublists = Vec::new();
+    let mut pos = 0;
+    while pos < chars.len() {
+        if chars[pos] == '<' {
+            if let Some(tag) = read_opening_tag(&chars, pos).filter(|t| t.name == "ul" || t.name == "ol") {
+                if let Some((inner, end)) = read_until_closing(&chars, tag.end, &tag.name) {
+                    sublists.push(list_node(&tag, &inner));
+                    pos = end;
+                    continue;
+                }
+            }
+        }
+        rest.push(chars[pos]);
+        pos += 1;
+    }
+    (rest, sublists)
+}
+
+/// The list an `<ul>` or `<ol>` opened by `tag` holds.
+fn list_node(tag: &TagInfo, inner: &str) -> HtmlNode {
+    let items = parse_list_items(inner);
+    match (tag.name.as_str(), tag_attr(tag, "data-type")) {
+        (_, Some("taskList")) => HtmlNode::TaskList { items },
+        ("ol", _) => HtmlNode::OrderedList { items },
+        _ => HtmlNode::UnorderedList { items },
+    }
+}
+
+fn parse_list_items(html: &str) -> Vec<ListItem> {
     let mut items = Vec::new();
     let chars: Vec<char> = html.chars().collect();
     let mut pos = 0;
@@ -374,9 +605,19 @@ fn parse_list_items(html: &str) -> Vec<Vec<InlineNode>> {
                 if tag_info.name == "li" {
                     pos = tag_info.end;
                     if let Some((inner, end)) = read_until_closing(&chars, pos, "li") {
+                        let (mut text, sublists) = split_sublists(&inner);
+                        // Task items hold their checkbox in a <label> and
+                        // their text in a <div>
+                        let checked = (tag_attr(&tag_info, "data-type") == Some("taskItem"))
+                            .then(|| tag_attr(&tag_info, "data-checked") == Some("true"));
+                        if let (Some(start), Some(end)) = (text.find("<label"), text.find("</label>")) {
+                            if start < end {
+                                text.replace_range(start..end + "</label>".len(), "");
+                            }
+                        }
                         // Strip inner <p> tags
-                        let stripped = strip_tags_simple(&inner, "p");
-                        items.push(parse_inline(&stripped));
+                        let stripped = strip_tags_simple(&strip_tags_simple(&text, "div"), "p");
+                        items.push(ListItem { children: parse_inline(&stripped), sublists, checked });
                         pos = end;
                         continue;
                     }
@@ -388,6 +629,16 @@ fn parse_list_items(html: &str) -> Vec<Vec<InlineNode>> {
     items
 }
 
+/// Link text nodes carry the `<a>`'s href; an inner link wins.
+fn with_href(mut nodes: Vec<InlineNode>, tag: &TagInfo) -> Vec<InlineNode> {
+    if let Some((_, href)) = tag.attrs.iter().find(|(k, _)| k == "href") {
+        for node in &mut nodes {
+            node.href.get_or_insert_with(|| decode_html_entities(href));
+        }
+    }
+    nodes
+}
+
 fn parse_inline(html: &str) -> Vec<InlineNode> {
     let mut nodes = Vec::new();
     let chars: Vec<char> = html.chars().collect();
@@ -402,6 +653,19 @@ fn parse_inline(html: &str) -> Vec<InlineNode> {
     while pos < chars.len() {
         if chars[pos] == '<' {
             if let Some(tag_info) = read_opening_tag(&chars, pos) {
+                if let Some((footnote, end)) = read_footnote(&chars, &tag_info) {
+                    nodes.push(InlineNode {
+                        text: String::new(),
+                        bold,
+                        italic,
+                        underline,
+                        code,
+                        href: None,
+                        footnote: Some(footnote),
+                    });
+                    pos = end;
+                    continue;
+                }
                 match tag_info.name.as_str() {
                     "strong" | "b" => {
                         pos = tag_info.end;
@@ -448,7 +712,7 @@ fn parse_inline(html: &str) -> Vec<InlineNode> {
                             // Treat link text as regular inline with underline
                             let inner_nodes =
                                 parse_inline_with_flags(&inner, bold, italic, true, code);
-                            nodes.extend(inner_nodes);
+                            nodes.extend(with_href(inner_nodes, &tag_info));
                             pos = end;
                             continue;
                         }
@@ -460,6 +724,8 @@ fn parse_inline(html: &str) -> Vec<InlineNode> {
                             italic,
                             underline,
                             code,
+                            href: None,
+                            footnote: None,
                         });
                         pos = tag_info.end;
                         continue;
@@ -512,6 +778,8 @@ fn parse_inline(html: &str) -> Vec<InlineNode> {
                     italic,
                     underline,
                     code,
+                    href: None,
+                    footnote: None,
                 });
             }
         }
@@ -533,6 +801,19 @@ fn parse_inline_with_flags(
     while pos < chars.len() {
         if chars[pos] == '<' {
             if let Some(tag_info) = read_opening_tag(&chars, pos) {
+                if let Some((footnote, end)) = read_footnote(&chars, &tag_info) {
+                    nodes.push(InlineNode {
+                        text: String::new(),
+                        bold,
+                        italic,
+                        underline,
+                        code,
+                        href: None,
+                        footnote: Some(footnote),
+                    });
+                    pos = end;
+                    continue;
+                }
                 match tag_info.name.as_str() {
                     "strong" | "b" => {
                         pos = tag_info.end;
@@ -574,7 +855,7 @@ fn parse_inline_with_flags(
                         pos = tag_info.end;
                         if let Some((inner, end)) = read_until_closing(&chars, pos, "a") {
                             let inner_nodes = parse_inline_with_flags(&inner, bold, italic, true, code);
-                            nodes.extend(inner_nodes);
+                            nodes.extend(with_href(inner_nodes, &tag_info));
                             pos = end;
                             continue;
                         }
@@ -586,6 +867,8 @@ fn parse_inline_with_flags(
                             italic,
                             underline,
                             code,
+                            href: None,
+                            footnote: None,
                         });
                         pos = tag_info.end;
                         continue;
@@ -631,6 +914,8 @@ fn parse_inline_with_flags(
                     italic,
                     underline,
                     code,
+                    href: None,
+                    footnote: None,
                 });
             }
         }
@@ -638,6 +923,120 @@ fn parse_inline_with_flags(
     nodes
 }
 
+fn tag_attr<'a>(tag: &'a TagInfo, name: &str) -> Option<&'a str> {
+    tag.attrs.iter().find(|(k, _)| k == name).map(|(_, v)| v.as_str())
+}
+
+/// A footnote starting at `tag`, in the markup Tiptap footnote extensions
+/// produce: a reference into the footnotes list (`data-type=
+/// "footnoteReference"`, a "footnote-ref" class, or a `<sup>` around a link
+/// to `#fn...`), or a note kept inline (`data-type="footnote"` with its
+/// text in `data-content` or as content). Returns it with the position
+/// after the element.
+fn read_footnote(chars: &[char], tag: &TagInfo) -> Option<(FootnoteRef, usize)> {
+    if !matches!(tag.name.as_str(), "sup" | "a" | "span" | "footnote") {
+        return None;
+    }
+    let kind = tag_attr(tag, "data-type").unwrap_or("").to_ascii_lowercase().replace(['-', '_'], "");
+    let classes: Vec<&str> = tag_attr(tag, "class").unwrap_or("").split_whitespace().collect();
+    let inline_note = tag.name == "footnote" || kind == "footnote" || kind == "inlinefootnote";
+    let marked = matches!(kind.as_str(), "footnotereference" | "footnoteref")
+        || classes.iter().any(|c| matches!(*c, "footnote-ref" | "footnote-reference"))
+        || tag_attr(tag, "role") == Some("doc-noteref");
+    // Plain links and spans are common; only read on when this could be one
+    if !inline_note && !marked && tag.name != "sup" {
+        return None;
+    }
+    let (inner, end) = read_until_closing(chars, tag.end, &tag.name)?;
+
+    if inline_note {
+        let note = tag_attr(tag, "data-content").map(decode_html_entities).unwrap_or(inner);
+        return Some((FootnoteRef { number: 0, id: None, note: parse_inline(&note) }, end));
+    }
+    let linked = tag_attr(tag, "href")
+        .map(str::to_string)
+        .or_else(|| inner.split("href=\"").nth(1).and_then(|rest| rest.split('"').next()).map(str::to_string))
+        .and_then(|href| href.strip_prefix('#').map(str::to_string));
+    if !marked && !linked.as_deref().is_some_and(|target| target.starts_with("fn")) {
+        return None;
+    }
+    let id = tag_attr(tag, "data-id").map(str::to_string).or(linked);
+    Some((FootnoteRef { number: 0, id, note: Vec::new() }, end))
+}
+
+/// Cut the footnotes list out of `html`: the element with
+/// `data-type="footnotes"`, class "footnotes" or role "doc-endnotes" that
+/// Tiptap and Markdown converters put at the end. Returns the rest of the
+/// HTML and each note's HTML by its `id` and its `data-id`.
+fn take_footnote_list(html: &str) -> (String, std::collections::HashMap<String, String>) {
+    let chars: Vec<char> = html.chars().collect();
+    let mut notes = std::collections::HashMap::new();
+    let mut rest = String::new();
+    let (mut pos, mut copied) = (0, 0);
+    while pos < chars.len() {
+        let Some(tag) = (chars[pos] == '<').then(|| read_opening_tag(&chars, pos)).flatten() else {
+            pos += 1;
+            continue;
+        };
+        let is_list = tag_attr(&tag, "data-type") == Some("footnotes")
+            || tag_attr(&tag, "class").is_some_and(|c| c.split_whitespace().any(|c| c == "footnotes"))
+            || tag_attr(&tag, "role") == Some("doc-endnotes");
+        match is_list.then(|| read_until_closing(&chars, tag.end, &tag.name)).flatten() {
+            Some((inner, end)) => {
+                collect_footnote_items(&inner, &mut notes);
+                rest.extend(&chars[copied..pos]);
+                pos = end;
+                copied = end;
+            }
+            None => pos = tag.end.max(pos + 1),
+        }
+    }
+    rest.extend(&chars[copied..]);
+    (rest, notes)
+}
+
+fn collect_footnote_items(html: &str, notes: &mut std::collections::HashMap<String, String>) {
+    let chars: Vec<char> = html.chars().collect();
+    let mut pos = 0;
+    while pos < chars.len() {
+        if let Some(tag) = (chars[pos] == '<').then(|| read_opening_tag(&chars, pos)).flatten() {
+            if tag.name == "li" {
+                if let Some((inner, end)) = read_until_closing(&chars, tag.end, "li") {
+                    let note = strip_backlinks(&strip_tags_simple(&inner, "p"));
+                    for key in [tag_attr(&tag, "id"), tag_attr(&tag, "data-id")].into_iter().flatten() {
+                        notes.insert(key.to_string(), note.trim().to_string());
+                    }
+                    pos = end;
+                    continue;
+                }
+            }
+        }
+        pos += 1;
+    }
+}
+
+/// Drop the "↩" links from a note back to its reference.
+fn strip_backlinks(html: &str) -> String {
+    let chars: Vec<char> = html.chars().collect();
+    let mut out = String::new();
+    let mut pos = 0;
+    while pos < chars.len() {
+        if let Some(tag) = (chars[pos] == '<').then(|| read_opening_tag(&chars, pos)).flatten() {
+            let backlink = tag.name == "a"
+                && (tag_attr(&tag, "href").is_some_and(|h| h.starts_with("#fnref"))
+                    || tag_attr(&tag, "role") == Some("doc-backlink")
+                    || tag_attr(&tag, "class").is_some_and(|c| c.contains("footnote-backref")));
+            if let Some((_, end)) = backlink.then(|| read_until_closing(&chars, tag.end, "a")).flatten() {
+                pos = end;
+                continue;
+            }
+        }
+        out.push(chars[pos]);
+        pos += 1;
+    }
+    out
+}
+
 fn strip_tags_simple(html: &str, tag: &str) -> String {
     let open = format!("<{}", tag);
     let close = format!("</{}>", tag);
@@ -666,7 +1065,24 @@ fn strip_tags_simple(html: &str, tag: &str) -> String {
 }
 
 fn parse_table(html: &str) -> HtmlNode {
-    let mut rows: Vec<Vec<Vec<InlineNode>>> = Vec::new();
+    // Rows before </thead> are headings whatever their cells are
+    let (head, body) = html.split_at(html.find("</thead>").unwrap_or(0));
+    let mut rows = Vec::new();
+    let mut header_rows = 0;
+    for (part, in_head) in [(head, true), (body, false)] {
+        for (cells, all_th) in parse_table_rows(part) {
+            if (in_head || all_th) && header_rows == rows.len() {
+                header_rows += 1;
+            }
+            rows.push(cells);
+        }
+    }
+    HtmlNode::Table { rows, header_rows }
+}
+
+/// Every `<tr>` in `html`, and whether its cells are all `<th>`.
+fn parse_table_rows(html: &str) -> Vec<(Vec<Vec<InlineNode>>, bool)> {
+    let mut rows = Vec::new();
 
     // Strip <thead>, <tbody>, <tfoot> wrappers
     let html = strip_tags_simple(html, "thead");
@@ -682,8 +1098,7 @@ fn parse_table(html: &str) -> HtmlNode {
                 if tag_info.name == "tr" {
                     pos = tag_info.end;
                     if let Some((inner, end)) = read_until_closing(&chars, pos, "tr") {
-                        let cells = parse_table_row(&inner);
-                        rows.push(cells);
+                        rows.push(parse_table_row(&inner));
                         pos = end;
                         continue;
                     }
@@ -692,12 +1107,12 @@ fn parse_table(html: &str) -> HtmlNode {
         }
         pos += 1;
     }
-
-    HtmlNode::Table { rows }
+    rows
 }
 
-fn parse_table_row(html: &str) -> Vec<Vec<InlineNode>> {
+fn parse_table_row(html: &str) -> (Vec<Vec<InlineNode>>, bool) {
     let mut cells: Vec<Vec<InlineNode>> = Vec::new();
+    let mut all_th = true;
     let chars: Vec<char> = html.chars().collect();
     let mut pos = 0;
 
@@ -709,6 +1124,7 @@ fn parse_table_row(html: &str) -> Vec<Vec<InlineNode>> {
                     if let Some((inner, end)) = read_until_closing(&chars, pos, &tag_info.name) {
                         let stripped = strip_tags_simple(&inner, "p");
                         cells.push(parse_inline(&stripped));
+                        all_th &= tag_info.name == "th";
                         pos = end;
                         continue;
                     }
@@ -717,10 +1133,27 @@ fn parse_table_row(html: &str) -> Vec<Vec<InlineNode>> {
         }
         pos += 1;
     }
-    cells
+    let header = all_th && !cells.is_empty();
+    (cells, header)
+}
+
+/// Share of the table's width for each of `columns`, from the length of
+/// the longest text in the column, so a column of numbers doesn't get as
+/// much room as one of sentences. Very long cells wrap instead of
+/// squeezing the rest; every column gets room for a short word.
+fn table_column_weights(rows: &[Vec<Vec<InlineNode>>], columns: usize) -> Vec<f32> {
+    let mut lengths = vec![4usize; columns];
+    for row in rows {
+        for (length, cell) in lengths.iter_mut().zip(row) {
+            let chars: usize = cell.iter().map(|n| n.bracketed_text().chars().count()).sum();
+            *length = (*length).max(chars.min(40));
+        }
+    }
+    let total = lengths.iter().sum::<usize>() as f32;
+    lengths.iter().map(|l| *l as f32 / total).collect()
 }
 
-fn decode_html_entities(text: &str) -> String {
+pub(crate) fn decode_html_entities(text: &str) -> String {
     text.replace("&amp;", "&")
         .replace("&lt;", "<")
         .replace("&gt;", ">")
@@ -739,33 +1172,241 @@ fn decode_html_entities(text: &str) -> String {
         .replace("&rdquo;", "\u{201D}")
 }
 
-/// Count words in plain text (strips all HTML).
-fn count_words(html: &str) -> u64 {
-    let mut in_tag = false;
+/// Flatten editor HTML to plain text, keeping block boundaries as blank
+/// lines so paragraphs survive (used for tweets and plain-text exports).
+pub(crate) fn html_to_plain_text(html: &str) -> String {
     let mut text = String::new();
+    let mut tag = String::new();
+    let mut in_tag = false;
     for ch in html.chars() {
-        if ch == '<' {
-            in_tag = true;
-            text.push(' ');
-        } else if ch == '>' {
-            in_tag = false;
-        } else if !in_tag {
-            text.push(ch);
+        match ch {
+            '<' => {
+                in_tag = true;
+                tag.clear();
+            }
+            '>' if in_tag => {
+                in_tag = false;
+                let name = tag
+                    .trim_start_matches('/')
+                    .split(|c: char| c.is_whitespace() || c == '/')
+                    .next()
+                    .unwrap_or("")
+                    .to_ascii_lowercase();
+                match name.as_str() {
+                    "br" => text.push('\n'),
+                    "p" | "div" | "h1" | "h2" | "h3" | "h4" | "h5" | "h6" | "li" | "blockquote"
+                    | "pre" | "tr" | "hr"
+                        if tag.starts_with('/') || name == "hr" =>
+                    {
+                        text.push_str("\n\n")
+                    }
+                    _ => {}
+                }
+            }
+            _ if in_tag => tag.push(ch),
+            _ => text.push(ch),
+        }
+    }
+
+    let decoded = decode_html_entities(&text);
+    let mut out = String::new();
+    let mut blank = false;
+    for line in decoded.lines().map(str::trim) {
+        if line.is_empty() {
+            blank = !out.is_empty();
+            continue;
         }
+        if blank {
+            out.push_str("\n\n");
+        } else if !out.is_empty() {
+            out.push('\n');
+        }
+        blank = false;
+        out.push_str(line);
     }
-    text.split_whitespace().count() as u64
+    out
 }
 
 // documents_dir and autosave_dir removed — documents now stored in SQLite
 
+// ---------------------------------------------------------------------------
+// Image loading
+// ---------------------------------------------------------------------------
+
+/// An image an exporter can embed, decoded to check it and to size it.
+struct LoadedImage {
+    bytes: Vec<u8>,
+    image: ::image::DynamicImage,
+}
+
+fn percent_decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes.get(i + 1..i + 3).and_then(|h| std::str::from_utf8(h).ok());
+        if let (b'%', Some(Ok(b))) = (bytes[i], hex.map(|h| u8::from_str_radix(h, 16))) {
+            out.push(b);
+            i += 3;
+            continue;
+        }
+        out.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+/// Local file behind an image `src`: `file://` URLs, the asset URLs the
+/// editor gets from `convertFileSrc` for uploaded images, plain absolute
+/// paths, or a bare file name inside the app's images directory.
+fn local_image_path(src: &str, images_dir: Option<&Path>) -> Option<PathBuf> {
+    let encoded = src
+        .strip_prefix("file://")
+        .or_else(|| src.strip_prefix("asset://localhost/"))
+        .or_else(|| src.strip_prefix("http://asset.localhost/"))
+        .or_else(|| src.strip_prefix("https://asset.localhost/"));
+    let path = match encoded {
+        Some(rest) => PathBuf::from(percent_decode(rest.split(['?', '#']).next().unwrap_or(""))),
+        None if src.contains("://") => return None,
+        None => PathBuf::from(percent_decode(src)),
+    };
+    // "asset://localhost/%2Fhome%2F..." decodes to an absolute path, but
+    // "file:///C:/..." leaves a leading slash before the drive letter
+    let path = match path.to_str() {
+        Some(p) if p.len() > 3 && p.starts_with('/') && p.as_bytes()[2] == b':' => PathBuf::from(&p[1..]),
+        _ => path,
+    };
+    if path.is_absolute() && path.is_file() {
+        return Some(path);
+    }
+    let in_images = images_dir?.join(path.file_name()?);
+    in_images.is_file().then_some(in_images)
+}
+
+/// Bytes of an image from a base64 data URI or a local file. Remote URLs
+/// aren't fetched; exports stay offline.
+fn load_image(src: &str, images_dir: Option<&Path>) -> Option<LoadedImage> {
+    let bytes = match src.strip_prefix("data:") {
+        Some(data_uri) => {
+            let (meta, data) = data_uri.split_once(',')?;
+            if !meta.ends_with(";base64") {
+                return None;
+            }
+            base64::engine::general_purpose::STANDARD.decode(data.trim()).ok()?
+        }
+        None => std::fs::read(local_image_path(src, images_dir)?).ok()?,
+    };
+    let image = ::image::load_from_memory(&bytes).ok()?;
+    Some(LoadedImage { bytes, image })
+}
+
+/// Raw bytes of a data URI or local image, for callers outside the exporters.
+pub(crate) fn image_bytes(src: &str, images_dir: Option<&Path>) -> Option<Vec<u8>> {
+    load_image(src, images_dir).map(|loaded| loaded.bytes)
+}
+
+/// Every `<img>` in `html`, nested or not, as (src, alt); `alt` is None
+/// when the attribute is absent.
+pub(crate) fn image_tags(html: &str) -> Vec<(String, Option<String>)> {
+    let chars: Vec<char> = html.chars().collect();
+    let mut images = Vec::new();
+    let mut pos = 0;
+    while pos < chars.len() {
+        if chars[pos] == '<' {
+            if let Some(tag) = read_opening_tag(&chars, pos) {
+                if tag.name.eq_ignore_ascii_case("img") {
+                    let attr = |name: &str| {
+                        tag.attrs.iter().find(|(k, _)| k.eq_ignore_ascii_case(name)).map(|(_, v)| v.clone())
+                    };
+                    images.push((attr("src").unwrap_or_default(), attr("alt")));
+                }
+                pos = tag.end.max(pos + 1);
+                continue;
+            }
+        }
+        pos += 1;
+    }
+    images
+}
+
+/// Size that fits within `max_w` x `max_h` while keeping the aspect ratio,
+/// never enlarging past the image's own size in the same units.
+fn fit_within(width: f32, height: f32, max_w: f32, max_h: f32) -> (f32, f32) {
+    if width <= 0.0 || height <= 0.0 {
+        return (max_w, max_h);
+    }
+    let scale = (max_w / width).min(max_h / height).min(1.0);
+    (width * scale, height * scale)
+}
+
+// ---------------------------------------------------------------------------
+// Code highlighting
+// ---------------------------------------------------------------------------
+
+/// A light theme, readable on white pages and the grey email code box
+const CODE_THEME: &str = "InspiredGitHub";
+
+/// Runs of text and their colour making up one line of highlighted code.
+type CodeLine = Vec<(String, [u8; 3])>;
+
+static CODE_SYNTAXES: std::sync::OnceLock<syntect::parsing::SyntaxSet> = std::sync::OnceLock::new();
+static CODE_THEMES: std::sync::OnceLock<syntect::highlighting::ThemeSet> = std::sync::OnceLock::new();
+
+/// The lines of `code` as coloured runs, going by the `language-*` name
+/// on the block (a name like "rust" or an extension like "rs"). `None`
+/// for a language the highlighter doesn't know, which stays uncoloured.
+fn highlight_code(code: &str, language: &str) -> Option<Vec<CodeLine>> {
+    let syntaxes = CODE_SYNTAXES.get_or_init(syntect::parsing::SyntaxSet::load_defaults_newlines);
+    let themes = CODE_THEMES.get_or_init(syntect::highlighting::ThemeSet::load_defaults);
+    let language = language.trim();
+    // No TypeScript grammar among the defaults; JavaScript's is close enough
+    let syntax = syntaxes.find_syntax_by_token(language).or_else(|| match language {
+        "ts" | "tsx" | "typescript" => syntaxes.find_syntax_by_token("js"),
+        _ => None,
+    })?;
+    let mut highlighter = syntect::easy::HighlightLines::new(syntax, themes.themes.get(CODE_THEME)?);
+
+    let mut lines = Vec::new();
+    for line in syntect::util::LinesWithEndings::from(code.trim_end_matches('\n')) {
+        let mut runs: CodeLine = Vec::new();
+        for (style, text) in highlighter.highlight_line(line, syntaxes).ok()? {
+            let text = text.trim_end_matches(['\n', '\r']);
+            let colour = [style.foreground.r, style.foreground.g, style.foreground.b];
+            match runs.last_mut() {
+                Some((last, last_colour)) if *last_colour == colour || text.trim().is_empty() => last.push_str(text),
+                _ if !text.is_empty() => runs.push((text.to_string(), colour)),
+                _ => {}
+            }
+        }
+        lines.push(runs);
+    }
+    Some(lines)
+}
+
 // ---------------------------------------------------------------------------
 // DOCX export
 // ---------------------------------------------------------------------------
 
-fn inline_nodes_to_runs(children: &[InlineNode]) -> Vec<Run> {
+/// Runs for `children`. Footnote references become Word footnotes, except
+/// with `footnotes` off (table cells, where docx-rs doesn't look for them)
+/// where only the mark is kept; see `number_docx_footnotes`.
+fn inline_nodes_to_runs(children: &[InlineNode], footnotes: bool) -> Vec<Run> {
     children
         .iter()
         .map(|node| {
+            if let Some(footnote) = &node.footnote {
+                let mark = Run::new().add_text(&node.text).style(DOCX_FOOTNOTE_REF_STYLE);
+                if !footnotes {
+                    return mark;
+                }
+                // docx-rs has no footnoteRef to number the note itself, so
+                // the number is written out
+                let mut note = Paragraph::new().style("FootnoteText").add_run(mark).add_run(Run::new().add_text(" "));
+                for run in inline_nodes_to_runs(&footnote.note, false) {
+                    note = note.add_run(run);
+                }
+                return Run::new().add_footnote_reference(docx_rs::Footnote::new().add_content(note));
+            }
             let mut run = Run::new().add_text(&node.text);
             if node.bold {
                 run = run.bold();
@@ -777,101 +1418,241 @@ fn inline_nodes_to_runs(children: &[InlineNode]) -> Vec<Run> {
                 run = run.underline("single");
             }
             if node.code {
-                run = run.fonts(RunFonts::new().ascii("Courier New"));
+                run = run.style(DOCX_CODE_CHAR_STYLE);
             }
             run
         })
         .collect()
 }
 
-fn build_docx(title: &str, html: &str) -> Result<Vec<u8>, String> {
-    let nodes = parse_html(html);
-    let mut docx = Docx::new();
+/// Inline code, linked to the Code paragraph style
+const DOCX_CODE_CHAR_STYLE: &str = "CodeChar";
+/// Footnote numbers, in the text and in front of each note
+const DOCX_FOOTNOTE_REF_STYLE: &str = "FootnoteReference";
+/// Numbering (and its abstract definition) shared by every bullet list
+const DOCX_BULLET_NUMBERING: usize = 1;
+/// Abstract definition of numbered lists; each list gets its own numbering
+/// on top of it, numbered from `DOCX_BULLET_NUMBERING + 1`, so it restarts at 1
+const DOCX_ORDERED_ABSTRACT: usize = 2;
+/// Word supports nine list levels
+const DOCX_LIST_LEVELS: usize = 9;
+
+/// Named styles for everything `build_docx` emits, so the structure is
+/// visible to Word themes, the navigation pane and other editors instead
+/// of living in per-run formatting. Sizes are in half-points.
+fn docx_styles(docx: Docx) -> Docx {
+    let paragraph = |id: &str, name: &str| {
+        Style::new(id, StyleType::Paragraph).name(name).based_on("Normal").next("Normal")
+    };
+    let mono = || RunFonts::new().ascii("Courier New").hi_ansi("Courier New").cs("Courier New");
+    let mut docx = docx
+        .add_style(
+            paragraph("Title", "Title")
+                .size(48)
+                .bold()
+                .align(AlignmentType::Center)
+                .line_spacing(LineSpacing::new().after(240)),
+        )
+        .add_style(paragraph("Quote", "Quote").italic().indent(Some(720), None, Some(720), None))
+        .add_style(
+            paragraph("Code", "Code")
+                .link(DOCX_CODE_CHAR_STYLE)
+                .fonts(mono())
+                .indent(Some(360), None, None, None)
+                .line_spacing(LineSpacing::new().before(0).after(0)),
+        )
+        .add_style(Style::new(DOCX_CODE_CHAR_STYLE, StyleType::Character).name("Code Char").link("Code").fonts(mono()))
+        .add_style(paragraph("ListParagraph", "List Paragraph").indent(Some(720), None, None, None))
+        .add_style(paragraph("Caption", "Caption").italic().size(18).align(AlignmentType::Center))
+        .add_style(paragraph("FootnoteText", "footnote text").size(20).line_spacing(LineSpacing::new().after(0)))
+        .add_style(paragraph("TOCHeading", "TOC Heading").size(32).bold().line_spacing(LineSpacing::new().after(120)));
+    let mut footnote_ref = Style::new(DOCX_FOOTNOTE_REF_STYLE, StyleType::Character).name("footnote reference");
+    footnote_ref.run_property = footnote_ref.run_property.vert_align(VertAlignType::SuperScript);
+    docx = docx.add_style(footnote_ref);
+    for (level, size) in [(1, 72), (2, 60), (3, 52), (4, 48)] {
+        docx = docx.add_style(
+            paragraph(&format!("Heading{}", level), &format!("heading {}", level))
+                .size(size)
+                .bold()
+                .outline_lvl(level - 1),
+        );
+    }
+    // The ids docx-rs gives table of contents entries
+    for level in 1..=3 {
+        docx = docx.add_style(
+            paragraph(&format!("ToC{}", level), &format!("toc {}", level))
+                .indent(Some(240 * (level - 1)), None, None, None)
+                .line_spacing(LineSpacing::new().after(60)),
+        );
+    }
+    docx
+}
+
+/// Multi-level bullet and number definitions: bullets go •, ◦, ▪ and
+/// numbers 1., a., i. by level, each level indented a further half inch.
+fn docx_list_numbering(docx: Docx) -> Docx {
+    let mut bullets = AbstractNumbering::new(DOCX_BULLET_NUMBERING);
+    let mut numbers = AbstractNumbering::new(DOCX_ORDERED_ABSTRACT);
+    for level in 0..DOCX_LIST_LEVELS {
+        let indent = |l: Level| l.indent(Some(720 * (level as i32 + 1)), Some(SpecialIndentType::Hanging(360)), None, None);
+        let bullet = ["\u{2022}", "\u{25e6}", "\u{25aa}"][level % 3];
+        bullets = bullets.add_level(indent(Level::new(
+            level,
+            Start::new(1),
+            NumberFormat::new("bullet"),
+            LevelText::new(bullet),
+            LevelJc::new("left"),
+        )));
+        let format = ["decimal", "lowerLetter", "lowerRoman"][level % 3];
+        numbers = numbers.add_level(indent(Level::new(
+            level,
+            Start::new(1),
+            NumberFormat::new(format),
+            LevelText::new(format!("%{}.", level + 1)),
+            LevelJc::new("left"),
+        )));
+    }
+    docx.add_abstract_numbering(bullets)
+        .add_abstract_numbering(numbers)
+        .add_numbering(Numbering::new(DOCX_BULLET_NUMBERING, DOCX_BULLET_NUMBERING))
+}
+
+/// Add `list` and its nested lists as numbered paragraphs. `next_numbering`
+/// is the id for the next numbered list. Task lists aren't numbered but
+/// start each item with a ☐ or ☑, indented like the other lists.
+fn docx_list(mut docx: Docx, list: &HtmlNode, depth: usize, next_numbering: &mut usize) -> Docx {
+    let level = depth.min(DOCX_LIST_LEVELS - 1);
+    if let HtmlNode::TaskList { items } = list {
+        for item in items {
+            let marker = if item.checked == Some(true) { "\u{2611}" } else { "\u{2610}" };
+            let mut para = Paragraph::new()
+                .style("ListParagraph")
+                .indent(Some(720 * (level as i32 + 1)), Some(SpecialIndentType::Hanging(360)), None, None)
+                .add_run(Run::new().add_text(format!("{}\t", marker)));
+            for run in inline_nodes_to_runs(&item.children, true) {
+                para = para.add_run(run);
+            }
+            docx = docx.add_paragraph(para);
+            for sublist in &item.sublists {
+                docx = docx_list(docx, sublist, depth + 1, next_numbering);
+            }
+        }
+        return docx;
+    }
+    let (items, numbering) = match list {
+        HtmlNode::UnorderedList { items } => (items, DOCX_BULLET_NUMBERING),
+        HtmlNode::OrderedList { items } => {
+            let id = *next_numbering;
+            *next_numbering += 1;
+            let restart = (0..DOCX_LIST_LEVELS).map(|level| LevelOverride::new(level).start(1)).collect();
+            docx = docx.add_numbering(Numbering::new(id, DOCX_ORDERED_ABSTRACT).overrides(restart));
+            (items, id)
+        }
+        _ => return docx,
+    };
+    for item in items {
+        let mut para = Paragraph::new()
+            .style("ListParagraph")
+            .numbering(NumberingId::new(numbering), IndentLevel::new(level));
+        for run in inline_nodes_to_runs(&item.children, true) {
+            para = para.add_run(run);
+        }
+        docx = docx.add_paragraph(para);
+        for sublist in &item.sublists {
+            docx = docx_list(docx, sublist, depth + 1, next_numbering);
+        }
+    }
+    docx
+}
 
-    // Title
-    let title_para = Paragraph::new()
-        .add_run(Run::new().add_text(title).bold().size(48))
-        .align(AlignmentType::Center);
-    docx = docx.add_paragraph(title_para);
+/// Text column of docx-rs's default page: A4 less 3cm margins
+const DOCX_TEXT_WIDTH_TWIPS: usize = 8504;
+/// Header row shading and table borders
+const DOCX_TABLE_HEADER_FILL: &str = "F2F2F2";
+const DOCX_TABLE_BORDER: &str = "BFBFBF";
+
+/// Images are scaled to fit the text column of a default page (96 dpi)
+const DOCX_IMAGE_MAX_WIDTH_PX: f32 = 576.0;
+const DOCX_IMAGE_MAX_HEIGHT_PX: f32 = 816.0;
+const EMU_PER_PX: f32 = 9525.0;
+
+/// Word numbers footnotes itself, counting only real ones. Notes on table
+/// cells are set under their table instead, lettered a, b, c, so the text
+/// of each reference is renumbered to match what Word shows.
+fn number_docx_footnotes(nodes: &mut [HtmlNode]) {
+    let mut number = 0;
+    for node in nodes {
+        let in_table = matches!(node, HtmlNode::Table { .. });
+        let mut letter = 0;
+        for inline in block_inlines_mut(node).into_iter().flatten() {
+            if inline.footnote.is_none() {
+                continue;
+            }
+            inline.text = if in_table {
+                letter += 1;
+                char::from(b'a' + ((letter - 1) % 26) as u8).to_string()
+            } else {
+                number += 1;
+                number.to_string()
+            };
+        }
+    }
+}
 
-    // Spacer
-    docx = docx.add_paragraph(Paragraph::new());
+/// `toc` adds a table of contents of the H1–H3 headings after the title,
+/// each entry linking to its heading.
+pub(crate) fn build_docx(title: &str, html: &str, images_dir: Option<&Path>, toc: bool) -> Result<Vec<u8>, String> {
+    let mut nodes = parse_html(html);
+    number_docx_footnotes(&mut nodes);
+    let mut docx = docx_list_numbering(docx_styles(Docx::new()));
+    let mut next_numbering = DOCX_BULLET_NUMBERING + 1;
+
+    docx = docx.add_paragraph(Paragraph::new().style("Title").add_run(Run::new().add_text(title)));
+
+    if toc && nodes.iter().any(|node| matches!(node, HtmlNode::Heading { level: 1..=3, .. })) {
+        // docx-rs fills in the entries from the headings, bookmarked, when
+        // the file is built. Page numbers are left blank for Word to fill
+        // in: the field is marked dirty, so it's updated on opening.
+        let contents = TableOfContents { page_ref_placeholder: Some(String::new()), ..TableOfContents::new() }
+            .heading_styles_range(1, 3)
+            .hyperlink()
+            .alias("Contents")
+            .auto()
+            .dirty()
+            .add_before_paragraph(Paragraph::new().style("TOCHeading").add_run(Run::new().add_text("Contents")))
+            .add_after_paragraph(Paragraph::new().add_run(Run::new().add_break(BreakType::Page)));
+        docx = docx.add_table_of_contents(contents);
+    }
 
     for node in &nodes {
         match node {
             HtmlNode::Heading { level, children } => {
-                let size = match level {
-                    1 => 36,
-                    2 => 30,
-                    3 => 26,
-                    _ => 24,
-                };
-                let mut para = Paragraph::new();
-                for child in children {
-                    let mut run = Run::new().add_text(&child.text).size(size * 2).bold();
-                    if child.italic {
-                        run = run.italic();
-                    }
-                    if child.underline {
-                        run = run.underline("single");
-                    }
+                let mut para = Paragraph::new().style(&format!("Heading{}", (*level).clamp(1, 4)));
+                for run in inline_nodes_to_runs(children, true) {
                     para = para.add_run(run);
                 }
                 docx = docx.add_paragraph(para);
             }
             HtmlNode::Paragraph { children } => {
                 let mut para = Paragraph::new();
-                for run in inline_nodes_to_runs(children) {
+                for run in inline_nodes_to_runs(children, true) {
                     para = para.add_run(run);
                 }
                 docx = docx.add_paragraph(para);
             }
-            HtmlNode::UnorderedList { items } => {
-                for item_children in items {
-                    let mut para = Paragraph::new();
-                    // Add bullet character as prefix
-                    let bullet_run = Run::new().add_text("\u{2022}  ");
-                    para = para.add_run(bullet_run);
-                    for run in inline_nodes_to_runs(item_children) {
-                        para = para.add_run(run);
-                    }
-                    para = para.indent(Some(720), None, None, None);
-                    docx = docx.add_paragraph(para);
-                }
-            }
-            HtmlNode::OrderedList { items } => {
-                for (i, item_children) in items.iter().enumerate() {
-                    let mut para = Paragraph::new();
-                    let num_run = Run::new().add_text(&format!("{}. ", i + 1));
-                    para = para.add_run(num_run);
-                    for run in inline_nodes_to_runs(item_children) {
-                        para = para.add_run(run);
-                    }
-                    para = para.indent(Some(720), None, None, None);
-                    docx = docx.add_paragraph(para);
-                }
+            HtmlNode::UnorderedList { .. } | HtmlNode::OrderedList { .. } | HtmlNode::TaskList { .. } => {
+                docx = docx_list(docx, node, 0, &mut next_numbering);
             }
             HtmlNode::Blockquote { children } => {
-                let mut para = Paragraph::new();
-                para = para.indent(Some(720), None, None, None);
-                for child in children {
-                    let mut run = Run::new().add_text(&child.text).italic();
-                    if child.bold {
-                        run = run.bold();
-                    }
+                let mut para = Paragraph::new().style("Quote");
+                for run in inline_nodes_to_runs(children, true) {
                     para = para.add_run(run);
                 }
                 docx = docx.add_paragraph(para);
             }
-            HtmlNode::CodeBlock { text } => {
+            HtmlNode::CodeBlock { text, .. } => {
                 for line in text.lines() {
-                    let para = Paragraph::new()
-                        .add_run(
-                            Run::new()
-                                .add_text(line)
-                                .fonts(RunFonts::new().ascii("Courier New")),
-                        )
-                        .indent(Some(360), None, None, None);
+                    let para = Paragraph::new().style("Code").add_run(Run::new().add_text(line));
                     docx = docx.add_paragraph(para);
                 }
             }
@@ -880,7 +1661,7 @@ fn build_docx(title: &str, html: &str) -> Result<Vec<u8>, String> {
                     .add_run(Run::new().add_text("________________________________________"));
                 docx = docx.add_paragraph(para);
             }
-            HtmlNode::Table { rows } => {
+            HtmlNode::Table { rows, header_rows } => {
                 if rows.is_empty() {
                     continue;
                 }
@@ -889,51 +1670,84 @@ fn build_docx(title: &str, html: &str) -> Result<Vec<u8>, String> {
                     continue;
                 }
 
-                let mut table = Table::new(Vec::new());
-                for row_cells in rows {
+                let widths: Vec<usize> = table_column_weights(rows, col_count)
+                    .iter()
+                    .map(|w| (w * DOCX_TEXT_WIDTH_TWIPS as f32).round() as usize)
+                    .collect();
+                let mut table = Table::new(Vec::new())
+                    .set_grid(widths.clone())
+                    .width(DOCX_TEXT_WIDTH_TWIPS, WidthType::Dxa)
+                    .layout(TableLayoutType::Fixed)
+                    .margins(TableCellMargins::new().margin(60, 100, 60, 100));
+                for position in [
+                    TableBorderPosition::Top,
+                    TableBorderPosition::Left,
+                    TableBorderPosition::Bottom,
+                    TableBorderPosition::Right,
+                    TableBorderPosition::InsideH,
+                    TableBorderPosition::InsideV,
+                ] {
+                    table = table.set_border(TableBorder::new(position).size(4).color(DOCX_TABLE_BORDER));
+                }
+                for (r, row_cells) in rows.iter().enumerate() {
+                    let header = r < *header_rows;
                     let mut cells: Vec<TableCell> = Vec::new();
-                    for cell_inlines in row_cells {
+                    for (c, width) in widths.iter().enumerate() {
                         let mut para = Paragraph::new();
-                        for run in inline_nodes_to_runs(cell_inlines) {
-                            para = para.add_run(run);
+                        for run in row_cells.get(c).map(|cell| inline_nodes_to_runs(cell, false)).unwrap_or_default() {
+                            para = para.add_run(if header { run.bold() } else { run });
                         }
-                        cells.push(TableCell::new().add_paragraph(para));
-                    }
-                    // Pad missing cells
-                    for _ in row_cells.len()..col_count {
-                        cells.push(TableCell::new().add_paragraph(Paragraph::new()));
+                        let mut cell = TableCell::new().width(*width, WidthType::Dxa).add_paragraph(para);
+                        if header {
+                            cell = cell.shading(Shading::new().fill(DOCX_TABLE_HEADER_FILL));
+                        }
+                        cells.push(cell);
                     }
-                    table = table.add_row(TableRow::new(cells));
+                    let row = TableRow::new(cells);
+                    table = table.add_row(if header { row.cant_split() } else { row });
                 }
                 docx = docx.add_table(table);
+                for inline in rows.iter().flatten().flatten() {
+                    let Some(footnote) = &inline.footnote else { continue };
+                    let mut para = Paragraph::new()
+                        .style("FootnoteText")
+                        .add_run(Run::new().add_text(&inline.text).style(DOCX_FOOTNOTE_REF_STYLE))
+                        .add_run(Run::new().add_text(" "));
+                    for run in inline_nodes_to_runs(&footnote.note, false) {
+                        para = para.add_run(run);
+                    }
+                    docx = docx.add_paragraph(para);
+                }
             }
-            HtmlNode::Image { src, alt } => {
-                // For images, include a placeholder with the alt text
-                let display = if alt.is_empty() { "[Image]" } else { alt };
-                let para = Paragraph::new()
-                    .add_run(Run::new().add_text(display).italic())
-                    .align(AlignmentType::Center);
-                docx = docx.add_paragraph(para);
-
-                // If it's a base64 data URI, try to embed the image
-                if src.starts_with("data:image/png;base64,") || src.starts_with("data:image/jpeg;base64,") {
-                    let base64_data = if let Some(data) = src.split(",").nth(1) {
-                        data
-                    } else {
-                        continue;
-                    };
-                    if let Ok(image_bytes) = base64::engine::general_purpose::STANDARD.decode(base64_data) {
-                        let pic = Pic::new(&image_bytes)
-                            .size(400 * 9525, 300 * 9525); // ~400x300 px in EMU
-                        let para = Paragraph::new()
+            HtmlNode::Image { src, alt } => match load_image(src, images_dir) {
+                Some(loaded) => {
+                    let (w, h) = fit_within(
+                        loaded.image.width() as f32,
+                        loaded.image.height() as f32,
+                        DOCX_IMAGE_MAX_WIDTH_PX,
+                        DOCX_IMAGE_MAX_HEIGHT_PX,
+                    );
+                    let pic = Pic::new(&loaded.bytes).size((w * EMU_PER_PX) as u32, (h * EMU_PER_PX) as u32);
+                    docx = docx.add_paragraph(
+                        Paragraph::new()
                             .add_run(Run::new().add_image(pic))
-                            .align(AlignmentType::Center);
-                        docx = docx.add_paragraph(para);
+                            .align(AlignmentType::Center),
+                    );
+                    if !alt.is_empty() {
+                        docx = docx.add_paragraph(Paragraph::new().style("Caption").add_run(Run::new().add_text(alt)));
                     }
                 }
-            }
-        }
-    }
+                None => {
+                    // Remote or unreadable image: keep a placeholder with the alt text
+                    let display = if alt.is_empty() { "[Image]" } else { alt };
+                    let para = Paragraph::new()
+                        .add_run(Run::new().add_text(display).italic())
+                        .align(AlignmentType::Center);
+                    docx = docx.add_paragraph(para);
+                }
+            },
+        }
+    }
 
     let mut buf = Vec::new();
     docx.build()
@@ -943,439 +1757,4312 @@ fn build_docx(title: &str, html: &str) -> Result<Vec<u8>, String> {
 }
 
 // ---------------------------------------------------------------------------
-// PDF export
+// DOCX import
 // ---------------------------------------------------------------------------
-
-/// A4 dimensions in mm
-const A4_WIDTH_MM: f32 = 210.0;
-const A4_HEIGHT_MM: f32 = 297.0;
-
-/// Margins in mm
-const MARGIN_LEFT: f32 = 25.0;
-const MARGIN_RIGHT: f32 = 25.0;
-const MARGIN_TOP: f32 = 25.0;
-const MARGIN_BOTTOM: f32 = 25.0;
-
-/// Usable width in mm
-const USABLE_WIDTH: f32 = A4_WIDTH_MM - MARGIN_LEFT - MARGIN_RIGHT;
-
-/// Points per mm (1pt = 0.3528mm, so 1mm ≈ 2.8346pt)
-const PT_PER_MM: f32 = 2.8346;
-
-struct PdfWriter {
-    doc: PdfDocumentReference,
-    current_page: PdfPageIndex,
-    current_layer: PdfLayerIndex,
-    y_pos: f32,         // current y position in mm from bottom
-    font_regular: IndirectFontRef,
-    font_bold: IndirectFontRef,
-    font_italic: IndirectFontRef,
-    font_bold_italic: IndirectFontRef,
-    font_mono: IndirectFontRef,
-    page_count: usize,
+//
+// A .docx is a zip of WordprocessingML parts. Only document.xml and the
+// parts it points at are read: relationships for links and images, the
+// numbering definitions to tell bullets from numbers, and style names to
+// find headings, quotes and code. Everything is mapped onto the editor's
+// HTML; formatting the editor has no notion of (fonts, colours, spacing)
+// is dropped.
+
+/// One token of an OOXML part. Self-closing elements produce an `Open`
+/// followed by a `Close`.
+enum OoxmlEvent {
+    Open(String, Vec<(String, String)>),
+    Close(String),
+    Text(String),
 }
 
-impl PdfWriter {
-    fn new(title: &str) -> Result<Self, String> {
-        let (doc, page_idx, layer_idx) = PdfDocument::new(
-            title,
-            Mm(A4_WIDTH_MM),
-            Mm(A4_HEIGHT_MM),
-            "Layer 1",
-        );
-
-        let font_regular = doc
-            .add_builtin_font(BuiltinFont::Helvetica)
-            .map_err(|e| format!("Failed to add Helvetica font: {}", e))?;
-        let font_bold = doc
-            .add_builtin_font(BuiltinFont::HelveticaBold)
-            .map_err(|e| format!("Failed to add Helvetica-Bold font: {}", e))?;
-        let font_italic = doc
-            .add_builtin_font(BuiltinFont::HelveticaOblique)
-            .map_err(|e| format!("Failed to add Helvetica-Oblique font: {}", e))?;
-        let font_bold_italic = doc
-            .add_builtin_font(BuiltinFont::HelveticaBoldOblique)
-            .map_err(|e| format!("Failed to add Helvetica-BoldOblique font: {}", e))?;
-        let font_mono = doc
-            .add_builtin_font(BuiltinFont::Courier)
-            .map_err(|e| format!("Failed to add Courier font: {}", e))?;
+impl OoxmlEvent {
+    fn attr(&self, name: &str) -> Option<&str> {
+        match self {
+            OoxmlEvent::Open(_, attrs) => attrs.iter().find(|(k, _)| k == name).map(|(_, v)| v.as_str()),
+            _ => None,
+        }
+    }
 
-        Ok(PdfWriter {
-            doc,
-            current_page: page_idx,
-            current_layer: layer_idx,
-            y_pos: A4_HEIGHT_MM - MARGIN_TOP,
-            font_regular,
-            font_bold,
-            font_italic,
-            font_bold_italic,
-            font_mono,
-            page_count: 1,
-        })
+    fn is_open(&self, tag: &str) -> bool {
+        matches!(self, OoxmlEvent::Open(name, _) if name == tag)
     }
 
-    fn new_page(&mut self) {
-        let (page_idx, layer_idx) = self.doc.add_page(
-            Mm(A4_WIDTH_MM),
-            Mm(A4_HEIGHT_MM),
-            &format!("Layer {}", self.page_count + 1),
-        );
-        self.current_page = page_idx;
-        self.current_layer = layer_idx;
-        self.y_pos = A4_HEIGHT_MM - MARGIN_TOP;
-        self.page_count += 1;
+    fn is_close(&self, tag: &str) -> bool {
+        matches!(self, OoxmlEvent::Close(name) if name == tag)
     }
+}
 
-    fn ensure_space(&mut self, needed_mm: f32) {
-        if self.y_pos - needed_mm < MARGIN_BOTTOM {
-            self.new_page();
+fn xml_unescape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        out.push_str(&rest[..start]);
+        let Some(len) = rest[start..].find(';') else {
+            break;
+        };
+        let entity = &rest[start + 1..start + len];
+        let decoded = match entity {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            _ => entity
+                .strip_prefix("#x")
+                .map(|hex| u32::from_str_radix(hex, 16))
+                .or_else(|| entity.strip_prefix('#').map(|dec| dec.parse::<u32>()))
+                .and_then(|n| n.ok())
+                .and_then(char::from_u32),
+        };
+        match decoded {
+            Some(c) => out.push(c),
+            None => out.push_str(&rest[start..start + len + 1]),
         }
+        rest = &rest[start + len + 1..];
     }
+    out.push_str(rest);
+    out
+}
 
-    fn select_font(&self, bold: bool, italic: bool, code: bool) -> &IndirectFontRef {
-        if code {
-            return &self.font_mono;
+fn ooxml_events(xml: &str) -> Vec<OoxmlEvent> {
+    let mut events = Vec::new();
+    let mut rest = xml;
+    while let Some(start) = rest.find('<') {
+        if start > 0 {
+            events.push(OoxmlEvent::Text(xml_unescape(&rest[..start])));
         }
-        match (bold, italic) {
-            (true, true) => &self.font_bold_italic,
-            (true, false) => &self.font_bold,
-            (false, true) => &self.font_italic,
-            (false, false) => &self.font_regular,
+        rest = &rest[start..];
+        // Declarations, comments and processing instructions
+        if rest.starts_with("<!--") {
+            rest = rest.find("-->").map(|i| &rest[i + 3..]).unwrap_or("");
+            continue;
+        }
+        if rest.starts_with("<?") || rest.starts_with("<!") {
+            rest = rest.find('>').map(|i| &rest[i + 1..]).unwrap_or("");
+            continue;
+        }
+        // Find the end of the tag, skipping '>' inside attribute values
+        let mut quote = None;
+        let Some(end) = rest.char_indices().find_map(|(i, c)| match (quote, c) {
+            (None, '"' | '\'') => {
+                quote = Some(c);
+                None
+            }
+            (Some(q), c) if c == q => {
+                quote = None;
+                None
+            }
+            (None, '>') => Some(i),
+            _ => None,
+        }) else {
+            break;
+        };
+        let inner = &rest[1..end];
+        rest = &rest[end + 1..];
+        if let Some(name) = inner.strip_prefix('/') {
+            events.push(OoxmlEvent::Close(name.trim().to_string()));
+            continue;
+        }
+        let self_closing = inner.ends_with('/');
+        let inner = inner.trim_end_matches('/');
+        let name_end = inner.find(char::is_whitespace).unwrap_or(inner.len());
+        let name = inner[..name_end].to_string();
+        let mut attrs = Vec::new();
+        let mut attr_text = &inner[name_end..];
+        while let Some(eq) = attr_text.find('=') {
+            let key = attr_text[..eq].trim().to_string();
+            let value_text = attr_text[eq + 1..].trim_start();
+            let Some(q) = value_text.chars().next().filter(|c| *c == '"' || *c == '\'') else {
+                break;
+            };
+            let Some(close) = value_text[1..].find(q) else {
+                break;
+            };
+            attrs.push((key, xml_unescape(&value_text[1..1 + close])));
+            attr_text = &value_text[close + 2..];
+        }
+        events.push(OoxmlEvent::Open(name.clone(), attrs));
+        if self_closing {
+            events.push(OoxmlEvent::Close(name));
         }
     }
+    events
+}
 
-    /// Approximate width of a string in mm for a given font size (pt).
-    /// Built-in Helvetica has ~600 units per 1000 average char width.
-    fn approx_text_width_mm(&self, text: &str, font_size_pt: f32, is_mono: bool) -> f32 {
-        let avg_char_width_ratio = if is_mono { 0.60 } else { 0.52 };
-        let char_width_pt = font_size_pt * avg_char_width_ratio;
-        let char_width_mm = char_width_pt / PT_PER_MM;
-        text.chars().count() as f32 * char_width_mm
-    }
-
-    /// Wrap text into lines that fit within the given width in mm.
-    fn wrap_text(&self, text: &str, font_size_pt: f32, max_width_mm: f32, is_mono: bool) -> Vec<String> {
-        let mut lines = Vec::new();
+/// What the document's relationship ids point at.
+struct DocxRels {
+    /// Id -> external URL
+    links: std::collections::HashMap<String, String>,
+    /// Id -> zip entry of an embedded image
+    images: std::collections::HashMap<String, String>,
+}
 
-        for hard_line in text.split('\n') {
-            let words: Vec<&str> = hard_line.split_whitespace().collect();
-            if words.is_empty() {
-                lines.push(String::new());
-                continue;
+fn docx_rels(xml: &str) -> DocxRels {
+    let mut rels = DocxRels { links: Default::default(), images: Default::default() };
+    for event in ooxml_events(xml) {
+        if !event.is_open("Relationship") {
+            continue;
+        }
+        let (Some(id), Some(target)) = (event.attr("Id"), event.attr("Target")) else {
+            continue;
+        };
+        let kind = event.attr("Type").unwrap_or("");
+        if event.attr("TargetMode") == Some("External") {
+            if kind.ends_with("/hyperlink") {
+                rels.links.insert(id.to_string(), target.to_string());
             }
+        } else if kind.ends_with("/image") {
+            // Targets are relative to word/, or absolute from the package root
+            let entry = match target.strip_prefix('/') {
+                Some(absolute) => absolute.to_string(),
+                None => format!("word/{}", target.trim_start_matches("./")),
+            };
+            rels.images.insert(id.to_string(), entry);
+        }
+    }
+    rels
+}
 
-            let mut current_line = String::new();
-            for word in &words {
-                let test = if current_line.is_empty() {
-                    word.to_string()
-                } else {
-                    format!("{} {}", current_line, word)
-                };
-                if self.approx_text_width_mm(&test, font_size_pt, is_mono) > max_width_mm
-                    && !current_line.is_empty()
-                {
-                    lines.push(current_line);
-                    current_line = word.to_string();
-                } else {
-                    current_line = test;
+/// (numId, level) pairs whose list is numbered rather than bulleted.
+fn docx_numbered_levels(xml: &str) -> std::collections::HashSet<(String, u8)> {
+    let events = ooxml_events(xml);
+    // abstractNumId -> levels with a numeric format
+    let mut abstract_numbered: std::collections::HashMap<String, Vec<u8>> = Default::default();
+    let mut current_abstract = None;
+    let mut current_level = 0u8;
+    let mut num_to_abstract = Vec::new();
+    let mut current_num = None;
+    for event in &events {
+        if event.is_open("w:abstractNum") {
+            current_abstract = event.attr("w:abstractNumId").map(str::to_string);
+        } else if event.is_open("w:lvl") {
+            current_level = event.attr("w:ilvl").and_then(|l| l.parse().ok()).unwrap_or(0);
+        } else if event.is_open("w:numFmt") {
+            if let Some(abstract_id) = &current_abstract {
+                if !matches!(event.attr("w:val"), Some("bullet") | Some("none")) {
+                    abstract_numbered.entry(abstract_id.clone()).or_default().push(current_level);
                 }
             }
-            if !current_line.is_empty() {
-                lines.push(current_line);
+        } else if event.is_close("w:abstractNum") {
+            current_abstract = None;
+        } else if event.is_open("w:num") {
+            current_num = event.attr("w:numId").map(str::to_string);
+        } else if event.is_open("w:abstractNumId") {
+            if let (Some(num), Some(abstract_id)) = (&current_num, event.attr("w:val")) {
+                num_to_abstract.push((num.clone(), abstract_id.to_string()));
             }
         }
-
-        if lines.is_empty() {
-            lines.push(String::new());
+    }
+    let mut numbered = std::collections::HashSet::new();
+    for (num, abstract_id) in num_to_abstract {
+        for level in abstract_numbered.get(&abstract_id).into_iter().flatten() {
+            numbered.insert((num.clone(), *level));
         }
-        lines
     }
+    numbered
+}
 
-    /// Write a single line of text at the current y position.
-    fn write_line(&mut self, text: &str, font_size_pt: f32, font: &IndirectFontRef, x_offset_mm: f32) {
-        let layer = self.doc.get_page(self.current_page).get_layer(self.current_layer);
-        layer.use_text(
-            text,
-            font_size_pt,
-            Mm(MARGIN_LEFT + x_offset_mm),
-            Mm(self.y_pos),
-            font,
-        );
+/// styleId -> lower-cased style name ("heading 1", "quote", ...).
+fn docx_style_names(xml: &str) -> std::collections::HashMap<String, String> {
+    let mut names = std::collections::HashMap::new();
+    let mut current = None;
+    for event in ooxml_events(xml) {
+        if event.is_open("w:style") {
+            current = event.attr("w:styleId").map(str::to_string);
+        } else if event.is_open("w:name") {
+            if let (Some(id), Some(name)) = (current.take(), event.attr("w:val")) {
+                names.insert(id, name.to_lowercase());
+            }
+        }
     }
+    names
+}
 
-    /// Write inline nodes as a block, with word-wrapping.
-    fn write_inline_block(
-        &mut self,
-        children: &[InlineNode],
-        font_size_pt: f32,
-        indent_mm: f32,
-        prefix: Option<&str>,
-    ) {
-        let line_height_mm = font_size_pt / PT_PER_MM * 1.4;
-        let max_width = USABLE_WIDTH - indent_mm;
+#[derive(Clone, Copy, PartialEq, Default)]
+struct DocxRunFormat {
+    bold: bool,
+    italic: bool,
+    underline: bool,
+    strike: bool,
+    code: bool,
+}
 
-        // Concatenate all inline text for simple wrapping.
-        // For mixed formatting, we do a simplified approach: concatenate text,
-        // wrap, then for each output line re-render with approximate formatting.
-        // A fully faithful approach would need per-character width measurement,
-        // which is impractical with built-in fonts.
+enum DocxInline {
+    Text(DocxRunFormat, Option<String>, String),
+    Break,
+    Image(String),
+}
 
-        let mut full_text = String::new();
-        if let Some(pfx) = prefix {
-            full_text.push_str(pfx);
-        }
-        for child in children {
-            full_text.push_str(&child.text);
-        }
+#[derive(Clone, Copy, PartialEq)]
+enum DocxParagraphKind {
+    Body,
+    Title,
+    Heading(u8),
+    Quote,
+    Code,
+    ListItem { ordered: bool, level: u8 },
+}
 
-        let is_mono = children.iter().any(|c| c.code);
-        let lines = self.wrap_text(&full_text, font_size_pt, max_width, is_mono);
+/// A table cell: column span and content.
+type DocxCell = (u32, Vec<DocxBlock>);
 
-        for line in &lines {
-            self.ensure_space(line_height_mm);
+enum DocxBlock {
+    Paragraph(DocxParagraphKind, Vec<DocxInline>),
+    /// Rows as (repeated header row, cells)
+    Table(Vec<(bool, Vec<DocxCell>)>),
+}
 
-            // Determine dominant formatting from the first non-empty child
-            let (bold, italic, code) = children
-                .iter()
-                .find(|c| !c.text.is_empty())
-                .map(|c| (c.bold, c.italic, c.code))
-                .unwrap_or((false, false, false));
+struct DocxContext<'a> {
+    rels: DocxRels,
+    numbered: std::collections::HashSet<(String, u8)>,
+    styles: std::collections::HashMap<String, String>,
+    /// Reads a zip entry and stores it as an image, returning its src
+    store_image: &'a mut dyn FnMut(&str) -> Option<String>,
+}
 
-            let font = self.select_font(bold, italic, code).clone();
-            self.write_line(line, font_size_pt, &font, indent_mm);
-            self.y_pos -= line_height_mm;
-        }
-    }
+/// `w:val` of an on/off property; absent means on.
+fn ooxml_toggle(event: &OoxmlEvent) -> bool {
+    !matches!(event.attr("w:val"), Some("0") | Some("false") | Some("none") | Some("off"))
+}
 
-    fn write_spacer(&mut self, mm: f32) {
-        self.y_pos -= mm;
-        if self.y_pos < MARGIN_BOTTOM {
-            self.new_page();
+fn docx_paragraph_kind(ctx: &DocxContext, style: Option<&str>, numbering: Option<(String, u8)>) -> DocxParagraphKind {
+    let name = style
+        .map(|id| ctx.styles.get(id).cloned().unwrap_or_else(|| id.to_lowercase()))
+        .unwrap_or_default();
+    let compact = name.replace(' ', "");
+    if let Some(level) = compact.strip_prefix("heading").and_then(|n| n.parse::<u8>().ok()) {
+        return DocxParagraphKind::Heading(level.clamp(1, 6));
+    }
+    if compact == "title" {
+        return DocxParagraphKind::Title;
+    }
+    if compact == "subtitle" {
+        return DocxParagraphKind::Heading(2);
+    }
+    if compact.contains("quote") {
+        return DocxParagraphKind::Quote;
+    }
+    if compact.contains("code") || compact.contains("preformatted") || compact == "sourcecode" {
+        return DocxParagraphKind::Code;
+    }
+    if let Some((num_id, level)) = numbering {
+        // numId 0 switches numbering off
+        if num_id != "0" {
+            let ordered = ctx.numbered.contains(&(num_id, level));
+            return DocxParagraphKind::ListItem { ordered, level };
         }
     }
+    DocxParagraphKind::Body
+}
 
-    fn finish(self) -> Result<Vec<u8>, String> {
-        let mut buf = BufWriter::new(Vec::new());
-        self.doc
-            .save(&mut buf)
-            .map_err(|e| format!("Failed to save PDF: {}", e))?;
-        buf.into_inner()
-            .map_err(|e| format!("Failed to finalize PDF buffer: {}", e))
+/// Parse from the `w:p` at `events[*i]` through its close.
+fn docx_paragraph(events: &[OoxmlEvent], i: &mut usize, ctx: &mut DocxContext) -> DocxBlock {
+    let mut style = None;
+    let mut num_id = None;
+    let mut level = 0u8;
+    let mut inlines = Vec::new();
+    let mut format = DocxRunFormat::default();
+    let mut link: Option<String> = None;
+    let (mut in_ppr, mut in_rpr, mut in_text) = (false, false, false);
+    let mut depth = 0;
+    while *i < events.len() {
+        let event = &events[*i];
+        *i += 1;
+        match event {
+            OoxmlEvent::Open(name, _) => match name.as_str() {
+                // Text boxes nest paragraphs inside a run; their text is kept
+                "w:p" => depth += 1,
+                "w:pPr" => in_ppr = true,
+                "w:pStyle" if in_ppr && depth == 1 => style = event.attr("w:val").map(str::to_string),
+                "w:numId" if in_ppr => num_id = event.attr("w:val").map(str::to_string),
+                "w:ilvl" if in_ppr => level = event.attr("w:val").and_then(|l| l.parse().ok()).unwrap_or(0),
+                "w:r" => format = DocxRunFormat::default(),
+                "w:rPr" => in_rpr = true,
+                "w:b" if in_rpr && !in_ppr => format.bold = ooxml_toggle(event),
+                "w:i" if in_rpr && !in_ppr => format.italic = ooxml_toggle(event),
+                "w:u" if in_rpr && !in_ppr => format.underline = ooxml_toggle(event),
+                "w:strike" | "w:dstrike" if in_rpr && !in_ppr => format.strike = ooxml_toggle(event),
+                "w:rStyle" if in_rpr => {
+                    let name = event.attr("w:val").map(|id| {
+                        ctx.styles.get(id).cloned().unwrap_or_else(|| id.to_lowercase())
+                    });
+                    if name.is_some_and(|n| n.contains("code") || n.contains("verbatim")) {
+                        format.code = true;
+                    }
+                }
+                "w:rFonts" if in_rpr && !in_ppr => {
+                    let font = event.attr("w:ascii").unwrap_or("").to_lowercase();
+                    if ["courier", "consolas", "menlo", "monaco", "mono"].iter().any(|m| font.contains(m)) {
+                        format.code = true;
+                    }
+                }
+                "w:hyperlink" => {
+                    link = event.attr("r:id").and_then(|id| ctx.rels.links.get(id)).cloned();
+                }
+                "w:t" => in_text = true,
+                "w:tab" if !in_ppr => inlines.push(DocxInline::Text(format, link.clone(), " ".to_string())),
+                // Page and column breaks don't mean anything in the editor
+                "w:br" | "w:cr" if event.attr("w:type").is_none_or(|t| t == "textWrapping") => {
+                    inlines.push(DocxInline::Break);
+                }
+                "a:blip" | "v:imagedata" => {
+                    let id = event.attr("r:embed").or_else(|| event.attr("r:id"));
+                    if let Some(entry) = id.and_then(|id| ctx.rels.images.get(id)).cloned() {
+                        if let Some(src) = (ctx.store_image)(&entry) {
+                            inlines.push(DocxInline::Image(src));
+                        }
+                    }
+                }
+                _ => {}
+            },
+            OoxmlEvent::Close(name) => match name.as_str() {
+                "w:p" => {
+                    depth -= 1;
+                    if depth == 0 {
+                        break;
+                    }
+                }
+                "w:pPr" => in_ppr = false,
+                "w:rPr" => in_rpr = false,
+                "w:hyperlink" => link = None,
+                "w:t" => in_text = false,
+                _ => {}
+            },
+            OoxmlEvent::Text(text) if in_text => inlines.push(DocxInline::Text(format, link.clone(), text.clone())),
+            OoxmlEvent::Text(_) => {}
+        }
     }
+    let numbering = num_id.map(|id| (id, level));
+    DocxBlock::Paragraph(docx_paragraph_kind(ctx, style.as_deref(), numbering), inlines)
 }
 
-fn build_pdf(title: &str, html: &str) -> Result<Vec<u8>, String> {
-    let nodes = parse_html(html);
-    let mut w = PdfWriter::new(title)?;
-
-    // Title
-    let title_font = w.font_bold.clone();
-    let title_lines = w.wrap_text(title, 20.0, USABLE_WIDTH, false);
-    for line in &title_lines {
-        w.ensure_space(20.0 / PT_PER_MM * 1.5);
-        w.write_line(line, 20.0, &title_font, 0.0);
-        w.y_pos -= 20.0 / PT_PER_MM * 1.5;
+/// Parse from the `w:tbl` at `events[*i]` through its close.
+fn docx_table(events: &[OoxmlEvent], i: &mut usize, ctx: &mut DocxContext) -> DocxBlock {
+    *i += 1;
+    let mut rows = Vec::new();
+    let mut header = false;
+    let mut cells = Vec::new();
+    while *i < events.len() {
+        let event = &events[*i];
+        if event.is_close("w:tbl") {
+            *i += 1;
+            break;
+        }
+        if event.is_open("w:tr") {
+            header = false;
+            cells = Vec::new();
+        } else if event.is_open("w:tblHeader") {
+            header = ooxml_toggle(event);
+        } else if event.is_open("w:tc") {
+            let mut span = 1;
+            *i += 1;
+            // w:tcPr comes first; read the span before the cell content
+            while *i < events.len() && !events[*i].is_open("w:p") && !events[*i].is_open("w:tbl") && !events[*i].is_close("w:tc") {
+                if events[*i].is_open("w:gridSpan") {
+                    span = events[*i].attr("w:val").and_then(|v| v.parse().ok()).unwrap_or(1);
+                }
+                *i += 1;
+            }
+            let blocks = docx_blocks(events, i, "w:tc", ctx);
+            cells.push((span.max(1), blocks));
+            continue;
+        } else if event.is_close("w:tr") {
+            rows.push((header, std::mem::take(&mut cells)));
+        }
+        *i += 1;
     }
-    w.write_spacer(6.0);
+    DocxBlock::Table(rows)
+}
 
-    // Horizontal rule under title
-    {
-        let layer = w.doc.get_page(w.current_page).get_layer(w.current_layer);
-        let line = printpdf::Line {
-            points: vec![
-                (printpdf::Point::new(Mm(MARGIN_LEFT), Mm(w.y_pos)), false),
-                (
-                    printpdf::Point::new(Mm(A4_WIDTH_MM - MARGIN_RIGHT), Mm(w.y_pos)),
-                    false,
-                ),
-            ],
-            is_closed: false,
-        };
-        let outline_color = printpdf::Color::Rgb(Rgb::new(0.7, 0.7, 0.7, None));
-        layer.set_outline_color(outline_color);
-        layer.set_outline_thickness(0.5);
-        layer.add_line(line);
+/// Blocks from `events[*i]` up to and including the close of `until`.
+fn docx_blocks(events: &[OoxmlEvent], i: &mut usize, until: &str, ctx: &mut DocxContext) -> Vec<DocxBlock> {
+    let mut blocks = Vec::new();
+    while *i < events.len() {
+        let event = &events[*i];
+        if event.is_close(until) {
+            *i += 1;
+            break;
+        }
+        if event.is_open("w:p") {
+            blocks.push(docx_paragraph(events, i, ctx));
+        } else if event.is_open("w:tbl") {
+            blocks.push(docx_table(events, i, ctx));
+        } else {
+            *i += 1;
+        }
     }
-    w.write_spacer(6.0);
+    blocks
+}
 
-    for node in &nodes {
-        match node {
-            HtmlNode::Heading { level, children } => {
-                let font_size = match level {
-                    1 => 18.0_f32,
-                    2 => 15.0,
-                    3 => 13.0,
-                    _ => 12.0,
-                };
-                w.write_spacer(3.0);
-                // Force bold for headings
-                let modified: Vec<InlineNode> = children
-                    .iter()
-                    .map(|c| InlineNode {
-                        bold: true,
-                        ..c.clone()
-                    })
-                    .collect();
-                w.write_inline_block(&modified, font_size, 0.0, None);
-                w.write_spacer(2.0);
+fn docx_inline_html(inlines: &[DocxInline]) -> String {
+    let mut html = String::new();
+    // Word splits runs freely; merge neighbours with the same formatting
+    let mut merged: Vec<(DocxRunFormat, Option<String>, String)> = Vec::new();
+    let flush = |html: &mut String, merged: &mut Vec<(DocxRunFormat, Option<String>, String)>| {
+        for (format, link, text) in merged.drain(..) {
+            let mut piece = html_escape_text(&text);
+            for (on, tag) in [
+                (format.code, "code"),
+                (format.strike, "s"),
+                (format.underline, "u"),
+                (format.italic, "em"),
+                (format.bold, "strong"),
+            ] {
+                if on {
+                    piece = format!("<{0}>{1}</{0}>", tag, piece);
+                }
             }
-            HtmlNode::Paragraph { children } => {
-                w.write_inline_block(children, 11.0, 0.0, None);
-                w.write_spacer(3.0);
+            if let Some(href) = link {
+                piece = format!("<a href=\"{}\">{}</a>", html_escape_attr(&href), piece);
             }
-            HtmlNode::UnorderedList { items } => {
-                for item_children in items {
-                    w.write_inline_block(item_children, 11.0, 8.0, Some("\u{2022}  "));
-                    w.write_spacer(1.5);
-                }
-                w.write_spacer(2.0);
+            html.push_str(&piece);
+        }
+    };
+    for inline in inlines {
+        match inline {
+            DocxInline::Text(format, link, text) => match merged.last_mut() {
+                Some((f, l, t)) if f == format && l == link => t.push_str(text),
+                _ => merged.push((*format, link.clone(), text.clone())),
+            },
+            DocxInline::Break => {
+                flush(&mut html, &mut merged);
+                html.push_str("<br>");
             }
-            HtmlNode::OrderedList { items } => {
-                for (i, item_children) in items.iter().enumerate() {
-                    let prefix = format!("{}. ", i + 1);
-                    w.write_inline_block(item_children, 11.0, 8.0, Some(&prefix));
-                    w.write_spacer(1.5);
-                }
-                w.write_spacer(2.0);
+            DocxInline::Image(src) => {
+                flush(&mut html, &mut merged);
+                html.push_str(&format!("<img src=\"{}\">", html_escape_attr(src)));
             }
-            HtmlNode::Blockquote { children } => {
+        }
+    }
+    flush(&mut html, &mut merged);
+    html
+}
+
+fn docx_plain_text(inlines: &[DocxInline]) -> String {
+    inlines
+        .iter()
+        .filter_map(|inline| match inline {
+            DocxInline::Text(_, _, text) => Some(text.as_str()),
+            _ => None,
+        })
+        .collect()
+}
+
+fn docx_is_empty(inlines: &[DocxInline]) -> bool {
+    inlines.iter().all(|inline| match inline {
+        DocxInline::Text(_, _, text) => text.trim().is_empty(),
+        DocxInline::Break => true,
+        DocxInline::Image(_) => false,
+    })
+}
+
+/// Editor HTML for a run of blocks. Consecutive list items become nested
+/// lists, quotes one blockquote and code paragraphs one code block.
+fn docx_blocks_html(blocks: &[DocxBlock]) -> String {
+    let mut html = String::new();
+    // Open lists as (ordered) from the outermost in
+    let mut lists: Vec<bool> = Vec::new();
+    let close_lists = |html: &mut String, lists: &mut Vec<bool>, depth: usize| {
+        while lists.len() > depth {
+            let ordered = lists.pop().unwrap_or(false);
+            html.push_str(if ordered { "</li></ol>" } else { "</li></ul>" });
+        }
+    };
+    let mut i = 0;
+    while i < blocks.len() {
+        match &blocks[i] {
+            DocxBlock::Paragraph(DocxParagraphKind::ListItem { ordered, level }, inlines) => {
+                let depth = (*level as usize + 1).min(lists.len() + 1);
+                close_lists(&mut html, &mut lists, depth);
+                if lists.len() == depth {
+                    if lists[depth - 1] == *ordered {
+                        html.push_str("</li>");
+                    } else {
+                        close_lists(&mut html, &mut lists, depth - 1);
+                    }
+                }
+                if lists.len() < depth {
+                    html.push_str(if *ordered { "<ol>" } else { "<ul>" });
+                    lists.push(*ordered);
+                }
+                html.push_str(&format!("<li><p>{}</p>", docx_inline_html(inlines)));
+                i += 1;
+                continue;
+            }
+            _ => close_lists(&mut html, &mut lists, 0),
+        }
+        match &blocks[i] {
+            DocxBlock::Paragraph(DocxParagraphKind::Code, _) => {
+                let mut lines = Vec::new();
+                while let Some(DocxBlock::Paragraph(DocxParagraphKind::Code, inlines)) = blocks.get(i) {
+                    lines.push(html_escape_text(&docx_plain_text(inlines)));
+                    i += 1;
+                }
+                html.push_str(&format!("<pre><code>{}</code></pre>", lines.join("\n")));
+                continue;
+            }
+            DocxBlock::Paragraph(DocxParagraphKind::Quote, _) => {
+                html.push_str("<blockquote>");
+                while let Some(DocxBlock::Paragraph(DocxParagraphKind::Quote, inlines)) = blocks.get(i) {
+                    if !docx_is_empty(inlines) {
+                        html.push_str(&format!("<p>{}</p>", docx_inline_html(inlines)));
+                    }
+                    i += 1;
+                }
+                html.push_str("</blockquote>");
+                continue;
+            }
+            DocxBlock::Paragraph(kind, inlines) => {
+                if !docx_is_empty(inlines) {
+                    let content = docx_inline_html(inlines);
+                    match kind {
+                        DocxParagraphKind::Title => html.push_str(&format!("<h1>{}</h1>", content)),
+                        DocxParagraphKind::Heading(level) => html.push_str(&format!("<h{0}>{1}</h{0}>", level, content)),
+                        _ => html.push_str(&format!("<p>{}</p>", content)),
+                    }
+                }
+            }
+            DocxBlock::Table(rows) => {
+                html.push_str("<table><tbody>");
+                for (header, cells) in rows {
+                    html.push_str("<tr>");
+                    let tag = if *header { "th" } else { "td" };
+                    for (span, blocks) in cells {
+                        let colspan = if *span > 1 { format!(" colspan=\"{}\"", span) } else { String::new() };
+                        let content = docx_blocks_html(blocks);
+                        let content = if content.is_empty() { "<p></p>".to_string() } else { content };
+                        html.push_str(&format!("<{0}{1}>{2}</{0}>", tag, colspan, content));
+                    }
+                    html.push_str("</tr>");
+                }
+                html.push_str("</tbody></table>");
+            }
+        }
+        i += 1;
+    }
+    close_lists(&mut html, &mut lists, 0);
+    html
+}
+
+/// The URL the webview loads a local file from, as `convertFileSrc`
+/// builds it on the frontend.
+fn asset_url(path: &Path) -> String {
+    let encoded: String = path
+        .to_string_lossy()
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'!' | b'~' | b'*' | b'\'' | b'(' | b')' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{:02X}", b),
+        })
+        .collect();
+    if cfg!(any(windows, target_os = "android")) {
+        format!("http://asset.localhost/{}", encoded)
+    } else {
+        format!("asset://localhost/{}", encoded)
+    }
+}
+
+/// Title and editor HTML of a .docx. Embedded images are copied into
+/// `images_dir`. The title comes from the document properties, then the
+/// first Title-styled paragraph (dropped from the body), then `fallback`.
+pub(crate) fn parse_docx(bytes: &[u8], images_dir: Option<&Path>, fallback: &str) -> Result<(String, String), AppError> {
+    use std::io::Read;
+
+    let mut zip = zip::ZipArchive::new(std::io::Cursor::new(bytes))
+        .map_err(|_| AppError::validation("Not a Word document (.docx)"))?;
+    let mut read_text = |name: &str| -> Option<String> {
+        let mut entry = zip.by_name(name).ok()?;
+        let mut text = String::new();
+        entry.read_to_string(&mut text).ok()?;
+        Some(text)
+    };
+    let document = read_text("word/document.xml")
+        .ok_or_else(|| AppError::validation("The file has no word/document.xml"))?;
+    let rels = docx_rels(&read_text("word/_rels/document.xml.rels").unwrap_or_default());
+    let numbered = docx_numbered_levels(&read_text("word/numbering.xml").unwrap_or_default());
+    let styles = docx_style_names(&read_text("word/styles.xml").unwrap_or_default());
+    let core_title = read_text("docProps/core.xml").and_then(|core| {
+        let events = ooxml_events(&core);
+        let start = events.iter().position(|e| e.is_open("dc:title"))?;
+        match events.get(start + 1) {
+            Some(OoxmlEvent::Text(title)) if !title.trim().is_empty() => Some(title.trim().to_string()),
+            _ => None,
+        }
+    });
+
+    let mut store_image = |entry: &str| -> Option<String> {
+        let dir = images_dir?;
+        let mut file = zip.by_name(entry).ok()?;
+        let mut data = Vec::new();
+        file.read_to_end(&mut data).ok()?;
+        let ext = Path::new(entry).extension()?.to_str()?.to_lowercase();
+        if !["png", "jpg", "jpeg", "gif", "bmp", "webp", "svg"].contains(&ext.as_str()) {
+            return None;
+        }
+        let path = dir.join(format!("{}.{}", uuid::Uuid::new_v4(), ext));
+        std::fs::write(&path, data).ok()?;
+        Some(asset_url(&path))
+    };
+    let mut ctx = DocxContext { rels, numbered, styles, store_image: &mut store_image };
+
+    let events = ooxml_events(&document);
+    let mut i = events
+        .iter()
+        .position(|e| e.is_open("w:body"))
+        .ok_or_else(|| AppError::validation("The document has no body"))?
+        + 1;
+    let mut blocks = docx_blocks(&events, &mut i, "w:body", &mut ctx);
+
+    let title = match core_title {
+        Some(title) => title,
+        None => {
+            let first_title = blocks.iter().position(|b| {
+                matches!(b, DocxBlock::Paragraph(DocxParagraphKind::Title, inlines) if !docx_is_empty(inlines))
+            });
+            match first_title.map(|index| blocks.remove(index)) {
+                Some(DocxBlock::Paragraph(_, inlines)) => docx_plain_text(&inlines).trim().to_string(),
+                _ => fallback.to_string(),
+            }
+        }
+    };
+    Ok((title, docx_blocks_html(&blocks)))
+}
+
+/// Create a draft from a Word document, optionally inside a project.
+#[tauri::command]
+pub async fn import_docx(
+    app: tauri::AppHandle,
+    file_path: String,
+    project_id: Option<String>,
+) -> Result<DocumentMeta, AppError> {
+    let path = PathBuf::from(&file_path);
+    let bytes = std::fs::read(&path).map_err(|e| AppError::not_found(format!("Cannot read {}: {}", file_path, e)))?;
+    let fallback = path.file_stem().and_then(|s| s.to_str()).unwrap_or("Imported document").to_string();
+    let images_dir = images::images_dir(&app).ok();
+    let (title, html) = tokio::task::spawn_blocking(move || parse_docx(&bytes, images_dir.as_deref(), &fallback))
+        .await
+        .map_err(|e| format!("Import task failed: {}", e))??;
+
+    create_imported_document(&app, &title, &html, project_id, &file_path)
+}
+
+/// Save imported HTML as a new draft; `source` (a path or URL) goes in the
+/// activity log.
+pub(crate) fn create_imported_document(
+    app: &tauri::AppHandle,
+    title: &str,
+    html: &str,
+    project_id: Option<String>,
+    source: &str,
+) -> Result<DocumentMeta, AppError> {
+    let id = uuid::Uuid::new_v4().to_string();
+    let html = &sanitize_html(html, None);
+    let content = html_to_plain_text(html);
+    let word_count = text::count_html(html, text::count_mode(app)) as i64;
+    let now = Utc::now().to_rfc3339();
+    let conn = db::get_db(app)?;
+    if let Some(project_id) = &project_id {
+        load_project(&conn, project_id)?;
+    }
+    conn.execute(
+        "INSERT INTO documents (id, title, content, html_content, project_id, status, word_count, character_count, version, workspace_id, created_at, updated_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, 'draft', ?6, 0, 1, ?8, ?7, ?7)",
+        rusqlite::params![id, title, content, html, project_id, word_count, now, workspaces::current(app)],
+    )?;
+    links::index_document(&conn, &id, html);
+    db::log_activity(&conn, "document.imported", "document", Some(&id), Some(source));
+    windows::document_changed(app, &id, "saved", Some(1), None);
+    Ok(DocumentMeta {
+        id,
+        title: title.to_string(),
+        created_at: now.clone(),
+        updated_at: now,
+        word_count,
+        project_id,
+        status: "draft".to_string(),
+        character_count: 0,
+        archived_at: None,
+        target_words: None,
+        goal_progress: None,
+        pinned_at: None,
+        sort_index: None,
+    })
+}
+
+// ---------------------------------------------------------------------------
+// PDF export
+// ---------------------------------------------------------------------------
+
+/// Page sizes (width, height) in mm
+const A4_MM: (f32, f32) = (210.0, 297.0);
+const LETTER_MM: (f32, f32) = (215.9, 279.4);
+const LEGAL_MM: (f32, f32) = (215.9, 355.6);
+/// Smallest and largest sides a custom page may have, in mm
+const PDF_PAGE_SIDE_MM: (f32, f32) = (75.0, 1000.0);
+
+/// Margins in mm, on every side by default
+const DEFAULT_MARGIN_MM: f32 = 25.0;
+/// Narrowest and shortest text area the margins may leave, in mm
+const PDF_MIN_TEXT_AREA_MM: f32 = 40.0;
+
+const MM_PER_INCH: f32 = 25.4;
+
+/// Points per mm (1pt = 0.3528mm, so 1mm ≈ 2.8346pt)
+const PT_PER_MM: f32 = 2.8346;
+/// Side of a task list checkbox, about the cap height of 11pt text
+const PDF_CHECKBOX_MM: f32 = 2.8;
+
+/// Advance widths from an embedded TrueType/OpenType font, for measuring
+/// text set in it.
+struct GlyphWidths {
+    data: Vec<u8>,
+}
+
+impl GlyphWidths {
+    fn parse(data: Vec<u8>) -> Option<Self> {
+        ttf_parser::Face::parse(&data, 0).ok()?;
+        Some(GlyphWidths { data })
+    }
+
+    /// Width of `text` in em; characters without a glyph count as half an em.
+    fn width_em(&self, text: &str) -> f32 {
+        let Ok(face) = ttf_parser::Face::parse(&self.data, 0) else {
+            return text.chars().count() as f32 * 0.5;
+        };
+        let units = face.units_per_em().max(1) as f32;
+        text.chars()
+            .map(|c| {
+                face.glyph_index(c)
+                    .and_then(|g| face.glyph_hor_advance(g))
+                    .map(|advance| advance as f32 / units)
+                    .unwrap_or(0.5)
+            })
+            .sum()
+    }
+}
+
+/// Advance widths of the built-in Helvetica and Helvetica-Bold for
+/// U+0020..=U+007E, in 1/1000 em, from the Adobe core font AFM files. The
+/// oblique faces share these widths.
+#[rustfmt::skip]
+const HELVETICA_WIDTHS: [u16; 95] = [
+    278, 278, 355, 556, 556, 889, 667, 191, 333, 333, 389, 584, 278, 333, 278, 278,
+    556, 556, 556, 556, 556, 556, 556, 556, 556, 556, 278, 278, 584, 584, 584, 556,
+    1015, 667, 667, 722, 722, 667, 611, 778, 722, 278, 500, 667, 556, 833, 722, 778,
+    667, 778, 722, 667, 611, 722, 667, 944, 667, 667, 611, 278, 278, 278, 469, 556,
+    333, 556, 556, 500, 556, 556, 278, 556, 556, 222, 222, 500, 222, 833, 556, 556,
+    556, 556, 333, 500, 278, 556, 500, 722, 500, 500, 500, 334, 260, 334, 584,
+];
+
+#[rustfmt::skip]
+const HELVETICA_BOLD_WIDTHS: [u16; 95] = [
+    278, 333, 474, 556, 556, 889, 722, 238, 333, 333, 389, 584, 278, 333, 278, 278,
+    556, 556, 556, 556, 556, 556, 556, 556, 556, 556, 333, 333, 584, 584, 584, 611,
+    975, 722, 722, 722, 722, 667, 611, 778, 722, 278, 556, 722, 611, 833, 722, 778,
+    667, 778, 722, 667, 611, 722, 667, 944, 667, 667, 611, 333, 278, 333, 584, 556,
+    333, 556, 611, 556, 611, 556, 333, 611, 611, 278, 278, 556, 278, 889, 611, 611,
+    611, 611, 389, 556, 333, 611, 556, 778, 556, 556, 500, 389, 280, 389, 584,
+];
+
+/// Every Courier glyph is 600/1000 em wide.
+const COURIER_WIDTH: u32 = 600;
+
+/// Helvetica advance width of `c` in 1/1000 em. Accented Latin letters
+/// take the width of their base letter; anything else the AFM lists is
+/// matched by hand, and the rest gets the width of "n".
+fn helvetica_width(c: char, bold: bool) -> u32 {
+    let table = if bold { &HELVETICA_BOLD_WIDTHS } else { &HELVETICA_WIDTHS };
+    let ascii = |c: char| table[c as usize - 0x20] as u32;
+    let fallback = ascii('n');
+    match c {
+        ' '..='~' => ascii(c),
+        '\u{a0}' => ascii(' '),
+        '\u{2018}' | '\u{2019}' => if bold { 278 } else { 222 },
+        '\u{201c}' | '\u{201d}' => if bold { 500 } else { 333 },
+        '\u{2013}' | '\u{20ac}' | '\u{a3}' => 556,
+        '\u{2014}' | '\u{2026}' => 1000,
+        '\u{2022}' => 350,
+        '\u{a9}' | '\u{ae}' => 737,
+        '\u{b0}' => 400,
+        '\u{df}' => 611,
+        // Accented i is built on the dotless i, which is wider than "i"
+        'ì'..='ï' => 278,
+        _ => latin_base(c).map(ascii).unwrap_or(fallback),
+    }
+}
+
+/// The unaccented letter behind a Latin-1 accented letter.
+fn latin_base(c: char) -> Option<char> {
+    Some(match c {
+        'À'..='Å' => 'A',
+        'Ç' => 'C',
+        'È'..='Ë' => 'E',
+        'Ì'..='Ï' => 'I',
+        'Ñ' => 'N',
+        'Ò'..='Ö' | 'Ø' => 'O',
+        'Ù'..='Ü' => 'U',
+        'Ý' => 'Y',
+        'à'..='å' => 'a',
+        'ç' => 'c',
+        'è'..='ë' => 'e',
+        'ñ' => 'n',
+        'ò'..='ö' | 'ø' => 'o',
+        'ù'..='ü' => 'u',
+        'ý' | 'ÿ' => 'y',
+        _ => return None,
+    })
+}
+
+/// Font files for body text in PDF export. Missing styles fall back to
+/// `bold` or `italic`, then `regular`.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct PdfFonts {
+    pub regular: Vec<u8>,
+    pub bold: Option<Vec<u8>>,
+    pub italic: Option<Vec<u8>>,
+    pub bold_italic: Option<Vec<u8>>,
+}
+
+/// Running header/footer for `export_pdf`, drawn in the top and bottom
+/// margins of every page. `page_numbers` adds "Page X of Y" at the bottom
+/// right.
+///
+/// `cover` puts the title on a page of its own with the subtitle, author,
+/// date (today's by default) and logo; the cover has no header, footer or
+/// number. Author and keywords are also set in the document's metadata.
+///
+/// `toc` adds a contents page after the title (or cover) listing the
+/// H1–H3 headings with their page numbers, each linking to its heading,
+/// and puts the same headings in the PDF's outline as bookmarks.
+///
+/// Pages are A4 with 25mm margins unless `page_size` or `page_width_mm`
+/// and `page_height_mm` (together, for a custom size) and `margins` say
+/// otherwise. The header and footer sit in the middle of the top and
+/// bottom margins.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct PdfExportOptions {
+    pub header_text: Option<String>,
+    pub footer_text: Option<String>,
+    pub page_numbers: bool,
+    /// Colour code blocks by their language
+    pub highlight_code: bool,
+    pub cover: bool,
+    pub author: Option<String>,
+    pub subtitle: Option<String>,
+    /// Shown as given, e.g. "March 2026"
+    pub date: Option<String>,
+    /// An image file, or the name of one in the image library
+    pub logo_path: Option<String>,
+    pub keywords: Vec<String>,
+    pub toc: bool,
+    /// "a4", "letter" or "legal"
+    pub page_size: Option<String>,
+    pub page_width_mm: Option<f32>,
+    pub page_height_mm: Option<f32>,
+    pub margins: Option<PdfMargins>,
+}
+
+/// Page margins in mm.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+#[serde(default)]
+pub struct PdfMargins {
+    pub top: f32,
+    pub right: f32,
+    pub bottom: f32,
+    pub left: f32,
+}
+
+impl Default for PdfMargins {
+    fn default() -> Self {
+        PdfMargins {
+            top: DEFAULT_MARGIN_MM,
+            right: DEFAULT_MARGIN_MM,
+            bottom: DEFAULT_MARGIN_MM,
+            left: DEFAULT_MARGIN_MM,
+        }
+    }
+}
+
+/// A PDF export's page size and margins, in mm.
+#[derive(Debug, Clone, Copy)]
+struct PdfPage {
+    width: f32,
+    height: f32,
+    margins: PdfMargins,
+}
+
+impl PdfPage {
+    /// Width of the text column
+    fn text_width(&self) -> f32 {
+        self.width - self.margins.left - self.margins.right
+    }
+
+    fn text_height(&self) -> f32 {
+        self.height - self.margins.top - self.margins.bottom
+    }
+
+    /// y of the top margin and x of the right one
+    fn top(&self) -> f32 {
+        self.height - self.margins.top
+    }
+
+    fn right(&self) -> f32 {
+        self.width - self.margins.right
+    }
+}
+
+impl PdfExportOptions {
+    /// The page these options ask for, or why it can't be laid out.
+    fn page(&self) -> Result<PdfPage, AppError> {
+        let (width, height) = match (self.page_width_mm, self.page_height_mm) {
+            (Some(width), Some(height)) => {
+                let (min, max) = PDF_PAGE_SIDE_MM;
+                if !(min..=max).contains(&width) || !(min..=max).contains(&height) {
+                    return Err(AppError::validation(format!(
+                        "Custom page sizes must be between {} and {}mm on each side",
+                        min, max
+                    )));
+                }
+                (width, height)
+            }
+            (Some(_), None) | (None, Some(_)) => {
+                return Err(AppError::validation("A custom page size needs both a width and a height"));
+            }
+            (None, None) => match self.page_size.as_deref().map(str::to_ascii_lowercase).as_deref() {
+                None | Some("a4") => A4_MM,
+                Some("letter") => LETTER_MM,
+                Some("legal") => LEGAL_MM,
+                Some(_) => {
+                    return Err(AppError::validation(format!(
+                        "Unknown page size '{}'",
+                        self.page_size.as_deref().unwrap_or_default()
+                    )));
+                }
+            },
+        };
+        let page = PdfPage { width, height, margins: self.margins.unwrap_or_default() };
+        let PdfMargins { top, right, bottom, left } = page.margins;
+        if [top, right, bottom, left].iter().any(|m| !m.is_finite() || *m < 0.0) {
+            return Err(AppError::validation("Margins can't be negative"));
+        }
+        if page.text_width() < PDF_MIN_TEXT_AREA_MM || page.text_height() < PDF_MIN_TEXT_AREA_MM {
+            return Err(AppError::validation("The margins leave too little of the page for text"));
+        }
+        Ok(page)
+    }
+
+    fn author(&self) -> Option<&str> {
+        self.author.as_deref().map(str::trim).filter(|a| !a.is_empty())
+    }
+}
+
+/// Running header/footer text size, in pt
+const PDF_MARGIN_TEXT_PT: f32 = 8.5;
+/// Cover page title, subtitle and byline sizes, in pt
+const PDF_COVER_TITLE_PT: f32 = 30.0;
+const PDF_COVER_SUBTITLE_PT: f32 = 16.0;
+const PDF_COVER_BYLINE_PT: f32 = 12.0;
+/// Largest a cover logo is drawn, in mm
+const PDF_COVER_LOGO_MM: (f32, f32) = (60.0, 40.0);
+/// Contents page heading and entry sizes, in pt
+const PDF_CONTENTS_TITLE_PT: f32 = 16.0;
+const PDF_CONTENTS_ENTRY_PT: f32 = 11.0;
+
+/// Font choice for `export_pdf`: explicit TTF/OTF paths, or a family name
+/// looked up in the app's fonts folder and the system font directories.
+/// Paths win over the family for the styles they set.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct PdfFontConfig {
+    pub family: Option<String>,
+    pub regular_path: Option<String>,
+    pub bold_path: Option<String>,
+    pub italic_path: Option<String>,
+    pub bold_italic_path: Option<String>,
+}
+
+fn normalize_font_name(name: &str) -> String {
+    name.chars().filter(|c| c.is_ascii_alphanumeric()).collect::<String>().to_ascii_lowercase()
+}
+
+/// Style index (regular, bold, italic, bold italic) from what follows the
+/// family in a file name: "Inter-BoldItalic.ttf", "georgiab.ttf", ...
+fn font_style_suffix(rest: &str) -> Option<usize> {
+    match rest {
+        "" | "regular" | "book" | "roman" | "r" => Some(0),
+        "bold" | "b" => Some(1),
+        "italic" | "oblique" | "it" | "i" => Some(2),
+        "bolditalic" | "boldoblique" | "bi" | "z" => Some(3),
+        _ => None,
+    }
+}
+
+fn font_dirs(app: &tauri::AppHandle) -> Vec<PathBuf> {
+    use tauri::Manager;
+    let mut dirs = Vec::new();
+    if let Ok(data) = app.path().app_data_dir() {
+        dirs.push(data.join("fonts"));
+    }
+    if let Ok(home) = app.path().home_dir() {
+        dirs.extend([home.join(".local/share/fonts"), home.join(".fonts"), home.join("Library/Fonts")]);
+    }
+    dirs.extend(["/usr/share/fonts", "/usr/local/share/fonts", "/Library/Fonts", "/System/Library/Fonts"].map(PathBuf::from));
+    if let Ok(windir) = std::env::var("WINDIR") {
+        dirs.push(PathBuf::from(windir).join("Fonts"));
+    }
+    if let Ok(local) = std::env::var("LOCALAPPDATA") {
+        dirs.push(PathBuf::from(local).join("Microsoft/Windows/Fonts"));
+    }
+    dirs
+}
+
+/// Files of `family` by style, searching `dirs` a few levels deep.
+fn find_font_family(dirs: &[PathBuf], family: &str) -> [Option<PathBuf>; 4] {
+    let wanted = normalize_font_name(family);
+    let mut found: [Option<PathBuf>; 4] = Default::default();
+    let mut stack: Vec<(PathBuf, usize)> = dirs.iter().map(|d| (d.clone(), 0)).collect();
+    while let Some((dir, depth)) = stack.pop() {
+        let Ok(entries) = std::fs::read_dir(&dir) else { continue };
+        for path in entries.filter_map(|e| e.ok()).map(|e| e.path()) {
+            if path.is_dir() {
+                if depth < 4 {
+                    stack.push((path, depth + 1));
+                }
+                continue;
+            }
+            let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("").to_ascii_lowercase();
+            if ext != "ttf" && ext != "otf" {
+                continue;
+            }
+            let stem = normalize_font_name(path.file_stem().and_then(|s| s.to_str()).unwrap_or(""));
+            let Some(style) = stem.strip_prefix(&wanted).and_then(font_style_suffix) else {
+                continue;
+            };
+            found[style].get_or_insert(path);
+        }
+    }
+    found
+}
+
+/// Load the fonts `config` names. `None` when it names none, so the
+/// built-in fonts are used.
+pub(crate) fn resolve_pdf_fonts(app: &tauri::AppHandle, config: &PdfFontConfig) -> Result<Option<PdfFonts>, AppError> {
+    let mut paths: [Option<PathBuf>; 4] = match config.family.as_deref().map(str::trim) {
+        Some(family) if !family.is_empty() => {
+            let found = find_font_family(&font_dirs(app), family);
+            if found.iter().all(Option::is_none) {
+                return Err(AppError::not_found(format!("Font family '{}' not found", family)));
+            }
+            found
+        }
+        _ => Default::default(),
+    };
+    for (slot, path) in paths.iter_mut().zip([
+        &config.regular_path,
+        &config.bold_path,
+        &config.italic_path,
+        &config.bold_italic_path,
+    ]) {
+        if let Some(path) = path.as_deref().filter(|p| !p.trim().is_empty()) {
+            *slot = Some(PathBuf::from(path));
+        }
+    }
+    if paths.iter().all(Option::is_none) {
+        return Ok(None);
+    }
+
+    let read = |path: &Option<PathBuf>| -> Result<Option<Vec<u8>>, AppError> {
+        let Some(path) = path else { return Ok(None) };
+        let data = std::fs::read(path)
+            .map_err(|e| AppError::not_found(format!("Can't read font {}: {}", path.display(), e)))?;
+        if ttf_parser::Face::parse(&data, 0).is_err() {
+            return Err(AppError::validation(format!("{} is not a TrueType or OpenType font", path.display())));
+        }
+        Ok(Some(data))
+    };
+    let [regular, bold, italic, bold_italic] = &paths;
+    let (bold, italic, bold_italic) = (read(bold)?, read(italic)?, read(bold_italic)?);
+    // A family without a plain face still needs a regular to fall back to
+    let regular = match read(regular)? {
+        Some(data) => data,
+        None => bold.clone().or_else(|| italic.clone()).or_else(|| bold_italic.clone()).unwrap_or_default(),
+    };
+    Ok(Some(PdfFonts { regular, bold, italic, bold_italic }))
+}
+
+struct PdfWriter {
+    doc: PdfDocumentReference,
+    current_page: PdfPageIndex,
+    current_layer: PdfLayerIndex,
+    y_pos: f32,         // current y position in mm from bottom
+    font_regular: IndirectFontRef,
+    font_bold: IndirectFontRef,
+    font_italic: IndirectFontRef,
+    font_bold_italic: IndirectFontRef,
+    font_mono: IndirectFontRef,
+    /// Widths for regular, bold, italic and bold italic when custom fonts
+    /// are embedded; built-in fonts are measured from their AFM widths
+    widths: Option<[std::rc::Rc<GlyphWidths>; 4]>,
+    page_count: usize,
+    /// Every page's (page, layer), for drawing headers and footers once the
+    /// page count is known
+    pages: Vec<(PdfPageIndex, PdfLayerIndex)>,
+    page: PdfPage,
+    options: PdfExportOptions,
+    /// What was drawn on page 1, kept for `preview_export`
+    first_page: Vec<PageMark>,
+    /// The H1–H3 headings in the body, in order
+    headings: Vec<PdfHeading>,
+    /// Where each entry of the contents page was drawn, in `headings` order
+    contents_links: Vec<PdfLink>,
+}
+
+/// A heading for the contents page and outline. `page` indexes
+/// `PdfWriter::pages`, and `top` is the y of the top of its first line in
+/// mm; neither is known until the heading has been laid out.
+#[derive(Clone)]
+struct PdfHeading {
+    level: u8,
+    text: String,
+    page: Option<usize>,
+    top: f32,
+}
+
+/// A contents entry's area on page `page` (indexing `PdfWriter::pages`),
+/// in mm from the bottom left.
+struct PdfLink {
+    page: usize,
+    lower_left: (f32, f32),
+    upper_right: (f32, f32),
+}
+
+/// A drawing operation on the first page, in mm from the bottom left.
+enum PageMark {
+    /// `face` indexes regular, bold, italic, bold italic
+    Text { x: f32, y: f32, text: String, size_pt: f32, face: usize, mono: bool, gray: f32 },
+    Rule { from: (f32, f32), to: (f32, f32), thickness_pt: f32, gray: f32 },
+    Fill { lower_left: (f32, f32), upper_right: (f32, f32), gray: f32 },
+    Image { x: f32, y: f32, w: f32, h: f32, image: ::image::DynamicImage },
+}
+
+/// A table cell wrapped to its column, and its (bold, italic, code) formatting
+type TableCellLines = (Vec<String>, (bool, bool, bool));
+
+impl PdfWriter {
+    fn new(title: &str, fonts: Option<&PdfFonts>, options: PdfExportOptions) -> Result<Self, AppError> {
+        let page = options.page()?;
+        let (doc, page_idx, layer_idx) = PdfDocument::new(
+            title,
+            Mm(page.width),
+            Mm(page.height),
+            "Layer 1",
+        );
+        let doc = match options.author() {
+            Some(author) => doc.with_author(author),
+            None => doc,
+        };
+        let keywords: Vec<&str> = options.keywords.iter().map(|k| k.trim()).filter(|k| !k.is_empty()).collect();
+        let doc = doc.with_keywords(keywords);
+
+        let font_regular = doc
+            .add_builtin_font(BuiltinFont::Helvetica)
+            .map_err(|e| format!("Failed to add Helvetica font: {}", e))?;
+        let font_bold = doc
+            .add_builtin_font(BuiltinFont::HelveticaBold)
+            .map_err(|e| format!("Failed to add Helvetica-Bold font: {}", e))?;
+        let font_italic = doc
+            .add_builtin_font(BuiltinFont::HelveticaOblique)
+            .map_err(|e| format!("Failed to add Helvetica-Oblique font: {}", e))?;
+        let font_bold_italic = doc
+            .add_builtin_font(BuiltinFont::HelveticaBoldOblique)
+            .map_err(|e| format!("Failed to add Helvetica-BoldOblique font: {}", e))?;
+        let font_mono = doc
+            .add_builtin_font(BuiltinFont::Courier)
+            .map_err(|e| format!("Failed to add Courier font: {}", e))?;
+
+        let (font_regular, font_bold, font_italic, font_bold_italic, widths) = match fonts {
+            None => (font_regular, font_bold, font_italic, font_bold_italic, None),
+            Some(fonts) => {
+                let embed = |data: &Vec<u8>| -> Result<(IndirectFontRef, std::rc::Rc<GlyphWidths>), AppError> {
+                    let widths =
+                        GlyphWidths::parse(data.clone()).ok_or_else(|| AppError::validation("Unreadable font file"))?;
+                    let font = doc
+                        .add_external_font(std::io::Cursor::new(data))
+                        .map_err(|e| format!("Failed to embed font: {}", e))?;
+                    Ok((font, std::rc::Rc::new(widths)))
+                };
+                let regular = embed(&fonts.regular)?;
+                let bold = fonts.bold.as_ref().map(embed).transpose()?.unwrap_or_else(|| regular.clone());
+                let italic = fonts.italic.as_ref().map(embed).transpose()?.unwrap_or_else(|| regular.clone());
+                let bold_italic = match &fonts.bold_italic {
+                    Some(data) => embed(data)?,
+                    None if fonts.bold.is_some() => bold.clone(),
+                    None => italic.clone(),
+                };
+                (
+                    regular.0,
+                    bold.0,
+                    italic.0,
+                    bold_italic.0,
+                    Some([regular.1, bold.1, italic.1, bold_italic.1]),
+                )
+            }
+        };
+
+        Ok(PdfWriter {
+            doc,
+            current_page: page_idx,
+            current_layer: layer_idx,
+            y_pos: page.top(),
+            font_regular,
+            font_bold,
+            font_italic,
+            font_bold_italic,
+            font_mono,
+            widths,
+            page_count: 1,
+            pages: vec![(page_idx, layer_idx)],
+            page,
+            options,
+            first_page: Vec::new(),
+            headings: Vec::new(),
+            contents_links: Vec::new(),
+        })
+    }
+
+    fn new_page(&mut self) {
+        let (page_idx, layer_idx) = self.doc.add_page(
+            Mm(self.page.width),
+            Mm(self.page.height),
+            &format!("Layer {}", self.page_count + 1),
+        );
+        self.current_page = page_idx;
+        self.current_layer = layer_idx;
+        self.y_pos = self.page.top();
+        self.page_count += 1;
+        self.pages.push((page_idx, layer_idx));
+    }
+
+    fn ensure_space(&mut self, needed_mm: f32) {
+        if self.y_pos - needed_mm < self.page.margins.bottom {
+            self.new_page();
+        }
+    }
+
+    fn select_font(&self, bold: bool, italic: bool, code: bool) -> &IndirectFontRef {
+        if code {
+            return &self.font_mono;
+        }
+        match (bold, italic) {
+            (true, true) => &self.font_bold_italic,
+            (true, false) => &self.font_bold,
+            (false, true) => &self.font_italic,
+            (false, false) => &self.font_regular,
+        }
+    }
+
+    /// Width of a string in mm for a given font size (pt), from glyph
+    /// advances when a custom font is embedded.
+    fn text_width_mm(&self, text: &str, font_size_pt: f32, bold: bool, italic: bool, is_mono: bool) -> f32 {
+        if let (Some(widths), false) = (&self.widths, is_mono) {
+            let face = &widths[match (bold, italic) {
+                (false, false) => 0,
+                (true, false) => 1,
+                (false, true) => 2,
+                (true, true) => 3,
+            }];
+            return face.width_em(text) * font_size_pt / PT_PER_MM;
+        }
+        let units: u32 = if is_mono {
+            text.chars().count() as u32 * COURIER_WIDTH
+        } else {
+            text.chars().map(|c| helvetica_width(c, bold)).sum()
+        };
+        units as f32 / 1000.0 * font_size_pt / PT_PER_MM
+    }
+
+    /// Wrap text into lines that fit within the given width in mm.
+    fn wrap_text(
+        &self,
+        text: &str,
+        font_size_pt: f32,
+        max_width_mm: f32,
+        (bold, italic): (bool, bool),
+        is_mono: bool,
+    ) -> Vec<String> {
+        let mut lines = Vec::new();
+
+        for hard_line in text.split('\n') {
+            let words: Vec<&str> = hard_line.split_whitespace().collect();
+            if words.is_empty() {
+                lines.push(String::new());
+                continue;
+            }
+
+            let mut current_line = String::new();
+            for word in &words {
+                let test = if current_line.is_empty() {
+                    word.to_string()
+                } else {
+                    format!("{} {}", current_line, word)
+                };
+                if self.text_width_mm(&test, font_size_pt, bold, italic, is_mono) > max_width_mm
+                    && !current_line.is_empty()
+                {
+                    lines.push(current_line);
+                    current_line = word.to_string();
+                } else {
+                    current_line = test;
+                }
+            }
+            if !current_line.is_empty() {
+                lines.push(current_line);
+            }
+        }
+
+        if lines.is_empty() {
+            lines.push(String::new());
+        }
+        lines
+    }
+
+    /// Write a single line of text at the current y position.
+    fn write_line(&mut self, text: &str, font_size_pt: f32, (bold, italic, code): (bool, bool, bool), x_offset_mm: f32) {
+        let font = self.select_font(bold, italic, code);
+        let layer = self.doc.get_page(self.current_page).get_layer(self.current_layer);
+        layer.use_text(
+            text,
+            font_size_pt,
+            Mm(self.page.margins.left + x_offset_mm),
+            Mm(self.y_pos),
+            font,
+        );
+        if self.page_count == 1 {
+            self.first_page.push(PageMark::Text {
+                x: self.page.margins.left + x_offset_mm,
+                y: self.y_pos,
+                text: text.to_string(),
+                size_pt: font_size_pt,
+                face: bold as usize + 2 * italic as usize,
+                mono: code,
+                gray: 0.0,
+            });
+        }
+    }
+
+    /// One line of highlighted code in the monospace font, each run in its
+    /// colour. Unlike other text, it wraps at the column edge mid-word and
+    /// keeps its indentation.
+    fn write_code_line(&mut self, runs: &[(String, [u8; 3])], font_size_pt: f32, indent_mm: f32) {
+        let line_height_mm = font_size_pt / PT_PER_MM * 1.4;
+        let char_mm = self.text_width_mm(" ", font_size_pt, false, false, true);
+        let per_row = ((self.page.text_width() - indent_mm) / char_mm).floor().max(1.0) as usize;
+
+        let mut rows: Vec<CodeLine> = vec![Vec::new()];
+        let mut used = 0;
+        for (text, colour) in runs {
+            for c in text.chars() {
+                if used == per_row {
+                    rows.push(Vec::new());
+                    used = 0;
+                }
+                let row = rows.last_mut().expect("rows is never empty");
+                match row.last_mut() {
+                    Some((run, run_colour)) if run_colour == colour => run.push(c),
+                    _ => row.push((c.to_string(), *colour)),
+                }
+                used += 1;
+            }
+        }
+
+        for row in rows {
+            self.ensure_space(line_height_mm);
+            let mut x = self.page.margins.left + indent_mm;
+            for (text, [r, g, b]) in &row {
+                let layer = self.doc.get_page(self.current_page).get_layer(self.current_layer);
+                let rgb = (*r as f32 / 255.0, *g as f32 / 255.0, *b as f32 / 255.0);
+                layer.set_fill_color(printpdf::Color::Rgb(Rgb::new(rgb.0, rgb.1, rgb.2, None)));
+                layer.use_text(text, font_size_pt, Mm(x), Mm(self.y_pos), &self.font_mono);
+                if self.page_count == 1 {
+                    self.first_page.push(PageMark::Text {
+                        x,
+                        y: self.y_pos,
+                        text: text.clone(),
+                        size_pt: font_size_pt,
+                        face: 0,
+                        mono: true,
+                        // Luminance, as the preview draws text in grey
+                        gray: 0.299 * rgb.0 + 0.587 * rgb.1 + 0.114 * rgb.2,
+                    });
+                }
+                x += char_mm * text.chars().count() as f32;
+            }
+            let layer = self.doc.get_page(self.current_page).get_layer(self.current_layer);
+            layer.set_fill_color(printpdf::Color::Rgb(Rgb::new(0.0, 0.0, 0.0, None)));
+            self.y_pos -= line_height_mm;
+        }
+    }
+
+    /// A straight line in `gray` (0 black, 1 white), `thickness_pt` wide.
+    fn draw_rule(&mut self, from: (f32, f32), to: (f32, f32), gray: f32, thickness_pt: f32) {
+        let layer = self.doc.get_page(self.current_page).get_layer(self.current_layer);
+        let line = printpdf::Line {
+            points: vec![
+                (printpdf::Point::new(Mm(from.0), Mm(from.1)), false),
+                (printpdf::Point::new(Mm(to.0), Mm(to.1)), false),
+            ],
+            is_closed: false,
+        };
+        layer.set_outline_color(printpdf::Color::Rgb(Rgb::new(gray, gray, gray, None)));
+        layer.set_outline_thickness(thickness_pt);
+        layer.add_line(line);
+        if self.page_count == 1 {
+            self.first_page.push(PageMark::Rule { from, to, thickness_pt, gray });
+        }
+    }
+
+    /// `text` wrapped to the text column, each line centred, in `gray`
+    /// (0 black, 1 white).
+    fn write_centered(&mut self, text: &str, font_size_pt: f32, bold: bool, gray: f32) {
+        let line_height = font_size_pt / PT_PER_MM * 1.3;
+        let color = |gray: f32| printpdf::Color::Rgb(Rgb::new(gray, gray, gray, None));
+        for line in self.wrap_text(text, font_size_pt, self.page.text_width(), (bold, false), false) {
+            let x = self.page.margins.left + (self.page.text_width() - self.text_width_mm(&line, font_size_pt, bold, false, false)) / 2.0;
+            let layer = self.doc.get_page(self.current_page).get_layer(self.current_layer);
+            layer.set_fill_color(color(gray));
+            layer.use_text(line.as_str(), font_size_pt, Mm(x), Mm(self.y_pos), self.select_font(bold, false, false));
+            layer.set_fill_color(color(0.0));
+            if self.page_count == 1 {
+                self.first_page.push(PageMark::Text {
+                    x,
+                    y: self.y_pos,
+                    text: line,
+                    size_pt: font_size_pt,
+                    face: bold as usize,
+                    mono: false,
+                    gray,
+                });
+            }
+            self.y_pos -= line_height;
+        }
+    }
+
+    /// The cover: logo and title in the upper third, subtitle under the
+    /// title, author and date at the foot of the page. Body text starts on
+    /// the next page.
+    fn write_cover(&mut self, title: &str, logo: Option<&::image::DynamicImage>) {
+        self.y_pos = self.page.top() - 30.0;
+        if let Some(logo) = logo {
+            self.write_image_within(logo, PDF_COVER_LOGO_MM.0, PDF_COVER_LOGO_MM.1);
+            self.y_pos -= 20.0;
+        }
+        self.y_pos = self.y_pos.min(self.page.height * 0.62) - PDF_COVER_TITLE_PT / PT_PER_MM;
+        self.write_centered(title, PDF_COVER_TITLE_PT, true, 0.0);
+        if let Some(subtitle) = self.options.subtitle.clone().filter(|s| !s.trim().is_empty()) {
+            self.y_pos -= 4.0;
+            self.write_centered(subtitle.trim(), PDF_COVER_SUBTITLE_PT, false, 0.35);
+        }
+
+        let date = match self.options.date.as_deref().map(str::trim) {
+            Some("") => None,
+            Some(date) => Some(date.to_string()),
+            None => Some(Utc::now().format("%-d %B %Y").to_string()),
+        };
+        self.y_pos = self.page.margins.bottom + 30.0;
+        if let Some(author) = self.options.author().map(str::to_string) {
+            self.write_centered(&author, PDF_COVER_BYLINE_PT, true, 0.0);
+        }
+        if let Some(date) = date {
+            self.write_centered(&date, PDF_COVER_BYLINE_PT, false, 0.35);
+        }
+        self.new_page();
+    }
+
+    /// The contents page: each of `entries` indented by level, with its
+    /// page number, as the footer counts pages, at the right margin. An
+    /// entry whose page isn't known yet is drawn without one; it wraps the
+    /// same either way. Body text starts on the next page.
+    fn write_contents(&mut self, entries: &[PdfHeading]) {
+        const NUMBER_MM: f32 = 12.0;
+        let size_mm = PDF_CONTENTS_ENTRY_PT / PT_PER_MM;
+        let line_height = size_mm * 1.5;
+        self.ensure_space(PDF_CONTENTS_TITLE_PT / PT_PER_MM * 1.5);
+        self.write_line("Contents", PDF_CONTENTS_TITLE_PT, (true, false, false), 0.0);
+        self.y_pos -= PDF_CONTENTS_TITLE_PT / PT_PER_MM * 1.5;
+        self.write_spacer(3.0);
+
+        let first = self.options.cover as usize;
+        for entry in entries {
+            let indent = 6.0 * entry.level.saturating_sub(1) as f32;
+            let bold = entry.level == 1;
+            let width = self.page.text_width() - indent - NUMBER_MM;
+            let lines = self.wrap_text(&entry.text, PDF_CONTENTS_ENTRY_PT, width, (bold, false), false);
+            // An entry stays on one page
+            self.ensure_space(line_height * lines.len() as f32);
+            let top = self.y_pos + size_mm;
+            for (i, line) in lines.iter().enumerate() {
+                self.write_line(line, PDF_CONTENTS_ENTRY_PT, (bold, false, false), indent);
+                if let (Some(page), true) = (entry.page, i + 1 == lines.len()) {
+                    let label = (page + 1).saturating_sub(first).to_string();
+                    let x = self.page.text_width() - self.text_width_mm(&label, PDF_CONTENTS_ENTRY_PT, bold, false, false);
+                    self.write_line(&label, PDF_CONTENTS_ENTRY_PT, (bold, false, false), x);
+                }
+                self.y_pos -= line_height;
+            }
+            self.contents_links.push(PdfLink {
+                page: self.pages.len() - 1,
+                lower_left: (self.page.margins.left + indent, self.y_pos + line_height - size_mm * 0.3),
+                upper_right: (self.page.right(), top),
+            });
+            self.write_spacer(1.0);
+        }
+        self.new_page();
+    }
+
+    /// A rectangle filled with `gray` (0 black, 1 white).
+    fn fill_rect(&mut self, lower_left: (f32, f32), upper_right: (f32, f32), gray: f32) {
+        let layer = self.doc.get_page(self.current_page).get_layer(self.current_layer);
+        layer.set_fill_color(printpdf::Color::Rgb(Rgb::new(gray, gray, gray, None)));
+        layer.add_rect(
+            printpdf::Rect::new(Mm(lower_left.0), Mm(lower_left.1), Mm(upper_right.0), Mm(upper_right.1))
+                .with_mode(printpdf::path::PaintMode::Fill),
+        );
+        // Text is drawn in the fill colour
+        layer.set_fill_color(printpdf::Color::Rgb(Rgb::new(0.0, 0.0, 0.0, None)));
+        if self.page_count == 1 {
+            self.first_page.push(PageMark::Fill { lower_left, upper_right, gray });
+        }
+    }
+
+    /// A bordered table across the text column, with column widths from
+    /// `table_column_weights` and the header rows shaded and in bold. Rows
+    /// don't break across pages, and the header rows are repeated at the top
+    /// of each page the table continues on.
+    fn write_table(&mut self, rows: &[Vec<Vec<InlineNode>>], header_rows: usize) {
+        const SIZE_PT: f32 = 10.0;
+        const PADDING_MM: f32 = 1.5;
+        let columns = rows.iter().map(|r| r.len()).max().unwrap_or(0);
+        if columns == 0 {
+            return;
+        }
+        let line_height = SIZE_PT / PT_PER_MM * 1.4;
+        let widths: Vec<f32> = table_column_weights(rows, columns).iter().map(|w| w * self.page.text_width()).collect();
+        let empty = Vec::new();
+
+        // Each row's lines per cell, with the cell's formatting, and height
+        let layouts: Vec<(Vec<TableCellLines>, f32)> = rows
+            .iter()
+            .enumerate()
+            .map(|(r, row)| {
+                let cells: Vec<_> = widths
+                    .iter()
+                    .enumerate()
+                    .map(|(c, width)| {
+                        let cell = row.get(c).unwrap_or(&empty);
+                        let text: String = cell.iter().map(|n| n.bracketed_text()).collect();
+                        let (bold, italic, code) = cell
+                            .iter()
+                            .find(|n| !n.text.is_empty())
+                            .map(|n| (n.bold, n.italic, n.code))
+                            .unwrap_or((false, false, false));
+                        let format = (bold || r < header_rows, italic, code);
+                        let lines = self.wrap_text(&text, SIZE_PT, width - 2.0 * PADDING_MM, (format.0, italic), code);
+                        (lines, format)
+                    })
+                    .collect();
+                let lines = cells.iter().map(|(lines, _)| lines.len()).max().unwrap_or(1).max(1);
+                (cells, lines as f32 * line_height + 2.0 * PADDING_MM)
+            })
+            .collect();
+
+        let left = self.page.margins.left;
+        let draw_row = |w: &mut PdfWriter, r: usize| {
+            let (cells, height) = &layouts[r];
+            let top = w.y_pos;
+            let bottom = top - height;
+            let right = left + widths.iter().sum::<f32>();
+            if r < header_rows {
+                w.fill_rect((left, bottom), (right, top), 0.95);
+            }
+            let mut x = left;
+            for ((lines, format), width) in cells.iter().zip(&widths) {
+                w.y_pos = top - PADDING_MM - SIZE_PT / PT_PER_MM;
+                for line in lines {
+                    w.write_line(line, SIZE_PT, *format, x - left + PADDING_MM);
+                    w.y_pos -= line_height;
+                }
+                w.draw_rule((x, top), (x, bottom), 0.75, 0.5);
+                x += width;
+            }
+            w.draw_rule((right, top), (right, bottom), 0.75, 0.5);
+            w.draw_rule((left, top), (right, top), 0.75, 0.5);
+            w.draw_rule((left, bottom), (right, bottom), 0.75, 0.5);
+            w.y_pos = bottom;
+        };
+
+        for (r, (_, height)) in layouts.iter().enumerate() {
+            if self.y_pos - height < self.page.margins.bottom {
+                self.new_page();
+                if r >= header_rows {
+                    for h in 0..header_rows {
+                        draw_row(self, h);
+                    }
+                }
+            }
+            draw_row(self, r);
+        }
+    }
+
+    /// A task list checkbox on the current line, `x_offset_mm` into the text
+    /// column, drawn as lines since the built-in fonts have no ☐ or ☑.
+    fn draw_checkbox(&mut self, x_offset_mm: f32, checked: bool) {
+        let (x, y) = (self.page.margins.left + x_offset_mm, self.y_pos - 0.3);
+        let size = PDF_CHECKBOX_MM;
+        let corners = [(x, y), (x + size, y), (x + size, y + size), (x, y + size)];
+        for i in 0..4 {
+            self.draw_rule(corners[i], corners[(i + 1) % 4], 0.3, 0.6);
+        }
+        if checked {
+            self.draw_rule((x + 0.6, y + 1.4), (x + 1.2, y + 0.6), 0.0, 1.0);
+            self.draw_rule((x + 1.2, y + 0.6), (x + size - 0.5, y + size - 0.5), 0.0, 1.0);
+        }
+    }
+
+    /// Write inline nodes as a block, with word-wrapping.
+    fn write_inline_block(
+        &mut self,
+        children: &[InlineNode],
+        font_size_pt: f32,
+        indent_mm: f32,
+        prefix: Option<&str>,
+    ) {
+        let line_height_mm = font_size_pt / PT_PER_MM * 1.4;
+        let max_width = self.page.text_width() - indent_mm;
+
+        // Concatenate all inline text for simple wrapping.
+        // For mixed formatting, we do a simplified approach: concatenate text,
+        // wrap, then render each output line in the dominant formatting.
+
+        let mut full_text = String::new();
+        if let Some(pfx) = prefix {
+            full_text.push_str(pfx);
+        }
+        for child in children {
+            full_text.push_str(&child.bracketed_text());
+        }
+
+        // Determine dominant formatting from the first non-empty child
+        let (bold, italic, code) = children
+            .iter()
+            .find(|c| !c.text.is_empty())
+            .map(|c| (c.bold, c.italic, c.code))
+            .unwrap_or((false, false, false));
+        let is_mono = children.iter().any(|c| c.code);
+        let lines = self.wrap_text(&full_text, font_size_pt, max_width, (bold, italic), is_mono);
+
+        for line in &lines {
+            self.ensure_space(line_height_mm);
+
+            self.write_line(line, font_size_pt, (bold, italic, code), indent_mm);
+            self.y_pos -= line_height_mm;
+        }
+    }
+
+    /// Draw an image centred in the text column, scaled to fit the page
+    /// (at most its size at 96 dpi). Transparent pixels are flattened onto
+    /// white, as the PDF has no soft mask.
+    fn write_image(&mut self, image: &::image::DynamicImage) {
+        self.write_image_within(image, self.page.text_width(), self.page.text_height());
+    }
+
+    /// `write_image`, fitting the image to `max_w_mm` x `max_h_mm`.
+    fn write_image_within(&mut self, image: &::image::DynamicImage, max_w_mm: f32, max_h_mm: f32) {
+        let rgba = image.to_rgba8();
+        let (px_w, px_h) = rgba.dimensions();
+        let (w_mm, h_mm) = fit_within(
+            px_w as f32 * MM_PER_INCH / 96.0,
+            px_h as f32 * MM_PER_INCH / 96.0,
+            max_w_mm,
+            max_h_mm,
+        );
+        let mut rgb = Vec::with_capacity((px_w * px_h * 3) as usize);
+        for pixel in rgba.pixels() {
+            let [r, g, b, a] = pixel.0;
+            let alpha = a as u32;
+            for channel in [r, g, b] {
+                rgb.push(((channel as u32 * alpha + 255 * (255 - alpha)) / 255) as u8);
+            }
+        }
+
+        self.ensure_space(h_mm);
+        let xobject = ImageXObject {
+            width: Px(px_w as usize),
+            height: Px(px_h as usize),
+            color_space: ColorSpace::Rgb,
+            bits_per_component: ColorBits::Bit8,
+            interpolate: true,
+            image_data: rgb,
+            image_filter: None,
+            smask: None,
+            clipping_bbox: None,
+        };
+        let layer = self.doc.get_page(self.current_page).get_layer(self.current_layer);
+        printpdf::Image::from(xobject).add_to_layer(
+            layer,
+            ImageTransform {
+                translate_x: Some(Mm(self.page.margins.left + (self.page.text_width() - w_mm) / 2.0)),
+                translate_y: Some(Mm(self.y_pos - h_mm)),
+                // The dpi at which the image's pixels span exactly w_mm
+                dpi: Some(px_w as f32 * MM_PER_INCH / w_mm),
+                ..Default::default()
+            },
+        );
+        if self.page_count == 1 {
+            self.first_page.push(PageMark::Image {
+                x: self.page.margins.left + (self.page.text_width() - w_mm) / 2.0,
+                y: self.y_pos - h_mm,
+                w: w_mm,
+                h: h_mm,
+                image: image.clone(),
+            });
+        }
+        self.y_pos -= h_mm;
+    }
+
+    fn write_spacer(&mut self, mm: f32) {
+        self.y_pos -= mm;
+        if self.y_pos < self.page.margins.bottom {
+            self.new_page();
+        }
+    }
+
+    /// (text, x, y) of the header, footer and page number on page `n`
+    /// (from 0), in the top and bottom margins.
+    fn margin_texts(&self, n: usize) -> Vec<(String, f32, f32)> {
+        let PdfExportOptions { header_text, footer_text, page_numbers, cover, .. } = &self.options;
+        // The cover is left clean and uncounted
+        let first = *cover as usize;
+        if n < first {
+            return Vec::new();
+        }
+        let header = header_text.as_deref().map(str::trim).filter(|t| !t.is_empty());
+        let footer = footer_text.as_deref().map(str::trim).filter(|t| !t.is_empty());
+        let size = PDF_MARGIN_TEXT_PT;
+        let header_y = self.page.height - self.page.margins.top / 2.0;
+        let footer_y = self.page.margins.bottom / 2.0;
+
+        let mut texts = Vec::new();
+        if let Some(text) = header {
+            texts.push((self.truncate_to_width(text, size, self.page.text_width()), self.page.margins.left, header_y));
+        }
+        let mut footer_width = self.page.text_width();
+        if *page_numbers {
+            let label = format!("Page {} of {}", n + 1 - first, self.pages.len() - first);
+            let width = self.text_width_mm(&label, size, false, false, false);
+            texts.push((label, self.page.right() - width, footer_y));
+            footer_width -= width + 6.0;
+        }
+        if let Some(text) = footer {
+            texts.push((self.truncate_to_width(text, size, footer_width), self.page.margins.left, footer_y));
+        }
+        texts
+    }
+
+    /// Header, footer and page numbers on every page.
+    fn draw_margins(&self) {
+        let gray = printpdf::Color::Rgb(Rgb::new(0.45, 0.45, 0.45, None));
+        for (n, (page, layer)) in self.pages.iter().enumerate() {
+            let texts = self.margin_texts(n);
+            if texts.is_empty() {
+                continue;
+            }
+            let layer = self.doc.get_page(*page).get_layer(*layer);
+            layer.set_fill_color(gray.clone());
+            for (text, x, y) in texts {
+                layer.use_text(text, PDF_MARGIN_TEXT_PT, Mm(x), Mm(y), &self.font_regular);
+            }
+            layer.set_fill_color(printpdf::Color::Rgb(Rgb::new(0.0, 0.0, 0.0, None)));
+        }
+    }
+
+    /// `text` on one line, cut with "..." where it would overflow.
+    fn truncate_to_width(&self, text: &str, font_size_pt: f32, max_width_mm: f32) -> String {
+        let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+        if self.text_width_mm(&text, font_size_pt, false, false, false) <= max_width_mm {
+            return text;
+        }
+        let mut out: String = text.clone();
+        while !out.is_empty() && self.text_width_mm(&format!("{}...", out), font_size_pt, false, false, false) > max_width_mm {
+            out.pop();
+        }
+        format!("{}...", out.trim_end())
+    }
+
+    fn finish(mut self) -> Result<Vec<u8>, String> {
+        self.draw_margins();
+        let headings = std::mem::take(&mut self.headings);
+        let links = std::mem::take(&mut self.contents_links);
+        let toc = self.options.toc;
+        let mut buf = BufWriter::new(Vec::new());
+        self.doc
+            .save(&mut buf)
+            .map_err(|e| format!("Failed to save PDF: {}", e))?;
+        let pdf = buf.into_inner()
+            .map_err(|e| format!("Failed to finalize PDF buffer: {}", e))?;
+        if toc && !headings.is_empty() {
+            return add_pdf_navigation(&pdf, &headings, &links);
+        }
+        Ok(pdf)
+    }
+}
+
+/// `pdf` with an outline of `headings`, nested by level and all expanded,
+/// and each contents entry in `links` linking to its heading. printpdf can
+/// only bookmark whole pages, one each, so both are added to the saved file.
+fn add_pdf_navigation(pdf: &[u8], headings: &[PdfHeading], links: &[PdfLink]) -> Result<Vec<u8>, String> {
+    use printpdf::lopdf::{Dictionary, Document, Object, ObjectId, StringFormat};
+
+    let mut doc = Document::load_mem(pdf).map_err(|e| format!("Failed to reopen PDF: {}", e))?;
+    let pages: Vec<ObjectId> = doc.get_pages().into_values().collect();
+    let destination = |heading: &PdfHeading| -> Option<Object> {
+        let page = *pages.get(heading.page?)?;
+        Some(Object::Array(vec![
+            Object::Reference(page),
+            Object::Name(b"XYZ".to_vec()),
+            Object::Null,
+            Object::Real(heading.top * PT_PER_MM),
+            Object::Null,
+        ]))
+    };
+    // UTF-16 with a byte order mark, which viewers read in any script
+    let text_string = |text: &str| {
+        let mut bytes = vec![0xFE, 0xFF];
+        bytes.extend(text.encode_utf16().flat_map(u16::to_be_bytes));
+        Object::String(bytes, StringFormat::Hexadecimal)
+    };
+
+    // A heading's parent is the closest heading before it of a higher level
+    let mut parents: Vec<Option<usize>> = Vec::new();
+    let mut open: Vec<usize> = Vec::new();
+    for (i, heading) in headings.iter().enumerate() {
+        while open.last().is_some_and(|&p| headings[p].level >= heading.level) {
+            open.pop();
+        }
+        parents.push(open.last().copied());
+        open.push(i);
+    }
+
+    let root = doc.new_object_id();
+    let ids: Vec<ObjectId> = headings.iter().map(|_| doc.new_object_id()).collect();
+    let mut items: Vec<Dictionary> = headings
+        .iter()
+        .map(|heading| {
+            let mut item = Dictionary::new();
+            item.set("Title", text_string(&heading.text));
+            if let Some(dest) = destination(heading) {
+                item.set("Dest", dest);
+            }
+            item
+        })
+        .collect();
+    let mut outlines = Dictionary::new();
+    outlines.set("Type", Object::Name(b"Outlines".to_vec()));
+    outlines.set("Count", headings.len() as i64);
+    for parent in std::iter::once(None).chain((0..headings.len()).map(Some)) {
+        let children: Vec<usize> = (0..headings.len()).filter(|&i| parents[i] == parent).collect();
+        let (Some(&first), Some(&last)) = (children.first(), children.last()) else { continue };
+        let parent_id = parent.map_or(root, |p| ids[p]);
+        for (n, &child) in children.iter().enumerate() {
+            items[child].set("Parent", Object::Reference(parent_id));
+            if n > 0 {
+                items[child].set("Prev", Object::Reference(ids[children[n - 1]]));
+            }
+            if let Some(&next) = children.get(n + 1) {
+                items[child].set("Next", Object::Reference(ids[next]));
+            }
+        }
+        let dict = match parent {
+            Some(p) => &mut items[p],
+            None => &mut outlines,
+        };
+        dict.set("First", Object::Reference(ids[first]));
+        dict.set("Last", Object::Reference(ids[last]));
+    }
+    // An open entry's count is that of everything under it
+    let mut counts = vec![0_i64; headings.len()];
+    for parent in &parents {
+        let mut ancestor = *parent;
+        while let Some(a) = ancestor {
+            counts[a] += 1;
+            ancestor = parents[a];
+        }
+    }
+    for ((id, mut item), count) in ids.iter().zip(items).zip(counts) {
+        if count > 0 {
+            item.set("Count", count);
+        }
+        doc.objects.insert(*id, Object::Dictionary(item));
+    }
+    doc.objects.insert(root, Object::Dictionary(outlines));
+    let catalog = doc.catalog_mut().map_err(|e| format!("Failed to add PDF outline: {}", e))?;
+    catalog.set("Outlines", Object::Reference(root));
+    catalog.set("PageMode", Object::Name(b"UseOutlines".to_vec()));
+
+    for (link, heading) in links.iter().zip(headings) {
+        let (Some(&page), Some(dest)) = (pages.get(link.page), destination(heading)) else { continue };
+        let rect = [link.lower_left.0, link.lower_left.1, link.upper_right.0, link.upper_right.1];
+        let mut annotation = Dictionary::new();
+        annotation.set("Type", Object::Name(b"Annot".to_vec()));
+        annotation.set("Subtype", Object::Name(b"Link".to_vec()));
+        annotation.set("Rect", Object::Array(rect.iter().map(|mm| Object::Real(mm * PT_PER_MM)).collect()));
+        annotation.set("Border", Object::Array(vec![0.into(), 0.into(), 0.into()]));
+        annotation.set("Dest", dest);
+        let annotation = Object::Reference(doc.add_object(annotation));
+        let page = doc.get_dictionary_mut(page).map_err(|e| format!("Failed to add PDF links: {}", e))?;
+        match page.get_mut(b"Annots") {
+            Ok(Object::Array(annotations)) => annotations.push(annotation),
+            _ => page.set("Annots", Object::Array(vec![annotation])),
+        }
+    }
+
+    let mut out = Vec::new();
+    doc.save_to(&mut out).map_err(|e| format!("Failed to save PDF: {}", e))?;
+    Ok(out)
+}
+
+/// `images_dir` resolves uploaded images given by file name; see
+/// `load_image`. `fonts` replaces Helvetica for body text.
+pub(crate) fn build_pdf(
+    title: &str,
+    html: &str,
+    images_dir: Option<&Path>,
+    fonts: Option<&PdfFonts>,
+    options: PdfExportOptions,
+) -> Result<Vec<u8>, AppError> {
+    Ok(lay_out_pdf(title, html, images_dir, fonts, options)?.finish()?)
+}
+
+/// The title over a rule at the top of the first page, without a cover.
+fn write_pdf_title(w: &mut PdfWriter, title: &str) {
+    let title_lines = w.wrap_text(title, 20.0, w.page.text_width(), (true, false), false);
+    for line in &title_lines {
+        w.ensure_space(20.0 / PT_PER_MM * 1.5);
+        w.write_line(line, 20.0, (true, false, false), 0.0);
+        w.y_pos -= 20.0 / PT_PER_MM * 1.5;
+    }
+    w.write_spacer(6.0);
+
+    // Horizontal rule under title
+    let y = w.y_pos;
+    w.draw_rule((w.page.margins.left, y), (w.page.right(), y), 0.7, 0.5);
+    w.write_spacer(6.0);
+}
+
+/// Every page of the PDF drawn, before headers and footers are added.
+fn lay_out_pdf(
+    title: &str,
+    html: &str,
+    images_dir: Option<&Path>,
+    fonts: Option<&PdfFonts>,
+    options: PdfExportOptions,
+) -> Result<PdfWriter, AppError> {
+    let nodes = parse_html(html);
+    let logo = match options.logo_path.as_deref().map(str::trim).filter(|p| !p.is_empty()) {
+        Some(path) if options.cover => Some(
+            load_image(path, images_dir)
+                .ok_or_else(|| AppError::not_found(format!("Couldn't read the cover logo '{}'", path)))?
+                .image,
+        ),
+        _ => None,
+    };
+    let contents: Vec<PdfHeading> = nodes
+        .iter()
+        .filter(|_| options.toc)
+        .filter_map(|node| match node {
+            HtmlNode::Heading { level: level @ 1..=3, children } => {
+                Some(PdfHeading { level: *level, text: inline_text(children), page: None, top: 0.0 })
+            }
+            _ => None,
+        })
+        .filter(|heading| !heading.text.is_empty())
+        .collect();
+    if contents.is_empty() {
+        return lay_out_pdf_pages(title, &nodes, images_dir, fonts, options, logo.as_ref(), None);
+    }
+    // The contents take up the same pages whether or not they're numbered,
+    // so a first pass finds the page of every heading
+    let numbered = lay_out_pdf_pages(title, &nodes, images_dir, fonts, options.clone(), logo.as_ref(), Some(&contents))?
+        .headings;
+    lay_out_pdf_pages(title, &nodes, images_dir, fonts, options, logo.as_ref(), Some(&numbered))
+}
+
+/// `lay_out_pdf` for parsed `nodes`, with a contents page of `contents`.
+fn lay_out_pdf_pages(
+    title: &str,
+    nodes: &[HtmlNode],
+    images_dir: Option<&Path>,
+    fonts: Option<&PdfFonts>,
+    options: PdfExportOptions,
+    logo: Option<&::image::DynamicImage>,
+    contents: Option<&[PdfHeading]>,
+) -> Result<PdfWriter, AppError> {
+    let mut w = PdfWriter::new(title, fonts, options)?;
+
+    if w.options.cover {
+        w.write_cover(title, logo);
+    } else {
+        write_pdf_title(&mut w, title);
+    }
+    if let Some(contents) = contents {
+        w.write_contents(contents);
+    }
+
+    for node in nodes {
+        match node {
+            HtmlNode::Heading { level, children } => {
+                let font_size = match level {
+                    1 => 18.0_f32,
+                    2 => 15.0,
+                    3 => 13.0,
+                    _ => 12.0,
+                };
+                w.write_spacer(3.0);
+                let text = inline_text(children);
+                if *level <= 3 && !text.is_empty() {
+                    // Where the first line will go
+                    w.ensure_space(font_size / PT_PER_MM * 1.4);
+                    let top = w.y_pos + font_size / PT_PER_MM;
+                    w.headings.push(PdfHeading { level: *level, text, page: Some(w.pages.len() - 1), top });
+                }
+                // Force bold for headings
+                let modified: Vec<InlineNode> = children
+                    .iter()
+                    .map(|c| InlineNode {
+                        bold: true,
+                        ..c.clone()
+                    })
+                    .collect();
+                w.write_inline_block(&modified, font_size, 0.0, None);
+                w.write_spacer(2.0);
+            }
+            HtmlNode::Paragraph { children } => {
+                w.write_inline_block(children, 11.0, 0.0, None);
+                w.write_spacer(3.0);
+            }
+            HtmlNode::UnorderedList { .. } | HtmlNode::OrderedList { .. } | HtmlNode::TaskList { .. } => {
+                for line in list_lines(node) {
+                    let indent = 8.0 + 6.0 * line.depth as f32;
+                    if let Some(checked) = line.checked {
+                        w.ensure_space(11.0 / PT_PER_MM * 1.4);
+                        w.draw_checkbox(indent, checked);
+                        w.write_inline_block(line.children, 11.0, indent + PDF_CHECKBOX_MM + 2.0, None);
+                        w.write_spacer(1.5);
+                        continue;
+                    }
+                    // The built-in fonts have no ◦ or ▪
+                    let marker = ordered_marker(line.number, line.depth)
+                        .unwrap_or_else(|| ["\u{2022}", "\u{2013}", "\u{00b7}"][line.depth % 3].to_string());
+                    w.write_inline_block(line.children, 11.0, indent, Some(&format!("{}  ", marker)));
+                    w.write_spacer(1.5);
+                }
+                w.write_spacer(2.0);
+            }
+            HtmlNode::Blockquote { children } => {
                 // Draw a left bar
                 {
-                    let bar_x = MARGIN_LEFT + 3.0;
+                    let bar_x = w.page.margins.left + 3.0;
                     let bar_top = w.y_pos + 2.0;
                     // Estimate height
                     let est_lines = children.len().max(1);
                     let bar_bottom = w.y_pos - (est_lines as f32 * 11.0 / PT_PER_MM * 1.4) - 2.0;
+                    w.draw_rule((bar_x, bar_top), (bar_x, bar_bottom.max(w.page.margins.bottom)), 0.6, 1.5);
+                }
+                // Make all children italic
+                let modified: Vec<InlineNode> = children
+                    .iter()
+                    .map(|c| InlineNode {
+                        italic: true,
+                        ..c.clone()
+                    })
+                    .collect();
+                w.write_inline_block(&modified, 11.0, 10.0, None);
+                w.write_spacer(3.0);
+            }
+            HtmlNode::CodeBlock { text, language } => {
+                w.write_spacer(2.0);
+                let highlighted = match language {
+                    Some(language) if w.options.highlight_code => highlight_code(text, language),
+                    _ => None,
+                };
+                for line in highlighted.iter().flatten() {
+                    w.write_code_line(line, 9.0, 6.0);
+                }
+                let plain = if highlighted.is_some() { "" } else { text.as_str() };
+                for line in plain.lines() {
+                    let code_node = InlineNode {
+                        text: line.to_string(),
+                        bold: false,
+                        italic: false,
+                        underline: false,
+                        code: true,
+                        href: None,
+                        footnote: None,
+                    };
+                    w.write_inline_block(&[code_node], 9.0, 6.0, None);
+                }
+                w.write_spacer(3.0);
+            }
+            HtmlNode::HorizontalRule => {
+                w.write_spacer(3.0);
+                let y = w.y_pos;
+                w.draw_rule((w.page.margins.left, y), (w.page.right(), y), 0.75, 0.5);
+                w.write_spacer(3.0);
+            }
+            HtmlNode::Table { rows, header_rows } => {
+                w.write_spacer(2.0);
+                w.write_table(rows, *header_rows);
+                w.write_spacer(4.0);
+            }
+            HtmlNode::Image { src, alt } => match load_image(src, images_dir) {
+                Some(loaded) => {
+                    w.write_image(&loaded.image);
+                    w.write_spacer(2.0);
+                    if !alt.is_empty() {
+                        let caption = InlineNode {
+                            text: alt.clone(),
+                            bold: false,
+                            italic: true,
+                            underline: false,
+                            code: false,
+                            href: None,
+                            footnote: None,
+                        };
+                        w.write_inline_block(&[caption], 9.0, 0.0, None);
+                    }
+                    w.write_spacer(3.0);
+                }
+                None => {
+                    let display = if alt.is_empty() {
+                        "[Image]".to_string()
+                    } else {
+                        format!("[Image: {}]", alt)
+                    };
+                    let node = InlineNode {
+                        text: display,
+                        bold: false,
+                        italic: true,
+                        underline: false,
+                        code: false,
+                        href: None,
+                        footnote: None,
+                    };
+                    w.write_inline_block(&[node], 10.0, 0.0, None);
+                    w.write_spacer(3.0);
+                }
+            },
+        }
+    }
+
+    // Footnotes as numbered endnotes
+    let notes = footnotes(nodes);
+    if !notes.is_empty() {
+        w.write_spacer(6.0);
+        let y = w.y_pos;
+        w.draw_rule((w.page.margins.left, y), (w.page.margins.left + w.page.text_width() / 3.0, y), 0.7, 0.5);
+        w.write_spacer(5.0);
+        let heading = InlineNode {
+            text: "Notes".to_string(),
+            bold: true,
+            italic: false,
+            underline: false,
+            code: false,
+            href: None,
+            footnote: None,
+        };
+        w.write_inline_block(&[heading], 12.0, 0.0, None);
+        w.write_spacer(2.0);
+        for footnote in notes {
+            let prefix = format!("{}. ", footnote.number);
+            w.write_inline_block(&footnote.note, 9.5, 0.0, Some(&prefix));
+            w.write_spacer(1.5);
+        }
+    }
+
+    Ok(w)
+}
+
+// ---------------------------------------------------------------------------
+// Export preview
+// ---------------------------------------------------------------------------
+
+/// Default preview width in px; the page's proportions give the height
+const PREVIEW_WIDTH_PX: u32 = 600;
+/// Samples per pixel along each axis when filling glyphs and rules
+const PREVIEW_SUPERSAMPLE: usize = 3;
+
+/// Faces used to draw preview text: regular, bold, italic, bold italic,
+/// then monospace.
+type PreviewFaces = [Option<std::sync::Arc<Vec<u8>>>; 5];
+
+/// Stand-ins for the PDF's built-in Helvetica and Courier, which have no
+/// outlines to draw; found once among the system fonts.
+static SYSTEM_PREVIEW_FACES: std::sync::OnceLock<PreviewFaces> = std::sync::OnceLock::new();
+
+fn system_preview_faces(app: &tauri::AppHandle) -> PreviewFaces {
+    SYSTEM_PREVIEW_FACES
+        .get_or_init(|| {
+            let dirs = font_dirs(app);
+            let read = |path: &Option<PathBuf>| {
+                let data = std::fs::read(path.as_ref()?).ok()?;
+                ttf_parser::Face::parse(&data, 0).ok()?;
+                Some(std::sync::Arc::new(data))
+            };
+            let mut faces: PreviewFaces = Default::default();
+            for family in ["Helvetica", "Arial", "Liberation Sans", "Nimbus Sans", "DejaVu Sans"] {
+                let found = find_font_family(&dirs, family);
+                if found[0].is_some() {
+                    for (slot, path) in faces.iter_mut().zip(found.iter()) {
+                        *slot = read(path);
+                    }
+                    break;
+                }
+            }
+            for family in ["Courier New", "Liberation Mono", "Nimbus Mono PS", "DejaVu Sans Mono", "Courier"] {
+                if let Some(mono) = read(&find_font_family(&dirs, family)[0]) {
+                    faces[4] = Some(mono);
+                    break;
+                }
+            }
+            faces
+        })
+        .clone()
+}
+
+/// The regular sans-serif stand-in, for text drawn outside the PDF
+/// writer such as chart labels.
+pub(crate) fn system_sans_face(app: &tauri::AppHandle) -> Option<std::sync::Arc<Vec<u8>>> {
+    system_preview_faces(app)[0].clone()
+}
+
+/// Preview faces for `fonts`, the custom fonts of a PDF export; the
+/// system stand-ins for whatever they leave out.
+fn preview_faces(app: &tauri::AppHandle, fonts: Option<&PdfFonts>) -> PreviewFaces {
+    let mut faces = system_preview_faces(app);
+    if let Some(fonts) = fonts {
+        let regular = std::sync::Arc::new(fonts.regular.clone());
+        let custom = |face: &Option<Vec<u8>>| face.clone().map(std::sync::Arc::new);
+        faces[0] = Some(regular.clone());
+        faces[1] = custom(&fonts.bold).or_else(|| Some(regular.clone()));
+        faces[2] = custom(&fonts.italic).or_else(|| Some(regular.clone()));
+        faces[3] = custom(&fonts.bold_italic).or_else(|| faces[1].clone());
+    }
+    faces
+}
+
+/// Glyph outlines flattened into polygons, in supersampled pixels.
+struct GlyphPath {
+    contours: Vec<Vec<(f32, f32)>>,
+    /// Font units to pixels, the pen origin, and the slant for faked italics
+    scale: f32,
+    origin: (f32, f32),
+    slant: f32,
+}
+
+impl GlyphPath {
+    fn point(&self, x: f32, y: f32) -> (f32, f32) {
+        (self.origin.0 + (x + y * self.slant) * self.scale, self.origin.1 - y * self.scale)
+    }
+
+    fn last(&self) -> (f32, f32) {
+        self.contours.last().and_then(|c| c.last()).copied().unwrap_or(self.origin)
+    }
+}
+
+impl ttf_parser::OutlineBuilder for GlyphPath {
+    fn move_to(&mut self, x: f32, y: f32) {
+        let p = self.point(x, y);
+        self.contours.push(vec![p]);
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        let p = self.point(x, y);
+        if let Some(contour) = self.contours.last_mut() {
+            contour.push(p);
+        }
+    }
+
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        let (p0, p1, p2) = (self.last(), self.point(x1, y1), self.point(x, y));
+        if let Some(contour) = self.contours.last_mut() {
+            for i in 1..=8 {
+                let t = i as f32 / 8.0;
+                let u = 1.0 - t;
+                contour.push((
+                    u * u * p0.0 + 2.0 * u * t * p1.0 + t * t * p2.0,
+                    u * u * p0.1 + 2.0 * u * t * p1.1 + t * t * p2.1,
+                ));
+            }
+        }
+    }
+
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        let (p0, p1, p2, p3) = (self.last(), self.point(x1, y1), self.point(x2, y2), self.point(x, y));
+        if let Some(contour) = self.contours.last_mut() {
+            for i in 1..=12 {
+                let t = i as f32 / 12.0;
+                let u = 1.0 - t;
+                let (a, b, c, d) = (u * u * u, 3.0 * u * u * t, 3.0 * u * t * t, t * t * t);
+                contour.push((
+                    a * p0.0 + b * p1.0 + c * p2.0 + d * p3.0,
+                    a * p0.1 + b * p1.1 + c * p2.1 + d * p3.1,
+                ));
+            }
+        }
+    }
+
+    fn close(&mut self) {}
+}
+
+/// Darkness per supersampled pixel, 0 (none) to 1 (black).
+struct InkLayer {
+    width: usize,
+    height: usize,
+    ink: Vec<f32>,
+}
+
+impl InkLayer {
+    /// Fill polygons with the non-zero rule, sampling pixel centres.
+    fn fill(&mut self, contours: &[Vec<(f32, f32)>], ink: f32) {
+        let points = contours.iter().flatten();
+        let (min_y, max_y) = points.fold((f32::MAX, f32::MIN), |(lo, hi), p| (lo.min(p.1), hi.max(p.1)));
+        if min_y > max_y {
+            return;
+        }
+        let first = (min_y.floor().max(0.0)) as usize;
+        let last = (max_y.ceil().max(0.0) as usize).min(self.height);
+        let mut crossings: Vec<(f32, i32)> = Vec::new();
+        for row in first..last {
+            let y = row as f32 + 0.5;
+            crossings.clear();
+            for contour in contours {
+                for (i, a) in contour.iter().enumerate() {
+                    let b = contour[(i + 1) % contour.len()];
+                    if (a.1 <= y) != (b.1 <= y) {
+                        let x = a.0 + (y - a.1) / (b.1 - a.1) * (b.0 - a.0);
+                        crossings.push((x, if b.1 > a.1 { 1 } else { -1 }));
+                    }
+                }
+            }
+            crossings.sort_by(|a, b| a.0.total_cmp(&b.0));
+            let mut winding = 0;
+            for pair in crossings.windows(2) {
+                winding += pair[0].1;
+                if winding == 0 {
+                    continue;
+                }
+                let start = (pair[0].0 - 0.5).ceil().max(0.0) as usize;
+                let end = ((pair[1].0 - 0.5).ceil().max(0.0) as usize).min(self.width);
+                for x in start..end {
+                    let cell = &mut self.ink[row * self.width + x];
+                    *cell = cell.max(ink);
+                }
+            }
+        }
+    }
+
+    fn rect(&mut self, (x0, y0): (f32, f32), (x1, y1): (f32, f32), ink: f32) {
+        self.fill(&[vec![(x0, y0), (x1, y0), (x1, y1), (x0, y1)]], ink);
+    }
+}
+
+/// The first page of `w` as a PNG `width_px` wide. Text without a face to
+/// draw it with is shown as grey bars of the same width.
+fn render_first_page(w: &PdfWriter, faces: &PreviewFaces, width_px: u32) -> Result<(Vec<u8>, u32, u32), String> {
+    let height_px = (width_px as f32 * w.page.height / w.page.width).round() as u32;
+    let ss = PREVIEW_SUPERSAMPLE;
+    let px_per_mm = width_px as f32 / w.page.width;
+    let sub_per_mm = px_per_mm * ss as f32;
+    // mm from the bottom left to supersampled pixels from the top left
+    let to_sub = |x: f32, y: f32| (x * sub_per_mm, (w.page.height - y) * sub_per_mm);
+    let mut layer = InkLayer {
+        width: width_px as usize * ss,
+        height: height_px as usize * ss,
+        ink: vec![0.0; width_px as usize * ss * height_px as usize * ss],
+    };
+    let parsed: Vec<Option<ttf_parser::Face>> =
+        faces.iter().map(|f| f.as_ref().and_then(|data| ttf_parser::Face::parse(data, 0).ok())).collect();
+
+    let mut canvas = ::image::RgbImage::from_pixel(width_px, height_px, ::image::Rgb([255, 255, 255]));
+    let margins = w.margin_texts(0).into_iter().map(|(text, x, y)| PageMark::Text {
+        x,
+        y,
+        text,
+        size_pt: PDF_MARGIN_TEXT_PT,
+        face: 0,
+        mono: false,
+        gray: 0.45,
+    });
+    let margins: Vec<PageMark> = margins.collect();
+    for mark in w.first_page.iter().chain(margins.iter()) {
+        match mark {
+            PageMark::Text { x, y, text, size_pt, face, mono, gray } => {
+                let ink = 1.0 - gray;
+                let slot = if *mono { 4 } else { *face };
+                // Italic falls back to a slanted regular, bold to regular
+                let (font, slant) = match (&parsed[slot], slot) {
+                    (Some(font), _) => (Some(font), 0.0),
+                    (None, 2 | 3) => (parsed[slot - 2].as_ref().or(parsed[0].as_ref()), 0.2),
+                    (None, _) => (parsed[0].as_ref(), 0.0),
+                };
+                let size_sub = size_pt / PT_PER_MM * sub_per_mm;
+                let (mut pen_x, baseline) = to_sub(*x, *y);
+                match font {
+                    Some(font) => {
+                        let scale = size_sub / font.units_per_em().max(1) as f32;
+                        for c in text.chars() {
+                            let Some(glyph) = font.glyph_index(c) else {
+                                pen_x += size_sub * 0.5;
+                                continue;
+                            };
+                            let mut path = GlyphPath { contours: Vec::new(), scale, origin: (pen_x, baseline), slant };
+                            if font.outline_glyph(glyph, &mut path).is_some() {
+                                layer.fill(&path.contours, ink);
+                            }
+                            pen_x += font.glyph_hor_advance(glyph).unwrap_or(0) as f32 * scale;
+                        }
+                    }
+                    None => {
+                        let width = w.text_width_mm(text.trim_end(), *size_pt, *face & 1 == 1, *face & 2 == 2, *mono);
+                        layer.rect((pen_x, baseline - size_sub * 0.5), (pen_x + width * sub_per_mm, baseline), ink * 0.35);
+                    }
+                }
+            }
+            PageMark::Rule { from, to, thickness_pt, gray } => {
+                let half = (thickness_pt / PT_PER_MM * sub_per_mm / 2.0).max(ss as f32 / 2.0);
+                let (a, b) = (to_sub(from.0, from.1), to_sub(to.0, to.1));
+                let (x0, x1) = (a.0.min(b.0) - half, a.0.max(b.0) + half);
+                let (y0, y1) = (a.1.min(b.1) - half, a.1.max(b.1) + half);
+                layer.rect((x0, y0), (x1, y1), 1.0 - gray);
+            }
+            PageMark::Fill { lower_left, upper_right, gray } => {
+                let (a, b) = (to_sub(lower_left.0, lower_left.1), to_sub(upper_right.0, upper_right.1));
+                layer.rect((a.0, b.1), (b.0, a.1), 1.0 - gray);
+            }
+            PageMark::Image { x, y, w: w_mm, h: h_mm, image } => {
+                let (left, top) = (x * px_per_mm, (w.page.height - y - h_mm) * px_per_mm);
+                let (iw, ih) = ((w_mm * px_per_mm).round().max(1.0) as u32, (h_mm * px_per_mm).round().max(1.0) as u32);
+                let scaled = image.resize_exact(iw, ih, ::image::imageops::FilterType::Triangle).to_rgba8();
+                for (ix, iy, pixel) in scaled.enumerate_pixels() {
+                    let (cx, cy) = (left as u32 + ix, top as u32 + iy);
+                    if cx >= width_px || cy >= height_px {
+                        continue;
+                    }
+                    let [r, g, b, a] = pixel.0;
+                    let alpha = a as u32;
+                    let blend = |c: u8| ((c as u32 * alpha + 255 * (255 - alpha)) / 255) as u8;
+                    canvas.put_pixel(cx, cy, ::image::Rgb([blend(r), blend(g), blend(b)]));
+                }
+            }
+        }
+    }
+
+    // Average each pixel's samples and darken the canvas by that much
+    for (px, py, pixel) in canvas.enumerate_pixels_mut() {
+        let mut sum = 0.0;
+        for sy in 0..ss {
+            let row = (py as usize * ss + sy) * layer.width + px as usize * ss;
+            sum += layer.ink[row..row + ss].iter().sum::<f32>();
+        }
+        let keep = 1.0 - sum / (ss * ss) as f32;
+        for channel in pixel.0.iter_mut() {
+            *channel = (*channel as f32 * keep).round() as u8;
+        }
+    }
+
+    let mut png = Vec::new();
+    canvas
+        .write_to(&mut std::io::Cursor::new(&mut png), ::image::ImageFormat::Png)
+        .map_err(|e| format!("Failed to encode preview: {}", e))?;
+    Ok((png, width_px, height_px))
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ExportPreview {
+    /// PNG of the first page
+    pub image: Vec<u8>,
+    pub width: u32,
+    pub height: u32,
+    /// Pages in the full export
+    pub page_count: usize,
+}
+
+/// The first page of an export as a PNG, for showing before the save
+/// dialog. "pdf" is drawn from the same layout `export_pdf` writes, with
+/// the same `font` and `options`. "docx" has no fixed layout until a word
+/// processor opens it, so its thumbnail is that page layout without
+/// running headers. `width` defaults to 600 px.
+#[tauri::command]
+pub async fn preview_export(
+    app: tauri::AppHandle,
+    html_content: String,
+    format: String,
+    title: Option<String>,
+    font: Option<PdfFontConfig>,
+    options: Option<PdfExportOptions>,
+    width: Option<u32>,
+) -> Result<ExportPreview, AppError> {
+    let options = match format.as_str() {
+        "pdf" => options.unwrap_or_default(),
+        "docx" => PdfExportOptions::default(),
+        other => return Err(AppError::validation(format!("No preview for '{}' exports", other))),
+    };
+    options.page()?;
+    let title = title.unwrap_or_default();
+    let width = width.unwrap_or(PREVIEW_WIDTH_PX).clamp(120, 1600);
+    let images_dir = images::images_dir(&app).ok();
+    let fonts = match (&font, format.as_str()) {
+        (Some(config), "pdf") => resolve_pdf_fonts(&app, config)?,
+        _ => None,
+    };
+    let faces = preview_faces(&app, fonts.as_ref());
+
+    let preview = tokio::task::spawn_blocking(move || {
+        let writer = lay_out_pdf(&title, &html_content, images_dir.as_deref(), fonts.as_ref(), options)?;
+        let (image, width, height) = render_first_page(&writer, &faces, width)?;
+        Ok::<_, AppError>(ExportPreview { image, width, height, page_count: writer.pages.len() })
+    })
+    .await
+    .map_err(|e| format!("Preview task failed: {}", e))??;
+    Ok(preview)
+}
+
+// ---------------------------------------------------------------------------
+// EPUB export
+// ---------------------------------------------------------------------------
+
+struct EpubChapter {
+    title: String,
+    body: String,
+}
+
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn inline_text(inlines: &[InlineNode]) -> String {
+    inlines.iter().map(|i| i.text.as_str()).collect::<String>().trim().to_string()
+}
+
+/// A list, with its nested lists inside their items.
+fn list_to_xhtml(list: &HtmlNode) -> String {
+    let (tag, attrs, items) = match list {
+        HtmlNode::UnorderedList { items } => ("ul", "", items),
+        HtmlNode::OrderedList { items } => ("ol", "", items),
+        HtmlNode::TaskList { items } => ("ul", " style=\"list-style:none\"", items),
+        _ => return String::new(),
+    };
+    let mut out = format!("<{}{}>", tag, attrs);
+    for item in items {
+        out.push_str(&format!("<li>{}{}", checkbox_prefix(item.checked), inlines_to_xhtml(&item.children)));
+        for sublist in &item.sublists {
+            out.push_str(&list_to_xhtml(sublist));
+        }
+        out.push_str("</li>");
+    }
+    out.push_str(&format!("</{}>", tag));
+    out
+}
+
+/// "☐ " or "☑ " before a task list item's text in HTML exports.
+fn checkbox_prefix(checked: Option<bool>) -> &'static str {
+    match checked {
+        Some(true) => "\u{2611} ",
+        Some(false) => "\u{2610} ",
+        None => "",
+    }
+}
+
+fn inlines_to_xhtml(inlines: &[InlineNode]) -> String {
+    let mut out = String::new();
+    for inline in inlines {
+        if inline.footnote.is_some() {
+            out.push_str(&format!("<sup>{}</sup>", xml_escape(&inline.text)));
+            continue;
+        }
+        let mut text = xml_escape(&inline.text);
+        if inline.code {
+            text = format!("<code>{}</code>", text);
+        }
+        if inline.underline {
+            text = format!("<u>{}</u>", text);
+        }
+        if inline.italic {
+            text = format!("<em>{}</em>", text);
+        }
+        if inline.bold {
+            text = format!("<strong>{}</strong>", text);
+        }
+        out.push_str(&text);
+    }
+    out
+}
+
+/// Split at H1/H2 into chapters. Content before the first heading becomes
+/// a chapter named after the book. Embedded PNG/JPEG data URIs are pulled
+/// out into `images` as (file name, media type, bytes).
+fn build_epub_chapters(
+    title: &str,
+    nodes: &[HtmlNode],
+    images: &mut Vec<(String, &'static str, Vec<u8>)>,
+) -> Vec<EpubChapter> {
+    let mut chapters: Vec<EpubChapter> = Vec::new();
+    let mut current = EpubChapter { title: title.to_string(), body: String::new() };
+
+    for node in nodes {
+        match node {
+            HtmlNode::Heading { level, children } if *level <= 2 => {
+                if !current.body.is_empty() {
+                    chapters.push(current);
+                }
+                let heading = inline_text(children);
+                current = EpubChapter {
+                    title: if heading.is_empty() { title.to_string() } else { heading },
+                    body: format!("<h{0}>{1}</h{0}>", level, inlines_to_xhtml(children)),
+                };
+            }
+            HtmlNode::Heading { level, children } => {
+                current.body.push_str(&format!("<h{0}>{1}</h{0}>", level, inlines_to_xhtml(children)));
+            }
+            HtmlNode::Paragraph { children } => {
+                current.body.push_str(&format!("<p>{}</p>", inlines_to_xhtml(children)));
+            }
+            HtmlNode::UnorderedList { .. } | HtmlNode::OrderedList { .. } | HtmlNode::TaskList { .. } => {
+                current.body.push_str(&list_to_xhtml(node));
+            }
+            HtmlNode::Blockquote { children } => {
+                current.body.push_str(&format!("<blockquote><p>{}</p></blockquote>", inlines_to_xhtml(children)));
+            }
+            HtmlNode::CodeBlock { text, .. } => {
+                current.body.push_str(&format!("<pre><code>{}</code></pre>", xml_escape(text)));
+            }
+            HtmlNode::HorizontalRule => current.body.push_str("<hr/>"),
+            HtmlNode::Table { rows, header_rows } => {
+                current.body.push_str("<table>");
+                for (r, row) in rows.iter().enumerate() {
+                    let tag = if r < *header_rows { "th" } else { "td" };
+                    current.body.push_str("<tr>");
+                    for cell in row {
+                        current.body.push_str(&format!("<{0}>{1}</{0}>", tag, inlines_to_xhtml(cell)));
+                    }
+                    current.body.push_str("</tr>");
+                }
+                current.body.push_str("</table>");
+            }
+            HtmlNode::Image { src, alt } => {
+                let embedded = [("data:image/png;base64,", "image/png", "png"), ("data:image/jpeg;base64,", "image/jpeg", "jpg")]
+                    .iter()
+                    .find_map(|(prefix, media_type, ext)| {
+                        let data = src.strip_prefix(prefix)?;
+                        let bytes = base64::engine::general_purpose::STANDARD.decode(data).ok()?;
+                        Some((format!("image{}.{}", images.len() + 1, ext), *media_type, bytes))
+                    });
+                match embedded {
+                    Some((name, media_type, bytes)) => {
+                        current.body.push_str(&format!(
+                            "<p><img src=\"images/{}\" alt=\"{}\"/></p>",
+                            name,
+                            xml_escape(alt)
+                        ));
+                        images.push((name, media_type, bytes));
+                    }
+                    // Remote images would need network access in the reader
+                    None => {
+                        let display = if alt.is_empty() { "[Image]".to_string() } else { format!("[Image: {}]", alt) };
+                        current.body.push_str(&format!("<p><em>{}</em></p>", xml_escape(&display)));
+                    }
+                }
+            }
+        }
+    }
+    // Footnotes as endnotes of the last chapter
+    let notes = footnotes(nodes);
+    if !notes.is_empty() {
+        current.body.push_str("<hr/><ol class=\"notes\">");
+        for footnote in notes {
+            current.body.push_str(&format!("<li value=\"{}\">{}</li>", footnote.number, inlines_to_xhtml(&footnote.note)));
+        }
+        current.body.push_str("</ol>");
+    }
+    if !current.body.is_empty() || chapters.is_empty() {
+        chapters.push(current);
+    }
+    chapters
+}
+
+fn chapter_xhtml(chapter: &EpubChapter) -> String {
+    format!(
+        "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<!DOCTYPE html>\n<html xmlns=\"http://www.w3.org/1999/xhtml\" xmlns:epub=\"http://www.idpf.org/2007/ops\"><head><title>{}</title><link rel=\"stylesheet\" type=\"text/css\" href=\"style.css\"/></head><body>{}</body></html>",
+        xml_escape(&chapter.title),
+        chapter.body
+    )
+}
+
+const EPUB_CSS: &str = "body{font-family:Georgia,serif;line-height:1.5}h1,h2,h3{font-family:Helvetica,Arial,sans-serif}pre{white-space:pre-wrap}blockquote{margin-left:1.5em;font-style:italic}img{max-width:100%}table{border-collapse:collapse}td{border:1px solid #ccc;padding:4px}";
+
+/// Build an EPUB3 file: one XHTML chapter per H1/H2 section plus a nav
+/// document, with embedded images copied into the package.
+fn build_epub(title: &str, html: &str) -> Result<Vec<u8>, String> {
+    use std::io::Write;
+
+    let nodes = parse_html(html);
+    let mut images = Vec::new();
+    let chapters = build_epub_chapters(title, &nodes, &mut images);
+    let book_id = uuid::Uuid::new_v4();
+    let modified = Utc::now().format("%Y-%m-%dT%H:%M:%SZ");
+
+    let mut manifest = String::from(
+        "<item id=\"nav\" href=\"nav.xhtml\" media-type=\"application/xhtml+xml\" properties=\"nav\"/><item id=\"css\" href=\"style.css\" media-type=\"text/css\"/>",
+    );
+    let mut spine = String::new();
+    let mut nav_items = String::new();
+    for (i, chapter) in chapters.iter().enumerate() {
+        manifest.push_str(&format!(
+            "<item id=\"ch{0}\" href=\"chapter{0}.xhtml\" media-type=\"application/xhtml+xml\"/>",
+            i + 1
+        ));
+        spine.push_str(&format!("<itemref idref=\"ch{}\"/>", i + 1));
+        nav_items.push_str(&format!("<li><a href=\"chapter{}.xhtml\">{}</a></li>", i + 1, xml_escape(&chapter.title)));
+    }
+    for (i, (name, media_type, _)) in images.iter().enumerate() {
+        manifest.push_str(&format!("<item id=\"img{}\" href=\"images/{}\" media-type=\"{}\"/>", i + 1, name, media_type));
+    }
+
+    let opf = format!(
+        "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<package xmlns=\"http://www.idpf.org/2007/opf\" version=\"3.0\" unique-identifier=\"book-id\"><metadata xmlns:dc=\"http://purl.org/dc/elements/1.1/\"><dc:identifier id=\"book-id\">urn:uuid:{}</dc:identifier><dc:title>{}</dc:title><dc:language>en</dc:language><meta property=\"dcterms:modified\">{}</meta></metadata><manifest>{}</manifest><spine>{}</spine></package>",
+        book_id,
+        xml_escape(title),
+        modified,
+        manifest,
+        spine
+    );
+    let nav = format!(
+        "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<!DOCTYPE html>\n<html xmlns=\"http://www.w3.org/1999/xhtml\" xmlns:epub=\"http://www.idpf.org/2007/ops\"><head><title>{0}</title></head><body><nav epub:type=\"toc\" id=\"toc\"><h1>{0}</h1><ol>{1}</ol></nav></body></html>",
+        xml_escape(title),
+        nav_items
+    );
+    let container = "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<container version=\"1.0\" xmlns=\"urn:oasis:names:tc:opendocument:xmlns:container\"><rootfiles><rootfile full-path=\"OEBPS/content.opf\" media-type=\"application/oebps-package+xml\"/></rootfiles></container>";
+
+    let mut buf = Vec::new();
+    {
+        let mut zip = zip::ZipWriter::new(std::io::Cursor::new(&mut buf));
+        let err = |e: zip::result::ZipError| format!("Failed to build EPUB: {}", e);
+        let io_err = |e: std::io::Error| format!("Failed to build EPUB: {}", e);
+        // The mimetype entry must come first and be stored uncompressed
+        let stored = zip::write::FileOptions::default().compression_method(zip::CompressionMethod::Stored);
+        let deflated = zip::write::FileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+        zip.start_file("mimetype", stored).map_err(err)?;
+        zip.write_all(b"application/epub+zip").map_err(io_err)?;
+
+        let mut entries: Vec<(String, Vec<u8>)> = vec![
+            ("META-INF/container.xml".to_string(), container.as_bytes().to_vec()),
+            ("OEBPS/content.opf".to_string(), opf.into_bytes()),
+            ("OEBPS/nav.xhtml".to_string(), nav.into_bytes()),
+            ("OEBPS/style.css".to_string(), EPUB_CSS.as_bytes().to_vec()),
+        ];
+        for (i, chapter) in chapters.iter().enumerate() {
+            entries.push((format!("OEBPS/chapter{}.xhtml", i + 1), chapter_xhtml(chapter).into_bytes()));
+        }
+        for (name, _, bytes) in images {
+            entries.push((format!("OEBPS/images/{}", name), bytes));
+        }
+        for (name, bytes) in entries {
+            zip.start_file(name, deflated).map_err(err)?;
+            zip.write_all(&bytes).map_err(io_err)?;
+        }
+        zip.finish().map_err(err)?;
+    }
+    Ok(buf)
+}
+
+// ---------------------------------------------------------------------------
+// Markdown export / import
+// ---------------------------------------------------------------------------
+
+/// Markdown converted to editor HTML. `title` comes from YAML front matter
+/// or a leading H1, which is then dropped from the body.
+#[derive(Debug, Serialize, Clone)]
+pub struct MarkdownImport {
+    pub title: Option<String>,
+    pub html_content: String,
+}
+
+fn escape_markdown(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '\\' | '*' | '_' | '`' | '[' | ']' | '<' | '|') {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
+
+/// Wrap `text` in `marker`, keeping edge whitespace outside so the
+/// delimiters still count as emphasis.
+fn wrap_marker(text: &str, marker: &str) -> String {
+    let core = text.trim();
+    if core.is_empty() {
+        return text.to_string();
+    }
+    let lead = &text[..text.len() - text.trim_start().len()];
+    let trail = &text[text.trim_end().len()..];
+    format!("{}{}{}{}{}", lead, marker, core, marker, trail)
+}
+
+fn inline_markdown(node: &InlineNode) -> String {
+    if let Some(footnote) = &node.footnote {
+        return format!("[^{}]", footnote.number);
+    }
+    if node.code {
+        let fence = if node.text.contains('`') { "``" } else { "`" };
+        return format!("{0}{1}{0}", fence, node.text);
+    }
+    let mut text = escape_markdown(&node.text).replace('\n', "  \n");
+    // Links are underlined by the parser; that's not the author's underline
+    if node.underline && node.href.is_none() {
+        text = format!("<u>{}</u>", text);
+    }
+    if node.italic {
+        text = wrap_marker(&text, "*");
+    }
+    if node.bold {
+        text = wrap_marker(&text, "**");
+    }
+    text
+}
+
+/// Inline runs as Markdown, merging consecutive runs of one link.
+fn inlines_to_markdown(inlines: &[InlineNode]) -> String {
+    let mut out = String::new();
+    let mut i = 0;
+    while i < inlines.len() {
+        match &inlines[i].href {
+            Some(href) => {
+                let mut label = String::new();
+                while i < inlines.len() && inlines[i].href.as_ref() == Some(href) {
+                    label.push_str(&inline_markdown(&inlines[i]));
+                    i += 1;
+                }
+                out.push_str(&format!("[{}]({})", label.trim(), href.replace(' ', "%20").replace(')', "%29")));
+            }
+            None => {
+                out.push_str(&inline_markdown(&inlines[i]));
+                i += 1;
+            }
+        }
+    }
+    out.trim().to_string()
+}
+
+fn nodes_to_markdown(nodes: &[HtmlNode]) -> String {
+    let mut blocks: Vec<String> = Vec::new();
+    for node in nodes {
+        let block = match node {
+            HtmlNode::Heading { level, children } => {
+                format!("{} {}", "#".repeat((*level).clamp(1, 6) as usize), inlines_to_markdown(children))
+            }
+            HtmlNode::Paragraph { children } => inlines_to_markdown(children),
+            HtmlNode::UnorderedList { .. } | HtmlNode::OrderedList { .. } | HtmlNode::TaskList { .. } => list_lines(node)
+                .iter()
+                .map(|line| {
+                    let marker = match (line.number, line.checked) {
+                        (Some(n), _) => format!("{}.", n),
+                        (None, Some(true)) => "- [x]".to_string(),
+                        (None, Some(false)) => "- [ ]".to_string(),
+                        (None, None) => "-".to_string(),
+                    };
+                    format!("{}{} {}", "    ".repeat(line.depth), marker, inlines_to_markdown(line.children))
+                })
+                .collect::<Vec<_>>()
+                .join("\n"),
+            HtmlNode::Blockquote { children } => inlines_to_markdown(children)
+                .lines()
+                .map(|line| format!("> {}", line).trim_end().to_string())
+                .collect::<Vec<_>>()
+                .join("\n"),
+            HtmlNode::CodeBlock { text, language } => {
+                let fence = if text.contains("```") { "~~~" } else { "```" };
+                format!("{}{}\n{}\n{}", fence, language.as_deref().unwrap_or(""), text.trim_end_matches('\n'), fence)
+            }
+            HtmlNode::HorizontalRule => "---".to_string(),
+            HtmlNode::Table { rows, .. } => {
+                let columns = rows.iter().map(|r| r.len()).max().unwrap_or(0);
+                if columns == 0 {
+                    continue;
+                }
+                let row_line = |row: &Vec<Vec<InlineNode>>| {
+                    let cells: Vec<String> = (0..columns)
+                        .map(|c| row.get(c).map(|cell| inlines_to_markdown(cell).replace('\n', " ")).unwrap_or_default())
+                        .collect();
+                    format!("| {} |", cells.join(" | "))
+                };
+                // GFM tables always have a header row; the first row is it
+                let mut lines = vec![row_line(&rows[0]), format!("|{}", " --- |".repeat(columns))];
+                lines.extend(rows.iter().skip(1).map(row_line));
+                lines.join("\n")
+            }
+            HtmlNode::Image { src, alt } => format!("![{}]({})", escape_markdown(alt), src.replace(' ', "%20")),
+        };
+        if !block.is_empty() {
+            blocks.push(block);
+        }
+    }
+    let notes: Vec<String> = footnotes(nodes)
+        .iter()
+        .map(|footnote| format!("[^{}]: {}", footnote.number, inlines_to_markdown(&footnote.note).replace('\n', " ")))
+        .collect();
+    if !notes.is_empty() {
+        blocks.push(notes.join("\n"));
+    }
+    blocks.join("\n\n")
+}
+
+pub(crate) fn html_escape_text(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+pub(crate) fn html_escape_attr(text: &str) -> String {
+    html_escape_text(text).replace('"', "&quot;")
+}
+
+/// Index of the `close` delimiter matching an opener, skipping escapes.
+fn find_closing(chars: &[char], from: usize, close: &[char]) -> Option<usize> {
+    let mut i = from;
+    while i + close.len() <= chars.len() {
+        if chars[i] == '\\' {
+            i += 2;
+            continue;
+        }
+        if chars[i..i + close.len()] == *close {
+            return Some(i);
+        }
+        i += 1;
+    }
+    None
+}
+
+/// `[label](url)` starting at `start` (the `[`): (label, url, end).
+fn read_link(chars: &[char], start: usize) -> Option<(String, String, usize)> {
+    let mut depth = 0;
+    let mut i = start;
+    let label_end = loop {
+        match chars.get(i)? {
+            '\\' => i += 1,
+            '[' => depth += 1,
+            ']' => {
+                depth -= 1;
+                if depth == 0 {
+                    break i;
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    };
+    if chars.get(label_end + 1) != Some(&'(') {
+        return None;
+    }
+    let url_end = find_closing(chars, label_end + 2, &[')'])?;
+    let label: String = chars[start + 1..label_end].iter().collect();
+    let target: String = chars[label_end + 2..url_end].iter().collect();
+    // Drop an optional "title" after the URL
+    let url = target.split_whitespace().next().unwrap_or("").trim_matches(['<', '>']).to_string();
+    Some((label, url, url_end + 1))
+}
+
+/// CommonMark inlines to HTML: code spans, links, images, autolinks,
+/// strong/emphasis, ~~strike~~, hard breaks and Obsidian [[wikilinks]]
+/// (kept as their text).
+fn markdown_inline_to_html(text: &str) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let mut out = String::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let rest = |n: usize| chars.get(i + n).copied();
+        match c {
+            '\\' if rest(1).is_some_and(|n| n.is_ascii_punctuation()) => {
+                out.push_str(&html_escape_text(&rest(1).unwrap_or_default().to_string()));
+                i += 2;
+            }
+            '\\' if rest(1) == Some('\n') => {
+                out.push_str("<br>");
+                i += 2;
+            }
+            '`' => {
+                let ticks = chars[i..].iter().take_while(|&&t| t == '`').count();
+                let fence: Vec<char> = vec!['`'; ticks];
+                match find_closing(&chars, i + ticks, &fence) {
+                    Some(end) => {
+                        let code: String = chars[i + ticks..end].iter().collect();
+                        out.push_str(&format!("<code>{}</code>", html_escape_text(code.trim())));
+                        i = end + ticks;
+                    }
+                    None => {
+                        out.push_str(&"`".repeat(ticks));
+                        i += ticks;
+                    }
+                }
+            }
+            '!' if rest(1) == Some('[') => match read_link(&chars, i + 1) {
+                Some((alt, src, end)) => {
+                    out.push_str(&format!("<img src=\"{}\" alt=\"{}\">", html_escape_attr(&src), html_escape_attr(&alt)));
+                    i = end;
+                }
+                None => {
+                    out.push('!');
+                    i += 1;
+                }
+            },
+            '[' if rest(1) == Some('[') => match find_closing(&chars, i + 2, &[']', ']']) {
+                Some(end) => {
+                    let target: String = chars[i + 2..end].iter().collect();
+                    let shown = target.rsplit('|').next().unwrap_or(&target);
+                    out.push_str(&html_escape_text(shown));
+                    i = end + 2;
+                }
+                None => {
+                    out.push('[');
+                    i += 1;
+                }
+            },
+            '[' => match read_link(&chars, i) {
+                Some((label, url, end)) => {
+                    out.push_str(&format!("<a href=\"{}\">{}</a>", html_escape_attr(&url), markdown_inline_to_html(&label)));
+                    i = end;
+                }
+                None => {
+                    out.push('[');
+                    i += 1;
+                }
+            },
+            '<' if chars[i..].iter().collect::<String>().starts_with("<http") => {
+                match find_closing(&chars, i + 1, &['>']) {
+                    Some(end) => {
+                        let url: String = chars[i + 1..end].iter().collect();
+                        out.push_str(&format!("<a href=\"{0}\">{1}</a>", html_escape_attr(&url), html_escape_text(&url)));
+                        i = end + 1;
+                    }
+                    None => {
+                        out.push_str("&lt;");
+                        i += 1;
+                    }
+                }
+            }
+            // Inline HTML the exporter writes for underline
+            '<' if chars[i..].iter().collect::<String>().starts_with("<u>") => {
+                out.push_str("<u>");
+                i += 3;
+            }
+            '<' if chars[i..].iter().collect::<String>().starts_with("</u>") => {
+                out.push_str("</u>");
+                i += 4;
+            }
+            '*' | '_' | '~' => {
+                let run = chars[i..].iter().take_while(|&&m| m == c).count();
+                // Intraword underscores (snake_case) aren't emphasis
+                let intraword = c == '_'
+                    && i > 0
+                    && chars[i - 1].is_alphanumeric()
+                    && chars.get(i + run).is_some_and(|n| n.is_alphanumeric());
+                let (len, tag) = match (c, run) {
+                    ('~', n) if n >= 2 => (2, "s"),
+                    ('~', _) => (0, ""),
+                    (_, n) if n >= 2 => (2, "strong"),
+                    _ => (1, "em"),
+                };
+                let opens = len > 0 && !intraword && chars.get(i + len).is_some_and(|n| !n.is_whitespace());
+                let closing = if opens { find_closing(&chars, i + len, &vec![c; len]) } else { None };
+                match closing {
+                    Some(end) if end > i + len => {
+                        let inner: String = chars[i + len..end].iter().collect();
+                        out.push_str(&format!("<{0}>{1}</{0}>", tag, markdown_inline_to_html(&inner)));
+                        i = end + len;
+                    }
+                    _ => {
+                        out.push_str(&c.to_string().repeat(run));
+                        i += run;
+                    }
+                }
+            }
+            ' ' if chars[i..].iter().take_while(|&&s| s == ' ').count() >= 2
+                && chars.get(i + chars[i..].iter().take_while(|&&s| s == ' ').count()) == Some(&'\n') =>
+            {
+                let spaces = chars[i..].iter().take_while(|&&s| s == ' ').count();
+                out.push_str("<br>");
+                i += spaces + 1;
+            }
+            '\n' => {
+                out.push(' ');
+                i += 1;
+            }
+            _ => {
+                out.push_str(&html_escape_text(&c.to_string()));
+                i += 1;
+            }
+        }
+    }
+    out
+}
+
+fn is_rule(line: &str) -> bool {
+    let compact: String = line.chars().filter(|c| !c.is_whitespace()).collect();
+    compact.len() >= 3
+        && ['-', '*', '_'].iter().any(|&m| compact.chars().all(|c| c == m))
+}
+
+/// Marker of a list item line: (indent, ordered, content start).
+fn list_marker(line: &str) -> Option<(usize, bool, usize)> {
+    let indent = line.len() - line.trim_start().len();
+    let trimmed = &line[indent..];
+    for bullet in ["- ", "* ", "+ "] {
+        if trimmed.starts_with(bullet) {
+            return Some((indent, false, indent + 2));
+        }
+    }
+    let digits = trimmed.chars().take_while(|c| c.is_ascii_digit()).count();
+    if digits > 0 && digits < 10 && (trimmed[digits..].starts_with(". ") || trimmed[digits..].starts_with(") ")) {
+        return Some((indent, true, indent + digits + 2));
+    }
+    None
+}
+
+fn table_cells(line: &str) -> Vec<String> {
+    let trimmed = line.trim().trim_start_matches('|');
+    let trimmed = trimmed.strip_suffix('|').filter(|t| !t.ends_with('\\')).unwrap_or(trimmed);
+    let mut cells = Vec::new();
+    let mut cell = String::new();
+    let mut chars = trimmed.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' if chars.peek() == Some(&'|') => {
+                cell.push('|');
+                chars.next();
+            }
+            '|' => cells.push(std::mem::take(&mut cell).trim().to_string()),
+            _ => cell.push(c),
+        }
+    }
+    cells.push(cell.trim().to_string());
+    cells
+}
 
-                    let layer = w.doc.get_page(w.current_page).get_layer(w.current_layer);
-                    let line = printpdf::Line {
-                        points: vec![
-                            (printpdf::Point::new(Mm(bar_x), Mm(bar_top)), false),
-                            (printpdf::Point::new(Mm(bar_x), Mm(bar_bottom.max(MARGIN_BOTTOM))), false),
-                        ],
-                        is_closed: false,
-                    };
-                    let gray = printpdf::Color::Rgb(Rgb::new(0.6, 0.6, 0.6, None));
-                    layer.set_outline_color(gray);
-                    layer.set_outline_thickness(1.5);
-                    layer.add_line(line);
-                }
-                // Make all children italic
-                let modified: Vec<InlineNode> = children
-                    .iter()
-                    .map(|c| InlineNode {
-                        italic: true,
-                        ..c.clone()
-                    })
-                    .collect();
-                w.write_inline_block(&modified, 11.0, 10.0, None);
-                w.write_spacer(3.0);
+fn is_table_separator(line: &str) -> bool {
+    let cells = table_cells(line);
+    line.contains('-')
+        && cells.iter().all(|c| !c.is_empty() && c.chars().all(|ch| matches!(ch, '-' | ':')))
+}
+
+/// A list starting at `lines[start]`; nested lists come from deeper
+/// indentation. Returns the HTML and the first line after the list.
+fn markdown_list_to_html(lines: &[&str], start: usize) -> (String, usize) {
+    let Some((indent, ordered, _)) = list_marker(lines[start]) else {
+        return (String::new(), start);
+    };
+    let tag = if ordered { "ol" } else { "ul" };
+    let mut html = format!("<{}>", tag);
+    let mut i = start;
+    while i < lines.len() {
+        let Some((item_indent, item_ordered, content_start)) = list_marker(lines[i]) else {
+            break;
+        };
+        if item_indent != indent || item_ordered != ordered {
+            break;
+        }
+        let mut text = lines[i][content_start..].trim().to_string();
+        i += 1;
+        // Lazy continuation lines belong to the item
+        while i < lines.len()
+            && !lines[i].trim().is_empty()
+            && list_marker(lines[i]).is_none()
+            && !lines[i].trim_start().starts_with("```")
+        {
+            text.push('\n');
+            text.push_str(lines[i].trim());
+            i += 1;
+        }
+        html.push_str(&format!("<li><p>{}</p>", markdown_inline_to_html(&text)));
+        if i < lines.len() && list_marker(lines[i]).is_some_and(|(n, _, _)| n > indent) {
+            let (nested, next) = markdown_list_to_html(lines, i);
+            html.push_str(&nested);
+            i = next;
+        }
+        html.push_str("</li>");
+        // A blank line between items keeps the list going
+        if i + 1 < lines.len()
+            && lines[i].trim().is_empty()
+            && list_marker(lines[i + 1]).is_some_and(|(n, o, _)| n == indent && o == ordered)
+        {
+            i += 1;
+        }
+    }
+    html.push_str(&format!("</{}>", tag));
+    (html, i)
+}
+
+fn markdown_to_html(markdown: &str) -> String {
+    let normalized = markdown.replace("\r\n", "\n").replace('\t', "    ");
+    let lines: Vec<&str> = normalized.lines().collect();
+    let mut html = String::new();
+    let mut i = 0;
+    while i < lines.len() {
+        let line = lines[i];
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            i += 1;
+            continue;
+        }
+
+        // Fenced code block
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            let fence = &trimmed[..3];
+            let language = trimmed[3..].trim();
+            let mut code = Vec::new();
+            i += 1;
+            while i < lines.len() && !lines[i].trim_start().starts_with(fence) {
+                code.push(lines[i]);
+                i += 1;
             }
-            HtmlNode::CodeBlock { text } => {
-                w.write_spacer(2.0);
-                let lines = text.lines();
-                for line in lines {
-                    let code_node = InlineNode {
-                        text: line.to_string(),
-                        bold: false,
-                        italic: false,
-                        underline: false,
-                        code: true,
-                    };
-                    w.write_inline_block(&[code_node], 9.0, 6.0, None);
+            i += 1;
+            let class = if language.is_empty() {
+                String::new()
+            } else {
+                format!(" class=\"language-{}\"", html_escape_attr(language.split_whitespace().next().unwrap_or("")))
+            };
+            html.push_str(&format!("<pre><code{}>{}</code></pre>", class, html_escape_text(&code.join("\n"))));
+            continue;
+        }
+
+        // ATX heading
+        let hashes = trimmed.chars().take_while(|&c| c == '#').count();
+        if (1..=6).contains(&hashes) && (trimmed.len() == hashes || trimmed[hashes..].starts_with(' ')) {
+            let text = trimmed[hashes..].trim().trim_end_matches('#').trim();
+            html.push_str(&format!("<h{0}>{1}</h{0}>", hashes, markdown_inline_to_html(text)));
+            i += 1;
+            continue;
+        }
+
+        if is_rule(trimmed) {
+            html.push_str("<hr>");
+            i += 1;
+            continue;
+        }
+
+        if trimmed.starts_with('>') {
+            let mut quoted = Vec::new();
+            while i < lines.len() && lines[i].trim_start().starts_with('>') {
+                let l = lines[i].trim_start()[1..].strip_prefix(' ').unwrap_or(&lines[i].trim_start()[1..]);
+                quoted.push(l.to_string());
+                i += 1;
+            }
+            html.push_str(&format!("<blockquote>{}</blockquote>", markdown_to_html(&quoted.join("\n"))));
+            continue;
+        }
+
+        if list_marker(line).is_some() {
+            let (list, next) = markdown_list_to_html(&lines, i);
+            html.push_str(&list);
+            i = next;
+            continue;
+        }
+
+        // GFM table: header row, separator row, body rows
+        if trimmed.contains('|') && i + 1 < lines.len() && is_table_separator(lines[i + 1]) {
+            html.push_str("<table><tbody><tr>");
+            for cell in table_cells(trimmed) {
+                html.push_str(&format!("<th><p>{}</p></th>", markdown_inline_to_html(&cell)));
+            }
+            html.push_str("</tr>");
+            i += 2;
+            while i < lines.len() && lines[i].contains('|') && !lines[i].trim().is_empty() {
+                html.push_str("<tr>");
+                for cell in table_cells(lines[i]) {
+                    html.push_str(&format!("<td><p>{}</p></td>", markdown_inline_to_html(&cell)));
                 }
-                w.write_spacer(3.0);
+                html.push_str("</tr>");
+                i += 1;
+            }
+            html.push_str("</tbody></table>");
+            continue;
+        }
+
+        // Paragraph: until a blank line or another block starts
+        let mut para = vec![line.trim_start()];
+        i += 1;
+        while i < lines.len() {
+            let next = lines[i];
+            let t = next.trim();
+            if t.is_empty()
+                || t.starts_with('#')
+                || t.starts_with('>')
+                || t.starts_with("```")
+                || t.starts_with("~~~")
+                || is_rule(t)
+                || list_marker(next).is_some()
+            {
+                break;
+            }
+            para.push(next.trim_start());
+            i += 1;
+        }
+        html.push_str(&format!("<p>{}</p>", markdown_inline_to_html(&para.join("\n"))));
+    }
+    html
+}
+
+/// (front matter title, body without the front matter)
+fn split_front_matter(markdown: &str) -> (Option<String>, &str) {
+    let Some(rest) = markdown.strip_prefix("---\n").or_else(|| markdown.strip_prefix("---\r\n")) else {
+        return (None, markdown);
+    };
+    let Some(end) = rest.find("\n---") else {
+        return (None, markdown);
+    };
+    let body = rest[end + 4..].trim_start_matches(['\r', '\n']);
+    let title = rest[..end].lines().find_map(|line| {
+        let value = line.strip_prefix("title:")?.trim();
+        if value.starts_with('"') {
+            serde_json::from_str::<String>(value).ok()
+        } else {
+            Some(value.trim_matches('\'').to_string())
+        }
+    });
+    (title.filter(|t| !t.is_empty()), body)
+}
+
+/// The document as CommonMark (with GFM tables). A non-empty `title` is
+/// written as YAML front matter, which `import_markdown` reads back.
+#[tauri::command]
+pub async fn export_markdown(app: tauri::AppHandle, title: String, html_content: String) -> Result<String, AppError> {
+    record_export(&app, "markdown", &title)?;
+    Ok(markdown_document(&title, &html_content)?)
+}
+
+/// Editor HTML as a Markdown body, without front matter.
+pub(crate) fn html_to_markdown(html: &str) -> String {
+    nodes_to_markdown(&parse_html(html))
+}
+
+fn markdown_document(title: &str, html: &str) -> Result<String, serde_json::Error> {
+    let body = html_to_markdown(html);
+    if title.trim().is_empty() {
+        return Ok(format!("{}\n", body));
+    }
+    Ok(format!("---\ntitle: {}\n---\n\n{}\n", serde_json::to_string(title.trim())?, body))
+}
+
+/// Convert Markdown (e.g. an Obsidian note) to editor HTML.
+#[tauri::command]
+pub async fn import_markdown(markdown: String) -> Result<MarkdownImport, AppError> {
+    Ok(parse_markdown(&markdown))
+}
+
+pub(crate) fn parse_markdown(markdown: &str) -> MarkdownImport {
+    let (mut title, body) = split_front_matter(markdown);
+    let mut body = body.to_string();
+    if title.is_none() {
+        let first = body.lines().find(|l| !l.trim().is_empty()).unwrap_or("");
+        if let Some(heading) = first.trim().strip_prefix("# ") {
+            title = Some(heading.trim().to_string());
+            body = body.replacen(first, "", 1);
+        }
+    }
+    // Markdown may carry raw HTML through
+    MarkdownImport { title, html_content: sanitize_html(&markdown_to_html(&body), None) }
+}
+
+// ---------------------------------------------------------------------------
+// Email HTML export
+// ---------------------------------------------------------------------------
+
+/// Colours and font for `export_email_html`. Colours are CSS hex values.
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct EmailTheme {
+    pub accent_color: String,
+    pub text_color: String,
+    /// Behind the 600px column
+    pub background_color: String,
+    pub content_background: String,
+    /// "sans", "serif" or "mono"
+    pub font: String,
+    /// Shown centred above the content
+    pub logo_url: Option<String>,
+    /// Colour code blocks by their language
+    pub highlight_code: bool,
+}
+
+impl Default for EmailTheme {
+    fn default() -> Self {
+        EmailTheme {
+            accent_color: "#7c3aed".to_string(),
+            text_color: "#1a1a1a".to_string(),
+            background_color: "#f4f4f5".to_string(),
+            content_background: "#ffffff".to_string(),
+            font: "sans".to_string(),
+            logo_url: None,
+            highlight_code: false,
+        }
+    }
+}
+
+const EMAIL_WIDTH_PX: f32 = 600.0;
+/// Content width inside the column's 40px side padding
+const EMAIL_CONTENT_WIDTH_PX: f32 = 520.0;
+const EMAIL_MONO: &str = "'Courier New', Courier, monospace";
+
+/// Inline styles resolved from a theme.
+struct EmailStyle {
+    accent: String,
+    text: String,
+    background: String,
+    content_background: String,
+    font: &'static str,
+    highlight_code: bool,
+}
+
+impl EmailStyle {
+    fn new(theme: &EmailTheme) -> Self {
+        // Anything but a plain colour value could break out of the style attribute
+        let colour = |value: &str, fallback: &str| {
+            if !value.is_empty() && value.chars().all(|c| c.is_ascii_alphanumeric() || c == '#') {
+                value.to_string()
+            } else {
+                fallback.to_string()
+            }
+        };
+        let defaults = EmailTheme::default();
+        EmailStyle {
+            accent: colour(&theme.accent_color, &defaults.accent_color),
+            text: colour(&theme.text_color, &defaults.text_color),
+            background: colour(&theme.background_color, &defaults.background_color),
+            content_background: colour(&theme.content_background, &defaults.content_background),
+            font: match theme.font.as_str() {
+                "serif" => "Georgia, 'Times New Roman', Times, serif",
+                "mono" => EMAIL_MONO,
+                _ => "Helvetica, Arial, sans-serif",
+            },
+            highlight_code: theme.highlight_code,
+        }
+    }
+
+    /// Font declarations repeated on every text element; several clients
+    /// don't inherit them from the containing cell.
+    fn text(&self, size_px: u32) -> String {
+        format!("font-family:{};font-size:{}px;line-height:1.6;color:{};", self.font, size_px, self.text)
+    }
+}
+
+/// A list with its nested lists inside their items; nested lists sit
+/// closer to the item above them than the outer list to the next block.
+fn email_list(list: &HtmlNode, style: &EmailStyle, depth: usize) -> String {
+    let (tag, items) = match list {
+        HtmlNode::UnorderedList { items } => ("ul", items),
+        HtmlNode::OrderedList { items } => ("ol", items),
+        HtmlNode::TaskList { items } => ("ul", items),
+        _ => return String::new(),
+    };
+    let margin = if depth == 0 { "0 0 16px" } else { "8px 0 0" };
+    let markers = if matches!(list, HtmlNode::TaskList { .. }) { "list-style:none;" } else { "" };
+    let mut html = format!("<{} style=\"margin:{};padding-left:24px;{}\">", tag, margin, markers);
+    for item in items {
+        html.push_str(&format!(
+            "<li style=\"margin:0 0 8px;{}\">{}{}",
+            style.text(16),
+            checkbox_prefix(item.checked),
+            email_inlines(&item.children, style)
+        ));
+        for sublist in &item.sublists {
+            html.push_str(&email_list(sublist, style, depth + 1));
+        }
+        html.push_str("</li>");
+    }
+    html.push_str(&format!("</{}>", tag));
+    html
+}
+
+fn email_inlines(inlines: &[InlineNode], style: &EmailStyle) -> String {
+    let mut out = String::new();
+    for inline in inlines {
+        if inline.footnote.is_some() {
+            out.push_str(&format!("<sup style=\"font-size:12px;line-height:0;\">{}</sup>", html_escape_text(&inline.text)));
+            continue;
+        }
+        let mut text = html_escape_text(&inline.text);
+        if inline.code {
+            text = format!(
+                "<code style=\"font-family:{};font-size:14px;background-color:#f4f4f5;padding:1px 4px;\">{}</code>",
+                EMAIL_MONO, text
+            );
+        }
+        if inline.underline {
+            text = format!("<u>{}</u>", text);
+        }
+        if inline.italic {
+            text = format!("<em>{}</em>", text);
+        }
+        if inline.bold {
+            text = format!("<strong>{}</strong>", text);
+        }
+        if let Some(href) = &inline.href {
+            text = format!(
+                "<a href=\"{}\" style=\"color:{};text-decoration:underline;\">{}</a>",
+                html_escape_attr(href),
+                style.accent,
+                text
+            );
+        }
+        out.push_str(&text);
+    }
+    out
+}
+
+/// `src` as something a mail client can load: remote URLs as they are,
+/// local images as a data URI, with the display width to use.
+fn email_image(src: &str, images_dir: Option<&Path>) -> Option<(String, u32)> {
+    if src.starts_with("http://") || src.starts_with("https://") {
+        return Some((src.to_string(), EMAIL_CONTENT_WIDTH_PX as u32));
+    }
+    let loaded = load_image(src, images_dir)?;
+    let (width, _) = fit_within(
+        loaded.image.width() as f32,
+        loaded.image.height() as f32,
+        EMAIL_CONTENT_WIDTH_PX,
+        f32::MAX,
+    );
+    let mime = match ::image::guess_format(&loaded.bytes) {
+        Ok(::image::ImageFormat::Jpeg) => "image/jpeg",
+        Ok(::image::ImageFormat::Gif) => "image/gif",
+        _ => "image/png",
+    };
+    let data = base64::engine::general_purpose::STANDARD.encode(&loaded.bytes);
+    Some((format!("data:{};base64,{}", mime, data), width.round() as u32))
+}
+
+fn email_body(nodes: &[HtmlNode], style: &EmailStyle, images_dir: Option<&Path>) -> String {
+    let mut html = String::new();
+    let block = format!("margin:0 0 16px;{}", style.text(16));
+    for node in nodes {
+        match node {
+            HtmlNode::Heading { level, children } => {
+                let size = match level {
+                    1 => 28,
+                    2 => 22,
+                    3 => 19,
+                    _ => 17,
+                };
+                html.push_str(&format!(
+                    "<h{0} style=\"margin:24px 0 12px;font-family:{1};font-size:{2}px;line-height:1.3;font-weight:bold;color:{3};\">{4}</h{0}>",
+                    level.clamp(&1, &6),
+                    style.font,
+                    size,
+                    style.text,
+                    email_inlines(children, style)
+                ));
+            }
+            HtmlNode::Paragraph { children } => {
+                html.push_str(&format!("<p style=\"{}\">{}</p>", block, email_inlines(children, style)));
+            }
+            HtmlNode::UnorderedList { .. } | HtmlNode::OrderedList { .. } | HtmlNode::TaskList { .. } => {
+                html.push_str(&email_list(node, style, 0));
+            }
+            HtmlNode::Blockquote { children } => {
+                html.push_str(&format!(
+                    "<blockquote style=\"margin:0 0 16px;padding:4px 0 4px 16px;border-left:4px solid {};\"><p style=\"margin:0;{}font-style:italic;\">{}</p></blockquote>",
+                    style.accent,
+                    style.text(16),
+                    email_inlines(children, style)
+                ));
+            }
+            HtmlNode::CodeBlock { text, language } => {
+                let highlighted = match language {
+                    Some(language) if style.highlight_code => highlight_code(text, language),
+                    _ => None,
+                };
+                let code = match highlighted {
+                    Some(lines) => lines
+                        .iter()
+                        .map(|runs| {
+                            runs.iter()
+                                .map(|(run, [r, g, b])| {
+                                    format!("<span style=\"color:#{:02x}{:02x}{:02x};\">{}</span>", r, g, b, html_escape_text(run))
+                                })
+                                .collect::<String>()
+                        })
+                        .collect::<Vec<_>>()
+                        .join("\n"),
+                    None => html_escape_text(text),
+                };
+                html.push_str(&format!(
+                    "<pre style=\"margin:0 0 16px;padding:12px;background-color:#f4f4f5;font-family:{};font-size:14px;line-height:1.5;color:{};white-space:pre-wrap;word-wrap:break-word;\">{}</pre>",
+                    EMAIL_MONO,
+                    style.text,
+                    code
+                ));
             }
             HtmlNode::HorizontalRule => {
-                w.write_spacer(3.0);
-                {
-                    let layer = w.doc.get_page(w.current_page).get_layer(w.current_layer);
-                    let line = printpdf::Line {
-                        points: vec![
-                            (printpdf::Point::new(Mm(MARGIN_LEFT), Mm(w.y_pos)), false),
-                            (
-                                printpdf::Point::new(Mm(A4_WIDTH_MM - MARGIN_RIGHT), Mm(w.y_pos)),
-                                false,
-                            ),
-                        ],
-                        is_closed: false,
+                html.push_str("<hr style=\"border:0;border-top:1px solid #e4e4e7;margin:24px 0;\">");
+            }
+            HtmlNode::Table { rows, header_rows } => {
+                html.push_str(
+                    "<table role=\"presentation\" width=\"100%\" cellpadding=\"0\" cellspacing=\"0\" border=\"0\" style=\"border-collapse:collapse;margin:0 0 16px;\">",
+                );
+                for (r, row) in rows.iter().enumerate() {
+                    let (tag, heading) = if r < *header_rows {
+                        ("th", "background-color:#f4f4f5;font-weight:bold;text-align:left;")
+                    } else {
+                        ("td", "")
                     };
-                    let gray = printpdf::Color::Rgb(Rgb::new(0.75, 0.75, 0.75, None));
-                    layer.set_outline_color(gray);
-                    layer.set_outline_thickness(0.5);
-                    layer.add_line(line);
+                    html.push_str("<tr>");
+                    for cell in row {
+                        html.push_str(&format!(
+                            "<{0} valign=\"top\" style=\"border:1px solid #e4e4e7;padding:8px;{1}{2}\">{3}</{0}>",
+                            tag,
+                            style.text(14),
+                            heading,
+                            email_inlines(cell, style)
+                        ));
+                    }
+                    html.push_str("</tr>");
                 }
-                w.write_spacer(3.0);
+                html.push_str("</table>");
             }
-            HtmlNode::Table { rows } => {
-                // Simple table rendering: render each cell as plain text rows
-                w.write_spacer(2.0);
-                for row_cells in rows {
-                    let mut row_text = String::new();
-                    for (i, cell) in row_cells.iter().enumerate() {
-                        if i > 0 {
-                            row_text.push_str("  |  ");
-                        }
-                        let cell_text: String =
-                            cell.iter().map(|n| n.text.clone()).collect::<Vec<_>>().join("");
-                        row_text.push_str(&cell_text);
-                    }
-                    let node = InlineNode {
-                        text: row_text,
-                        bold: false,
-                        italic: false,
-                        underline: false,
-                        code: false,
-                    };
-                    w.write_inline_block(&[node], 10.0, 0.0, None);
-                    w.write_spacer(1.0);
+            HtmlNode::Image { src, alt } => match email_image(src, images_dir) {
+                Some((src, width)) => html.push_str(&format!(
+                    "<p style=\"margin:0 0 16px;\"><img src=\"{}\" alt=\"{}\" width=\"{}\" style=\"display:block;width:100%;max-width:{}px;height:auto;border:0;outline:none;text-decoration:none;\"></p>",
+                    html_escape_attr(&src),
+                    html_escape_attr(alt),
+                    width,
+                    width
+                )),
+                None if !alt.is_empty() => {
+                    html.push_str(&format!("<p style=\"{}font-style:italic;\">{}</p>", block, html_escape_text(alt)));
                 }
-                w.write_spacer(2.0);
+                None => {}
+            },
+        }
+    }
+    let notes = footnotes(nodes);
+    if !notes.is_empty() {
+        html.push_str("<hr style=\"border:0;border-top:1px solid #e4e4e7;margin:24px 0 16px;width:33%;\">");
+        html.push_str("<ol style=\"margin:0 0 16px;padding-left:24px;\">");
+        for footnote in notes {
+            html.push_str(&format!(
+                "<li value=\"{}\" style=\"margin:0 0 6px;{}\">{}</li>",
+                footnote.number,
+                style.text(14),
+                email_inlines(&footnote.note, style)
+            ));
+        }
+        html.push_str("</ol>");
+    }
+    html
+}
+
+/// A complete email document: nested 100%/600px presentation tables with
+/// every style inlined, which is what ESP editors and mail clients render
+/// consistently.
+fn build_email_html(title: &str, html: &str, theme: &EmailTheme, images_dir: Option<&Path>) -> String {
+    let style = EmailStyle::new(theme);
+    let mut content = String::new();
+    if let Some(logo) = theme.logo_url.as_deref().filter(|l| !l.trim().is_empty()) {
+        content.push_str(&format!(
+            "<p style=\"margin:0 0 24px;text-align:center;\"><img src=\"{}\" alt=\"\" width=\"96\" style=\"width:96px;max-width:96px;height:auto;border:0;\"></p>",
+            html_escape_attr(logo)
+        ));
+    }
+    if !title.trim().is_empty() {
+        content.push_str(&format!(
+            "<h1 style=\"margin:0 0 24px;font-family:{};font-size:32px;line-height:1.25;font-weight:bold;color:{};\">{}</h1>",
+            style.font,
+            style.text,
+            html_escape_text(title.trim())
+        ));
+    }
+    content.push_str(&email_body(&parse_html(html), &style, images_dir));
+
+    format!(
+        "<!DOCTYPE html PUBLIC \"-//W3C//DTD XHTML 1.0 Transitional//EN\" \"http://www.w3.org/TR/xhtml1/DTD/xhtml1-transitional.dtd\">\
+<html xmlns=\"http://www.w3.org/1999/xhtml\"><head>\
+<meta http-equiv=\"Content-Type\" content=\"text/html; charset=UTF-8\">\
+<meta name=\"viewport\" content=\"width=device-width, initial-scale=1.0\">\
+<title>{title}</title></head>\
+<body style=\"margin:0;padding:0;background-color:{background};\">\
+<table role=\"presentation\" width=\"100%\" cellpadding=\"0\" cellspacing=\"0\" border=\"0\" style=\"background-color:{background};\"><tr><td align=\"center\" style=\"padding:24px 12px;\">\
+<table role=\"presentation\" width=\"{width}\" cellpadding=\"0\" cellspacing=\"0\" border=\"0\" style=\"width:100%;max-width:{width}px;background-color:{content_background};\"><tr>\
+<td style=\"padding:32px 40px;{text}\">{content}</td>\
+</tr></table></td></tr></table></body></html>",
+        title = html_escape_text(title),
+        background = style.background,
+        content_background = style.content_background,
+        width = EMAIL_WIDTH_PX as u32,
+        text = style.text(16),
+        content = content,
+    )
+}
+
+/// Email-safe HTML for pasting into an ESP the app doesn't integrate with.
+/// Local images are embedded as data URIs; `theme` defaults to the app's
+/// purple accent on white.
+#[tauri::command]
+pub async fn export_email_html(
+    app: tauri::AppHandle,
+    title: String,
+    html_content: String,
+    theme: Option<EmailTheme>,
+) -> Result<String, AppError> {
+    record_export(&app, "email_html", &title)?;
+    let images_dir = images::images_dir(&app).ok();
+    let theme = theme.unwrap_or_default();
+    let html = tokio::task::spawn_blocking(move || build_email_html(&title, &html_content, &theme, images_dir.as_deref()))
+        .await
+        .map_err(|e| format!("Export task failed: {}", e))?;
+    Ok(html)
+}
+
+// ---------------------------------------------------------------------------
+// Plain-text export
+// ---------------------------------------------------------------------------
+
+/// Line width for plain text when none is given; what mail clients expect
+/// of a text/plain part.
+pub(crate) const DEFAULT_PLAIN_TEXT_WIDTH: usize = 72;
+
+/// Links collected while rendering, printed as numbered footnotes.
+#[derive(Default)]
+struct PlainLinks {
+    urls: Vec<String>,
+}
+
+impl PlainLinks {
+    /// Footnote number for `url`; a URL used twice keeps its first number.
+    fn number(&mut self, url: &str) -> usize {
+        match self.urls.iter().position(|u| u == url) {
+            Some(i) => i + 1,
+            None => {
+                self.urls.push(url.to_string());
+                self.urls.len()
             }
-            HtmlNode::Image { alt, .. } => {
-                let display = if alt.is_empty() {
-                    "[Image]".to_string()
-                } else {
-                    format!("[Image: {}]", alt)
-                };
-                let node = InlineNode {
-                    text: display,
-                    bold: false,
-                    italic: true,
-                    underline: false,
-                    code: false,
+        }
+    }
+}
+
+fn plain_inlines(inlines: &[InlineNode], links: &mut PlainLinks) -> String {
+    let mut out = String::new();
+    for (i, inline) in inlines.iter().enumerate() {
+        // Footnotes share the numbered list at the end with link URLs
+        if let Some(footnote) = &inline.footnote {
+            let note = plain_inlines(&footnote.note, links).replace('\n', " ");
+            out.push_str(&format!(" [{}]", links.number(note.trim())));
+            continue;
+        }
+        out.push_str(&inline.text);
+        let Some(href) = inline.href.as_deref().filter(|h| !h.starts_with('#')) else {
+            continue;
+        };
+        // One marker after the whole link, not after each formatted run
+        if inlines.get(i + 1).is_some_and(|next| next.href.as_deref() == Some(href)) {
+            continue;
+        }
+        let label = inlines[..=i]
+            .iter()
+            .rev()
+            .take_while(|n| n.href.as_deref() == Some(href))
+            .map(|n| n.text.as_str())
+            .collect::<Vec<_>>()
+            .concat();
+        let bare = href.strip_prefix("mailto:").unwrap_or(href);
+        if label.trim() != bare && label.trim() != href {
+            out.push_str(&format!(" [{}]", links.number(href)));
+        }
+    }
+    out
+}
+
+/// Greedy word wrap of `text` (which may hold hard breaks) to `width`
+/// columns, `first` prefixing the first line and `rest` the others. Words
+/// longer than the line, such as URLs, are left whole. A width of 0 only
+/// applies the prefixes.
+fn wrap_plain(text: &str, width: usize, first: &str, rest: &str) -> String {
+    let mut lines: Vec<String> = Vec::new();
+    for hard_line in text.split('\n') {
+        let prefix = if lines.is_empty() { first } else { rest };
+        let mut line = prefix.to_string();
+        let mut line_len = prefix.chars().count();
+        let mut empty = true;
+        for word in hard_line.split_whitespace() {
+            let word_len = word.chars().count();
+            if !empty && width > 0 && line_len + 1 + word_len > width {
+                lines.push(std::mem::replace(&mut line, rest.to_string()));
+                line_len = rest.chars().count();
+                empty = true;
+            }
+            if !empty {
+                line.push(' ');
+                line_len += 1;
+            }
+            line.push_str(word);
+            line_len += word_len;
+            empty = false;
+        }
+        lines.push(line.trim_end().to_string());
+    }
+    lines.join("\n")
+}
+
+/// Editor HTML as wrapped plain text: headings underlined, list markers,
+/// "> " before quotes, code indented and left unwrapped, and link URLs and
+/// footnotes gathered as a numbered list at the end.
+pub(crate) fn plain_text_document(html: &str, width: usize) -> String {
+    let mut links = PlainLinks::default();
+    let mut blocks: Vec<String> = Vec::new();
+    for node in parse_html(html) {
+        let block = match node {
+            HtmlNode::Heading { level, children } => {
+                let text = wrap_plain(&plain_inlines(&children, &mut links), width, "", "");
+                let underline = match level {
+                    1 => '=',
+                    2 => '-',
+                    _ => {
+                        blocks.push(text);
+                        continue;
+                    }
                 };
-                w.write_inline_block(&[node], 10.0, 0.0, None);
-                w.write_spacer(3.0);
+                let len = text.lines().map(|l| l.chars().count()).max().unwrap_or(0);
+                format!("{}\n{}", text, underline.to_string().repeat(len))
+            }
+            HtmlNode::Paragraph { children } => wrap_plain(&plain_inlines(&children, &mut links), width, "", ""),
+            HtmlNode::UnorderedList { .. } | HtmlNode::OrderedList { .. } | HtmlNode::TaskList { .. } => list_lines(&node)
+                .iter()
+                .map(|line| {
+                    // Nested items line up with the text of the item above
+                    let indent = "   ".repeat(line.depth);
+                    let marker = match line.checked {
+                        Some(true) => "[x]".to_string(),
+                        Some(false) => "[ ]".to_string(),
+                        None => ordered_marker(line.number, line.depth).unwrap_or_else(|| "-".to_string()),
+                    };
+                    let first = format!("{}{} ", indent, marker);
+                    let rest = " ".repeat(first.chars().count());
+                    wrap_plain(&plain_inlines(line.children, &mut links), width, &first, &rest)
+                })
+                .collect::<Vec<_>>()
+                .join("\n"),
+            HtmlNode::Blockquote { children } => wrap_plain(&plain_inlines(&children, &mut links), width, "> ", "> "),
+            HtmlNode::CodeBlock { text, .. } => text
+                .trim_end_matches('\n')
+                .lines()
+                .map(|line| format!("    {}", line).trim_end().to_string())
+                .collect::<Vec<_>>()
+                .join("\n"),
+            HtmlNode::HorizontalRule => "-".repeat(if width > 0 { width.min(40) } else { 40 }),
+            HtmlNode::Table { rows, header_rows } => {
+                let mut lines: Vec<String> = rows
+                    .iter()
+                    .map(|row| {
+                        row.iter()
+                            .map(|cell| plain_inlines(cell, &mut links).replace('\n', " ").trim().to_string())
+                            .collect::<Vec<_>>()
+                            .join(" | ")
+                    })
+                    .collect();
+                // Underline the headings like a heading
+                if header_rows > 0 && header_rows < lines.len() {
+                    let len = lines[..header_rows].iter().map(|l| l.chars().count()).max().unwrap_or(0);
+                    lines.insert(header_rows, "-".repeat(len));
+                }
+                lines.join("\n")
             }
+            HtmlNode::Image { alt, .. } if !alt.trim().is_empty() => format!("[Image: {}]", alt.trim()),
+            HtmlNode::Image { .. } => continue,
+        };
+        if !block.trim().is_empty() {
+            blocks.push(block);
         }
     }
 
-    w.finish()
+    let mut text = blocks.join("\n\n");
+    if !links.urls.is_empty() {
+        text.push_str("\n\n");
+        let notes: Vec<String> = links
+            .urls
+            .iter()
+            .enumerate()
+            .map(|(i, url)| format!("[{}] {}", i + 1, url))
+            .collect();
+        text.push_str(&notes.join("\n"));
+    }
+    text.push('\n');
+    text
+}
+
+/// Wrapped plain text, e.g. for the text part of a multipart email.
+/// `width` defaults to 72 columns; 0 turns wrapping off.
+#[tauri::command]
+pub async fn export_plaintext(html_content: String, width: Option<usize>) -> Result<String, AppError> {
+    Ok(plain_text_document(&html_content, width.unwrap_or(DEFAULT_PLAIN_TEXT_WIDTH)))
 }
 
 // ---------------------------------------------------------------------------
 // Tauri Commands
 // ---------------------------------------------------------------------------
 
+fn record_export(app: &tauri::AppHandle, format: &str, title: &str) -> Result<(), AppError> {
+    let conn = db::get_db(app)?;
+    audit::record(app, &conn, &format!("export.{}", format), "document", None, Some(title))
+}
+
+/// `toc` adds a table of contents of the H1–H3 headings after the title.
+/// With `document_id`, the export can be repeated from the export history.
+#[tauri::command]
+pub async fn export_docx(
+    app: tauri::AppHandle,
+    title: String,
+    html_content: String,
+    toc: Option<bool>,
+    document_id: Option<String>,
+) -> Result<Vec<u8>, AppError> {
+    record_export(&app, "docx", &title)?;
+    let images_dir = images::images_dir(&app).ok();
+    let toc = toc.unwrap_or(false);
+    let content_hash = export_history::content_hash(&html_content);
+    let history_title = title.clone();
+    let bytes = tokio::task::spawn_blocking(move || build_docx(&title, &html_content, images_dir.as_deref(), toc))
+        .await
+        .map_err(|e| format!("Export task failed: {}", e))??;
+    let settings = export_history::ExportSettings::Docx { toc };
+    export_history::record(&app, document_id.as_deref(), &history_title, &settings, &content_hash, bytes.len());
+    Ok(bytes)
+}
+
+/// `font` embeds a custom font for body text (see `PdfFontConfig`);
+/// without it the built-in Helvetica is used. `options` adds a running
+/// header, footer and page numbers, a cover page and document metadata,
+/// and sets the page size and margins. With `document_id`, the export
+/// can be repeated from the export history.
+#[tauri::command]
+pub async fn export_pdf(
+    app: tauri::AppHandle,
+    title: String,
+    html_content: String,
+    font: Option<PdfFontConfig>,
+    options: Option<PdfExportOptions>,
+    document_id: Option<String>,
+) -> Result<Vec<u8>, AppError> {
+    let options = options.unwrap_or_default();
+    options.page()?;
+    record_export(&app, "pdf", &title)?;
+    let images_dir = images::images_dir(&app).ok();
+    let fonts = match &font {
+        Some(config) => resolve_pdf_fonts(&app, config)?,
+        None => None,
+    };
+    let content_hash = export_history::content_hash(&html_content);
+    let history_title = title.clone();
+    let settings = export_history::ExportSettings::Pdf { font, options: Box::new(options.clone()) };
+    let bytes = tokio::task::spawn_blocking(move || {
+        build_pdf(&title, &html_content, images_dir.as_deref(), fonts.as_ref(), options)
+    })
+        .await
+        .map_err(|e| format!("Export task failed: {}", e))??;
+    export_history::record(&app, document_id.as_deref(), &history_title, &settings, &content_hash, bytes.len());
+    Ok(bytes)
+}
+
 #[tauri::command]
-pub async fn export_docx(title: String, html_content: String) -> Result<Vec<u8>, String> {
-    tokio::task::spawn_blocking(move || build_docx(&title, &html_content))
+pub async fn export_epub(app: tauri::AppHandle, title: String, html_content: String) -> Result<Vec<u8>, AppError> {
+    record_export(&app, "epub", &title)?;
+    let bytes = tokio::task::spawn_blocking(move || build_epub(&title, &html_content))
         .await
-        .map_err(|e| format!("Export task failed: {}", e))?
+        .map_err(|e| format!("Export task failed: {}", e))??;
+    Ok(bytes)
+}
+
+/// File name for an archive entry: `NN-title.ext`, with characters that
+/// trip up common filesystems replaced.
+fn archive_entry_name(index: usize, title: &str, extension: &str) -> String {
+    let cleaned: String = title
+        .trim()
+        .chars()
+        .map(|c| if c.is_alphanumeric() || matches!(c, ' ' | '-' | '_' | '.') { c } else { '-' })
+        .take(80)
+        .collect();
+    let cleaned = cleaned.trim_matches(['.', ' ', '-']);
+    let stem = if cleaned.is_empty() { "Untitled" } else { cleaned };
+    format!("{:02}-{}.{}", index + 1, stem, extension)
+}
+
+/// Archive entry extension for a multi-document export format.
+fn archive_extension(format: &str) -> Result<&'static str, AppError> {
+    match format {
+        "docx" => Ok("docx"),
+        "pdf" => Ok("pdf"),
+        "markdown" => Ok("md"),
+        other => Err(AppError::validation(format!("Unknown export format '{}'", other))),
+    }
+}
+
+/// A document going into an archive, with the attachment files found on disk.
+struct ArchiveDocument {
+    title: String,
+    html: String,
+    attachments: Vec<crate::commands::platform::PublishAttachment>,
+}
+
+fn with_attachments(
+    app: &tauri::AppHandle,
+    conn: &rusqlite::Connection,
+    id: &str,
+    title: String,
+    html: String,
+) -> ArchiveDocument {
+    ArchiveDocument { attachments: attachments::for_publish(app, conn, id), title, html }
+}
+
+/// Attachment path inside the archive: beside the document's own entry,
+/// under "attachments/<entry>/", numbered when two share a name.
+fn attachment_entry_name(document_entry: &str, filename: &str, taken: &mut std::collections::HashSet<String>) -> String {
+    let stem = document_entry.rsplit_once('.').map_or(document_entry, |(stem, _)| stem);
+    let cleaned: String = filename
+        .trim()
+        .chars()
+        .map(|c| if c.is_alphanumeric() || matches!(c, ' ' | '-' | '_' | '.') { c } else { '-' })
+        .take(120)
+        .collect();
+    let cleaned = cleaned.trim_matches(['.', ' ']);
+    let name = if cleaned.is_empty() { "attachment" } else { cleaned };
+    let mut candidate = name.to_string();
+    let mut n = 1;
+    while !taken.insert(candidate.clone()) {
+        n += 1;
+        candidate = match name.rsplit_once('.') {
+            Some((base, ext)) => format!("{} ({}).{}", base, n, ext),
+            None => format!("{} ({})", name, n),
+        };
+    }
+    format!("attachments/{}/{}", stem, candidate)
+}
+
+/// One ZIP of `documents` rendered as `extension`, each followed by its
+/// attachments, checking for cancellation and reporting progress after
+/// each document.
+fn build_archive(
+    ctx: &JobContext,
+    documents: &[ArchiveDocument],
+    extension: &str,
+    images_dir: Option<&Path>,
+) -> Result<Vec<u8>, String> {
+    use std::io::Write;
+
+    let mut buf = Vec::new();
+    {
+        let mut zip = zip::ZipWriter::new(std::io::Cursor::new(&mut buf));
+        let err = |e: zip::result::ZipError| format!("Failed to build archive: {}", e);
+        // DOCX and PDF are already compressed
+        let method = if extension == "md" { zip::CompressionMethod::Deflated } else { zip::CompressionMethod::Stored };
+        let options = zip::write::FileOptions::default().compression_method(method);
+        for (i, ArchiveDocument { title, html, attachments }) in documents.iter().enumerate() {
+            ctx.check()?;
+            let bytes = match extension {
+                "docx" => build_docx(title, html, images_dir, false)?,
+                "pdf" => {
+                    // Same running header as a single export from the editor
+                    let options = PdfExportOptions {
+                        header_text: Some(title.clone()),
+                        footer_text: None,
+                        page_numbers: true,
+                        ..Default::default()
+                    };
+                    build_pdf(title, html, images_dir, None, options).map_err(|e| e.to_string())?
+                }
+                _ => markdown_document(title, html).map_err(|e| e.to_string())?.into_bytes(),
+            };
+            let entry = archive_entry_name(i, title, extension);
+            zip.start_file(entry.as_str(), options).map_err(err)?;
+            zip.write_all(&bytes).map_err(|e| format!("Failed to build archive: {}", e))?;
+
+            let mut taken = std::collections::HashSet::new();
+            for attachment in attachments {
+                let file = std::fs::read(&attachment.path)
+                    .map_err(|e| format!("Failed to read attachment '{}': {}", attachment.filename, e))?;
+                let name = attachment_entry_name(&entry, &attachment.filename, &mut taken);
+                // Media is usually compressed already
+                let stored = zip::write::FileOptions::default().compression_method(zip::CompressionMethod::Stored);
+                zip.start_file(name, stored).map_err(err)?;
+                zip.write_all(&file).map_err(|e| format!("Failed to build archive: {}", e))?;
+            }
+            ctx.progress(i + 1);
+        }
+        zip.finish().map_err(err)?;
+    }
+    Ok(buf)
+}
+
+/// Every document in a project rendered to `format` ("docx", "pdf" or
+/// "markdown") and returned as one ZIP, oldest document first, with each
+/// document's attachments under "attachments/". Runs on the
+/// export queue; pass `job_id` to follow "export:progress" or cancel it.
+#[tauri::command]
+pub async fn export_project_archive(
+    app: tauri::AppHandle,
+    project_id: String,
+    format: String,
+    job_id: Option<String>,
+) -> Result<Vec<u8>, AppError> {
+    let extension = archive_extension(&format)?;
+    let (project, documents) = {
+        let conn = db::get_db(&app)?;
+        let project = load_project(&conn, &project_id)?;
+        let mut stmt = conn.prepare(
+            "SELECT id, title, html_content FROM documents WHERE project_id = ?1 ORDER BY created_at ASC",
+        )?;
+        let rows = stmt.query_map(rusqlite::params![project_id], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get::<_, String>(2)?))
+        })?;
+        let documents: Vec<ArchiveDocument> = rows
+            .filter_map(|r| r.ok())
+            .map(|(id, title, html)| with_attachments(&app, &conn, &id, title, html))
+            .collect();
+        audit::record(&app, &conn, &format!("export.project_{}", format), "project", Some(&project_id), Some(&project.name))?;
+        (project, documents)
+    };
+    if documents.is_empty() {
+        return Err(AppError::validation(format!("Project '{}' has no documents to export", project.name)));
+    }
+
+    let images_dir = images::images_dir(&app).ok();
+    let total = documents.len();
+    export_jobs::run_export_job(&app, job_id, "project_archive", &project.name, &format, total, move |ctx| {
+        build_archive(ctx, &documents, extension, images_dir.as_deref())
+    })
+    .await
 }
 
+/// The given documents as one ZIP in `format`, in the order passed. Runs
+/// on the export queue like `export_project_archive`.
 #[tauri::command]
-pub async fn export_pdf(title: String, html_content: String) -> Result<Vec<u8>, String> {
-    tokio::task::spawn_blocking(move || build_pdf(&title, &html_content))
-        .await
-        .map_err(|e| format!("Export task failed: {}", e))?
+pub async fn export_documents_archive(
+    app: tauri::AppHandle,
+    document_ids: Vec<String>,
+    format: String,
+    job_id: Option<String>,
+) -> Result<Vec<u8>, AppError> {
+    let extension = archive_extension(&format)?;
+    if document_ids.is_empty() {
+        return Err(AppError::validation("Select documents to export"));
+    }
+    let documents = {
+        let conn = db::get_db(&app)?;
+        let documents = document_ids
+            .iter()
+            .map(|id| {
+                conn.query_row(
+                    "SELECT title, html_content FROM documents WHERE id = ?1",
+                    rusqlite::params![id],
+                    |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)),
+                )
+                .map(|(title, html)| with_attachments(&app, &conn, id, title, html))
+                .map_err(|_| AppError::not_found(format!("Document '{}' not found", id)))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let details = format!("{} documents", documents.len());
+        audit::record(&app, &conn, &format!("export.documents_{}", format), "document", None, Some(&details))?;
+        documents
+    };
+
+    let images_dir = images::images_dir(&app).ok();
+    let label = format!("{} documents", documents.len());
+    let total = documents.len();
+    export_jobs::run_export_job(&app, job_id, "documents_archive", &label, &format, total, move |ctx| {
+        build_archive(ctx, &documents, extension, images_dir.as_deref())
+    })
+    .await
 }
 
 // ---------------------------------------------------------------------------
 // Document commands — SQLite backed
 // ---------------------------------------------------------------------------
 
+/// Optimistic concurrency for document saves. When the caller read
+/// `expected` but the row has since moved on, the incoming edit is kept as
+/// a new "(conflict copy)" document instead of overwriting the other
+/// editor's work, and a `Conflict` error describes both sides.
+fn check_document_version(
+    conn: &rusqlite::Connection,
+    id: &str,
+    expected: Option<i64>,
+    title: &str,
+    content: &str,
+    html_content: &str,
+    word_count: i64,
+) -> Result<(), AppError> {
+    let Some(expected) = expected else { return Ok(()) };
+    let current: Option<(i64, String, Option<String>, String)> = conn
+        .query_row(
+            "SELECT COALESCE(version, 0), updated_at, project_id, workspace_id FROM documents WHERE id = ?1",
+            rusqlite::params![id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+        )
+        .ok();
+    let Some((current_version, updated_at, project_id, workspace_id)) = current else {
+        return Ok(());
+    };
+    if current_version == expected {
+        return Ok(());
+    }
+
+    let copy_id = uuid::Uuid::new_v4().to_string();
+    let copy_title = format!("{} (conflict copy)", if title.is_empty() { "Untitled" } else { title });
+    let now = Utc::now().to_rfc3339();
+    conn.execute(
+        "INSERT INTO documents (id, title, content, html_content, project_id, status, word_count, character_count, version, workspace_id, created_at, updated_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, 'draft', ?6, 0, 1, ?8, ?7, ?7)",
+        rusqlite::params![copy_id, copy_title, content, html_content, project_id, word_count, now, workspace_id],
+    )
+    .map_err(|e| format!("Failed to store conflict copy: {}", e))?;
+    db::log_activity(
+        conn,
+        "document.conflict",
+        "document",
+        Some(id),
+        Some(&format!("Expected version {}, found {}; kept edit as {}", expected, current_version, copy_id)),
+    );
+
+    Err(AppError::new(
+        ErrorKind::Conflict,
+        format!(
+            "This document was changed elsewhere (version {} is now {}). Your edit was saved as \"{}\".",
+            expected, current_version, copy_title
+        ),
+    )
+    .with_details(serde_json::json!({
+        "document_id": id,
+        "expected_version": expected,
+        "current_version": current_version,
+        "updated_at": updated_at,
+        "conflict_copy_id": copy_id,
+        "conflict_copy_title": copy_title,
+    })))
+}
+
+/// Saves the document and snapshots it, returning the new edit version.
+/// Pass the version last read as `expected_version` to detect conflicting
+/// writes; omit it to overwrite unconditionally.
 #[tauri::command]
 pub async fn save_document(
     app: tauri::AppHandle,
@@ -1383,10 +6070,14 @@ pub async fn save_document(
     title: String,
     content: String,
     html_content: String,
-) -> Result<(), String> {
+    expected_version: Option<i64>,
+) -> Result<i64, AppError> {
     let conn = db::get_db(&app)?;
     let now = Utc::now().to_rfc3339();
-    let wc = count_words(&html_content) as i64;
+    let wc = text::count_html(&html_content, text::count_mode(&app)) as i64;
+
+    check_document_version(&conn, &id, expected_version, &title, &content, &html_content, wc)?;
+    let previous_words = saved_word_count(&conn, &id);
 
     // Check if exists to preserve created_at
     let existing_created: Option<String> = conn
@@ -1410,39 +6101,56 @@ pub async fn save_document(
     let new_version = current_version + 1;
 
     conn.execute(
-        "INSERT OR REPLACE INTO documents (id, title, content, html_content, project_id, status, word_count, character_count, version, created_at, updated_at)
+        "INSERT INTO documents (id, title, content, html_content, project_id, status, word_count, character_count, version, workspace_id, created_at, updated_at)
          VALUES (?1, ?2, ?3, ?4,
                  COALESCE((SELECT project_id FROM documents WHERE id = ?1), NULL),
                  COALESCE((SELECT status FROM documents WHERE id = ?1), 'draft'),
-                 ?5, 0, ?6, ?7, ?8)",
-        rusqlite::params![id, title, content, html_content, wc, new_version, created_at, now],
+                 ?5, 0, ?6, ?9, ?7, ?8)
+         ON CONFLICT(id) DO UPDATE SET title = excluded.title, content = excluded.content,
+             html_content = excluded.html_content, word_count = excluded.word_count,
+             character_count = excluded.character_count, version = excluded.version, updated_at = excluded.updated_at",
+        rusqlite::params![id, title, content, html_content, wc, new_version, created_at, now, workspaces::current(&app)],
     )
     .map_err(|e| format!("Failed to save document: {}", e))?;
 
-    // Save version snapshot
-    conn.execute(
-        "INSERT INTO document_versions (document_id, title, content, html_content, version, created_at)
-         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
-        rusqlite::params![id, title, content, html_content, new_version, now],
-    ).ok();
+    // Save version snapshot, at most once per configured interval
+    if storage::snapshot_due(&app, &conn, &id) {
+        conn.execute(
+            "INSERT INTO document_versions (document_id, title, content, html_content, version, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            rusqlite::params![id, title, content, html_content, new_version, now],
+        ).ok();
+    }
 
-    // Keep only last 50 versions
+    // Keep only the newest versions
     conn.execute(
-        "DELETE FROM document_versions WHERE document_id = ?1 AND id NOT IN (SELECT id FROM document_versions WHERE document_id = ?1 ORDER BY version DESC LIMIT 50)",
-        rusqlite::params![id],
+        "DELETE FROM document_versions WHERE document_id = ?1 AND id NOT IN (SELECT id FROM document_versions WHERE document_id = ?1 ORDER BY version DESC LIMIT ?2)",
+        rusqlite::params![id, storage::max_versions(&app)],
     ).ok();
 
+    links::index_document(&conn, &id, &html_content);
     db::log_activity(&conn, "document.saved", "document", Some(&id), None);
+    analytics::record_words(&conn, &id, wc - previous_words);
+    focus::track_words(&app, &conn, &id, wc - previous_words);
+    track_goal(&app, &conn, &id, wc);
+    storage::check_budget(&app, &conn);
+    windows::document_changed(&app, &id, "saved", Some(new_version), None);
 
-    Ok(())
+    Ok(new_version)
+}
+
+/// Stored word count, 0 for a document not saved before.
+fn saved_word_count(conn: &rusqlite::Connection, id: &str) -> i64 {
+    conn.query_row("SELECT word_count FROM documents WHERE id = ?1", rusqlite::params![id], |row| row.get(0))
+        .unwrap_or(0)
 }
 
 #[tauri::command]
-pub async fn load_document(app: tauri::AppHandle, id: String) -> Result<String, String> {
+pub async fn load_document(app: tauri::AppHandle, id: String) -> Result<String, AppError> {
     let conn = db::get_db(&app)?;
 
     let result = conn.query_row(
-        "SELECT id, title, content, html_content, created_at, updated_at FROM documents WHERE id = ?1",
+        "SELECT id, title, content, html_content, created_at, updated_at, COALESCE(version, 0) FROM documents WHERE id = ?1",
         rusqlite::params![id],
         |row| {
             Ok(StationDocument {
@@ -1453,57 +6161,367 @@ pub async fn load_document(app: tauri::AppHandle, id: String) -> Result<String,
                 html_content: row.get(3)?,
                 created_at: row.get(4)?,
                 updated_at: row.get(5)?,
+                document_version: row.get(6)?,
             })
         },
-    ).map_err(|_| format!("Document '{}' not found", id))?;
+    ).map_err(|_| AppError::not_found(format!("Document '{}' not found", id)))?;
+
+    Ok(serde_json::to_string(&result).map_err(|e| format!("Serialization failed: {}", e))?)
+}
+
+const DOCUMENT_META_COLUMNS: &str = "d.id, d.title, d.created_at, d.updated_at, d.word_count, d.project_id, d.status,
+     d.character_count, d.archived_at, d.target_words, d.pinned_at, d.sort_index";
+
+fn document_meta_from_row(row: &rusqlite::Row) -> rusqlite::Result<DocumentMeta> {
+    let word_count: i64 = row.get(4)?;
+    let target_words: Option<i64> = row.get(9)?;
+    Ok(DocumentMeta {
+        id: row.get(0)?,
+        title: row.get(1)?,
+        created_at: row.get(2)?,
+        updated_at: row.get(3)?,
+        word_count,
+        project_id: row.get(5)?,
+        status: row.get::<_, String>(6).unwrap_or_else(|_| "draft".to_string()),
+        character_count: row.get(7)?,
+        archived_at: row.get(8)?,
+        target_words,
+        goal_progress: goal_progress(word_count, target_words),
+        pinned_at: row.get(10)?,
+        sort_index: row.get(11)?,
+    })
+}
+
+/// Percent of the goal written, to one decimal place.
+fn goal_progress(word_count: i64, target_words: Option<i64>) -> Option<f64> {
+    let target = target_words.filter(|t| *t > 0)?;
+    Some((word_count as f64 * 1000.0 / target as f64).round() / 10.0)
+}
+
+/// Payload of `document:goal_progress`, sent after each save of a
+/// document with a word count goal.
+#[derive(Debug, Serialize, Clone)]
+pub struct GoalProgress {
+    pub document_id: String,
+    pub word_count: i64,
+    pub target_words: i64,
+    pub percent: f64,
+    pub reached: bool,
+}
+
+/// Report goal progress after a save, logging the first time the goal
+/// is met.
+fn track_goal(app: &tauri::AppHandle, conn: &rusqlite::Connection, id: &str, word_count: i64) {
+    let goal: Option<(Option<i64>, Option<String>)> = conn
+        .query_row(
+            "SELECT target_words, goal_reached_at FROM documents WHERE id = ?1",
+            rusqlite::params![id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .ok();
+    let Some((target_words, reached_at)) = goal else { return };
+    let (Some(target_words), Some(percent)) = (target_words, goal_progress(word_count, target_words)) else {
+        return;
+    };
+    let reached = word_count >= target_words;
+    if reached && reached_at.is_none() {
+        conn.execute(
+            "UPDATE documents SET goal_reached_at = ?1 WHERE id = ?2",
+            rusqlite::params![Utc::now().to_rfc3339(), id],
+        )
+        .ok();
+        db::log_activity(
+            conn,
+            "document.goal_reached",
+            "document",
+            Some(id),
+            Some(&format!("{} of {} words", word_count, target_words)),
+        );
+    }
+    let progress = GoalProgress { document_id: id.to_string(), word_count, target_words, percent, reached };
+    app.emit("document:goal_progress", &progress).ok();
+}
 
-    serde_json::to_string(&result).map_err(|e| format!("Serialization failed: {}", e))
+/// Filters for `list_documents`; every field is optional.
+#[derive(Debug, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct DocumentFilter {
+    pub status: Option<String>,
+    pub project_id: Option<String>,
+    pub tag: Option<String>,
+    /// RFC 3339 bounds on updated_at, inclusive
+    pub updated_after: Option<String>,
+    pub updated_before: Option<String>,
+    /// Only archived documents; unarchived ones otherwise
+    pub archived: bool,
+    /// Archived and unarchived together, overriding `archived`
+    pub include_archived: bool,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
 }
 
+/// Documents, pinned first, then most recently updated first; within one
+/// project, in the order set with `reorder_documents`. Archived documents
+/// are left out unless the filter asks for them.
 #[tauri::command]
-pub async fn list_documents(app: tauri::AppHandle) -> Result<Vec<DocumentMeta>, String> {
+pub async fn list_documents(app: tauri::AppHandle, filter: Option<DocumentFilter>) -> Result<Vec<DocumentMeta>, AppError> {
+    let filter = filter.unwrap_or_default();
+    let archived = match (filter.include_archived, filter.archived) {
+        (true, _) => "1",
+        (false, true) => "d.archived_at IS NOT NULL",
+        (false, false) => "d.archived_at IS NULL",
+    };
+    // Manual order only means something among one project's documents
+    let manual = if filter.project_id.is_some() { "d.sort_index IS NULL, d.sort_index, " } else { "" };
     let conn = db::get_db(&app)?;
 
     let mut stmt = conn
-        .prepare(
-            "SELECT id, title, created_at, updated_at, word_count, project_id, status, character_count
-             FROM documents ORDER BY updated_at DESC",
-        )
+        .prepare(&format!(
+            "SELECT {} FROM documents d
+             WHERE {}
+               AND (?1 IS NULL OR d.status = ?1)
+               AND (?2 IS NULL OR d.project_id = ?2)
+               AND (?3 IS NULL OR d.id IN (SELECT document_id FROM document_tags WHERE tag = ?3))
+               AND (?4 IS NULL OR d.updated_at >= ?4)
+               AND (?5 IS NULL OR d.updated_at <= ?5)
+               AND d.workspace_id = ?8
+             ORDER BY d.pinned_at IS NULL, {}d.updated_at DESC LIMIT ?6 OFFSET ?7",
+            DOCUMENT_META_COLUMNS, archived, manual
+        ))
         .map_err(|e| format!("Query failed: {}", e))?;
 
     let rows = stmt
-        .query_map([], |row| {
-            Ok(DocumentMeta {
-                id: row.get(0)?,
-                title: row.get(1)?,
-                created_at: row.get(2)?,
-                updated_at: row.get(3)?,
-                word_count: row.get(4)?,
-                project_id: row.get(5)?,
-                status: row.get::<_, String>(6).unwrap_or_else(|_| "draft".to_string()),
-                character_count: row.get(7)?,
-            })
-        })
+        .query_map(
+            rusqlite::params![
+                filter.status,
+                filter.project_id,
+                filter.tag,
+                filter.updated_after,
+                filter.updated_before,
+                filter.limit.filter(|l| *l > 0).unwrap_or(-1),
+                filter.offset.unwrap_or(0).max(0),
+                workspaces::current(&app)
+            ],
+            document_meta_from_row,
+        )
         .map_err(|e| format!("Query map failed: {}", e))?;
 
     Ok(rows.filter_map(|r| r.ok()).collect())
 }
 
+/// Archive a document, or restore it with `archived: false`. Archived
+/// documents keep their status and schedule; they only drop out of the
+/// default list.
+#[tauri::command]
+pub async fn archive_document(app: tauri::AppHandle, id: String, archived: Option<bool>) -> Result<(), AppError> {
+    let conn = db::get_db(&app)?;
+    let now = Utc::now().to_rfc3339();
+    let archived = archived.unwrap_or(true);
+    let changed = conn
+        .execute(
+            "UPDATE documents SET archived_at = ?1 WHERE id = ?2",
+            rusqlite::params![archived.then_some(&now), id],
+        )
+        .map_err(|e| format!("Failed to archive document: {}", e))?;
+    if changed == 0 {
+        return Err(AppError::not_found(format!("Document '{}' not found", id)));
+    }
+    let action = if archived { "document.archived" } else { "document.unarchived" };
+    db::log_activity(&conn, action, "document", Some(&id), None);
+    windows::document_changed(&app, &id, if archived { "archived" } else { "unarchived" }, None, None);
+    Ok(())
+}
+
+/// Pin a document to the top of the list, or unpin it with `pinned: false`.
+#[tauri::command]
+pub async fn pin_document(app: tauri::AppHandle, id: String, pinned: bool) -> Result<(), AppError> {
+    let conn = db::get_db(&app)?;
+    let now = Utc::now().to_rfc3339();
+    // Re-pinning keeps the original pin time
+    let changed = conn.execute(
+        "UPDATE documents SET pinned_at = CASE WHEN ?1 THEN COALESCE(pinned_at, ?2) END WHERE id = ?3",
+        rusqlite::params![pinned, now, id],
+    )?;
+    if changed == 0 {
+        return Err(AppError::not_found(format!("Document '{}' not found", id)));
+    }
+    windows::document_changed(&app, &id, if pinned { "pinned" } else { "unpinned" }, None, None);
+    Ok(())
+}
+
+/// Set the manual order of a project's documents: `ids` first to last.
+/// Documents of the project left out go after them, most recently updated
+/// first.
+#[tauri::command]
+pub async fn reorder_documents(app: tauri::AppHandle, project_id: String, ids: Vec<String>) -> Result<(), AppError> {
+    let mut conn = db::get_db(&app)?;
+    load_project(&conn, &project_id)?;
+    let tx = conn.transaction()?;
+    tx.execute("UPDATE documents SET sort_index = NULL WHERE project_id = ?1", rusqlite::params![project_id])?;
+    for (index, id) in ids.iter().enumerate() {
+        let changed = tx.execute(
+            "UPDATE documents SET sort_index = ?1 WHERE id = ?2 AND project_id = ?3",
+            rusqlite::params![index as i64, id, project_id],
+        )?;
+        if changed == 0 {
+            // Dropping the transaction keeps the previous order
+            return Err(AppError::validation(format!("Document '{}' isn't in this project", id)));
+        }
+    }
+    tx.commit()?;
+    for id in &ids {
+        windows::document_changed(&app, id, "reordered", None, None);
+    }
+    Ok(())
+}
+
+/// Set a word count goal for a document, or clear it with `None`.
+/// Changing the goal starts tracking it afresh.
+#[tauri::command]
+pub async fn set_document_goal(
+    app: tauri::AppHandle,
+    document_id: String,
+    target_words: Option<i64>,
+) -> Result<DocumentMeta, AppError> {
+    if target_words.is_some_and(|t| t <= 0) {
+        return Err(AppError::validation("A word count goal must be at least 1 word"));
+    }
+    let conn = db::get_db(&app)?;
+    let changed = conn.execute(
+        "UPDATE documents SET target_words = ?1,
+             goal_reached_at = CASE WHEN ?1 IS NOT NULL AND word_count >= ?1 THEN ?2 END
+         WHERE id = ?3",
+        rusqlite::params![target_words, Utc::now().to_rfc3339(), document_id],
+    )?;
+    if changed == 0 {
+        return Err(AppError::not_found(format!("Document '{}' not found", document_id)));
+    }
+    db::log_activity(
+        &conn,
+        "document.goal_set",
+        "document",
+        Some(&document_id),
+        target_words.map(|t| t.to_string()).as_deref(),
+    );
+    Ok(conn.query_row(
+        &format!("SELECT {} FROM documents d WHERE d.id = ?1", DOCUMENT_META_COLUMNS),
+        rusqlite::params![document_id],
+        document_meta_from_row,
+    )?)
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct DocumentSearchHit {
+    pub document: DocumentMeta,
+    /// Matched passage as HTML-escaped text, matches wrapped in `<mark>`
+    pub snippet: String,
+}
+
+/// FTS5 query from free text: each word quoted (so `-`, `:` and quotes in
+/// the input are taken literally), the last one as a prefix so results
+/// follow as-you-type.
+fn fts_query(query: &str) -> Option<String> {
+    let terms: Vec<String> = query
+        .split_whitespace()
+        .map(|t| t.replace('"', ""))
+        .filter(|t| !t.is_empty())
+        .map(|t| format!("\"{}\"", t))
+        .collect();
+    let last = terms.len().checked_sub(1)?;
+    Some(
+        terms
+            .iter()
+            .enumerate()
+            .map(|(i, t)| if i == last { format!("{}*", t) } else { t.clone() })
+            .collect::<Vec<_>>()
+            .join(" "),
+    )
+}
+
+const SNIPPET_OPEN: char = '\u{2}';
+const SNIPPET_CLOSE: char = '\u{3}';
+
+/// A snippet cut from html_content: tags (including ones the cut split)
+/// dropped, text escaped, match markers turned into `<mark>`.
+fn clean_snippet(raw: &str) -> String {
+    let mut text = String::new();
+    let mut in_tag = false;
+    for (i, c) in raw.char_indices() {
+        match c {
+            '<' => in_tag = true,
+            // A '>' before any '<' closes a tag the cut started inside
+            '>' if in_tag || !raw[..i].contains('<') => {
+                if !in_tag {
+                    text.clear();
+                }
+                in_tag = false;
+            }
+            _ if in_tag => {}
+            _ => text.push(c),
+        }
+    }
+    let text = decode_html_entities(&text.split_whitespace().collect::<Vec<_>>().join(" "));
+    html_escape_text(&text)
+        .replace(SNIPPET_OPEN, "<mark>")
+        .replace(SNIPPET_CLOSE, "</mark>")
+}
+
+/// Full-text search over title and body, best matches first (title hits
+/// weigh most). Optionally limited to one project.
+#[tauri::command]
+pub async fn search_documents(
+    app: tauri::AppHandle,
+    query: String,
+    project_id: Option<String>,
+    limit: Option<i64>,
+) -> Result<Vec<DocumentSearchHit>, AppError> {
+    let Some(match_expr) = fts_query(&query) else {
+        return Ok(Vec::new());
+    };
+    let conn = db::get_db(&app)?;
+    let mut stmt = conn.prepare(
+        &format!(
+            "SELECT {}, snippet(documents_fts, 2, char(2), char(3), '…', 16)
+             FROM documents_fts JOIN documents d ON d.rowid = documents_fts.rowid
+             WHERE documents_fts MATCH ?1 AND (?2 IS NULL OR d.project_id = ?2) AND d.workspace_id = ?4
+             ORDER BY bm25(documents_fts, 10.0, 1.0, 1.0) LIMIT ?3",
+            DOCUMENT_META_COLUMNS
+        ),
+    )?;
+    let rows = stmt.query_map(
+        rusqlite::params![match_expr, project_id, limit.unwrap_or(50).clamp(1, 500), workspaces::current(&app)],
+        |row| {
+            Ok(DocumentSearchHit {
+                document: document_meta_from_row(row)?,
+                snippet: clean_snippet(&row.get::<_, String>(12)?),
+            })
+        },
+    )?;
+    Ok(rows.filter_map(|r| r.ok()).collect())
+}
+
 #[tauri::command]
-pub async fn delete_document(app: tauri::AppHandle, id: String) -> Result<(), String> {
+pub async fn delete_document(app: tauri::AppHandle, id: String) -> Result<(), AppError> {
+    permissions::require(&app, Permission::DeleteContent)?;
     let conn = db::get_db(&app)?;
 
     conn.execute("DELETE FROM document_versions WHERE document_id = ?1", rusqlite::params![id]).ok();
     conn.execute("DELETE FROM document_tags WHERE document_id = ?1", rusqlite::params![id]).ok();
     conn.execute("DELETE FROM scheduled_posts WHERE document_id = ?1", rusqlite::params![id]).ok();
+    attachments::delete_for_document(&app, &conn, &id);
+    conn.execute("DELETE FROM podcast_episodes WHERE document_id = ?1", rusqlite::params![id]).ok();
+    links::remove_document(&conn, &id);
     conn.execute("DELETE FROM documents WHERE id = ?1", rusqlite::params![id])
         .map_err(|e| format!("Failed to delete document: {}", e))?;
 
     db::log_activity(&conn, "document.deleted", "document", Some(&id), None);
+    windows::document_changed(&app, &id, "deleted", None, None);
 
     Ok(())
 }
 
+/// Like `save_document` without the version snapshot. Still bumps the edit
+/// version so a second window's stale autosave is caught as a conflict.
 #[tauri::command]
 pub async fn auto_save(
     app: tauri::AppHandle,
@@ -1511,10 +6529,14 @@ pub async fn auto_save(
     title: String,
     content: String,
     html_content: String,
-) -> Result<(), String> {
+    expected_version: Option<i64>,
+) -> Result<i64, AppError> {
     let conn = db::get_db(&app)?;
     let now = Utc::now().to_rfc3339();
-    let wc = count_words(&html_content) as i64;
+    let wc = text::count_html(&html_content, text::count_mode(&app)) as i64;
+
+    check_document_version(&conn, &id, expected_version, &title, &content, &html_content, wc)?;
+    let previous_words = saved_word_count(&conn, &id);
 
     let existing_created: Option<String> = conn
         .query_row(
@@ -1526,18 +6548,31 @@ pub async fn auto_save(
     let created_at = existing_created.unwrap_or_else(|| now.clone());
 
     conn.execute(
-        "INSERT OR REPLACE INTO documents (id, title, content, html_content, project_id, status, word_count, character_count, version, created_at, updated_at)
+        "INSERT INTO documents (id, title, content, html_content, project_id, status, word_count, character_count, version, workspace_id, created_at, updated_at)
          VALUES (?1, ?2, ?3, ?4,
                  COALESCE((SELECT project_id FROM documents WHERE id = ?1), NULL),
                  COALESCE((SELECT status FROM documents WHERE id = ?1), 'draft'),
                  ?5, 0,
-                 COALESCE((SELECT version FROM documents WHERE id = ?1), 1),
-                 ?6, ?7)",
-        rusqlite::params![id, title, content, html_content, wc, created_at, now],
+                 COALESCE((SELECT version FROM documents WHERE id = ?1), 0) + 1,
+                 ?8, ?6, ?7)
+         ON CONFLICT(id) DO UPDATE SET title = excluded.title, content = excluded.content,
+             html_content = excluded.html_content, word_count = excluded.word_count,
+             character_count = excluded.character_count, version = excluded.version, updated_at = excluded.updated_at",
+        rusqlite::params![id, title, content, html_content, wc, created_at, now, workspaces::current(&app)],
     )
     .map_err(|e| format!("Failed to auto-save: {}", e))?;
+    links::index_document(&conn, &id, &html_content);
+    analytics::record_words(&conn, &id, wc - previous_words);
+    focus::track_words(&app, &conn, &id, wc - previous_words);
+    track_goal(&app, &conn, &id, wc);
 
-    Ok(())
+    let version: i64 = conn.query_row(
+        "SELECT version FROM documents WHERE id = ?1",
+        rusqlite::params![id],
+        |row| row.get(0),
+    )?;
+    windows::document_changed(&app, &id, "autosaved", Some(version), None);
+    Ok(version)
 }
 
 // ---------------------------------------------------------------------------
@@ -1553,17 +6588,146 @@ pub struct Project {
     pub icon: String,
     pub sort_order: i64,
     pub document_count: i64,
+    pub defaults: ProjectDefaults,
+    /// Set while archived; documents stay where they are
+    pub archived_at: Option<String>,
     pub created_at: String,
     pub updated_at: String,
 }
 
+/// Applied by `schedule_post` to every document in the project; explicit
+/// arguments win over these.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct ProjectDefaults {
+    /// Scheduled to when neither a preset nor a platform is given
+    #[serde(default)]
+    pub targets: Vec<PresetTarget>,
+    /// Added to the platform tags (Ghost tags, Beehiiv content tags)
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub brand: BrandKit,
+    #[serde(default)]
+    pub seo: SeoDefaults,
+}
+
+/// Exposed to content blocks as `{{brand.*}}` placeholders.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct BrandKit {
+    pub logo_url: Option<String>,
+    pub accent_color: Option<String>,
+    pub font_family: Option<String>,
+    /// Content blocks merged into every issue (header, footer, ...)
+    #[serde(default)]
+    pub block_ids: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct SeoDefaults {
+    /// Appended to the post title for the meta title, e.g. " | Field Notes"
+    pub title_suffix: Option<String>,
+    pub meta_description: Option<String>,
+    /// Social card image when the post has none
+    pub og_image: Option<String>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ProjectDetail {
+    pub project: Project,
+    pub total_words: i64,
+    pub published_count: i64,
+    pub scheduled_count: i64,
+    pub last_published_at: Option<String>,
+    /// Average of analytics snapshot rates for the publications this
+    /// project's posts went out to; None before any snapshot
+    pub avg_open_rate: Option<f64>,
+    pub avg_click_rate: Option<f64>,
+}
+
+const PROJECT_COLUMNS: &str = "p.id, p.name, p.description, p.color, p.icon, p.sort_order, p.created_at, p.updated_at,
+     (SELECT COUNT(*) FROM documents d WHERE d.project_id = p.id) as doc_count, p.defaults_json, p.archived_at";
+
+fn project_from_row(row: &rusqlite::Row) -> rusqlite::Result<Project> {
+    let defaults_json: String = row.get(9)?;
+    Ok(Project {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        description: row.get(2)?,
+        color: row.get(3)?,
+        icon: row.get(4)?,
+        sort_order: row.get(5)?,
+        created_at: row.get(6)?,
+        updated_at: row.get(7)?,
+        document_count: row.get(8)?,
+        defaults: serde_json::from_str(&defaults_json).unwrap_or_default(),
+        archived_at: row.get(10)?,
+    })
+}
+
+pub(crate) fn load_project(conn: &rusqlite::Connection, id: &str) -> Result<Project, AppError> {
+    conn.query_row(
+        &format!("SELECT {} FROM projects p WHERE p.id = ?1", PROJECT_COLUMNS),
+        rusqlite::params![id],
+        project_from_row,
+    )
+    .map_err(|_| AppError::not_found(format!("Project '{}' not found", id)))
+}
+
+/// Defaults of `project_id`, if it exists.
+pub(crate) fn project_defaults(conn: &rusqlite::Connection, project_id: &str) -> Option<ProjectDefaults> {
+    conn.query_row(
+        "SELECT defaults_json FROM projects WHERE id = ?1",
+        rusqlite::params![project_id],
+        |row| row.get::<_, String>(0),
+    )
+    .ok()
+    .and_then(|json| serde_json::from_str(&json).ok())
+}
+
+/// A self-contained HTML page (signup form, link page) styled from a
+/// brand kit: its font, accent colour for links and buttons, and logo.
+pub(crate) fn standalone_page(title: &str, brand: &BrandKit, body: &str) -> String {
+    let accent = brand
+        .accent_color
+        .as_deref()
+        .filter(|c| c.chars().all(|ch| ch.is_ascii_alphanumeric() || ch == '#'))
+        .unwrap_or("#7c3aed");
+    let font = brand
+        .font_family
+        .as_deref()
+        .map(|f| f.replace(['"', '<', '>', ';', '{', '}'], ""))
+        .filter(|f| !f.trim().is_empty())
+        .map(|f| format!("\"{}\", ", f))
+        .unwrap_or_default();
+    let logo = brand
+        .logo_url
+        .as_deref()
+        .map(|url| format!("<img class=\"logo\" src=\"{}\" alt=\"\">", html_escape_attr(url)))
+        .unwrap_or_default();
+    format!(
+        "<!DOCTYPE html><html><head><meta charset=\"utf-8\"><meta name=\"viewport\" content=\"width=device-width,initial-scale=1\"><title>{title}</title><style>body{{font-family:{font}system-ui,sans-serif;max-width:480px;margin:3rem auto;padding:0 1.25rem;color:#1a1a1a;line-height:1.6;text-align:center}}.logo{{max-width:96px;max-height:96px;border-radius:50%}}a{{color:{accent}}}input{{width:100%;box-sizing:border-box;padding:.7rem;margin:.35rem 0;border:1px solid #d4d4d8;border-radius:8px;font:inherit}}button,.button{{display:block;width:100%;box-sizing:border-box;padding:.75rem;margin:.5rem 0;border:0;border-radius:8px;background:{accent};color:#fff;font:inherit;font-weight:600;text-decoration:none;cursor:pointer}}.muted{{color:#71717a;font-size:.875rem}}</style></head><body>{logo}{body}</body></html>",
+        title = html_escape_text(title),
+    )
+}
+
+/// Defaults of the project `document_id` belongs to, if any.
+pub(crate) fn defaults_for_document(conn: &rusqlite::Connection, document_id: &str) -> Option<ProjectDefaults> {
+    conn.query_row(
+        "SELECT p.defaults_json FROM projects p JOIN documents d ON d.project_id = p.id WHERE d.id = ?1",
+        rusqlite::params![document_id],
+        |row| row.get::<_, String>(0),
+    )
+    .ok()
+    .and_then(|json| serde_json::from_str(&json).ok())
+}
+
 #[tauri::command]
 pub async fn create_project(
     app: tauri::AppHandle,
     name: String,
     color: Option<String>,
     icon: Option<String>,
-) -> Result<Project, String> {
+) -> Result<Project, AppError> {
     let conn = db::get_db(&app)?;
     let id = uuid::Uuid::new_v4().to_string();
     let now = Utc::now().to_rfc3339();
@@ -1575,42 +6739,34 @@ pub async fn create_project(
         .unwrap_or(0);
 
     conn.execute(
-        "INSERT INTO projects (id, name, description, color, icon, sort_order, created_at, updated_at)
-         VALUES (?1, ?2, '', ?3, ?4, ?5, ?6, ?6)",
-        rusqlite::params![id, name, c, i, sort, now],
+        "INSERT INTO projects (id, name, description, color, icon, sort_order, workspace_id, created_at, updated_at)
+         VALUES (?1, ?2, '', ?3, ?4, ?5, ?7, ?6, ?6)",
+        rusqlite::params![id, name, c, i, sort, now, workspaces::current(&app)],
     ).map_err(|e| format!("Failed to create project: {}", e))?;
 
     db::log_activity(&conn, "project.created", "project", Some(&id), Some(&name));
 
     Ok(Project {
         id, name, description: String::new(), color: c, icon: i,
-        sort_order: sort, document_count: 0, created_at: now.clone(), updated_at: now,
+        sort_order: sort, document_count: 0, defaults: ProjectDefaults::default(), archived_at: None,
+        created_at: now.clone(), updated_at: now,
     })
 }
 
+/// The active workspace's projects in sidebar order; archived ones only
+/// with `include_archived`.
 #[tauri::command]
-pub async fn list_projects(app: tauri::AppHandle) -> Result<Vec<Project>, String> {
+pub async fn list_projects(app: tauri::AppHandle, include_archived: Option<bool>) -> Result<Vec<Project>, AppError> {
     let conn = db::get_db(&app)?;
 
     let mut stmt = conn.prepare(
-        "SELECT p.id, p.name, p.description, p.color, p.icon, p.sort_order, p.created_at, p.updated_at,
-                (SELECT COUNT(*) FROM documents d WHERE d.project_id = p.id) as doc_count
-         FROM projects p ORDER BY p.sort_order ASC"
+        &format!(
+            "SELECT {} FROM projects p WHERE (?1 OR p.archived_at IS NULL) AND p.workspace_id = ?2 ORDER BY p.sort_order ASC",
+            PROJECT_COLUMNS
+        )
     ).map_err(|e| format!("Query failed: {}", e))?;
 
-    let rows = stmt.query_map([], |row| {
-        Ok(Project {
-            id: row.get(0)?,
-            name: row.get(1)?,
-            description: row.get(2)?,
-            color: row.get(3)?,
-            icon: row.get(4)?,
-            sort_order: row.get(5)?,
-            created_at: row.get(6)?,
-            updated_at: row.get(7)?,
-            document_count: row.get(8)?,
-        })
-    }).map_err(|e| format!("Query map failed: {}", e))?;
+    let rows = stmt.query_map(rusqlite::params![include_archived.unwrap_or(false), workspaces::current(&app)], project_from_row).map_err(|e| format!("Query map failed: {}", e))?;
 
     Ok(rows.filter_map(|r| r.ok()).collect())
 }
@@ -1622,7 +6778,8 @@ pub async fn update_project(
     name: Option<String>,
     color: Option<String>,
     icon: Option<String>,
-) -> Result<(), String> {
+    defaults: Option<ProjectDefaults>,
+) -> Result<(), AppError> {
     let conn = db::get_db(&app)?;
     let now = Utc::now().to_rfc3339();
 
@@ -1635,13 +6792,145 @@ pub async fn update_project(
     if let Some(i) = icon {
         conn.execute("UPDATE projects SET icon = ?1, updated_at = ?2 WHERE id = ?3", rusqlite::params![i, now, id]).ok();
     }
+    if let Some(d) = defaults {
+        let json = serde_json::to_string(&d)?;
+        conn.execute(
+            "UPDATE projects SET defaults_json = ?1, updated_at = ?2 WHERE id = ?3",
+            rusqlite::params![json, now, id],
+        ).map_err(|e| format!("Failed to update project defaults: {}", e))?;
+    }
     Ok(())
 }
 
+/// A project with its totals: words across documents, posts published and
+/// scheduled, and average open/click rates of the publications it went to.
+#[tauri::command]
+pub async fn get_project_detail(app: tauri::AppHandle, id: String) -> Result<ProjectDetail, AppError> {
+    let conn = db::get_db(&app)?;
+    let project = load_project(&conn, &id)?;
+
+    let (total_words, published_count, last_published_at): (i64, i64, Option<String>) = conn.query_row(
+        "SELECT COALESCE(SUM(word_count), 0), COALESCE(SUM(status = 'published'), 0), MAX(published_at)
+         FROM documents WHERE project_id = ?1",
+        rusqlite::params![id],
+        |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+    )?;
+    let scheduled_count: i64 = conn.query_row(
+        "SELECT COUNT(*) FROM scheduled_posts sp JOIN documents d ON d.id = sp.document_id
+         WHERE d.project_id = ?1 AND sp.status = 'pending'",
+        rusqlite::params![id],
+        |row| row.get(0),
+    )?;
+    let (avg_open_rate, avg_click_rate): (Option<f64>, Option<f64>) = conn.query_row(
+        "SELECT AVG(a.open_rate), AVG(a.click_rate) FROM analytics_snapshots a
+         WHERE EXISTS (
+             SELECT 1 FROM scheduled_posts sp JOIN documents d ON d.id = sp.document_id
+             WHERE d.project_id = ?1 AND sp.status = 'published'
+               AND sp.platform = a.platform AND sp.account_id = a.account_id
+               AND COALESCE(sp.publication_id, '') = COALESCE(a.publication_id, '')
+         )",
+        rusqlite::params![id],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    )?;
+
+    Ok(ProjectDetail {
+        project,
+        total_words,
+        published_count,
+        scheduled_count,
+        last_published_at,
+        avg_open_rate,
+        avg_click_rate,
+    })
+}
+
+/// Hide a project from the default list, or bring it back with
+/// `archived: false`. Its documents are untouched.
+#[tauri::command]
+pub async fn archive_project(app: tauri::AppHandle, id: String, archived: Option<bool>) -> Result<Project, AppError> {
+    let conn = db::get_db(&app)?;
+    let project = load_project(&conn, &id)?;
+    let now = Utc::now().to_rfc3339();
+    let archived = archived.unwrap_or(true);
+    conn.execute(
+        "UPDATE projects SET archived_at = ?1, updated_at = ?2 WHERE id = ?3",
+        rusqlite::params![archived.then_some(&now), now, id],
+    ).map_err(|e| format!("Failed to archive project: {}", e))?;
+    let action = if archived { "project.archived" } else { "project.unarchived" };
+    db::log_activity(&conn, action, "project", Some(&id), Some(&project.name));
+    load_project(&conn, &id)
+}
+
+/// Start a new project with the structure of `template_id`: its look,
+/// publish defaults and publishing presets. The content blocks its brand
+/// kit uses are copied so the two can diverge; documents are not.
+#[tauri::command]
+pub async fn create_project_from_template(
+    app: tauri::AppHandle,
+    template_id: String,
+    name: String,
+) -> Result<Project, AppError> {
+    if name.trim().is_empty() {
+        return Err(AppError::validation("Project name is required"));
+    }
+    let conn = db::get_db(&app)?;
+    let template = load_project(&conn, &template_id)?;
+    let id = uuid::Uuid::new_v4().to_string();
+    let now = Utc::now().to_rfc3339();
+
+    let mut defaults = template.defaults.clone();
+    defaults.brand.block_ids = defaults
+        .brand
+        .block_ids
+        .iter()
+        .filter_map(|block_id| {
+            let copy_id = uuid::Uuid::new_v4().to_string();
+            conn.execute(
+                "INSERT INTO content_blocks (id, name, kind, html, position, created_at, updated_at)
+                 SELECT ?1, name, kind, html, position, ?2, ?2 FROM content_blocks WHERE id = ?3",
+                rusqlite::params![copy_id, now, block_id],
+            )
+            .ok()
+            .filter(|&n| n > 0)
+            .map(|_| copy_id)
+        })
+        .collect();
+
+    let sort: i64 = conn
+        .query_row("SELECT COALESCE(MAX(sort_order), 0) + 1 FROM projects", [], |row| row.get(0))
+        .unwrap_or(0);
+    conn.execute(
+        "INSERT INTO projects (id, name, description, color, icon, sort_order, defaults_json, workspace_id, created_at, updated_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?9, ?8, ?8)",
+        rusqlite::params![
+            id, name, template.description, template.color, template.icon, sort,
+            serde_json::to_string(&defaults)?, now, workspaces::current(&app)
+        ],
+    ).map_err(|e| format!("Failed to create project: {}", e))?;
+
+    let preset_ids: Vec<String> = {
+        let mut stmt = conn.prepare("SELECT id FROM publishing_presets WHERE project_id = ?1")?;
+        let rows = stmt.query_map(rusqlite::params![template_id], |row| row.get(0))?;
+        rows.filter_map(|r| r.ok()).collect()
+    };
+    for preset_id in preset_ids {
+        conn.execute(
+            "INSERT INTO publishing_presets (id, project_id, name, targets_json, default_time, publish_status, recurrence_days, created_at, updated_at)
+             SELECT ?1, ?2, name, targets_json, default_time, publish_status, recurrence_days, ?3, ?3
+             FROM publishing_presets WHERE id = ?4",
+            rusqlite::params![uuid::Uuid::new_v4().to_string(), id, now, preset_id],
+        ).map_err(|e| format!("Failed to copy preset: {}", e))?;
+    }
+
+    db::log_activity(&conn, "project.created", "project", Some(&id), Some(&name));
+    load_project(&conn, &id)
+}
+
 #[tauri::command]
-pub async fn delete_project(app: tauri::AppHandle, id: String) -> Result<(), String> {
+pub async fn delete_project(app: tauri::AppHandle, id: String) -> Result<(), AppError> {
+    permissions::require(&app, Permission::DeleteContent)?;
     let conn = db::get_db(&app)?;
-    conn.execute("UPDATE documents SET project_id = NULL WHERE project_id = ?1", rusqlite::params![id]).ok();
+    conn.execute("UPDATE documents SET project_id = NULL, sort_index = NULL WHERE project_id = ?1", rusqlite::params![id]).ok();
     conn.execute("DELETE FROM projects WHERE id = ?1", rusqlite::params![id])
         .map_err(|e| format!("Failed to delete project: {}", e))?;
     Ok(())
@@ -1652,13 +6941,17 @@ pub async fn move_document_to_project(
     app: tauri::AppHandle,
     document_id: String,
     project_id: Option<String>,
-) -> Result<(), String> {
+) -> Result<(), AppError> {
     let conn = db::get_db(&app)?;
     let now = Utc::now().to_rfc3339();
+    // A document follows its project into the project's workspace
     conn.execute(
-        "UPDATE documents SET project_id = ?1, updated_at = ?2 WHERE id = ?3",
+        "UPDATE documents SET project_id = ?1, sort_index = NULL, updated_at = ?2,
+             workspace_id = COALESCE((SELECT workspace_id FROM projects WHERE id = ?1), workspace_id)
+         WHERE id = ?3",
         rusqlite::params![project_id, now, document_id],
     ).map_err(|e| format!("Failed to move document: {}", e))?;
+    windows::document_changed(&app, &document_id, "moved", None, None);
     Ok(())
 }
 
@@ -1667,13 +6960,14 @@ pub async fn set_document_status(
     app: tauri::AppHandle,
     document_id: String,
     status: String,
-) -> Result<(), String> {
+) -> Result<(), AppError> {
     let conn = db::get_db(&app)?;
     let now = Utc::now().to_rfc3339();
     conn.execute(
         "UPDATE documents SET status = ?1, updated_at = ?2 WHERE id = ?3",
         rusqlite::params![status, now, document_id],
     ).map_err(|e| format!("Failed to update status: {}", e))?;
+    windows::document_changed(&app, &document_id, "status", None, Some(&status));
     Ok(())
 }
 
@@ -1682,7 +6976,7 @@ pub async fn add_document_tags(
     app: tauri::AppHandle,
     document_id: String,
     tags: Vec<String>,
-) -> Result<(), String> {
+) -> Result<(), AppError> {
     let conn = db::get_db(&app)?;
     for tag in &tags {
         conn.execute(
@@ -1698,7 +6992,7 @@ pub async fn remove_document_tag(
     app: tauri::AppHandle,
     document_id: String,
     tag: String,
-) -> Result<(), String> {
+) -> Result<(), AppError> {
     let conn = db::get_db(&app)?;
     conn.execute(
         "DELETE FROM document_tags WHERE document_id = ?1 AND tag = ?2",
@@ -1725,7 +7019,8 @@ pub struct DocumentVersion {
 pub async fn get_document_versions(
     app: tauri::AppHandle,
     document_id: String,
-) -> Result<Vec<DocumentVersion>, String> {
+) -> Result<Vec<DocumentVersion>, AppError> {
+    let mode = text::count_mode(&app);
     let conn = db::get_db(&app)?;
     let mut stmt = conn.prepare(
         "SELECT id, document_id, title, version, created_at, html_content FROM document_versions WHERE document_id = ?1 ORDER BY version DESC"
@@ -1739,7 +7034,7 @@ pub async fn get_document_versions(
             title: row.get(2)?,
             version: row.get(3)?,
             created_at: row.get(4)?,
-            word_count: count_words(&html) as i64,
+            word_count: text::count_html(&html, mode) as i64,
         })
     }).map_err(|e| format!("Query map failed: {}", e))?;
 
@@ -1751,7 +7046,7 @@ pub async fn restore_document_version(
     app: tauri::AppHandle,
     document_id: String,
     version: i64,
-) -> Result<(), String> {
+) -> Result<i64, AppError> {
     let conn = db::get_db(&app)?;
 
     let (title, content, html_content): (String, String, String) = conn.query_row(
@@ -1759,11 +7054,15 @@ pub async fn restore_document_version(
         rusqlite::params![document_id, version],
         |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
     ).map_err(|_| "Version not found".to_string())?;
+    snapshots::take_snapshot(&app, &conn, "restore")?;
 
     let now = Utc::now().to_rfc3339();
-    let wc = count_words(&html_content) as i64;
+    let wc = text::count_html(&html_content, text::count_mode(&app)) as i64;
+    // Autosaves bump the edit version without a snapshot, so the document
+    // row can be ahead of its history.
     let new_version: i64 = conn.query_row(
-        "SELECT COALESCE(MAX(version), 0) + 1 FROM document_versions WHERE document_id = ?1",
+        "SELECT MAX(COALESCE((SELECT MAX(version) FROM document_versions WHERE document_id = ?1), 0),
+                    COALESCE((SELECT version FROM documents WHERE id = ?1), 0)) + 1",
         rusqlite::params![document_id], |row| row.get(0),
     ).unwrap_or(1);
 
@@ -1778,8 +7077,9 @@ pub async fn restore_document_version(
     ).ok();
 
     db::log_activity(&conn, "document.restored", "document", Some(&document_id), Some(&format!("Restored to version {}", version)));
+    windows::document_changed(&app, &document_id, "restored", Some(new_version), None);
 
-    Ok(())
+    Ok(new_version)
 }
 
 // ---------------------------------------------------------------------------
@@ -1794,18 +7094,19 @@ pub struct ActivityEntry {
     pub entity_id: Option<String>,
     pub details: Option<String>,
     pub created_at: String,
+    pub trace_id: Option<String>,
 }
 
 #[tauri::command]
 pub async fn get_recent_activity(
     app: tauri::AppHandle,
     limit: Option<i64>,
-) -> Result<Vec<ActivityEntry>, String> {
+) -> Result<Vec<ActivityEntry>, AppError> {
     let conn = db::get_db(&app)?;
     let lim = limit.unwrap_or(50);
 
     let mut stmt = conn.prepare(
-        "SELECT id, action, entity_type, entity_id, details, created_at FROM activity_log ORDER BY created_at DESC LIMIT ?1"
+        "SELECT id, action, entity_type, entity_id, details, created_at, trace_id FROM activity_log ORDER BY created_at DESC LIMIT ?1"
     ).map_err(|e| format!("Query failed: {}", e))?;
 
     let rows = stmt.query_map(rusqlite::params![lim], |row| {
@@ -1816,12 +7117,306 @@ pub async fn get_recent_activity(
             entity_id: row.get(3)?,
             details: row.get(4)?,
             created_at: row.get(5)?,
+            trace_id: row.get(6)?,
         })
     }).map_err(|e| format!("Query map failed: {}", e))?;
 
     Ok(rows.filter_map(|r| r.ok()).collect())
 }
 
+// ---------------------------------------------------------------------------
+// Template thumbnails
+// ---------------------------------------------------------------------------
+//
+// Canvas templates are drawn to a small SVG from their elements_json, so
+// every template gets the same kind of preview whatever the frontend had
+// on hand when saving. Geometry follows the canvas: rects, text and images
+// are placed by their top-left corner, round shapes by their centre.
+
+/// Longest side of a template thumbnail, in pixels
+const THUMBNAIL_SIZE: f64 = 320.0;
+/// Embedded images larger than this are drawn as a placeholder
+const THUMBNAIL_MAX_IMAGE_BYTES: usize = 256 * 1024;
+
+fn el_num(el: &serde_json::Value, key: &str, default: f64) -> f64 {
+    el.get(key).and_then(|v| v.as_f64()).unwrap_or(default)
+}
+
+fn el_str<'a>(el: &'a serde_json::Value, key: &str) -> &'a str {
+    el.get(key).and_then(|v| v.as_str()).unwrap_or("")
+}
+
+/// An element colour as an SVG paint; empty means none
+fn svg_paint(color: &str) -> String {
+    if color.trim().is_empty() {
+        "none".to_string()
+    } else {
+        xml_escape(color.trim())
+    }
+}
+
+/// Vertices of a regular polygon, or a star when `inner` is set, centred
+/// on the origin with the first point straight up.
+fn shape_points(sides: usize, outer: f64, inner: Option<f64>) -> String {
+    let count = if inner.is_some() { sides * 2 } else { sides };
+    (0..count)
+        .map(|i| {
+            let r = match inner {
+                Some(inner) if i % 2 == 1 => inner,
+                _ => outer,
+            };
+            let angle = std::f64::consts::TAU * i as f64 / count as f64;
+            format!("{:.1},{:.1}", r * angle.sin(), -r * angle.cos())
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// The element's linear gradient as an SVG definition, when enabled
+fn gradient_def(el: &serde_json::Value, id: &str) -> Option<String> {
+    let gradient = el.get("gradient")?;
+    if !gradient.get("enabled")?.as_bool()? {
+        return None;
+    }
+    let stops = gradient.get("colorStops")?.as_array()?;
+    let (from, to) = (stops.first()?.as_str()?, stops.get(1)?.as_str()?);
+    let angle = el_num(gradient, "angle", 0.0).to_radians();
+    let (cos, sin) = (angle.cos() / 2.0, angle.sin() / 2.0);
+    Some(format!(
+        r#"<linearGradient id="{}" x1="{:.3}" y1="{:.3}" x2="{:.3}" y2="{:.3}"><stop offset="0" stop-color="{}"/><stop offset="1" stop-color="{}"/></linearGradient>"#,
+        id,
+        0.5 - cos,
+        0.5 - sin,
+        0.5 + cos,
+        0.5 + sin,
+        xml_escape(from),
+        xml_escape(to)
+    ))
+}
+
+/// Lines of `text` wrapped to `width` with an average glyph width, which
+/// is close enough at thumbnail size.
+fn wrap_template_text(text: &str, font_size: f64, width: f64) -> Vec<String> {
+    let max_chars = if width > 0.0 {
+        ((width / (font_size * 0.55)).floor() as usize).max(1)
+    } else {
+        usize::MAX
+    };
+    let mut lines = Vec::new();
+    for paragraph in text.split('\n') {
+        let mut line = String::new();
+        for word in paragraph.split_whitespace() {
+            if !line.is_empty() && line.chars().count() + 1 + word.chars().count() > max_chars {
+                lines.push(std::mem::take(&mut line));
+            }
+            if !line.is_empty() {
+                line.push(' ');
+            }
+            line.push_str(word);
+        }
+        lines.push(line);
+    }
+    lines
+}
+
+fn template_text_svg(el: &serde_json::Value, width: f64) -> String {
+    let font_size = el_num(el, "fontSize", 16.0).max(1.0);
+    let line_height = el_num(el, "lineHeight", 1.0).max(0.5);
+    let style = el_str(el, "fontStyle");
+    let (anchor, x) = match el_str(el, "align") {
+        "center" => ("middle", width / 2.0),
+        "right" => ("end", width),
+        _ => ("start", 0.0),
+    };
+    let mut attrs = format!(
+        r#" font-size="{:.1}" fill="{}" text-anchor="{}""#,
+        font_size,
+        svg_paint(el_str(el, "fill")),
+        anchor
+    );
+    let family = el_str(el, "fontFamily");
+    if !family.is_empty() {
+        attrs.push_str(&format!(r#" font-family="{}""#, xml_escape(family)));
+    }
+    if style.contains("bold") {
+        attrs.push_str(r#" font-weight="700""#);
+    }
+    if style.contains("italic") {
+        attrs.push_str(r#" font-style="italic""#);
+    }
+    if let Some(decoration) = el.get("textDecoration").and_then(|v| v.as_str()).filter(|d| *d != "none" && !d.is_empty()) {
+        attrs.push_str(&format!(r#" text-decoration="{}""#, xml_escape(decoration)));
+    }
+    if let Some(spacing) = el.get("letterSpacing").and_then(|v| v.as_f64()) {
+        attrs.push_str(&format!(r#" letter-spacing="{:.1}""#, spacing));
+    }
+    let outline = el_str(el, "strokeColor");
+    if !outline.is_empty() {
+        attrs.push_str(&format!(
+            r#" stroke="{}" stroke-width="{:.1}""#,
+            xml_escape(outline),
+            el_num(el, "textStrokeWidth", 1.0)
+        ));
+    }
+    let lines: String = wrap_template_text(el_str(el, "text"), font_size, width)
+        .iter()
+        .enumerate()
+        .map(|(i, line)| {
+            // Baseline of each line box, roughly where the canvas puts it
+            let y = (i as f64 * line_height + 0.5 * line_height + 0.35) * font_size;
+            format!(r#"<tspan x="{:.1}" y="{:.1}">{}</tspan>"#, x, y, xml_escape(line))
+        })
+        .collect();
+    format!("<text{}>{}</text>", attrs, lines)
+}
+
+/// A polyline through `points`, with a filled head for arrows
+fn template_line_svg(el: &serde_json::Value, arrow: bool) -> Option<String> {
+    let points: Vec<f64> = el.get("points")?.as_array()?.iter().filter_map(|p| p.as_f64()).collect();
+    if points.len() < 4 {
+        return None;
+    }
+    let stroke = svg_paint(el_str(el, "stroke"));
+    let coords: Vec<String> = points.chunks_exact(2).map(|p| format!("{:.1},{:.1}", p[0], p[1])).collect();
+    let mut svg = format!(
+        r#"<polyline points="{}" fill="none" stroke="{}" stroke-width="{:.1}"/>"#,
+        coords.join(" "),
+        stroke,
+        el_num(el, "strokeWidth", 1.0)
+    );
+    if arrow {
+        let n = points.len() - points.len() % 2;
+        let (x1, y1, x2, y2) = (points[n - 4], points[n - 3], points[n - 2], points[n - 1]);
+        let length = ((x2 - x1).powi(2) + (y2 - y1).powi(2)).sqrt().max(f64::EPSILON);
+        let (dx, dy) = ((x2 - x1) / length, (y2 - y1) / length);
+        let pointer_length = el.get("pointerLength").and_then(|v| v.as_f64()).filter(|l| *l > 0.0).unwrap_or(15.0);
+        let half_width = el.get("pointerWidth").and_then(|v| v.as_f64()).filter(|w| *w > 0.0).unwrap_or(15.0) / 2.0;
+        let (bx, by) = (x2 - dx * pointer_length, y2 - dy * pointer_length);
+        let fill = match svg_paint(el_str(el, "fill")) {
+            none if none == "none" => stroke,
+            fill => fill,
+        };
+        svg.push_str(&format!(
+            r#"<polygon points="{:.1},{:.1} {:.1},{:.1} {:.1},{:.1}" fill="{}"/>"#,
+            x2,
+            y2,
+            bx - dy * half_width,
+            by + dx * half_width,
+            bx + dy * half_width,
+            by - dx * half_width,
+            fill
+        ));
+    }
+    Some(svg)
+}
+
+/// One element as an SVG group; hidden and unknown elements are skipped.
+/// Gradient definitions are appended to `defs`.
+fn template_element_svg(el: &serde_json::Value, index: usize, defs: &mut String) -> Option<String> {
+    if el.get("visible").and_then(|v| v.as_bool()) == Some(false) {
+        return None;
+    }
+    let (width, height) = (el_num(el, "width", 0.0), el_num(el, "height", 0.0));
+    let radius = width.min(height) / 2.0;
+    let gradient_id = format!("g{}", index);
+    let mut fill = || match gradient_def(el, &gradient_id) {
+        Some(def) => {
+            defs.push_str(&def);
+            format!("url(#{})", gradient_id)
+        }
+        None => svg_paint(el_str(el, "fill")),
+    };
+    let stroke = format!(
+        r#"stroke="{}" stroke-width="{:.1}""#,
+        svg_paint(el_str(el, "stroke")),
+        el_num(el, "strokeWidth", 0.0)
+    );
+
+    let body = match el_str(el, "type") {
+        "rect" => format!(
+            r#"<rect width="{:.1}" height="{:.1}" rx="{:.1}" fill="{}" {}/>"#,
+            width,
+            height,
+            el_num(el, "cornerRadius", 0.0),
+            fill(),
+            stroke
+        ),
+        "circle" => format!(r#"<circle r="{:.1}" fill="{}" {}/>"#, radius, fill(), stroke),
+        kind @ ("triangle" | "polygon") => {
+            let default_sides = if kind == "triangle" { 3.0 } else { 6.0 };
+            let sides = (el_num(el, "sides", default_sides) as usize).max(3);
+            format!(r#"<polygon points="{}" fill="{}" {}/>"#, shape_points(sides, radius, None), fill(), stroke)
+        }
+        "star" => {
+            let points = (el_num(el, "numPoints", 5.0) as usize).max(2);
+            let inner = Some(el_num(el, "innerRadius", 0.4)).filter(|r| *r > 0.0).unwrap_or(0.4);
+            format!(
+                r#"<polygon points="{}" fill="{}" {}/>"#,
+                shape_points(points, radius, Some(radius * inner)),
+                fill(),
+                stroke
+            )
+        }
+        "line" => template_line_svg(el, false)?,
+        "arrow" => template_line_svg(el, true)?,
+        "text" => template_text_svg(el, width),
+        "image" => {
+            let src = el_str(el, "src");
+            if src.starts_with("data:image/") && src.len() <= THUMBNAIL_MAX_IMAGE_BYTES {
+                format!(
+                    r#"<image href="{}" width="{:.1}" height="{:.1}" preserveAspectRatio="none"/>"#,
+                    xml_escape(src),
+                    width,
+                    height
+                )
+            } else {
+                format!(r##"<rect width="{:.1}" height="{:.1}" fill="#d4d4d8"/>"##, width, height)
+            }
+        }
+        _ => return None,
+    };
+    Some(format!(
+        r#"<g transform="translate({:.1} {:.1}) rotate({:.1})" opacity="{:.2}">{}</g>"#,
+        el_num(el, "x", 0.0),
+        el_num(el, "y", 0.0),
+        el_num(el, "rotation", 0.0),
+        el_num(el, "opacity", 1.0),
+        body
+    ))
+}
+
+/// A template's thumbnail as an SVG data URI, at most `THUMBNAIL_SIZE`
+/// pixels on its longest side.
+pub(crate) fn render_template_thumbnail(width: i64, height: i64, elements_json: &str) -> Result<String, AppError> {
+    if width <= 0 || height <= 0 {
+        return Err(AppError::validation("Template width and height must be positive"));
+    }
+    let elements: Vec<serde_json::Value> = serde_json::from_str(elements_json)
+        .map_err(|e| AppError::validation(format!("Template elements aren't valid JSON: {}", e)))?;
+    let scale = THUMBNAIL_SIZE / width.max(height) as f64;
+    let mut defs = String::new();
+    let body: String = elements
+        .iter()
+        .enumerate()
+        .filter_map(|(i, el)| template_element_svg(el, i, &mut defs))
+        .collect();
+    let svg = format!(
+        r##"<svg xmlns="http://www.w3.org/2000/svg" width="{:.0}" height="{:.0}" viewBox="0 0 {} {}" font-family="Inter, Helvetica, Arial, sans-serif"><defs>{}</defs><rect width="{}" height="{}" fill="#ffffff"/>{}</svg>"##,
+        (width as f64 * scale).max(1.0),
+        (height as f64 * scale).max(1.0),
+        width,
+        height,
+        defs,
+        width,
+        height,
+        body
+    );
+    Ok(format!(
+        "data:image/svg+xml;base64,{}",
+        base64::engine::general_purpose::STANDARD.encode(svg)
+    ))
+}
+
 // ---------------------------------------------------------------------------
 // User template commands
 // ---------------------------------------------------------------------------
@@ -1849,12 +7444,11 @@ pub async fn save_user_template(
     width: i64,
     height: i64,
     elements_json: String,
-    thumbnail: Option<String>,
-) -> Result<String, String> {
+) -> Result<String, AppError> {
+    let thumb = render_template_thumbnail(width, height, &elements_json)?;
     let conn = db::get_db(&app)?;
     let id = uuid::Uuid::new_v4().to_string();
     let now = Utc::now().to_rfc3339();
-    let thumb = thumbnail.unwrap_or_default();
 
     conn.execute(
         "INSERT INTO user_templates (id, name, category, width, height, thumbnail, elements_json, usage_count, is_builtin, created_at, updated_at)
@@ -1865,8 +7459,33 @@ pub async fn save_user_template(
     Ok(id)
 }
 
+/// Redraw every stored template's thumbnail, e.g. after the renderer
+/// changes. Templates whose elements don't parse keep their old one.
+/// Returns how many were updated.
+#[tauri::command]
+pub async fn regenerate_thumbnails(app: tauri::AppHandle) -> Result<i64, AppError> {
+    let conn = db::get_db(&app)?;
+    let templates: Vec<(String, i64, i64, String)> = {
+        let mut stmt = conn.prepare("SELECT id, width, height, elements_json FROM user_templates")?;
+        let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)))?;
+        rows.filter_map(|r| r.ok()).collect()
+    };
+    let mut updated = 0;
+    for (id, width, height, elements_json) in templates {
+        let Ok(thumb) = render_template_thumbnail(width, height, &elements_json) else {
+            continue;
+        };
+        updated += conn.execute(
+            "UPDATE user_templates SET thumbnail = ?1 WHERE id = ?2",
+            rusqlite::params![thumb, id],
+        )? as i64;
+    }
+    db::log_activity(&conn, "template.thumbnails_regenerated", "template", None, Some(&format!("{} templates", updated)));
+    Ok(updated)
+}
+
 #[tauri::command]
-pub async fn list_user_templates(app: tauri::AppHandle) -> Result<Vec<UserTemplate>, String> {
+pub async fn list_user_templates(app: tauri::AppHandle) -> Result<Vec<UserTemplate>, AppError> {
     let conn = db::get_db(&app)?;
     let mut stmt = conn.prepare(
         "SELECT id, name, category, width, height, thumbnail, elements_json, usage_count, is_builtin, created_at, updated_at
@@ -1893,7 +7512,8 @@ pub async fn list_user_templates(app: tauri::AppHandle) -> Result<Vec<UserTempla
 }
 
 #[tauri::command]
-pub async fn delete_user_template(app: tauri::AppHandle, id: String) -> Result<(), String> {
+pub async fn delete_user_template(app: tauri::AppHandle, id: String) -> Result<(), AppError> {
+    permissions::require(&app, Permission::DeleteContent)?;
     let conn = db::get_db(&app)?;
     conn.execute("DELETE FROM user_templates WHERE id = ?1 AND is_builtin = 0", rusqlite::params![id])
         .map_err(|e| format!("Failed to delete template: {}", e))?;
@@ -1901,8 +7521,30 @@ pub async fn delete_user_template(app: tauri::AppHandle, id: String) -> Result<(
 }
 
 #[tauri::command]
-pub async fn increment_template_usage(app: tauri::AppHandle, id: String) -> Result<(), String> {
+pub async fn increment_template_usage(app: tauri::AppHandle, id: String) -> Result<(), AppError> {
     let conn = db::get_db(&app)?;
     conn.execute("UPDATE user_templates SET usage_count = usage_count + 1 WHERE id = ?1", rusqlite::params![id]).ok();
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn attachments_sit_beside_their_document_entry() {
+        let mut taken = std::collections::HashSet::new();
+        let entry = archive_entry_name(0, "Issue #12", "pdf");
+        assert_eq!(attachment_entry_name(&entry, "episode.mp3", &mut taken), "attachments/01-Issue -12/episode.mp3");
+    }
+
+    #[test]
+    fn repeated_attachment_names_are_numbered() {
+        let mut taken = std::collections::HashSet::new();
+        let first = attachment_entry_name("01-Notes.md", "slides.pdf", &mut taken);
+        let second = attachment_entry_name("01-Notes.md", "slides.pdf", &mut taken);
+        assert_eq!(first, "attachments/01-Notes/slides.pdf");
+        assert_eq!(second, "attachments/01-Notes/slides (2).pdf");
+        assert_eq!(attachment_entry_name("01-Notes.md", "../../etc", &mut taken), "attachments/01-Notes/-..-etc");
+    }
+}
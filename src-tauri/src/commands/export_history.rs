@@ -0,0 +1,164 @@
+use chrono::Utc;
+use rusqlite::{Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tauri::AppHandle;
+
+use crate::commands::export::{self, PdfExportOptions, PdfFontConfig};
+use crate::db;
+use crate::error::AppError;
+
+// ─── Export history ─────────────────────────────────────────────
+//
+// Every DOCX and PDF export is recorded with the settings it was made
+// with, so a deliverable can be regenerated later without re-entering
+// them. Re-exporting renders the document as it is now; the history shows
+// whether it has changed since, by a hash of the exported HTML.
+
+/// Entries kept in the history
+const EXPORT_HISTORY: i64 = 500;
+
+/// What `export_docx` or `export_pdf` was called with, besides the content.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(tag = "format", rename_all = "lowercase")]
+pub(crate) enum ExportSettings {
+    Pdf { font: Option<PdfFontConfig>, options: Box<PdfExportOptions> },
+    Docx { toc: bool },
+}
+
+impl ExportSettings {
+    fn format(&self) -> &'static str {
+        match self {
+            ExportSettings::Pdf { .. } => "pdf",
+            ExportSettings::Docx { .. } => "docx",
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ExportEntry {
+    pub id: String,
+    pub document_id: Option<String>,
+    /// "docx" or "pdf"
+    pub format: String,
+    pub title: String,
+    /// `{ "format": "docx", "toc": ... }` or `{ "format": "pdf", "font":
+    /// ..., "options": ... }`
+    pub options: serde_json::Value,
+    pub size_bytes: i64,
+    pub created_at: String,
+    /// Whether the document differs from what was exported; `None` when
+    /// the export wasn't of a saved document or it has been deleted
+    pub content_changed: Option<bool>,
+}
+
+pub(crate) fn content_hash(html: &str) -> String {
+    Sha256::digest(html.as_bytes()).iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Record a finished export. Failing to record doesn't fail the export.
+pub(crate) fn record(
+    app: &AppHandle,
+    document_id: Option<&str>,
+    title: &str,
+    settings: &ExportSettings,
+    content_hash: &str,
+    size: usize,
+) {
+    let Ok(conn) = db::get_db(app) else { return };
+    let Ok(options) = serde_json::to_string(settings) else { return };
+    conn.execute(
+        "INSERT INTO export_history (id, document_id, format, title, options, content_hash, size_bytes, created_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        rusqlite::params![
+            uuid::Uuid::new_v4().to_string(),
+            document_id,
+            settings.format(),
+            title,
+            options,
+            content_hash,
+            size as i64,
+            Utc::now().to_rfc3339()
+        ],
+    )
+    .ok();
+    conn.execute(
+        "DELETE FROM export_history WHERE id NOT IN (SELECT id FROM export_history ORDER BY created_at DESC LIMIT ?1)",
+        rusqlite::params![EXPORT_HISTORY],
+    )
+    .ok();
+}
+
+/// The document's current HTML.
+fn document_html(conn: &Connection, document_id: &str) -> Result<Option<String>, AppError> {
+    Ok(conn
+        .query_row("SELECT html_content FROM documents WHERE id = ?1", rusqlite::params![document_id], |row| row.get(0))
+        .optional()?)
+}
+
+/// Exports, newest first; `document_id` narrows them to one document.
+#[tauri::command]
+pub async fn list_exports(
+    app: AppHandle,
+    document_id: Option<String>,
+    limit: Option<i64>,
+) -> Result<Vec<ExportEntry>, AppError> {
+    let conn = db::get_db(&app)?;
+    let mut stmt = conn.prepare(
+        "SELECT h.id, h.document_id, h.format, h.title, h.options, h.size_bytes, h.created_at,
+                h.content_hash, d.html_content
+         FROM export_history h LEFT JOIN documents d ON d.id = h.document_id
+         WHERE ?1 IS NULL OR h.document_id = ?1
+         ORDER BY h.created_at DESC LIMIT ?2",
+    )?;
+    let rows = stmt.query_map(rusqlite::params![document_id, limit.unwrap_or(50).clamp(1, 500)], |row| {
+        let options: String = row.get(4)?;
+        let exported_hash: String = row.get(7)?;
+        let current: Option<String> = row.get(8)?;
+        Ok(ExportEntry {
+            id: row.get(0)?,
+            document_id: row.get(1)?,
+            format: row.get(2)?,
+            title: row.get(3)?,
+            options: serde_json::from_str(&options).unwrap_or_default(),
+            size_bytes: row.get(5)?,
+            created_at: row.get(6)?,
+            content_changed: current.map(|html| content_hash(&html) != exported_hash),
+        })
+    })?;
+    Ok(rows.filter_map(|r| r.ok()).collect())
+}
+
+/// Export the entry's document again with the same title and settings.
+/// The new export is recorded in the history too.
+#[tauri::command]
+pub async fn re_export(app: AppHandle, entry_id: String) -> Result<Vec<u8>, AppError> {
+    let (document_id, title, options, html) = {
+        let conn = db::get_db(&app)?;
+        let entry: Option<(Option<String>, String, String)> = conn
+            .query_row(
+                "SELECT document_id, title, options FROM export_history WHERE id = ?1",
+                rusqlite::params![entry_id],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .optional()?;
+        let Some((document_id, title, options)) = entry else {
+            return Err(AppError::not_found(format!("Export '{}' not found", entry_id)));
+        };
+        let Some(document_id) = document_id else {
+            return Err(AppError::validation("That export wasn't of a saved document"));
+        };
+        let Some(html) = document_html(&conn, &document_id)? else {
+            return Err(AppError::not_found("The exported document has been deleted"));
+        };
+        (document_id, title, options, html)
+    };
+    let settings: ExportSettings = serde_json::from_str(&options)
+        .map_err(|e| AppError::validation(format!("Unreadable export settings: {}", e)))?;
+    match settings {
+        ExportSettings::Docx { toc } => export::export_docx(app, title, html, Some(toc), Some(document_id)).await,
+        ExportSettings::Pdf { font, options } => {
+            export::export_pdf(app, title, html, font, Some(*options), Some(document_id)).await
+        }
+    }
+}
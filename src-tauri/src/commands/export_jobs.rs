@@ -0,0 +1,244 @@
+use chrono::Utc;
+use rusqlite::Connection;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, LazyLock, Mutex};
+use tauri::{AppHandle, Emitter};
+use tokio::sync::{Notify, Semaphore};
+
+use crate::db;
+use crate::error::{AppError, ErrorKind};
+
+// ─── Export queue ───────────────────────────────────────────────
+//
+// Multi-document exports run as jobs: at most `MAX_CONCURRENT_EXPORTS`
+// render at once on the blocking pool, the rest wait their turn. Every job
+// is recorded in export_jobs, reports progress through "export:progress"
+// and can be cancelled while queued or between documents.
+
+const MAX_CONCURRENT_EXPORTS: usize = 2;
+/// Finished jobs kept in the history
+const JOB_HISTORY: i64 = 200;
+const CANCELLED: &str = "Export cancelled";
+
+static SLOTS: LazyLock<Semaphore> = LazyLock::new(|| Semaphore::new(MAX_CONCURRENT_EXPORTS));
+
+struct ActiveJob {
+    cancelled: Arc<AtomicBool>,
+    notify: Arc<Notify>,
+}
+
+static ACTIVE: LazyLock<Mutex<HashMap<String, ActiveJob>>> = LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// A row of export_jobs; also the "export:progress" payload.
+#[derive(Debug, Serialize, Clone)]
+pub struct ExportJob {
+    pub id: String,
+    /// "project_archive", "documents_archive" or "static_site"
+    pub kind: String,
+    pub label: String,
+    pub format: String,
+    /// queued, running, completed, failed, cancelled or interrupted
+    pub status: String,
+    pub done: i64,
+    pub total: i64,
+    pub error: Option<String>,
+    pub created_at: String,
+    pub started_at: Option<String>,
+    pub finished_at: Option<String>,
+}
+
+/// Handed to the job's work function on the blocking pool.
+pub(crate) struct JobContext {
+    app: AppHandle,
+    id: String,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl JobContext {
+    /// Fails once the job has been cancelled; call between units of work.
+    pub(crate) fn check(&self) -> Result<(), String> {
+        if self.cancelled.load(Ordering::SeqCst) {
+            Err(CANCELLED.to_string())
+        } else {
+            Ok(())
+        }
+    }
+
+    pub(crate) fn progress(&self, done: usize) {
+        if let Ok(conn) = db::get_db(&self.app) {
+            conn.execute(
+                "UPDATE export_jobs SET done = ?1 WHERE id = ?2",
+                rusqlite::params![done as i64, self.id],
+            )
+            .ok();
+            emit(&self.app, &conn, &self.id);
+        }
+    }
+}
+
+fn job_from_row(row: &rusqlite::Row) -> rusqlite::Result<ExportJob> {
+    Ok(ExportJob {
+        id: row.get(0)?,
+        kind: row.get(1)?,
+        label: row.get(2)?,
+        format: row.get(3)?,
+        status: row.get(4)?,
+        done: row.get(5)?,
+        total: row.get(6)?,
+        error: row.get(7)?,
+        created_at: row.get(8)?,
+        started_at: row.get(9)?,
+        finished_at: row.get(10)?,
+    })
+}
+
+const JOB_COLUMNS: &str =
+    "id, kind, label, format, status, done, total, error, created_at, started_at, finished_at";
+
+fn load_job(conn: &Connection, id: &str) -> Option<ExportJob> {
+    conn.query_row(
+        &format!("SELECT {} FROM export_jobs WHERE id = ?1", JOB_COLUMNS),
+        rusqlite::params![id],
+        job_from_row,
+    )
+    .ok()
+}
+
+fn emit(app: &AppHandle, conn: &Connection, id: &str) {
+    if let Some(job) = load_job(conn, id) {
+        app.emit("export:progress", &job).ok();
+    }
+}
+
+fn set_status(app: &AppHandle, id: &str, status: &str, error: Option<&str>) {
+    let Ok(conn) = db::get_db(app) else { return };
+    let now = Utc::now().to_rfc3339();
+    let finished = matches!(status, "completed" | "failed" | "cancelled");
+    conn.execute(
+        "UPDATE export_jobs SET status = ?1, error = ?2,
+             started_at = CASE WHEN ?1 = 'running' THEN ?3 ELSE started_at END,
+             finished_at = CASE WHEN ?4 THEN ?3 ELSE finished_at END,
+             done = CASE WHEN ?1 = 'completed' THEN total ELSE done END
+         WHERE id = ?5",
+        rusqlite::params![status, error, now, finished, id],
+    )
+    .ok();
+    emit(app, &conn, id);
+}
+
+/// Run `work` as a queued export job and wait for its result. `job_id`
+/// lets the caller name the job up front so it can cancel it; one is
+/// generated otherwise. `total` is the number of units `work` reports
+/// progress against.
+pub(crate) async fn run_export_job<T, F>(
+    app: &AppHandle,
+    job_id: Option<String>,
+    kind: &str,
+    label: &str,
+    format: &str,
+    total: usize,
+    work: F,
+) -> Result<T, AppError>
+where
+    T: Send + 'static,
+    F: FnOnce(&JobContext) -> Result<T, String> + Send + 'static,
+{
+    let id = job_id.filter(|j| !j.trim().is_empty()).unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+    let cancelled = Arc::new(AtomicBool::new(false));
+    let notify = Arc::new(Notify::new());
+    {
+        let mut active = ACTIVE.lock().map_err(|_| "Export queue unavailable".to_string())?;
+        if active.contains_key(&id) {
+            return Err(AppError::validation(format!("Export job '{}' is already running", id)));
+        }
+        let conn = db::get_db(app)?;
+        conn.execute(
+            "INSERT OR REPLACE INTO export_jobs (id, kind, label, format, status, done, total, created_at)
+             VALUES (?1, ?2, ?3, ?4, 'queued', 0, ?5, ?6)",
+            rusqlite::params![id, kind, label, format, total as i64, Utc::now().to_rfc3339()],
+        )?;
+        conn.execute(
+            "DELETE FROM export_jobs WHERE id NOT IN (SELECT id FROM export_jobs ORDER BY created_at DESC LIMIT ?1)",
+            rusqlite::params![JOB_HISTORY],
+        )?;
+        active.insert(id.clone(), ActiveJob { cancelled: cancelled.clone(), notify: notify.clone() });
+        emit(app, &conn, &id);
+    }
+
+    let result = async {
+        let _permit = tokio::select! {
+            permit = SLOTS.acquire() => permit.map_err(|_| "Export queue closed".to_string())?,
+            _ = notify.notified() => return Err(CANCELLED.to_string()),
+        };
+        if cancelled.load(Ordering::SeqCst) {
+            return Err(CANCELLED.to_string());
+        }
+        set_status(app, &id, "running", None);
+        let ctx = JobContext { app: app.clone(), id: id.clone(), cancelled: cancelled.clone() };
+        tokio::task::spawn_blocking(move || work(&ctx))
+            .await
+            .map_err(|e| format!("Export task failed: {}", e))?
+    }
+    .await;
+
+    if let Ok(mut active) = ACTIVE.lock() {
+        active.remove(&id);
+    }
+    match result {
+        Ok(value) => {
+            set_status(app, &id, "completed", None);
+            Ok(value)
+        }
+        Err(_) if cancelled.load(Ordering::SeqCst) => {
+            set_status(app, &id, "cancelled", None);
+            Err(AppError::new(ErrorKind::Cancelled, CANCELLED))
+        }
+        Err(e) => {
+            set_status(app, &id, "failed", Some(&e));
+            Err(AppError::from(e))
+        }
+    }
+}
+
+/// Recent export jobs, newest first. Jobs still marked queued or running
+/// but no longer known to the queue were cut off by the app closing.
+#[tauri::command]
+pub async fn list_export_jobs(app: AppHandle, limit: Option<i64>) -> Result<Vec<ExportJob>, AppError> {
+    let active: Vec<String> = ACTIVE.lock().map(|a| a.keys().cloned().collect()).unwrap_or_default();
+    let conn = db::get_db(&app)?;
+    let placeholders = active.iter().map(|_| "?").collect::<Vec<_>>().join(", ");
+    conn.execute(
+        &format!(
+            "UPDATE export_jobs SET status = 'interrupted', finished_at = COALESCE(finished_at, ?1)
+             WHERE status IN ('queued', 'running') AND id NOT IN ({})",
+            placeholders
+        ),
+        rusqlite::params_from_iter(std::iter::once(Utc::now().to_rfc3339()).chain(active)),
+    )?;
+
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM export_jobs ORDER BY created_at DESC LIMIT ?1",
+        JOB_COLUMNS
+    ))?;
+    let rows = stmt.query_map(rusqlite::params![limit.unwrap_or(50).clamp(1, 500)], job_from_row)?;
+    Ok(rows.filter_map(|r| r.ok()).collect())
+}
+
+/// Cancel a queued or running export. A running job stops after the
+/// document it is on.
+#[tauri::command]
+pub async fn cancel_export_job(app: AppHandle, job_id: String) -> Result<(), AppError> {
+    {
+        let active = ACTIVE.lock().map_err(|_| "Export queue unavailable".to_string())?;
+        let job = active
+            .get(&job_id)
+            .ok_or_else(|| AppError::not_found(format!("No queued or running export '{}'", job_id)))?;
+        job.cancelled.store(true, Ordering::SeqCst);
+        job.notify.notify_one();
+    }
+    let conn = db::get_db(&app)?;
+    db::log_activity(&conn, "export.cancelled", "export_job", Some(&job_id), None);
+    Ok(())
+}
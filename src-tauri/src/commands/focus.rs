@@ -0,0 +1,390 @@
+use chrono::{DateTime, Utc};
+use rusqlite::{Connection, OptionalExtension};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+
+use crate::commands::analytics::DateRange;
+use crate::db;
+use crate::error::AppError;
+
+// ─── Focus sessions ─────────────────────────────────────────────
+//
+// A timed writing session with a goal of minutes, words, or either one
+// first ("45 min or 800 words"). Time is counted here rather than by the
+// editor's timer, so a session survives window reloads; words come from
+// the same save hook as the writing stats. While a session runs it is
+// reported on "focus:progress" every TICK, and on "focus:completed" once
+// the goal is met, which also ends it.
+
+const TICK: Duration = Duration::from_secs(10);
+const MAX_GOAL_MINUTES: i64 = 600;
+const MAX_GOAL_WORDS: i64 = 100_000;
+
+const SESSION_COLUMNS: &str =
+    "id, document_id, goal_minutes, goal_words, status, active_seconds, resumed_at, words_written, started_at, ended_at";
+
+#[derive(Debug, Serialize, Clone)]
+pub struct FocusSession {
+    pub id: String,
+    /// Only saves of this document count; any document's when unset
+    pub document_id: Option<String>,
+    pub goal_minutes: Option<i64>,
+    pub goal_words: Option<i64>,
+    /// "active" | "paused" | "completed" (goal met) | "ended" (stopped short)
+    pub status: String,
+    /// Time spent running, pauses excluded
+    pub elapsed_seconds: i64,
+    /// Net words saved during the session
+    pub words_written: i64,
+    /// Of whichever goal is closest, 0 to 100
+    pub percent: f64,
+    pub started_at: String,
+    pub ended_at: Option<String>,
+}
+
+impl FocusSession {
+    fn goal_reached(&self) -> bool {
+        self.percent >= 100.0
+    }
+}
+
+fn session_from_row(row: &rusqlite::Row) -> rusqlite::Result<FocusSession> {
+    let goal_minutes: Option<i64> = row.get(2)?;
+    let goal_words: Option<i64> = row.get(3)?;
+    let active_seconds: i64 = row.get(5)?;
+    let resumed_at: Option<String> = row.get(6)?;
+    let words_written: i64 = row.get(7)?;
+    let elapsed_seconds = active_seconds + running_seconds(resumed_at.as_deref(), Utc::now());
+    Ok(FocusSession {
+        id: row.get(0)?,
+        document_id: row.get(1)?,
+        status: row.get(4)?,
+        percent: percent(goal_minutes, goal_words, elapsed_seconds, words_written),
+        goal_minutes,
+        goal_words,
+        elapsed_seconds,
+        words_written,
+        started_at: row.get(8)?,
+        ended_at: row.get(9)?,
+    })
+}
+
+/// Seconds from `resumed_at` (unset while paused) to `until`.
+fn running_seconds(resumed_at: Option<&str>, until: DateTime<Utc>) -> i64 {
+    resumed_at
+        .and_then(|t| DateTime::parse_from_rfc3339(t).ok())
+        .map_or(0, |t| (until - t.with_timezone(&Utc)).num_seconds().max(0))
+}
+
+fn percent(goal_minutes: Option<i64>, goal_words: Option<i64>, elapsed_seconds: i64, words_written: i64) -> f64 {
+    let time = goal_minutes.map(|m| elapsed_seconds as f64 / (m * 60) as f64);
+    let words = goal_words.map(|w| words_written.max(0) as f64 / w as f64);
+    let best = time.into_iter().chain(words).fold(0.0, f64::max);
+    ((best * 1000.0).round() / 10.0).min(100.0)
+}
+
+fn load(conn: &Connection, id: &str) -> Result<FocusSession, AppError> {
+    conn.query_row(
+        &format!("SELECT {} FROM focus_sessions WHERE id = ?1", SESSION_COLUMNS),
+        rusqlite::params![id],
+        session_from_row,
+    )
+    .optional()?
+    .ok_or_else(|| AppError::not_found(format!("Focus session '{}' not found", id)))
+}
+
+/// The running or paused session, if any; there is at most one.
+fn current(conn: &Connection) -> Result<Option<FocusSession>, AppError> {
+    Ok(conn
+        .query_row(
+            &format!(
+                "SELECT {} FROM focus_sessions WHERE status IN ('active', 'paused') ORDER BY started_at DESC LIMIT 1",
+                SESSION_COLUMNS
+            ),
+            [],
+            session_from_row,
+        )
+        .optional()?)
+}
+
+fn require_current(conn: &Connection) -> Result<FocusSession, AppError> {
+    current(conn)?.ok_or_else(|| AppError::not_found("No focus session is running"))
+}
+
+/// Bank the running time and stop the clock, leaving the session `status`.
+fn stop_clock(conn: &Connection, id: &str, status: &str, until: DateTime<Utc>) -> Result<(), AppError> {
+    let resumed_at: Option<String> =
+        conn.query_row("SELECT resumed_at FROM focus_sessions WHERE id = ?1", rusqlite::params![id], |row| row.get(0))?;
+    let ended_at = matches!(status, "completed" | "ended").then(|| Utc::now().to_rfc3339());
+    conn.execute(
+        "UPDATE focus_sessions SET status = ?1, active_seconds = active_seconds + ?2, resumed_at = NULL,
+             updated_at = ?3, ended_at = ?4 WHERE id = ?5",
+        rusqlite::params![status, running_seconds(resumed_at.as_deref(), until), Utc::now().to_rfc3339(), ended_at, id],
+    )?;
+    Ok(())
+}
+
+/// End the session, as completed when its goal is met.
+fn finish(conn: &Connection, session: &FocusSession) -> Result<FocusSession, AppError> {
+    let status = if session.goal_reached() { "completed" } else { "ended" };
+    stop_clock(conn, &session.id, status, Utc::now())?;
+    let finished = load(conn, &session.id)?;
+    db::log_activity(
+        conn,
+        &format!("focus.{}", status),
+        "focus_session",
+        Some(&finished.id),
+        Some(&format!("{} min, {} words", finished.elapsed_seconds / 60, finished.words_written)),
+    );
+    Ok(finished)
+}
+
+/// Announce progress, or completion if the goal has been met.
+fn report(app: &AppHandle, conn: &Connection, session: FocusSession) -> Result<FocusSession, AppError> {
+    if session.goal_reached() {
+        let finished = finish(conn, &session)?;
+        app.emit("focus:completed", &finished).ok();
+        return Ok(finished);
+    }
+    app.emit("focus:progress", &session).ok();
+    Ok(session)
+}
+
+/// Count a save's word change towards the running session.
+pub(crate) fn track_words(app: &AppHandle, conn: &Connection, document_id: &str, delta: i64) {
+    if delta == 0 {
+        return;
+    }
+    let Ok(Some(session)) = current(conn) else { return };
+    if session.status != "active" || session.document_id.as_deref().is_some_and(|d| d != document_id) {
+        return;
+    }
+    let updated = conn.execute(
+        "UPDATE focus_sessions SET words_written = words_written + ?1 WHERE id = ?2",
+        rusqlite::params![delta, session.id],
+    );
+    if let Err(e) = updated.map_err(AppError::from).and_then(|_| load(conn, &session.id)).and_then(|s| report(app, conn, s)) {
+        crate::trace::log("Focus", &format!("Word tracking failed: {}", e.message));
+    }
+}
+
+/// Tick while the session keeps running from `resumed_at`; a pause, or a
+/// later resume with its own ticker, ends this one.
+fn start_ticker(app: AppHandle, id: String, resumed_at: String) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(TICK);
+        interval.tick().await;
+        loop {
+            interval.tick().await;
+            let Ok(conn) = db::get_db(&app) else { break };
+            let running: Option<String> = conn
+                .query_row(
+                    "SELECT resumed_at FROM focus_sessions WHERE id = ?1 AND status = 'active'",
+                    rusqlite::params![id],
+                    |row| row.get(0),
+                )
+                .optional()
+                .ok()
+                .flatten();
+            if running.as_deref() != Some(resumed_at.as_str()) {
+                break;
+            }
+            conn.execute(
+                "UPDATE focus_sessions SET updated_at = ?1 WHERE id = ?2",
+                rusqlite::params![Utc::now().to_rfc3339(), id],
+            )
+            .ok();
+            match load(&conn, &id).and_then(|s| report(&app, &conn, s)) {
+                Ok(session) if session.status == "active" => {}
+                Ok(_) => break,
+                Err(e) => {
+                    crate::trace::log("Focus", &format!("Tick failed: {}", e.message));
+                    break;
+                }
+            }
+        }
+    });
+}
+
+/// Pause a session left running when the app last quit, as of its last
+/// tick, so the time the app was closed doesn't count.
+pub fn pause_interrupted(app: &AppHandle) {
+    let Ok(conn) = db::get_db(app) else { return };
+    let interrupted: Vec<(String, String)> = conn
+        .prepare("SELECT id, updated_at FROM focus_sessions WHERE status = 'active'")
+        .and_then(|mut stmt| {
+            let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+            Ok(rows.filter_map(|r| r.ok()).collect())
+        })
+        .unwrap_or_default();
+    for (id, updated_at) in interrupted {
+        let until = DateTime::parse_from_rfc3339(&updated_at).map_or_else(|_| Utc::now(), |t| t.with_timezone(&Utc));
+        if let Err(e) = stop_clock(&conn, &id, "paused", until) {
+            crate::trace::log("Focus", &format!("Failed to pause interrupted session: {}", e.message));
+        }
+    }
+}
+
+/// Start a session with a goal of `goal_minutes`, `goal_words` or both,
+/// whichever is met first. Only saves of `document_id` count, if given.
+#[tauri::command]
+pub async fn start_focus_session(
+    app: AppHandle,
+    document_id: Option<String>,
+    goal_minutes: Option<i64>,
+    goal_words: Option<i64>,
+) -> Result<FocusSession, AppError> {
+    if goal_minutes.is_none() && goal_words.is_none() {
+        return Err(AppError::validation("Set a goal of minutes, words or both"));
+    }
+    if goal_minutes.is_some_and(|m| !(1..=MAX_GOAL_MINUTES).contains(&m)) {
+        return Err(AppError::validation(format!("A session lasts 1 to {} minutes", MAX_GOAL_MINUTES)));
+    }
+    if goal_words.is_some_and(|w| !(1..=MAX_GOAL_WORDS).contains(&w)) {
+        return Err(AppError::validation(format!("A word goal is 1 to {} words", MAX_GOAL_WORDS)));
+    }
+    let session = {
+        let conn = db::get_db(&app)?;
+        if current(&conn)?.is_some() {
+            return Err(AppError::validation("A focus session is already running"));
+        }
+        if let Some(document_id) = &document_id {
+            conn.query_row("SELECT 1 FROM documents WHERE id = ?1", rusqlite::params![document_id], |_| Ok(()))
+                .map_err(|_| AppError::not_found(format!("Document '{}' not found", document_id)))?;
+        }
+        let id = uuid::Uuid::new_v4().to_string();
+        let now = Utc::now().to_rfc3339();
+        conn.execute(
+            "INSERT INTO focus_sessions (id, document_id, goal_minutes, goal_words, status, resumed_at, started_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, 'active', ?5, ?5, ?5)",
+            rusqlite::params![id, document_id, goal_minutes, goal_words, now],
+        )?;
+        db::log_activity(&conn, "focus.started", "focus_session", Some(&id), None);
+        load(&conn, &id)?
+    };
+    start_ticker(app, session.id.clone(), session.started_at.clone());
+    Ok(session)
+}
+
+#[tauri::command]
+pub async fn pause_focus_session(app: AppHandle) -> Result<FocusSession, AppError> {
+    let conn = db::get_db(&app)?;
+    let session = require_current(&conn)?;
+    if session.status == "active" {
+        stop_clock(&conn, &session.id, "paused", Utc::now())?;
+    }
+    load(&conn, &session.id)
+}
+
+#[tauri::command]
+pub async fn resume_focus_session(app: AppHandle) -> Result<FocusSession, AppError> {
+    let (session, resumed_at) = {
+        let conn = db::get_db(&app)?;
+        let session = require_current(&conn)?;
+        if session.status == "active" {
+            return Ok(session);
+        }
+        let now = Utc::now().to_rfc3339();
+        conn.execute(
+            "UPDATE focus_sessions SET status = 'active', resumed_at = ?1, updated_at = ?1 WHERE id = ?2",
+            rusqlite::params![now, session.id],
+        )?;
+        (load(&conn, &session.id)?, now)
+    };
+    start_ticker(app, session.id.clone(), resumed_at);
+    Ok(session)
+}
+
+/// Stop the current session early; it counts as completed if the goal was
+/// met in the meantime.
+#[tauri::command]
+pub async fn end_focus_session(app: AppHandle) -> Result<FocusSession, AppError> {
+    let conn = db::get_db(&app)?;
+    let session = require_current(&conn)?;
+    finish(&conn, &session)
+}
+
+#[tauri::command]
+pub async fn get_active_focus_session(app: AppHandle) -> Result<Option<FocusSession>, AppError> {
+    let conn = db::get_db(&app)?;
+    current(&conn)
+}
+
+/// Finished sessions started within `range`, newest first; only those
+/// that met their goal when `completed_only`.
+#[tauri::command]
+pub async fn list_focus_sessions(
+    app: AppHandle,
+    range: DateRange,
+    completed_only: Option<bool>,
+) -> Result<Vec<FocusSession>, AppError> {
+    let (from, to) = range.bounds()?;
+    let statuses = if completed_only.unwrap_or(false) { "('completed')" } else { "('completed', 'ended')" };
+    let conn = db::get_db(&app)?;
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM focus_sessions WHERE status IN {} AND started_at >= ?1 AND started_at <= ?2
+         ORDER BY started_at DESC",
+        SESSION_COLUMNS, statuses
+    ))?;
+    let rows = stmt.query_map(rusqlite::params![from.to_rfc3339(), to.to_rfc3339()], session_from_row)?;
+    Ok(rows.filter_map(|r| r.ok()).collect())
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct FocusDay {
+    /// YYYY-MM-DD
+    pub date: String,
+    pub minutes: i64,
+    pub words: i64,
+    pub sessions: i64,
+    pub completed: i64,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct FocusStats {
+    /// Finished sessions; running and paused ones aren't counted yet
+    pub sessions: i64,
+    pub completed: i64,
+    /// Percent of sessions that met their goal
+    pub completion_rate: f64,
+    pub total_minutes: i64,
+    pub total_words: i64,
+    pub average_minutes: f64,
+    pub longest_minutes: i64,
+    /// Only days with a session, in order
+    pub days: Vec<FocusDay>,
+}
+
+fn build_focus_stats(sessions: &[FocusSession]) -> FocusStats {
+    let mut days: BTreeMap<String, FocusDay> = BTreeMap::new();
+    for session in sessions {
+        let date = session.started_at.get(..10).unwrap_or_default().to_string();
+        let day = days.entry(date.clone()).or_insert(FocusDay { date, minutes: 0, words: 0, sessions: 0, completed: 0 });
+        day.minutes += session.elapsed_seconds / 60;
+        day.words += session.words_written;
+        day.sessions += 1;
+        day.completed += i64::from(session.status == "completed");
+    }
+    let count = sessions.len() as i64;
+    let completed = sessions.iter().filter(|s| s.status == "completed").count() as i64;
+    let total_minutes = sessions.iter().map(|s| s.elapsed_seconds / 60).sum();
+    FocusStats {
+        sessions: count,
+        completed,
+        completion_rate: if count == 0 { 0.0 } else { (completed as f64 * 1000.0 / count as f64).round() / 10.0 },
+        total_minutes,
+        total_words: sessions.iter().map(|s| s.words_written).sum(),
+        average_minutes: total_minutes as f64 / count.max(1) as f64,
+        longest_minutes: sessions.iter().map(|s| s.elapsed_seconds / 60).max().unwrap_or(0),
+        days: days.into_values().collect(),
+    }
+}
+
+/// Totals and per-day figures of the sessions started within `range`.
+#[tauri::command]
+pub async fn get_focus_stats(app: AppHandle, range: DateRange) -> Result<FocusStats, AppError> {
+    let sessions = list_focus_sessions(app, range, None).await?;
+    Ok(build_focus_stats(&sessions))
+}
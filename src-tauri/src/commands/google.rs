@@ -0,0 +1,206 @@
+use base64::Engine;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+
+use crate::commands::export::{self, BrandKit, DocumentMeta};
+use crate::commands::{credentials, images, platform, usage};
+use crate::db;
+use crate::error::AppError;
+use crate::listener::{self, Request, Response};
+use crate::services::google::{self, GoogleCredential, GoogleService};
+
+// ─── Google Docs ────────────────────────────────────────────────
+//
+// Connecting opens Google's consent page in the browser; the redirect
+// lands on the local listener at /oauth/google, which trades the code for
+// a refresh token and stores it as the account's credential. Export and
+// import then go through Drive's DOCX conversion.
+
+/// Consent pages left open longer than this are refused
+const AUTH_TTL: Duration = Duration::from_secs(10 * 60);
+
+struct PendingAuth {
+    account_id: String,
+    client_id: String,
+    client_secret: String,
+    verifier: String,
+    redirect_uri: String,
+    started: Instant,
+}
+
+static PENDING: LazyLock<Mutex<HashMap<String, PendingAuth>>> = LazyLock::new(|| Mutex::new(HashMap::new()));
+
+#[derive(Debug, Serialize, Clone)]
+pub struct GoogleConnected {
+    pub account_id: String,
+    pub email: String,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct GoogleDocExport {
+    pub file_id: String,
+    pub url: Option<String>,
+}
+
+fn message_page(status: u16, heading: &str, text: &str) -> Response {
+    let body = format!("<h1>{}</h1><p>{}</p>", heading, text.replace('&', "&amp;").replace('<', "&lt;"));
+    Response::html(status, export::standalone_page("Google", &BrandKit::default(), &body))
+}
+
+/// Trade the code for tokens and store the credential.
+async fn complete_auth(app: &AppHandle, pending: PendingAuth, code: &str) -> Result<GoogleConnected, AppError> {
+    let tokens = GoogleService::exchange_code(
+        &pending.client_id,
+        &pending.client_secret,
+        code,
+        &pending.redirect_uri,
+        &pending.verifier,
+    )
+    .await?;
+    let refresh_token = tokens.refresh_token.ok_or_else(|| {
+        AppError::auth("Google didn't return a refresh token. Remove the app's access in your Google account and connect again.")
+            .with_platform("google")
+    })?;
+    let (name, email) = GoogleService::account(&tokens.access_token).await?;
+    let credential = GoogleCredential {
+        client_id: pending.client_id,
+        client_secret: pending.client_secret,
+        refresh_token,
+    };
+    credentials::store_credential(
+        app.clone(),
+        "google".to_string(),
+        pending.account_id.clone(),
+        serde_json::to_string(&credential)?,
+        name,
+        email.clone(),
+    )
+    .await?;
+    Ok(GoogleConnected { account_id: pending.account_id, email })
+}
+
+/// Listener route: GET /oauth/google, Google's redirect after consent.
+pub(crate) async fn handle(app: &AppHandle, request: &Request) -> Option<Response> {
+    if (request.method.as_str(), request.path.as_str()) != ("GET", "/oauth/google") {
+        return None;
+    }
+    let state = request.query.get("state").cloned().unwrap_or_default();
+    let pending = PENDING.lock().ok().and_then(|mut p| p.remove(&state));
+    let Some(pending) = pending.filter(|p| p.started.elapsed() < AUTH_TTL) else {
+        return Some(message_page(410, "Link expired", "Start connecting Google again from the app."));
+    };
+    if let Some(error) = request.query.get("error") {
+        return Some(message_page(400, "Google access was not granted", error));
+    }
+    let code = request.query.get("code").map(String::as_str).unwrap_or("");
+    Some(match complete_auth(app, pending, code).await {
+        Ok(connected) => {
+            app.emit("google:connected", &connected).ok();
+            message_page(200, "Google connected", "You can close this window and return to the app.")
+        }
+        Err(e) => message_page(400, "Couldn't connect Google", &e.message),
+    })
+}
+
+/// Start connecting a Google account with the user's own OAuth client
+/// (type "Desktop app"). Returns the consent URL to open in the browser;
+/// `google:connected` fires once the credential is stored.
+#[tauri::command]
+pub async fn start_google_auth(
+    app: AppHandle,
+    account_id: String,
+    client_id: String,
+    client_secret: String,
+) -> Result<String, AppError> {
+    if client_id.trim().is_empty() || client_secret.trim().is_empty() {
+        return Err(AppError::validation("A Google OAuth client id and secret are required"));
+    }
+    // The redirect needs the listener even when it isn't enabled at launch
+    if !listener::status(&app).running {
+        listener::start(&app).await?;
+    }
+    let redirect_uri = format!("http://127.0.0.1:{}/oauth/google", listener::listener_settings(&app).port);
+    let state = uuid::Uuid::new_v4().simple().to_string();
+    let verifier = format!("{}{}", uuid::Uuid::new_v4().simple(), uuid::Uuid::new_v4().simple());
+    let challenge = base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(Sha256::digest(verifier.as_bytes()));
+    let url = GoogleService::authorize_url(client_id.trim(), &redirect_uri, &state, &challenge);
+
+    let mut pending = PENDING.lock().map_err(|_| "Google sign-in state unavailable".to_string())?;
+    pending.retain(|_, p| p.started.elapsed() < AUTH_TTL);
+    pending.insert(
+        state,
+        PendingAuth {
+            account_id,
+            client_id: client_id.trim().to_string(),
+            client_secret: client_secret.trim().to_string(),
+            verifier,
+            redirect_uri,
+            started: Instant::now(),
+        },
+    );
+    Ok(url)
+}
+
+/// Upload a document as a new Google Doc for commenting.
+#[tauri::command]
+pub async fn export_to_google_doc(
+    app: AppHandle,
+    document_id: String,
+    account_id: String,
+) -> Result<GoogleDocExport, AppError> {
+    let (title, html) = {
+        let conn = db::get_db(&app)?;
+        conn.query_row(
+            "SELECT title, html_content FROM documents WHERE id = ?1",
+            rusqlite::params![document_id],
+            |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)),
+        )
+        .map_err(|_| AppError::not_found(format!("Document '{}' not found", document_id)))?
+    };
+    let images_dir = images::images_dir(&app).ok();
+    let docx_title = title.clone();
+    let docx = tokio::task::spawn_blocking(move || export::build_docx(&docx_title, &html, images_dir.as_deref(), false))
+        .await
+        .map_err(|e| format!("Export task failed: {}", e))??;
+
+    let api_key = platform::get_api_key(&app, "google", &account_id)?;
+    let access_token = GoogleService::access_token(&api_key).await?;
+    let file = GoogleService::create_doc_from_docx(&access_token, &title, &docx).await?;
+    usage::record_api_call(&app, "google", &account_id);
+
+    let conn = db::get_db(&app)?;
+    db::log_activity(
+        &conn,
+        "document.exported_google",
+        "document",
+        Some(&document_id),
+        file.web_view_link.as_deref().or(Some(&file.id)),
+    );
+    Ok(GoogleDocExport { file_id: file.id, url: file.web_view_link })
+}
+
+/// Create a draft from a Google Doc, given its URL or file id.
+#[tauri::command]
+pub async fn import_google_doc(
+    app: AppHandle,
+    doc_url: String,
+    account_id: String,
+    project_id: Option<String>,
+) -> Result<DocumentMeta, AppError> {
+    let file_id = google::doc_id_from_url(&doc_url)
+        .ok_or_else(|| AppError::validation("That doesn't look like a Google Docs link"))?;
+    let api_key = platform::get_api_key(&app, "google", &account_id)?;
+    let access_token = GoogleService::access_token(&api_key).await?;
+    let (name, docx) = GoogleService::export_doc_as_docx(&access_token, &file_id).await?;
+    usage::record_api_call(&app, "google", &account_id);
+
+    let images_dir = images::images_dir(&app).ok();
+    let (title, html) = tokio::task::spawn_blocking(move || export::parse_docx(&docx, images_dir.as_deref(), &name))
+        .await
+        .map_err(|e| format!("Import task failed: {}", e))??;
+    export::create_imported_document(&app, &title, &html, project_id, &doc_url)
+}
@@ -0,0 +1,169 @@
+use crate::db;
+use crate::error::AppError;
+use crate::permissions::{self, Permission};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+/// A backlog item that isn't a document yet. `target_date` ("YYYY-MM-DD")
+/// places it on the planning calendar.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Idea {
+    pub id: String,
+    pub title: String,
+    pub notes: String,
+    pub project_id: Option<String>,
+    pub target_date: Option<String>,
+    pub status: String, // "open" | "in_progress" | "done" | "dropped"
+    pub document_id: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+const IDEA_COLUMNS: &str =
+    "id, title, notes, project_id, target_date, status, document_id, created_at, updated_at";
+
+fn idea_from_row(row: &rusqlite::Row) -> rusqlite::Result<Idea> {
+    Ok(Idea {
+        id: row.get(0)?,
+        title: row.get(1)?,
+        notes: row.get(2)?,
+        project_id: row.get(3)?,
+        target_date: row.get(4)?,
+        status: row.get(5)?,
+        document_id: row.get(6)?,
+        created_at: row.get(7)?,
+        updated_at: row.get(8)?,
+    })
+}
+
+fn validate_target_date(date: &Option<String>) -> Result<(), String> {
+    if let Some(d) = date {
+        chrono::NaiveDate::parse_from_str(d, "%Y-%m-%d")
+            .map_err(|_| format!("Invalid target date '{}', expected YYYY-MM-DD", d))?;
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn create_idea(
+    app: AppHandle,
+    title: String,
+    notes: Option<String>,
+    project_id: Option<String>,
+    target_date: Option<String>,
+) -> Result<Idea, AppError> {
+    validate_target_date(&target_date)?;
+    let conn = db::get_db(&app)?;
+    let id = uuid::Uuid::new_v4().to_string();
+    let now = Utc::now().to_rfc3339();
+    let notes = notes.unwrap_or_default();
+
+    conn.execute(
+        "INSERT INTO ideas (id, title, notes, project_id, target_date, status, created_at, updated_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, 'open', ?6, ?6)",
+        rusqlite::params![id, title, notes, project_id, target_date, now],
+    )
+    .map_err(|e| format!("Failed to create idea: {}", e))?;
+
+    db::log_activity(&conn, "idea.created", "idea", Some(&id), Some(&title));
+
+    Ok(Idea {
+        id,
+        title,
+        notes,
+        project_id,
+        target_date,
+        status: "open".to_string(),
+        document_id: None,
+        created_at: now.clone(),
+        updated_at: now,
+    })
+}
+
+#[tauri::command]
+pub async fn list_ideas(
+    app: AppHandle,
+    project_id: Option<String>,
+    status: Option<String>,
+) -> Result<Vec<Idea>, AppError> {
+    let conn = db::get_db(&app)?;
+
+    let mut sql = format!("SELECT {} FROM ideas WHERE 1=1", IDEA_COLUMNS);
+    let mut params: Vec<Box<dyn rusqlite::types::ToSql>> = Vec::new();
+
+    if let Some(ref p) = project_id {
+        sql.push_str(&format!(" AND project_id = ?{}", params.len() + 1));
+        params.push(Box::new(p.clone()));
+    }
+    if let Some(ref s) = status {
+        sql.push_str(&format!(" AND status = ?{}", params.len() + 1));
+        params.push(Box::new(s.clone()));
+    }
+    // Dated ideas first, in date order; undated backlog after, newest first
+    sql.push_str(" ORDER BY target_date IS NULL, target_date ASC, created_at DESC");
+
+    let param_refs: Vec<&dyn rusqlite::types::ToSql> = params.iter().map(|p| p.as_ref()).collect();
+
+    let mut stmt = conn.prepare(&sql).map_err(|e| format!("Query failed: {}", e))?;
+    let rows = stmt
+        .query_map(param_refs.as_slice(), idea_from_row)
+        .map_err(|e| format!("Query map failed: {}", e))?;
+
+    Ok(rows.filter_map(|r| r.ok()).collect())
+}
+
+#[tauri::command]
+pub async fn update_idea(
+    app: AppHandle,
+    id: String,
+    title: Option<String>,
+    notes: Option<String>,
+    target_date: Option<String>,
+    status: Option<String>,
+    document_id: Option<String>,
+) -> Result<Idea, AppError> {
+    validate_target_date(&target_date)?;
+    let conn = db::get_db(&app)?;
+    let mut idea = conn
+        .query_row(
+            &format!("SELECT {} FROM ideas WHERE id = ?1", IDEA_COLUMNS),
+            rusqlite::params![id],
+            idea_from_row,
+        )
+        .map_err(|e| format!("Idea not found: {}", e))?;
+
+    if let Some(t) = title {
+        idea.title = t;
+    }
+    if let Some(n) = notes {
+        idea.notes = n;
+    }
+    if target_date.is_some() {
+        idea.target_date = target_date;
+    }
+    if let Some(s) = status {
+        idea.status = s;
+    }
+    if document_id.is_some() {
+        idea.document_id = document_id;
+    }
+    idea.updated_at = Utc::now().to_rfc3339();
+
+    conn.execute(
+        "UPDATE ideas SET title = ?1, notes = ?2, target_date = ?3, status = ?4, document_id = ?5, updated_at = ?6 WHERE id = ?7",
+        rusqlite::params![idea.title, idea.notes, idea.target_date, idea.status, idea.document_id, idea.updated_at, id],
+    )
+    .map_err(|e| format!("Failed to update idea: {}", e))?;
+
+    Ok(idea)
+}
+
+#[tauri::command]
+pub async fn delete_idea(app: AppHandle, id: String) -> Result<(), AppError> {
+    permissions::require(&app, Permission::DeleteContent)?;
+    let conn = db::get_db(&app)?;
+    conn.execute("DELETE FROM ideas WHERE id = ?1", rusqlite::params![id])
+        .map_err(|e| format!("Failed to delete idea: {}", e))?;
+    Ok(())
+}
@@ -4,6 +4,9 @@ use std::path::PathBuf;
 use tauri::{AppHandle, Manager};
 use uuid::Uuid;
 
+use crate::error::AppError;
+use crate::permissions::{self, Permission};
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct ImageEntry {
     pub id: String,
@@ -13,7 +16,7 @@ pub struct ImageEntry {
     pub created_at: String,
 }
 
-fn images_dir(app: &AppHandle) -> Result<PathBuf, String> {
+pub(crate) fn images_dir(app: &AppHandle) -> Result<PathBuf, String> {
     let data_dir = app
         .path()
         .app_data_dir()
@@ -26,10 +29,10 @@ fn images_dir(app: &AppHandle) -> Result<PathBuf, String> {
 }
 
 #[tauri::command]
-pub async fn upload_image(app: AppHandle, file_path: String) -> Result<ImageEntry, String> {
+pub async fn upload_image(app: AppHandle, file_path: String) -> Result<ImageEntry, AppError> {
     let source = PathBuf::from(&file_path);
     if !source.exists() {
-        return Err("File not found".into());
+        return Err(AppError::not_found("File not found"));
     }
 
     let ext = source
@@ -40,7 +43,7 @@ pub async fn upload_image(app: AppHandle, file_path: String) -> Result<ImageEntr
 
     let allowed = ["png", "jpg", "jpeg", "gif", "webp", "svg", "bmp"];
     if !allowed.contains(&ext.as_str()) {
-        return Err(format!("Unsupported image format: .{}", ext));
+        return Err(AppError::validation(format!("Unsupported image format: .{}", ext)));
     }
 
     let id = Uuid::new_v4().to_string();
@@ -62,7 +65,7 @@ pub async fn upload_image(app: AppHandle, file_path: String) -> Result<ImageEntr
 }
 
 #[tauri::command]
-pub async fn list_images(app: AppHandle) -> Result<Vec<ImageEntry>, String> {
+pub async fn list_images(app: AppHandle) -> Result<Vec<ImageEntry>, AppError> {
     let dir = images_dir(&app)?;
     let mut entries = Vec::new();
 
@@ -122,7 +125,8 @@ pub async fn list_images(app: AppHandle) -> Result<Vec<ImageEntry>, String> {
 }
 
 #[tauri::command]
-pub async fn delete_image(app: AppHandle, image_id: String) -> Result<(), String> {
+pub async fn delete_image(app: AppHandle, image_id: String) -> Result<(), AppError> {
+    permissions::require(&app, Permission::DeleteContent)?;
     let dir = images_dir(&app)?;
 
     // Find the file matching this ID
@@ -142,5 +146,5 @@ pub async fn delete_image(app: AppHandle, image_id: String) -> Result<(), String
         }
     }
 
-    Err("Image not found".into())
+    Err(AppError::not_found("Image not found"))
 }
@@ -0,0 +1,209 @@
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tauri::AppHandle;
+
+use crate::commands::export::{self, BrandKit};
+use crate::commands::{platform, settings, usage};
+use crate::db;
+use crate::error::AppError;
+use crate::services::ghost::GhostService;
+use crate::services::wordpress::WordPressService;
+
+// ─── Link-in-bio page ───────────────────────────────────────────
+//
+// One page with the configured links and the latest published issues,
+// styled from a project's brand kit. It can be written to a folder as a
+// static index.html or pushed to Ghost or WordPress as a page; the pushed
+// page id is remembered so republishing updates it in place.
+
+pub(crate) const LINK_PAGE_SETTING: &str = "link_page";
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct LinkPageLink {
+    pub label: String,
+    pub url: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct LinkPageConfig {
+    pub title: String,
+    pub bio: Option<String>,
+    pub links: Vec<LinkPageLink>,
+    /// Project whose brand kit styles the page
+    pub project_id: Option<String>,
+    /// Latest published issues to list; 0 hides the section
+    pub issue_count: u32,
+    /// Page ids from earlier pushes, keyed "platform:account_id"
+    pub published_pages: HashMap<String, String>,
+}
+
+impl Default for LinkPageConfig {
+    fn default() -> Self {
+        LinkPageConfig {
+            title: "Links".to_string(),
+            bio: None,
+            links: Vec::new(),
+            project_id: None,
+            issue_count: 5,
+            published_pages: HashMap::new(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct LinkPagePublishResult {
+    pub page_id: String,
+    pub url: Option<String>,
+}
+
+fn page_config(app: &AppHandle) -> LinkPageConfig {
+    settings::get_setting(app, LINK_PAGE_SETTING).unwrap_or_default()
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Latest (title, url) pairs with a published URL, newest first. Issues
+/// posted to several platforms are listed once.
+fn latest_issues(conn: &Connection, project_id: Option<&str>, limit: u32) -> Result<Vec<(String, String)>, AppError> {
+    let mut stmt = conn.prepare(
+        "SELECT COALESCE(NULLIF(d.title, ''), NULLIF(sp.title, ''), 'Untitled'), sp.published_url
+         FROM scheduled_posts sp JOIN documents d ON d.id = sp.document_id
+         WHERE sp.published_url IS NOT NULL AND sp.published_url != ''
+           AND (?1 IS NULL OR d.project_id = ?1)
+         GROUP BY sp.document_id
+         ORDER BY MAX(sp.scheduled_at) DESC LIMIT ?2",
+    )?;
+    let rows = stmt.query_map(rusqlite::params![project_id, limit], |row| Ok((row.get(0)?, row.get(1)?)))?;
+    Ok(rows.filter_map(|r| r.ok()).collect())
+}
+
+/// Page body without the surrounding document, for pushing into a theme.
+fn page_body(config: &LinkPageConfig, issues: &[(String, String)]) -> String {
+    let mut body = format!("<h1>{}</h1>", escape(&config.title));
+    if let Some(bio) = config.bio.as_deref().filter(|b| !b.trim().is_empty()) {
+        body.push_str(&format!("<p>{}</p>", escape(bio)));
+    }
+    for link in &config.links {
+        body.push_str(&format!(
+            "<a class=\"button\" href=\"{}\">{}</a>",
+            escape(&link.url),
+            escape(&link.label)
+        ));
+    }
+    if !issues.is_empty() {
+        body.push_str("<h2>Latest issues</h2><ul style=\"list-style:none;padding:0\">");
+        for (title, url) in issues {
+            body.push_str(&format!("<li><a href=\"{}\">{}</a></li>", escape(url), escape(title)));
+        }
+        body.push_str("</ul>");
+    }
+    body
+}
+
+fn validate(config: &LinkPageConfig) -> Result<(), AppError> {
+    if config.title.trim().is_empty() {
+        return Err(AppError::validation("The link page needs a title"));
+    }
+    for link in &config.links {
+        if link.label.trim().is_empty() {
+            return Err(AppError::validation("Every link needs a label"));
+        }
+        if !(link.url.starts_with("https://") || link.url.starts_with("http://") || link.url.starts_with("mailto:")) {
+            return Err(AppError::validation(format!("'{}' is not a web or mailto link", link.url)));
+        }
+    }
+    Ok(())
+}
+
+/// (brand, body) for a config, reading the issues list from the database.
+fn render(app: &AppHandle, config: &LinkPageConfig) -> Result<(BrandKit, String), AppError> {
+    let conn = db::get_db(app)?;
+    let issues = if config.issue_count > 0 {
+        latest_issues(&conn, config.project_id.as_deref(), config.issue_count)?
+    } else {
+        Vec::new()
+    };
+    let brand = config
+        .project_id
+        .as_deref()
+        .and_then(|id| export::project_defaults(&conn, id))
+        .map(|d| d.brand)
+        .unwrap_or_default();
+    Ok((brand, page_body(config, &issues)))
+}
+
+#[tauri::command]
+pub async fn get_link_page_config(app: AppHandle) -> Result<LinkPageConfig, AppError> {
+    Ok(page_config(&app))
+}
+
+#[tauri::command]
+pub async fn save_link_page_config(app: AppHandle, config: LinkPageConfig) -> Result<(), AppError> {
+    validate(&config)?;
+    // Pushed page ids are tracked here, not by the editor
+    let config = LinkPageConfig { published_pages: page_config(&app).published_pages, ..config };
+    settings::set_setting(&app, LINK_PAGE_SETTING, serde_json::to_value(&config)?)?;
+    Ok(())
+}
+
+/// The page as a standalone HTML document. `config` previews unsaved
+/// changes.
+#[tauri::command]
+pub async fn generate_link_page(app: AppHandle, config: Option<LinkPageConfig>) -> Result<String, AppError> {
+    let config = config.unwrap_or_else(|| page_config(&app));
+    validate(&config)?;
+    let (brand, body) = render(&app, &config)?;
+    Ok(export::standalone_page(&config.title, &brand, &body))
+}
+
+/// Write the page to `folder`/index.html; returns the file path.
+#[tauri::command]
+pub async fn export_link_page(app: AppHandle, folder: String) -> Result<String, AppError> {
+    let html = generate_link_page(app, None).await?;
+    let dir = std::path::Path::new(&folder);
+    std::fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {}", folder, e))?;
+    let path = dir.join("index.html");
+    std::fs::write(&path, html).map_err(|e| format!("Failed to write link page: {}", e))?;
+    Ok(path.to_string_lossy().to_string())
+}
+
+/// Create or update the page on a Ghost or WordPress account. The site's
+/// theme styles it, so only the body is sent.
+#[tauri::command]
+pub async fn publish_link_page(
+    app: AppHandle,
+    platform: String,
+    account_id: String,
+) -> Result<LinkPagePublishResult, AppError> {
+    let mut config = page_config(&app);
+    validate(&config)?;
+    let (_, body) = render(&app, &config)?;
+    let api_key = platform::get_api_key(&app, &platform, &account_id)?;
+    let key = format!("{}:{}", platform, account_id);
+    let existing = config.published_pages.get(&key).map(String::as_str);
+
+    let (page_id, url) = match platform.as_str() {
+        "ghost" => GhostService::upsert_page(&api_key, existing, &config.title, &body).await?,
+        "wordpress" => WordPressService::upsert_page(&api_key, existing, &config.title, &body).await?,
+        other => {
+            return Err(AppError::validation(format!(
+                "Link pages can be published to Ghost or WordPress, not {}",
+                other
+            )))
+        }
+    };
+    usage::record_api_call(&app, &platform, &account_id);
+
+    config.published_pages.insert(key, page_id.clone());
+    settings::set_setting(&app, LINK_PAGE_SETTING, serde_json::to_value(&config)?)?;
+    let conn = db::get_db(&app)?;
+    db::log_activity(&conn, "link_page.published", "link_page", Some(&page_id), Some(&platform));
+    Ok(LinkPagePublishResult { page_id, url })
+}
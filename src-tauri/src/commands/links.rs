@@ -0,0 +1,303 @@
+use rusqlite::Connection;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use tauri::AppHandle;
+
+use crate::commands::workspaces;
+use crate::db;
+use crate::error::AppError;
+
+// ─── Link index ─────────────────────────────────────────────────
+//
+// Every saved document's `<a href>`s are recorded in document_links under
+// a normalized key. A key resolves to a local document either directly
+// (station://document/<id> links from the editor) or through a published
+// URL recorded by the scheduler or an archive import. Resolution happens at
+// query time, so a link written before its target was published is picked
+// up once it is.
+
+/// Editor link to another local document
+pub const LOCAL_LINK_PREFIX: &str = "station://document/";
+
+#[derive(Debug, Serialize, Clone)]
+pub struct Backlink {
+    pub source_document_id: String,
+    pub title: String,
+    pub status: String,
+    pub url: String,
+    pub anchor_text: String,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct OutgoingLink {
+    pub url: String,
+    pub anchor_text: String,
+    /// Local document the link resolves to, directly or by published URL
+    pub target_document_id: Option<String>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct LinkNode {
+    pub id: String,
+    pub title: String,
+    pub status: String,
+    pub inbound: i64,
+    pub outbound: i64,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct LinkEdge {
+    pub source: String,
+    pub target: String,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct LinkGraph {
+    pub nodes: Vec<LinkNode>,
+    pub edges: Vec<LinkEdge>,
+}
+
+/// Key a link is matched on: "doc:<id>" for local links, otherwise host
+/// (without "www.") and path, lowercased, ignoring scheme, query, fragment
+/// and a trailing slash. Mailto, in-page and relative links have none.
+fn link_key(url: &str) -> Option<String> {
+    let url = url.trim();
+    if let Some(id) = url.strip_prefix(LOCAL_LINK_PREFIX) {
+        let id = id.trim_end_matches('/');
+        return (!id.is_empty()).then(|| format!("doc:{}", id));
+    }
+    let rest = url
+        .strip_prefix("https://")
+        .or_else(|| url.strip_prefix("http://"))?;
+    let rest = rest.split(['?', '#']).next().unwrap_or("");
+    let rest = rest.strip_prefix("www.").unwrap_or(rest);
+    let key = rest.trim_end_matches('/').to_lowercase();
+    (!key.is_empty()).then_some(key)
+}
+
+fn attr_value(tag: &str, name: &str) -> Option<String> {
+    let lower = tag.to_ascii_lowercase();
+    let mut from = 0;
+    while let Some(i) = lower[from..].find(name) {
+        let start = from + i;
+        from = start + name.len();
+        let preceded = start == 0 || lower.as_bytes()[start - 1].is_ascii_whitespace();
+        let rest = lower[from..].trim_start();
+        if !preceded || !rest.starts_with('=') {
+            continue;
+        }
+        let value_start = tag.len() - rest.len() + 1;
+        let value = tag[value_start..].trim_start();
+        let quote = value.chars().next()?;
+        return if quote == '"' || quote == '\'' {
+            value[1..].split(quote).next().map(str::to_string)
+        } else {
+            value.split(|c: char| c.is_whitespace() || c == '>').next().map(str::to_string)
+        };
+    }
+    None
+}
+
+fn strip_tags(html: &str) -> String {
+    let mut out = String::new();
+    let mut in_tag = false;
+    for c in html.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            _ if !in_tag => out.push(c),
+            _ => {}
+        }
+    }
+    out.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// (href, anchor text) for each `<a>` in `html`.
+fn extract_links(html: &str) -> Vec<(String, String)> {
+    let lower = html.to_ascii_lowercase();
+    let mut links = Vec::new();
+    let mut from = 0;
+    while let Some(i) = lower[from..].find("<a") {
+        let start = from + i;
+        let Some(tag_len) = lower[start..].find('>') else {
+            break;
+        };
+        let tag_end = start + tag_len + 1;
+        from = tag_end;
+        // "<abbr>", "<aside>" and friends
+        if !lower[start + 2..].starts_with(|c: char| c.is_whitespace()) {
+            continue;
+        }
+        let Some(href) = attr_value(&html[start..tag_end], "href") else {
+            continue;
+        };
+        let close = lower[tag_end..].find("</a>").map(|n| tag_end + n).unwrap_or(tag_end);
+        links.push((href, strip_tags(&html[tag_end..close])));
+    }
+    links
+}
+
+/// Replace the document's outgoing links in the index.
+pub(crate) fn index_document(conn: &Connection, document_id: &str, html: &str) {
+    conn.execute("DELETE FROM document_links WHERE source_document_id = ?1", rusqlite::params![document_id])
+        .ok();
+    let mut seen = HashSet::new();
+    for (url, anchor_text) in extract_links(html) {
+        let Some(key) = link_key(&url) else {
+            continue;
+        };
+        if key == format!("doc:{}", document_id) || !seen.insert(key.clone()) {
+            continue;
+        }
+        conn.execute(
+            "INSERT OR IGNORE INTO document_links (source_document_id, url, target_key, anchor_text)
+             VALUES (?1, ?2, ?3, ?4)",
+            rusqlite::params![document_id, url, key, anchor_text],
+        )
+        .ok();
+    }
+}
+
+/// Drop a deleted document's outgoing links.
+pub(crate) fn remove_document(conn: &Connection, document_id: &str) {
+    conn.execute("DELETE FROM document_links WHERE source_document_id = ?1", rusqlite::params![document_id])
+        .ok();
+}
+
+/// Published URL key -> local document, from scheduled posts and imports.
+fn published_keys(conn: &Connection) -> Result<HashMap<String, String>, AppError> {
+    let mut stmt = conn.prepare(
+        "SELECT published_url, document_id FROM scheduled_posts WHERE published_url IS NOT NULL
+         UNION ALL
+         SELECT url, document_id FROM platform_posts WHERE url IS NOT NULL",
+    )?;
+    let rows = stmt.query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))?;
+    Ok(rows
+        .filter_map(|r| r.ok())
+        .filter_map(|(url, document_id)| link_key(&url).map(|key| (key, document_id)))
+        .collect())
+}
+
+fn resolve(key: &str, published: &HashMap<String, String>) -> Option<String> {
+    match key.strip_prefix("doc:") {
+        Some(id) => Some(id.to_string()),
+        None => published.get(key).cloned(),
+    }
+}
+
+/// Documents linking to `document_id`, by its local link or any URL it
+/// was published at.
+#[tauri::command]
+pub async fn get_backlinks(app: AppHandle, document_id: String) -> Result<Vec<Backlink>, AppError> {
+    let conn = db::get_db(&app)?;
+    let published = published_keys(&conn)?;
+    let mut keys = vec![format!("doc:{}", document_id)];
+    keys.extend(published.iter().filter(|(_, id)| **id == document_id).map(|(key, _)| key.clone()));
+
+    let placeholders: Vec<String> = (2..keys.len() + 2).map(|n| format!("?{}", n)).collect();
+    let sql = format!(
+        "SELECT l.source_document_id, d.title, COALESCE(d.status, 'draft'), l.url, l.anchor_text
+         FROM document_links l JOIN documents d ON d.id = l.source_document_id
+         WHERE l.source_document_id != ?1 AND l.target_key IN ({})
+         ORDER BY d.updated_at DESC",
+        placeholders.join(", ")
+    );
+    let mut params: Vec<&dyn rusqlite::types::ToSql> = vec![&document_id];
+    params.extend(keys.iter().map(|k| k as &dyn rusqlite::types::ToSql));
+
+    let mut stmt = conn.prepare(&sql)?;
+    let rows = stmt.query_map(params.as_slice(), |row| {
+        Ok(Backlink {
+            source_document_id: row.get(0)?,
+            title: row.get(1)?,
+            status: row.get(2)?,
+            url: row.get(3)?,
+            anchor_text: row.get(4)?,
+        })
+    })?;
+    // A document linking to two of the target's URLs is listed once
+    let mut seen = HashSet::new();
+    Ok(rows
+        .filter_map(|r| r.ok())
+        .filter(|b| seen.insert(b.source_document_id.clone()))
+        .collect())
+}
+
+#[tauri::command]
+pub async fn get_outgoing_links(app: AppHandle, document_id: String) -> Result<Vec<OutgoingLink>, AppError> {
+    let conn = db::get_db(&app)?;
+    let published = published_keys(&conn)?;
+    let mut stmt = conn.prepare(
+        "SELECT url, target_key, anchor_text FROM document_links WHERE source_document_id = ?1 ORDER BY rowid",
+    )?;
+    let rows = stmt.query_map(rusqlite::params![document_id], |row| {
+        Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get::<_, String>(2)?))
+    })?;
+    Ok(rows
+        .filter_map(|r| r.ok())
+        .map(|(url, key, anchor_text)| OutgoingLink {
+            url,
+            anchor_text,
+            target_document_id: resolve(&key, &published),
+        })
+        .collect())
+}
+
+/// Links between the active workspace's documents as a node/edge graph,
+/// optionally limited to one project. Links to anything outside the set
+/// are left out.
+#[tauri::command]
+pub async fn get_link_graph(app: AppHandle, project_id: Option<String>) -> Result<LinkGraph, AppError> {
+    let workspace_id = workspaces::current(&app);
+    let conn = db::get_db(&app)?;
+    let published = published_keys(&conn)?;
+
+    let mut stmt = conn.prepare(
+        "SELECT id, title, COALESCE(status, 'draft') FROM documents
+         WHERE workspace_id = ?2 AND (?1 IS NULL OR project_id = ?1)",
+    )?;
+    let mut nodes: Vec<LinkNode> = stmt
+        .query_map(rusqlite::params![project_id, workspace_id], |row| {
+            Ok(LinkNode { id: row.get(0)?, title: row.get(1)?, status: row.get(2)?, inbound: 0, outbound: 0 })
+        })?
+        .filter_map(|r| r.ok())
+        .collect();
+    let index: HashMap<String, usize> = nodes.iter().enumerate().map(|(i, n)| (n.id.clone(), i)).collect();
+
+    let mut stmt = conn.prepare("SELECT source_document_id, target_key FROM document_links")?;
+    let rows = stmt.query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))?;
+    let mut edges = Vec::new();
+    let mut seen = HashSet::new();
+    for (source, key) in rows.filter_map(|r| r.ok()) {
+        let Some(target) = resolve(&key, &published) else {
+            continue;
+        };
+        let (Some(&s), Some(&t)) = (index.get(&source), index.get(&target)) else {
+            continue;
+        };
+        if s == t || !seen.insert((s, t)) {
+            continue;
+        }
+        nodes[s].outbound += 1;
+        nodes[t].inbound += 1;
+        edges.push(LinkEdge { source, target });
+    }
+    Ok(LinkGraph { nodes, edges })
+}
+
+/// Re-extract links from every document in the active workspace, e.g.
+/// after an import. Returns how many documents were indexed.
+#[tauri::command]
+pub async fn rebuild_link_index(app: AppHandle) -> Result<usize, AppError> {
+    let workspace_id = workspaces::current(&app);
+    let conn = db::get_db(&app)?;
+    let mut stmt = conn.prepare("SELECT id, html_content FROM documents WHERE workspace_id = ?1")?;
+    let docs: Vec<(String, String)> = stmt
+        .query_map(rusqlite::params![workspace_id], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .filter_map(|r| r.ok())
+        .collect();
+    for (id, html) in &docs {
+        index_document(&conn, id, html);
+    }
+    Ok(docs.len())
+}
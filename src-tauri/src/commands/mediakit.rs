@@ -0,0 +1,299 @@
+use chrono::{Datelike, Utc};
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tauri::AppHandle;
+
+use crate::commands::analytics::{escape, subscribers_at, thousands};
+use crate::commands::export::{build_pdf, PdfExportOptions};
+use crate::commands::charts::{self, ChartSpec};
+use crate::commands::workspaces::{self, Scope};
+use crate::commands::{settings, testimonials};
+use crate::db;
+use crate::error::AppError;
+
+// ─── Media kit ──────────────────────────────────────────────────
+//
+// A sponsor-facing PDF: audience size and growth, open and click rates,
+// the demographics and ad rates the writer keeps in settings, and their
+// best testimonials. Numbers come from the same sources as the analytics
+// views (latest snapshot per account, else the local subscriber list),
+// limited to the active workspace.
+// Charts are embedded as PNGs, with the exact figures in a table under
+// them.
+
+pub(crate) const MEDIA_KIT_SETTING: &str = "media_kit";
+const DEFAULT_MONTHS: u32 = 12;
+
+/// One row of the demographics or rates table, e.g. "Ages 25–34" / "41%".
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct MediaKitField {
+    pub label: String,
+    pub value: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct MediaKitSettings {
+    /// Heads the kit as "<name> media kit"
+    pub publication_name: String,
+    pub tagline: String,
+    pub demographics: Vec<MediaKitField>,
+    /// Sponsorship placements and prices
+    pub rates: Vec<MediaKitField>,
+    pub contact_email: String,
+    /// Testimonials quoted, best first
+    pub testimonial_count: Option<i64>,
+}
+
+fn media_kit_settings(app: &AppHandle) -> MediaKitSettings {
+    settings::get_setting(app, MEDIA_KIT_SETTING).unwrap_or_default()
+}
+
+#[tauri::command]
+pub async fn get_media_kit_settings(app: AppHandle) -> Result<MediaKitSettings, AppError> {
+    Ok(media_kit_settings(&app))
+}
+
+#[tauri::command]
+pub async fn save_media_kit_settings(app: AppHandle, settings: MediaKitSettings) -> Result<(), AppError> {
+    if settings.testimonial_count.is_some_and(|n| !(0..=20).contains(&n)) {
+        return Err(AppError::validation("A media kit quotes up to 20 testimonials"));
+    }
+    settings::set_setting(&app, MEDIA_KIT_SETTING, serde_json::to_value(&settings)?)?;
+    Ok(())
+}
+
+/// Subscribers at the end of a month and that month's average open rate.
+struct MonthFigures {
+    label: String,
+    subscribers: i64,
+    open_rate: Option<f64>,
+}
+
+fn monthly_figures(conn: &Connection, scope: &Scope, months: u32) -> Result<Vec<MonthFigures>, AppError> {
+    let now = Utc::now();
+    let this_month = now.date_naive().with_day(1).unwrap_or(now.date_naive());
+    let first = this_month
+        .checked_sub_months(chrono::Months::new(months - 1))
+        .ok_or_else(|| AppError::validation("Invalid month range"))?;
+
+    let mut stmt = conn.prepare(
+        "SELECT strftime('%Y-%m', captured_at) AS month, AVG(open_rate) FROM analytics_snapshots
+         WHERE captured_at >= ?1 AND open_rate > 0 AND (platform || ':' || account_id) IN (SELECT value FROM json_each(?2))
+         GROUP BY month",
+    )?;
+    let open_rates: HashMap<String, f64> = stmt
+        .query_map(rusqlite::params![first.format("%Y-%m-%d").to_string(), scope.accounts], |row| {
+            Ok((row.get(0)?, row.get(1)?))
+        })?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    let mut figures = Vec::new();
+    for i in 0..months {
+        let start = first + chrono::Months::new(i);
+        let end = if start == this_month {
+            now.to_rfc3339()
+        } else {
+            ((start + chrono::Months::new(1)) - chrono::Duration::days(1)).format("%Y-%m-%dT23:59:59~").to_string()
+        };
+        figures.push(MonthFigures {
+            label: start.format("%b %Y").to_string(),
+            subscribers: subscribers_at(conn, scope, &end, None)? as i64,
+            open_rate: open_rates.get(&start.format("%Y-%m").to_string()).copied(),
+        });
+    }
+    Ok(figures)
+}
+
+/// Average open and click rates (percentages) across each account's
+/// latest snapshot that reports them.
+fn current_rates(conn: &Connection, scope: &Scope) -> Result<(Option<f64>, Option<f64>), AppError> {
+    Ok(conn.query_row(
+        "SELECT AVG(open_rate), AVG(click_rate) FROM analytics_snapshots s
+         WHERE captured_at = (
+             SELECT MAX(captured_at) FROM analytics_snapshots
+             WHERE platform = s.platform AND account_id = s.account_id
+         )
+         AND open_rate > 0 AND (platform || ':' || account_id) IN (SELECT value FROM json_each(?1))",
+        rusqlite::params![scope.accounts],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    )?)
+}
+
+/// Subscribers per platform from the latest snapshots, else from the
+/// local subscriber list.
+fn platform_breakdown(conn: &Connection, scope: &Scope) -> Result<Vec<(String, i64)>, AppError> {
+    let mut stmt = conn.prepare(
+        "SELECT platform, SUM(total_subscribers) AS total FROM analytics_snapshots s
+         WHERE captured_at = (
+             SELECT MAX(captured_at) FROM analytics_snapshots
+             WHERE platform = s.platform AND account_id = s.account_id
+         )
+         AND (platform || ':' || account_id) IN (SELECT value FROM json_each(?1))
+         GROUP BY platform ORDER BY total DESC",
+    )?;
+    let from_snapshots: Vec<(String, i64)> = stmt
+        .query_map(rusqlite::params![scope.accounts], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .filter_map(|r| r.ok())
+        .collect();
+    if !from_snapshots.is_empty() {
+        return Ok(from_snapshots);
+    }
+    let mut stmt = conn.prepare(
+        "SELECT sp.platform, COUNT(DISTINCT sp.subscriber_id) AS total FROM subscriber_platforms sp
+         JOIN subscribers s ON s.id = sp.subscriber_id
+         WHERE s.workspace_id = ?1
+         GROUP BY sp.platform ORDER BY total DESC",
+    )?;
+    let local = stmt
+        .query_map(rusqlite::params![scope.workspace_id], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .filter_map(|r| r.ok())
+        .collect();
+    Ok(local)
+}
+
+fn percent(rate: Option<f64>) -> String {
+    rate.map_or_else(|| "—".to_string(), |r| format!("{:.1}%", r))
+}
+
+fn fields_table(fields: &[MediaKitField]) -> String {
+    let mut html = String::from("<table>");
+    for field in fields.iter().filter(|f| !f.label.trim().is_empty() || !f.value.trim().is_empty()) {
+        html.push_str(&format!("<tr><td>{}</td><td>{}</td></tr>", escape(field.label.trim()), escape(field.value.trim())));
+    }
+    html.push_str("</table>");
+    html
+}
+
+/// The kit's HTML, below the title.
+fn kit_body(
+    conn: &Connection,
+    scope: &Scope,
+    kit: &MediaKitSettings,
+    project_id: Option<&str>,
+    months: u32,
+    chart_text: bool,
+) -> Result<String, AppError> {
+    let now = Utc::now();
+    let subscribers = subscribers_at(conn, scope, &now.to_rfc3339(), None)? as i64;
+    let month_ago = subscribers_at(conn, scope, &(now - chrono::Duration::days(30)).to_rfc3339(), None)? as i64;
+    let (open_rate, click_rate) = current_rates(conn, scope)?;
+    let platforms = platform_breakdown(conn, scope)?;
+    let figures = monthly_figures(conn, scope, months)?;
+    let quotes = match kit.testimonial_count.unwrap_or(4) {
+        0 => Vec::new(),
+        limit => testimonials::best_testimonials(conn, limit, None, project_id)?,
+    };
+
+    let mut body = String::new();
+    if !kit.tagline.trim().is_empty() {
+        body.push_str(&format!("<p><em>{}</em></p>", escape(kit.tagline.trim())));
+    }
+    body.push_str("<h2>Audience</h2><ul>");
+    body.push_str(&format!("<li>{} subscribers</li>", thousands(subscribers)));
+    body.push_str(&format!("<li>{:+} in the last 30 days</li>", subscribers - month_ago));
+    if open_rate.is_some() {
+        body.push_str(&format!("<li>Average open rate: {}</li>", percent(open_rate)));
+        body.push_str(&format!("<li>Average click rate: {}</li>", percent(click_rate)));
+    }
+    body.push_str("</ul>");
+    if platforms.len() > 1 {
+        body.push_str("<table><tr><th>Platform</th><th>Subscribers</th></tr>");
+        for (platform, count) in &platforms {
+            body.push_str(&format!("<tr><td>{}</td><td>{}</td></tr>", escape(platform), thousands(*count)));
+        }
+        body.push_str("</table>");
+    }
+
+    let labels: Vec<String> = figures.iter().map(|f| f.label.clone()).collect();
+    let subscriber_values = figures.iter().map(|f| f.subscribers as f64).collect();
+    let growth = ChartSpec::single("bar", labels.clone(), "Subscribers", subscriber_values, "number");
+    body.push_str(&format!(
+        "<h2>Subscriber growth</h2><p><img src=\"{}\" alt=\"Subscribers per month\"></p>",
+        charts::png_data_uri(&growth, chart_text)?
+    ));
+    let has_open_rates = figures.iter().any(|f| f.open_rate.is_some());
+    if has_open_rates {
+        let open_values = figures.iter().map(|f| f.open_rate.unwrap_or(0.0)).collect();
+        let opens = ChartSpec::single("line", labels, "Open rate", open_values, "percent");
+        body.push_str(&format!(
+            "<h2>Open rates</h2><p><img src=\"{}\" alt=\"Average open rate per month\"></p>",
+            charts::png_data_uri(&opens, chart_text)?
+        ));
+    }
+    body.push_str("<table><tr><th>Month</th><th>Subscribers</th>");
+    if has_open_rates {
+        body.push_str("<th>Open rate</th>");
+    }
+    body.push_str("</tr>");
+    for month in &figures {
+        body.push_str(&format!("<tr><td>{}</td><td>{}</td>", month.label, thousands(month.subscribers)));
+        if has_open_rates {
+            body.push_str(&format!("<td>{}</td>", percent(month.open_rate)));
+        }
+        body.push_str("</tr>");
+    }
+    body.push_str("</table>");
+
+    if !kit.demographics.is_empty() {
+        body.push_str("<h2>Readers</h2>");
+        body.push_str(&fields_table(&kit.demographics));
+    }
+    if !quotes.is_empty() {
+        body.push_str("<h2>What readers say</h2>");
+        for quote in &quotes {
+            let author = match &quote.author_handle {
+                Some(handle) if !handle.is_empty() => format!("{} ({})", quote.author_name, handle),
+                _ => quote.author_name.clone(),
+            };
+            body.push_str(&format!("<blockquote>“{}”</blockquote><p>— {}</p>", escape(&quote.quote), escape(&author)));
+        }
+    }
+    if !kit.rates.is_empty() {
+        body.push_str("<h2>Sponsorship</h2>");
+        body.push_str(&fields_table(&kit.rates));
+    }
+    if !kit.contact_email.trim().is_empty() {
+        let email = escape(kit.contact_email.trim());
+        body.push_str(&format!("<h2>Contact</h2><p><a href=\"mailto:{email}\">{email}</a></p>"));
+    }
+
+    Ok(body)
+}
+
+/// Build a media kit PDF for sponsors. `months` of growth are charted
+/// (12 by default, 3 to 36); `project_id` limits the testimonials quoted
+/// to that project's issues.
+#[tauri::command]
+pub async fn generate_media_kit_pdf(
+    app: AppHandle,
+    project_id: Option<String>,
+    months: Option<u32>,
+) -> Result<Vec<u8>, AppError> {
+    let kit = media_kit_settings(&app);
+    let title = match kit.publication_name.trim() {
+        "" => "Media kit".to_string(),
+        name => format!("{} media kit", name),
+    };
+    let scope = workspaces::scope(&app);
+    let body = {
+        let conn = db::get_db(&app)?;
+        let months = months.unwrap_or(DEFAULT_MONTHS).clamp(3, 36);
+        let body = kit_body(&conn, &scope, &kit, project_id.as_deref(), months, charts::chart_text(&app))?;
+        db::log_activity(&conn, "report.media_kit", "report", None, Some(&format!("Generated {}", title)));
+        body
+    };
+
+    let options = PdfExportOptions {
+        footer_text: Some(kit.contact_email.trim().to_string()).filter(|e| !e.is_empty()),
+        page_numbers: true,
+        ..Default::default()
+    };
+    let pdf = tokio::task::spawn_blocking(move || build_pdf(&title, &body, None, None, options))
+        .await
+        .map_err(|e| format!("Export task failed: {}", e))??;
+    Ok(pdf)
+}
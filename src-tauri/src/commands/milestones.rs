@@ -0,0 +1,118 @@
+use chrono::Utc;
+use rusqlite::Connection;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+
+use crate::commands::{analytics, revenue, settings, workspaces};
+use crate::db;
+use crate::error::AppError;
+
+// ─── Milestones ─────────────────────────────────────────────────
+//
+// Evaluated after anything that can move the numbers (subscriber syncs,
+// backfills, revenue entries and imports). A threshold is recorded the
+// first time it's crossed and announced once on "milestone:reached".
+
+const SUBSCRIBER_THRESHOLDS: &[i64] = &[1_000, 5_000, 10_000];
+
+/// First $1k MRR
+const MRR_THRESHOLDS_CENTS: &[i64] = &[100_000];
+
+#[derive(Debug, Serialize, Clone)]
+pub struct Milestone {
+    pub id: String,
+    /// "subscribers" | "mrr"
+    pub kind: String,
+    pub threshold: i64,
+    /// The reading that crossed the threshold
+    pub value: i64,
+    pub achieved_at: String,
+    pub title: String,
+}
+
+fn title(kind: &str, threshold: i64) -> String {
+    match kind {
+        "mrr" => format!("${} MRR", threshold / 100),
+        _ if threshold >= 1_000 && threshold % 1_000 == 0 => format!("{}k subscribers", threshold / 1_000),
+        _ => format!("{} subscribers", threshold),
+    }
+}
+
+fn record(conn: &Connection, kind: &str, thresholds: &[i64], value: i64) -> Vec<Milestone> {
+    let now = Utc::now().to_rfc3339();
+    let mut reached = Vec::new();
+    for &threshold in thresholds.iter().filter(|&&t| value >= t) {
+        let milestone = Milestone {
+            id: uuid::Uuid::new_v4().to_string(),
+            kind: kind.to_string(),
+            threshold,
+            value,
+            achieved_at: now.clone(),
+            title: title(kind, threshold),
+        };
+        let inserted = conn
+            .execute(
+                "INSERT OR IGNORE INTO milestones (id, kind, threshold, value, achieved_at) VALUES (?1, ?2, ?3, ?4, ?5)",
+                rusqlite::params![milestone.id, kind, threshold, value, now],
+            )
+            .unwrap_or(0);
+        if inserted > 0 {
+            reached.push(milestone);
+        }
+    }
+    reached
+}
+
+/// Record and announce any thresholds crossed since the last evaluation.
+/// Failures are logged rather than returned: callers are finishing a sync
+/// or import and shouldn't fail because of this.
+pub(crate) fn evaluate(app: &AppHandle, conn: &Connection) -> Vec<Milestone> {
+    let scope = workspaces::scope(app);
+    let subscribers = match analytics::subscribers_at(conn, &scope, &Utc::now().to_rfc3339(), None) {
+        Ok(n) => n as i64,
+        Err(e) => {
+            crate::trace::log("Milestones", &format!("Subscriber count failed: {}", e));
+            return Vec::new();
+        }
+    };
+    let amortized = settings::get_setting::<String>(app, revenue::RECOGNITION_MODE_SETTING).as_deref()
+        == Some("amortized");
+    let mrr = revenue::current_mrr(conn, &scope.workspace_id, amortized).unwrap_or(0);
+
+    let mut reached = record(conn, "subscribers", SUBSCRIBER_THRESHOLDS, subscribers);
+    reached.extend(record(conn, "mrr", MRR_THRESHOLDS_CENTS, mrr));
+
+    for milestone in &reached {
+        db::log_activity(conn, "milestone.reached", "milestone", Some(&milestone.id), Some(&milestone.title));
+        let _ = app.emit("milestone:reached", milestone);
+    }
+    reached
+}
+
+#[tauri::command]
+pub async fn list_milestones(app: AppHandle) -> Result<Vec<Milestone>, AppError> {
+    let conn = db::get_db(&app)?;
+    let mut stmt = conn.prepare(
+        "SELECT id, kind, threshold, value, achieved_at FROM milestones ORDER BY achieved_at DESC",
+    )?;
+    let rows = stmt.query_map([], |row| {
+        let kind: String = row.get(1)?;
+        let threshold: i64 = row.get(2)?;
+        Ok(Milestone {
+            id: row.get(0)?,
+            title: title(&kind, threshold),
+            kind,
+            threshold,
+            value: row.get(3)?,
+            achieved_at: row.get(4)?,
+        })
+    })?;
+    Ok(rows.filter_map(|r| r.ok()).collect())
+}
+
+/// Re-run evaluation on demand; returns only newly reached milestones.
+#[tauri::command]
+pub async fn evaluate_milestones(app: AppHandle) -> Result<Vec<Milestone>, AppError> {
+    let conn = db::get_db(&app)?;
+    Ok(evaluate(&app, &conn))
+}
@@ -1,8 +1,49 @@
 pub mod ai;
+pub mod accessibility;
+pub mod analytics;
+pub mod archives;
+pub mod attachments;
+pub mod audit;
 pub mod audience;
+pub mod backfill;
+pub mod blocks;
+pub mod charts;
+pub mod compare;
+pub mod contacts;
 pub mod credentials;
+pub mod deliverability;
 pub mod export;
+pub mod export_history;
+pub mod export_jobs;
+pub mod focus;
+pub mod google;
+pub mod ideas;
 pub mod images;
+pub mod linkpage;
+pub mod links;
+pub mod mediakit;
+pub mod milestones;
+pub mod offline;
 pub mod platform;
+pub mod podcast;
+pub mod publish_profiles;
+pub mod replace;
 pub mod revenue;
 pub mod scheduler;
+pub mod settings;
+pub mod signup;
+pub mod site;
+pub mod similarity;
+pub mod smtp;
+pub mod snapshots;
+pub mod snippets;
+pub mod spelling;
+pub mod storage;
+pub mod style;
+pub mod testimonials;
+pub mod transcription;
+pub mod usage;
+pub mod vault;
+pub mod webhooks;
+pub mod windows;
+pub mod workspaces;
@@ -0,0 +1,352 @@
+use crate::commands::audience;
+use crate::commands::platform::{self, PublishRequest};
+use crate::connectivity;
+use crate::db;
+use crate::error::{AppError, ErrorKind};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+use tauri::{AppHandle, Emitter};
+
+// ─── Offline queue ──────────────────────────────────────────────
+//
+// Publish and subscriber-sync commands that can't reach the network are
+// stored in pending_operations and answered with an `AppError` of kind
+// "queued". The connectivity monitor flushes the queue, in order, once it is
+// back online. A publish is only queued when its request never left: after
+// a timeout the post may already exist, and replaying it would post twice.
+
+/// A command captured for later replay. Stored as JSON in payload_json.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum QueuedOperation {
+    Publish {
+        platform: String,
+        account_id: String,
+        publication_id: String,
+        request: Box<PublishRequest>,
+    },
+    SyncSubscribers {
+        platform: String,
+        account_id: String,
+        publication_id: Option<String>,
+    },
+}
+
+impl QueuedOperation {
+    /// Whether running it twice does no harm.
+    fn idempotent(&self) -> bool {
+        matches!(self, QueuedOperation::SyncSubscribers { .. })
+    }
+
+    fn kind(&self) -> &'static str {
+        match self {
+            QueuedOperation::Publish { .. } => "publish",
+            QueuedOperation::SyncSubscribers { .. } => "sync_subscribers",
+        }
+    }
+
+    fn describe(&self) -> String {
+        match self {
+            QueuedOperation::Publish { platform, request, .. } => {
+                format!("Publish '{}' to {}", request.title, platform)
+            }
+            QueuedOperation::SyncSubscribers { platform, .. } => {
+                format!("Sync subscribers from {}", platform)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PendingOperation {
+    pub id: String,
+    pub kind: String,
+    pub description: String,
+    pub operation: QueuedOperation,
+    pub status: String, // "queued" | "running" | "done" | "failed"
+    pub attempts: i64,
+    pub last_error: Option<String>,
+    pub trace_id: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ConnectivityStatus {
+    pub online: bool,
+    pub queued: i64,
+}
+
+/// Emitted as "offline:progress" while the queue is flushed.
+#[derive(Debug, Serialize, Clone)]
+pub struct OfflineQueueProgress {
+    pub operation_id: Option<String>,
+    pub status: String, // "running" | "done" | "failed" | "queued" | "finished"
+    pub processed: usize,
+    pub total: usize,
+    pub message: String,
+}
+
+static FLUSHING: AtomicBool = AtomicBool::new(false);
+
+/// Whether a failed call of `op` should be queued rather than reported.
+pub(crate) fn should_queue(err: &AppError, op: &QueuedOperation) -> bool {
+    err.kind == ErrorKind::Network && (err.unsent || op.idempotent())
+}
+
+/// At startup: operations left 'running' by a quit go back in the queue.
+/// A publish might have gone through before the quit, so it's marked failed
+/// for the user to check instead of being sent again.
+pub(crate) fn recover_interrupted(app: &AppHandle) {
+    let Ok(conn) = db::get_db(app) else { return };
+    let now = Utc::now().to_rfc3339();
+    let result = conn
+        .execute(
+            "UPDATE pending_operations SET status = 'queued', updated_at = ?1
+             WHERE status = 'running' AND kind != 'publish'",
+            rusqlite::params![now],
+        )
+        .and_then(|_| {
+            conn.execute(
+                "UPDATE pending_operations
+                 SET status = 'failed', updated_at = ?1,
+                     last_error = 'Interrupted while publishing. Check whether the post went out before publishing again.'
+                 WHERE status = 'running' AND kind = 'publish'",
+                rusqlite::params![now],
+            )
+        });
+    if let Err(e) = result {
+        crate::trace::log("Offline", &format!("Failed to recover interrupted operations: {}", e));
+    }
+}
+
+/// Store `op` for replay and return the "queued" error the command hands
+/// back to the frontend.
+pub(crate) fn enqueue(app: &AppHandle, op: QueuedOperation) -> AppError {
+    let description = op.describe();
+    let stored = (|| -> Result<String, String> {
+        let conn = db::get_db(app)?;
+        let id = uuid::Uuid::new_v4().to_string();
+        let now = Utc::now().to_rfc3339();
+        let payload = serde_json::to_string(&op).map_err(|e| e.to_string())?;
+        conn.execute(
+            "INSERT INTO pending_operations (id, kind, payload_json, status, attempts, trace_id, created_at, updated_at)
+             VALUES (?1, ?2, ?3, 'queued', 0, ?4, ?5, ?5)",
+            rusqlite::params![id, op.kind(), payload, crate::trace::current(), now],
+        )
+        .map_err(|e| format!("Failed to queue operation: {}", e))?;
+        db::log_activity(&conn, "offline.queued", "pending_operation", Some(&id), Some(&description));
+        Ok(id)
+    })();
+
+    match stored {
+        Ok(_) => AppError::new(
+            ErrorKind::Queued,
+            format!("You're offline. {} is queued and will run when you're back online.", description),
+        ),
+        Err(e) => AppError::from(e),
+    }
+}
+
+fn load_operations(conn: &rusqlite::Connection, status: Option<&str>) -> Result<Vec<PendingOperation>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, kind, payload_json, status, attempts, last_error, trace_id, created_at, updated_at
+             FROM pending_operations
+             WHERE (?1 IS NULL OR status = ?1)
+             ORDER BY created_at ASC",
+        )
+        .map_err(|e| format!("Query failed: {}", e))?;
+    let rows = stmt
+        .query_map(rusqlite::params![status], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, String>(3)?,
+                row.get::<_, i64>(4)?,
+                row.get::<_, Option<String>>(5)?,
+                row.get::<_, Option<String>>(6)?,
+                row.get::<_, String>(7)?,
+                row.get::<_, String>(8)?,
+            ))
+        })
+        .map_err(|e| format!("Query map failed: {}", e))?;
+
+    Ok(rows
+        .filter_map(|r| r.ok())
+        .filter_map(|(id, kind, payload, status, attempts, last_error, trace_id, created_at, updated_at)| {
+            // Skip payloads from an incompatible older version rather than fail the list
+            let operation: QueuedOperation = serde_json::from_str(&payload).ok()?;
+            Some(PendingOperation {
+                id,
+                kind,
+                description: operation.describe(),
+                operation,
+                status,
+                attempts,
+                last_error,
+                trace_id,
+                created_at,
+                updated_at,
+            })
+        })
+        .collect())
+}
+
+fn set_status(app: &AppHandle, id: &str, status: &str, error: Option<&str>) {
+    if let Ok(conn) = db::get_db(app) {
+        let now = Utc::now().to_rfc3339();
+        let _ = conn.execute(
+            "UPDATE pending_operations
+             SET status = ?1, last_error = COALESCE(?2, last_error),
+                 attempts = attempts + CASE WHEN ?1 = 'running' THEN 1 ELSE 0 END, updated_at = ?3
+             WHERE id = ?4",
+            rusqlite::params![status, error, now, id],
+        );
+    }
+}
+
+async fn run(app: &AppHandle, op: QueuedOperation) -> Result<(), AppError> {
+    match op {
+        QueuedOperation::Publish { platform, account_id, publication_id, request } => {
+            platform::publish_now(app, &platform, &account_id, &publication_id, *request).await?;
+        }
+        QueuedOperation::SyncSubscribers { platform, account_id, publication_id } => {
+            audience::sync_now(app, &platform, &account_id, publication_id.as_deref()).await?;
+        }
+    }
+    Ok(())
+}
+
+/// Replay queued operations in the order they were queued. Stops (leaving
+/// the rest queued) at the first network failure. Only one flush runs at a
+/// time.
+pub(crate) async fn flush_queue(app: &AppHandle) {
+    if FLUSHING.swap(true, Ordering::SeqCst) {
+        return;
+    }
+
+    let queued = match db::get_db(app).and_then(|conn| load_operations(&conn, Some("queued"))) {
+        Ok(ops) => ops,
+        Err(e) => {
+            crate::trace::log("Offline", &format!("Failed to load queue: {}", e));
+            FLUSHING.store(false, Ordering::SeqCst);
+            return;
+        }
+    };
+    let total = queued.len();
+    if total == 0 {
+        FLUSHING.store(false, Ordering::SeqCst);
+        return;
+    }
+
+    let emit = |operation_id: Option<&str>, status: &str, processed: usize, message: String| {
+        let _ = app.emit(
+            "offline:progress",
+            OfflineQueueProgress {
+                operation_id: operation_id.map(String::from),
+                status: status.to_string(),
+                processed,
+                total,
+                message,
+            },
+        );
+    };
+
+    let mut processed = 0;
+    for op in queued {
+        emit(Some(&op.id), "running", processed, op.description.clone());
+        set_status(app, &op.id, "running", None);
+
+        // Replay under the trace id of the command that queued it
+        let trace_id = op.trace_id.clone().unwrap_or_else(crate::trace::new_id);
+        let result = crate::trace::scope(trace_id, run(app, op.operation.clone())).await;
+        match result {
+            Ok(()) => {
+                processed += 1;
+                set_status(app, &op.id, "done", None);
+                emit(Some(&op.id), "done", processed, op.description);
+            }
+            Err(e) if should_queue(&e, &op.operation) => {
+                set_status(app, &op.id, "queued", Some(&e.message));
+                emit(Some(&op.id), "queued", processed, e.message);
+                connectivity::set_online(app, false);
+                break;
+            }
+            Err(e) => {
+                processed += 1;
+                // A publish that timed out after sending isn't replayed
+                let message = if e.kind == ErrorKind::Network {
+                    format!("{}. It may have been published; check before publishing again.", e.message)
+                } else {
+                    e.message
+                };
+                set_status(app, &op.id, "failed", Some(&message));
+                if let Ok(conn) = db::get_db(app) {
+                    db::log_activity(&conn, "offline.failed", "pending_operation", Some(&op.id), Some(&message));
+                }
+                emit(Some(&op.id), "failed", processed, message);
+            }
+        }
+    }
+
+    emit(None, "finished", processed, format!("Processed {} of {} queued operations", processed, total));
+    FLUSHING.store(false, Ordering::SeqCst);
+}
+
+// ─── Commands ───────────────────────────────────────────────────
+
+#[tauri::command]
+pub async fn get_connectivity_status(app: AppHandle) -> Result<ConnectivityStatus, AppError> {
+    let conn = db::get_db(&app)?;
+    let queued: i64 = conn
+        .query_row(
+            "SELECT COUNT(*) FROM pending_operations WHERE status = 'queued'",
+            [],
+            |row| row.get(0),
+        )
+        .unwrap_or(0);
+    Ok(ConnectivityStatus {
+        online: connectivity::is_online(),
+        queued,
+    })
+}
+
+#[tauri::command]
+pub async fn list_pending_operations(
+    app: AppHandle,
+    status: Option<String>,
+) -> Result<Vec<PendingOperation>, AppError> {
+    let conn = db::get_db(&app)?;
+    Ok(load_operations(&conn, status.as_deref())?)
+}
+
+#[tauri::command]
+pub async fn cancel_pending_operation(app: AppHandle, id: String) -> Result<(), AppError> {
+    let conn = db::get_db(&app)?;
+    let changed = conn
+        .execute(
+            "DELETE FROM pending_operations WHERE id = ?1 AND status IN ('queued', 'failed')",
+            rusqlite::params![id],
+        )
+        .map_err(|e| format!("Failed to cancel operation: {}", e))?;
+    if changed == 0 {
+        return Err(AppError::not_found("Operation not found or already running"));
+    }
+    Ok(())
+}
+
+/// Flush the queue now (e.g. from a "Retry now" button). Runs in the
+/// background; progress arrives as "offline:progress" events.
+#[tauri::command]
+pub async fn flush_pending_operations(app: AppHandle) -> Result<(), AppError> {
+    if !connectivity::is_online() {
+        return Err(AppError::new(ErrorKind::Network, "Still offline"));
+    }
+    tokio::spawn(async move {
+        flush_queue(&app).await;
+    });
+    Ok(())
+}
@@ -2,7 +2,17 @@ use serde::{Deserialize, Serialize};
 use tauri::AppHandle;
 use tauri_plugin_store::StoreExt;
 
-use crate::services::{beehiiv, ghost, kit, linkedin, substack, twitter, PlatformService};
+use crate::commands::accessibility;
+use crate::commands::attachments;
+use crate::commands::blocks;
+use crate::commands::offline::{self, QueuedOperation};
+use crate::commands::publish_profiles;
+use crate::commands::usage;
+use crate::connectivity;
+use crate::db;
+use crate::error::{AppError, ErrorKind};
+use crate::sanitize::sanitize_html;
+use crate::services::{beehiiv, ghost, kit, linkedin, mastodon, substack, twitter, PlatformService};
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Publication {
@@ -21,6 +31,9 @@ pub struct Subscriber {
     pub status: String,
     pub created_at: String,
     pub platform: String,
+    /// ISO 3166 alpha-2 code, when the platform knows it
+    #[serde(default)]
+    pub country: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -49,13 +62,99 @@ pub struct PostPerformance {
     pub platform: String,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct PublishRequest {
     pub title: String,
     pub html_content: String,
     pub subtitle: Option<String>,
     pub preview_text: Option<String>,
     pub status: String, // "draft" or "published"
+    #[serde(default)]
+    pub options: PublishOptions,
+    /// Document being published; its attachments are uploaded to platforms
+    /// that host files
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub document_id: Option<String>,
+    /// Filled from the document's attachments at publish time
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attachments: Vec<PublishAttachment>,
+}
+
+/// A file uploaded alongside a post.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PublishAttachment {
+    pub filename: String,
+    pub mime_type: String,
+    /// Absolute path of the stored copy
+    pub path: String,
+}
+
+// ─── Per-platform publish options ───────────────────────────────
+
+/// Platform-specific publish fields. Each service only reads its own entry,
+/// so one value can be stored on a scheduled post regardless of platform.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct PublishOptions {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ghost: Option<GhostPublishOptions>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub beehiiv: Option<BeehiivPublishOptions>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kit: Option<KitPublishOptions>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub twitter: Option<TwitterPublishOptions>,
+    /// Content blocks merged into the HTML on every platform (see blocks.rs)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub block_ids: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct GhostPublishOptions {
+    /// Newsletter slug to email when publishing; omitted means web-only
+    pub newsletter: Option<String>,
+    /// Member filter for the email, e.g. "all", "status:free", "status:-free"
+    pub email_segment: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub meta_title: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub meta_description: Option<String>,
+    /// Social card image URL
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub og_image: Option<String>,
+    /// URL slug; Ghost derives one from the title when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub slug: Option<String>,
+    /// Where the post was first published, when cross-posting
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub canonical_url: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct BeehiivPublishOptions {
+    /// "free" | "premium" | "all"
+    pub audience: Option<String>,
+    #[serde(default)]
+    pub segment_ids: Vec<String>,
+    #[serde(default)]
+    pub content_tags: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct KitPublishOptions {
+    /// Only subscribers with any of these tags receive the broadcast
+    #[serde(default)]
+    pub tag_ids: Vec<u64>,
+    #[serde(default)]
+    pub segment_ids: Vec<u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct TwitterPublishOptions {
+    /// Split the post into a thread instead of a single truncated tweet
+    #[serde(default)]
+    pub thread: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -68,7 +167,40 @@ pub struct ImportedPost {
     pub platform: String,
 }
 
-fn get_api_key(app: &AppHandle, platform: &str, account_id: &str) -> Result<String, String> {
+/// What a connected account's credential allows, so the UI can hide
+/// actions that would only fail.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PlatformCapabilities {
+    pub platform: String,
+    /// e.g. "api_key", "admin_api_key", "staff_token", "content_api_key"
+    pub credential_type: String,
+    pub can_publish: bool,
+    /// Posts can be created but only as drafts for someone else to publish
+    pub drafts_only: bool,
+    pub can_read_posts: bool,
+    pub can_read_subscribers: bool,
+    pub can_read_analytics: bool,
+    pub read_only: bool,
+    pub notes: Vec<String>,
+}
+
+impl PlatformCapabilities {
+    pub fn full(platform: &str, credential_type: &str) -> Self {
+        Self {
+            platform: platform.to_string(),
+            credential_type: credential_type.to_string(),
+            can_publish: true,
+            drafts_only: false,
+            can_read_posts: true,
+            can_read_subscribers: true,
+            can_read_analytics: true,
+            read_only: false,
+            notes: Vec::new(),
+        }
+    }
+}
+
+pub(crate) fn get_api_key(app: &AppHandle, platform: &str, account_id: &str) -> Result<String, AppError> {
     let store = app.store("credentials.json").map_err(|e| e.to_string())?;
     let key = format!("{}:{}", platform, account_id);
     match store.get(&key) {
@@ -77,7 +209,8 @@ fn get_api_key(app: &AppHandle, platform: &str, account_id: &str) -> Result<Stri
                 serde_json::from_value(val.clone()).map_err(|e| e.to_string())?;
             Ok(cred.api_key)
         }
-        None => Err(format!("No credentials found for {}:{}", platform, account_id)),
+        None => Err(AppError::auth(format!("No credentials found for {}:{}", platform, account_id))
+            .with_platform(platform)),
     }
 }
 
@@ -86,8 +219,9 @@ pub async fn connect_platform(
     app: AppHandle,
     platform: String,
     account_id: String,
-) -> Result<bool, String> {
+) -> Result<bool, AppError> {
     let api_key = get_api_key(&app, &platform, &account_id)?;
+    usage::record_api_call(&app, &platform, &account_id);
     match platform.as_str() {
         "beehiiv" => beehiiv::BeehiivService::validate_connection(&api_key).await,
         "substack" => substack::SubstackService::validate_connection(&api_key).await,
@@ -95,7 +229,8 @@ pub async fn connect_platform(
         "ghost" => ghost::GhostService::validate_connection(&api_key).await,
         "twitter" => twitter::TwitterService::validate(&api_key).await,
         "linkedin" => linkedin::LinkedinService::validate(&api_key).await,
-        _ => Err(format!("Unknown platform: {}", platform)),
+        "mastodon" => mastodon::MastodonService::validate(&api_key).await,
+        _ => Err(AppError::validation(format!("Unknown platform: {}", platform))),
     }
 }
 
@@ -104,28 +239,131 @@ pub async fn disconnect_platform(
     app: AppHandle,
     platform: String,
     account_id: String,
-) -> Result<(), String> {
+) -> Result<(), AppError> {
     let store = app.store("credentials.json").map_err(|e| e.to_string())?;
     let key = format!("{}:{}", platform, account_id);
     store.delete(&key);
     store.save().map_err(|e| e.to_string())?;
+
+    let conn = db::get_db(&app)?;
+    conn.execute(
+        "DELETE FROM publications WHERE platform = ?1 AND account_id = ?2",
+        rusqlite::params![platform, account_id],
+    )?;
     Ok(())
 }
 
 #[tauri::command]
-pub async fn get_publications(
+pub async fn get_platform_capabilities(
     app: AppHandle,
     platform: String,
     account_id: String,
-) -> Result<Vec<Publication>, String> {
+) -> Result<PlatformCapabilities, AppError> {
     let api_key = get_api_key(&app, &platform, &account_id)?;
     match platform.as_str() {
+        "ghost" => ghost::GhostService::capabilities(&api_key).await,
+        "beehiiv" | "substack" | "kit" => Ok(PlatformCapabilities::full(&platform, "api_key")),
+        "twitter" | "linkedin" | "mastodon" => Ok(PlatformCapabilities {
+            can_read_posts: false,
+            can_read_subscribers: false,
+            can_read_analytics: false,
+            ..PlatformCapabilities::full(&platform, "oauth_token")
+        }),
+        _ => Err(AppError::validation(format!("Unknown platform: {}", platform))),
+    }
+}
+
+/// Publications for an account, served from the local cache. The network
+/// is only hit the first time; use `refresh_publications` to update.
+#[tauri::command]
+pub async fn get_publications(
+    app: AppHandle,
+    platform: String,
+    account_id: String,
+) -> Result<Vec<Publication>, AppError> {
+    let cached = {
+        let conn = db::get_db(&app)?;
+        cached_publications(&conn, &platform, &account_id)?
+    };
+    if !cached.is_empty() {
+        return Ok(cached);
+    }
+    refresh_publications(app, platform, account_id).await
+}
+
+/// Fetch publications (with subscriber counts where the platform reports
+/// them) and replace the cached copy.
+#[tauri::command]
+pub async fn refresh_publications(
+    app: AppHandle,
+    platform: String,
+    account_id: String,
+) -> Result<Vec<Publication>, AppError> {
+    let api_key = get_api_key(&app, &platform, &account_id)?;
+    usage::record_api_call(&app, &platform, &account_id);
+    let publications = match platform.as_str() {
         "beehiiv" => beehiiv::BeehiivService::get_publications(&api_key).await,
         "substack" => substack::SubstackService::get_publications(&api_key).await,
         "kit" => kit::KitService::get_publications(&api_key).await,
         "ghost" => ghost::GhostService::get_publications(&api_key).await,
-        _ => Err(format!("Unknown platform: {}", platform)),
+        _ => Err(AppError::validation(format!("Unknown platform: {}", platform))),
+    }?;
+
+    let conn = db::get_db(&app)?;
+    store_publications(&conn, &platform, &account_id, &publications)?;
+    Ok(publications)
+}
+
+fn cached_publications(
+    conn: &rusqlite::Connection,
+    platform: &str,
+    account_id: &str,
+) -> Result<Vec<Publication>, AppError> {
+    let mut stmt = conn.prepare(
+        "SELECT publication_id, name, url, subscriber_count, description FROM publications
+         WHERE platform = ?1 AND account_id = ?2 ORDER BY name",
+    )?;
+    let rows = stmt.query_map(rusqlite::params![platform, account_id], |row| {
+        Ok(Publication {
+            id: row.get(0)?,
+            name: row.get(1)?,
+            url: row.get(2)?,
+            platform: platform.to_string(),
+            subscriber_count: row.get::<_, Option<i64>>(3)?.map(|n| n as u64),
+            description: row.get(4)?,
+        })
+    })?;
+    Ok(rows.filter_map(|r| r.ok()).collect())
+}
+
+fn store_publications(
+    conn: &rusqlite::Connection,
+    platform: &str,
+    account_id: &str,
+    publications: &[Publication],
+) -> Result<(), AppError> {
+    let now = chrono::Utc::now().to_rfc3339();
+    conn.execute(
+        "DELETE FROM publications WHERE platform = ?1 AND account_id = ?2",
+        rusqlite::params![platform, account_id],
+    )?;
+    for p in publications {
+        conn.execute(
+            "INSERT INTO publications (platform, account_id, publication_id, name, url, subscriber_count, description, fetched_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            rusqlite::params![
+                platform,
+                account_id,
+                p.id,
+                p.name,
+                p.url,
+                p.subscriber_count.map(|n| n as i64),
+                p.description,
+                now
+            ],
+        )?;
     }
+    Ok(())
 }
 
 #[tauri::command]
@@ -134,8 +372,9 @@ pub async fn get_subscribers(
     platform: String,
     account_id: String,
     publication_id: Option<String>,
-) -> Result<Vec<Subscriber>, String> {
+) -> Result<Vec<Subscriber>, AppError> {
     let api_key = get_api_key(&app, &platform, &account_id)?;
+    usage::record_api_call(&app, &platform, &account_id);
     match platform.as_str() {
         "beehiiv" => {
             beehiiv::BeehiivService::get_subscribers(&api_key, publication_id.as_deref()).await
@@ -147,7 +386,7 @@ pub async fn get_subscribers(
         "ghost" => {
             ghost::GhostService::get_subscribers(&api_key, publication_id.as_deref()).await
         }
-        _ => Err(format!("Unknown platform: {}", platform)),
+        _ => Err(AppError::validation(format!("Unknown platform: {}", platform))),
     }
 }
 
@@ -157,8 +396,9 @@ pub async fn get_analytics(
     platform: String,
     account_id: String,
     publication_id: Option<String>,
-) -> Result<AnalyticsData, String> {
+) -> Result<AnalyticsData, AppError> {
     let api_key = get_api_key(&app, &platform, &account_id)?;
+    usage::record_api_call(&app, &platform, &account_id);
     match platform.as_str() {
         "beehiiv" => {
             beehiiv::BeehiivService::get_analytics(&api_key, publication_id.as_deref()).await
@@ -170,7 +410,7 @@ pub async fn get_analytics(
         "ghost" => {
             ghost::GhostService::get_analytics(&api_key, publication_id.as_deref()).await
         }
-        _ => Err(format!("Unknown platform: {}", platform)),
+        _ => Err(AppError::validation(format!("Unknown platform: {}", platform))),
     }
 }
 
@@ -181,21 +421,137 @@ pub async fn publish_post(
     account_id: String,
     publication_id: String,
     request: PublishRequest,
-) -> Result<String, String> {
-    let api_key = get_api_key(&app, &platform, &account_id)?;
-    match platform.as_str() {
-        "beehiiv" => {
-            beehiiv::BeehiivService::publish(&api_key, &publication_id, request).await
+) -> Result<String, AppError> {
+    crate::trace::traced(async move {
+        accessibility::enforce(&app, &request.html_content, false)?;
+        let queued = || QueuedOperation::Publish {
+            platform: platform.clone(),
+            account_id: account_id.clone(),
+            publication_id: publication_id.clone(),
+            request: Box::new(request.clone()),
+        };
+        if !connectivity::is_online() {
+            return Err(offline::enqueue(&app, queued()));
         }
-        "substack" => {
-            substack::SubstackService::publish(&api_key, &publication_id, request).await
+        match publish_now(&app, &platform, &account_id, &publication_id, request.clone()).await {
+            Err(e) if offline::should_queue(&e, &queued()) => {
+                connectivity::set_online(&app, false);
+                Err(offline::enqueue(&app, queued()))
+            }
+            other => other,
         }
-        "kit" => kit::KitService::publish(&api_key, &publication_id, request).await,
-        "ghost" => {
-            ghost::GhostService::publish(&api_key, &publication_id, request).await
+    })
+    .await
+}
+
+/// Publish without the offline check; the offline queue replays through here.
+pub(crate) async fn publish_now(
+    app: &AppHandle,
+    platform: &str,
+    account_id: &str,
+    publication_id: &str,
+    mut request: PublishRequest,
+) -> Result<String, AppError> {
+    let api_key = get_api_key(app, platform, account_id)?;
+    if let Some(document_id) = request.document_id.as_deref() {
+        let conn = db::get_db(app)?;
+        request.attachments = attachments::for_publish(app, &conn, document_id);
+        if !request.options.block_ids.is_empty() {
+            let assembled = blocks::assemble(
+                &conn,
+                document_id,
+                &request.html_content,
+                &request.options.block_ids,
+                &Default::default(),
+            )?;
+            request.html_content = assembled.html;
+        }
+        publish_profiles::apply(&conn, platform, &mut request)?;
+    }
+    let planned = {
+        let conn = db::get_db(app)?;
+        let planned = usage::publish_usage(&conn, platform, account_id, Some(publication_id), &request.status, 1);
+        usage::check_limits(app, &conn, platform, account_id, &planned)?;
+        planned
+    };
+    let attempt_id = {
+        let conn = db::get_db(app)?;
+        db::start_publish_attempt(&conn, None, platform, account_id, Some(publication_id))
+    };
+    let result = match platform {
+        "beehiiv" => beehiiv::BeehiivService::publish(&api_key, publication_id, request).await,
+        "substack" => substack::SubstackService::publish(&api_key, publication_id, request).await,
+        "kit" => kit::KitService::publish(&api_key, publication_id, request).await,
+        "ghost" => ghost::GhostService::publish(&api_key, publication_id, request).await,
+        _ => Err(AppError::validation(format!("Unknown platform: {}", platform))),
+    };
+    let conn = db::get_db(app)?;
+    db::finish_publish_attempt(&conn, attempt_id, &result);
+    usage::record(&conn, platform, account_id, usage::API_CALLS, 1);
+    if result.is_ok() {
+        for (metric, amount) in planned {
+            usage::record(&conn, platform, account_id, metric, amount);
         }
-        _ => Err(format!("Unknown platform: {}", platform)),
     }
+    result
+}
+
+/// Outcome of publishing to one publication within a multi-target publish.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PublishTargetResult {
+    pub publication_id: String,
+    pub status: String, // "published" | "failed" | "queued"
+    pub post_id: Option<String>,
+    pub error: Option<String>,
+    pub trace_id: Option<String>,
+}
+
+/// Publish the same post to several publications on one account (e.g. a
+/// Ghost or Beehiiv account running multiple newsletters). One failing
+/// target does not stop the others.
+#[tauri::command]
+pub async fn publish_post_multi(
+    app: AppHandle,
+    platform: String,
+    account_id: String,
+    publication_ids: Vec<String>,
+    request: PublishRequest,
+) -> Result<Vec<PublishTargetResult>, AppError> {
+    if publication_ids.is_empty() {
+        return Err(AppError::validation("At least one publication is required"));
+    }
+    // All targets share one trace id
+    crate::trace::traced(async move {
+        let mut results = Vec::new();
+        for publication_id in publication_ids {
+            let outcome = publish_post(
+                app.clone(),
+                platform.clone(),
+                account_id.clone(),
+                publication_id.clone(),
+                request.clone(),
+            )
+            .await;
+            results.push(match outcome {
+                Ok(post_id) => PublishTargetResult {
+                    publication_id,
+                    status: "published".to_string(),
+                    post_id: Some(post_id),
+                    error: None,
+                    trace_id: crate::trace::current(),
+                },
+                Err(e) => PublishTargetResult {
+                    publication_id,
+                    status: if e.kind == ErrorKind::Queued { "queued" } else { "failed" }.to_string(),
+                    post_id: None,
+                    error: Some(e.to_string()),
+                    trace_id: e.trace_id,
+                },
+            });
+        }
+        Ok(results)
+    })
+    .await
 }
 
 // ─── Import from Platforms ──────────────────────────────────────
@@ -206,17 +562,22 @@ pub async fn import_posts(
     platform: String,
     account_id: String,
     publication_id: Option<String>,
-) -> Result<Vec<ImportedPost>, String> {
+) -> Result<Vec<ImportedPost>, AppError> {
     let api_key = get_api_key(&app, &platform, &account_id)?;
-    match platform.as_str() {
+    usage::record_api_call(&app, &platform, &account_id);
+    let mut posts = match platform.as_str() {
         "beehiiv" => {
             beehiiv::BeehiivService::import_posts(&api_key, publication_id.as_deref()).await
         }
         "kit" => kit::KitService::import_posts(&api_key).await,
         "ghost" => ghost::GhostService::import_posts(&api_key).await,
         "substack" => substack::SubstackService::import_posts(&api_key).await,
-        _ => Err(format!("Import not supported for platform: {}", platform)),
+        _ => Err(AppError::validation(format!("Import not supported for platform: {}", platform))),
+    }?;
+    for post in &mut posts {
+        post.html_content = sanitize_html(&post.html_content, post.url.as_deref());
     }
+    Ok(posts)
 }
 
 // ─── Social Platform Posting ────────────────────────────────────
@@ -226,9 +587,9 @@ pub async fn post_tweet(
     app: AppHandle,
     account_id: String,
     content: String,
-) -> Result<String, String> {
+) -> Result<String, AppError> {
     let api_key = get_api_key(&app, "twitter", &account_id)?;
-    twitter::TwitterService::post_tweet(&api_key, &content).await
+    counted_social_post(&app, "twitter", &account_id, 1, twitter::TwitterService::post_tweet(&api_key, &content)).await
 }
 
 #[tauri::command]
@@ -236,9 +597,10 @@ pub async fn post_thread(
     app: AppHandle,
     account_id: String,
     tweets: Vec<String>,
-) -> Result<Vec<String>, String> {
+) -> Result<Vec<String>, AppError> {
     let api_key = get_api_key(&app, "twitter", &account_id)?;
-    twitter::TwitterService::post_thread(&api_key, tweets).await
+    let count = tweets.len() as i64;
+    counted_social_post(&app, "twitter", &account_id, count, twitter::TwitterService::post_thread(&api_key, tweets)).await
 }
 
 #[tauri::command]
@@ -247,7 +609,119 @@ pub async fn post_linkedin(
     account_id: String,
     content: String,
     article_url: Option<String>,
-) -> Result<String, String> {
+) -> Result<String, AppError> {
     let api_key = get_api_key(&app, "linkedin", &account_id)?;
-    linkedin::LinkedinService::post(&api_key, &content, article_url.as_deref()).await
+    counted_social_post(
+        &app,
+        "linkedin",
+        &account_id,
+        1,
+        linkedin::LinkedinService::post(&api_key, &content, article_url.as_deref()),
+    )
+    .await
+}
+
+/// Check the post quota, run the post, and count it when it went out.
+async fn counted_social_post<T>(
+    app: &AppHandle,
+    platform: &str,
+    account_id: &str,
+    posts: i64,
+    post: impl std::future::Future<Output = Result<T, AppError>>,
+) -> Result<T, AppError> {
+    {
+        let conn = db::get_db(app)?;
+        usage::check_limits(app, &conn, platform, account_id, &[(usage::POSTS, posts)])?;
+    }
+    let result = post.await;
+    let conn = db::get_db(app)?;
+    usage::record(&conn, platform, account_id, usage::API_CALLS, posts);
+    if result.is_ok() {
+        usage::record(&conn, platform, account_id, usage::POSTS, posts);
+    }
+    result
+}
+
+// ─── Substack session health ────────────────────────────────────
+//
+// Substack auth is a browser session cookie that stops working without
+// any error until the next write, so scheduled posts would fail silently.
+
+#[derive(Debug, Serialize, Clone)]
+pub struct SubstackSessionStatus {
+    pub account_id: String,
+    pub valid: bool,
+    pub expires_at: Option<String>,
+    pub days_remaining: Option<i64>,
+    /// Pending or held Substack posts for this account
+    pub scheduled_posts: i64,
+    /// Scheduled posts that would go out after the session lapses
+    pub posts_at_risk: i64,
+    pub next_scheduled_at: Option<String>,
+    pub message: String,
+}
+
+pub(crate) async fn substack_session_status(
+    app: &AppHandle,
+    account_id: &str,
+) -> Result<SubstackSessionStatus, AppError> {
+    let api_key = get_api_key(app, "substack", account_id)?;
+    let check = substack::SubstackService::check_session(&api_key).await?;
+
+    let scheduled: Vec<String> = {
+        let conn = db::get_db(app)?;
+        let mut stmt = conn.prepare(
+            "SELECT scheduled_at FROM scheduled_posts
+             WHERE platform = 'substack' AND account_id = ?1 AND status IN ('pending', 'held')
+             ORDER BY scheduled_at ASC",
+        )?;
+        let rows = stmt.query_map(rusqlite::params![account_id], |row| row.get(0))?;
+        rows.filter_map(|r| r.ok()).collect()
+    };
+
+    let now = chrono::Utc::now();
+    let posts_at_risk = if !check.valid {
+        scheduled.len()
+    } else if let Some(expires) = check.expires_at {
+        let cutoff = expires.to_rfc3339();
+        scheduled.iter().filter(|at| at.as_str() >= cutoff.as_str()).count()
+    } else {
+        0
+    };
+    let days_remaining = check.expires_at.map(|e| (e - now).num_days());
+
+    let message = if !check.has_cookie {
+        "No session cookie saved. Add your substack.sid cookie to publish.".to_string()
+    } else if !check.valid {
+        "Session cookie has expired. Sign in to Substack in your browser and copy a fresh substack.sid cookie.".to_string()
+    } else if posts_at_risk > 0 {
+        format!(
+            "Session cookie expires in {} days, before {} scheduled post(s). Refresh it from your browser.",
+            days_remaining.unwrap_or(0).max(0),
+            posts_at_risk
+        )
+    } else if let Some(days) = days_remaining {
+        format!("Session cookie is valid for another {} days.", days.max(0))
+    } else {
+        "Session cookie is valid.".to_string()
+    };
+
+    Ok(SubstackSessionStatus {
+        account_id: account_id.to_string(),
+        valid: check.valid,
+        expires_at: check.expires_at.map(|e| e.to_rfc3339()),
+        days_remaining,
+        scheduled_posts: scheduled.len() as i64,
+        posts_at_risk: posts_at_risk as i64,
+        next_scheduled_at: scheduled.first().cloned(),
+        message,
+    })
+}
+
+#[tauri::command]
+pub async fn check_substack_session(
+    app: AppHandle,
+    account_id: String,
+) -> Result<SubstackSessionStatus, AppError> {
+    substack_session_status(&app, &account_id).await
 }
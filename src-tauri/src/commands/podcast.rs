@@ -0,0 +1,403 @@
+use chrono::{DateTime, Utc};
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+use crate::commands::ai::{self, AiMessage, AiRequest};
+use crate::commands::attachments::{self, Attachment};
+use crate::commands::export::html_to_plain_text;
+use crate::commands::platform::{self, PublishRequest};
+use crate::commands::{usage, windows};
+use crate::db;
+use crate::error::AppError;
+use crate::services::ghost::GhostService;
+
+// ─── Podcast episodes ───────────────────────────────────────────
+//
+// An episode is a document whose body is the show notes, plus one audio
+// attachment. Publishing uploads the audio to the platform, records its
+// hosted URL, and posts the show notes with a link to it. The RSS
+// `<item>` for the episode is built from the same record, so an existing
+// feed can carry it with a proper enclosure.
+
+const EPISODE_TYPES: &[&str] = &["full", "trailer", "bonus"];
+
+/// Show notes longer than this are cut before asking for a summary
+const SUMMARY_INPUT_CHARS: usize = 24_000;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PodcastEpisode {
+    pub document_id: String,
+    pub audio_attachment_id: String,
+    pub season: Option<i64>,
+    pub episode_number: Option<i64>,
+    /// "full" | "trailer" | "bonus"
+    pub episode_type: String,
+    pub explicit: bool,
+    pub duration_secs: Option<i64>,
+    /// Hosted audio URL, set when the episode is published
+    pub audio_url: Option<String>,
+    pub summary: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct EpisodeInput {
+    pub audio_attachment_id: String,
+    pub season: Option<i64>,
+    pub episode_number: Option<i64>,
+    pub episode_type: Option<String>,
+    #[serde(default)]
+    pub explicit: bool,
+    pub duration_secs: Option<i64>,
+    /// Set when the audio is already hosted elsewhere
+    pub audio_url: Option<String>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct EpisodeEnclosure {
+    pub url: String,
+    pub length: i64,
+    pub mime_type: String,
+    /// RSS 2.0 `<item>` with iTunes tags, ready to paste into a feed
+    pub item_xml: String,
+}
+
+fn episode(conn: &Connection, document_id: &str) -> Result<PodcastEpisode, AppError> {
+    conn.query_row(
+        "SELECT document_id, audio_attachment_id, season, episode_number, episode_type, explicit,
+                duration_secs, audio_url, summary, created_at, updated_at
+         FROM podcast_episodes WHERE document_id = ?1",
+        rusqlite::params![document_id],
+        |row| {
+            Ok(PodcastEpisode {
+                document_id: row.get(0)?,
+                audio_attachment_id: row.get(1)?,
+                season: row.get(2)?,
+                episode_number: row.get(3)?,
+                episode_type: row.get(4)?,
+                explicit: row.get::<_, i64>(5)? != 0,
+                duration_secs: row.get(6)?,
+                audio_url: row.get(7)?,
+                summary: row.get(8)?,
+                created_at: row.get(9)?,
+                updated_at: row.get(10)?,
+            })
+        },
+    )
+    .map_err(|_| AppError::not_found(format!("Document '{}' is not a podcast episode", document_id)))
+}
+
+fn audio(app: &AppHandle, conn: &Connection, episode: &PodcastEpisode) -> Result<Attachment, AppError> {
+    attachments::get(app, conn, &episode.audio_attachment_id)
+}
+
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+/// HH:MM:SS, as Apple Podcasts expects for `itunes:duration`.
+fn format_duration(secs: i64) -> String {
+    format!("{:02}:{:02}:{:02}", secs / 3600, (secs % 3600) / 60, secs % 60)
+}
+
+fn item_xml(
+    episode: &PodcastEpisode,
+    title: &str,
+    show_notes_html: &str,
+    published_at: &str,
+    url: &str,
+    audio: &Attachment,
+) -> String {
+    let pub_date = DateTime::parse_from_rfc3339(published_at)
+        .map(|d| d.with_timezone(&Utc))
+        .unwrap_or_else(|_| Utc::now())
+        .to_rfc2822();
+    let description = episode
+        .summary
+        .clone()
+        .unwrap_or_else(|| html_to_plain_text(show_notes_html).trim().to_string());
+
+    let mut xml = String::from("<item>\n");
+    xml.push_str(&format!("  <title>{}</title>\n", xml_escape(title)));
+    xml.push_str(&format!("  <guid isPermaLink=\"false\">{}</guid>\n", xml_escape(&episode.document_id)));
+    xml.push_str(&format!("  <pubDate>{}</pubDate>\n", pub_date));
+    xml.push_str(&format!("  <description>{}</description>\n", xml_escape(&description)));
+    // CDATA can't contain its own terminator
+    xml.push_str(&format!(
+        "  <content:encoded><![CDATA[{}]]></content:encoded>\n",
+        show_notes_html.replace("]]>", "]]]]><![CDATA[>")
+    ));
+    xml.push_str(&format!(
+        "  <enclosure url=\"{}\" length=\"{}\" type=\"{}\" />\n",
+        xml_escape(url),
+        audio.size,
+        xml_escape(&audio.mime_type)
+    ));
+    if let Some(secs) = episode.duration_secs {
+        xml.push_str(&format!("  <itunes:duration>{}</itunes:duration>\n", format_duration(secs)));
+    }
+    if let Some(season) = episode.season {
+        xml.push_str(&format!("  <itunes:season>{}</itunes:season>\n", season));
+    }
+    if let Some(number) = episode.episode_number {
+        xml.push_str(&format!("  <itunes:episode>{}</itunes:episode>\n", number));
+    }
+    xml.push_str(&format!("  <itunes:episodeType>{}</itunes:episodeType>\n", episode.episode_type));
+    xml.push_str(&format!("  <itunes:explicit>{}</itunes:explicit>\n", episode.explicit));
+    xml.push_str("</item>\n");
+    xml
+}
+
+/// Make a document a podcast episode, or update its episode details.
+#[tauri::command]
+pub async fn set_podcast_episode(
+    app: AppHandle,
+    document_id: String,
+    episode: EpisodeInput,
+) -> Result<PodcastEpisode, AppError> {
+    let episode_type = episode.episode_type.unwrap_or_else(|| "full".to_string());
+    if !EPISODE_TYPES.contains(&episode_type.as_str()) {
+        return Err(AppError::validation(format!("Unknown episode type '{}'", episode_type)));
+    }
+    if episode.duration_secs.is_some_and(|d| d < 0) {
+        return Err(AppError::validation("duration_secs can't be negative"));
+    }
+
+    let conn = db::get_db(&app)?;
+    let audio = attachments::get(&app, &conn, &episode.audio_attachment_id)?;
+    if audio.document_id != document_id {
+        return Err(AppError::validation("The audio file is attached to a different document"));
+    }
+    if !audio.mime_type.starts_with("audio/") {
+        return Err(AppError::validation(format!("'{}' is not an audio file", audio.filename)));
+    }
+
+    let now = Utc::now().to_rfc3339();
+    conn.execute(
+        "INSERT INTO podcast_episodes (document_id, audio_attachment_id, season, episode_number, episode_type,
+                                       explicit, duration_secs, audio_url, created_at, updated_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?9)
+         ON CONFLICT (document_id) DO UPDATE SET
+             audio_attachment_id = excluded.audio_attachment_id,
+             season = excluded.season,
+             episode_number = excluded.episode_number,
+             episode_type = excluded.episode_type,
+             explicit = excluded.explicit,
+             duration_secs = excluded.duration_secs,
+             -- A new audio file invalidates the hosted copy of the old one
+             audio_url = CASE
+                 WHEN excluded.audio_url IS NOT NULL THEN excluded.audio_url
+                 WHEN podcast_episodes.audio_attachment_id = excluded.audio_attachment_id THEN podcast_episodes.audio_url
+             END,
+             updated_at = excluded.updated_at",
+        rusqlite::params![
+            document_id,
+            episode.audio_attachment_id,
+            episode.season,
+            episode.episode_number,
+            episode_type,
+            episode.explicit as i64,
+            episode.duration_secs,
+            episode.audio_url,
+            now,
+        ],
+    )?;
+    db::log_activity(&conn, "podcast.episode_updated", "document", Some(&document_id), None);
+    self::episode(&conn, &document_id)
+}
+
+#[tauri::command]
+pub async fn get_podcast_episode(app: AppHandle, document_id: String) -> Result<Option<PodcastEpisode>, AppError> {
+    let conn = db::get_db(&app)?;
+    match episode(&conn, &document_id) {
+        Ok(e) => Ok(Some(e)),
+        Err(_) => Ok(None),
+    }
+}
+
+#[tauri::command]
+pub async fn remove_podcast_episode(app: AppHandle, document_id: String) -> Result<(), AppError> {
+    let conn = db::get_db(&app)?;
+    conn.execute("DELETE FROM podcast_episodes WHERE document_id = ?1", rusqlite::params![document_id])?;
+    Ok(())
+}
+
+/// The episode's RSS enclosure and `<item>`. Needs a hosted audio URL,
+/// from publishing or from `set_podcast_episode`.
+#[tauri::command]
+pub async fn get_episode_enclosure(app: AppHandle, document_id: String) -> Result<EpisodeEnclosure, AppError> {
+    let conn = db::get_db(&app)?;
+    let episode = episode(&conn, &document_id)?;
+    let audio = audio(&app, &conn, &episode)?;
+    let url = episode
+        .audio_url
+        .clone()
+        .ok_or_else(|| AppError::validation("Publish the episode or set its audio URL first"))?;
+    let (title, html, published_at): (String, String, Option<String>) = conn.query_row(
+        "SELECT title, html_content, published_at FROM documents WHERE id = ?1",
+        rusqlite::params![document_id],
+        |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+    )?;
+    let published_at = published_at.unwrap_or_else(|| Utc::now().to_rfc3339());
+
+    Ok(EpisodeEnclosure {
+        item_xml: item_xml(&episode, &title, &html, &published_at, &url, &audio),
+        url,
+        length: audio.size,
+        mime_type: audio.mime_type,
+    })
+}
+
+/// Upload the audio and publish the show notes. Only Ghost hosts audio
+/// among the connected platforms; other attachments go up as usual.
+#[tauri::command]
+pub async fn publish_podcast_episode(
+    app: AppHandle,
+    document_id: String,
+    platform: String,
+    account_id: String,
+    publication_id: String,
+    status: String,
+) -> Result<String, AppError> {
+    if platform != "ghost" {
+        return Err(AppError::validation(format!("{} can't host podcast audio", platform)).with_platform(&platform));
+    }
+    crate::trace::traced(async move {
+        let (episode, audio, title, html, others) = {
+            let conn = db::get_db(&app)?;
+            let episode = episode(&conn, &document_id)?;
+            let audio = audio(&app, &conn, &episode)?;
+            let (title, html): (String, String) = conn.query_row(
+                "SELECT title, html_content FROM documents WHERE id = ?1",
+                rusqlite::params![document_id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )?;
+            let others: Vec<_> = attachments::for_publish(&app, &conn, &document_id)
+                .into_iter()
+                .filter(|a| a.path != audio.path)
+                .collect();
+            (episode, audio, title, html, others)
+        };
+
+        let audio_url = match episode.audio_url {
+            Some(url) => url,
+            None => {
+                let api_key = platform::get_api_key(&app, &platform, &account_id)?;
+                let upload = platform::PublishAttachment {
+                    filename: audio.filename.clone(),
+                    mime_type: audio.mime_type.clone(),
+                    path: audio.path.clone(),
+                };
+                let url = GhostService::upload_file(&api_key, &upload).await?;
+                usage::record_api_call(&app, &platform, &account_id);
+                let conn = db::get_db(&app)?;
+                conn.execute(
+                    "UPDATE podcast_episodes SET audio_url = ?1, updated_at = ?2 WHERE document_id = ?3",
+                    rusqlite::params![url, Utc::now().to_rfc3339(), document_id],
+                )?;
+                url
+            }
+        };
+
+        let listen = format!(
+            "<p><a href=\"{}\">Listen to the episode ({})</a></p>",
+            xml_escape(&audio_url),
+            xml_escape(&audio.filename)
+        );
+        let request = PublishRequest {
+            title,
+            html_content: format!("{}{}", listen, html),
+            subtitle: None,
+            preview_text: episode.summary.clone(),
+            status: status.clone(),
+            options: Default::default(),
+            document_id: None,
+            attachments: others,
+        };
+        let post_id = platform::publish_now(&app, &platform, &account_id, &publication_id, request).await?;
+
+        if status == "published" {
+            let conn = db::get_db(&app)?;
+            let now = Utc::now().to_rfc3339();
+            conn.execute(
+                "UPDATE documents SET status = 'published', published_at = COALESCE(published_at, ?1), updated_at = ?1 WHERE id = ?2",
+                rusqlite::params![now, document_id],
+            )?;
+            db::log_activity(&conn, "podcast.episode_published", "document", Some(&document_id), Some(&platform));
+            windows::document_changed(&app, &document_id, "status", None, Some("published"));
+        }
+        Ok(post_id)
+    })
+    .await
+}
+
+/// Ask an AI provider for a short episode summary, from the audio's
+/// transcript when there is one and the show notes otherwise, and store
+/// it; the enclosure uses it as the item description.
+#[tauri::command]
+pub async fn summarize_podcast_episode(
+    app: AppHandle,
+    document_id: String,
+    provider_id: String,
+) -> Result<String, AppError> {
+    let (title, text) = {
+        let conn = db::get_db(&app)?;
+        let episode = episode(&conn, &document_id)?;
+        let (title, notes): (String, String) = conn.query_row(
+            "SELECT title, html_content FROM documents WHERE id = ?1",
+            rusqlite::params![document_id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )?;
+        // The transcript says more about the episode than the notes do
+        let transcript: Option<String> = audio(&app, &conn, &episode)
+            .ok()
+            .and_then(|a| a.transcript_document_id)
+            .and_then(|id| {
+                conn.query_row(
+                    "SELECT html_content FROM documents WHERE id = ?1",
+                    rusqlite::params![id],
+                    |row| row.get(0),
+                )
+                .ok()
+            });
+        let html = transcript.unwrap_or(notes);
+        let text: String = html_to_plain_text(&html).chars().take(SUMMARY_INPUT_CHARS).collect();
+        (title, text)
+    };
+    if text.trim().is_empty() {
+        return Err(AppError::validation("The episode has no show notes or transcript to summarize"));
+    }
+
+    let response = ai::ai_chat(
+        app.clone(),
+        AiRequest {
+            provider_id,
+            messages: vec![AiMessage {
+                role: "user".to_string(),
+                content: format!("Episode: {}\n\n{}", title, text),
+            }],
+            max_tokens: Some(400),
+            temperature: Some(0.4),
+            system_prompt: Some(
+                "Write a podcast episode description for a podcast directory: two or three plain-text sentences, \
+                 no markdown, no episode number, no sign-off."
+                    .to_string(),
+            ),
+        },
+    )
+    .await?;
+    let summary = response.content.trim().to_string();
+
+    let conn = db::get_db(&app)?;
+    conn.execute(
+        "UPDATE podcast_episodes SET summary = ?1, updated_at = ?2 WHERE document_id = ?3",
+        rusqlite::params![summary, Utc::now().to_rfc3339(), document_id],
+    )?;
+    Ok(summary)
+}
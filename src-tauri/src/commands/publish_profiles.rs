@@ -0,0 +1,247 @@
+use chrono::Utc;
+use rusqlite::{Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+use crate::commands::platform::PublishRequest;
+use crate::db;
+use crate::error::AppError;
+
+// ─── Publish profiles ───────────────────────────────────────────
+//
+// The tags, slug and canonical URL a document goes out with on each
+// platform, so cross-posting the same issue follows every platform's
+// conventions without re-entering them. Profiles are normalized on save
+// to what the platform accepts, and merged into the request whenever the
+// document is published there, immediately or on schedule: tags are added
+// to any given with the request, the slug and canonical URL only fill gaps.
+
+/// Ghost limits slugs to 191 characters
+const MAX_SLUG_LEN: usize = 191;
+
+/// What a platform's post API takes from a profile.
+struct Conventions {
+    name: &'static str,
+    tags: bool,
+    slug: bool,
+    canonical_url: bool,
+}
+
+fn conventions(platform: &str) -> Option<Conventions> {
+    Some(match platform {
+        "ghost" => Conventions { name: "Ghost", tags: true, slug: true, canonical_url: true },
+        "beehiiv" => Conventions { name: "Beehiiv", tags: true, slug: false, canonical_url: false },
+        "kit" => Conventions { name: "Kit", tags: false, slug: false, canonical_url: false },
+        "substack" => Conventions { name: "Substack", tags: false, slug: false, canonical_url: false },
+        _ => return None,
+    })
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct PublishProfile {
+    pub document_id: String,
+    pub platform: String,
+    /// Ghost tags or Beehiiv content tags
+    pub tags: Vec<String>,
+    pub slug: Option<String>,
+    /// Where the issue was first published
+    pub canonical_url: Option<String>,
+    /// Instead of `canonical_url`: the document's latest published URL on
+    /// this platform, looked up at publish time
+    pub canonical_platform: Option<String>,
+    pub updated_at: String,
+}
+
+fn profile_from_row(row: &rusqlite::Row) -> rusqlite::Result<PublishProfile> {
+    let tags: String = row.get(2)?;
+    Ok(PublishProfile {
+        document_id: row.get(0)?,
+        platform: row.get(1)?,
+        tags: serde_json::from_str(&tags).unwrap_or_default(),
+        slug: row.get(3)?,
+        canonical_url: row.get(4)?,
+        canonical_platform: row.get(5)?,
+        updated_at: row.get(6)?,
+    })
+}
+
+const PROFILE_COLUMNS: &str = "document_id, platform, tags, slug, canonical_url, canonical_platform, updated_at";
+
+/// Lowercase words joined by hyphens, as every platform's URLs use.
+fn slugify(text: &str) -> String {
+    let mut slug = String::new();
+    for c in text.trim().chars().flat_map(char::to_lowercase) {
+        if c.is_alphanumeric() {
+            slug.push(c);
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    let slug = slug.trim_end_matches('-');
+    match slug.char_indices().nth(MAX_SLUG_LEN) {
+        Some((end, _)) => slug[..end].trim_end_matches('-').to_string(),
+        None => slug.to_string(),
+    }
+}
+
+/// `profile` as it will be stored, or why the platform can't take it.
+fn normalize(mut profile: PublishProfile) -> Result<PublishProfile, AppError> {
+    let rules = conventions(&profile.platform)
+        .ok_or_else(|| AppError::validation(format!("Unknown platform: {}", profile.platform)))?;
+
+    let mut tags: Vec<String> = Vec::new();
+    for tag in profile.tags.iter().map(|t| t.trim()).filter(|t| !t.is_empty()) {
+        if !tags.iter().any(|t| t.eq_ignore_ascii_case(tag)) {
+            tags.push(tag.to_string());
+        }
+    }
+    profile.tags = tags;
+    profile.slug = profile.slug.as_deref().map(slugify).filter(|s| !s.is_empty());
+    profile.canonical_url = profile.canonical_url.map(|u| u.trim().to_string()).filter(|u| !u.is_empty());
+    profile.canonical_platform = profile.canonical_platform.filter(|p| !p.is_empty());
+
+    if !rules.tags && !profile.tags.is_empty() {
+        return Err(AppError::validation(format!("{} posts don't take tags", rules.name)));
+    }
+    if !rules.slug && profile.slug.is_some() {
+        return Err(AppError::validation(format!("{} posts don't take a slug", rules.name)));
+    }
+    let canonical = profile.canonical_url.is_some() || profile.canonical_platform.is_some();
+    if !rules.canonical_url && canonical {
+        return Err(AppError::validation(format!("{} posts don't take a canonical URL", rules.name)));
+    }
+    if profile.canonical_url.is_some() && profile.canonical_platform.is_some() {
+        return Err(AppError::validation("Set a canonical URL or a platform to take it from, not both"));
+    }
+    if let Some(url) = &profile.canonical_url {
+        if !url.starts_with("https://") && !url.starts_with("http://") {
+            return Err(AppError::validation("The canonical URL must start with http:// or https://"));
+        }
+    }
+    if let Some(source) = &profile.canonical_platform {
+        if *source == profile.platform || conventions(source).is_none() {
+            return Err(AppError::validation(format!("Can't take the canonical URL from '{}'", source)));
+        }
+    }
+    Ok(profile)
+}
+
+fn load(conn: &Connection, document_id: &str, platform: &str) -> Result<Option<PublishProfile>, AppError> {
+    Ok(conn
+        .query_row(
+            &format!("SELECT {} FROM publish_profiles WHERE document_id = ?1 AND platform = ?2", PROFILE_COLUMNS),
+            rusqlite::params![document_id, platform],
+            profile_from_row,
+        )
+        .optional()?)
+}
+
+/// The document's most recent published URL on `platform`.
+fn published_url(conn: &Connection, document_id: &str, platform: &str) -> Option<String> {
+    conn.query_row(
+        "SELECT published_url FROM scheduled_posts
+         WHERE document_id = ?1 AND platform = ?2 AND published_url IS NOT NULL AND published_url != ''
+         ORDER BY updated_at DESC LIMIT 1",
+        rusqlite::params![document_id, platform],
+        |row| row.get(0),
+    )
+    .ok()
+}
+
+/// Merge the document's profile for `platform` into `request`, if it has
+/// one. Called by both publish paths just before the platform service.
+pub(crate) fn apply(conn: &Connection, platform: &str, request: &mut PublishRequest) -> Result<(), AppError> {
+    let Some(document_id) = request.document_id.as_deref() else {
+        return Ok(());
+    };
+    let Some(profile) = load(conn, document_id, platform)? else {
+        return Ok(());
+    };
+    let canonical_url = profile
+        .canonical_url
+        .clone()
+        .or_else(|| profile.canonical_platform.as_deref().and_then(|source| published_url(conn, document_id, source)));
+    let add_tags = |tags: &mut Vec<String>| {
+        for tag in &profile.tags {
+            if !tags.iter().any(|t| t.eq_ignore_ascii_case(tag)) {
+                tags.push(tag.clone());
+            }
+        }
+    };
+    match platform {
+        "ghost" => {
+            let ghost = request.options.ghost.get_or_insert_with(Default::default);
+            add_tags(&mut ghost.tags);
+            if ghost.slug.is_none() {
+                ghost.slug = profile.slug.clone();
+            }
+            if ghost.canonical_url.is_none() {
+                ghost.canonical_url = canonical_url;
+            }
+        }
+        "beehiiv" if !profile.tags.is_empty() => {
+            add_tags(&mut request.options.beehiiv.get_or_insert_with(Default::default).content_tags);
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Every platform profile of a document.
+#[tauri::command]
+pub async fn list_publish_profiles(app: AppHandle, document_id: String) -> Result<Vec<PublishProfile>, AppError> {
+    let conn = db::get_db(&app)?;
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM publish_profiles WHERE document_id = ?1 ORDER BY platform",
+        PROFILE_COLUMNS
+    ))?;
+    let rows = stmt.query_map(rusqlite::params![document_id], profile_from_row)?;
+    Ok(rows.filter_map(|r| r.ok()).collect())
+}
+
+/// Create or replace a document's profile for one platform. Tags are
+/// trimmed and de-duplicated and the slug is normalized; fields the
+/// platform has no use for are rejected.
+#[tauri::command]
+pub async fn save_publish_profile(app: AppHandle, profile: PublishProfile) -> Result<PublishProfile, AppError> {
+    let mut profile = normalize(profile)?;
+    profile.updated_at = Utc::now().to_rfc3339();
+    let conn = db::get_db(&app)?;
+    conn.query_row("SELECT 1 FROM documents WHERE id = ?1", rusqlite::params![profile.document_id], |_| Ok(()))
+        .map_err(|_| AppError::not_found(format!("Document '{}' not found", profile.document_id)))?;
+    conn.execute(
+        "INSERT INTO publish_profiles (document_id, platform, tags, slug, canonical_url, canonical_platform, updated_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+         ON CONFLICT(document_id, platform) DO UPDATE SET tags = excluded.tags, slug = excluded.slug,
+             canonical_url = excluded.canonical_url, canonical_platform = excluded.canonical_platform,
+             updated_at = excluded.updated_at",
+        rusqlite::params![
+            profile.document_id,
+            profile.platform,
+            serde_json::to_string(&profile.tags)?,
+            profile.slug,
+            profile.canonical_url,
+            profile.canonical_platform,
+            profile.updated_at
+        ],
+    )?;
+    db::log_activity(
+        &conn,
+        "document.publish_profile_saved",
+        "document",
+        Some(&profile.document_id),
+        Some(&profile.platform),
+    );
+    Ok(profile)
+}
+
+#[tauri::command]
+pub async fn delete_publish_profile(app: AppHandle, document_id: String, platform: String) -> Result<(), AppError> {
+    let conn = db::get_db(&app)?;
+    conn.execute(
+        "DELETE FROM publish_profiles WHERE document_id = ?1 AND platform = ?2",
+        rusqlite::params![document_id, platform],
+    )?;
+    Ok(())
+}
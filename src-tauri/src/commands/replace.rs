@@ -0,0 +1,317 @@
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+use crate::commands::export::{decode_html_entities, html_escape_text};
+use crate::commands::{analytics, audit, links, snapshots, windows, workspaces};
+use crate::db;
+use crate::error::AppError;
+use crate::text;
+
+// ─── Find and replace ───────────────────────────────────────────
+//
+// Search-and-replace over many documents at once, for fixing a name or a
+// link everywhere it appears. Only text is touched: in html_content the
+// text between tags, in the editor JSON (`content`) the text nodes, so
+// markup and attributes survive. A match can't span a formatting change,
+// e.g. "Acme" in bold followed by " Corp" in plain text.
+
+/// Previews returned per document
+const MAX_PREVIEWS: usize = 5;
+/// Characters of context either side of a preview
+const CONTEXT_CHARS: usize = 40;
+
+/// Which documents to search and how to match. Defaults to every
+/// unarchived document, case-insensitive.
+#[derive(Debug, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct FindScope {
+    pub project_id: Option<String>,
+    /// Only these documents, when not empty
+    pub document_ids: Vec<String>,
+    pub include_archived: bool,
+    pub match_case: bool,
+    /// Skip matches inside longer words ("Acme" in "Acmeco")
+    pub whole_word: bool,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct MatchPreview {
+    pub before: String,
+    pub matched: String,
+    pub after: String,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct DocumentMatches {
+    pub document_id: String,
+    pub title: String,
+    pub matches: usize,
+    /// The first few matches in their surrounding text
+    pub previews: Vec<MatchPreview>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ReplaceResult {
+    pub dry_run: bool,
+    pub replacements: usize,
+    pub documents: Vec<DocumentMatches>,
+}
+
+/// Byte ranges of `query` in `text`.
+fn find_spans(text: &str, query: &[char], scope: &FindScope) -> Vec<(usize, usize)> {
+    let same = |a: char, b: char| a == b || (!scope.match_case && a.to_lowercase().eq(b.to_lowercase()));
+    let is_word = |c: Option<char>| c.is_some_and(|c| c.is_alphanumeric() || c == '_');
+    let mut spans = Vec::new();
+    let mut from = 0;
+    while from < text.len() {
+        let mut rest = text[from..].char_indices();
+        let mut end = from;
+        let mut matched = true;
+        for q in query {
+            match rest.next() {
+                Some((i, c)) if same(c, *q) => end = from + i + c.len_utf8(),
+                _ => {
+                    matched = false;
+                    break;
+                }
+            }
+        }
+        let next = from + text[from..].chars().next().map_or(1, char::len_utf8);
+        if matched
+            && (!scope.whole_word || (!is_word(text[..from].chars().next_back()) && !is_word(text[end..].chars().next())))
+        {
+            spans.push((from, end));
+            from = end;
+        } else {
+            from = next;
+        }
+    }
+    spans
+}
+
+fn replace_spans(text: &str, spans: &[(usize, usize)], replacement: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut last = 0;
+    for (start, end) in spans {
+        out.push_str(&text[last..*start]);
+        out.push_str(replacement);
+        last = *end;
+    }
+    out.push_str(&text[last..]);
+    out
+}
+
+fn preview(text: &str, (start, end): (usize, usize)) -> MatchPreview {
+    let before: String = text[..start].chars().rev().take(CONTEXT_CHARS).collect::<Vec<_>>().into_iter().rev().collect();
+    let after: String = text[end..].chars().take(CONTEXT_CHARS).collect();
+    MatchPreview {
+        before: before.split_whitespace().collect::<Vec<_>>().join(" "),
+        matched: text[start..end].to_string(),
+        after: after.split_whitespace().collect::<Vec<_>>().join(" "),
+    }
+}
+
+/// Run `query` over the text between tags of `html`. Returns the new HTML
+/// (when `replacement` is given), the match count and previews.
+fn replace_in_html(
+    html: &str,
+    query: &[char],
+    replacement: Option<&str>,
+    scope: &FindScope,
+) -> (String, usize, Vec<MatchPreview>) {
+    let mut out = String::with_capacity(html.len());
+    let mut count = 0;
+    let mut previews = Vec::new();
+    let mut rest = html;
+    while !rest.is_empty() {
+        let (segment, tail) = match rest.find('<') {
+            Some(0) => {
+                let end = rest.find('>').map_or(rest.len(), |i| i + 1);
+                out.push_str(&rest[..end]);
+                rest = &rest[end..];
+                continue;
+            }
+            Some(i) => rest.split_at(i),
+            None => (rest, ""),
+        };
+        rest = tail;
+        let decoded = decode_html_entities(segment);
+        let spans = find_spans(&decoded, query, scope);
+        count += spans.len();
+        for span in spans.iter().take(MAX_PREVIEWS.saturating_sub(previews.len())) {
+            previews.push(preview(&decoded, *span));
+        }
+        match replacement {
+            // Untouched segments keep their original entities
+            Some(replacement) if !spans.is_empty() => {
+                out.push_str(&html_escape_text(&replace_spans(&decoded, &spans, replacement)))
+            }
+            _ => out.push_str(segment),
+        }
+    }
+    (out, count, previews)
+}
+
+/// Replace in the text nodes of editor JSON. Content that isn't JSON (old
+/// imports) is treated as plain text.
+fn replace_in_content(content: &str, query: &[char], replacement: &str, scope: &FindScope) -> String {
+    fn walk(node: &mut serde_json::Value, query: &[char], replacement: &str, scope: &FindScope) {
+        match node {
+            serde_json::Value::Object(map) => {
+                if map.get("type").and_then(|t| t.as_str()) == Some("text") {
+                    if let Some(serde_json::Value::String(text)) = map.get_mut("text") {
+                        let spans = find_spans(text, query, scope);
+                        if !spans.is_empty() {
+                            *text = replace_spans(text, &spans, replacement);
+                        }
+                    }
+                }
+                if let Some(children) = map.get_mut("content") {
+                    walk(children, query, replacement, scope);
+                }
+            }
+            serde_json::Value::Array(items) => {
+                for item in items {
+                    walk(item, query, replacement, scope);
+                }
+            }
+            _ => {}
+        }
+    }
+    match serde_json::from_str::<serde_json::Value>(content) {
+        Ok(mut json) if json.is_object() => {
+            walk(&mut json, query, replacement, scope);
+            json.to_string()
+        }
+        _ => replace_spans(content, &find_spans(content, query, scope), replacement),
+    }
+}
+
+/// (id, title, content, html_content, word_count) of the documents in scope.
+type ScopedDocument = (String, String, String, String, i64);
+
+/// Documents of `workspace_id` matching `scope`; other workspaces are never
+/// touched, even with no project selected.
+fn scoped_documents(
+    conn: &rusqlite::Connection,
+    workspace_id: &str,
+    scope: &FindScope,
+) -> Result<Vec<ScopedDocument>, AppError> {
+    let ids = serde_json::to_string(&scope.document_ids)?;
+    let mut stmt = conn.prepare(
+        "SELECT id, title, content, html_content, word_count FROM documents
+         WHERE workspace_id = ?4
+           AND (?1 IS NULL OR project_id = ?1)
+           AND (?2 = '[]' OR id IN (SELECT value FROM json_each(?2)))
+           AND (?3 OR archived_at IS NULL)
+         ORDER BY updated_at DESC",
+    )?;
+    let rows = stmt.query_map(rusqlite::params![scope.project_id, ids, scope.include_archived, workspace_id], |row| {
+        Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?))
+    })?;
+    Ok(rows.filter_map(|r| r.ok()).collect())
+}
+
+fn query_chars(query: &str) -> Result<Vec<char>, AppError> {
+    if query.is_empty() {
+        return Err(AppError::validation("Enter text to find"));
+    }
+    Ok(query.chars().collect())
+}
+
+/// Documents in scope containing `query`, with a count and previews each.
+#[tauri::command]
+pub async fn find_in_documents(
+    app: AppHandle,
+    query: String,
+    scope: Option<FindScope>,
+) -> Result<Vec<DocumentMatches>, AppError> {
+    let scope = scope.unwrap_or_default();
+    let query = query_chars(&query)?;
+    let workspace_id = workspaces::current(&app);
+    let conn = db::get_db(&app)?;
+    Ok(scoped_documents(&conn, &workspace_id, &scope)?
+        .into_iter()
+        .filter_map(|(document_id, title, _, html, _)| {
+            let (_, matches, previews) = replace_in_html(&html, &query, None, &scope);
+            (matches > 0).then_some(DocumentMatches { document_id, title, matches, previews })
+        })
+        .collect())
+}
+
+/// Replace `query` with `replacement` in every document in scope. With
+/// `dry_run` nothing is written and the result shows what would change.
+/// Otherwise a safety snapshot is taken first and each changed document
+/// gets a new version, all in one transaction.
+#[tauri::command]
+pub async fn replace_in_documents(
+    app: AppHandle,
+    query: String,
+    replacement: String,
+    scope: Option<FindScope>,
+    dry_run: bool,
+) -> Result<ReplaceResult, AppError> {
+    let scope = scope.unwrap_or_default();
+    let query_text = query;
+    let query = query_chars(&query_text)?;
+    let workspace_id = workspaces::current(&app);
+    let mut conn = db::get_db(&app)?;
+
+    let mut documents = Vec::new();
+    // (id, content, html, old word count)
+    let mut changes = Vec::new();
+    for (document_id, title, content, html, word_count) in scoped_documents(&conn, &workspace_id, &scope)? {
+        let (new_html, matches, previews) = replace_in_html(&html, &query, Some(&replacement), &scope);
+        if matches == 0 {
+            continue;
+        }
+        if !dry_run {
+            let new_content = replace_in_content(&content, &query, &replacement, &scope);
+            changes.push((document_id.clone(), new_content, new_html, word_count));
+        }
+        documents.push(DocumentMatches { document_id, title, matches, previews });
+    }
+    let replacements = documents.iter().map(|d| d.matches).sum();
+    if dry_run || changes.is_empty() {
+        return Ok(ReplaceResult { dry_run, replacements, documents });
+    }
+
+    snapshots::take_snapshot(&app, &conn, "replace")?;
+    let now = Utc::now().to_rfc3339();
+    let mode = text::count_mode(&app);
+    let mut versions = Vec::new();
+    {
+        let tx = conn.transaction().map_err(|e| format!("Failed to start transaction: {}", e))?;
+        for (id, content, html, old_words) in &changes {
+            let words = text::count_html(html, mode) as i64;
+            let version: i64 = tx.query_row(
+                "SELECT MAX(COALESCE((SELECT MAX(version) FROM document_versions WHERE document_id = ?1), 0),
+                            COALESCE((SELECT version FROM documents WHERE id = ?1), 0)) + 1",
+                rusqlite::params![id],
+                |row| row.get(0),
+            )?;
+            tx.execute(
+                "UPDATE documents SET content = ?1, html_content = ?2, word_count = ?3, version = ?4, updated_at = ?5 WHERE id = ?6",
+                rusqlite::params![content, html, words, version, now, id],
+            )
+            .map_err(|e| format!("Failed to replace text: {}", e))?;
+            tx.execute(
+                "INSERT INTO document_versions (document_id, title, content, html_content, version, created_at)
+                 SELECT id, title, content, html_content, version, ?2 FROM documents WHERE id = ?1",
+                rusqlite::params![id, now],
+            )?;
+            links::index_document(&tx, id, html);
+            analytics::record_words(&tx, id, words - old_words);
+            versions.push((id.clone(), version));
+        }
+        tx.commit().map_err(|e| format!("Failed to commit replacements: {}", e))?;
+    }
+
+    let details = format!("'{}' → '{}' in {} documents", query_text, replacement, changes.len());
+    audit::record(&app, &conn, "document.replaced", "document", None, Some(&details))?;
+    for (id, version) in versions {
+        windows::document_changed(&app, &id, "replaced", Some(version), None);
+    }
+    Ok(ReplaceResult { dry_run, replacements, documents })
+}
@@ -1,5 +1,11 @@
+use crate::commands::{milestones, settings, workspaces};
 use crate::db;
-use chrono::Utc;
+use crate::error::AppError;
+use crate::permissions::{self, Permission};
+use crate::query_cache::{self, Key};
+use crate::services::paddle::{self, PaddleService};
+use crate::services::stripe::StripeService;
+use chrono::{Datelike, Utc};
 use serde::{Deserialize, Serialize};
 use tauri::AppHandle;
 
@@ -17,13 +23,22 @@ pub struct RevenueEntry {
     pub period_end: Option<String>,
     pub recorded_at: String,
     pub created_at: String,
+    /// For refunds/chargebacks: the id of the entry being refunded.
+    pub refund_of: Option<String>,
+    pub external_id: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct RevenueStats {
+    /// "cash" (recognised when recorded) or "amortized" (spread over the period)
+    pub recognition_mode: String,
     pub mrr: i64,
     pub arr: i64,
     pub total_revenue: i64,
+    /// Estimated platform/processor fees on revenue in range
+    pub total_fees: i64,
+    /// total_revenue minus total_fees — what actually lands in the bank
+    pub net_revenue: i64,
     pub avg_per_subscriber: f64,
     pub monthly_data: Vec<MonthlyRevenue>,
     pub source_breakdown: Vec<SourceRevenue>,
@@ -33,15 +48,99 @@ pub struct RevenueStats {
 pub struct MonthlyRevenue {
     pub month: String,
     pub amount_cents: i64,
+    pub fees_cents: i64,
+    pub net_cents: i64,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct SourceRevenue {
     pub source: String,
     pub amount_cents: i64,
+    pub fees_cents: i64,
+    pub net_cents: i64,
+}
+
+/// Fee rule for one revenue source. `percent` is e.g. 2.9 for 2.9%.
+/// When `tiers` is non-empty the tier matching the source's cumulative
+/// lifetime gross (before the transaction) overrides percent/fixed_cents.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct FeeRule {
+    pub source: String,
+    pub percent: f64,
+    pub fixed_cents: i64,
+    #[serde(default)]
+    pub tiers: Vec<FeeTier>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct FeeTier {
+    /// Tier applies once cumulative gross for the source reaches this amount
+    pub from_cents: i64,
+    pub percent: f64,
+    pub fixed_cents: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MonthlyNetRevenue {
+    pub month: String,
+    pub gross_cents: i64,
+    /// Refunds issued against transactions recorded in this month (plus any
+    /// unlinked refunds recorded in this month).
+    pub refunded_cents: i64,
+    pub net_cents: i64,
+    pub refunds: Vec<RefundLink>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RefundLink {
+    pub refund_id: String,
+    pub refund_of: Option<String>,
+    pub amount_cents: i64,
+    pub refunded_at: String,
+    pub original_recorded_at: Option<String>,
+    pub original_description: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SubscriberLtvStats {
+    pub total_subscribers: i64,
+    /// Subscribers with at least one non-refund revenue entry
+    pub paying_subscribers: i64,
+    pub paid_conversion_rate: f64,
+    /// Net revenue (after refunds) matched to a known subscriber email
+    pub matched_revenue_cents: i64,
+    /// Net revenue with no email or an email not in the audience
+    pub unmatched_revenue_cents: i64,
+    /// Matched revenue spread over every subscriber, free or paid
+    pub arpu_cents: f64,
+    /// Average lifetime net revenue per paying subscriber
+    pub avg_ltv_cents: f64,
+    pub cohorts: Vec<LtvCohort>,
+}
+
+/// Subscribers grouped by the month they were first seen.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct LtvCohort {
+    pub cohort: String,
+    pub subscribers: i64,
+    pub paying_subscribers: i64,
+    pub paid_conversion_rate: f64,
+    pub revenue_cents: i64,
+    pub arpu_cents: f64,
+    pub avg_ltv_cents: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RevenueImportResult {
+    pub entries_imported: i64,
+    /// Refunds and chargebacks
+    pub refunds_imported: i64,
+    pub refunds_linked: i64,
+    pub skipped: i64,
 }
 
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub async fn add_revenue_entry(
     app: AppHandle,
     source: String,
@@ -53,18 +152,40 @@ pub async fn add_revenue_entry(
     period_start: Option<String>,
     period_end: Option<String>,
     recorded_at: Option<String>,
-) -> Result<String, String> {
+    refund_of: Option<String>,
+) -> Result<String, AppError> {
+    permissions::require(&app, Permission::ManageRevenue)?;
+    let workspace_id = workspaces::current(&app);
     let conn = db::get_db(&app)?;
     let id = uuid::Uuid::new_v4().to_string();
     let now = Utc::now().to_rfc3339();
     let recorded = recorded_at.unwrap_or_else(|| now.clone());
     let curr = currency.unwrap_or_else(|| "USD".to_string());
-    let etype = entry_type.unwrap_or_else(|| "recurring".to_string());
+    // Linking to an original entry implies this is a refund
+    let etype = entry_type.unwrap_or_else(|| {
+        if refund_of.is_some() { "refund" } else { "recurring" }.to_string()
+    });
+
+    if let Some(ref original_id) = refund_of {
+        if etype != "refund" {
+            return Err(AppError::validation("Only refund entries can reference an original entry"));
+        }
+        let original_type: String = conn
+            .query_row(
+                "SELECT type FROM revenue_entries WHERE id = ?1",
+                rusqlite::params![original_id],
+                |row| row.get(0),
+            )
+            .map_err(|_| format!("Original revenue entry '{}' not found", original_id))?;
+        if original_type == "refund" {
+            return Err(AppError::validation("A refund cannot reference another refund"));
+        }
+    }
 
     conn.execute(
-        "INSERT INTO revenue_entries (id, source, amount_cents, currency, type, subscriber_email, description, period_start, period_end, recorded_at, created_at)
-         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
-        rusqlite::params![id, source, amount_cents, curr, etype, subscriber_email, description, period_start, period_end, recorded, now],
+        "INSERT INTO revenue_entries (id, source, amount_cents, currency, type, subscriber_email, description, period_start, period_end, recorded_at, created_at, refund_of, workspace_id)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
+        rusqlite::params![id, source, amount_cents, curr, etype, subscriber_email, description, period_start, period_end, recorded, now, refund_of, workspace_id],
     )
     .map_err(|e| format!("Failed to add revenue entry: {}", e))?;
 
@@ -76,6 +197,8 @@ pub async fn add_revenue_entry(
         Some(&format!("{} {} cents from {}", etype, amount_cents, source)),
     );
 
+    milestones::evaluate(&app, &conn);
+
     Ok(id)
 }
 
@@ -85,14 +208,15 @@ pub async fn list_revenue_entries(
     from: Option<String>,
     to: Option<String>,
     source: Option<String>,
-) -> Result<Vec<RevenueEntry>, String> {
+) -> Result<Vec<RevenueEntry>, AppError> {
+    permissions::require(&app, Permission::ViewRevenue)?;
     let conn = db::get_db(&app)?;
 
     let mut sql = String::from(
-        "SELECT id, source, amount_cents, currency, type, subscriber_email, description, period_start, period_end, recorded_at, created_at
-         FROM revenue_entries WHERE 1=1",
+        "SELECT id, source, amount_cents, currency, type, subscriber_email, description, period_start, period_end, recorded_at, created_at, refund_of, external_id
+         FROM revenue_entries WHERE workspace_id = ?1",
     );
-    let mut params: Vec<Box<dyn rusqlite::types::ToSql>> = Vec::new();
+    let mut params: Vec<Box<dyn rusqlite::types::ToSql>> = vec![Box::new(workspaces::current(&app))];
 
     if let Some(ref f) = from {
         sql.push_str(&format!(" AND recorded_at >= ?{}", params.len() + 1));
@@ -125,6 +249,8 @@ pub async fn list_revenue_entries(
                 period_end: row.get(8)?,
                 recorded_at: row.get(9)?,
                 created_at: row.get(10)?,
+                refund_of: row.get(11)?,
+                external_id: row.get(12)?,
             })
         })
         .map_err(|e| format!("Query map failed: {}", e))?;
@@ -132,27 +258,66 @@ pub async fn list_revenue_entries(
     Ok(rows.filter_map(|r| r.ok()).collect())
 }
 
+/// Setting key for the revenue recognition mode ("cash" | "amortized").
+pub const RECOGNITION_MODE_SETTING: &str = "revenue.recognition_mode";
+
+/// MRR: the workspace's recurring revenue recognised in the current month.
+pub(crate) fn current_mrr(conn: &rusqlite::Connection, workspace_id: &str, amortized: bool) -> Result<i64, String> {
+    let now = Utc::now();
+    let current_month = now.format("%Y-%m").to_string();
+    if amortized {
+        return Ok(recognised_by_month(conn, workspace_id, &current_month, &current_month, true)?
+            .get(&current_month)
+            .copied()
+            .unwrap_or(0));
+    }
+    let month_start = format!("{}-{:02}-01T00:00:00Z", now.format("%Y"), now.format("%m"));
+    Ok(conn
+        .query_row(
+            "SELECT COALESCE(SUM(amount_cents), 0) FROM revenue_entries
+             WHERE type = 'recurring' AND recorded_at >= ?1 AND workspace_id = ?2",
+            rusqlite::params![month_start, workspace_id],
+            |row| row.get(0),
+        )
+        .unwrap_or(0))
+}
+
 #[tauri::command]
 pub async fn get_revenue_stats(
     app: AppHandle,
     from: Option<String>,
     to: Option<String>,
-) -> Result<RevenueStats, String> {
-    let conn = db::get_db(&app)?;
+    recognition_mode: Option<String>,
+) -> Result<RevenueStats, AppError> {
+    permissions::require(&app, Permission::ViewRevenue)?;
+    let mode = recognition_mode
+        .or_else(|| settings::get_setting::<String>(&app, RECOGNITION_MODE_SETTING))
+        .unwrap_or_else(|| "cash".to_string());
+    let rules = load_fee_rules(&app);
+    let workspace_id = workspaces::current(&app);
+    let key = Key::new("get_revenue_stats", &(&from, &to, &mode, &rules, &workspace_id));
+    query_cache::cached(key, &["revenue_entries", "subscribers"], || {
+        revenue_stats(&app, &workspace_id, from, to, mode, rules)
+    })
+}
+
+fn revenue_stats(
+    app: &AppHandle,
+    workspace_id: &str,
+    from: Option<String>,
+    to: Option<String>,
+    mode: String,
+    rules: Vec<FeeRule>,
+) -> Result<RevenueStats, AppError> {
+    let amortized = mode == "amortized";
+
+    let conn = db::get_db(app)?;
 
     let now = Utc::now();
-    let month_start = format!("{}-{:02}-01T00:00:00Z", now.format("%Y"), now.format("%m"));
     let from_date = from.unwrap_or_else(|| (now - chrono::Duration::days(365)).to_rfc3339());
     let to_date = to.unwrap_or_else(|| now.to_rfc3339());
 
-    // MRR: sum of recurring entries in current month
-    let mrr: i64 = conn
-        .query_row(
-            "SELECT COALESCE(SUM(amount_cents), 0) FROM revenue_entries WHERE type = 'recurring' AND recorded_at >= ?1",
-            rusqlite::params![month_start],
-            |row| row.get(0),
-        )
-        .unwrap_or(0);
+    let mrr = current_mrr(&conn, workspace_id, amortized)?;
 
     let arr = mrr * 12;
 
@@ -160,72 +325,1050 @@ pub async fn get_revenue_stats(
     let total_revenue: i64 = conn
         .query_row(
             "SELECT COALESCE(SUM(CASE WHEN type != 'refund' THEN amount_cents ELSE -amount_cents END), 0)
-             FROM revenue_entries WHERE recorded_at >= ?1 AND recorded_at <= ?2",
-            rusqlite::params![from_date, to_date],
+             FROM revenue_entries WHERE recorded_at >= ?1 AND recorded_at <= ?2 AND workspace_id = ?3",
+            rusqlite::params![from_date, to_date, workspace_id],
             |row| row.get(0),
         )
         .unwrap_or(0);
 
     // Avg per subscriber
     let sub_count: i64 = conn
-        .query_row("SELECT COUNT(*) FROM subscribers", [], |row| row.get(0))
+        .query_row("SELECT COUNT(*) FROM subscribers WHERE workspace_id = ?1", rusqlite::params![workspace_id], |row| {
+            row.get(0)
+        })
         .unwrap_or(1)
         .max(1);
     let avg_per_subscriber = total_revenue as f64 / sub_count as f64;
 
     // Monthly breakdown
-    let mut monthly_stmt = conn
-        .prepare(
-            "SELECT strftime('%Y-%m', recorded_at) as month,
-                    SUM(CASE WHEN type != 'refund' THEN amount_cents ELSE -amount_cents END)
-             FROM revenue_entries
-             WHERE recorded_at >= ?1 AND recorded_at <= ?2
-             GROUP BY month ORDER BY month ASC",
-        )
-        .map_err(|e| format!("Query failed: {}", e))?;
-    let monthly_data: Vec<MonthlyRevenue> = monthly_stmt
-        .query_map(rusqlite::params![from_date, to_date], |row| {
-            Ok(MonthlyRevenue {
-                month: row.get(0)?,
-                amount_cents: row.get(1)?,
+    let monthly_data: Vec<MonthlyRevenue> = if amortized {
+        let from_month: String = from_date.chars().take(7).collect();
+        let to_month: String = to_date.chars().take(7).collect();
+        recognised_by_month(&conn, workspace_id, &from_month, &to_month, false)?
+            .into_iter()
+            .map(|(month, amount_cents)| MonthlyRevenue {
+                month,
+                amount_cents,
+                fees_cents: 0,
+                net_cents: 0,
             })
-        })
-        .map_err(|e| format!("Query map failed: {}", e))?
-        .filter_map(|r| r.ok())
-        .collect();
+            .collect()
+    } else {
+        let mut monthly_stmt = conn
+            .prepare(
+                "SELECT strftime('%Y-%m', recorded_at) as month,
+                        SUM(CASE WHEN type != 'refund' THEN amount_cents ELSE -amount_cents END)
+                 FROM revenue_entries
+                 WHERE recorded_at >= ?1 AND recorded_at <= ?2 AND workspace_id = ?3
+                 GROUP BY month ORDER BY month ASC",
+            )
+            .map_err(|e| format!("Query failed: {}", e))?;
+        let rows: Vec<MonthlyRevenue> = monthly_stmt
+            .query_map(rusqlite::params![from_date, to_date, workspace_id], |row| {
+                Ok(MonthlyRevenue {
+                    month: row.get(0)?,
+                    amount_cents: row.get(1)?,
+                    fees_cents: 0,
+                    net_cents: 0,
+                })
+            })
+            .map_err(|e| format!("Query map failed: {}", e))?
+            .filter_map(|r| r.ok())
+            .collect();
+        rows
+    };
 
     // Source breakdown
     let mut source_stmt = conn
         .prepare(
             "SELECT source, SUM(amount_cents) FROM revenue_entries
-             WHERE recorded_at >= ?1 AND recorded_at <= ?2 AND type != 'refund'
+             WHERE recorded_at >= ?1 AND recorded_at <= ?2 AND type != 'refund' AND workspace_id = ?3
              GROUP BY source ORDER BY SUM(amount_cents) DESC",
         )
         .map_err(|e| format!("Query failed: {}", e))?;
     let source_breakdown: Vec<SourceRevenue> = source_stmt
-        .query_map(rusqlite::params![from_date, to_date], |row| {
+        .query_map(rusqlite::params![from_date, to_date, workspace_id], |row| {
             Ok(SourceRevenue {
                 source: row.get(0)?,
                 amount_cents: row.get(1)?,
+                fees_cents: 0,
+                net_cents: 0,
             })
         })
         .map_err(|e| format!("Query map failed: {}", e))?
         .filter_map(|r| r.ok())
         .collect();
 
+    // Fees are a cash cost, so they always land in the month they were
+    // charged — even when revenue itself is amortised.
+    let mut monthly_data = monthly_data;
+    let mut source_breakdown = source_breakdown;
+    let mut total_fees = 0i64;
+    for (source, recorded_at, fee) in entry_fees(&conn, workspace_id, &rules)? {
+        if recorded_at.as_str() < from_date.as_str() || recorded_at.as_str() > to_date.as_str() {
+            continue;
+        }
+        total_fees += fee;
+        let month: String = recorded_at.chars().take(7).collect();
+        match monthly_data.iter_mut().find(|m| m.month == month) {
+            Some(m) => m.fees_cents += fee,
+            None => monthly_data.push(MonthlyRevenue {
+                month,
+                amount_cents: 0,
+                fees_cents: fee,
+                net_cents: 0,
+            }),
+        }
+        if let Some(src) = source_breakdown.iter_mut().find(|s| s.source == source) {
+            src.fees_cents += fee;
+        }
+    }
+    monthly_data.sort_by(|a, b| a.month.cmp(&b.month));
+    for m in &mut monthly_data {
+        m.net_cents = m.amount_cents - m.fees_cents;
+    }
+    for src in &mut source_breakdown {
+        src.net_cents = src.amount_cents - src.fees_cents;
+    }
+
     Ok(RevenueStats {
+        recognition_mode: mode,
         mrr,
         arr,
         total_revenue,
+        total_fees,
+        net_revenue: total_revenue - total_fees,
         avg_per_subscriber,
         monthly_data,
         source_breakdown,
     })
 }
 
+// ---------------------------------------------------------------------------
+// Fee modeling
+// ---------------------------------------------------------------------------
+
+/// Setting key for the per-source fee rules (`Vec<FeeRule>`).
+pub const FEE_RULES_SETTING: &str = "revenue.fee_rules";
+
+/// Published list pricing, used until the user configures their own rules.
+pub fn default_fee_rules() -> Vec<FeeRule> {
+    let gumroad_tier = |from_cents: i64, percent: f64| FeeTier {
+        from_cents,
+        percent,
+        fixed_cents: 30,
+    };
+    vec![
+        FeeRule {
+            source: "stripe".to_string(),
+            percent: 2.9,
+            fixed_cents: 30,
+            tiers: vec![],
+        },
+        FeeRule {
+            source: "substack".to_string(),
+            percent: 10.0,
+            fixed_cents: 0,
+            tiers: vec![],
+        },
+        FeeRule {
+            source: "paddle".to_string(),
+            percent: 5.0,
+            fixed_cents: 50,
+            tiers: vec![],
+        },
+        FeeRule {
+            source: "gumroad".to_string(),
+            percent: 9.0,
+            fixed_cents: 30,
+            tiers: vec![
+                gumroad_tier(0, 9.0),
+                gumroad_tier(100_000, 7.0),
+                gumroad_tier(1_000_000, 5.0),
+                gumroad_tier(10_000_000, 3.0),
+            ],
+        },
+    ]
+}
+
+fn load_fee_rules(app: &AppHandle) -> Vec<FeeRule> {
+    settings::get_setting::<Vec<FeeRule>>(app, FEE_RULES_SETTING)
+        .unwrap_or_else(default_fee_rules)
+}
+
+impl FeeRule {
+    fn fee_for(&self, amount_cents: i64, cumulative_cents: i64) -> i64 {
+        let (percent, fixed) = self
+            .tiers
+            .iter()
+            .filter(|t| cumulative_cents >= t.from_cents)
+            .max_by_key(|t| t.from_cents)
+            .map(|t| (t.percent, t.fixed_cents))
+            .unwrap_or((self.percent, self.fixed_cents));
+        ((amount_cents as f64 * percent / 100.0).round() as i64 + fixed).min(amount_cents)
+    }
+}
+
+/// Fee per non-refund entry as (source, recorded_at, fee_cents). Walks the
+/// workspace's entries chronologically so tiered rules see lifetime volume.
+/// Refunds carry no fee: processors typically keep the original fee.
+fn entry_fees(
+    conn: &rusqlite::Connection,
+    workspace_id: &str,
+    rules: &[FeeRule],
+) -> Result<Vec<(String, String, i64)>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT source, amount_cents, recorded_at FROM revenue_entries
+             WHERE type != 'refund' AND workspace_id = ?1 ORDER BY recorded_at ASC",
+        )
+        .map_err(|e| format!("Query failed: {}", e))?;
+    let rows: Vec<(String, i64, String)> = stmt
+        .query_map(rusqlite::params![workspace_id], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+        .map_err(|e| format!("Query map failed: {}", e))?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    let mut cumulative: std::collections::HashMap<String, i64> = std::collections::HashMap::new();
+    let mut fees = Vec::new();
+    for (source, amount, recorded_at) in rows {
+        let Some(rule) = rules.iter().find(|r| r.source.eq_ignore_ascii_case(&source)) else {
+            continue;
+        };
+        let total = cumulative.entry(source.clone()).or_insert(0);
+        let fee = rule.fee_for(amount, *total);
+        *total += amount;
+        fees.push((source, recorded_at, fee));
+    }
+    Ok(fees)
+}
+
 #[tauri::command]
-pub async fn delete_revenue_entry(app: AppHandle, id: String) -> Result<(), String> {
+pub async fn get_fee_rules(app: AppHandle) -> Result<Vec<FeeRule>, AppError> {
+    permissions::require(&app, Permission::ViewRevenue)?;
+    Ok(load_fee_rules(&app))
+}
+
+#[tauri::command]
+pub async fn save_fee_rules(app: AppHandle, rules: Vec<FeeRule>) -> Result<(), AppError> {
+    permissions::require(&app, Permission::ManageRevenue)?;
+    for rule in &rules {
+        if rule.source.trim().is_empty() {
+            return Err(AppError::validation("Fee rule source cannot be empty"));
+        }
+        let invalid_percent = |p: f64| !(0.0..=100.0).contains(&p);
+        if invalid_percent(rule.percent) || rule.tiers.iter().any(|t| invalid_percent(t.percent)) {
+            return Err(AppError::validation(format!("Fee percent for '{}' must be between 0 and 100", rule.source)));
+        }
+    }
+    settings::set_setting(
+        &app,
+        FEE_RULES_SETTING,
+        serde_json::to_value(&rules).map_err(|e| e.to_string())?,
+    )?;
+    Ok(())
+}
+
+/// Parse the "YYYY-MM" prefix of a date string into (year, month).
+fn parse_month(date: &str) -> Option<(i32, u32)> {
+    let year = date.get(0..4)?.parse::<i32>().ok()?;
+    let month = date.get(5..7)?.parse::<u32>().ok()?;
+    if (1..=12).contains(&month) {
+        Some((year, month))
+    } else {
+        None
+    }
+}
+
+/// Months since year 0 for the "YYYY-MM" prefix of a date string.
+fn month_index(date: &str) -> Option<i32> {
+    parse_month(date).map(|(y, m)| y * 12 + m as i32 - 1)
+}
+
+fn month_key(index: i32) -> String {
+    format!("{:04}-{:02}", index.div_euclid(12), index.rem_euclid(12) + 1)
+}
+
+/// First month index and number of months a billing period covers. An end
+/// on the 1st or on a whole-month anniversary of the start (Jan 15 to Feb 15,
+/// or Jan 31 to Feb 28 where the month is shorter) is exclusive; any other
+/// end date (Jan 1 to Dec 31) is the period's last day.
+fn period_months(start: &str, end: &str) -> Option<(i32, i32)> {
+    let first = month_index(start)?;
+    let date = |d: &str| d.get(0..10).and_then(|d| chrono::NaiveDate::parse_from_str(d, "%Y-%m-%d").ok());
+    let anniversary = |s: chrono::NaiveDate, e: chrono::NaiveDate| {
+        let months = u32::try_from(month_index(end)? - first).ok()?;
+        Some(s.checked_add_months(chrono::Months::new(months))? == e)
+    };
+    let last = match (date(start), date(end)) {
+        (Some(s), Some(e)) if e.day() != 1 && anniversary(s, e) != Some(true) => {
+            month_index(&e.succ_opt()?.to_string())?
+        }
+        _ => month_index(end)?,
+    };
+    Some((first, last - first))
+}
+
+/// (amount_cents, type, recorded_at, period_start, period_end, and for refunds
+/// the refunded entry's period_start and period_end)
+type RecognitionRow = (i64, String, String, Option<String>, Option<String>, Option<String>, Option<String>);
+
+/// Amortised revenue per month between `from_month` and `to_month` (inclusive,
+/// "YYYY-MM"). Entries whose period spans several months (annual plans) are
+/// spread evenly across those months; everything else lands in the month it
+/// was recorded. A refund of a spread entry is spread over the entry's
+/// months from the refund onwards, so it offsets what's still to be
+/// recognised instead of landing as one large negative month; other refunds
+/// are subtracted in the month they were recorded.
+fn recognised_by_month(
+    conn: &rusqlite::Connection,
+    workspace_id: &str,
+    from_month: &str,
+    to_month: &str,
+    recurring_only: bool,
+) -> Result<std::collections::BTreeMap<String, i64>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT r.amount_cents, r.type, r.recorded_at, r.period_start, r.period_end, o.period_start, o.period_end
+             FROM revenue_entries r
+             LEFT JOIN revenue_entries o ON o.id = r.refund_of AND r.type = 'refund'
+             WHERE r.workspace_id = ?3
+               AND ((strftime('%Y-%m', r.recorded_at) >= ?1 AND strftime('%Y-%m', r.recorded_at) <= ?2)
+                OR (r.period_start IS NOT NULL AND r.period_end IS NOT NULL
+                    AND strftime('%Y-%m', r.period_start) <= ?2 AND strftime('%Y-%m', r.period_end) >= ?1)
+                OR (o.period_end IS NOT NULL
+                    AND strftime('%Y-%m', r.recorded_at) <= ?2 AND strftime('%Y-%m', o.period_end) >= ?1))",
+        )
+        .map_err(|e| format!("Query failed: {}", e))?;
+    let rows: Vec<RecognitionRow> = stmt
+        .query_map(rusqlite::params![from_month, to_month, workspace_id], |row| {
+            Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?, row.get(5)?, row.get(6)?))
+        })
+        .map_err(|e| format!("Query map failed: {}", e))?
+        .filter_map(|r| r.ok())
+        .collect();
+    Ok(recognise(rows, from_month, to_month, recurring_only))
+}
+
+fn recognise(
+    rows: Vec<RecognitionRow>,
+    from_month: &str,
+    to_month: &str,
+    recurring_only: bool,
+) -> std::collections::BTreeMap<String, i64> {
+    let mut months: std::collections::BTreeMap<String, i64> = std::collections::BTreeMap::new();
+    // Spread evenly; the remainder goes to the first month so the sum
+    // still matches the cash amount.
+    let mut add = |first: i32, count: i32, amount: i64| {
+        let per_month = amount / count as i64;
+        let remainder = amount - per_month * count as i64;
+        for i in 0..count {
+            let key = month_key(first + i);
+            if key.as_str() >= from_month && key.as_str() <= to_month {
+                let share = if i == 0 { per_month + remainder } else { per_month };
+                *months.entry(key).or_insert(0) += share;
+            }
+        }
+    };
+    let spread = |start: &Option<String>, end: &Option<String>| {
+        period_months(start.as_deref()?, end.as_deref()?).filter(|(_, count)| *count > 1)
+    };
+
+    for (amount, etype, recorded_at, period_start, period_end, refunded_start, refunded_end) in rows {
+        if recurring_only && etype != "recurring" {
+            continue;
+        }
+        let Some(recorded) = month_index(&recorded_at) else {
+            continue;
+        };
+        if etype == "refund" {
+            match spread(&refunded_start, &refunded_end) {
+                Some((first, count)) if recorded < first + count => {
+                    let from = recorded.max(first);
+                    add(from, first + count - from, -amount);
+                }
+                _ => add(recorded, 1, -amount),
+            }
+            continue;
+        }
+        match spread(&period_start, &period_end) {
+            Some((first, count)) => add(first, count, amount),
+            None => add(recorded, 1, amount),
+        }
+    }
+    months
+}
+
+#[tauri::command]
+pub async fn get_net_revenue(
+    app: AppHandle,
+    from: Option<String>,
+    to: Option<String>,
+) -> Result<Vec<MonthlyNetRevenue>, AppError> {
+    permissions::require(&app, Permission::ViewRevenue)?;
+    let workspace_id = workspaces::current(&app);
+    let key = Key::new("get_net_revenue", &(&from, &to, &workspace_id));
+    query_cache::cached(key, &["revenue_entries"], || net_revenue(&app, &workspace_id, from, to))
+}
+
+fn net_revenue(
+    app: &AppHandle,
+    workspace_id: &str,
+    from: Option<String>,
+    to: Option<String>,
+) -> Result<Vec<MonthlyNetRevenue>, AppError> {
+    let conn = db::get_db(app)?;
+
+    let now = Utc::now();
+    let from_date = from.unwrap_or_else(|| (now - chrono::Duration::days(365)).to_rfc3339());
+    let to_date = to.unwrap_or_else(|| now.to_rfc3339());
+
+    // Gross per month (everything except refunds)
+    let mut gross_stmt = conn
+        .prepare(
+            "SELECT strftime('%Y-%m', recorded_at) as month, SUM(amount_cents)
+             FROM revenue_entries
+             WHERE recorded_at >= ?1 AND recorded_at <= ?2 AND type != 'refund' AND workspace_id = ?3
+             GROUP BY month ORDER BY month ASC",
+        )
+        .map_err(|e| format!("Query failed: {}", e))?;
+    let mut months: Vec<MonthlyNetRevenue> = gross_stmt
+        .query_map(rusqlite::params![from_date, to_date, workspace_id], |row| {
+            Ok(MonthlyNetRevenue {
+                month: row.get(0)?,
+                gross_cents: row.get(1)?,
+                refunded_cents: 0,
+                net_cents: 0,
+                refunds: Vec::new(),
+            })
+        })
+        .map_err(|e| format!("Query map failed: {}", e))?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    // Refunds are attributed to the month of the transaction they reverse, so
+    // a January sale refunded in February reduces January's net. Unlinked
+    // refunds fall back to the month they were recorded in.
+    let mut refund_stmt = conn
+        .prepare(
+            "SELECT r.id, r.refund_of, r.amount_cents, r.recorded_at, o.recorded_at, o.description,
+                    strftime('%Y-%m', COALESCE(o.recorded_at, r.recorded_at)) as month
+             FROM revenue_entries r
+             LEFT JOIN revenue_entries o ON o.id = r.refund_of
+             WHERE r.type = 'refund' AND r.workspace_id = ?3
+               AND COALESCE(o.recorded_at, r.recorded_at) >= ?1
+               AND COALESCE(o.recorded_at, r.recorded_at) <= ?2
+             ORDER BY r.recorded_at ASC",
+        )
+        .map_err(|e| format!("Query failed: {}", e))?;
+    let refunds: Vec<(String, RefundLink)> = refund_stmt
+        .query_map(rusqlite::params![from_date, to_date, workspace_id], |row| {
+            Ok((
+                row.get::<_, String>(6)?,
+                RefundLink {
+                    refund_id: row.get(0)?,
+                    refund_of: row.get(1)?,
+                    amount_cents: row.get(2)?,
+                    refunded_at: row.get(3)?,
+                    original_recorded_at: row.get(4)?,
+                    original_description: row.get(5)?,
+                },
+            ))
+        })
+        .map_err(|e| format!("Query map failed: {}", e))?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    for (month, refund) in refunds {
+        let idx = match months.iter().position(|m| m.month == month) {
+            Some(idx) => idx,
+            None => {
+                months.push(MonthlyNetRevenue {
+                    month,
+                    gross_cents: 0,
+                    refunded_cents: 0,
+                    net_cents: 0,
+                    refunds: Vec::new(),
+                });
+                months.len() - 1
+            }
+        };
+        months[idx].refunded_cents += refund.amount_cents;
+        months[idx].refunds.push(refund);
+    }
+
+    for m in &mut months {
+        m.net_cents = m.gross_cents - m.refunded_cents;
+    }
+    months.sort_by(|a, b| a.month.cmp(&b.month));
+
+    Ok(months)
+}
+
+#[tauri::command]
+pub async fn get_subscriber_ltv_stats(app: AppHandle) -> Result<SubscriberLtvStats, AppError> {
+    permissions::require(&app, Permission::ViewRevenue)?;
+    let workspace_id = workspaces::current(&app);
+    let key = Key::new("get_subscriber_ltv_stats", &workspace_id);
+    query_cache::cached(key, &["revenue_entries", "subscribers"], || subscriber_ltv_stats(&app, &workspace_id))
+}
+
+fn subscriber_ltv_stats(app: &AppHandle, workspace_id: &str) -> Result<SubscriberLtvStats, AppError> {
+    let conn = db::get_db(app)?;
+
+    // Net revenue and paid-entry count per email. Refunds often arrive
+    // without an email, so fall back to the email on the entry they reverse.
+    let mut rev_stmt = conn
+        .prepare(
+            "SELECT lower(COALESCE(r.subscriber_email, o.subscriber_email)) as email,
+                    SUM(CASE WHEN r.type = 'refund' THEN -r.amount_cents ELSE r.amount_cents END),
+                    SUM(CASE WHEN r.type = 'refund' THEN 0 ELSE 1 END)
+             FROM revenue_entries r
+             LEFT JOIN revenue_entries o ON o.id = r.refund_of
+             WHERE r.workspace_id = ?1
+             GROUP BY email",
+        )
+        .map_err(|e| format!("Query failed: {}", e))?;
+    let mut by_email: std::collections::HashMap<String, (i64, i64)> =
+        std::collections::HashMap::new();
+    let mut unmatched_revenue_cents = 0i64;
+    let rows: Vec<(Option<String>, i64, i64)> = rev_stmt
+        .query_map(rusqlite::params![workspace_id], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+        .map_err(|e| format!("Query map failed: {}", e))?
+        .filter_map(|r| r.ok())
+        .collect();
+    for (email, net, paid_entries) in rows {
+        match email.filter(|e| !e.trim().is_empty()) {
+            Some(email) => {
+                by_email.insert(email, (net, paid_entries));
+            }
+            None => unmatched_revenue_cents += net,
+        }
+    }
+
+    let mut sub_stmt = conn
+        .prepare(
+            "SELECT lower(email), strftime('%Y-%m', first_seen_at) FROM subscribers
+             WHERE workspace_id = ?1 ORDER BY first_seen_at ASC",
+        )
+        .map_err(|e| format!("Query failed: {}", e))?;
+    let subscribers: Vec<(String, String)> = sub_stmt
+        .query_map(rusqlite::params![workspace_id], |row| Ok((row.get(0)?, row.get(1)?)))
+        .map_err(|e| format!("Query map failed: {}", e))?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    let rate = |num: i64, den: i64| if den > 0 { num as f64 / den as f64 } else { 0.0 };
+
+    // cohort -> (subscribers, paying, revenue)
+    let mut cohorts: std::collections::BTreeMap<String, (i64, i64, i64)> =
+        std::collections::BTreeMap::new();
+    for (email, cohort) in &subscribers {
+        let entry = cohorts.entry(cohort.clone()).or_insert((0, 0, 0));
+        entry.0 += 1;
+        if let Some((net, paid_entries)) = by_email.remove(email) {
+            if paid_entries > 0 {
+                entry.1 += 1;
+            }
+            entry.2 += net;
+        }
+    }
+    // Whatever is left matched no subscriber
+    unmatched_revenue_cents += by_email.values().map(|(net, _)| net).sum::<i64>();
+
+    let total_subscribers = subscribers.len() as i64;
+    let paying_subscribers: i64 = cohorts.values().map(|c| c.1).sum();
+    let matched_revenue_cents: i64 = cohorts.values().map(|c| c.2).sum();
+
+    let cohorts = cohorts
+        .into_iter()
+        .map(|(cohort, (subs, paying, revenue))| LtvCohort {
+            cohort,
+            subscribers: subs,
+            paying_subscribers: paying,
+            paid_conversion_rate: rate(paying, subs),
+            revenue_cents: revenue,
+            arpu_cents: rate(revenue, subs),
+            avg_ltv_cents: rate(revenue, paying),
+        })
+        .collect();
+
+    Ok(SubscriberLtvStats {
+        total_subscribers,
+        paying_subscribers,
+        paid_conversion_rate: rate(paying_subscribers, total_subscribers),
+        matched_revenue_cents,
+        unmatched_revenue_cents,
+        arpu_cents: rate(matched_revenue_cents, total_subscribers),
+        avg_ltv_cents: rate(matched_revenue_cents, paying_subscribers),
+        cohorts,
+    })
+}
+
+/// Import Stripe charges, refunds and disputes. Refunds and chargebacks are
+/// entries of type 'refund' linked to their charge through `refund_of`; a
+/// dispute later won, or one that was only an inquiry, is removed again.
+#[tauri::command]
+pub async fn import_stripe_revenue(
+    app: AppHandle,
+    account_id: String,
+) -> Result<RevenueImportResult, AppError> {
+    permissions::require(&app, Permission::ManageRevenue)?;
+    let api_key = crate::commands::platform::get_api_key(&app, "stripe", &account_id)?;
+
+    let charges = StripeService::fetch_charges(&api_key, 100).await?;
+    let refunds = StripeService::fetch_refunds(&api_key, 100).await?;
+    let disputes = StripeService::fetch_disputes(&api_key, 100).await?;
+    let workspace_id = workspaces::of_account(&app, "stripe", &account_id);
+
+    let conn = db::get_db(&app)?;
+    let now = Utc::now().to_rfc3339();
+    let mut entries_imported = 0i64;
+    let mut refunds_imported = 0i64;
+    let mut refunds_linked = 0i64;
+    let mut skipped = 0i64;
+
+    for charge in charges.iter().filter(|c| c.status == "succeeded") {
+        let etype = if charge.invoice.is_some() { "recurring" } else { "one_time" };
+        let inserted = conn
+            .execute(
+                "INSERT OR IGNORE INTO revenue_entries (id, source, amount_cents, currency, type, subscriber_email, description, recorded_at, created_at, external_id, workspace_id)
+                 VALUES (?1, 'stripe', ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+                rusqlite::params![
+                    uuid::Uuid::new_v4().to_string(),
+                    charge.amount,
+                    charge.currency.to_uppercase(),
+                    etype,
+                    charge.receipt_email,
+                    charge.description,
+                    stripe_timestamp(charge.created),
+                    now,
+                    charge.id,
+                    workspace_id,
+                ],
+            )
+            .unwrap_or(0);
+        entries_imported += inserted as i64;
+        if inserted == 0 {
+            skipped += 1;
+        }
+    }
+
+    for refund in refunds.iter().filter(|r| r.status.as_deref() != Some("failed")) {
+        // Resolve the original entry by its Stripe charge id
+        let original_id: Option<String> = refund.charge.as_ref().and_then(|charge_id| {
+            conn.query_row(
+                "SELECT id FROM revenue_entries WHERE source = 'stripe' AND external_id = ?1 AND type != 'refund'",
+                rusqlite::params![charge_id],
+                |row| row.get(0),
+            )
+            .ok()
+        });
+
+        let inserted = conn
+            .execute(
+                "INSERT OR IGNORE INTO revenue_entries (id, source, amount_cents, currency, type, description, recorded_at, created_at, external_id, refund_of, workspace_id)
+                 VALUES (?1, 'stripe', ?2, ?3, 'refund', ?4, ?5, ?6, ?7, ?8, ?9)",
+                rusqlite::params![
+                    uuid::Uuid::new_v4().to_string(),
+                    refund.amount,
+                    refund.currency.to_uppercase(),
+                    refund.reason.as_ref().map(|r| format!("Refund: {}", r)),
+                    stripe_timestamp(refund.created),
+                    now,
+                    refund.id,
+                    original_id,
+                    workspace_id,
+                ],
+            )
+            .unwrap_or(0);
+        refunds_imported += inserted as i64;
+
+        // Link refunds imported before their charge was available
+        if let Some(ref original) = original_id {
+            let linked = conn
+                .execute(
+                    "UPDATE revenue_entries SET refund_of = ?1 WHERE source = 'stripe' AND external_id = ?2 AND refund_of IS NULL",
+                    rusqlite::params![original, refund.id],
+                )
+                .unwrap_or(0);
+            if inserted > 0 || linked > 0 {
+                refunds_linked += 1;
+            }
+        }
+    }
+
+    let mut chargebacks = 0i64;
+    for dispute in &disputes {
+        if !dispute.funds_withdrawn() {
+            conn.execute(
+                "DELETE FROM revenue_entries WHERE source = 'stripe' AND external_id = ?1 AND type = 'refund'",
+                rusqlite::params![dispute.id],
+            )
+            .ok();
+            continue;
+        }
+        let original_id: Option<String> = dispute.charge.as_ref().and_then(|charge_id| {
+            conn.query_row(
+                "SELECT id FROM revenue_entries WHERE source = 'stripe' AND external_id = ?1 AND type != 'refund'",
+                rusqlite::params![charge_id],
+                |row| row.get(0),
+            )
+            .ok()
+        });
+
+        let inserted = conn
+            .execute(
+                "INSERT OR IGNORE INTO revenue_entries (id, source, amount_cents, currency, type, description, recorded_at, created_at, external_id, refund_of, workspace_id)
+                 VALUES (?1, 'stripe', ?2, ?3, 'refund', ?4, ?5, ?6, ?7, ?8, ?9)",
+                rusqlite::params![
+                    uuid::Uuid::new_v4().to_string(),
+                    dispute.amount,
+                    dispute.currency.to_uppercase(),
+                    format!("Chargeback: {}", dispute.reason.as_deref().unwrap_or("unspecified")),
+                    stripe_timestamp(dispute.created),
+                    now,
+                    dispute.id,
+                    original_id,
+                    workspace_id,
+                ],
+            )
+            .unwrap_or(0);
+        refunds_imported += inserted as i64;
+        chargebacks += inserted as i64;
+
+        if let Some(ref original) = original_id {
+            let linked = conn
+                .execute(
+                    "UPDATE revenue_entries SET refund_of = ?1 WHERE source = 'stripe' AND external_id = ?2 AND refund_of IS NULL",
+                    rusqlite::params![original, dispute.id],
+                )
+                .unwrap_or(0);
+            if inserted > 0 || linked > 0 {
+                refunds_linked += 1;
+            }
+        }
+    }
+
+    db::log_activity(
+        &conn,
+        "revenue.imported",
+        "revenue",
+        None,
+        Some(&format!(
+            "Stripe import: {} charges, {} refunds incl. {} chargebacks ({} linked)",
+            entries_imported, refunds_imported, chargebacks, refunds_linked
+        )),
+    );
+
+    milestones::evaluate(&app, &conn);
+
+    Ok(RevenueImportResult {
+        entries_imported,
+        refunds_imported,
+        refunds_linked,
+        skipped,
+    })
+}
+
+#[tauri::command]
+pub async fn import_paddle_revenue(
+    app: AppHandle,
+    account_id: String,
+) -> Result<RevenueImportResult, AppError> {
+    permissions::require(&app, Permission::ManageRevenue)?;
+    let api_key = crate::commands::platform::get_api_key(&app, "paddle", &account_id)?;
+
+    let transactions = PaddleService::fetch_transactions(&api_key, 200).await?;
+    let refunds = PaddleService::fetch_refunds(&api_key, 200).await?;
+    let workspace_id = workspaces::of_account(&app, "paddle", &account_id);
+
     let conn = db::get_db(&app)?;
+    let now = Utc::now().to_rfc3339();
+    let mut entries_imported = 0i64;
+    let mut refunds_imported = 0i64;
+    let mut refunds_linked = 0i64;
+    let mut skipped = 0i64;
+
+    for tx in &transactions {
+        // Subscription renewals are recurring; checkout one-offs are not
+        let etype = if tx.subscription_id.is_some() { "recurring" } else { "one_time" };
+        let (period_start, period_end) = match &tx.billing_period {
+            Some(p) => (Some(p.starts_at.clone()), Some(p.ends_at.clone())),
+            None => (None, None),
+        };
+        let inserted = conn
+            .execute(
+                "INSERT OR IGNORE INTO revenue_entries (id, source, amount_cents, currency, type, subscriber_email, period_start, period_end, recorded_at, created_at, external_id, workspace_id)
+                 VALUES (?1, 'paddle', ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+                rusqlite::params![
+                    uuid::Uuid::new_v4().to_string(),
+                    paddle::amount_cents(tx.details.as_ref().and_then(|d| d.totals.as_ref())),
+                    tx.currency_code.to_uppercase(),
+                    etype,
+                    tx.customer.as_ref().and_then(|c| c.email.as_ref()).map(|e| e.to_lowercase()),
+                    period_start,
+                    period_end,
+                    tx.billed_at.clone().unwrap_or_else(|| tx.created_at.clone()),
+                    now,
+                    tx.id,
+                    workspace_id,
+                ],
+            )
+            .unwrap_or(0);
+        entries_imported += inserted as i64;
+        if inserted == 0 {
+            skipped += 1;
+        }
+    }
+
+    for adj in refunds.iter().filter(|a| a.action == "refund" && a.status == "approved") {
+        let original_id: Option<String> = conn
+            .query_row(
+                "SELECT id FROM revenue_entries WHERE source = 'paddle' AND external_id = ?1 AND type != 'refund'",
+                rusqlite::params![adj.transaction_id],
+                |row| row.get(0),
+            )
+            .ok();
+
+        let inserted = conn
+            .execute(
+                "INSERT OR IGNORE INTO revenue_entries (id, source, amount_cents, currency, type, description, recorded_at, created_at, external_id, refund_of, workspace_id)
+                 VALUES (?1, 'paddle', ?2, ?3, 'refund', ?4, ?5, ?6, ?7, ?8, ?9)",
+                rusqlite::params![
+                    uuid::Uuid::new_v4().to_string(),
+                    paddle::amount_cents(adj.totals.as_ref()),
+                    adj.currency_code.to_uppercase(),
+                    adj.reason.as_ref().map(|r| format!("Refund: {}", r)),
+                    adj.created_at,
+                    now,
+                    adj.id,
+                    original_id,
+                    workspace_id,
+                ],
+            )
+            .unwrap_or(0);
+        refunds_imported += inserted as i64;
+
+        if let Some(ref original) = original_id {
+            let linked = conn
+                .execute(
+                    "UPDATE revenue_entries SET refund_of = ?1 WHERE source = 'paddle' AND external_id = ?2 AND refund_of IS NULL",
+                    rusqlite::params![original, adj.id],
+                )
+                .unwrap_or(0);
+            if inserted > 0 || linked > 0 {
+                refunds_linked += 1;
+            }
+        }
+    }
+
+    db::log_activity(
+        &conn,
+        "revenue.imported",
+        "revenue",
+        None,
+        Some(&format!(
+            "Paddle import: {} transactions, {} refunds ({} linked)",
+            entries_imported, refunds_imported, refunds_linked
+        )),
+    );
+
+    milestones::evaluate(&app, &conn);
+
+    Ok(RevenueImportResult {
+        entries_imported,
+        refunds_imported,
+        refunds_linked,
+        skipped,
+    })
+}
+
+/// Header names Substack has used for each column of its subscriber
+/// export, compared case-insensitively.
+const SUBSTACK_EMAIL_HEADERS: &[&str] = &["email", "email address"];
+const SUBSTACK_PLAN_HEADERS: &[&str] = &["plan", "subscription type", "subscription_interval", "type"];
+const SUBSTACK_AMOUNT_HEADERS: &[&str] = &["amount", "price", "revenue", "total"];
+const SUBSTACK_DATE_HEADERS: &[&str] = &[
+    "subscription_created_at",
+    "subscription created",
+    "subscription date",
+    "start date",
+    "created_at",
+    "date",
+];
+
+/// Import a Substack paid-subscriber CSV export. The email and plan columns
+/// must be there under a known header; anything else is refused rather
+/// than guessed at, since a misread plan column turns free readers into
+/// paying ones.
+///
+/// Substack's export doesn't reliably include prices, so the monthly/annual
+/// price can be supplied and is used when the row has no amount column.
+/// Annual and founding plans get a 12-month period so amortised recognition
+/// spreads them correctly.
+#[tauri::command]
+pub async fn import_substack_revenue_csv(
+    app: AppHandle,
+    file_path: String,
+    monthly_price_cents: Option<i64>,
+    annual_price_cents: Option<i64>,
+    currency: Option<String>,
+) -> Result<RevenueImportResult, AppError> {
+    permissions::require(&app, Permission::ManageRevenue)?;
+    let mut reader = csv::ReaderBuilder::new()
+        .flexible(true)
+        .trim(csv::Trim::All)
+        .from_path(&file_path)
+        .map_err(|e| AppError::not_found(format!("Failed to open CSV: {}", e)))?;
+
+    let headers: Vec<String> = reader
+        .headers()
+        .map_err(|e| AppError::validation(format!("Failed to read CSV header: {}", e)))?
+        .iter()
+        .map(|h| h.to_lowercase())
+        .collect();
+    let find = |names: &[&str]| names.iter().find_map(|n| headers.iter().position(|h| h.trim() == *n));
+    let required = |names: &[&str], what: &str| {
+        find(names).ok_or_else(|| {
+            AppError::validation(format!(
+                "This doesn't look like a Substack subscriber export: no {} column (expected one of: {})",
+                what,
+                names.join(", ")
+            ))
+        })
+    };
+    let email_col = required(SUBSTACK_EMAIL_HEADERS, "email")?;
+    let plan_col = required(SUBSTACK_PLAN_HEADERS, "plan")?;
+    let amount_col = find(SUBSTACK_AMOUNT_HEADERS);
+    let date_col = find(SUBSTACK_DATE_HEADERS);
+
+    let curr = currency.unwrap_or_else(|| "USD".to_string());
+    let workspace_id = workspaces::current(&app);
+    let conn = db::get_db(&app)?;
+    let now = Utc::now().to_rfc3339();
+    let mut entries_imported = 0i64;
+    let mut skipped = 0i64;
+
+    for record in reader.records() {
+        let record = match record {
+            Ok(r) => r,
+            Err(_) => {
+                skipped += 1;
+                continue;
+            }
+        };
+        let email = record.get(email_col).unwrap_or("").trim().to_lowercase();
+        if email.is_empty() {
+            skipped += 1;
+            continue;
+        }
+
+        let plan = record.get(plan_col).unwrap_or("").to_lowercase();
+        let annual = plan.contains("year") || plan.contains("annual") || plan.contains("founding");
+        let comp = plan.contains("comp") || plan.contains("free") || plan.contains("gift");
+        if comp {
+            skipped += 1;
+            continue;
+        }
+
+        let amount = amount_col
+            .and_then(|c| record.get(c))
+            .and_then(parse_money_cents)
+            .or(if annual { annual_price_cents } else { monthly_price_cents });
+        let amount = match amount {
+            Some(a) if a > 0 => a,
+            _ => {
+                skipped += 1;
+                continue;
+            }
+        };
+
+        let started = date_col
+            .and_then(|c| record.get(c))
+            .and_then(|d| d.get(0..10))
+            .and_then(|d| chrono::NaiveDate::parse_from_str(d, "%Y-%m-%d").ok());
+        let start = started.unwrap_or_else(|| Utc::now().date_naive());
+        let months = if annual { 12 } else { 1 };
+        let end = start
+            .checked_add_months(chrono::Months::new(months))
+            .unwrap_or(start);
+
+        let inserted = conn
+            .execute(
+                "INSERT OR IGNORE INTO revenue_entries (id, source, amount_cents, currency, type, subscriber_email, description, period_start, period_end, recorded_at, created_at, external_id, workspace_id)
+                 VALUES (?1, 'substack', ?2, ?3, 'recurring', ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+                rusqlite::params![
+                    uuid::Uuid::new_v4().to_string(),
+                    amount,
+                    curr,
+                    email,
+                    if plan.is_empty() { None } else { Some(format!("Substack plan: {}", plan)) },
+                    start.format("%Y-%m-%d").to_string(),
+                    end.format("%Y-%m-%d").to_string(),
+                    format!("{}T00:00:00Z", start.format("%Y-%m-%d")),
+                    now,
+                    format!("{}:{}", email, start.format("%Y-%m-%d")),
+                    workspace_id,
+                ],
+            )
+            .unwrap_or(0);
+        entries_imported += inserted as i64;
+        if inserted == 0 {
+            skipped += 1;
+        }
+    }
+
+    db::log_activity(
+        &conn,
+        "revenue.imported",
+        "revenue",
+        None,
+        Some(&format!("Substack CSV import: {} entries, {} skipped", entries_imported, skipped)),
+    );
+
+    milestones::evaluate(&app, &conn);
+
+    Ok(RevenueImportResult {
+        entries_imported,
+        refunds_imported: 0,
+        refunds_linked: 0,
+        skipped,
+    })
+}
+
+/// Parse "$12.50", "12.5" or "1,200.00" into cents. Accounting-style
+/// "(12.00)" is negative.
+fn parse_money_cents(raw: &str) -> Option<i64> {
+    let negative = raw.contains('(') && raw.contains(')');
+    let cleaned: String = raw
+        .chars()
+        .filter(|c| c.is_ascii_digit() || *c == '.' || *c == '-')
+        .collect();
+    if cleaned.is_empty() {
+        return None;
+    }
+    let value: f64 = cleaned.parse().ok()?;
+    let cents = (value * 100.0).round() as i64;
+    Some(if negative { -cents.abs() } else { cents })
+}
+
+fn stripe_timestamp(ts: i64) -> String {
+    chrono::DateTime::from_timestamp(ts, 0)
+        .map(|dt| dt.to_rfc3339())
+        .unwrap_or_default()
+}
+
+#[tauri::command]
+pub async fn delete_revenue_entry(app: AppHandle, id: String) -> Result<(), AppError> {
+    permissions::require(&app, Permission::ManageRevenue)?;
+    let conn = db::get_db(&app)?;
+    // Orphaned refunds stay, but no longer point at a missing entry
+    conn.execute(
+        "UPDATE revenue_entries SET refund_of = NULL WHERE refund_of = ?1",
+        rusqlite::params![id],
+    )
+    .ok();
     conn.execute(
         "DELETE FROM revenue_entries WHERE id = ?1",
         rusqlite::params![id],
@@ -233,3 +1376,102 @@ pub async fn delete_revenue_entry(app: AppHandle, id: String) -> Result<(), Stri
     .map_err(|e| format!("Failed to delete: {}", e))?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(amount: i64, etype: &str, recorded: &str, period: Option<(&str, &str)>) -> RecognitionRow {
+        let (start, end) = period.map_or((None, None), |(s, e)| (Some(s.to_string()), Some(e.to_string())));
+        (amount, etype.to_string(), recorded.to_string(), start, end, None, None)
+    }
+
+    fn refund_of(amount: i64, recorded: &str, period: (&str, &str)) -> RecognitionRow {
+        let mut row = entry(amount, "refund", recorded, None);
+        row.5 = Some(period.0.to_string());
+        row.6 = Some(period.1.to_string());
+        row
+    }
+
+    #[test]
+    fn period_end_on_the_first_or_anniversary_is_exclusive() {
+        let jan_2025 = 2025 * 12;
+        assert_eq!(period_months("2025-01-01", "2026-01-01"), Some((jan_2025, 12)));
+        assert_eq!(period_months("2025-01-15", "2026-01-15"), Some((jan_2025, 12)));
+        assert_eq!(period_months("2025-01-31T10:00:00Z", "2026-01-31T10:00:00Z"), Some((jan_2025, 12)));
+        assert_eq!(period_months("2025-01-15", "2025-02-15"), Some((jan_2025, 1)));
+    }
+
+    #[test]
+    fn clamped_month_end_anniversary_is_exclusive() {
+        // What the Substack import stores: start + 1 or 12 months, clamped
+        // to the end of a shorter month
+        assert_eq!(period_months("2023-01-31", "2023-02-28"), Some((2023 * 12, 1)));
+        assert_eq!(period_months("2024-01-31", "2024-02-29"), Some((2024 * 12, 1)));
+        assert_eq!(period_months("2024-02-29", "2025-02-28"), Some((2024 * 12 + 1, 12)));
+        assert_eq!(period_months("2025-01-31", "2025-04-30"), Some((2025 * 12, 3)));
+    }
+
+    #[test]
+    fn other_period_ends_are_inclusive() {
+        let jan_2025 = 2025 * 12;
+        assert_eq!(period_months("2025-01-01", "2025-12-31"), Some((jan_2025, 12)));
+        assert_eq!(period_months("2025-01-01", "2025-01-31"), Some((jan_2025, 1)));
+        assert_eq!(period_months("2025-01-15", "2026-01-14"), Some((jan_2025, 12)));
+        // Month-only dates carry no day to go by
+        assert_eq!(period_months("2025-01", "2026-01"), Some((jan_2025, 12)));
+    }
+
+    #[test]
+    fn inclusive_annual_plan_spreads_over_twelve_months() {
+        let rows = vec![entry(12_000, "recurring", "2025-01-01", Some(("2025-01-01", "2025-12-31")))];
+        let months = recognise(rows, "2025-01", "2025-12", false);
+        assert_eq!(months.len(), 12);
+        assert!(months.values().all(|v| *v == 1_000));
+    }
+
+    #[test]
+    fn refund_of_spread_entry_offsets_remaining_months() {
+        let period = ("2025-01-01", "2026-01-01");
+        let rows = vec![
+            entry(12_000, "recurring", "2025-01-01", Some(period)),
+            refund_of(12_000, "2025-04-10", period),
+        ];
+        let months = recognise(rows, "2025-01", "2025-12", false);
+        assert_eq!(months["2025-03"], 1_000);
+        // 12,000 over the nine months April to December
+        assert_eq!(months["2025-04"], 1_000 - 1_333 - 3);
+        assert_eq!(months["2025-05"], 1_000 - 1_333);
+        assert_eq!(months.values().sum::<i64>(), 0);
+    }
+
+    #[test]
+    fn refund_after_the_period_lands_in_its_own_month() {
+        let period = ("2025-01-01", "2025-12-31");
+        let rows = vec![refund_of(500, "2026-02-03", period), entry(300, "one_time", "2026-02-01", None)];
+        let months = recognise(rows, "2026-01", "2026-03", false);
+        assert_eq!(months["2026-02"], -200);
+    }
+
+    #[test]
+    fn money_with_thousands_separators() {
+        assert_eq!(parse_money_cents("1,234.50"), Some(123_450));
+        assert_eq!(parse_money_cents("$12.50"), Some(1_250));
+        assert_eq!(parse_money_cents("12.5"), Some(1_250));
+        assert_eq!(parse_money_cents("USD 1,200"), Some(120_000));
+    }
+
+    #[test]
+    fn money_negatives_in_either_notation() {
+        assert_eq!(parse_money_cents("(12.00)"), Some(-1_200));
+        assert_eq!(parse_money_cents("$(1,234.50)"), Some(-123_450));
+        assert_eq!(parse_money_cents("-3.00"), Some(-300));
+    }
+
+    #[test]
+    fn money_without_digits_is_none() {
+        assert_eq!(parse_money_cents(""), None);
+        assert_eq!(parse_money_cents("n/a"), None);
+        assert_eq!(parse_money_cents("1.2.3"), None);
+    }
+}
@@ -1,4 +1,11 @@
+use crate::commands::accessibility;
+use crate::commands::export::{self, ProjectDefaults};
+use crate::commands::platform::PublishOptions;
+use crate::commands::settings;
+use crate::commands::windows;
 use crate::db;
+use crate::error::AppError;
+use crate::permissions::{self, Permission};
 use chrono::Utc;
 use serde::{Deserialize, Serialize};
 use tauri::AppHandle;
@@ -13,8 +20,17 @@ pub struct ScheduledPost {
     pub title: String,
     pub scheduled_at: String,
     pub status: String,
+    pub publish_status: String,
+    pub options: PublishOptions,
+    /// Shared by posts created together (several publications or preset
+    /// targets) so their per-target statuses can be tracked as one send
+    pub group_id: Option<String>,
     pub error_message: Option<String>,
     pub published_url: Option<String>,
+    /// When the scheduler takes the post down again, for time-limited
+    /// promos; see `set_unpublish_at`
+    pub unpublish_at: Option<String>,
+    pub unpublished_at: Option<String>,
     pub created_at: String,
     pub updated_at: String,
 }
@@ -24,68 +40,701 @@ pub struct CalendarEvent {
     pub id: String,
     pub title: String,
     pub date: String,
-    pub event_type: String, // "scheduled" | "published" | "draft"
+    pub event_type: String, // "scheduled" | "published" | "draft" | "projected" | "idea"
     pub platform: Option<String>,
     pub status: String,
     pub document_id: Option<String>,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CalendarBucket {
+    /// "YYYY-MM-DD" for day buckets, "YYYY-MM-DDTHH" for hour buckets
+    pub key: String,
+    pub all_day: bool,
+    pub total: i64,
+    pub scheduled: i64,
+    pub published: i64,
+    pub draft: i64,
+    pub projected: i64,
+    pub ideas: i64,
+}
+
+/// Everything a planning view needs in one call.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CalendarView {
+    pub start: String,
+    pub end: String,
+    pub granularity: String, // "day" | "hour"
+    pub events: Vec<CalendarEvent>,
+    pub buckets: Vec<CalendarBucket>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PresetTarget {
+    pub platform: String,
+    pub account_id: String,
+    pub publication_id: Option<String>,
+}
+
+/// A saved platform set + defaults so recurring issues can be scheduled
+/// without re-entering every field.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PublishingPreset {
+    pub id: String,
+    pub project_id: Option<String>,
+    pub name: String,
+    pub targets: Vec<PresetTarget>,
+    /// "HH:MM" (UTC) used when `schedule_post` is given a bare date
+    pub default_time: Option<String>,
+    /// Status sent to the platform at publish time ("draft" | "published")
+    pub publish_status: String,
+    /// Weekdays ("mon".."sun") this preset recurs on, projected onto the calendar
+    pub recurrence_days: Vec<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+fn validate_default_time(time: &Option<String>) -> Result<(), String> {
+    if let Some(t) = time {
+        chrono::NaiveTime::parse_from_str(t, "%H:%M")
+            .map_err(|_| format!("Invalid default time '{}', expected HH:MM", t))?;
+    }
+    Ok(())
+}
+
+fn load_preset(conn: &rusqlite::Connection, id: &str) -> Result<PublishingPreset, String> {
+    conn.query_row(
+        "SELECT id, project_id, name, targets_json, default_time, publish_status, created_at, updated_at, recurrence_days
+         FROM publishing_presets WHERE id = ?1",
+        rusqlite::params![id],
+        preset_from_row,
+    )
+    .map_err(|e| format!("Preset not found: {}", e))
+}
+
+fn preset_from_row(row: &rusqlite::Row) -> rusqlite::Result<PublishingPreset> {
+    let targets_json: String = row.get(3)?;
+    Ok(PublishingPreset {
+        id: row.get(0)?,
+        project_id: row.get(1)?,
+        name: row.get(2)?,
+        targets: serde_json::from_str(&targets_json).unwrap_or_default(),
+        default_time: row.get(4)?,
+        publish_status: row.get(5)?,
+        recurrence_days: row
+            .get::<_, String>(8)?
+            .split(',')
+            .filter(|d| !d.is_empty())
+            .map(str::to_string)
+            .collect(),
+        created_at: row.get(6)?,
+        updated_at: row.get(7)?,
+    })
+}
+
+fn parse_weekday(day: &str) -> Option<chrono::Weekday> {
+    day.trim().parse::<chrono::Weekday>().ok()
+}
+
+/// Normalise to lowercase three-letter names, rejecting anything unknown.
+fn normalize_recurrence(days: Vec<String>) -> Result<Vec<String>, AppError> {
+    days.iter()
+        .map(|d| {
+            parse_weekday(d)
+                .map(|w| w.to_string().to_lowercase())
+                .ok_or_else(|| AppError::validation(format!("Invalid weekday '{}'", d)))
+        })
+        .collect()
+}
+
+/// Combine a bare "YYYY-MM-DD" with the preset's default time; full
+/// timestamps pass through untouched.
+fn apply_default_time(scheduled_at: &str, default_time: Option<&str>) -> String {
+    match (chrono::NaiveDate::parse_from_str(scheduled_at, "%Y-%m-%d"), default_time) {
+        (Ok(date), Some(time)) => format!("{}T{}:00Z", date.format("%Y-%m-%d"), time),
+        (Ok(date), None) => format!("{}T09:00:00Z", date.format("%Y-%m-%d")),
+        _ => scheduled_at.to_string(),
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn insert_scheduled_post(
+    conn: &rusqlite::Connection,
+    document_id: &str,
+    platform: &str,
+    account_id: &str,
+    publication_id: Option<String>,
+    title: &str,
+    scheduled_at: &str,
+    publish_status: &str,
+    options: &PublishOptions,
+    group_id: Option<&str>,
+    unpublish_at: Option<&str>,
+) -> Result<ScheduledPost, String> {
+    let id = uuid::Uuid::new_v4().to_string();
+    let now = Utc::now().to_rfc3339();
+    let options_json = serde_json::to_string(options).map_err(|e| e.to_string())?;
+
+    conn.execute(
+        "INSERT INTO scheduled_posts (id, document_id, platform, account_id, publication_id, title, scheduled_at, status, publish_status, options_json, group_id, unpublish_at, created_at, updated_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, 'pending', ?8, ?9, ?10, ?11, ?12, ?12)",
+        rusqlite::params![id, document_id, platform, account_id, publication_id, title, scheduled_at, publish_status, options_json, group_id, unpublish_at, now],
+    )
+    .map_err(|e| format!("Failed to schedule post: {}", e))?;
+
+    db::log_activity(conn, "post.scheduled", "scheduled_post", Some(&id), Some(&format!("Scheduled for {} on {}", platform, scheduled_at)));
+
+    Ok(ScheduledPost {
+        id,
+        document_id: document_id.to_string(),
+        platform: platform.to_string(),
+        account_id: account_id.to_string(),
+        publication_id,
+        title: title.to_string(),
+        scheduled_at: scheduled_at.to_string(),
+        status: "pending".to_string(),
+        publish_status: publish_status.to_string(),
+        options: options.clone(),
+        group_id: group_id.map(str::to_string),
+        error_message: None,
+        published_url: None,
+        unpublish_at: unpublish_at.map(str::to_string),
+        unpublished_at: None,
+        created_at: now.clone(),
+        updated_at: now,
+    })
+}
+
+/// Targets resolved from a preset or explicit arguments, before insert:
+/// (platform, account_id, publication_id, scheduled_at, publish_status)
+type PlannedPost = (String, String, Option<String>, String, String);
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ScheduleResult {
+    pub posts: Vec<ScheduledPost>,
+    /// Guardrail violations that were accepted (warn mode or `force`)
+    pub warnings: Vec<GuardrailViolation>,
+}
+
+/// Schedule a document. With `preset_id`, one post is created per preset
+/// target and `scheduled_at` may be a bare date (the preset's default time
+/// is applied); otherwise `platform` and `account_id` are required, and
+/// `publication_ids` fans one send out to several publications on that
+/// account.
+///
+/// Without a preset or platform the document's project default targets
+/// are used, and its default tags, SEO fields and content blocks fill in
+/// whatever `options` leaves unset.
+/// Guardrails (blackouts, minimum gap per list) either block or only warn,
+/// depending on the `scheduling.guardrail_mode` setting; `force` overrides
+/// a block.
+///
+/// `unpublish_at` takes every target down again at that time, on platforms
+/// that support it (see `supports_unpublish`).
+#[allow(clippy::too_many_arguments)]
 #[tauri::command]
 pub async fn schedule_post(
     app: AppHandle,
     document_id: String,
-    platform: String,
-    account_id: String,
+    platform: Option<String>,
+    account_id: Option<String>,
     publication_id: Option<String>,
     title: String,
     scheduled_at: String,
-) -> Result<ScheduledPost, String> {
+    preset_id: Option<String>,
+    force: Option<bool>,
+    options: Option<PublishOptions>,
+    publication_ids: Option<Vec<String>>,
+    unpublish_at: Option<String>,
+) -> Result<ScheduleResult, AppError> {
+    let rules = guardrail_rules(&app);
+    let mut conn = db::get_db(&app)?;
+    let now = Utc::now().to_rfc3339();
+    let defaults = export::defaults_for_document(&conn, &document_id).unwrap_or_default();
+    let options = apply_project_defaults(options.unwrap_or_default(), &defaults, &title);
+
+    let planned: Vec<PlannedPost> = match preset_id {
+        Some(preset_id) => {
+            let preset = load_preset(&conn, &preset_id)?;
+            if preset.targets.is_empty() {
+                return Err(AppError::validation(format!("Preset '{}' has no platforms", preset.name)));
+            }
+            let at = apply_default_time(&scheduled_at, preset.default_time.as_deref());
+            preset
+                .targets
+                .iter()
+                .map(|t| {
+                    (
+                        t.platform.clone(),
+                        t.account_id.clone(),
+                        t.publication_id.clone().or_else(|| publication_id.clone()),
+                        at.clone(),
+                        preset.publish_status.clone(),
+                    )
+                })
+                .collect()
+        }
+        None if platform.is_none() && !defaults.targets.is_empty() => defaults
+            .targets
+            .iter()
+            .map(|t| {
+                (
+                    t.platform.clone(),
+                    t.account_id.clone(),
+                    t.publication_id.clone().or_else(|| publication_id.clone()),
+                    scheduled_at.clone(),
+                    "draft".to_string(),
+                )
+            })
+            .collect(),
+        None => {
+            let platform = platform
+                .ok_or_else(|| AppError::validation("platform is required without a preset or project targets"))?;
+            let account_id =
+                account_id.ok_or_else(|| AppError::validation("account_id is required without a preset"))?;
+            let targets: Vec<Option<String>> = match publication_ids {
+                Some(ids) if !ids.is_empty() => ids.into_iter().map(Some).collect(),
+                _ => vec![publication_id],
+            };
+            targets
+                .into_iter()
+                .map(|pub_id| {
+                    (platform.clone(), account_id.clone(), pub_id, scheduled_at.clone(), "draft".to_string())
+                })
+                .collect()
+        }
+    };
+    let group_id = (planned.len() > 1).then(|| uuid::Uuid::new_v4().to_string());
+    let unpublish_at = unpublish_at.filter(|at| !at.trim().is_empty());
+    if let Some(unpublish_at) = &unpublish_at {
+        for (platform, _, _, at, _) in &planned {
+            validate_unpublish_at(platform, Some(at), unpublish_at)?;
+        }
+    }
+
+    let mut warnings = Vec::new();
+    for (platform, account_id, publication_id, at, _) in &planned {
+        warnings.extend(check_guardrails(
+            &conn,
+            &rules,
+            platform,
+            account_id,
+            publication_id.as_deref(),
+            at,
+            None,
+        )?);
+    }
+    // Alt text blocks under its own setting, independent of the guardrail mode
+    let html: String = conn
+        .query_row("SELECT html_content FROM documents WHERE id = ?1", rusqlite::params![document_id], |row| row.get(0))
+        .unwrap_or_default();
+    let missing_alt = accessibility::enforce(&app, &html, force.unwrap_or(false))?;
+    if !missing_alt.is_empty() {
+        warnings.push(GuardrailViolation {
+            rule: "alt_text".to_string(),
+            message: accessibility::describe(&missing_alt),
+            platform: String::new(),
+            scheduled_at: scheduled_at.clone(),
+        });
+    }
+    if warnings.iter().any(|w| w.rule != "alt_text") && rules.mode == "block" && !force.unwrap_or(false) {
+        let reasons: Vec<&str> = warnings.iter().filter(|w| w.rule != "alt_text").map(|w| w.message.as_str()).collect();
+        return Err(AppError::validation(format!("Blocked by publishing guardrails: {}", reasons.join("; "))));
+    }
+
+    // All targets are scheduled or none are
+    let tx = conn
+        .transaction()
+        .map_err(|e| format!("Failed to start transaction: {}", e))?;
+    let mut posts = Vec::new();
+    for (platform, account_id, publication_id, at, publish_status) in planned {
+        posts.push(insert_scheduled_post(
+            &tx,
+            &document_id,
+            &platform,
+            &account_id,
+            publication_id,
+            &title,
+            &at,
+            &publish_status,
+            &options,
+            group_id.as_deref(),
+            unpublish_at.as_deref(),
+        )?);
+    }
+
+    // Update document status
+    if let Some(first) = posts.first() {
+        tx.execute(
+            "UPDATE documents SET status = 'scheduled', scheduled_at = ?1, updated_at = ?2 WHERE id = ?3",
+            rusqlite::params![first.scheduled_at, now, document_id],
+        )?;
+    }
+    tx.commit()
+        .map_err(|e| format!("Failed to commit schedule: {}", e))?;
+    if !posts.is_empty() {
+        windows::document_changed(&app, &document_id, "status", None, Some("scheduled"));
+    }
+    windows::schedule_changed(&app, Some(&document_id), posts.iter().map(|p| p.id.clone()).collect(), "scheduled");
+
+    Ok(ScheduleResult { posts, warnings })
+}
+
+/// Merge project defaults into explicit options: tags are added, SEO and
+/// blocks only fill gaps.
+fn apply_project_defaults(mut options: PublishOptions, defaults: &ProjectDefaults, title: &str) -> PublishOptions {
+    let seo = &defaults.seo;
+    if !defaults.tags.is_empty() || seo.title_suffix.is_some() || seo.meta_description.is_some() || seo.og_image.is_some() {
+        let ghost = options.ghost.get_or_insert_with(Default::default);
+        for tag in &defaults.tags {
+            if !ghost.tags.contains(tag) {
+                ghost.tags.push(tag.clone());
+            }
+        }
+        if ghost.meta_title.is_none() {
+            ghost.meta_title = seo.title_suffix.as_ref().map(|suffix| format!("{}{}", title, suffix));
+        }
+        if ghost.meta_description.is_none() {
+            ghost.meta_description = seo.meta_description.clone();
+        }
+        if ghost.og_image.is_none() {
+            ghost.og_image = seo.og_image.clone();
+        }
+    }
+    if !defaults.tags.is_empty() {
+        let beehiiv = options.beehiiv.get_or_insert_with(Default::default);
+        for tag in &defaults.tags {
+            if !beehiiv.content_tags.contains(tag) {
+                beehiiv.content_tags.push(tag.clone());
+            }
+        }
+    }
+    if options.block_ids.is_empty() {
+        options.block_ids = defaults.brand.block_ids.clone();
+    }
+    options
+}
+
+// ─── Guardrails ─────────────────────────────────────────────────
+
+pub const GUARDRAIL_MODE_SETTING: &str = "scheduling.guardrail_mode";
+pub const MIN_GAP_HOURS_SETTING: &str = "scheduling.min_gap_hours";
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BlackoutPeriod {
+    pub id: String,
+    pub name: String,
+    pub starts_at: String,
+    pub ends_at: String,
+    pub created_at: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GuardrailViolation {
+    pub rule: String, // "blackout" | "min_gap" | "alt_text"
+    pub message: String,
+    pub platform: String,
+    pub scheduled_at: String,
+}
+
+pub(crate) struct GuardrailRules {
+    /// "warn" (default) or "block"
+    pub mode: String,
+    /// Minimum hours between two sends to the same list; 0 disables
+    pub min_gap_hours: i64,
+}
+
+pub(crate) fn guardrail_rules(app: &AppHandle) -> GuardrailRules {
+    GuardrailRules {
+        mode: settings::get_setting(app, GUARDRAIL_MODE_SETTING).unwrap_or_else(|| "warn".to_string()),
+        min_gap_hours: settings::get_setting(app, MIN_GAP_HOURS_SETTING).unwrap_or(24),
+    }
+}
+
+/// The blackout containing `at`, if any.
+pub(crate) fn blackout_at(conn: &rusqlite::Connection, at: &str) -> Option<BlackoutPeriod> {
+    let at = chrono::DateTime::parse_from_rfc3339(at).ok()?.with_timezone(&Utc).to_rfc3339();
+    conn.query_row(
+        "SELECT id, name, starts_at, ends_at, created_at FROM blackout_periods
+         WHERE starts_at <= ?1 AND ends_at > ?1 LIMIT 1",
+        rusqlite::params![at],
+        |row| {
+            Ok(BlackoutPeriod {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                starts_at: row.get(2)?,
+                ends_at: row.get(3)?,
+                created_at: row.get(4)?,
+            })
+        },
+    )
+    .ok()
+}
+
+/// Violations for sending to one list at `at`. `exclude_id` skips the post
+/// being checked when it already exists.
+pub(crate) fn check_guardrails(
+    conn: &rusqlite::Connection,
+    rules: &GuardrailRules,
+    platform: &str,
+    account_id: &str,
+    publication_id: Option<&str>,
+    at: &str,
+    exclude_id: Option<&str>,
+) -> Result<Vec<GuardrailViolation>, String> {
+    let mut violations = Vec::new();
+    let violation = |rule: &str, message: String| GuardrailViolation {
+        rule: rule.to_string(),
+        message,
+        platform: platform.to_string(),
+        scheduled_at: at.to_string(),
+    };
+
+    let when = chrono::DateTime::parse_from_rfc3339(at)
+        .map_err(|_| format!("Invalid scheduled time '{}'", at))?
+        .with_timezone(&Utc);
+
+    if let Some(blackout) = blackout_at(conn, at) {
+        violations.push(violation(
+            "blackout",
+            format!("{} falls within blackout '{}'", at, blackout.name),
+        ));
+    }
+
+    if rules.min_gap_hours > 0 {
+        let mut stmt = conn
+            .prepare(
+                "SELECT id, scheduled_at FROM scheduled_posts
+                 WHERE platform = ?1 AND account_id = ?2 AND COALESCE(publication_id, '') = ?3
+                 AND status IN ('pending', 'publishing', 'published')",
+            )
+            .map_err(|e| format!("Query failed: {}", e))?;
+        let nearby = stmt
+            .query_map(
+                rusqlite::params![platform, account_id, publication_id.unwrap_or("")],
+                |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)),
+            )
+            .map_err(|e| format!("Query map failed: {}", e))?
+            .filter_map(|r| r.ok())
+            .filter(|(id, _)| Some(id.as_str()) != exclude_id)
+            .find_map(|(_, other)| {
+                let other_dt = chrono::DateTime::parse_from_rfc3339(&other).ok()?.with_timezone(&Utc);
+                ((other_dt - when).num_minutes().abs() < rules.min_gap_hours * 60).then_some(other)
+            });
+        if let Some(other) = nearby {
+            violations.push(violation(
+                "min_gap",
+                format!(
+                    "Another send to this {} list is scheduled at {} (within {}h)",
+                    platform, other, rules.min_gap_hours
+                ),
+            ));
+        }
+    }
+
+    Ok(violations)
+}
+
+#[tauri::command]
+pub async fn create_blackout(
+    app: AppHandle,
+    name: String,
+    starts_at: String,
+    ends_at: String,
+) -> Result<BlackoutPeriod, AppError> {
+    let parse = |s: &str| {
+        chrono::DateTime::parse_from_rfc3339(s)
+            .map(|dt| dt.with_timezone(&Utc))
+            .map_err(|_| format!("Invalid timestamp '{}'", s))
+    };
+    let (start, end) = (parse(&starts_at)?, parse(&ends_at)?);
+    if end <= start {
+        return Err(AppError::validation("Blackout must end after it starts"));
+    }
+
     let conn = db::get_db(&app)?;
     let id = uuid::Uuid::new_v4().to_string();
     let now = Utc::now().to_rfc3339();
+    // Stored normalised to UTC so range checks can compare strings
+    let (starts_at, ends_at) = (start.to_rfc3339(), end.to_rfc3339());
 
     conn.execute(
-        "INSERT INTO scheduled_posts (id, document_id, platform, account_id, publication_id, title, scheduled_at, status, created_at, updated_at)
-         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, 'pending', ?8, ?8)",
-        rusqlite::params![id, document_id, platform, account_id, publication_id, title, scheduled_at, now],
+        "INSERT INTO blackout_periods (id, name, starts_at, ends_at, created_at) VALUES (?1, ?2, ?3, ?4, ?5)",
+        rusqlite::params![id, name, starts_at, ends_at, now],
     )
-    .map_err(|e| format!("Failed to schedule post: {}", e))?;
+    .map_err(|e| format!("Failed to create blackout: {}", e))?;
 
-    // Update document status
+    db::log_activity(&conn, "blackout.created", "blackout", Some(&id), Some(&name));
+
+    Ok(BlackoutPeriod {
+        id,
+        name,
+        starts_at,
+        ends_at,
+        created_at: now,
+    })
+}
+
+#[tauri::command]
+pub async fn list_blackouts(app: AppHandle) -> Result<Vec<BlackoutPeriod>, AppError> {
+    let conn = db::get_db(&app)?;
+    let mut stmt = conn
+        .prepare("SELECT id, name, starts_at, ends_at, created_at FROM blackout_periods ORDER BY starts_at ASC")
+        .map_err(|e| format!("Query failed: {}", e))?;
+    let rows = stmt
+        .query_map([], |row| {
+            Ok(BlackoutPeriod {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                starts_at: row.get(2)?,
+                ends_at: row.get(3)?,
+                created_at: row.get(4)?,
+            })
+        })
+        .map_err(|e| format!("Query map failed: {}", e))?;
+
+    Ok(rows.filter_map(|r| r.ok()).collect())
+}
+
+#[tauri::command]
+pub async fn delete_blackout(app: AppHandle, id: String) -> Result<(), AppError> {
+    permissions::require(&app, Permission::DeleteContent)?;
+    let conn = db::get_db(&app)?;
     conn.execute(
-        "UPDATE documents SET status = 'scheduled', scheduled_at = ?1, updated_at = ?2 WHERE id = ?3",
-        rusqlite::params![scheduled_at, now, document_id],
-    ).ok();
+        "DELETE FROM blackout_periods WHERE id = ?1",
+        rusqlite::params![id],
+    )
+    .map_err(|e| format!("Failed to delete blackout: {}", e))?;
+    Ok(())
+}
 
-    db::log_activity(&conn, "post.scheduled", "scheduled_post", Some(&id), Some(&format!("Scheduled for {} on {}", platform, scheduled_at)));
+// ─── Publishing presets ─────────────────────────────────────────
 
-    Ok(ScheduledPost {
+#[tauri::command]
+pub async fn create_publishing_preset(
+    app: AppHandle,
+    project_id: Option<String>,
+    name: String,
+    targets: Vec<PresetTarget>,
+    default_time: Option<String>,
+    publish_status: Option<String>,
+    recurrence_days: Option<Vec<String>>,
+) -> Result<PublishingPreset, AppError> {
+    validate_default_time(&default_time)?;
+    let recurrence_days = normalize_recurrence(recurrence_days.unwrap_or_default())?;
+    let conn = db::get_db(&app)?;
+    let id = uuid::Uuid::new_v4().to_string();
+    let now = Utc::now().to_rfc3339();
+    let publish_status = publish_status.unwrap_or_else(|| "draft".to_string());
+    let targets_json = serde_json::to_string(&targets).map_err(|e| e.to_string())?;
+
+    conn.execute(
+        "INSERT INTO publishing_presets (id, project_id, name, targets_json, default_time, publish_status, recurrence_days, created_at, updated_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?8)",
+        rusqlite::params![id, project_id, name, targets_json, default_time, publish_status, recurrence_days.join(","), now],
+    )
+    .map_err(|e| format!("Failed to create preset: {}", e))?;
+
+    Ok(PublishingPreset {
         id,
-        document_id,
-        platform,
-        account_id,
-        publication_id,
-        title,
-        scheduled_at,
-        status: "pending".to_string(),
-        error_message: None,
-        published_url: None,
+        project_id,
+        name,
+        targets,
+        default_time,
+        publish_status,
+        recurrence_days,
         created_at: now.clone(),
         updated_at: now,
     })
 }
 
+/// Presets for a project, plus global (project-less) presets.
+#[tauri::command]
+pub async fn list_publishing_presets(
+    app: AppHandle,
+    project_id: Option<String>,
+) -> Result<Vec<PublishingPreset>, AppError> {
+    let conn = db::get_db(&app)?;
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, project_id, name, targets_json, default_time, publish_status, created_at, updated_at, recurrence_days
+             FROM publishing_presets
+             WHERE project_id IS NULL OR project_id = ?1
+             ORDER BY name ASC",
+        )
+        .map_err(|e| format!("Query failed: {}", e))?;
+    let rows = stmt
+        .query_map(rusqlite::params![project_id], preset_from_row)
+        .map_err(|e| format!("Query map failed: {}", e))?;
+
+    Ok(rows.filter_map(|r| r.ok()).collect())
+}
+
+#[tauri::command]
+pub async fn update_publishing_preset(
+    app: AppHandle,
+    id: String,
+    name: Option<String>,
+    targets: Option<Vec<PresetTarget>>,
+    default_time: Option<String>,
+    publish_status: Option<String>,
+    recurrence_days: Option<Vec<String>>,
+) -> Result<PublishingPreset, AppError> {
+    validate_default_time(&default_time)?;
+    let conn = db::get_db(&app)?;
+    let mut preset = load_preset(&conn, &id)?;
+
+    if let Some(n) = name {
+        preset.name = n;
+    }
+    if let Some(t) = targets {
+        preset.targets = t;
+    }
+    if default_time.is_some() {
+        preset.default_time = default_time;
+    }
+    if let Some(s) = publish_status {
+        preset.publish_status = s;
+    }
+    if let Some(days) = recurrence_days {
+        preset.recurrence_days = normalize_recurrence(days)?;
+    }
+    preset.updated_at = Utc::now().to_rfc3339();
+    let targets_json = serde_json::to_string(&preset.targets).map_err(|e| e.to_string())?;
+
+    conn.execute(
+        "UPDATE publishing_presets SET name = ?1, targets_json = ?2, default_time = ?3, publish_status = ?4, recurrence_days = ?5, updated_at = ?6 WHERE id = ?7",
+        rusqlite::params![preset.name, targets_json, preset.default_time, preset.publish_status, preset.recurrence_days.join(","), preset.updated_at, id],
+    )
+    .map_err(|e| format!("Failed to update preset: {}", e))?;
+
+    Ok(preset)
+}
+
+#[tauri::command]
+pub async fn delete_publishing_preset(app: AppHandle, id: String) -> Result<(), AppError> {
+    permissions::require(&app, Permission::DeleteContent)?;
+    let conn = db::get_db(&app)?;
+    conn.execute(
+        "DELETE FROM publishing_presets WHERE id = ?1",
+        rusqlite::params![id],
+    )
+    .map_err(|e| format!("Failed to delete preset: {}", e))?;
+    Ok(())
+}
+
 #[tauri::command]
 pub async fn list_scheduled_posts(
     app: AppHandle,
     from: Option<String>,
     to: Option<String>,
     status: Option<String>,
-) -> Result<Vec<ScheduledPost>, String> {
+    group_id: Option<String>,
+) -> Result<Vec<ScheduledPost>, AppError> {
     let conn = db::get_db(&app)?;
 
     let mut sql = String::from(
-        "SELECT id, document_id, platform, account_id, publication_id, title, scheduled_at, status, error_message, published_url, created_at, updated_at
+        "SELECT id, document_id, platform, account_id, publication_id, title, scheduled_at, status, error_message, published_url, created_at, updated_at, publish_status, options_json, group_id, unpublish_at, unpublished_at
          FROM scheduled_posts WHERE 1=1",
     );
     let mut params: Vec<Box<dyn rusqlite::types::ToSql>> = Vec::new();
@@ -102,6 +751,10 @@ pub async fn list_scheduled_posts(
         sql.push_str(&format!(" AND status = ?{}", params.len() + 1));
         params.push(Box::new(s.clone()));
     }
+    if let Some(ref g) = group_id {
+        sql.push_str(&format!(" AND group_id = ?{}", params.len() + 1));
+        params.push(Box::new(g.clone()));
+    }
 
     sql.push_str(" ORDER BY scheduled_at ASC");
 
@@ -119,8 +772,13 @@ pub async fn list_scheduled_posts(
                 title: row.get(5)?,
                 scheduled_at: row.get(6)?,
                 status: row.get(7)?,
+                publish_status: row.get(12)?,
+                options: serde_json::from_str(&row.get::<_, String>(13)?).unwrap_or_default(),
+                group_id: row.get(14)?,
                 error_message: row.get(8)?,
                 published_url: row.get(9)?,
+                unpublish_at: row.get(15)?,
+                unpublished_at: row.get(16)?,
                 created_at: row.get(10)?,
                 updated_at: row.get(11)?,
             })
@@ -131,7 +789,7 @@ pub async fn list_scheduled_posts(
 }
 
 #[tauri::command]
-pub async fn cancel_scheduled_post(app: AppHandle, id: String) -> Result<(), String> {
+pub async fn cancel_scheduled_post(app: AppHandle, id: String) -> Result<(), AppError> {
     let conn = db::get_db(&app)?;
     let now = Utc::now().to_rfc3339();
 
@@ -151,12 +809,16 @@ pub async fn cancel_scheduled_post(app: AppHandle, id: String) -> Result<(), Str
     .map_err(|e| format!("Failed to cancel: {}", e))?;
 
     // Reset document status to draft if it was scheduled
-    if let Some(doc_id) = doc_id {
-        conn.execute(
+    if let Some(doc_id) = &doc_id {
+        let reset = conn.execute(
             "UPDATE documents SET status = 'draft', scheduled_at = NULL, updated_at = ?1 WHERE id = ?2 AND status = 'scheduled'",
             rusqlite::params![now, doc_id],
-        ).ok();
+        ).unwrap_or(0);
+        if reset > 0 {
+            windows::document_changed(&app, doc_id, "status", None, Some("draft"));
+        }
     }
+    windows::schedule_changed(&app, doc_id.as_deref(), vec![id], "cancelled");
 
     Ok(())
 }
@@ -166,21 +828,224 @@ pub async fn reschedule_post(
     app: AppHandle,
     id: String,
     new_scheduled_at: String,
-) -> Result<(), String> {
+) -> Result<(), AppError> {
     let conn = db::get_db(&app)?;
     let now = Utc::now().to_rfc3339();
 
     conn.execute(
-        "UPDATE scheduled_posts SET scheduled_at = ?1, status = 'pending', error_message = NULL, updated_at = ?2 WHERE id = ?3",
+        "UPDATE scheduled_posts SET scheduled_at = ?1, error_message = NULL, updated_at = ?2,
+             status = CASE WHEN status = 'awaiting_approval' THEN status ELSE 'pending' END
+         WHERE id = ?3",
         rusqlite::params![new_scheduled_at, now, id],
     )
     .map_err(|e| format!("Failed to reschedule: {}", e))?;
+    windows::schedule_changed(&app, None, vec![id], "rescheduled");
+
+    Ok(())
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RescheduleChange {
+    pub id: String,
+    pub new_scheduled_at: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RescheduleConflict {
+    pub id: String,
+    pub reason: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RescheduleBatchResult {
+    /// False when any change conflicted; in that case nothing was written.
+    pub applied: bool,
+    pub updated: usize,
+    pub conflicts: Vec<RescheduleConflict>,
+}
+
+/// Apply many reschedules at once (calendar drag-and-drop). All changes are
+/// validated inside one transaction and committed only if none conflict:
+/// unknown or duplicate ids, unparseable times, posts already published, or
+/// two pending posts landing on the same account in the same minute.
+#[tauri::command]
+pub async fn reschedule_posts_batch(
+    app: AppHandle,
+    changes: Vec<RescheduleChange>,
+) -> Result<RescheduleBatchResult, AppError> {
+    let mut conn = db::get_db(&app)?;
+    let tx = conn
+        .transaction()
+        .map_err(|e| format!("Failed to start transaction: {}", e))?;
+    let now = Utc::now().to_rfc3339();
+
+    let mut conflicts = Vec::new();
+    let mut seen = std::collections::HashSet::new();
+    // (id, platform, account_id, new time)
+    let mut pending: Vec<(String, String, String, chrono::DateTime<Utc>)> = Vec::new();
+
+    for change in &changes {
+        let conflict = |reason: String| RescheduleConflict {
+            id: change.id.clone(),
+            reason,
+        };
+        if !seen.insert(change.id.clone()) {
+            conflicts.push(conflict("Post appears more than once in the batch".to_string()));
+            continue;
+        }
+        let new_at = match chrono::DateTime::parse_from_rfc3339(&change.new_scheduled_at) {
+            Ok(dt) => dt.with_timezone(&Utc),
+            Err(_) => {
+                conflicts.push(conflict(format!("Invalid time '{}'", change.new_scheduled_at)));
+                continue;
+            }
+        };
+        let row: Option<(String, String, String)> = tx
+            .query_row(
+                "SELECT platform, account_id, status FROM scheduled_posts WHERE id = ?1",
+                rusqlite::params![change.id],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .ok();
+        let Some((platform, account_id, status)) = row else {
+            conflicts.push(conflict("Scheduled post not found".to_string()));
+            continue;
+        };
+        if matches!(status.as_str(), "published" | "publishing" | "unpublished" | "unpublish_failed") {
+            conflicts.push(conflict(format!("Post is already {}", status)));
+            continue;
+        }
+
+        tx.execute(
+            "UPDATE scheduled_posts SET scheduled_at = ?1, error_message = NULL, updated_at = ?2,
+             status = CASE WHEN status = 'awaiting_approval' THEN status ELSE 'pending' END
+         WHERE id = ?3",
+            rusqlite::params![new_at.to_rfc3339(), now, change.id],
+        )
+        .map_err(|e| format!("Failed to reschedule: {}", e))?;
+        pending.push((change.id.clone(), platform, account_id, new_at));
+    }
+
+    // Slot clashes are checked after every update so that swapping two
+    // posts within one batch is allowed.
+    for (id, platform, account_id, new_at) in &pending {
+        let mut stmt = tx
+            .prepare(
+                "SELECT id, scheduled_at FROM scheduled_posts
+                 WHERE id != ?1 AND platform = ?2 AND account_id = ?3 AND status IN ('pending', 'failed')",
+            )
+            .map_err(|e| format!("Query failed: {}", e))?;
+        let clash = stmt
+            .query_map(rusqlite::params![id, platform, account_id], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+            })
+            .map_err(|e| format!("Query map failed: {}", e))?
+            .filter_map(|r| r.ok())
+            .find(|(_, at)| {
+                chrono::DateTime::parse_from_rfc3339(at)
+                    .map(|dt| (dt.with_timezone(&Utc) - *new_at).num_seconds().abs() < 60)
+                    .unwrap_or(false)
+            });
+        if let Some((other_id, _)) = clash {
+            conflicts.push(RescheduleConflict {
+                id: id.clone(),
+                reason: format!("Clashes with post {} on the same {} account", other_id, platform),
+            });
+        }
+    }
+
+    if !conflicts.is_empty() {
+        // Dropping the transaction rolls everything back
+        return Ok(RescheduleBatchResult {
+            applied: false,
+            updated: 0,
+            conflicts,
+        });
+    }
+
+    db::log_activity(&tx, "post.rescheduled", "scheduled_post", None, Some(&format!("Rescheduled {} posts", pending.len())));
+    tx.commit()
+        .map_err(|e| format!("Failed to commit reschedule: {}", e))?;
+    let updated = pending.len();
+    windows::schedule_changed(&app, None, pending.into_iter().map(|(id, ..)| id).collect(), "rescheduled");
+
+    Ok(RescheduleBatchResult {
+        applied: true,
+        updated,
+        conflicts,
+    })
+}
+
+// ─── Unpublishing ───────────────────────────────────────────────
+//
+// A post with `unpublish_at` is taken down by the scheduler once it has
+// been published and that time has passed: Ghost posts revert to drafts,
+// Beehiiv posts are deleted (archived once sent), Kit broadcasts leave the
+// public archive. The post's status becomes 'unpublished', or
+// 'unpublish_failed' with the error; setting the time again retries.
+
+/// Platforms the scheduler can take a post down from.
+pub(crate) fn supports_unpublish(platform: &str) -> bool {
+    matches!(platform, "ghost" | "beehiiv" | "kit")
+}
+
+/// `unpublish_at` must be a valid time after `scheduled_at` (when given),
+/// on a platform that can unpublish.
+fn validate_unpublish_at(platform: &str, scheduled_at: Option<&str>, unpublish_at: &str) -> Result<(), AppError> {
+    if !supports_unpublish(platform) {
+        return Err(AppError::validation(format!("Posts on {} can't be unpublished automatically", platform)));
+    }
+    let until = chrono::DateTime::parse_from_rfc3339(unpublish_at)
+        .map_err(|_| AppError::validation(format!("Invalid unpublish time '{}'", unpublish_at)))?;
+    let from = scheduled_at.and_then(|at| chrono::DateTime::parse_from_rfc3339(at).ok());
+    if from.is_some_and(|from| until <= from) {
+        return Err(AppError::validation("The unpublish time must be after the post goes out"));
+    }
+    Ok(())
+}
+
+/// Set or clear when a scheduled post is taken down. Works before and
+/// after it's published; on a post whose unpublish failed, setting a time
+/// retries at that time.
+#[tauri::command]
+pub async fn set_unpublish_at(app: AppHandle, id: String, unpublish_at: Option<String>) -> Result<(), AppError> {
+    let conn = db::get_db(&app)?;
+    let (platform, scheduled_at, status): (String, String, String) = conn
+        .query_row(
+            "SELECT platform, scheduled_at, status FROM scheduled_posts WHERE id = ?1",
+            rusqlite::params![id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )
+        .map_err(|_| AppError::not_found(format!("Scheduled post '{}' not found", id)))?;
+    if status == "unpublished" {
+        return Err(AppError::validation("The post has already been unpublished"));
+    }
+    let unpublish_at = unpublish_at.filter(|at| !at.trim().is_empty());
+    if let Some(at) = &unpublish_at {
+        // Already-published posts may be taken down any time from now
+        let after = (status != "published" && status != "unpublish_failed").then_some(scheduled_at.as_str());
+        validate_unpublish_at(&platform, after, at)?;
+    }
 
+    let now = Utc::now().to_rfc3339();
+    conn.execute(
+        "UPDATE scheduled_posts SET unpublish_at = ?1, updated_at = ?2,
+             status = CASE status WHEN 'unpublish_failed' THEN 'published' ELSE status END,
+             error_message = CASE status WHEN 'unpublish_failed' THEN NULL ELSE error_message END
+         WHERE id = ?3",
+        rusqlite::params![unpublish_at, now, id],
+    )?;
+    let details = match &unpublish_at {
+        Some(at) => format!("Unpublish set for {}", at),
+        None => "Unpublish cleared".to_string(),
+    };
+    db::log_activity(&conn, "post.unpublish_scheduled", "scheduled_post", Some(&id), Some(&details));
+    windows::schedule_changed(&app, None, vec![id], "unpublish_scheduled");
     Ok(())
 }
 
 #[tauri::command]
-pub async fn publish_scheduled_now(app: AppHandle, id: String) -> Result<(), String> {
+pub async fn publish_scheduled_now(app: AppHandle, id: String) -> Result<(), AppError> {
     let conn = db::get_db(&app)?;
     let now = Utc::now().to_rfc3339();
 
@@ -190,24 +1055,422 @@ pub async fn publish_scheduled_now(app: AppHandle, id: String) -> Result<(), Str
         rusqlite::params![now, id],
     )
     .map_err(|e| format!("Failed to publish now: {}", e))?;
+    windows::schedule_changed(&app, None, vec![id], "publish_now");
 
     Ok(())
 }
 
+// ─── Pausing ────────────────────────────────────────────────────
+//
+// A global pause and per-platform pauses both leave due posts pending
+// instead of cancelling them; they go out on the first tick after resume.
+// Stored in settings so a pause survives a restart.
+
+pub const PAUSED_SETTING: &str = "scheduler.paused";
+pub const PAUSED_PLATFORMS_SETTING: &str = "scheduler.paused_platforms";
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SchedulerPauseState {
+    pub paused: bool,
+    pub paused_platforms: Vec<String>,
+}
+
+pub(crate) fn pause_state(app: &AppHandle) -> SchedulerPauseState {
+    SchedulerPauseState {
+        paused: settings::get_setting(app, PAUSED_SETTING).unwrap_or(false),
+        paused_platforms: settings::get_setting(app, PAUSED_PLATFORMS_SETTING).unwrap_or_default(),
+    }
+}
+
+fn set_paused(app: &AppHandle, platform: Option<String>, paused: bool) -> Result<SchedulerPauseState, AppError> {
+    let mut state = pause_state(app);
+    match &platform {
+        None => {
+            state.paused = paused;
+            settings::set_setting(app, PAUSED_SETTING, serde_json::json!(paused))?;
+        }
+        Some(platform) => {
+            state.paused_platforms.retain(|p| p != platform);
+            if paused {
+                state.paused_platforms.push(platform.clone());
+                state.paused_platforms.sort();
+            }
+            settings::set_setting(app, PAUSED_PLATFORMS_SETTING, serde_json::json!(state.paused_platforms))?;
+        }
+    }
+
+    let conn = db::get_db(app)?;
+    let scope = platform.as_deref().unwrap_or("all platforms");
+    db::log_activity(
+        &conn,
+        if paused { "scheduler.paused" } else { "scheduler.resumed" },
+        "scheduler",
+        platform.as_deref(),
+        Some(&format!("{} publishing for {}", if paused { "Paused" } else { "Resumed" }, scope)),
+    );
+    Ok(state)
+}
+
+/// Stop the scheduler from publishing, everywhere or for one platform.
+#[tauri::command]
+pub async fn pause_scheduler(app: AppHandle, platform: Option<String>) -> Result<SchedulerPauseState, AppError> {
+    set_paused(&app, platform, true)
+}
+
+/// Undo `pause_scheduler` for the same scope. Resuming globally leaves
+/// per-platform pauses in place.
+#[tauri::command]
+pub async fn resume_scheduler(app: AppHandle, platform: Option<String>) -> Result<SchedulerPauseState, AppError> {
+    set_paused(&app, platform, false)
+}
+
+#[tauri::command]
+pub async fn get_scheduler_pause_state(app: AppHandle) -> Result<SchedulerPauseState, AppError> {
+    Ok(pause_state(&app))
+}
+
+// ─── Publish attempts ───────────────────────────────────────────
+
+/// One publish call, scheduled or manual, as recorded in publish_attempts.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PublishAttempt {
+    pub id: i64,
+    pub trace_id: Option<String>,
+    pub scheduled_post_id: Option<String>,
+    pub platform: String,
+    pub account_id: String,
+    pub publication_id: Option<String>,
+    pub status: String, // "started" | "published" | "failed"
+    pub post_id: Option<String>,
+    pub error_kind: Option<String>,
+    pub error_message: Option<String>,
+    pub started_at: String,
+    pub finished_at: Option<String>,
+}
+
+/// Publish attempts, newest first, optionally narrowed to one scheduled post
+/// or one trace id.
+#[tauri::command]
+pub async fn list_publish_attempts(
+    app: AppHandle,
+    scheduled_post_id: Option<String>,
+    trace_id: Option<String>,
+    limit: Option<i64>,
+) -> Result<Vec<PublishAttempt>, AppError> {
+    let conn = db::get_db(&app)?;
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, trace_id, scheduled_post_id, platform, account_id, publication_id, status,
+                    post_id, error_kind, error_message, started_at, finished_at
+             FROM publish_attempts
+             WHERE (?1 IS NULL OR scheduled_post_id = ?1) AND (?2 IS NULL OR trace_id = ?2)
+             ORDER BY started_at DESC
+             LIMIT ?3",
+        )
+        .map_err(|e| format!("Query failed: {}", e))?;
+
+    let rows = stmt
+        .query_map(
+            rusqlite::params![scheduled_post_id, trace_id, limit.unwrap_or(50)],
+            |row| {
+                Ok(PublishAttempt {
+                    id: row.get(0)?,
+                    trace_id: row.get(1)?,
+                    scheduled_post_id: row.get(2)?,
+                    platform: row.get(3)?,
+                    account_id: row.get(4)?,
+                    publication_id: row.get(5)?,
+                    status: row.get(6)?,
+                    post_id: row.get(7)?,
+                    error_kind: row.get(8)?,
+                    error_message: row.get(9)?,
+                    started_at: row.get(10)?,
+                    finished_at: row.get(11)?,
+                })
+            },
+        )
+        .map_err(|e| format!("Query map failed: {}", e))?;
+
+    Ok(rows.filter_map(|r| r.ok()).collect())
+}
+// ─── Failure triage ─────────────────────────────────────────────
+//
+// After a platform outage, failed posts pile up with the same cause. They
+// are grouped by platform and by the kind of error their last publish
+// attempt ended with (the `ErrorKind` names: auth, rate_limited, network,
+// ...), so a whole group can be put back in the queue in one go.
+
+/// Which failed posts to summarize or retry; unset fields match everything.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct FailureFilter {
+    pub platform: Option<String>,
+    pub account_id: Option<String>,
+    pub error_kind: Option<String>,
+    /// "24h" | "7d" | "30d" | "all", by when the post failed
+    pub period: Option<String>,
+    /// Only these posts, when not empty
+    pub post_ids: Vec<String>,
+}
+
+/// Failed posts sharing a platform and error kind.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct FailureGroup {
+    pub platform: String,
+    pub error_kind: String,
+    pub count: usize,
+    pub post_ids: Vec<String>,
+    pub latest_error: Option<String>,
+    pub latest_at: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct FailureSummary {
+    pub period: String,
+    pub total: usize,
+    /// Largest first
+    pub groups: Vec<FailureGroup>,
+}
+
+struct FailedPost {
+    id: String,
+    document_id: String,
+    platform: String,
+    error_kind: String,
+    error_message: Option<String>,
+    failed_at: String,
+}
+
+/// Start of `period`, or None for "all".
+fn failure_period_start(period: &str) -> Result<Option<String>, AppError> {
+    let days = match period {
+        "24h" => 1,
+        "7d" => 7,
+        "30d" => 30,
+        "all" => return Ok(None),
+        other => return Err(AppError::validation(format!("Unknown period '{}': use 24h, 7d, 30d or all", other))),
+    };
+    Ok(Some((Utc::now() - chrono::Duration::days(days)).to_rfc3339()))
+}
+
+/// Failures caught before any publish call was made have no attempt to
+/// take the kind from.
+fn failure_kind(attempt_kind: Option<String>, message: Option<&str>) -> String {
+    attempt_kind.unwrap_or_else(|| match message {
+        Some("No API key found for account") => "auth".to_string(),
+        _ => "validation".to_string(),
+    })
+}
+
+fn failed_posts(conn: &rusqlite::Connection, filter: &FailureFilter) -> Result<Vec<FailedPost>, AppError> {
+    let since = failure_period_start(filter.period.as_deref().unwrap_or("all"))?;
+    // The attempt whose message the post was left with; an earlier failed
+    // attempt may have had a different cause
+    let mut stmt = conn.prepare(
+        "SELECT sp.id, sp.document_id, sp.platform, sp.error_message, sp.updated_at,
+                (SELECT pa.error_kind FROM publish_attempts pa
+                 WHERE pa.scheduled_post_id = sp.id AND pa.status = 'failed' AND pa.error_message = sp.error_message
+                 ORDER BY pa.started_at DESC LIMIT 1)
+         FROM scheduled_posts sp
+         WHERE sp.status = 'failed' AND (?1 IS NULL OR sp.updated_at >= ?1)
+           AND (?2 IS NULL OR sp.platform = ?2) AND (?3 IS NULL OR sp.account_id = ?3)
+         ORDER BY sp.updated_at DESC",
+    )?;
+    let rows = stmt.query_map(rusqlite::params![since, filter.platform, filter.account_id], |row| {
+        let error_message: Option<String> = row.get(3)?;
+        Ok(FailedPost {
+            id: row.get(0)?,
+            document_id: row.get(1)?,
+            platform: row.get(2)?,
+            error_kind: failure_kind(row.get(5)?, error_message.as_deref()),
+            error_message,
+            failed_at: row.get(4)?,
+        })
+    })?;
+    Ok(rows
+        .filter_map(|r| r.ok())
+        .filter(|post| filter.error_kind.as_ref().is_none_or(|kind| *kind == post.error_kind))
+        .filter(|post| filter.post_ids.is_empty() || filter.post_ids.contains(&post.id))
+        .collect())
+}
+
+/// Failed posts in `period` (default "7d"), grouped by platform and error
+/// kind.
+#[tauri::command]
+pub async fn get_failure_summary(app: AppHandle, period: Option<String>) -> Result<FailureSummary, AppError> {
+    let period = period.unwrap_or_else(|| "7d".to_string());
+    let conn = db::get_db(&app)?;
+    let posts = failed_posts(&conn, &FailureFilter { period: Some(period.clone()), ..Default::default() })?;
+
+    // Posts come newest first, so each group's first post is its latest
+    let mut groups: Vec<FailureGroup> = Vec::new();
+    for post in &posts {
+        match groups.iter_mut().find(|g| g.platform == post.platform && g.error_kind == post.error_kind) {
+            Some(group) => {
+                group.count += 1;
+                group.post_ids.push(post.id.clone());
+            }
+            None => groups.push(FailureGroup {
+                platform: post.platform.clone(),
+                error_kind: post.error_kind.clone(),
+                count: 1,
+                post_ids: vec![post.id.clone()],
+                latest_error: post.error_message.clone(),
+                latest_at: post.failed_at.clone(),
+            }),
+        }
+    }
+    groups.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| b.latest_at.cmp(&a.latest_at)));
+
+    Ok(FailureSummary { period, total: posts.len(), groups })
+}
+
+/// Put every failed post matching `filter` back in the queue. They keep
+/// their scheduled time, so the scheduler publishes them on its next pass
+/// unless their platform is paused. Returns the requeued post ids.
+#[tauri::command]
+pub async fn retry_failed_posts(app: AppHandle, filter: FailureFilter) -> Result<Vec<String>, AppError> {
+    let mut conn = db::get_db(&app)?;
+    let posts = failed_posts(&conn, &filter)?;
+    if posts.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let now = Utc::now().to_rfc3339();
+    let tx = conn.transaction()?;
+    for post in &posts {
+        tx.execute(
+            "UPDATE scheduled_posts SET status = 'pending', error_message = NULL, updated_at = ?1
+             WHERE id = ?2 AND status = 'failed'",
+            rusqlite::params![now, post.id],
+        )?;
+        db::log_activity(
+            &tx,
+            "post.retried",
+            "scheduled_post",
+            Some(&post.id),
+            Some(&format!("Requeued for {} ({})", post.platform, post.error_kind)),
+        );
+    }
+    tx.commit()?;
+
+    let mut documents: Vec<&str> = posts.iter().map(|p| p.document_id.as_str()).collect();
+    documents.sort_unstable();
+    documents.dedup();
+    let document_id = if documents.len() == 1 { Some(documents[0]) } else { None };
+    let ids: Vec<String> = posts.iter().map(|p| p.id.clone()).collect();
+    windows::schedule_changed(&app, document_id, ids.clone(), "retried");
+    Ok(ids)
+}
+
 #[tauri::command]
 pub async fn get_calendar_events(
     app: AppHandle,
     year: i32,
     month: u32,
-) -> Result<Vec<CalendarEvent>, String> {
+) -> Result<Vec<CalendarEvent>, AppError> {
+    let conn = db::get_db(&app)?;
+    let (start, end) = month_range(year, month)?;
+    Ok(collect_calendar_events(&conn, &start, &end)?)
+}
+
+/// Month grid data: every event plus per-day counts.
+#[tauri::command]
+pub async fn get_calendar_month_view(
+    app: AppHandle,
+    year: i32,
+    month: u32,
+) -> Result<CalendarView, AppError> {
     let conn = db::get_db(&app)?;
+    let (start, end) = month_range(year, month)?;
+    let events = collect_calendar_events(&conn, &start, &end)?;
+    let buckets = bucket_events(&events, false);
+    Ok(CalendarView {
+        start,
+        end,
+        granularity: "day".to_string(),
+        events,
+        buckets,
+    })
+}
 
-    // Build date range for the month (with padding for calendar display)
+/// Week grid data with hour-granularity buckets. `week_start` is a
+/// "YYYY-MM-DD" date; the view covers the seven days from it.
+#[tauri::command]
+pub async fn get_calendar_week_view(
+    app: AppHandle,
+    week_start: String,
+) -> Result<CalendarView, AppError> {
+    let conn = db::get_db(&app)?;
+    let first = chrono::NaiveDate::parse_from_str(&week_start, "%Y-%m-%d")
+        .map_err(|_| format!("Invalid week start '{}', expected YYYY-MM-DD", week_start))?;
+    let start = format!("{}T00:00:00Z", first.format("%Y-%m-%d"));
+    let end = format!(
+        "{}T00:00:00Z",
+        (first + chrono::Duration::days(7)).format("%Y-%m-%d")
+    );
+    let events = collect_calendar_events(&conn, &start, &end)?;
+    let buckets = bucket_events(&events, true);
+    Ok(CalendarView {
+        start,
+        end,
+        granularity: "hour".to_string(),
+        events,
+        buckets,
+    })
+}
+
+fn month_range(year: i32, month: u32) -> Result<(String, String), AppError> {
+    if !(1..=12).contains(&month) {
+        return Err(AppError::validation(format!("Invalid month: {}", month)));
+    }
     let start = format!("{:04}-{:02}-01T00:00:00Z", year, month);
     let end_month = if month == 12 { 1 } else { month + 1 };
     let end_year = if month == 12 { year + 1 } else { year };
     let end = format!("{:04}-{:02}-01T00:00:00Z", end_year, end_month);
+    Ok((start, end))
+}
 
+/// Count events per day (or per hour). Date-only events such as ideas land
+/// in an all-day bucket keyed by the date in the hourly view.
+fn bucket_events(events: &[CalendarEvent], hourly: bool) -> Vec<CalendarBucket> {
+    let mut buckets: std::collections::BTreeMap<String, CalendarBucket> =
+        std::collections::BTreeMap::new();
+    for event in events {
+        let has_time = event.date.len() > 10;
+        let key = if hourly && has_time {
+            event.date.chars().take(13).collect::<String>()
+        } else {
+            event.date.chars().take(10).collect::<String>()
+        };
+        let bucket = buckets.entry(key.clone()).or_insert_with(|| CalendarBucket {
+            key,
+            all_day: !(hourly && has_time),
+            total: 0,
+            scheduled: 0,
+            published: 0,
+            draft: 0,
+            projected: 0,
+            ideas: 0,
+        });
+        bucket.total += 1;
+        match event.event_type.as_str() {
+            "scheduled" => bucket.scheduled += 1,
+            "published" => bucket.published += 1,
+            "draft" => bucket.draft += 1,
+            "projected" => bucket.projected += 1,
+            "idea" => bucket.ideas += 1,
+            _ => {}
+        }
+    }
+    buckets.into_values().collect()
+}
+
+fn collect_calendar_events(
+    conn: &rusqlite::Connection,
+    start: &str,
+    end: &str,
+) -> Result<Vec<CalendarEvent>, String> {
     let mut events = Vec::new();
 
     // Scheduled posts
@@ -296,5 +1559,104 @@ pub async fn get_calendar_events(
         events.extend(rows.filter_map(|r| r.ok()));
     }
 
+    // Idea backlog items with a target date
+    {
+        let mut stmt = conn
+            .prepare(
+                "SELECT id, title, target_date, status, document_id
+                 FROM ideas
+                 WHERE target_date IS NOT NULL AND target_date >= substr(?1, 1, 10) AND target_date < substr(?2, 1, 10)
+                 AND status NOT IN ('done', 'dropped')
+                 ORDER BY target_date ASC",
+            )
+            .map_err(|e| format!("Query failed: {}", e))?;
+
+        let rows = stmt
+            .query_map(rusqlite::params![start, end], |row| {
+                Ok(CalendarEvent {
+                    id: row.get(0)?,
+                    title: row.get(1)?,
+                    date: row.get(2)?,
+                    event_type: "idea".to_string(),
+                    platform: None,
+                    status: row.get(3)?,
+                    document_id: row.get(4)?,
+                })
+            })
+            .map_err(|e| format!("Query map failed: {}", e))?;
+
+        events.extend(rows.filter_map(|r| r.ok()));
+    }
+
+    events.extend(project_recurring(conn, start, end, &events)?);
+    events.sort_by(|a, b| a.date.cmp(&b.date));
+
     Ok(events)
 }
+
+/// Expand recurring presets into "projected" slots, skipping days where one
+/// of the preset's accounts already has something scheduled.
+fn project_recurring(
+    conn: &rusqlite::Connection,
+    start: &str,
+    end: &str,
+    existing: &[CalendarEvent],
+) -> Result<Vec<CalendarEvent>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, project_id, name, targets_json, default_time, publish_status, created_at, updated_at, recurrence_days
+             FROM publishing_presets WHERE recurrence_days != ''",
+        )
+        .map_err(|e| format!("Query failed: {}", e))?;
+    let presets: Vec<PublishingPreset> = stmt
+        .query_map([], preset_from_row)
+        .map_err(|e| format!("Query map failed: {}", e))?
+        .filter_map(|r| r.ok())
+        .collect();
+    if presets.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let parse_day = |s: &str| chrono::NaiveDate::parse_from_str(&s.chars().take(10).collect::<String>(), "%Y-%m-%d");
+    let (Ok(first), Ok(last)) = (parse_day(start), parse_day(end)) else {
+        return Ok(Vec::new());
+    };
+    let today = Utc::now().date_naive();
+
+    let mut projected = Vec::new();
+    for preset in &presets {
+        let weekdays: Vec<chrono::Weekday> =
+            preset.recurrence_days.iter().filter_map(|d| parse_weekday(d)).collect();
+        let time = preset.default_time.as_deref().unwrap_or("09:00");
+        let platforms: Vec<&str> = preset.targets.iter().map(|t| t.platform.as_str()).collect();
+
+        let mut day = first.max(today);
+        while day < last {
+            if weekdays.contains(&chrono::Datelike::weekday(&day)) {
+                let date = day.format("%Y-%m-%d").to_string();
+                let taken = existing.iter().any(|e| {
+                    e.event_type == "scheduled"
+                        && e.date.starts_with(&date)
+                        && e.platform.as_deref().is_some_and(|p| platforms.contains(&p))
+                });
+                if !taken {
+                    projected.push(CalendarEvent {
+                        id: format!("projection:{}:{}", preset.id, date),
+                        title: preset.name.clone(),
+                        date: format!("{}T{}:00Z", date, time),
+                        event_type: "projected".to_string(),
+                        platform: match platforms.as_slice() {
+                            [only] => Some(only.to_string()),
+                            _ => None,
+                        },
+                        status: "projected".to_string(),
+                        document_id: None,
+                    });
+                }
+            }
+            day += chrono::Duration::days(1);
+        }
+    }
+
+    Ok(projected)
+}
@@ -0,0 +1,224 @@
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use tauri::AppHandle;
+use tauri_plugin_store::StoreExt;
+
+use crate::error::AppError;
+use crate::commands::{linkpage, mediakit, revenue, signup, smtp, storage, transcription, vault, workspaces};
+use crate::{http, listener, query_cache};
+use crate::permissions::{self, Permission, Role};
+
+// ─── Backend settings ───────────────────────────────────────────
+//
+// Settings the Rust side needs to read (recognition modes, fee rules, ...)
+// live in settings.json via tauri-plugin-store. The frontend reads and edits
+// them through the commands below; the webview has no direct store access,
+// so keys with their own commands (access role, workspace, ...) stay behind
+// those commands' checks.
+
+const SETTINGS_STORE: &str = "settings.json";
+
+/// Keys written only through their own commands, which validate the value
+/// and check the role; `set_app_setting` would skip both.
+const DEDICATED_SETTINGS: &[(&str, &str)] = &[
+    (permissions::ACCESS_SETTING, "set_access_role"),
+    (workspaces::WORKSPACE_SETTING, "switch_workspace"),
+    (revenue::FEE_RULES_SETTING, "save_fee_rules"),
+    (storage::RETENTION_SETTING, "save_retention_policy"),
+    (smtp::SMTP_SETTING, "save_smtp_settings"),
+    (listener::LISTENER_SETTING, "save_listener_settings"),
+    (transcription::WHISPER_CPP_SETTING, "save_whisper_cpp_settings"),
+    (vault::VAULT_SETTING, "save_vault_sync_config"),
+    (signup::SIGNUP_SETTING, "save_signup_form_config"),
+    (linkpage::LINK_PAGE_SETTING, "save_link_page_config"),
+    (mediakit::MEDIA_KIT_SETTING, "save_media_kit_settings"),
+];
+
+/// Read a typed setting, returning `None` when unset or of the wrong shape.
+pub fn get_setting<T: DeserializeOwned>(app: &AppHandle, key: &str) -> Option<T> {
+    let store = app.store(SETTINGS_STORE).ok()?;
+    let value = store.get(key)?;
+    serde_json::from_value(value).ok()
+}
+
+pub fn set_setting(app: &AppHandle, key: &str, value: serde_json::Value) -> Result<(), String> {
+    let store = app.store(SETTINGS_STORE).map_err(|e| e.to_string())?;
+    store.set(key, value);
+    store.save().map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_app_settings(
+    app: AppHandle,
+) -> Result<serde_json::Map<String, serde_json::Value>, AppError> {
+    let store = app.store(SETTINGS_STORE).map_err(|e| e.to_string())?;
+    // The passphrase hash stays on the Rust side; get_access_info covers the rest
+    Ok(store.entries().into_iter().filter(|(key, _)| key != permissions::ACCESS_SETTING).collect())
+}
+
+#[tauri::command]
+pub async fn set_app_setting(
+    app: AppHandle,
+    key: String,
+    value: serde_json::Value,
+) -> Result<(), AppError> {
+    if key == http::NETWORK_SETTING {
+        let settings: http::NetworkSettings = serde_json::from_value(value)
+            .map_err(|e| AppError::validation(format!("Invalid network settings: {}", e)))?;
+        return save_network_settings(app, settings).await;
+    }
+    if key == query_cache::CACHE_SETTING {
+        let settings: query_cache::CacheSettings = serde_json::from_value(value)
+            .map_err(|e| AppError::validation(format!("Invalid cache settings: {}", e)))?;
+        return save_cache_settings(app, settings).await;
+    }
+    if let Some((_, command)) = DEDICATED_SETTINGS.iter().find(|(k, _)| *k == key) {
+        return Err(AppError::validation(format!("Use {} to change '{}'", command, key)));
+    }
+    Ok(set_setting(&app, &key, value)?)
+}
+
+// ─── Network ────────────────────────────────────────────────────
+
+#[tauri::command]
+pub async fn get_network_settings() -> Result<http::NetworkSettings, AppError> {
+    Ok(http::current_settings())
+}
+
+/// Validate, persist and activate proxy / CA / timeout settings. Clients
+/// built after this call use them.
+#[tauri::command]
+pub async fn save_network_settings(
+    app: AppHandle,
+    settings: http::NetworkSettings,
+) -> Result<(), AppError> {
+    let value = serde_json::to_value(&settings).map_err(|e| e.to_string())?;
+    http::configure(settings).map_err(AppError::validation)?;
+    set_setting(&app, http::NETWORK_SETTING, value)?;
+    Ok(())
+}
+
+// ─── Query cache ────────────────────────────────────────────────
+
+#[tauri::command]
+pub async fn get_cache_settings() -> Result<query_cache::CacheSettings, AppError> {
+    Ok(query_cache::current_settings())
+}
+
+/// Persist and activate dashboard cache settings. Cached results are
+/// dropped so new TTLs apply straight away.
+#[tauri::command]
+pub async fn save_cache_settings(app: AppHandle, settings: query_cache::CacheSettings) -> Result<(), AppError> {
+    set_setting(&app, query_cache::CACHE_SETTING, serde_json::to_value(&settings)?)?;
+    query_cache::configure(settings);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn clear_query_cache() -> Result<(), AppError> {
+    query_cache::clear();
+    Ok(())
+}
+
+// ─── Local listener ─────────────────────────────────────────────
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ListenerInfo {
+    pub settings: listener::ListenerSettings,
+    pub status: listener::ListenerStatus,
+}
+
+#[tauri::command]
+pub async fn get_listener_info(app: AppHandle) -> Result<ListenerInfo, AppError> {
+    Ok(ListenerInfo { settings: listener::listener_settings(&app), status: listener::status(&app) })
+}
+
+/// Persist listener settings and start, restart or stop it to match.
+/// Owner only, since a non-local bind exposes the app to the network.
+#[tauri::command]
+pub async fn save_listener_settings(
+    app: AppHandle,
+    settings: listener::ListenerSettings,
+) -> Result<ListenerInfo, AppError> {
+    permissions::require(&app, Permission::ManageCredentials)?;
+    if settings.port < 1024 {
+        return Err(AppError::validation("Listener port must be 1024 or above"));
+    }
+    if settings.bind_address.parse::<std::net::IpAddr>().is_err() {
+        return Err(AppError::validation(format!("'{}' isn't an IP address to bind to", settings.bind_address)));
+    }
+    let value = serde_json::to_value(&settings)?;
+    set_setting(&app, listener::LISTENER_SETTING, value)?;
+    if settings.enabled {
+        listener::start(&app).await.map_err(AppError::validation)?;
+    } else {
+        listener::stop();
+    }
+    Ok(ListenerInfo { settings, status: listener::status(&app) })
+}
+
+// ─── Access roles ───────────────────────────────────────────────
+
+#[derive(Debug, Serialize, Clone)]
+pub struct AccessInfo {
+    pub role: Role,
+    pub permissions: Vec<Permission>,
+    /// Whether switching back to owner asks for a passphrase
+    pub passphrase_set: bool,
+}
+
+fn access_info(access: &permissions::AccessSettings) -> AccessInfo {
+    AccessInfo {
+        role: access.role,
+        permissions: permissions::ALL_PERMISSIONS.iter().copied().filter(|p| access.role.allows(*p)).collect(),
+        passphrase_set: access.owner_passphrase_hash.is_some(),
+    }
+}
+
+#[tauri::command]
+pub async fn get_access_info(app: AppHandle) -> Result<AccessInfo, AppError> {
+    Ok(access_info(&permissions::load(&app)))
+}
+
+/// Switch the active role. Moving down is always allowed; becoming owner
+/// needs the owner passphrase when one is set.
+#[tauri::command]
+pub async fn set_access_role(
+    app: AppHandle,
+    role: Role,
+    passphrase: Option<String>,
+) -> Result<AccessInfo, AppError> {
+    let mut access = permissions::load(&app);
+    permissions::switch_role(&mut access, role, passphrase.as_deref())?;
+    permissions::save(&app, &access)?;
+    Ok(access_info(&access))
+}
+
+/// Set, change or (with `passphrase: None`) clear the owner passphrase.
+/// Only the owner can, and must give the current one if set.
+#[tauri::command]
+pub async fn set_owner_passphrase(
+    app: AppHandle,
+    current: Option<String>,
+    passphrase: Option<String>,
+) -> Result<AccessInfo, AppError> {
+    let mut access = permissions::load(&app);
+    if access.role != Role::Owner {
+        return Err(AppError::auth("Only the owner can change the owner passphrase"));
+    }
+    if !permissions::check_passphrase(&access, current.as_deref()) {
+        return Err(AppError::auth("Incorrect owner passphrase"));
+    }
+    match passphrase.filter(|p| !p.is_empty()) {
+        Some(p) => {
+            if p.chars().count() < 4 {
+                return Err(AppError::validation("Passphrase must be at least 4 characters"));
+            }
+            permissions::set_passphrase(&mut access, Some(&p))?;
+        }
+        None => permissions::set_passphrase(&mut access, None)?,
+    }
+    permissions::save(&app, &access)?;
+    Ok(access_info(&access))
+}
@@ -0,0 +1,369 @@
+use chrono::Utc;
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+use tauri::AppHandle;
+
+use crate::commands::export::{self, BrandKit};
+use crate::commands::platform::Subscriber;
+use crate::commands::{audience, milestones, settings, smtp, workspaces};
+use crate::db;
+use crate::error::AppError;
+use crate::listener::{self, Request, Response};
+
+// ─── Signup form ────────────────────────────────────────────────
+//
+// A double opt-in signup page. The form can be served by the local
+// listener at /signup or exported as a static page for any host; either
+// way it posts to the listener, which mails a confirmation link. The link
+// opens a page whose button posts to /confirm, so mail scanners that
+// prefetch links don't confirm anything; only that post adds the address
+// to the unified subscriber table, tagged with the form's source tag.
+// Each client address gets a few signups an hour, and an address with a
+// recent unconfirmed signup isn't mailed again.
+
+pub(crate) const SIGNUP_SETTING: &str = "signup_form";
+/// Confirmation links older than this are refused
+const CONFIRM_TTL_DAYS: i64 = 7;
+/// Hidden field real visitors leave empty
+const HONEYPOT_FIELD: &str = "website";
+/// Signup posts accepted from one client address per hour
+const SIGNUPS_PER_CLIENT_PER_HOUR: usize = 5;
+/// An address isn't mailed again while an earlier link is this fresh
+const RESEND_AFTER_MINUTES: i64 = 60;
+
+static RECENT_SIGNUPS: LazyLock<Mutex<HashMap<IpAddr, Vec<Instant>>>> = LazyLock::new(|| Mutex::new(HashMap::new()));
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct SignupFormConfig {
+    pub title: String,
+    pub description: Option<String>,
+    pub button_label: String,
+    /// Tag given to confirmed subscribers, so signups from this form can be
+    /// told apart
+    pub source_tag: String,
+    /// Project whose brand kit styles the page
+    pub project_id: Option<String>,
+    pub confirm_subject: String,
+}
+
+impl Default for SignupFormConfig {
+    fn default() -> Self {
+        SignupFormConfig {
+            title: "Subscribe".to_string(),
+            description: None,
+            button_label: "Subscribe".to_string(),
+            source_tag: "signup-form".to_string(),
+            project_id: None,
+            confirm_subject: "Confirm your subscription".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct PendingSignup {
+    pub email: String,
+    pub name: Option<String>,
+    pub source_tag: String,
+    pub created_at: String,
+    pub confirmed_at: Option<String>,
+}
+
+fn form_config(app: &AppHandle) -> SignupFormConfig {
+    settings::get_setting(app, SIGNUP_SETTING).unwrap_or_default()
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn brand(conn: &Connection, config: &SignupFormConfig) -> BrandKit {
+    config
+        .project_id
+        .as_deref()
+        .and_then(|id| export::project_defaults(conn, id))
+        .map(|d| d.brand)
+        .unwrap_or_default()
+}
+
+fn form_page(config: &SignupFormConfig, brand: &BrandKit, action: &str) -> String {
+    let description = config
+        .description
+        .as_deref()
+        .map(|d| format!("<p>{}</p>", escape(d)))
+        .unwrap_or_default();
+    let body = format!(
+        "<h1>{title}</h1>{description}\
+         <form method=\"post\" action=\"{action}\">\
+         <input type=\"email\" name=\"email\" placeholder=\"you@example.com\" required>\
+         <input type=\"text\" name=\"name\" placeholder=\"Name (optional)\">\
+         <input type=\"text\" name=\"{honeypot}\" tabindex=\"-1\" autocomplete=\"off\" style=\"position:absolute;left:-9999px\" aria-hidden=\"true\">\
+         <button type=\"submit\">{button}</button></form>\
+         <p class=\"muted\">We'll email you a link to confirm.</p>",
+        title = escape(&config.title),
+        action = escape(action),
+        honeypot = HONEYPOT_FIELD,
+        button = escape(&config.button_label),
+    );
+    export::standalone_page(&config.title, brand, &body)
+}
+
+fn confirm_page(config: &SignupFormConfig, brand: &BrandKit, token: &str) -> Response {
+    let body = format!(
+        "<h1>Confirm your subscription</h1>\
+         <form method=\"post\" action=\"/confirm\">\
+         <input type=\"hidden\" name=\"token\" value=\"{token}\">\
+         <button type=\"submit\">Confirm subscription</button></form>",
+        token = escape(token),
+    );
+    Response::html(200, export::standalone_page(&config.title, brand, &body))
+}
+
+fn message_page(config: &SignupFormConfig, brand: &BrandKit, status: u16, heading: &str, text: &str) -> Response {
+    let body = format!("<h1>{}</h1><p>{}</p>", escape(heading), escape(text));
+    Response::html(status, export::standalone_page(&config.title, brand, &body))
+}
+
+/// Count a signup post against the client's hourly allowance; false once
+/// it's used up.
+fn allow_client(peer: Option<IpAddr>) -> bool {
+    let Some(ip) = peer else {
+        return true;
+    };
+    let Ok(mut recent) = RECENT_SIGNUPS.lock() else {
+        return true;
+    };
+    let now = Instant::now();
+    let window = Duration::from_secs(60 * 60);
+    recent.retain(|_, times| {
+        times.retain(|t| now.duration_since(*t) < window);
+        !times.is_empty()
+    });
+    let times = recent.entry(ip).or_default();
+    if times.len() >= SIGNUPS_PER_CLIENT_PER_HOUR {
+        return false;
+    }
+    times.push(now);
+    true
+}
+
+/// Record the request and mail the confirmation link, unless the address
+/// already has a recent unconfirmed signup. Signups join the
+/// workspace of the form's project, or the active one without a project.
+async fn request_signup(app: &AppHandle, config: &SignupFormConfig, request: &Request) -> Result<(), AppError> {
+    let email = request.form.get("email").map(|e| e.trim().to_lowercase()).unwrap_or_default();
+    if !email.contains('@') || email.len() > 254 {
+        return Err(AppError::validation("Please enter a valid email address"));
+    }
+    let name = request.form.get("name").map(|n| n.trim().to_string()).filter(|n| !n.is_empty());
+    let token = uuid::Uuid::new_v4().simple().to_string();
+    let workspace_id = workspaces::current(app);
+    {
+        let conn = db::get_db(app)?;
+        let since = (Utc::now() - chrono::Duration::minutes(RESEND_AFTER_MINUTES)).to_rfc3339();
+        let recent: bool = conn.query_row(
+            "SELECT EXISTS(SELECT 1 FROM pending_signups WHERE email = ?1 AND confirmed_at IS NULL AND created_at > ?2)",
+            rusqlite::params![email, since],
+            |row| row.get(0),
+        )?;
+        if recent {
+            // Same answer as a fresh signup, so the form can't be used to
+            // probe for addresses or to flood one inbox
+            return Ok(());
+        }
+        conn.execute(
+            "INSERT INTO pending_signups (token, email, name, source_tag, workspace_id, created_at)
+             VALUES (?1, ?2, ?3, ?4, COALESCE((SELECT workspace_id FROM projects WHERE id = ?6), ?7), ?5)",
+            rusqlite::params![token, email, name, config.source_tag, Utc::now().to_rfc3339(), config.project_id, workspace_id],
+        )?;
+    }
+    let link = format!("{}/confirm?token={}", listener::base_url(app), token);
+    let html = format!(
+        "<p>Thanks for signing up{}! Confirm your subscription to {}:</p><p><a href=\"{}\">Confirm subscription</a></p><p>If you didn't ask for this, ignore this email.</p>",
+        name.as_deref().map(|n| format!(", {}", escape(n))).unwrap_or_default(),
+        escape(&config.title),
+        link
+    );
+    smtp::send_transactional(app, &email, name.as_deref(), &config.confirm_subject, &html).await
+}
+
+/// Add the confirmed address to the audience. Returns false when the token
+/// is unknown, already used or expired.
+fn confirm(conn: &Connection, token: &str) -> Result<bool, AppError> {
+    let pending: Option<(String, Option<String>, String, String, String)> = conn
+        .query_row(
+            "SELECT email, name, source_tag, created_at, workspace_id FROM pending_signups
+             WHERE token = ?1 AND confirmed_at IS NULL",
+            rusqlite::params![token],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?)),
+        )
+        .ok();
+    let Some((email, name, source_tag, created_at, workspace_id)) = pending else {
+        return Ok(false);
+    };
+    let expired = chrono::DateTime::parse_from_rfc3339(&created_at)
+        .map(|t| Utc::now().signed_duration_since(t).num_days() >= CONFIRM_TTL_DAYS)
+        .unwrap_or(true);
+    if expired {
+        return Ok(false);
+    }
+
+    let now = Utc::now().to_rfc3339();
+    let subscriber = Subscriber {
+        id: email.clone(),
+        email: email.clone(),
+        status: "active".to_string(),
+        created_at: now.clone(),
+        platform: "signup".to_string(),
+        country: None,
+    };
+    audience::upsert_platform_subscribers(conn, &workspace_id, "signup", &source_tag, &[subscriber]);
+    let id: String = conn.query_row(
+        "SELECT id FROM subscribers WHERE workspace_id = ?1 AND email = ?2",
+        rusqlite::params![workspace_id, email],
+        |row| row.get(0),
+    )?;
+    if let Some(name) = &name {
+        conn.execute(
+            "UPDATE subscribers SET name = ?1 WHERE id = ?2 AND (name IS NULL OR name = '')",
+            rusqlite::params![name, id],
+        )?;
+    }
+    conn.execute(
+        "INSERT OR IGNORE INTO subscriber_tags (subscriber_id, tag) VALUES (?1, ?2)",
+        rusqlite::params![id, source_tag],
+    )?;
+    // Opting in again lifts an earlier unsubscribe, not a hard bounce
+    conn.execute(
+        "DELETE FROM suppression_list WHERE email = ?1 AND reason = 'Unsubscribed'",
+        rusqlite::params![email],
+    )?;
+    conn.execute(
+        "UPDATE pending_signups SET confirmed_at = ?1 WHERE token = ?2",
+        rusqlite::params![now, token],
+    )?;
+    db::log_activity(conn, "audience.signup_confirmed", "subscribers", Some(&id), Some(&source_tag));
+    Ok(true)
+}
+
+/// Listener routes: GET/POST /signup and GET/POST /confirm.
+pub(crate) async fn handle(app: &AppHandle, request: &Request) -> Option<Response> {
+    let config = form_config(app);
+    let brand = db::get_db(app).map(|conn| brand(&conn, &config)).unwrap_or_default();
+    let response = match (request.method.as_str(), request.path.as_str()) {
+        ("GET", "/signup") => Response::html(200, form_page(&config, &brand, "/signup")),
+        ("POST", "/signup") => {
+            if request.form.get(HONEYPOT_FIELD).is_some_and(|v| !v.is_empty()) {
+                // Bots get the same answer as people
+                return Some(message_page(&config, &brand, 200, "Check your inbox", "We sent you a confirmation link."));
+            }
+            if !allow_client(request.peer) {
+                return Some(message_page(
+                    &config,
+                    &brand,
+                    429,
+                    "Too many signups",
+                    "Please wait a while before trying again.",
+                ));
+            }
+            match request_signup(app, &config, request).await {
+                Ok(()) => message_page(&config, &brand, 200, "Check your inbox", "We sent you a confirmation link."),
+                Err(e) => message_page(&config, &brand, 400, "Couldn't sign you up", &e.message),
+            }
+        }
+        ("GET", "/confirm") => {
+            let token = request.query.get("token").map(String::as_str).unwrap_or("");
+            confirm_page(&config, &brand, token)
+        }
+        ("POST", "/confirm") => {
+            let token = request.form.get("token").map(String::as_str).unwrap_or("");
+            let confirmed = (|| -> Result<bool, AppError> {
+                let conn = db::get_db(app)?;
+                let confirmed = confirm(&conn, token)?;
+                if confirmed {
+                    milestones::evaluate(app, &conn);
+                }
+                Ok(confirmed)
+            })();
+            match confirmed {
+                Ok(true) => message_page(&config, &brand, 200, "You're subscribed", "Thanks for confirming."),
+                Ok(false) => message_page(
+                    &config,
+                    &brand,
+                    410,
+                    "Link expired",
+                    "This confirmation link has expired or was already used. Please sign up again.",
+                ),
+                Err(e) => message_page(&config, &brand, 400, "Something went wrong", &e.message),
+            }
+        }
+        _ => return None,
+    };
+    Some(response)
+}
+
+#[tauri::command]
+pub async fn get_signup_form_config(app: AppHandle) -> Result<SignupFormConfig, AppError> {
+    Ok(form_config(&app))
+}
+
+#[tauri::command]
+pub async fn save_signup_form_config(app: AppHandle, config: SignupFormConfig) -> Result<(), AppError> {
+    if config.source_tag.trim().is_empty() {
+        return Err(AppError::validation("A source tag is required"));
+    }
+    if config.title.trim().is_empty() {
+        return Err(AppError::validation("The form needs a title"));
+    }
+    settings::set_setting(&app, SIGNUP_SETTING, serde_json::to_value(&config)?)?;
+    Ok(())
+}
+
+/// The form as a standalone page posting to the listener's public URL, for
+/// hosting anywhere. `config` previews unsaved changes.
+#[tauri::command]
+pub async fn generate_signup_form(app: AppHandle, config: Option<SignupFormConfig>) -> Result<String, AppError> {
+    let config = config.unwrap_or_else(|| form_config(&app));
+    let conn = db::get_db(&app)?;
+    let action = format!("{}/signup", listener::base_url(&app));
+    Ok(form_page(&config, &brand(&conn, &config), &action))
+}
+
+/// Write the generated form to `folder`/index.html; returns the file path.
+#[tauri::command]
+pub async fn export_signup_form(app: AppHandle, folder: String) -> Result<String, AppError> {
+    let html = generate_signup_form(app, None).await?;
+    let dir = std::path::Path::new(&folder);
+    std::fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {}", folder, e))?;
+    let path = dir.join("index.html");
+    std::fs::write(&path, html).map_err(|e| format!("Failed to write signup page: {}", e))?;
+    Ok(path.to_string_lossy().to_string())
+}
+
+/// The active workspace's signups, newest first.
+#[tauri::command]
+pub async fn list_pending_signups(app: AppHandle, include_confirmed: Option<bool>) -> Result<Vec<PendingSignup>, AppError> {
+    let workspace_id = workspaces::current(&app);
+    let conn = db::get_db(&app)?;
+    let mut stmt = conn.prepare(
+        "SELECT email, name, source_tag, created_at, confirmed_at FROM pending_signups
+         WHERE workspace_id = ?2 AND (?1 OR confirmed_at IS NULL) ORDER BY created_at DESC LIMIT 500",
+    )?;
+    let rows = stmt.query_map(rusqlite::params![include_confirmed.unwrap_or(false), workspace_id], |row| {
+        Ok(PendingSignup {
+            email: row.get(0)?,
+            name: row.get(1)?,
+            source_tag: row.get(2)?,
+            created_at: row.get(3)?,
+            confirmed_at: row.get(4)?,
+        })
+    })?;
+    Ok(rows.filter_map(|r| r.ok()).collect())
+}
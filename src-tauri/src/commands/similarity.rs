@@ -0,0 +1,352 @@
+use serde::Serialize;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+use tauri::AppHandle;
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::commands::export::html_to_plain_text;
+use crate::db;
+use crate::error::AppError;
+
+// ─── Recycled-content detection ─────────────────────────────────
+//
+// Paragraphs are reduced to word shingles and MinHash signatures, then
+// compared against every paragraph of the workspace's published
+// documents. The share of matching signature slots estimates the Jaccard
+// similarity of the two shingle sets, so reworded-but-recycled passages
+// still match.
+
+const SHINGLE_WORDS: usize = 5;
+const SIGNATURE_SIZE: u64 = 64;
+/// Shorter paragraphs (sign-offs, headings) match too easily to be useful
+const MIN_PARAGRAPH_WORDS: usize = 12;
+const DEFAULT_THRESHOLD: f64 = 0.7;
+const EXCERPT_CHARS: usize = 160;
+
+#[derive(Debug, Serialize, Clone)]
+pub struct SimilarPassage {
+    /// Paragraph position in the checked document, from 0
+    pub paragraph_index: usize,
+    pub excerpt: String,
+    pub match_document_id: String,
+    pub match_title: String,
+    pub match_published_at: Option<String>,
+    pub match_paragraph_index: usize,
+    pub match_excerpt: String,
+    /// Estimated Jaccard similarity of the two paragraphs, 0-1
+    pub similarity: f64,
+}
+
+struct Paragraph {
+    index: usize,
+    text: String,
+    signature: Vec<u64>,
+}
+
+fn excerpt(text: &str) -> String {
+    if text.chars().count() <= EXCERPT_CHARS {
+        return text.to_string();
+    }
+    let cut: String = text.chars().take(EXCERPT_CHARS).collect();
+    format!("{}…", cut.trim_end())
+}
+
+fn shingles(words: &[String]) -> HashSet<u64> {
+    words
+        .windows(SHINGLE_WORDS.min(words.len()))
+        .map(|window| {
+            let mut h = DefaultHasher::new();
+            window.hash(&mut h);
+            h.finish()
+        })
+        .collect()
+}
+
+/// One minimum per seeded hash function.
+fn signature(shingles: &HashSet<u64>) -> Vec<u64> {
+    (0..SIGNATURE_SIZE)
+        .map(|seed| {
+            shingles
+                .iter()
+                .map(|s| {
+                    let mut h = DefaultHasher::new();
+                    (seed, s).hash(&mut h);
+                    h.finish()
+                })
+                .min()
+                .unwrap_or(u64::MAX)
+        })
+        .collect()
+}
+
+fn paragraphs(html: &str) -> Vec<Paragraph> {
+    html_to_plain_text(html)
+        .split("\n\n")
+        .enumerate()
+        .filter_map(|(index, text)| {
+            let words: Vec<String> = text.unicode_words().map(str::to_lowercase).collect();
+            if words.len() < MIN_PARAGRAPH_WORDS {
+                return None;
+            }
+            Some(Paragraph {
+                index,
+                text: text.trim().to_string(),
+                signature: signature(&shingles(&words)),
+            })
+        })
+        .collect()
+}
+
+fn similarity(a: &[u64], b: &[u64]) -> f64 {
+    let same = a.iter().zip(b).filter(|(x, y)| x == y).count();
+    same as f64 / SIGNATURE_SIZE as f64
+}
+
+/// Paragraphs of `document_id` that closely match a paragraph of another
+/// published document, best matches first. `threshold` is the minimum
+/// estimated similarity (0-1, default 0.7).
+#[tauri::command]
+pub async fn find_similar_passages(
+    app: AppHandle,
+    document_id: String,
+    threshold: Option<f64>,
+) -> Result<Vec<SimilarPassage>, AppError> {
+    let threshold = threshold.unwrap_or(DEFAULT_THRESHOLD);
+    if !(0.0..=1.0).contains(&threshold) {
+        return Err(AppError::validation("threshold must be between 0 and 1"));
+    }
+
+    let (html, archive) = {
+        let conn = db::get_db(&app)?;
+        let html: String = conn
+            .query_row(
+                "SELECT html_content FROM documents WHERE id = ?1",
+                rusqlite::params![document_id],
+                |row| row.get(0),
+            )
+            .map_err(|_| AppError::not_found(format!("Document '{}' not found", document_id)))?;
+        let mut stmt = conn.prepare(
+            "SELECT id, title, published_at, html_content FROM documents
+             WHERE id != ?1 AND status = 'published'
+               AND workspace_id = (SELECT workspace_id FROM documents WHERE id = ?1)",
+        )?;
+        let archive: Vec<(String, String, Option<String>, String)> = stmt
+            .query_map(rusqlite::params![document_id], |row| {
+                Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))
+            })?
+            .filter_map(|r| r.ok())
+            .collect();
+        (html, archive)
+    };
+
+    // Shingling the archive is CPU-bound; keep it off the async runtime
+    tokio::task::spawn_blocking(move || {
+        let checked = paragraphs(&html);
+        let mut matches = Vec::new();
+        if checked.is_empty() {
+            return matches;
+        }
+        for (id, title, published_at, other_html) in archive {
+            let others = paragraphs(&other_html);
+            for para in &checked {
+                let best = others
+                    .iter()
+                    .map(|o| (o, similarity(&para.signature, &o.signature)))
+                    .filter(|(_, s)| *s >= threshold)
+                    .max_by(|a, b| a.1.total_cmp(&b.1));
+                if let Some((other, score)) = best {
+                    matches.push(SimilarPassage {
+                        paragraph_index: para.index,
+                        excerpt: excerpt(&para.text),
+                        match_document_id: id.clone(),
+                        match_title: title.clone(),
+                        match_published_at: published_at.clone(),
+                        match_paragraph_index: other.index,
+                        match_excerpt: excerpt(&other.text),
+                        similarity: (score * 100.0).round() / 100.0,
+                    });
+                }
+            }
+        }
+        matches.sort_by(|a, b| b.similarity.total_cmp(&a.similarity).then(a.paragraph_index.cmp(&b.paragraph_index)));
+        matches
+    })
+    .await
+    .map_err(|e| AppError::from(format!("Similarity check failed: {}", e)))
+}
+
+// ─── Related documents ──────────────────────────────────────────
+//
+// Published issues are ranked by TF-IDF cosine similarity of their terms
+// against the document, so shared specific vocabulary counts for more
+// than shared common words.
+
+const DEFAULT_RELATED: usize = 5;
+const MAX_RELATED: usize = 10;
+const SHARED_TERMS: usize = 5;
+const MIN_TERM_CHARS: usize = 3;
+const STOP_WORDS: &[&str] = &[
+    "the", "and", "for", "are", "but", "not", "you", "all", "any", "can", "had", "her", "was", "one", "our",
+    "out", "has", "its", "his", "how", "man", "new", "now", "see", "two", "who", "did", "get", "let", "say",
+    "she", "too", "use", "that", "with", "have", "this", "will", "your", "from", "they", "been", "were",
+    "what", "when", "them", "than", "then", "some", "into", "just", "like", "more", "also", "about",
+    "there", "their", "which", "would", "could", "should", "these", "those", "other", "because", "very",
+];
+
+#[derive(Debug, Serialize, Clone)]
+pub struct RelatedDocument {
+    pub document_id: String,
+    pub title: String,
+    pub published_at: Option<String>,
+    /// Most recent published URL, when the post went out through a platform
+    pub url: Option<String>,
+    /// Cosine similarity, 0-1
+    pub score: f64,
+    /// Terms contributing most to the score
+    pub shared_terms: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct RelatedSuggestions {
+    pub documents: Vec<RelatedDocument>,
+    /// "Further reading" list of the suggestions that have a URL; empty
+    /// when none do
+    pub html: String,
+}
+
+/// (id, title, published_at, html_content, url)
+type ArchivedIssue = (String, String, Option<String>, String, Option<String>);
+
+fn term_counts(html: &str) -> HashMap<String, f64> {
+    let mut counts = HashMap::new();
+    for word in html_to_plain_text(html).unicode_words() {
+        let word = word.to_lowercase();
+        if word.chars().count() < MIN_TERM_CHARS || word.chars().all(char::is_numeric) || STOP_WORDS.contains(&word.as_str()) {
+            continue;
+        }
+        *counts.entry(word).or_insert(0.0) += 1.0;
+    }
+    counts
+}
+
+fn html_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn further_reading_html(related: &[RelatedDocument]) -> String {
+    let items: Vec<String> = related
+        .iter()
+        .filter_map(|r| {
+            r.url
+                .as_ref()
+                .map(|url| format!("<li><a href=\"{}\">{}</a></li>", html_escape(url), html_escape(&r.title)))
+        })
+        .collect();
+    if items.is_empty() {
+        return String::new();
+    }
+    format!("<h3>Further reading</h3><ul>{}</ul>", items.join(""))
+}
+
+/// Published issues most related to `document_id` by term overlap, best
+/// first, with a ready-to-insert "Further reading" block. `limit` defaults
+/// to 5.
+#[tauri::command]
+pub async fn suggest_related_documents(
+    app: AppHandle,
+    document_id: String,
+    limit: Option<usize>,
+) -> Result<RelatedSuggestions, AppError> {
+    let limit = limit.unwrap_or(DEFAULT_RELATED).clamp(1, MAX_RELATED);
+
+    let (html, archive) = {
+        let conn = db::get_db(&app)?;
+        let html: String = conn
+            .query_row(
+                "SELECT html_content FROM documents WHERE id = ?1",
+                rusqlite::params![document_id],
+                |row| row.get(0),
+            )
+            .map_err(|_| AppError::not_found(format!("Document '{}' not found", document_id)))?;
+        let mut stmt = conn.prepare(
+            "SELECT d.id, d.title, d.published_at, d.html_content,
+                    COALESCE(
+                        (SELECT sp.published_url FROM scheduled_posts sp
+                         WHERE sp.document_id = d.id AND sp.published_url IS NOT NULL
+                         ORDER BY sp.updated_at DESC LIMIT 1),
+                        (SELECT pp.url FROM platform_posts pp
+                         WHERE pp.document_id = d.id AND pp.url IS NOT NULL
+                         ORDER BY pp.published_at DESC LIMIT 1)
+                    )
+             FROM documents d WHERE d.id != ?1 AND d.status = 'published'
+               AND d.workspace_id = (SELECT workspace_id FROM documents WHERE id = ?1)",
+        )?;
+        let archive: Vec<ArchivedIssue> = stmt
+            .query_map(rusqlite::params![document_id], |row| {
+                Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?))
+            })?
+            .filter_map(|r| r.ok())
+            .collect();
+        (html, archive)
+    };
+
+    tokio::task::spawn_blocking(move || {
+        let query = term_counts(&html);
+        let docs: Vec<HashMap<String, f64>> = archive.iter().map(|(_, _, _, h, _)| term_counts(h)).collect();
+
+        // Document frequency over the archive plus the document itself
+        let mut df: HashMap<&str, f64> = HashMap::new();
+        for terms in docs.iter().chain(std::iter::once(&query)) {
+            for term in terms.keys() {
+                *df.entry(term.as_str()).or_insert(0.0) += 1.0;
+            }
+        }
+        let n = (docs.len() + 1) as f64;
+        let idf = |term: &str| (n / df.get(term).copied().unwrap_or(1.0)).ln() + 1.0;
+        let norm = |terms: &HashMap<String, f64>| {
+            terms.iter().map(|(t, tf)| (tf * idf(t)).powi(2)).sum::<f64>().sqrt()
+        };
+
+        let query_norm = norm(&query);
+        if query_norm == 0.0 {
+            return Vec::new();
+        }
+        let mut related: Vec<RelatedDocument> = archive
+            .into_iter()
+            .zip(&docs)
+            .filter_map(|((id, title, published_at, _, url), terms)| {
+                let doc_norm = norm(terms);
+                if doc_norm == 0.0 {
+                    return None;
+                }
+                let mut contributions: Vec<(&String, f64)> = query
+                    .iter()
+                    .filter_map(|(term, tf)| terms.get(term).map(|other| (term, tf * other * idf(term).powi(2))))
+                    .collect();
+                let score = contributions.iter().map(|(_, c)| c).sum::<f64>() / (query_norm * doc_norm);
+                if score <= 0.0 {
+                    return None;
+                }
+                contributions.sort_by(|a, b| b.1.total_cmp(&a.1));
+                Some(RelatedDocument {
+                    document_id: id,
+                    title,
+                    published_at,
+                    url,
+                    score: (score * 1000.0).round() / 1000.0,
+                    shared_terms: contributions.into_iter().take(SHARED_TERMS).map(|(t, _)| t.clone()).collect(),
+                })
+            })
+            .collect();
+        related.sort_by(|a, b| b.score.total_cmp(&a.score));
+        related.truncate(limit);
+        related
+    })
+    .await
+    .map(|documents| RelatedSuggestions { html: further_reading_html(&documents), documents })
+    .map_err(|e| AppError::from(format!("Related documents lookup failed: {}", e)))
+}
@@ -0,0 +1,367 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+
+use crate::commands::export::{self, BrandKit};
+use crate::commands::export_jobs::{self, JobContext};
+use crate::commands::{audit, images};
+use crate::db;
+use crate::error::AppError;
+
+// ─── Static archive site ────────────────────────────────────────
+//
+// A project's published issues written out as a plain website: an index,
+// one page per post, an RSS feed and a sitemap, styled from the brand
+// kit. Nothing in it depends on a platform or on the app, so it can be
+// hosted anywhere that serves files. Local images are copied into
+// "assets/"; remote ones are linked as they are.
+
+/// Built-in looks; the brand kit's accent colour and font override them.
+const THEMES: &[&str] = &["classic", "dark", "editorial"];
+const EXCERPT_CHARS: usize = 220;
+
+#[derive(Debug, Serialize, Clone)]
+pub struct StaticSite {
+    pub out_dir: String,
+    pub posts: usize,
+    /// Local images copied into assets/
+    pub images: usize,
+}
+
+/// A published document ready to render.
+struct SitePost {
+    slug: String,
+    title: String,
+    html: String,
+    published_at: DateTime<Utc>,
+    updated_at: String,
+}
+
+struct SiteMeta {
+    title: String,
+    description: String,
+    theme: &'static str,
+    brand: BrandKit,
+    /// Absolute site URL without a trailing slash; links stay relative
+    /// without one
+    base_url: Option<String>,
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// URL-safe file stem for a post, unique among `taken`.
+fn slug(title: &str, taken: &mut HashSet<String>) -> String {
+    let mut base = String::new();
+    for c in title.trim().to_lowercase().chars() {
+        if c.is_ascii_alphanumeric() {
+            base.push(c);
+        } else if !base.is_empty() && !base.ends_with('-') {
+            base.push('-');
+        }
+    }
+    let base: String = base.trim_end_matches('-').chars().take(60).collect();
+    let base = if base.is_empty() { "post".to_string() } else { base };
+    let mut slug = base.clone();
+    let mut n = 2;
+    while !taken.insert(slug.clone()) {
+        slug = format!("{}-{}", base, n);
+        n += 1;
+    }
+    slug
+}
+
+fn excerpt(html: &str) -> String {
+    let text = export::html_to_plain_text(html);
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if text.chars().count() <= EXCERPT_CHARS {
+        return text;
+    }
+    let cut: String = text.chars().take(EXCERPT_CHARS).collect();
+    format!("{}…", cut.trim_end())
+}
+
+/// `path` as an absolute URL when the site has one.
+fn site_url(meta: &SiteMeta, path: &str) -> String {
+    match &meta.base_url {
+        Some(base) => format!("{}/{}", base, path),
+        None => path.to_string(),
+    }
+}
+
+fn stylesheet(meta: &SiteMeta) -> String {
+    let (background, text, muted, rule, font) = match meta.theme {
+        "dark" => ("#18181b", "#e4e4e7", "#a1a1aa", "#3f3f46", "system-ui, sans-serif"),
+        "editorial" => ("#fdfcf8", "#1c1917", "#78716c", "#e7e5e4", "Georgia, \"Times New Roman\", serif"),
+        _ => ("#ffffff", "#1a1a1a", "#71717a", "#e4e4e7", "system-ui, sans-serif"),
+    };
+    let accent = meta
+        .brand
+        .accent_color
+        .as_deref()
+        .filter(|c| c.chars().all(|ch| ch.is_ascii_alphanumeric() || ch == '#'))
+        .unwrap_or(if meta.theme == "dark" { "#a78bfa" } else { "#7c3aed" });
+    let font = meta
+        .brand
+        .font_family
+        .as_deref()
+        .map(|f| f.replace(['"', '<', '>', ';', '{', '}'], ""))
+        .filter(|f| !f.trim().is_empty())
+        .map(|f| format!("\"{}\", {}", f, font))
+        .unwrap_or_else(|| font.to_string());
+    format!(
+        "body{{background:{background};color:{text};font-family:{font};line-height:1.7;margin:0}}\
+         main,header,footer{{max-width:680px;margin:0 auto;padding:0 1.25rem}}\
+         header{{padding-top:2.5rem;padding-bottom:1rem;border-bottom:1px solid {rule}}}\
+         header a{{color:inherit;text-decoration:none}}.logo{{max-height:56px;display:block;margin-bottom:.75rem}}\
+         a{{color:{accent}}}img{{max-width:100%;height:auto}}\
+         .muted,time{{color:{muted};font-size:.875rem}}\
+         .post-list{{list-style:none;padding:0}}.post-list li{{padding:1.25rem 0;border-bottom:1px solid {rule}}}\
+         .post-list h2{{margin:.25rem 0;font-size:1.3rem}}.post-list p{{margin:.25rem 0}}\
+         article h1{{line-height:1.25;margin-bottom:.25rem}}\
+         blockquote{{border-left:3px solid {accent};margin-left:0;padding-left:1rem;color:{muted}}}\
+         pre{{overflow-x:auto;padding:1rem;border:1px solid {rule}}}\
+         table{{border-collapse:collapse}}td,th{{border:1px solid {rule};padding:.4rem .6rem}}\
+         footer{{padding-top:1.5rem;padding-bottom:3rem}}"
+    )
+}
+
+/// One HTML page. `root` is the relative path back to the site root.
+fn page(meta: &SiteMeta, root: &str, title: &str, description: &str, body: &str) -> String {
+    let logo = meta
+        .brand
+        .logo_url
+        .as_deref()
+        .map(|url| format!("<img class=\"logo\" src=\"{}\" alt=\"\">", escape(url)))
+        .unwrap_or_default();
+    format!(
+        "<!DOCTYPE html><html lang=\"en\"><head><meta charset=\"utf-8\">\
+         <meta name=\"viewport\" content=\"width=device-width,initial-scale=1\">\
+         <title>{title}</title><meta name=\"description\" content=\"{description}\">\
+         <link rel=\"stylesheet\" href=\"{root}style.css\">\
+         <link rel=\"alternate\" type=\"application/rss+xml\" title=\"{site}\" href=\"{root}feed.xml\">\
+         </head><body><header><a href=\"{root}index.html\">{logo}<strong>{site}</strong></a></header>\
+         <main>{body}</main><footer class=\"muted\"><a href=\"{root}feed.xml\">RSS</a></footer></body></html>",
+        title = escape(title),
+        description = escape(description),
+        site = escape(&meta.title),
+    )
+}
+
+fn index_page(meta: &SiteMeta, posts: &[SitePost]) -> String {
+    let mut body = String::new();
+    if !meta.description.trim().is_empty() {
+        body.push_str(&format!("<p class=\"muted\">{}</p>", escape(&meta.description)));
+    }
+    body.push_str("<ul class=\"post-list\">");
+    for post in posts {
+        body.push_str(&format!(
+            "<li><time datetime=\"{}\">{}</time><h2><a href=\"posts/{}.html\">{}</a></h2><p>{}</p></li>",
+            post.published_at.to_rfc3339(),
+            post.published_at.format("%B %-d, %Y"),
+            post.slug,
+            escape(&post.title),
+            escape(&excerpt(&post.html)),
+        ));
+    }
+    body.push_str("</ul>");
+    page(meta, "", &meta.title, &meta.description, &body)
+}
+
+fn post_page(meta: &SiteMeta, post: &SitePost) -> String {
+    let body = format!(
+        "<article><h1>{}</h1><time datetime=\"{}\">{}</time>{}</article>",
+        escape(&post.title),
+        post.published_at.to_rfc3339(),
+        post.published_at.format("%B %-d, %Y"),
+        post.html,
+    );
+    page(meta, "../", &post.title, &excerpt(&post.html), &body)
+}
+
+/// RSS 2.0 with the full post in `content:encoded`.
+fn feed(meta: &SiteMeta, posts: &[SitePost]) -> String {
+    let mut items = String::new();
+    let assets = format!("src=\"{}", site_url(meta, "assets/"));
+    for post in posts {
+        let link = site_url(meta, &format!("posts/{}.html", post.slug));
+        let html = post.html.replace("src=\"../assets/", &assets);
+        items.push_str(&format!(
+            "<item><title>{}</title><link>{}</link><guid>{}</guid><pubDate>{}</pubDate>\
+             <description>{}</description><content:encoded>{}</content:encoded></item>",
+            escape(&post.title),
+            escape(&link),
+            escape(&link),
+            post.published_at.to_rfc2822(),
+            escape(&excerpt(&post.html)),
+            escape(&html),
+        ));
+    }
+    format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\
+         <rss version=\"2.0\" xmlns:content=\"http://purl.org/rss/1.0/modules/content/\"><channel>\
+         <title>{}</title><link>{}</link><description>{}</description><lastBuildDate>{}</lastBuildDate>{}\
+         </channel></rss>",
+        escape(&meta.title),
+        escape(&site_url(meta, "index.html")),
+        escape(&meta.description),
+        Utc::now().to_rfc2822(),
+        items,
+    )
+}
+
+fn sitemap(meta: &SiteMeta, posts: &[SitePost]) -> String {
+    let mut urls = format!("<url><loc>{}</loc></url>", escape(&site_url(meta, "index.html")));
+    for post in posts {
+        urls.push_str(&format!(
+            "<url><loc>{}</loc><lastmod>{}</lastmod></url>",
+            escape(&site_url(meta, &format!("posts/{}.html", post.slug))),
+            post.updated_at.get(..10).unwrap_or(&post.updated_at),
+        ));
+    }
+    format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\
+         <urlset xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\">{}</urlset>",
+        urls
+    )
+}
+
+/// Copy the post's local images into `assets` and point the HTML at the
+/// copies. Identical images are written once.
+fn copy_images(html: &str, assets: &Path, images_dir: Option<&Path>, copied: &mut HashSet<String>) -> Result<String, String> {
+    let mut out = html.to_string();
+    for (src, _) in export::image_tags(html) {
+        if src.starts_with("http://") || src.starts_with("https://") || src.starts_with("data:") || src.starts_with("//") {
+            continue;
+        }
+        let Some(bytes) = export::image_bytes(&src, images_dir) else {
+            continue;
+        };
+        let extension = ::image::guess_format(&bytes)
+            .ok()
+            .and_then(|f| f.extensions_str().first().copied())
+            .unwrap_or("img");
+        let digest: String = Sha256::digest(&bytes).iter().take(8).map(|b| format!("{:02x}", b)).collect();
+        let name = format!("{}.{}", digest, extension);
+        if copied.insert(name.clone()) {
+            fs::write(assets.join(&name), &bytes).map_err(|e| format!("Failed to copy image: {}", e))?;
+        }
+        out = out.replace(&format!("src=\"{}\"", src), &format!("src=\"../assets/{}\"", name));
+    }
+    Ok(out)
+}
+
+fn write_site(ctx: &JobContext, dir: &Path, meta: &SiteMeta, mut posts: Vec<SitePost>, images_dir: Option<&Path>) -> Result<usize, String> {
+    let err = |e: std::io::Error| format!("Failed to write site: {}", e);
+    let assets = dir.join("assets");
+    fs::create_dir_all(dir.join("posts")).map_err(err)?;
+    fs::create_dir_all(&assets).map_err(err)?;
+
+    let mut copied = HashSet::new();
+    for (i, post) in posts.iter_mut().enumerate() {
+        ctx.check()?;
+        post.html = copy_images(&post.html, &assets, images_dir, &mut copied)?;
+        fs::write(dir.join("posts").join(format!("{}.html", post.slug)), post_page(meta, post)).map_err(err)?;
+        ctx.progress(i + 1);
+    }
+    fs::write(dir.join("index.html"), index_page(meta, &posts)).map_err(err)?;
+    fs::write(dir.join("style.css"), stylesheet(meta)).map_err(err)?;
+    fs::write(dir.join("feed.xml"), feed(meta, &posts)).map_err(err)?;
+    fs::write(dir.join("sitemap.xml"), sitemap(meta, &posts)).map_err(err)?;
+    Ok(copied.len())
+}
+
+/// Write the project's published documents to `out_dir` as a static site,
+/// newest first. `theme` is "classic", "dark" or "editorial"; `base_url`
+/// (e.g. "https://archive.example.com") makes the feed and sitemap links
+/// absolute. Existing files in `out_dir` are overwritten, others are kept.
+/// Runs on the export queue; pass `job_id` to follow or cancel it.
+#[tauri::command]
+pub async fn generate_static_site(
+    app: AppHandle,
+    project_id: String,
+    theme: String,
+    out_dir: String,
+    base_url: Option<String>,
+    job_id: Option<String>,
+) -> Result<StaticSite, AppError> {
+    let theme = THEMES
+        .iter()
+        .copied()
+        .find(|t| *t == theme)
+        .ok_or_else(|| AppError::validation(format!("Unknown site theme '{}'", theme)))?;
+    let base_url = base_url.map(|u| u.trim().trim_end_matches('/').to_string()).filter(|u| !u.is_empty());
+    if let Some(url) = &base_url {
+        if !(url.starts_with("https://") || url.starts_with("http://")) {
+            return Err(AppError::validation(format!("'{}' is not a web address", url)));
+        }
+    }
+    if out_dir.trim().is_empty() {
+        return Err(AppError::validation("Choose a folder for the site"));
+    }
+
+    let (meta, posts) = {
+        let conn = db::get_db(&app)?;
+        let project = export::load_project(&conn, &project_id)?;
+        let mut stmt = conn.prepare(
+            "SELECT title, html_content, COALESCE(published_at, updated_at), updated_at FROM documents
+             WHERE project_id = ?1 AND status = 'published'",
+        )?;
+        let rows = stmt.query_map(rusqlite::params![project_id], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get::<_, String>(2)?, row.get::<_, String>(3)?))
+        })?;
+        let mut taken = HashSet::new();
+        let mut posts: Vec<SitePost> = rows
+            .filter_map(|r| r.ok())
+            .map(|(title, html, published_at, updated_at)| {
+                let title = if title.trim().is_empty() { "Untitled".to_string() } else { title };
+                SitePost {
+                    slug: String::new(),
+                    published_at: DateTime::parse_from_rfc3339(&published_at)
+                        .map(|d| d.with_timezone(&Utc))
+                        .unwrap_or_else(|_| Utc::now()),
+                    title,
+                    html,
+                    updated_at,
+                }
+            })
+            .collect();
+        posts.sort_by_key(|p| std::cmp::Reverse(p.published_at));
+        // Slugs handed out oldest first, so a new post never renames an old page
+        for post in posts.iter_mut().rev() {
+            post.slug = slug(&post.title, &mut taken);
+        }
+        audit::record(&app, &conn, "export.static_site", "project", Some(&project_id), Some(&project.name))?;
+
+        let description = project
+            .defaults
+            .seo
+            .meta_description
+            .clone()
+            .filter(|d| !d.trim().is_empty())
+            .unwrap_or_else(|| project.description.clone());
+        let meta = SiteMeta { title: project.name, description, theme, brand: project.defaults.brand, base_url };
+        (meta, posts)
+    };
+    if posts.is_empty() {
+        return Err(AppError::validation(format!("'{}' has no published documents", meta.title)));
+    }
+
+    let dir = PathBuf::from(&out_dir);
+    let images_dir = images::images_dir(&app).ok();
+    let label = meta.title.clone();
+    let total = posts.len();
+    let copied = export_jobs::run_export_job(&app, job_id, "static_site", &label, "html", total, move |ctx| {
+        write_site(ctx, &dir, &meta, posts, images_dir.as_deref())
+    })
+    .await?;
+    Ok(StaticSite { out_dir, posts: total, images: copied })
+}
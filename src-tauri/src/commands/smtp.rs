@@ -0,0 +1,604 @@
+use chrono::Utc;
+use lettre::message::header::{HeaderName, HeaderValue};
+use lettre::message::{Mailbox, MultiPart};
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+
+use crate::commands::export::{plain_text_document, DEFAULT_PLAIN_TEXT_WIDTH};
+use crate::commands::{blocks, credentials, platform, settings};
+use crate::db;
+use crate::error::{AppError, ErrorKind};
+use crate::permissions::{self, Permission};
+
+// ─── SMTP sending ───────────────────────────────────────────────
+//
+// For lists small enough not to need an ESP: an issue is sent straight
+// from the app through the user's own SMTP server to the unified
+// subscriber list, minus the suppression list. Each message gets merge
+// tags filled per recipient and an unsubscribe link backed by
+// unsubscribe_tokens. Sends are throttled to the configured rate and run
+// in the background, reporting on the "smtp:progress" event; every
+// recipient's outcome is kept in smtp_send_log.
+
+pub(crate) const SMTP_SETTING: &str = "smtp";
+/// Credential key ("smtp:default") holding the SMTP password
+const SMTP_CREDENTIAL: (&str, &str) = ("smtp", "default");
+/// Beyond this an ESP is the better tool
+const MAX_RECIPIENTS: usize = 2000;
+const UNSUBSCRIBE_TAG: &str = "{{unsubscribe_url}}";
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct SmtpSettings {
+    pub host: String,
+    pub port: u16,
+    /// "starttls" | "tls" | "none"
+    pub security: String,
+    pub username: String,
+    pub from_email: String,
+    pub from_name: Option<String>,
+    pub reply_to: Option<String>,
+    pub messages_per_minute: u32,
+    /// Page that handles unsubscribes, given the token as `?token=` (or in
+    /// place of a `{token}` in the URL). Without one, links are mailto:
+    /// the sender with the token in the subject.
+    pub unsubscribe_url: Option<String>,
+}
+
+impl Default for SmtpSettings {
+    fn default() -> Self {
+        SmtpSettings {
+            host: String::new(),
+            port: 587,
+            security: "starttls".to_string(),
+            username: String::new(),
+            from_email: String::new(),
+            from_name: None,
+            reply_to: None,
+            messages_per_minute: 30,
+            unsubscribe_url: None,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct SmtpSend {
+    pub id: String,
+    pub document_id: String,
+    pub subject: String,
+    /// "sending" | "sent" | "partial" | "failed"
+    pub status: String,
+    pub total: i64,
+    pub sent: i64,
+    pub failed: i64,
+    pub started_at: String,
+    pub finished_at: Option<String>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct SmtpLogEntry {
+    pub email: String,
+    /// "sent" | "failed"
+    pub status: String,
+    pub error: Option<String>,
+    pub sent_at: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SmtpProgress {
+    pub send_id: String,
+    pub total: i64,
+    pub sent: i64,
+    pub failed: i64,
+    pub done: bool,
+}
+
+struct Recipient {
+    email: String,
+    name: Option<String>,
+}
+
+/// Rejected logins are `auth`; timeouts, TLS failures and temporary
+/// server refusals are `network`.
+fn smtp_error(context: &str, err: lettre::transport::smtp::Error) -> AppError {
+    let kind = match err.status().map(|code| code.to_string()) {
+        Some(code) if code == "535" || code == "534" => ErrorKind::Auth,
+        _ if err.is_timeout() || err.is_tls() || err.is_transient() => ErrorKind::Network,
+        _ => ErrorKind::Platform,
+    };
+    AppError::new(kind, format!("{}: {}", context, err)).with_platform("smtp")
+}
+
+fn smtp_settings(app: &AppHandle) -> SmtpSettings {
+    settings::get_setting(app, SMTP_SETTING).unwrap_or_default()
+}
+
+fn mailbox(email: &str, name: Option<&str>) -> Result<Mailbox, AppError> {
+    let address = email
+        .parse()
+        .map_err(|_| AppError::validation(format!("'{}' is not a valid email address", email)))?;
+    Ok(Mailbox::new(name.map(str::to_string), address))
+}
+
+fn transport(app: &AppHandle, config: &SmtpSettings) -> Result<AsyncSmtpTransport<Tokio1Executor>, AppError> {
+    if config.host.trim().is_empty() || config.from_email.trim().is_empty() {
+        return Err(AppError::validation("SMTP host and sender address are required"));
+    }
+    let builder = match config.security.as_str() {
+        "tls" => AsyncSmtpTransport::<Tokio1Executor>::relay(&config.host),
+        "none" => Ok(AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&config.host)),
+        _ => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&config.host),
+    }
+    .map_err(|e| smtp_error("SMTP setup failed", e))?
+    .port(config.port);
+    let builder = if config.username.is_empty() {
+        builder
+    } else {
+        let password = platform::get_api_key(app, SMTP_CREDENTIAL.0, SMTP_CREDENTIAL.1)?;
+        builder.credentials(Credentials::new(config.username.clone(), password))
+    };
+    Ok(builder.timeout(Some(Duration::from_secs(30))).build())
+}
+
+/// The address's unsubscribe token, created on first use.
+fn unsubscribe_token(conn: &Connection, email: &str) -> Result<String, AppError> {
+    let existing: Option<String> = conn
+        .query_row(
+            "SELECT token FROM unsubscribe_tokens WHERE email = ?1",
+            rusqlite::params![email],
+            |row| row.get(0),
+        )
+        .ok();
+    if let Some(token) = existing {
+        return Ok(token);
+    }
+    let token = uuid::Uuid::new_v4().simple().to_string();
+    conn.execute(
+        "INSERT INTO unsubscribe_tokens (token, email, created_at) VALUES (?1, ?2, ?3)",
+        rusqlite::params![token, email, Utc::now().to_rfc3339()],
+    )?;
+    Ok(token)
+}
+
+/// (link for the body, List-Unsubscribe header value)
+fn unsubscribe_links(config: &SmtpSettings, token: &str) -> (String, String) {
+    let mailto = format!("mailto:{}?subject=unsubscribe%20{}", config.from_email, token);
+    match config.unsubscribe_url.as_deref().map(str::trim).filter(|u| !u.is_empty()) {
+        Some(base) => {
+            let url = if base.contains("{token}") {
+                base.replace("{token}", token)
+            } else {
+                format!("{}{}token={}", base, if base.contains('?') { '&' } else { '?' }, token)
+            };
+            (url.clone(), format!("<{}>, <{}>", url, mailto))
+        }
+        None => (mailto.clone(), format!("<{}>", mailto)),
+    }
+}
+
+/// Active subscribers in the document's workspace, optionally with `tag`,
+/// who aren't suppressed. People synced from a platform count only while
+/// some platform has them active.
+fn recipients(conn: &Connection, document_id: &str, tag: Option<&str>) -> Result<Vec<Recipient>, AppError> {
+    let mut stmt = conn.prepare(
+        "SELECT s.email, s.name FROM subscribers s
+         WHERE s.email NOT IN (SELECT email FROM suppression_list)
+           AND s.workspace_id = (SELECT workspace_id FROM documents WHERE id = ?2)
+           AND (?1 IS NULL OR EXISTS (SELECT 1 FROM subscriber_tags t WHERE t.subscriber_id = s.id AND t.tag = ?1))
+           AND (NOT EXISTS (SELECT 1 FROM subscriber_platforms p WHERE p.subscriber_id = s.id)
+                OR EXISTS (SELECT 1 FROM subscriber_platforms p WHERE p.subscriber_id = s.id AND p.status = 'active'))
+         ORDER BY s.email",
+    )?;
+    let rows = stmt.query_map(rusqlite::params![tag, document_id], |row| {
+        Ok(Recipient { email: row.get(0)?, name: row.get(1)? })
+    })?;
+    Ok(rows.filter_map(|r| r.ok()).collect())
+}
+
+/// The issue for one recipient: merge tags filled, with an unsubscribe
+/// footer added when the issue doesn't place `{{unsubscribe_url}}` itself.
+/// Values are HTML-escaped once, by `fill_placeholders`; names come from
+/// the public signup form.
+fn personalize(html: &str, recipient: &Recipient, unsubscribe_url: &str) -> String {
+    let name = recipient.name.clone().unwrap_or_default();
+    let first_name = name.split_whitespace().next().unwrap_or("").to_string();
+    let values = HashMap::from([
+        ("email".to_string(), recipient.email.clone()),
+        ("name".to_string(), name),
+        ("first_name".to_string(), first_name),
+        ("unsubscribe_url".to_string(), unsubscribe_url.to_string()),
+    ]);
+    let html = if html.contains(UNSUBSCRIBE_TAG) {
+        html.to_string()
+    } else {
+        format!(
+            "{}<p style=\"font-size:12px;color:#6b7280\"><a href=\"{}\">Unsubscribe</a></p>",
+            html, UNSUBSCRIBE_TAG
+        )
+    };
+    blocks::fill_placeholders(&html, &values, &mut Vec::new())
+}
+
+fn build_message(
+    config: &SmtpSettings,
+    to: &Recipient,
+    subject: &str,
+    html: &str,
+    list_unsubscribe: &str,
+) -> Result<Message, AppError> {
+    let mut builder = Message::builder()
+        .from(mailbox(&config.from_email, config.from_name.as_deref())?)
+        .to(mailbox(&to.email, to.name.as_deref())?)
+        .subject(subject)
+        .raw_header(HeaderValue::new(
+            HeaderName::new_from_ascii_str("List-Unsubscribe"),
+            list_unsubscribe.to_string(),
+        ));
+    if let Some(reply_to) = config.reply_to.as_deref().filter(|r| !r.trim().is_empty()) {
+        builder = builder.reply_to(mailbox(reply_to, None)?);
+    }
+    builder
+        .multipart(MultiPart::alternative_plain_html(plain_text_document(html, DEFAULT_PLAIN_TEXT_WIDTH), html.to_string()))
+        .map_err(|e| AppError::validation(format!("Failed to build message: {}", e)))
+}
+
+/// Document HTML with any content blocks merged, and its title.
+fn load_issue(conn: &Connection, document_id: &str, block_ids: &[String]) -> Result<(String, String), AppError> {
+    let (title, html): (String, String) = conn
+        .query_row(
+            "SELECT title, html_content FROM documents WHERE id = ?1",
+            rusqlite::params![document_id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .map_err(|_| AppError::not_found(format!("Document '{}' not found", document_id)))?;
+    let html = if block_ids.is_empty() {
+        html
+    } else {
+        blocks::assemble(conn, document_id, &html, block_ids, &HashMap::new())?.html
+    };
+    Ok((title, html))
+}
+
+/// One transactional message (signup confirmations and the like): no
+/// merge tags, unsubscribe link or send log.
+pub(crate) async fn send_transactional(
+    app: &AppHandle,
+    to_email: &str,
+    to_name: Option<&str>,
+    subject: &str,
+    html: &str,
+) -> Result<(), AppError> {
+    let config = smtp_settings(app);
+    let mailer = transport(app, &config)?;
+    let mut builder = Message::builder()
+        .from(mailbox(&config.from_email, config.from_name.as_deref())?)
+        .to(mailbox(to_email, to_name)?)
+        .subject(subject);
+    if let Some(reply_to) = config.reply_to.as_deref().filter(|r| !r.trim().is_empty()) {
+        builder = builder.reply_to(mailbox(reply_to, None)?);
+    }
+    let message = builder
+        .multipart(MultiPart::alternative_plain_html(plain_text_document(html, DEFAULT_PLAIN_TEXT_WIDTH), html.to_string()))
+        .map_err(|e| AppError::validation(format!("Failed to build message: {}", e)))?;
+    mailer.send(message).await.map_err(|e| smtp_error("SMTP send failed", e))?;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_smtp_settings(app: AppHandle) -> Result<SmtpSettings, AppError> {
+    Ok(smtp_settings(&app))
+}
+
+/// Save the server settings; `password`, when given, goes to the
+/// credential store rather than settings.json.
+#[tauri::command]
+pub async fn save_smtp_settings(
+    app: AppHandle,
+    settings: SmtpSettings,
+    password: Option<String>,
+) -> Result<(), AppError> {
+    permissions::require(&app, Permission::ManageCredentials)?;
+    if !["starttls", "tls", "none"].contains(&settings.security.as_str()) {
+        return Err(AppError::validation(format!("Unknown SMTP security '{}'", settings.security)));
+    }
+    if settings.messages_per_minute == 0 {
+        return Err(AppError::validation("Sending rate must be at least one message per minute"));
+    }
+    if !settings.from_email.is_empty() {
+        mailbox(&settings.from_email, None)?;
+    }
+    if let Some(password) = password {
+        credentials::store_credential(
+            app.clone(),
+            SMTP_CREDENTIAL.0.to_string(),
+            SMTP_CREDENTIAL.1.to_string(),
+            password,
+            settings.host.clone(),
+            settings.username.clone(),
+        )
+        .await?;
+    }
+    settings::set_setting(&app, SMTP_SETTING, serde_json::to_value(&settings)?)?;
+    Ok(())
+}
+
+/// Connect and authenticate without sending anything.
+#[tauri::command]
+pub async fn test_smtp_connection(app: AppHandle) -> Result<bool, AppError> {
+    let config = smtp_settings(&app);
+    transport(&app, &config)?
+        .test_connection()
+        .await
+        .map_err(|e| smtp_error("SMTP connection failed", e))
+}
+
+/// Send one copy of the issue to `to`, merge tags filled with placeholder
+/// values, without logging it as a send.
+#[tauri::command]
+pub async fn send_smtp_test(
+    app: AppHandle,
+    document_id: String,
+    to: String,
+    subject: Option<String>,
+    block_ids: Option<Vec<String>>,
+) -> Result<(), AppError> {
+    permissions::require(&app, Permission::SendEmail)?;
+    let config = smtp_settings(&app);
+    let mailer = transport(&app, &config)?;
+    let (title, html) = {
+        let conn = db::get_db(&app)?;
+        load_issue(&conn, &document_id, &block_ids.unwrap_or_default())?
+    };
+    let recipient = Recipient { email: to.trim().to_string(), name: Some("Test Reader".to_string()) };
+    let (link, header) = unsubscribe_links(&config, "test");
+    let subject = format!("[Test] {}", subject.unwrap_or(title));
+    let message = build_message(&config, &recipient, &subject, &personalize(&html, &recipient, &link), &header)?;
+    mailer
+        .send(message)
+        .await
+        .map_err(|e| smtp_error("SMTP send failed", e))?;
+    Ok(())
+}
+
+/// Send the issue to every eligible subscriber (optionally only those
+/// tagged `tag`). Returns the send id at once; progress arrives on
+/// "smtp:progress" and the outcome in `list_smtp_sends`.
+#[tauri::command]
+pub async fn send_issue_smtp(
+    app: AppHandle,
+    document_id: String,
+    subject: Option<String>,
+    tag: Option<String>,
+    block_ids: Option<Vec<String>>,
+) -> Result<String, AppError> {
+    permissions::require(&app, Permission::SendEmail)?;
+    let config = smtp_settings(&app);
+    let mailer = transport(&app, &config)?;
+    let send_id = uuid::Uuid::new_v4().to_string();
+
+    let (subject, html, prepared) = {
+        let conn = db::get_db(&app)?;
+        let (title, html) = load_issue(&conn, &document_id, &block_ids.unwrap_or_default())?;
+        let list = recipients(&conn, &document_id, tag.as_deref())?;
+        if list.is_empty() {
+            return Err(AppError::validation("No subscribers to send to"));
+        }
+        if list.len() > MAX_RECIPIENTS {
+            return Err(AppError::validation(format!(
+                "{} recipients is more than direct sending supports ({}); publish through a platform instead",
+                list.len(),
+                MAX_RECIPIENTS
+            )));
+        }
+        let mut prepared = Vec::with_capacity(list.len());
+        for recipient in list {
+            let token = unsubscribe_token(&conn, &recipient.email)?;
+            prepared.push((recipient, unsubscribe_links(&config, &token)));
+        }
+        let subject = subject.filter(|s| !s.trim().is_empty()).unwrap_or(title);
+        conn.execute(
+            "INSERT INTO smtp_sends (id, document_id, subject, status, total, started_at) VALUES (?1, ?2, ?3, 'sending', ?4, ?5)",
+            rusqlite::params![send_id, document_id, subject, prepared.len() as i64, Utc::now().to_rfc3339()],
+        )?;
+        (subject, html, prepared)
+    };
+
+    let trace_id = crate::trace::new_id();
+    let id = send_id.clone();
+    tokio::spawn(crate::trace::scope(trace_id, async move {
+        let interval = Duration::from_secs_f64(60.0 / config.messages_per_minute.max(1) as f64);
+        let mut progress = SmtpProgress { send_id: id.clone(), total: prepared.len() as i64, sent: 0, failed: 0, done: false };
+
+        for (i, (recipient, (link, header))) in prepared.iter().enumerate() {
+            if i > 0 {
+                tokio::time::sleep(interval).await;
+            }
+            let outcome = match build_message(&config, recipient, &subject, &personalize(&html, recipient, link), header) {
+                Ok(message) => mailer.send(message).await.map(|_| ()).map_err(|e| e.to_string()),
+                Err(e) => Err(e.to_string()),
+            };
+            match &outcome {
+                Ok(()) => progress.sent += 1,
+                Err(_) => progress.failed += 1,
+            }
+            if let Ok(conn) = db::get_db(&app) {
+                conn.execute(
+                    "INSERT INTO smtp_send_log (send_id, email, status, error, sent_at) VALUES (?1, ?2, ?3, ?4, ?5)",
+                    rusqlite::params![
+                        id,
+                        recipient.email,
+                        if outcome.is_ok() { "sent" } else { "failed" },
+                        outcome.err(),
+                        Utc::now().to_rfc3339()
+                    ],
+                )
+                .ok();
+                conn.execute(
+                    "UPDATE smtp_sends SET sent = ?1, failed = ?2 WHERE id = ?3",
+                    rusqlite::params![progress.sent, progress.failed, id],
+                )
+                .ok();
+            }
+            let _ = app.emit("smtp:progress", &progress);
+        }
+
+        let status = match (progress.sent, progress.failed) {
+            (_, 0) => "sent",
+            (0, _) => "failed",
+            _ => "partial",
+        };
+        if let Ok(conn) = db::get_db(&app) {
+            conn.execute(
+                "UPDATE smtp_sends SET status = ?1, finished_at = ?2 WHERE id = ?3",
+                rusqlite::params![status, Utc::now().to_rfc3339(), id],
+            )
+            .ok();
+            db::log_activity(
+                &conn,
+                "smtp.sent",
+                "document",
+                Some(&document_id),
+                Some(&format!("'{}': {} sent, {} failed", subject, progress.sent, progress.failed)),
+            );
+        }
+        progress.done = true;
+        let _ = app.emit("smtp:progress", &progress);
+    }));
+    Ok(send_id)
+}
+
+#[tauri::command]
+pub async fn list_smtp_sends(app: AppHandle, document_id: Option<String>) -> Result<Vec<SmtpSend>, AppError> {
+    let conn = db::get_db(&app)?;
+    let mut stmt = conn.prepare(
+        "SELECT id, document_id, subject, status, total, sent, failed, started_at, finished_at FROM smtp_sends
+         WHERE (?1 IS NULL OR document_id = ?1) ORDER BY started_at DESC",
+    )?;
+    let rows = stmt.query_map(rusqlite::params![document_id], |row| {
+        Ok(SmtpSend {
+            id: row.get(0)?,
+            document_id: row.get(1)?,
+            subject: row.get(2)?,
+            status: row.get(3)?,
+            total: row.get(4)?,
+            sent: row.get(5)?,
+            failed: row.get(6)?,
+            started_at: row.get(7)?,
+            finished_at: row.get(8)?,
+        })
+    })?;
+    Ok(rows.filter_map(|r| r.ok()).collect())
+}
+
+#[tauri::command]
+pub async fn get_smtp_send_log(app: AppHandle, send_id: String) -> Result<Vec<SmtpLogEntry>, AppError> {
+    let conn = db::get_db(&app)?;
+    let mut stmt =
+        conn.prepare("SELECT email, status, error, sent_at FROM smtp_send_log WHERE send_id = ?1 ORDER BY id")?;
+    let rows = stmt.query_map(rusqlite::params![send_id], |row| {
+        Ok(SmtpLogEntry { email: row.get(0)?, status: row.get(1)?, error: row.get(2)?, sent_at: row.get(3)? })
+    })?;
+    Ok(rows.filter_map(|r| r.ok()).collect())
+}
+
+/// Act on an unsubscribe token (from the unsubscribe page or a mailto
+/// reply): the address joins the suppression list. Returns the address.
+#[tauri::command]
+pub async fn process_unsubscribe(app: AppHandle, token: String) -> Result<String, AppError> {
+    let conn = db::get_db(&app)?;
+    let token = token.trim().trim_start_matches("unsubscribe").trim();
+    let email: String = conn
+        .query_row(
+            "SELECT email FROM unsubscribe_tokens WHERE token = ?1",
+            rusqlite::params![token],
+            |row| row.get(0),
+        )
+        .map_err(|_| AppError::not_found("Unknown unsubscribe token"))?;
+    let now = Utc::now().to_rfc3339();
+    conn.execute(
+        "UPDATE unsubscribe_tokens SET used_at = COALESCE(used_at, ?1) WHERE token = ?2",
+        rusqlite::params![now, token],
+    )?;
+    conn.execute(
+        "INSERT OR IGNORE INTO suppression_list (email, reason, platform, created_at) VALUES (?1, 'Unsubscribed', 'smtp', ?2)",
+        rusqlite::params![email, now],
+    )?;
+    db::log_activity(&conn, "audience.unsubscribed", "subscribers", None, Some(&email));
+    Ok(email)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reader(email: &str, name: Option<&str>) -> Recipient {
+        Recipient { email: email.to_string(), name: name.map(str::to_string) }
+    }
+
+    fn settings(unsubscribe_url: Option<&str>) -> SmtpSettings {
+        SmtpSettings {
+            from_email: "editor@example.com".to_string(),
+            unsubscribe_url: unsubscribe_url.map(str::to_string),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn personalize_escapes_merge_values_once() {
+        let html = personalize(
+            "<p>Hi {{first_name}}, from {{name}} to {{ email }}</p><a href=\"{{unsubscribe_url}}\">Leave</a>",
+            &reader("ada@example.com", Some("<b>Ada</b> & \"Co\"")),
+            "https://example.com/u?list=1&token=abc",
+        );
+        assert_eq!(
+            html,
+            "<p>Hi &lt;b&gt;Ada&lt;/b&gt;, from &lt;b&gt;Ada&lt;/b&gt; &amp; &quot;Co&quot; to ada@example.com</p>\
+             <a href=\"https://example.com/u?list=1&amp;token=abc\">Leave</a>"
+        );
+    }
+
+    #[test]
+    fn personalize_adds_a_footer_only_when_the_issue_has_no_link() {
+        let html = personalize("<p>Hi {{name}}</p>", &reader("ada@example.com", None), "mailto:x?subject=unsubscribe%20t");
+        assert_eq!(
+            html,
+            "<p>Hi </p><p style=\"font-size:12px;color:#6b7280\"><a href=\"mailto:x?subject=unsubscribe%20t\">Unsubscribe</a></p>"
+        );
+
+        let html = personalize("<a href=\"{{unsubscribe_url}}\">Bye</a>", &reader("ada@example.com", None), "https://u");
+        assert_eq!(html, "<a href=\"https://u\">Bye</a>");
+    }
+
+    #[test]
+    fn unsubscribe_links_fill_a_token_placeholder() {
+        let (link, header) = unsubscribe_links(&settings(Some("https://example.com/unsubscribe/{token}")), "abc");
+        assert_eq!(link, "https://example.com/unsubscribe/abc");
+        assert_eq!(
+            header,
+            "<https://example.com/unsubscribe/abc>, <mailto:editor@example.com?subject=unsubscribe%20abc>"
+        );
+    }
+
+    #[test]
+    fn unsubscribe_links_append_a_token_query() {
+        let (link, _) = unsubscribe_links(&settings(Some("https://example.com/unsubscribe")), "abc");
+        assert_eq!(link, "https://example.com/unsubscribe?token=abc");
+
+        let (link, _) = unsubscribe_links(&settings(Some(" https://example.com/u?list=weekly ")), "abc");
+        assert_eq!(link, "https://example.com/u?list=weekly&token=abc");
+    }
+
+    #[test]
+    fn unsubscribe_links_fall_back_to_mailto() {
+        for url in [None, Some("  ")] {
+            let (link, header) = unsubscribe_links(&settings(url), "abc");
+            assert_eq!(link, "mailto:editor@example.com?subject=unsubscribe%20abc");
+            assert_eq!(header, "<mailto:editor@example.com?subject=unsubscribe%20abc>");
+        }
+    }
+}
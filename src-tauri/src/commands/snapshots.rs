@@ -0,0 +1,244 @@
+use chrono::{DateTime, Utc};
+use rusqlite::Connection;
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::commands::{audit, settings};
+use crate::db;
+use crate::error::AppError;
+use crate::permissions::{self, Permission};
+use crate::query_cache;
+
+// ─── Safety snapshots ───────────────────────────────────────────
+//
+// A full copy of station.db taken just before an operation that rewrites
+// a lot of rows at once: schema migrations, archive imports, backfills,
+// version restores and subscriber syncs. Copies are written with
+// `VACUUM INTO`, so they are consistent and compact, and live in
+// "snapshots/" next to the database. Only the newest few are kept.
+
+/// Snapshots kept on disk
+pub const MAX_SNAPSHOTS_SETTING: &str = "storage.max_safety_snapshots";
+pub(crate) const DEFAULT_MAX_SNAPSHOTS: usize = 10;
+
+#[derive(Debug, Serialize, Clone)]
+pub struct SafetySnapshot {
+    /// File stem: a sortable UTC timestamp and the reason
+    pub id: String,
+    pub reason: String,
+    pub created_at: String,
+    pub size_bytes: u64,
+}
+
+pub(crate) fn snapshots_dir(base: &Path) -> PathBuf {
+    base.join("snapshots")
+}
+
+fn max_snapshots(app: &AppHandle) -> usize {
+    settings::get_setting(app, MAX_SNAPSHOTS_SETTING).unwrap_or(DEFAULT_MAX_SNAPSHOTS)
+}
+
+fn app_snapshots_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    let base = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data dir: {}", e))?;
+    Ok(snapshots_dir(&base))
+}
+
+fn snapshot_info(path: &Path) -> Option<SafetySnapshot> {
+    if path.extension().and_then(|e| e.to_str()) != Some("db") {
+        return None;
+    }
+    let id = path.file_stem()?.to_str()?.to_string();
+    let (_, reason) = id.split_once('-')?;
+    let meta = fs::metadata(path).ok()?;
+    let created_at = meta.modified().map(|t| DateTime::<Utc>::from(t).to_rfc3339()).unwrap_or_default();
+    Some(SafetySnapshot {
+        reason: reason.to_string(),
+        id,
+        created_at,
+        size_bytes: meta.len(),
+    })
+}
+
+/// Snapshots in `dir`, newest first.
+fn list_in(dir: &Path) -> Vec<SafetySnapshot> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut snapshots: Vec<SafetySnapshot> = entries.flatten().filter_map(|e| snapshot_info(&e.path())).collect();
+    snapshots.sort_by(|a, b| b.id.cmp(&a.id));
+    snapshots
+}
+
+/// Drop all but the newest `keep` snapshots in `dir`.
+pub(crate) fn prune(dir: &Path, keep: usize) {
+    for old in list_in(dir).into_iter().skip(keep.max(1)) {
+        fs::remove_file(dir.join(format!("{}.db", old.id))).ok();
+    }
+}
+
+/// Copy the database into `dir`.
+pub(crate) fn write_snapshot(conn: &Connection, dir: &Path, reason: &str) -> Result<SafetySnapshot, String> {
+    fs::create_dir_all(dir).map_err(|e| format!("Failed to create snapshots dir: {}", e))?;
+    let reason: String = reason
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_lowercase() } else { '_' })
+        .collect();
+    let id = format!("{}-{}", Utc::now().format("%Y%m%dT%H%M%S%3f"), reason);
+    let path = dir.join(format!("{}.db", id));
+    conn.execute("VACUUM INTO ?1", rusqlite::params![path.to_string_lossy()])
+        .map_err(|e| format!("Failed to write safety snapshot: {}", e))?;
+    snapshot_info(&path).ok_or_else(|| "Safety snapshot was not written".to_string())
+}
+
+/// Take a snapshot before a risky operation. Callers bail out when this
+/// fails, since the operation would not be reversible.
+pub(crate) fn take_snapshot(app: &AppHandle, conn: &Connection, reason: &str) -> Result<SafetySnapshot, AppError> {
+    let dir = app_snapshots_dir(app)?;
+    let snapshot = write_snapshot(conn, &dir, reason)?;
+    prune(&dir, max_snapshots(app));
+    Ok(snapshot)
+}
+
+const AUDIT_COLUMNS: &str =
+    "seq, action, entity_type, entity_id, details, actor_role, created_at, trace_id, prev_hash, hash";
+
+/// Replace the audit log in `conn` with the one in the database at `from`.
+/// A rollback undoes data, not the record of what was done to it, so the
+/// trail keeps every row written since the snapshot and its chain stays
+/// whole.
+fn carry_audit_log(conn: &Connection, from: &Path) -> Result<(), String> {
+    conn.execute("ATTACH DATABASE ?1 AS live", rusqlite::params![from.to_string_lossy()])
+        .map_err(|e| format!("Failed to open the current audit log: {}", e))?;
+    let copied = conn.execute_batch(&format!(
+        "BEGIN;
+         DELETE FROM main.audit_log;
+         INSERT INTO main.audit_log ({0}) SELECT {0} FROM live.audit_log ORDER BY seq;
+         COMMIT;",
+        AUDIT_COLUMNS
+    ));
+    if copied.is_err() {
+        conn.execute_batch("ROLLBACK").ok();
+    }
+    conn.execute_batch("DETACH DATABASE live").ok();
+    copied.map_err(|e| format!("Failed to carry the audit log across the rollback: {}", e))
+}
+
+/// Reopen the live database from `source`, keeping the audit log of
+/// `current`, a copy of the database being replaced. The old connection is
+/// closed first so its WAL is checkpointed and the files can be replaced.
+fn replace_database(conn: &mut Connection, base: &Path, source: &Path, current: &Path) -> Result<(), String> {
+    let db_path = base.join("station.db");
+    let old = std::mem::replace(conn, Connection::open_in_memory().map_err(|e| e.to_string())?);
+    old.close().map_err(|(_, e)| format!("Failed to close database: {}", e))?;
+
+    let copied = fs::copy(source, &db_path).map(|_| ());
+    if copied.is_ok() {
+        for suffix in ["-wal", "-shm"] {
+            fs::remove_file(base.join(format!("station.db{}", suffix))).ok();
+        }
+    }
+    // Reopen in any case so the app keeps a working connection
+    let reopened = Connection::open(&db_path).map_err(|e| format!("Failed to reopen database: {}", e))?;
+    reopened
+        .execute_batch("PRAGMA journal_mode=WAL;")
+        .map_err(|e| format!("Failed to set WAL mode: {}", e))?;
+    // Snapshots from before a migration come back up to date
+    db::run_migrations(&reopened)?;
+    let carried = if copied.is_ok() { carry_audit_log(&reopened, current) } else { Ok(()) };
+    query_cache::watch(&reopened);
+    query_cache::clear();
+    *conn = reopened;
+    copied.map_err(|e| format!("Failed to restore snapshot: {}", e))?;
+    carried
+}
+
+#[tauri::command]
+pub async fn list_safety_snapshots(app: AppHandle) -> Result<Vec<SafetySnapshot>, AppError> {
+    Ok(list_in(&app_snapshots_dir(&app)?))
+}
+
+/// Put the whole database back as it was at snapshot `id`. The current
+/// state is snapshotted first, so a rollback can itself be undone.
+/// Emits "database:rolled_back" so open windows reload.
+#[tauri::command]
+pub async fn rollback_to_snapshot(app: AppHandle, id: String) -> Result<SafetySnapshot, AppError> {
+    permissions::require(&app, Permission::DeleteContent)?;
+    let dir = app_snapshots_dir(&app)?;
+    let target = list_in(&dir)
+        .into_iter()
+        .find(|s| s.id == id)
+        .ok_or_else(|| AppError::not_found(format!("Safety snapshot '{}' not found", id)))?;
+    let source = dir.join(format!("{}.db", target.id));
+    Connection::open(&source)
+        .and_then(|c| c.query_row("PRAGMA quick_check", [], |row| row.get::<_, String>(0)))
+        .ok()
+        .filter(|check| check == "ok")
+        .ok_or_else(|| AppError::validation("That snapshot is damaged and can't be restored"))?;
+
+    let base = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data dir: {}", e))?;
+    let mut conn = db::get_db(&app)?;
+    // Pruned only afterwards, so the target can't be the one dropped
+    let current = write_snapshot(&conn, &dir, "rollback")?;
+    replace_database(&mut conn, &base, &source, &dir.join(format!("{}.db", current.id)))?;
+    prune(&dir, max_snapshots(&app));
+
+    audit::record(&app, &conn, "database.rolled_back", "snapshot", Some(&target.id), Some(&target.reason))?;
+    app.emit("database:rolled_back", &target).ok();
+    Ok(target)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn audit_db(path: &Path, actions: &[&str]) {
+        let conn = Connection::open(path).unwrap();
+        db::run_migrations(&conn).unwrap();
+        for (i, action) in actions.iter().enumerate() {
+            conn.execute(
+                "INSERT INTO audit_log (seq, action, entity_type, actor_role, created_at, prev_hash, hash)
+                 VALUES (?1, ?2, 'test', 'owner', '2026-01-01T00:00:00Z', ?3, ?4)",
+                rusqlite::params![i as i64 + 1, action, format!("h{}", i), format!("h{}", i + 1)],
+            )
+            .unwrap();
+        }
+    }
+
+    #[test]
+    fn rollback_keeps_audit_rows_written_after_the_snapshot() {
+        let dir = std::env::temp_dir().join(format!("station-audit-{}", uuid::Uuid::new_v4().simple()));
+        fs::create_dir_all(&dir).unwrap();
+        let (restored, current) = (dir.join("restored.db"), dir.join("current.db"));
+        audit_db(&restored, &["one"]);
+        audit_db(&current, &["one", "two", "three"]);
+
+        let conn = Connection::open(&restored).unwrap();
+        carry_audit_log(&conn, &current).unwrap();
+        let actions: Vec<String> = conn
+            .prepare("SELECT action FROM audit_log ORDER BY seq")
+            .unwrap()
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .map(|r| r.unwrap())
+            .collect();
+        assert_eq!(actions, ["one", "two", "three"]);
+        // New rows continue the sequence rather than reuse it
+        conn.execute(
+            "INSERT INTO audit_log (action, entity_type, actor_role, created_at, prev_hash, hash)
+             VALUES ('four', 'test', 'owner', '2026-01-01T00:00:00Z', 'h3', 'h4')",
+            [],
+        )
+        .unwrap();
+        assert_eq!(conn.last_insert_rowid(), 4);
+        drop(conn);
+        fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -0,0 +1,166 @@
+use chrono::Utc;
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tauri::AppHandle;
+
+use crate::commands::blocks;
+use crate::db;
+use crate::error::AppError;
+use crate::permissions::{self, Permission};
+
+// ─── Snippets ───────────────────────────────────────────────────
+//
+// Small pieces of HTML the writer inserts into a draft by name or by a
+// typed shortcut: a sign-off, a recurring call to action, a disclosure
+// line. `{{placeholder}}` fields are filled in once, when the snippet is
+// inserted, so unlike content blocks the result belongs to the draft.
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Snippet {
+    pub id: String,
+    pub name: String,
+    /// Typed in the editor to expand the snippet, e.g. "/signoff"
+    pub shortcut: Option<String>,
+    pub html: String,
+    /// `{{placeholder}}` keys in the HTML, in order of first use
+    pub placeholders: Vec<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct RenderedSnippet {
+    pub html: String,
+    /// Placeholders with no value; they are left out of the HTML
+    pub unresolved: Vec<String>,
+}
+
+const COLUMNS: &str = "id, name, shortcut, html, created_at, updated_at";
+
+fn snippet_from_row(row: &rusqlite::Row) -> rusqlite::Result<Snippet> {
+    let html: String = row.get(3)?;
+    Ok(Snippet {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        shortcut: row.get(2)?,
+        placeholders: placeholder_keys(&html),
+        html,
+        created_at: row.get(4)?,
+        updated_at: row.get(5)?,
+    })
+}
+
+fn load_snippet(conn: &Connection, id: &str) -> Result<Snippet, AppError> {
+    conn.query_row(
+        &format!("SELECT {} FROM snippets WHERE id = ?1", COLUMNS),
+        rusqlite::params![id],
+        snippet_from_row,
+    )
+    .map_err(|_| AppError::not_found(format!("Snippet '{}' not found", id)))
+}
+
+/// The distinct `{{key}}` fields in `html`.
+fn placeholder_keys(html: &str) -> Vec<String> {
+    let mut keys = Vec::new();
+    blocks::fill_placeholders(html, &HashMap::new(), &mut keys);
+    keys
+}
+
+/// A blank shortcut means none; otherwise it must be a single token.
+fn normalize_shortcut(shortcut: Option<String>) -> Result<Option<String>, AppError> {
+    let Some(shortcut) = shortcut.map(|s| s.trim().to_string()).filter(|s| !s.is_empty()) else {
+        return Ok(None);
+    };
+    if shortcut.chars().any(char::is_whitespace) || shortcut.len() > 40 {
+        return Err(AppError::validation("A shortcut is one word of up to 40 characters"));
+    }
+    Ok(Some(shortcut))
+}
+
+fn taken(e: &rusqlite::Error) -> bool {
+    matches!(e, rusqlite::Error::SqliteFailure(f, _) if f.code == rusqlite::ErrorCode::ConstraintViolation)
+}
+
+/// Create a snippet, or replace snippet `id`'s name, shortcut and HTML.
+#[tauri::command]
+pub async fn save_snippet(
+    app: AppHandle,
+    id: Option<String>,
+    name: String,
+    html: String,
+    shortcut: Option<String>,
+) -> Result<Snippet, AppError> {
+    if name.trim().is_empty() {
+        return Err(AppError::validation("Snippet name is required"));
+    }
+    let shortcut = normalize_shortcut(shortcut)?;
+    let conn = db::get_db(&app)?;
+    let now = Utc::now().to_rfc3339();
+    let new_id = uuid::Uuid::new_v4().to_string();
+
+    let result = match id {
+        Some(ref id) => {
+            load_snippet(&conn, id)?;
+            conn.execute(
+                "UPDATE snippets SET name = ?1, shortcut = ?2, html = ?3, updated_at = ?4 WHERE id = ?5",
+                rusqlite::params![name, shortcut, html, now, id],
+            )
+        }
+        None => conn.execute(
+            "INSERT INTO snippets (id, name, shortcut, html, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?5)",
+            rusqlite::params![new_id, name, shortcut, html, now],
+        ),
+    };
+    match result {
+        Err(e) if taken(&e) => {
+            return Err(AppError::validation(format!(
+                "The shortcut '{}' is already used by another snippet",
+                shortcut.unwrap_or_default()
+            )))
+        }
+        other => other.map_err(|e| format!("Failed to save snippet: {}", e))?,
+    };
+    load_snippet(&conn, id.as_deref().unwrap_or(&new_id))
+}
+
+#[tauri::command]
+pub async fn list_snippets(app: AppHandle) -> Result<Vec<Snippet>, AppError> {
+    let conn = db::get_db(&app)?;
+    let mut stmt = conn.prepare(&format!("SELECT {} FROM snippets ORDER BY name COLLATE NOCASE ASC", COLUMNS))?;
+    let rows = stmt.query_map([], snippet_from_row)?;
+    Ok(rows.filter_map(|r| r.ok()).collect())
+}
+
+#[tauri::command]
+pub async fn delete_snippet(app: AppHandle, id: String) -> Result<(), AppError> {
+    permissions::require(&app, Permission::DeleteContent)?;
+    let conn = db::get_db(&app)?;
+    conn.execute("DELETE FROM snippets WHERE id = ?1", rusqlite::params![id])
+        .map_err(|e| format!("Failed to delete snippet: {}", e))?;
+    Ok(())
+}
+
+/// The snippet's HTML ready to insert. With `document_id`, the document's
+/// built-in values (`title`, `date`, `brand.*`, `sponsor.*`, ...) are
+/// available too; `values` override them.
+#[tauri::command]
+pub async fn render_snippet(
+    app: AppHandle,
+    id: String,
+    values: Option<HashMap<String, String>>,
+    document_id: Option<String>,
+) -> Result<RenderedSnippet, AppError> {
+    let conn = db::get_db(&app)?;
+    let snippet = load_snippet(&conn, &id)?;
+    let mut all_values = match document_id {
+        Some(document_id) => blocks::document_values(&conn, &document_id)?,
+        None => HashMap::new(),
+    };
+    all_values.extend(values.unwrap_or_default());
+
+    let mut unresolved = Vec::new();
+    let html = blocks::fill_placeholders(&snippet.html, &all_values, &mut unresolved);
+    Ok(RenderedSnippet { html, unresolved })
+}
@@ -0,0 +1,247 @@
+use chrono::Utc;
+use rusqlite::Connection;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::{Arc, LazyLock, Mutex};
+use tauri::{AppHandle, Manager};
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::commands::settings;
+use crate::db;
+use crate::error::AppError;
+use crate::hunspell::{self, Dictionary};
+
+// ─── Spellcheck ─────────────────────────────────────────────────
+//
+// Hunspell dictionaries are installed per language into the app data
+// folder ("dictionaries/<lang>.aff" and ".dic") and loaded on first use.
+// Words added to the user dictionary live in the database, so they travel
+// with it to other devices.
+
+pub const LANGUAGE_SETTING: &str = "spellcheck.language";
+const DEFAULT_LANGUAGE: &str = "en_US";
+
+static LOADED: LazyLock<Mutex<HashMap<String, Arc<Dictionary>>>> = LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// A misspelled word. Offsets are UTF-16 code units into the checked
+/// text, the way the editor's JavaScript strings index it.
+#[derive(Debug, Serialize, Clone)]
+pub struct SpellingIssue {
+    pub start: usize,
+    pub end: usize,
+    pub word: String,
+    pub suggestions: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct DictionaryInfo {
+    pub language: String,
+    pub user_words: i64,
+}
+
+fn dictionaries_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data dir: {}", e))?
+        .join("dictionaries");
+    std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create dictionaries dir: {}", e))?;
+    Ok(dir)
+}
+
+/// `language` or the configured default, checked to be a plain language
+/// tag since it becomes a file name.
+fn language(app: &AppHandle, language: Option<String>) -> Result<String, AppError> {
+    let language = language
+        .filter(|l| !l.trim().is_empty())
+        .or_else(|| settings::get_setting(app, LANGUAGE_SETTING))
+        .unwrap_or_else(|| DEFAULT_LANGUAGE.to_string());
+    let language = language.trim().replace('-', "_");
+    if language.len() > 20 || !language.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        return Err(AppError::validation(format!("'{}' is not a language code", language)));
+    }
+    Ok(language)
+}
+
+fn read_dictionary(aff: &[u8], dic: &[u8]) -> Result<Dictionary, AppError> {
+    let parsed = Dictionary::parse(&hunspell::decode(aff, aff), &hunspell::decode(dic, aff));
+    parsed.map_err(|e| AppError::validation(format!("Not a usable Hunspell dictionary: {}", e)))
+}
+
+fn dictionary(app: &AppHandle, language: &str) -> Result<Arc<Dictionary>, AppError> {
+    if let Some(dict) = LOADED.lock().ok().and_then(|l| l.get(language).cloned()) {
+        return Ok(dict);
+    }
+    let dir = dictionaries_dir(app)?;
+    let (Ok(aff), Ok(dic)) = (
+        std::fs::read(dir.join(format!("{}.aff", language))),
+        std::fs::read(dir.join(format!("{}.dic", language))),
+    ) else {
+        return Err(AppError::not_found(format!("No spelling dictionary installed for {}", language)));
+    };
+    let dict = Arc::new(read_dictionary(&aff, &dic)?);
+    if let Ok(mut loaded) = LOADED.lock() {
+        loaded.insert(language.to_string(), dict.clone());
+    }
+    Ok(dict)
+}
+
+fn user_words(conn: &Connection, language: &str) -> Result<HashSet<String>, AppError> {
+    let mut stmt = conn.prepare("SELECT word FROM user_dictionary WHERE language = ?1")?;
+    let rows = stmt.query_map(rusqlite::params![language], |row| row.get::<_, String>(0))?;
+    Ok(rows.filter_map(|r| r.ok()).collect())
+}
+
+/// Byte ranges of URLs and email addresses, which aren't spellchecked.
+fn skipped_spans(text: &str) -> Vec<(usize, usize)> {
+    text.split_whitespace()
+        .filter(|token| token.contains("://") || token.starts_with("www.") || token.contains('@'))
+        .map(|token| {
+            let start = token.as_ptr() as usize - text.as_ptr() as usize;
+            (start, start + token.len())
+        })
+        .collect()
+}
+
+fn utf16_len(text: &str) -> usize {
+    text.chars().map(char::len_utf16).sum()
+}
+
+/// Check plain text against the language's dictionary and the user
+/// dictionary. Words with digits, URLs and email addresses are skipped.
+#[tauri::command]
+pub async fn check_spelling(
+    app: AppHandle,
+    text: String,
+    language: Option<String>,
+) -> Result<Vec<SpellingIssue>, AppError> {
+    let language = self::language(&app, language)?;
+    let dict = dictionary(&app, &language)?;
+    let user = {
+        let conn = db::get_db(&app)?;
+        user_words(&conn, &language)?
+    };
+
+    let issues = tokio::task::spawn_blocking(move || {
+        let skipped = skipped_spans(&text);
+        let mut suggestions: HashMap<String, Vec<String>> = HashMap::new();
+        let mut issues = Vec::new();
+        // UTF-16 offset of `last_byte`, advanced incrementally
+        let (mut last_byte, mut last_utf16) = (0, 0);
+        for (byte, word) in text.unicode_word_indices() {
+            if word.chars().any(|c| c.is_numeric()) || skipped.iter().any(|(s, e)| byte >= *s && byte < *e) {
+                continue;
+            }
+            if user.contains(word) || user.contains(&word.to_lowercase()) || dict.check(word) {
+                continue;
+            }
+            last_utf16 += utf16_len(&text[last_byte..byte]);
+            last_byte = byte;
+            let suggested = suggestions.entry(word.to_string()).or_insert_with(|| dict.suggest(word)).clone();
+            issues.push(SpellingIssue {
+                start: last_utf16,
+                end: last_utf16 + utf16_len(word),
+                word: word.to_string(),
+                suggestions: suggested,
+            });
+        }
+        issues
+    })
+    .await
+    .map_err(|e| format!("Spellcheck task failed: {}", e))?;
+    Ok(issues)
+}
+
+/// Copy a Hunspell .aff/.dic pair into the app as `language`'s dictionary,
+/// replacing any installed one.
+#[tauri::command]
+pub async fn install_dictionary(
+    app: AppHandle,
+    language: String,
+    aff_path: String,
+    dic_path: String,
+) -> Result<DictionaryInfo, AppError> {
+    let language = self::language(&app, Some(language))?;
+    let aff = std::fs::read(&aff_path).map_err(|e| format!("Failed to read {}: {}", aff_path, e))?;
+    let dic = std::fs::read(&dic_path).map_err(|e| format!("Failed to read {}: {}", dic_path, e))?;
+    let dict = read_dictionary(&aff, &dic)?;
+
+    let dir = dictionaries_dir(&app)?;
+    std::fs::write(dir.join(format!("{}.aff", language)), &aff).map_err(|e| format!("Failed to install dictionary: {}", e))?;
+    std::fs::write(dir.join(format!("{}.dic", language)), &dic).map_err(|e| format!("Failed to install dictionary: {}", e))?;
+    if let Ok(mut loaded) = LOADED.lock() {
+        loaded.insert(language.clone(), Arc::new(dict));
+    }
+
+    let conn = db::get_db(&app)?;
+    db::log_activity(&conn, "spelling.dictionary_installed", "dictionary", Some(&language), None);
+    let user_words = user_words(&conn, &language)?.len() as i64;
+    Ok(DictionaryInfo { language, user_words })
+}
+
+/// Installed dictionaries with the size of each user dictionary.
+#[tauri::command]
+pub async fn list_dictionaries(app: AppHandle) -> Result<Vec<DictionaryInfo>, AppError> {
+    let dir = dictionaries_dir(&app)?;
+    let mut languages: Vec<String> = std::fs::read_dir(&dir)
+        .map_err(|e| format!("Failed to read dictionaries: {}", e))?
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let language = name.strip_suffix(".dic")?.to_string();
+            dir.join(format!("{}.aff", language)).exists().then_some(language)
+        })
+        .collect();
+    languages.sort();
+    let conn = db::get_db(&app)?;
+    languages
+        .into_iter()
+        .map(|language| {
+            let user_words = conn.query_row(
+                "SELECT COUNT(*) FROM user_dictionary WHERE language = ?1",
+                rusqlite::params![language],
+                |row| row.get(0),
+            )?;
+            Ok(DictionaryInfo { language, user_words })
+        })
+        .collect()
+}
+
+/// Accept `word` from now on. Lowercase entries also accept the
+/// capitalised form.
+#[tauri::command]
+pub async fn add_to_dictionary(app: AppHandle, word: String, language: Option<String>) -> Result<(), AppError> {
+    let language = self::language(&app, language)?;
+    let word = word.trim();
+    if word.is_empty() || word.chars().any(char::is_whitespace) {
+        return Err(AppError::validation("Add one word at a time"));
+    }
+    let conn = db::get_db(&app)?;
+    conn.execute(
+        "INSERT OR IGNORE INTO user_dictionary (word, language, created_at) VALUES (?1, ?2, ?3)",
+        rusqlite::params![word, language, Utc::now().to_rfc3339()],
+    )?;
+    db::log_activity(&conn, "spelling.word_added", "dictionary", Some(&language), Some(word));
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn remove_from_dictionary(app: AppHandle, word: String, language: Option<String>) -> Result<(), AppError> {
+    let language = self::language(&app, language)?;
+    let conn = db::get_db(&app)?;
+    conn.execute(
+        "DELETE FROM user_dictionary WHERE word = ?1 AND language = ?2",
+        rusqlite::params![word.trim(), language],
+    )?;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn list_user_dictionary(app: AppHandle, language: Option<String>) -> Result<Vec<String>, AppError> {
+    let language = self::language(&app, language)?;
+    let conn = db::get_db(&app)?;
+    let mut words: Vec<String> = user_words(&conn, &language)?.into_iter().collect();
+    words.sort_by_key(|w| w.to_lowercase());
+    Ok(words)
+}
@@ -0,0 +1,458 @@
+use chrono::{Duration, Utc};
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use tauri::{AppHandle, Emitter};
+
+use crate::commands::{audit, images, settings};
+use crate::db;
+use crate::error::AppError;
+use crate::permissions::{self, Permission};
+
+// ─── Storage budget ─────────────────────────────────────────────
+//
+// Version snapshots are the bulk of the database for long-running
+// documents. These settings bound them, `get_storage_breakdown` reports
+// where the space goes, and `prune_storage` frees one category at a time.
+// The autosave cadence itself is a frontend setting (autoSaveInterval).
+
+/// Minutes between manual-save snapshots of one document; 0 snapshots
+/// every save
+pub const SNAPSHOT_INTERVAL_SETTING: &str = "storage.snapshot_interval_minutes";
+/// Snapshots kept per document
+pub const MAX_VERSIONS_SETTING: &str = "storage.max_versions";
+/// Database size budget in MB; unset or 0 means no budget
+pub const MAX_DB_MB_SETTING: &str = "storage.max_db_mb";
+
+const DEFAULT_MAX_VERSIONS: i64 = 50;
+const DEFAULT_ACTIVITY_DAYS: i64 = 90;
+
+#[derive(Debug, Serialize, Clone)]
+pub struct StorageBreakdown {
+    /// station.db on disk, free pages included
+    pub database_bytes: u64,
+    pub documents_bytes: u64,
+    pub versions_bytes: u64,
+    pub version_count: i64,
+    pub images_bytes: u64,
+    pub image_count: usize,
+    pub attachments_bytes: u64,
+    pub attachment_count: i64,
+    /// Cached publication lists
+    pub cache_bytes: u64,
+    /// Activity log and publish attempts
+    pub log_bytes: u64,
+    pub budget_bytes: Option<u64>,
+    pub over_budget: bool,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct PruneResult {
+    pub target: String,
+    pub removed: i64,
+    pub bytes_freed: u64,
+}
+
+fn snapshot_interval_minutes(app: &AppHandle) -> i64 {
+    settings::get_setting(app, SNAPSHOT_INTERVAL_SETTING).unwrap_or(0)
+}
+
+/// Whether a manual save should snapshot: always with no interval set,
+/// otherwise only once the newest snapshot is older than the interval.
+pub(crate) fn snapshot_due(app: &AppHandle, conn: &Connection, document_id: &str) -> bool {
+    let minutes = snapshot_interval_minutes(app);
+    if minutes <= 0 {
+        return true;
+    }
+    let cutoff = (Utc::now() - Duration::minutes(minutes)).to_rfc3339();
+    !conn
+        .query_row(
+            "SELECT EXISTS (SELECT 1 FROM document_versions WHERE document_id = ?1 AND created_at > ?2)",
+            rusqlite::params![document_id, cutoff],
+            |row| row.get::<_, bool>(0),
+        )
+        .unwrap_or(false)
+}
+
+pub(crate) fn max_versions(app: &AppHandle) -> i64 {
+    settings::get_setting::<i64>(app, MAX_VERSIONS_SETTING)
+        .filter(|n| *n > 0)
+        .unwrap_or(DEFAULT_MAX_VERSIONS)
+}
+
+fn budget_bytes(app: &AppHandle) -> Option<u64> {
+    settings::get_setting::<u64>(app, MAX_DB_MB_SETTING)
+        .filter(|mb| *mb > 0)
+        .map(|mb| mb * 1024 * 1024)
+}
+
+fn sum(conn: &Connection, sql: &str) -> u64 {
+    conn.query_row(sql, [], |row| row.get::<_, Option<i64>>(0))
+        .ok()
+        .flatten()
+        .unwrap_or(0)
+        .max(0) as u64
+}
+
+fn database_bytes(conn: &Connection) -> u64 {
+    sum(conn, "SELECT page_count * page_size FROM pragma_page_count(), pragma_page_size()")
+}
+
+/// Stored images as (id, path, size).
+fn image_files(app: &AppHandle) -> Result<Vec<(String, std::path::PathBuf, u64)>, AppError> {
+    let dir = images::images_dir(app)?;
+    let read_dir = fs::read_dir(&dir).map_err(|e| format!("Failed to read images dir: {}", e))?;
+    Ok(read_dir
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.is_file())
+        .filter_map(|p| {
+            let id = p.file_stem()?.to_str()?.to_string();
+            let size = fs::metadata(&p).map(|m| m.len()).unwrap_or(0);
+            Some((id, p, size))
+        })
+        .collect())
+}
+
+fn breakdown(app: &AppHandle, conn: &Connection) -> Result<StorageBreakdown, AppError> {
+    let images = image_files(app)?;
+    let database_bytes = database_bytes(conn);
+    let budget_bytes = budget_bytes(app);
+    Ok(StorageBreakdown {
+        database_bytes,
+        documents_bytes: sum(conn, "SELECT SUM(LENGTH(title) + LENGTH(content) + LENGTH(html_content)) FROM documents"),
+        versions_bytes: sum(conn, "SELECT SUM(LENGTH(title) + LENGTH(content) + LENGTH(html_content)) FROM document_versions"),
+        version_count: conn.query_row("SELECT COUNT(*) FROM document_versions", [], |row| row.get(0))?,
+        images_bytes: images.iter().map(|(_, _, size)| size).sum(),
+        image_count: images.len(),
+        attachments_bytes: sum(conn, "SELECT SUM(size) FROM attachments"),
+        attachment_count: conn.query_row("SELECT COUNT(*) FROM attachments", [], |row| row.get(0))?,
+        cache_bytes: sum(
+            conn,
+            "SELECT SUM(LENGTH(name) + LENGTH(url) + COALESCE(LENGTH(description), 0)) FROM publications",
+        ),
+        log_bytes: sum(
+            conn,
+            "SELECT (SELECT COALESCE(SUM(LENGTH(action) + COALESCE(LENGTH(details), 0)), 0) FROM activity_log)
+                  + (SELECT COALESCE(SUM(COALESCE(LENGTH(error_message), 0)), 0) FROM publish_attempts)",
+        ),
+        budget_bytes,
+        over_budget: budget_bytes.is_some_and(|b| database_bytes > b),
+    })
+}
+
+/// Warn the frontend with "storage:over_budget" once the database passes
+/// its budget. Called after saves, which are what grow it.
+pub(crate) fn check_budget(app: &AppHandle, conn: &Connection) {
+    let Some(budget) = budget_bytes(app) else { return };
+    let size = database_bytes(conn);
+    if size > budget {
+        let _ = app.emit(
+            "storage:over_budget",
+            serde_json::json!({ "database_bytes": size, "budget_bytes": budget }),
+        );
+    }
+}
+
+#[tauri::command]
+pub async fn get_storage_breakdown(app: AppHandle) -> Result<StorageBreakdown, AppError> {
+    let conn = db::get_db(&app)?;
+    breakdown(&app, &conn)
+}
+
+/// Free one category of storage and compact the database.
+///
+/// - "versions": keep the newest `keep` snapshots per document (default:
+///   the storage.max_versions setting)
+/// - "activity": drop activity and publish-attempt rows older than `keep`
+///   days (default 90)
+/// - "caches": clear cached publications; they refill on next use
+/// - "images": delete images not referenced by any document or version
+#[tauri::command]
+pub async fn prune_storage(app: AppHandle, target: String, keep: Option<i64>) -> Result<PruneResult, AppError> {
+    permissions::require(&app, Permission::DeleteContent)?;
+    let conn = db::get_db(&app)?;
+    let before = database_bytes(&conn);
+    let mut image_bytes = 0;
+
+    let removed = match target.as_str() {
+        "versions" => {
+            let keep = keep.unwrap_or_else(|| max_versions(&app)).max(1);
+            conn.execute(
+                "DELETE FROM document_versions WHERE id IN (
+                     SELECT id FROM (
+                         SELECT id, ROW_NUMBER() OVER (PARTITION BY document_id ORDER BY version DESC) AS n
+                         FROM document_versions
+                     ) WHERE n > ?1
+                 )",
+                rusqlite::params![keep],
+            )? as i64
+        }
+        "activity" => {
+            let cutoff = (Utc::now() - Duration::days(keep.unwrap_or(DEFAULT_ACTIVITY_DAYS).max(0))).to_rfc3339();
+            let activity = conn.execute("DELETE FROM activity_log WHERE created_at < ?1", rusqlite::params![cutoff])?;
+            let attempts = conn.execute("DELETE FROM publish_attempts WHERE started_at < ?1", rusqlite::params![cutoff])?;
+            (activity + attempts) as i64
+        }
+        "caches" => conn.execute("DELETE FROM publications", [])? as i64,
+        "images" => {
+            let mut removed = 0;
+            for (id, path, size) in image_files(&app)? {
+                let pattern = format!("%{}%", id);
+                let used: bool = conn.query_row(
+                    "SELECT EXISTS (SELECT 1 FROM documents WHERE html_content LIKE ?1 OR content LIKE ?1)
+                         OR EXISTS (SELECT 1 FROM document_versions WHERE html_content LIKE ?1 OR content LIKE ?1)",
+                    rusqlite::params![pattern],
+                    |row| row.get(0),
+                )?;
+                if !used && fs::remove_file(&path).is_ok() {
+                    removed += 1;
+                    image_bytes += size;
+                }
+            }
+            removed
+        }
+        other => return Err(AppError::validation(format!("Unknown storage target '{}'", other))),
+    };
+
+    conn.execute_batch("VACUUM")?;
+    let bytes_freed = before.saturating_sub(database_bytes(&conn)) + image_bytes;
+    db::log_activity(
+        &conn,
+        "storage.pruned",
+        "storage",
+        Some(&target),
+        Some(&format!("Removed {} items, freed {} bytes", removed, bytes_freed)),
+    );
+    audit::record(
+        &app,
+        &conn,
+        "storage.pruned",
+        "storage",
+        Some(&target),
+        Some(&format!("Removed {} items", removed)),
+    )?;
+    Ok(PruneResult { target, removed, bytes_freed })
+}
+
+// ─── Retention ──────────────────────────────────────────────────
+//
+// Optional limits on how long subscriber and analytics data is kept, for
+// users who promise subscribers a retention period. Every rule is off
+// until set. The maintenance tick in crate::scheduler applies the policy
+// once a day; `preview_retention` counts what a policy would remove first.
+
+pub const RETENTION_SETTING: &str = "storage.retention";
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct RetentionPolicy {
+    /// Drop subscribers unseen for this many months who are no longer
+    /// active on any platform
+    #[serde(default)]
+    pub inactive_subscriber_months: Option<i64>,
+    /// Drop bounce and complaint events older than this
+    #[serde(default)]
+    pub subscriber_event_months: Option<i64>,
+    /// Thin analytics snapshots older than this to one per account per week
+    #[serde(default)]
+    pub weekly_snapshots_after_months: Option<i64>,
+    /// Drop analytics snapshots older than this entirely
+    #[serde(default)]
+    pub max_snapshot_months: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct RetentionResult {
+    pub subscribers: i64,
+    /// Subscriber events removed for being past their maximum age
+    pub subscriber_events: i64,
+    /// Snapshots removed by weekly thinning
+    pub snapshots_thinned: i64,
+    /// Snapshots removed for being past the maximum age
+    pub snapshots_expired: i64,
+}
+
+pub(crate) fn retention_policy(app: &AppHandle) -> RetentionPolicy {
+    settings::get_setting(app, RETENTION_SETTING).unwrap_or_default()
+}
+
+fn months_ago(months: i64) -> String {
+    (Utc::now() - Duration::days(months.max(0) * 30)).to_rfc3339()
+}
+
+const INACTIVE_SUBSCRIBERS: &str = "SELECT id FROM subscribers s WHERE s.last_seen_at < ?1
+     AND NOT EXISTS (SELECT 1 FROM subscriber_platforms p WHERE p.subscriber_id = s.id AND p.status = 'active')";
+
+/// Snapshots in [?2, ?1) other than the newest of their account's week
+const SURPLUS_SNAPSHOTS: &str = "SELECT id FROM (
+         SELECT id, ROW_NUMBER() OVER (
+             PARTITION BY platform, account_id, COALESCE(publication_id, ''), strftime('%Y-%W', substr(captured_at, 1, 10))
+             ORDER BY captured_at DESC
+         ) AS n
+         FROM analytics_snapshots WHERE captured_at < ?1 AND captured_at >= ?2
+     ) WHERE n > 1";
+
+/// Apply `policy`, or with `dry_run` only count what it would remove.
+fn run_retention(conn: &Connection, policy: &RetentionPolicy, dry_run: bool) -> Result<RetentionResult, AppError> {
+    let mut result = RetentionResult::default();
+    let count = |sql: &str, cutoff: &str| -> Result<i64, AppError> {
+        Ok(conn.query_row(&format!("SELECT COUNT(*) FROM ({})", sql), rusqlite::params![cutoff], |row| row.get(0))?)
+    };
+
+    // Before subscribers, so the count covers every event that goes for its age
+    if let Some(months) = policy.subscriber_event_months {
+        let cutoff = months_ago(months);
+        result.subscriber_events = count("SELECT id FROM subscriber_events WHERE occurred_at < ?1", &cutoff)?;
+        if !dry_run && result.subscriber_events > 0 {
+            conn.execute("DELETE FROM subscriber_events WHERE occurred_at < ?1", rusqlite::params![cutoff])?;
+        }
+    }
+
+    if let Some(months) = policy.inactive_subscriber_months {
+        let cutoff = months_ago(months);
+        result.subscribers = count(INACTIVE_SUBSCRIBERS, &cutoff)?;
+        if !dry_run && result.subscribers > 0 {
+            // Suppressions are keyed by email and outlive the subscriber
+            for table in ["subscriber_platforms", "subscriber_tags", "subscriber_events"] {
+                conn.execute(
+                    &format!("DELETE FROM {} WHERE subscriber_id IN ({})", table, INACTIVE_SUBSCRIBERS),
+                    rusqlite::params![cutoff],
+                )?;
+            }
+            conn.execute(
+                &format!("DELETE FROM subscribers WHERE id IN ({})", INACTIVE_SUBSCRIBERS),
+                rusqlite::params![cutoff],
+            )?;
+        }
+    }
+
+    let expiry_cutoff = policy.max_snapshot_months.map(months_ago);
+    if let Some(cutoff) = &expiry_cutoff {
+        let sql = "SELECT id FROM analytics_snapshots WHERE captured_at < ?1";
+        result.snapshots_expired = count(sql, cutoff)?;
+        if !dry_run {
+            conn.execute("DELETE FROM analytics_snapshots WHERE captured_at < ?1", rusqlite::params![cutoff])?;
+        }
+    }
+
+    if let Some(months) = policy.weekly_snapshots_after_months {
+        let cutoff = months_ago(months);
+        // Rows past expiry are gone on a real run; skip them on a dry one too
+        let floor = expiry_cutoff.clone().unwrap_or_default();
+        result.snapshots_thinned = conn.query_row(
+            &format!("SELECT COUNT(*) FROM ({})", SURPLUS_SNAPSHOTS),
+            rusqlite::params![cutoff, floor],
+            |row| row.get(0),
+        )?;
+        if !dry_run && result.snapshots_thinned > 0 {
+            conn.execute(
+                &format!("DELETE FROM analytics_snapshots WHERE id IN ({})", SURPLUS_SNAPSHOTS),
+                rusqlite::params![cutoff, floor],
+            )?;
+        }
+    }
+    Ok(result)
+}
+
+/// Apply the saved policy. Called by the scheduler's daily maintenance.
+pub(crate) fn apply_retention(app: &AppHandle) -> Result<RetentionResult, AppError> {
+    let policy = retention_policy(app);
+    let conn = db::get_db(app)?;
+    let result = run_retention(&conn, &policy, false)?;
+    let removed = result.subscribers + result.subscriber_events + result.snapshots_thinned + result.snapshots_expired;
+    if removed > 0 {
+        let details = format!(
+            "Removed {} subscribers, {} subscriber events, {} thinned and {} expired snapshots",
+            result.subscribers, result.subscriber_events, result.snapshots_thinned, result.snapshots_expired
+        );
+        db::log_activity(&conn, "storage.retention", "storage", None, Some(&details));
+        audit::record(app, &conn, "storage.retention", "storage", None, Some(&details))?;
+    }
+    Ok(result)
+}
+
+#[tauri::command]
+pub async fn get_retention_policy(app: AppHandle) -> Result<RetentionPolicy, AppError> {
+    Ok(retention_policy(&app))
+}
+
+#[tauri::command]
+pub async fn save_retention_policy(app: AppHandle, policy: RetentionPolicy) -> Result<(), AppError> {
+    permissions::require(&app, Permission::DeleteContent)?;
+    for months in [
+        policy.inactive_subscriber_months,
+        policy.subscriber_event_months,
+        policy.weekly_snapshots_after_months,
+        policy.max_snapshot_months,
+    ]
+    .into_iter()
+    .flatten()
+    {
+        if months < 1 {
+            return Err(AppError::validation("Retention periods must be at least one month"));
+        }
+    }
+    let value = serde_json::to_value(&policy)?;
+    settings::set_setting(&app, RETENTION_SETTING, value)?;
+    Ok(())
+}
+
+/// What `policy` (default: the saved one) would remove if applied now.
+#[tauri::command]
+pub async fn preview_retention(app: AppHandle, policy: Option<RetentionPolicy>) -> Result<RetentionResult, AppError> {
+    let policy = policy.unwrap_or_else(|| retention_policy(&app));
+    let conn = db::get_db(&app)?;
+    run_retention(&conn, &policy, true)
+}
+
+/// Apply the saved policy now instead of waiting for the daily run.
+#[tauri::command]
+pub async fn apply_retention_now(app: AppHandle) -> Result<RetentionResult, AppError> {
+    permissions::require(&app, Permission::DeleteContent)?;
+    apply_retention(&app)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn with_events(occurred: &[&str]) -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        db::run_migrations(&conn).unwrap();
+        for (i, at) in occurred.iter().enumerate() {
+            conn.execute(
+                "INSERT INTO subscriber_events (id, email, event_type, platform, source, occurred_at, created_at)
+                 VALUES (?1, 'ada@example.com', 'bounce', 'kit', 'sync', ?2, ?2)",
+                rusqlite::params![i.to_string(), at],
+            )
+            .unwrap();
+        }
+        conn
+    }
+
+    fn events(conn: &Connection) -> i64 {
+        conn.query_row("SELECT COUNT(*) FROM subscriber_events", [], |row| row.get(0)).unwrap()
+    }
+
+    #[test]
+    fn old_subscriber_events_expire() {
+        let recent = Utc::now().to_rfc3339();
+        let conn = with_events(&["2020-01-01T00:00:00Z", "2021-06-01T00:00:00Z", &recent]);
+        let policy = RetentionPolicy { subscriber_event_months: Some(24), ..Default::default() };
+
+        let preview = run_retention(&conn, &policy, true).unwrap();
+        assert_eq!(preview.subscriber_events, 2);
+        assert_eq!(events(&conn), 3);
+
+        let applied = run_retention(&conn, &policy, false).unwrap();
+        assert_eq!(applied.subscriber_events, 2);
+        assert_eq!(events(&conn), 1);
+    }
+
+    #[test]
+    fn events_are_kept_without_a_policy() {
+        let conn = with_events(&["2020-01-01T00:00:00Z"]);
+        assert_eq!(run_retention(&conn, &RetentionPolicy::default(), false).unwrap().subscriber_events, 0);
+        assert_eq!(events(&conn), 1);
+    }
+}
@@ -0,0 +1,210 @@
+use serde::Serialize;
+use tauri::AppHandle;
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::commands::export::html_to_plain_text;
+use crate::db;
+use crate::error::AppError;
+
+// ─── Style metrics ──────────────────────────────────────────────
+//
+// Local, English-oriented heuristics for the editor sidebar: readability
+// (Flesch-Kincaid), passive voice, adverbs and sentence lengths. The
+// syllable counter matches src/lib/readability.ts so the backend and the
+// live Insights tab agree on scores.
+
+/// Upper bounds of the sentence-length buckets, in words; the last bucket
+/// holds everything longer.
+const LENGTH_BUCKETS: &[(usize, &str)] = &[(10, "1-10"), (20, "11-20"), (30, "21-30"), (40, "31-40")];
+
+const BE_FORMS: &[&str] = &["am", "is", "are", "was", "were", "be", "been", "being", "isn't", "aren't", "wasn't", "weren't"];
+
+/// Common irregular past participles; regular ones end in "-ed".
+const IRREGULAR_PARTICIPLES: &[&str] = &[
+    "begun", "bitten", "blown", "born", "broken", "brought", "built", "bought", "caught", "chosen", "done", "drawn",
+    "driven", "eaten", "fallen", "felt", "forgotten", "found", "given", "gone", "grown", "heard", "held", "hidden",
+    "kept", "known", "laid", "led", "left", "lost", "made", "meant", "met", "paid", "put", "read", "run", "said",
+    "seen", "sent", "set", "shown", "shut", "sold", "spent", "spoken", "stolen", "taken", "taught", "thought",
+    "thrown", "told", "understood", "won", "worn", "written",
+];
+
+/// "-ly" words that aren't adverbs.
+const NOT_ADVERBS: &[&str] = &[
+    "only", "family", "reply", "supply", "apply", "rely", "early", "daily", "weekly", "monthly", "yearly", "holy",
+    "ugly", "silly", "belly", "jelly", "bully", "fly", "july", "italy", "lovely", "friendly", "likely", "lonely",
+];
+
+#[derive(Debug, Serialize, Clone)]
+pub struct LengthBucket {
+    pub label: String,
+    pub sentences: usize,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct StyleMetrics {
+    pub document_id: String,
+    /// None for the current text, otherwise the version analysed
+    pub version: Option<i64>,
+    pub words: usize,
+    pub sentences: usize,
+    pub syllables: usize,
+    /// U.S. school grade (Flesch-Kincaid Grade Level)
+    pub flesch_kincaid_grade: f64,
+    /// 0-100, higher is easier (Flesch Reading Ease)
+    pub reading_ease: f64,
+    pub passive_sentences: usize,
+    /// Share of sentences in the passive voice, 0-1
+    pub passive_ratio: f64,
+    pub adverbs: usize,
+    /// Adverbs per word, 0-1
+    pub adverb_density: f64,
+    pub avg_sentence_length: f64,
+    pub longest_sentence: usize,
+    pub sentence_lengths: Vec<LengthBucket>,
+}
+
+fn round1(x: f64) -> f64 {
+    (x * 10.0).round() / 10.0
+}
+
+fn round3(x: f64) -> f64 {
+    (x * 1000.0).round() / 1000.0
+}
+
+/// Split on sentence-ending punctuation, including the CJK full stops.
+fn sentences(text: &str) -> Vec<&str> {
+    text.split(['.', '!', '?', '。', '！', '？', '\n'])
+        .map(str::trim)
+        .filter(|s| s.unicode_words().next().is_some())
+        .collect()
+}
+
+/// English syllable estimate: vowel groups, minus a silent final "e",
+/// plus a syllabic "-le".
+fn syllables(word: &str) -> usize {
+    let w: Vec<char> = word.to_lowercase().chars().filter(|c| c.is_ascii_lowercase()).collect();
+    if w.len() <= 3 {
+        return 1;
+    }
+    let is_vowel = |c: char| "aeiouy".contains(c);
+    let mut count = 0;
+    let mut prev = false;
+    for &c in &w {
+        let v = is_vowel(c);
+        if v && !prev {
+            count += 1;
+        }
+        prev = v;
+    }
+    let n = w.len();
+    if w[n - 1] == 'e' && count > 1 {
+        count -= 1;
+    }
+    if w[n - 2] == 'l' && w[n - 1] == 'e' && !is_vowel(w[n - 3]) {
+        count += 1;
+    }
+    count.max(1)
+}
+
+fn is_participle(word: &str) -> bool {
+    (word.len() > 3 && word.ends_with("ed")) || IRREGULAR_PARTICIPLES.contains(&word)
+}
+
+/// A form of "to be" with a past participle up to two words later:
+/// "was written", "is being reviewed", "were quickly sold".
+fn is_passive(words: &[String]) -> bool {
+    words.iter().enumerate().any(|(i, w)| {
+        BE_FORMS.contains(&w.as_str()) && words.iter().skip(i + 1).take(3).any(|next| is_participle(next))
+    })
+}
+
+fn is_adverb(word: &str) -> bool {
+    word.len() > 4 && word.ends_with("ly") && !NOT_ADVERBS.contains(&word)
+}
+
+fn analyze(document_id: &str, version: Option<i64>, html: &str) -> StyleMetrics {
+    let text = html_to_plain_text(html);
+    let mut words = 0;
+    let mut syllable_total = 0;
+    let mut adverbs = 0;
+    let mut passive = 0;
+    let mut lengths = Vec::new();
+
+    for sentence in sentences(&text) {
+        let sentence_words: Vec<String> = sentence.unicode_words().map(str::to_lowercase).collect();
+        words += sentence_words.len();
+        syllable_total += sentence_words.iter().map(|w| syllables(w)).sum::<usize>();
+        adverbs += sentence_words.iter().filter(|w| is_adverb(w)).count();
+        if is_passive(&sentence_words) {
+            passive += 1;
+        }
+        lengths.push(sentence_words.len());
+    }
+
+    let sentence_count = lengths.len();
+    let (grade, ease) = if words == 0 || sentence_count == 0 {
+        (0.0, 0.0)
+    } else {
+        let wps = words as f64 / sentence_count as f64;
+        let spw = syllable_total as f64 / words as f64;
+        (
+            (0.39 * wps + 11.8 * spw - 15.59).max(0.0),
+            (206.835 - 1.015 * wps - 84.6 * spw).clamp(0.0, 100.0),
+        )
+    };
+
+    let mut buckets: Vec<LengthBucket> = LENGTH_BUCKETS
+        .iter()
+        .map(|(_, label)| LengthBucket { label: label.to_string(), sentences: 0 })
+        .collect();
+    buckets.push(LengthBucket { label: format!("{}+", LENGTH_BUCKETS[LENGTH_BUCKETS.len() - 1].0 + 1), sentences: 0 });
+    for len in &lengths {
+        let idx = LENGTH_BUCKETS.iter().position(|(max, _)| len <= max).unwrap_or(LENGTH_BUCKETS.len());
+        buckets[idx].sentences += 1;
+    }
+
+    let ratio = |n: usize, d: usize| if d == 0 { 0.0 } else { round3(n as f64 / d as f64) };
+    StyleMetrics {
+        document_id: document_id.to_string(),
+        version,
+        words,
+        sentences: sentence_count,
+        syllables: syllable_total,
+        flesch_kincaid_grade: round1(grade),
+        reading_ease: round1(ease),
+        passive_sentences: passive,
+        passive_ratio: ratio(passive, sentence_count),
+        adverbs,
+        adverb_density: ratio(adverbs, words),
+        avg_sentence_length: if sentence_count == 0 { 0.0 } else { round1(words as f64 / sentence_count as f64) },
+        longest_sentence: lengths.iter().copied().max().unwrap_or(0),
+        sentence_lengths: buckets,
+    }
+}
+
+/// Style metrics for a document's current text, or for one saved version.
+#[tauri::command]
+pub async fn get_style_metrics(
+    app: AppHandle,
+    document_id: String,
+    version: Option<i64>,
+) -> Result<StyleMetrics, AppError> {
+    let conn = db::get_db(&app)?;
+    let html: String = match version {
+        Some(v) => conn
+            .query_row(
+                "SELECT html_content FROM document_versions WHERE document_id = ?1 AND version = ?2",
+                rusqlite::params![document_id, v],
+                |row| row.get(0),
+            )
+            .map_err(|_| AppError::not_found(format!("Version {} of document '{}' not found", v, document_id)))?,
+        None => conn
+            .query_row(
+                "SELECT html_content FROM documents WHERE id = ?1",
+                rusqlite::params![document_id],
+                |row| row.get(0),
+            )
+            .map_err(|_| AppError::not_found(format!("Document '{}' not found", document_id)))?,
+    };
+    Ok(analyze(&document_id, version, &html))
+}
@@ -0,0 +1,332 @@
+use chrono::Utc;
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+use crate::commands::{platform, usage};
+use crate::db;
+use crate::error::AppError;
+use crate::permissions::{self, Permission};
+use crate::services::mastodon::MastodonService;
+use crate::services::twitter::TwitterService;
+
+// ─── Testimonials ───────────────────────────────────────────────
+//
+// Reader replies and quotes kept for landing pages and sponsor decks.
+// They are entered by hand or imported from Twitter and Mastodon mentions;
+// imported ones wait for approval, since most mentions aren't praise. A
+// mention linking to an issue's published URL is tagged with that issue.
+
+const SOURCES: &[&str] = &["manual", "email", "twitter", "mastodon", "other"];
+
+#[derive(Debug, Serialize, Clone)]
+pub struct Testimonial {
+    pub id: String,
+    pub quote: String,
+    pub author_name: String,
+    pub author_handle: Option<String>,
+    /// "manual" | "email" | "twitter" | "mastodon" | "other"
+    pub source: String,
+    pub source_url: Option<String>,
+    /// The issue it is about
+    pub document_id: Option<String>,
+    /// 1 to 5, set by the writer
+    pub rating: Option<i64>,
+    pub featured: bool,
+    /// Only approved testimonials are offered by `get_best_testimonials`
+    pub approved: bool,
+    pub received_at: String,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// Fields for `save_testimonial`; `id` updates an existing one.
+#[derive(Debug, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct TestimonialInput {
+    pub id: Option<String>,
+    pub quote: String,
+    pub author_name: String,
+    pub author_handle: Option<String>,
+    pub source: Option<String>,
+    pub source_url: Option<String>,
+    pub document_id: Option<String>,
+    pub rating: Option<i64>,
+    pub featured: bool,
+    pub approved: Option<bool>,
+    /// RFC 3339; now when unset
+    pub received_at: Option<String>,
+}
+
+/// A post mentioning the account, as returned by the social services.
+#[derive(Debug, Serialize, Clone)]
+pub struct SocialMention {
+    pub id: String,
+    pub text: String,
+    pub author_name: String,
+    pub author_handle: String,
+    pub url: Option<String>,
+    /// Links inside the post, expanded where the platform shortens them
+    pub links: Vec<String>,
+    pub created_at: String,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct MentionImport {
+    pub imported: usize,
+    /// Already imported before
+    pub skipped: usize,
+    /// Tagged with an issue from a link to it
+    pub tagged: usize,
+}
+
+const COLUMNS: &str = "id, quote, author_name, author_handle, source, source_url, document_id, rating, featured, approved, received_at, created_at, updated_at";
+
+fn testimonial_from_row(row: &rusqlite::Row) -> rusqlite::Result<Testimonial> {
+    Ok(Testimonial {
+        id: row.get(0)?,
+        quote: row.get(1)?,
+        author_name: row.get(2)?,
+        author_handle: row.get(3)?,
+        source: row.get(4)?,
+        source_url: row.get(5)?,
+        document_id: row.get(6)?,
+        rating: row.get(7)?,
+        featured: row.get(8)?,
+        approved: row.get(9)?,
+        received_at: row.get(10)?,
+        created_at: row.get(11)?,
+        updated_at: row.get(12)?,
+    })
+}
+
+fn load_testimonial(conn: &Connection, id: &str) -> Result<Testimonial, AppError> {
+    conn.query_row(
+        &format!("SELECT {} FROM testimonials WHERE id = ?1", COLUMNS),
+        rusqlite::params![id],
+        testimonial_from_row,
+    )
+    .map_err(|_| AppError::not_found(format!("Testimonial '{}' not found", id)))
+}
+
+/// The published issue a link points at, matching scheduled posts'
+/// published URLs without their query string or trailing slash.
+fn issue_for_links(conn: &Connection, links: &[String]) -> Option<String> {
+    let normalize = |url: &str| url.split(['?', '#']).next().unwrap_or("").trim_end_matches('/').to_lowercase();
+    let wanted: Vec<String> = links.iter().map(|l| normalize(l)).filter(|l| !l.is_empty()).collect();
+    if wanted.is_empty() {
+        return None;
+    }
+    let mut stmt = conn
+        .prepare("SELECT document_id, published_url FROM scheduled_posts WHERE published_url IS NOT NULL AND published_url != ''")
+        .ok()?;
+    let published: Vec<(String, String)> = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+        .ok()?
+        .filter_map(|r| r.ok())
+        .collect();
+    published
+        .into_iter()
+        .find(|(_, url)| wanted.contains(&normalize(url)))
+        .map(|(document_id, _)| document_id)
+}
+
+#[tauri::command]
+pub async fn save_testimonial(app: AppHandle, input: TestimonialInput) -> Result<Testimonial, AppError> {
+    if input.quote.trim().is_empty() {
+        return Err(AppError::validation("A testimonial needs a quote"));
+    }
+    let source = input.source.clone().unwrap_or_else(|| "manual".to_string());
+    if !SOURCES.contains(&source.as_str()) {
+        return Err(AppError::validation(format!("Unknown testimonial source '{}'", source)));
+    }
+    if let Some(rating) = input.rating {
+        if !(1..=5).contains(&rating) {
+            return Err(AppError::validation("Ratings go from 1 to 5"));
+        }
+    }
+    let conn = db::get_db(&app)?;
+    let now = Utc::now().to_rfc3339();
+
+    let id = match input.id {
+        Some(id) => {
+            let existing = load_testimonial(&conn, &id)?;
+            conn.execute(
+                "UPDATE testimonials SET quote = ?1, author_name = ?2, author_handle = ?3, source = ?4, source_url = ?5,
+                     document_id = ?6, rating = ?7, featured = ?8, approved = ?9, received_at = ?10, updated_at = ?11
+                 WHERE id = ?12",
+                rusqlite::params![
+                    input.quote.trim(),
+                    input.author_name.trim(),
+                    input.author_handle,
+                    source,
+                    input.source_url,
+                    input.document_id,
+                    input.rating,
+                    input.featured,
+                    input.approved.unwrap_or(existing.approved),
+                    input.received_at.unwrap_or(existing.received_at),
+                    now,
+                    id
+                ],
+            )
+            .map_err(|e| format!("Failed to update testimonial: {}", e))?;
+            id
+        }
+        None => {
+            let id = uuid::Uuid::new_v4().to_string();
+            conn.execute(
+                "INSERT INTO testimonials (id, quote, author_name, author_handle, source, source_url, document_id,
+                     rating, featured, approved, received_at, created_at, updated_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?12)",
+                rusqlite::params![
+                    id,
+                    input.quote.trim(),
+                    input.author_name.trim(),
+                    input.author_handle,
+                    source,
+                    input.source_url,
+                    input.document_id,
+                    input.rating,
+                    input.featured,
+                    input.approved.unwrap_or(true),
+                    input.received_at.unwrap_or_else(|| now.clone()),
+                    now
+                ],
+            )
+            .map_err(|e| format!("Failed to save testimonial: {}", e))?;
+            id
+        }
+    };
+    load_testimonial(&conn, &id)
+}
+
+/// Testimonials, newest first. `approved` filters on approval, e.g.
+/// `false` for imported mentions still to review.
+#[tauri::command]
+pub async fn list_testimonials(
+    app: AppHandle,
+    document_id: Option<String>,
+    approved: Option<bool>,
+) -> Result<Vec<Testimonial>, AppError> {
+    let conn = db::get_db(&app)?;
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM testimonials
+         WHERE (?1 IS NULL OR document_id = ?1) AND (?2 IS NULL OR approved = ?2)
+         ORDER BY received_at DESC",
+        COLUMNS
+    ))?;
+    let rows = stmt.query_map(rusqlite::params![document_id, approved], testimonial_from_row)?;
+    Ok(rows.filter_map(|r| r.ok()).collect())
+}
+
+#[tauri::command]
+pub async fn delete_testimonial(app: AppHandle, id: String) -> Result<(), AppError> {
+    permissions::require(&app, Permission::DeleteContent)?;
+    let conn = db::get_db(&app)?;
+    conn.execute("DELETE FROM testimonials WHERE id = ?1", rusqlite::params![id])
+        .map_err(|e| format!("Failed to delete testimonial: {}", e))?;
+    Ok(())
+}
+
+/// Pull recent mentions from a Twitter or Mastodon account in as
+/// unapproved testimonials. Mentions imported before are skipped. Those not
+/// linking to a published issue are tagged with `document_id`, if given.
+#[tauri::command]
+pub async fn import_social_mentions(
+    app: AppHandle,
+    platform: String,
+    account_id: String,
+    document_id: Option<String>,
+) -> Result<MentionImport, AppError> {
+    let api_key = platform::get_api_key(&app, &platform, &account_id)?;
+    let mentions = match platform.as_str() {
+        "twitter" => {
+            let since_id: Option<String> = {
+                let conn = db::get_db(&app)?;
+                conn.query_row(
+                    "SELECT external_id FROM testimonials WHERE source = 'twitter' AND external_id IS NOT NULL
+                     ORDER BY LENGTH(external_id) DESC, external_id DESC LIMIT 1",
+                    [],
+                    |row| row.get(0),
+                )
+                .ok()
+            };
+            TwitterService::mentions(&api_key, since_id.as_deref()).await?
+        }
+        "mastodon" => MastodonService::mentions(&api_key).await?,
+        other => {
+            return Err(AppError::validation(format!(
+                "Mentions can be imported from Twitter or Mastodon, not {}",
+                other
+            )))
+        }
+    };
+    usage::record_api_call(&app, &platform, &account_id);
+
+    let conn = db::get_db(&app)?;
+    let now = Utc::now().to_rfc3339();
+    let (mut imported, mut tagged) = (0, 0);
+    for mention in &mentions {
+        let linked = issue_for_links(&conn, &mention.links);
+        let tag = linked.clone().or_else(|| document_id.clone());
+        let received_at = if mention.created_at.is_empty() { now.clone() } else { mention.created_at.clone() };
+        let inserted = conn.execute(
+            "INSERT OR IGNORE INTO testimonials (id, quote, author_name, author_handle, source, source_url, external_id,
+                 document_id, approved, received_at, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, 0, ?9, ?10, ?10)",
+            rusqlite::params![
+                uuid::Uuid::new_v4().to_string(),
+                mention.text,
+                mention.author_name,
+                mention.author_handle,
+                platform,
+                mention.url,
+                mention.id,
+                tag,
+                received_at,
+                now
+            ],
+        )?;
+        if inserted > 0 {
+            imported += 1;
+            tagged += linked.is_some() as usize;
+        }
+    }
+    db::log_activity(&conn, "testimonials.imported", "testimonial", None, Some(&format!("{} from {}", imported, platform)));
+    Ok(MentionImport { imported, skipped: mentions.len() - imported, tagged })
+}
+
+/// Approved testimonials worth showing first: featured ones, then by
+/// rating, preferring quotes of a readable length (40 to 280 characters),
+/// then the most recent. Optionally limited to one issue or project.
+pub(crate) fn best_testimonials(
+    conn: &Connection,
+    limit: i64,
+    document_id: Option<&str>,
+    project_id: Option<&str>,
+) -> Result<Vec<Testimonial>, AppError> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM testimonials
+         WHERE approved = 1
+           AND (?1 IS NULL OR document_id = ?1)
+           AND (?2 IS NULL OR document_id IN (SELECT id FROM documents WHERE project_id = ?2))
+         ORDER BY featured DESC, COALESCE(rating, 0) DESC, LENGTH(quote) BETWEEN 40 AND 280 DESC, received_at DESC
+         LIMIT ?3",
+        COLUMNS
+    ))?;
+    let rows = stmt.query_map(rusqlite::params![document_id, project_id, limit.clamp(1, 100)], testimonial_from_row)?;
+    Ok(rows.filter_map(|r| r.ok()).collect())
+}
+
+/// The best approved testimonials, six unless `limit` says otherwise.
+#[tauri::command]
+pub async fn get_best_testimonials(
+    app: AppHandle,
+    limit: Option<i64>,
+    document_id: Option<String>,
+    project_id: Option<String>,
+) -> Result<Vec<Testimonial>, AppError> {
+    let conn = db::get_db(&app)?;
+    best_testimonials(&conn, limit.unwrap_or(6), document_id.as_deref(), project_id.as_deref())
+}
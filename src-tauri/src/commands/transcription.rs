@@ -0,0 +1,276 @@
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+use uuid::Uuid;
+
+use crate::commands::export::html_to_plain_text;
+use crate::commands::{ai, attachments, settings, windows, workspaces};
+use crate::db;
+use crate::error::{AppError, ErrorKind};
+use crate::permissions::{self, Permission};
+use crate::text;
+
+// ─── Transcription ──────────────────────────────────────────────
+//
+// Audio and video attachments are transcribed either by OpenAI's Whisper
+// API (using the saved "openai" AI provider key) or by a local whisper.cpp
+// build. Either way the segments become a new draft document with a
+// timestamp per paragraph, linked from the attachment, so an interview can
+// be edited into an issue like any other draft.
+
+/// `{ "binary_path": ..., "model_path": ... }` for local transcription
+pub const WHISPER_CPP_SETTING: &str = "transcription.whisper_cpp";
+
+/// OpenAI rejects larger uploads
+const OPENAI_MAX_BYTES: i64 = 25 * 1024 * 1024;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct WhisperCppSettings {
+    /// whisper.cpp CLI ("whisper-cli", or "main" in older builds)
+    pub binary_path: String,
+    /// ggml model file, e.g. ggml-base.en.bin
+    pub model_path: String,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct TranscriptSegment {
+    pub start_secs: f64,
+    pub end_secs: f64,
+    pub text: String,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct TranscriptResult {
+    pub attachment_id: String,
+    pub document_id: String,
+    pub title: String,
+    pub provider: String,
+    pub language: Option<String>,
+    pub segments: Vec<TranscriptSegment>,
+}
+
+struct Transcript {
+    language: Option<String>,
+    segments: Vec<TranscriptSegment>,
+}
+
+fn timestamp(secs: f64) -> String {
+    let secs = secs.max(0.0) as u64;
+    format!("{:02}:{:02}:{:02}", secs / 3600, (secs % 3600) / 60, secs % 60)
+}
+
+fn html_escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+fn transcript_html(filename: &str, segments: &[TranscriptSegment]) -> String {
+    let mut html = format!("<p><em>Transcribed from {}</em></p>", html_escape(filename));
+    for segment in segments {
+        html.push_str(&format!(
+            "<p><strong>[{}]</strong> {}</p>",
+            timestamp(segment.start_secs),
+            html_escape(segment.text.trim())
+        ));
+    }
+    html
+}
+
+async fn transcribe_openai(
+    app: &AppHandle,
+    attachment: &attachments::Attachment,
+    language: Option<&str>,
+) -> Result<Transcript, AppError> {
+    if attachment.size > OPENAI_MAX_BYTES {
+        return Err(AppError::validation(
+            "OpenAI transcribes files up to 25 MB; use whisper.cpp for longer recordings",
+        )
+        .with_platform("openai"));
+    }
+    let provider = ai::load_provider(app, "openai")
+        .map_err(|_| AppError::auth("Add an OpenAI provider in AI settings to transcribe with Whisper"))?;
+    let url = if provider.base_url.is_empty() {
+        "https://api.openai.com/v1/audio/transcriptions".to_string()
+    } else {
+        format!("{}/v1/audio/transcriptions", provider.base_url.trim_end_matches('/'))
+    };
+
+    let bytes = tokio::fs::read(&attachment.path)
+        .await
+        .map_err(|e| format!("Failed to read '{}': {}", attachment.filename, e))?;
+    let mut fields = vec![
+        ("model", "whisper-1"),
+        ("response_format", "verbose_json"),
+        ("timestamp_granularities[]", "segment"),
+    ];
+    if let Some(language) = language {
+        fields.push(("language", language));
+    }
+    let (content_type, body) =
+        crate::http::multipart_form(&fields, "file", &attachment.filename, &attachment.mime_type, &bytes);
+
+    let resp = crate::http::client("ai")?
+        .post(&url)
+        .header("Authorization", format!("Bearer {}", provider.api_key))
+        .header("content-type", content_type)
+        .body(body)
+        .send()
+        .await
+        .map_err(|e| AppError::network("openai", "OpenAI request failed", e))?;
+    if !resp.status().is_success() {
+        return Err(AppError::from_response("openai", "OpenAI transcription error", resp).await);
+    }
+
+    let json: serde_json::Value = resp.json().await.map_err(|e| e.to_string())?;
+    let segments = json["segments"]
+        .as_array()
+        .map(|segments| {
+            segments
+                .iter()
+                .map(|s| TranscriptSegment {
+                    start_secs: s["start"].as_f64().unwrap_or(0.0),
+                    end_secs: s["end"].as_f64().unwrap_or(0.0),
+                    text: s["text"].as_str().unwrap_or("").trim().to_string(),
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    Ok(Transcript {
+        language: json["language"].as_str().map(str::to_string),
+        segments,
+    })
+}
+
+/// Run the whisper.cpp CLI with JSON output into a temp file. Builds
+/// without an audio decoder only read 16 kHz WAV, so convert first there.
+async fn transcribe_whisper_cpp(
+    app: &AppHandle,
+    attachment: &attachments::Attachment,
+    language: Option<&str>,
+) -> Result<Transcript, AppError> {
+    let config: WhisperCppSettings = settings::get_setting(app, WHISPER_CPP_SETTING).ok_or_else(|| {
+        AppError::validation("Set the whisper.cpp binary and model paths in settings to transcribe locally")
+    })?;
+    let out_base = std::env::temp_dir().join(format!("station-transcript-{}", Uuid::new_v4()));
+
+    let output = tokio::process::Command::new(&config.binary_path)
+        .arg("-m")
+        .arg(&config.model_path)
+        .arg("-f")
+        .arg(&attachment.path)
+        .arg("-l")
+        .arg(language.unwrap_or("auto"))
+        .arg("-oj")
+        .arg("-of")
+        .arg(&out_base)
+        .arg("-np")
+        .output()
+        .await
+        .map_err(|e| format!("Failed to run whisper.cpp at '{}': {}", config.binary_path, e))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let detail = stderr.lines().rev().find(|l| !l.trim().is_empty()).unwrap_or("no output");
+        return Err(AppError::new(ErrorKind::Internal, format!("whisper.cpp failed: {}", detail)));
+    }
+
+    let json_path = out_base.with_extension("json");
+    let raw = tokio::fs::read_to_string(&json_path)
+        .await
+        .map_err(|e| format!("whisper.cpp wrote no transcript: {}", e))?;
+    tokio::fs::remove_file(&json_path).await.ok();
+    let json: serde_json::Value = serde_json::from_str(&raw)?;
+
+    let segments = json["transcription"]
+        .as_array()
+        .map(|segments| {
+            segments
+                .iter()
+                .map(|s| TranscriptSegment {
+                    start_secs: s["offsets"]["from"].as_f64().unwrap_or(0.0) / 1000.0,
+                    end_secs: s["offsets"]["to"].as_f64().unwrap_or(0.0) / 1000.0,
+                    text: s["text"].as_str().unwrap_or("").trim().to_string(),
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    Ok(Transcript {
+        language: json["result"]["language"].as_str().map(str::to_string),
+        segments,
+    })
+}
+
+/// Transcribe an audio or video attachment into a new draft document
+/// next to its source. `provider` is "openai" or "whisper_cpp";
+/// `language` is an ISO 639-1 code, detected when omitted.
+#[tauri::command]
+pub async fn transcribe_audio(
+    app: AppHandle,
+    attachment_id: String,
+    provider: String,
+    language: Option<String>,
+) -> Result<TranscriptResult, AppError> {
+    let (attachment, source_title, project_id) = {
+        let conn = db::get_db(&app)?;
+        let attachment = attachments::get(&app, &conn, &attachment_id)?;
+        let (title, project_id): (String, Option<String>) = conn.query_row(
+            "SELECT title, project_id FROM documents WHERE id = ?1",
+            rusqlite::params![attachment.document_id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )?;
+        (attachment, title, project_id)
+    };
+    if !attachment.mime_type.starts_with("audio/") && !attachment.mime_type.starts_with("video/") {
+        return Err(AppError::validation(format!("'{}' is not an audio or video file", attachment.filename)));
+    }
+
+    let transcript = match provider.as_str() {
+        "openai" => transcribe_openai(&app, &attachment, language.as_deref()).await?,
+        "whisper_cpp" => transcribe_whisper_cpp(&app, &attachment, language.as_deref()).await?,
+        other => return Err(AppError::validation(format!("Unknown transcription provider '{}'", other))),
+    };
+    if transcript.segments.is_empty() {
+        return Err(AppError::new(ErrorKind::Platform, "The transcript came back empty"));
+    }
+
+    let document_id = Uuid::new_v4().to_string();
+    let title = format!("Transcript: {}", source_title);
+    let html = transcript_html(&attachment.filename, &transcript.segments);
+    let content = html_to_plain_text(&html);
+    let word_count = text::count_html(&html, text::count_mode(&app)) as i64;
+    let now = Utc::now().to_rfc3339();
+
+    let conn = db::get_db(&app)?;
+    conn.execute(
+        "INSERT INTO documents (id, title, content, html_content, project_id, status, word_count, character_count, version, workspace_id, created_at, updated_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, 'draft', ?6, 0, 1, ?8, ?7, ?7)",
+        rusqlite::params![document_id, title, content, html, project_id, word_count, now, workspaces::current(&app)],
+    )?;
+    conn.execute(
+        "UPDATE attachments SET transcript_document_id = ?1 WHERE id = ?2",
+        rusqlite::params![document_id, attachment_id],
+    )?;
+    db::log_activity(&conn, "attachment.transcribed", "document", Some(&document_id), Some(&attachment.filename));
+    windows::document_changed(&app, &document_id, "saved", Some(1), None);
+
+    Ok(TranscriptResult {
+        attachment_id,
+        document_id,
+        title,
+        provider,
+        language: transcript.language,
+        segments: transcript.segments,
+    })
+}
+
+/// Point local transcription at a whisper.cpp build. The binary is run as
+/// a process, so only roles that manage credentials may change it.
+#[tauri::command]
+pub async fn save_whisper_cpp_settings(app: AppHandle, settings: WhisperCppSettings) -> Result<(), AppError> {
+    permissions::require(&app, Permission::ManageCredentials)?;
+    for (label, path) in [("binary", &settings.binary_path), ("model", &settings.model_path)] {
+        if !std::path::Path::new(path.trim()).is_file() {
+            return Err(AppError::validation(format!("whisper.cpp {} not found at '{}'", label, path)));
+        }
+    }
+    settings::set_setting(&app, WHISPER_CPP_SETTING, serde_json::to_value(&settings)?)?;
+    Ok(())
+}
@@ -0,0 +1,207 @@
+use chrono::Utc;
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tauri::AppHandle;
+
+use crate::commands::settings;
+use crate::db;
+use crate::error::{AppError, ErrorKind};
+
+// ─── Platform usage ─────────────────────────────────────────────
+//
+// Counts what we spend against each account's platform quota, per UTC day
+// and month: "api_calls" (one per platform command), "posts" (posts and
+// tweets published) and "emails_sent" (cached subscriber count of each
+// newsletter sent). Soft limits from "usage.limits" are checked before
+// publishing so a post is refused here rather than by the platform.
+
+pub const LIMITS_SETTING: &str = "usage.limits";
+
+pub const API_CALLS: &str = "api_calls";
+pub const POSTS: &str = "posts";
+pub const EMAILS_SENT: &str = "emails_sent";
+
+const NEWSLETTER_PLATFORMS: &[&str] = &["beehiiv", "substack", "kit", "ghost"];
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct UsageLimit {
+    pub day: Option<i64>,
+    pub month: Option<i64>,
+}
+
+/// platform -> metric -> limit
+pub type UsageLimits = HashMap<String, HashMap<String, UsageLimit>>;
+
+#[derive(Debug, Serialize, Clone)]
+pub struct PlatformUsage {
+    pub platform: String,
+    pub account_id: String,
+    pub metric: String,
+    pub today: i64,
+    pub this_month: i64,
+    pub day_limit: Option<i64>,
+    pub month_limit: Option<i64>,
+}
+
+/// X's free API tier allows 17 posts a day and 500 a month per app.
+fn default_limits() -> UsageLimits {
+    let mut twitter = HashMap::new();
+    twitter.insert(POSTS.to_string(), UsageLimit { day: Some(17), month: Some(500) });
+    let mut limits = HashMap::new();
+    limits.insert("twitter".to_string(), twitter);
+    limits
+}
+
+fn limits(app: &AppHandle) -> UsageLimits {
+    settings::get_setting(app, LIMITS_SETTING).unwrap_or_else(default_limits)
+}
+
+fn periods() -> [(&'static str, String); 2] {
+    let now = Utc::now();
+    [
+        ("day", now.format("%Y-%m-%d").to_string()),
+        ("month", now.format("%Y-%m").to_string()),
+    ]
+}
+
+fn current_count(conn: &Connection, platform: &str, account_id: &str, metric: &str, period: &str, start: &str) -> i64 {
+    conn.query_row(
+        "SELECT count FROM usage_counters
+         WHERE platform = ?1 AND account_id = ?2 AND metric = ?3 AND period = ?4 AND period_start = ?5",
+        rusqlite::params![platform, account_id, metric, period, start],
+        |row| row.get(0),
+    )
+    .unwrap_or(0)
+}
+
+/// Add `amount` to today's and this month's counter.
+pub(crate) fn record(conn: &Connection, platform: &str, account_id: &str, metric: &str, amount: i64) {
+    if amount <= 0 {
+        return;
+    }
+    let now = Utc::now().to_rfc3339();
+    for (period, start) in periods() {
+        conn.execute(
+            "INSERT INTO usage_counters (platform, account_id, metric, period, period_start, count, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+             ON CONFLICT (platform, account_id, metric, period, period_start)
+             DO UPDATE SET count = count + excluded.count, updated_at = excluded.updated_at",
+            rusqlite::params![platform, account_id, metric, period, start, amount, now],
+        )
+        .ok();
+    }
+}
+
+/// What publishing one post costs: `posts` items (tweets in a thread), plus
+/// an email per subscriber when a newsletter post is actually sent.
+pub(crate) fn publish_usage(
+    conn: &Connection,
+    platform: &str,
+    account_id: &str,
+    publication_id: Option<&str>,
+    status: &str,
+    posts: i64,
+) -> Vec<(&'static str, i64)> {
+    let mut usage = vec![(POSTS, posts)];
+    if NEWSLETTER_PLATFORMS.contains(&platform) && status != "draft" {
+        let recipients: Option<i64> = publication_id.and_then(|pub_id| {
+            conn.query_row(
+                "SELECT subscriber_count FROM publications
+                 WHERE platform = ?1 AND account_id = ?2 AND publication_id = ?3",
+                rusqlite::params![platform, account_id, pub_id],
+                |row| row.get(0),
+            )
+            .ok()
+            .flatten()
+        });
+        if let Some(recipients) = recipients {
+            usage.push((EMAILS_SENT, recipients));
+        }
+    }
+    usage
+}
+
+/// Refuse `planned` usage that would take the account past a soft limit.
+pub(crate) fn check_limits(
+    app: &AppHandle,
+    conn: &Connection,
+    platform: &str,
+    account_id: &str,
+    planned: &[(&str, i64)],
+) -> Result<(), AppError> {
+    let limits = limits(app);
+    let Some(platform_limits) = limits.get(platform) else { return Ok(()) };
+    for (metric, amount) in planned {
+        let Some(limit) = platform_limits.get(*metric) else { continue };
+        for (period, start) in periods() {
+            let cap = if period == "day" { limit.day } else { limit.month };
+            let Some(cap) = cap else { continue };
+            let used = current_count(conn, platform, account_id, metric, period, &start);
+            if used + amount > cap {
+                let mut err = AppError::new(
+                    ErrorKind::RateLimited,
+                    format!(
+                        "{} {} limit reached for this {}: {} of {} used{}",
+                        platform,
+                        metric.replace('_', " "),
+                        period,
+                        used,
+                        cap,
+                        if *amount > 1 { format!(", {} more needed", amount) } else { String::new() },
+                    ),
+                )
+                .with_platform(platform);
+                // Waiting for the next period, not a quick retry
+                err.retryable = false;
+                return Err(err);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Count one platform command against the account's API calls.
+pub(crate) fn record_api_call(app: &AppHandle, platform: &str, account_id: &str) {
+    if let Ok(conn) = db::get_db(app) {
+        record(&conn, platform, account_id, API_CALLS, 1);
+    }
+}
+
+/// Today's and this month's usage for every counted metric, with the
+/// limits that apply, for the settings screen.
+#[tauri::command]
+pub async fn get_platform_usage(app: AppHandle, platform: Option<String>) -> Result<Vec<PlatformUsage>, AppError> {
+    let limits = limits(&app);
+    let conn = db::get_db(&app)?;
+    let [(_, today), (_, month)] = periods();
+
+    let mut stmt = conn.prepare(
+        "SELECT platform, account_id, metric,
+                COALESCE(SUM(CASE WHEN period = 'day' AND period_start = ?1 THEN count END), 0),
+                COALESCE(SUM(CASE WHEN period = 'month' AND period_start = ?2 THEN count END), 0)
+         FROM usage_counters
+         WHERE (?3 IS NULL OR platform = ?3) AND period_start IN (?1, ?2)
+         GROUP BY platform, account_id, metric
+         ORDER BY platform, account_id, metric",
+    )?;
+    let rows = stmt.query_map(rusqlite::params![today, month, platform], |row| {
+        Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get::<_, String>(2)?, row.get(3)?, row.get(4)?))
+    })?;
+
+    Ok(rows
+        .filter_map(|r| r.ok())
+        .map(|(platform, account_id, metric, today, this_month)| {
+            let limit = limits.get(&platform).and_then(|l| l.get(&metric)).cloned().unwrap_or_default();
+            PlatformUsage {
+                platform,
+                account_id,
+                metric,
+                today,
+                this_month,
+                day_limit: limit.day,
+                month_limit: limit.month,
+            }
+        })
+        .collect())
+}
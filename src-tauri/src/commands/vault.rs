@@ -0,0 +1,545 @@
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter};
+
+use crate::commands::export::{self, DocumentMeta};
+use crate::commands::{links, settings, storage, windows, workspaces};
+use crate::db;
+use crate::error::AppError;
+use crate::permissions::{self, Permission};
+use crate::text;
+
+// ─── Vault folder sync ──────────────────────────────────────────
+//
+// Opt-in mirror of documents as Markdown files in a folder (an Obsidian
+// vault or anything else a Markdown editor opens). Each file carries the
+// document id in its front matter, and vault_files remembers the file's
+// hash and the document version at the last sync, so each pass can tell
+// which side changed:
+//
+//   app changed        → the file is rewritten
+//   file changed       → the edit is saved as a new document version
+//   both changed       → the app's text is snapshotted first, then the
+//                        file's edit is applied, so both stay in history
+//   new .md file       → imported as a draft
+//   document deleted   → its file is removed, unless edited since
+//
+// The folder is polled rather than watched. A mapped file is only read
+// once its modification time differs from the last sync; new files are
+// read each pass to look for a moved document's id.
+
+pub(crate) const VAULT_SETTING: &str = "vault_sync";
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Keeps the poller and `sync_vault_now` from importing the same file twice
+static SYNC_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct VaultSyncConfig {
+    pub enabled: bool,
+    pub folder: Option<String>,
+    /// Only mirror this project's documents, and import new files into it
+    pub project_id: Option<String>,
+    /// Workspace mirrored and imported into, fixed when the config is saved
+    /// so switching workspaces doesn't mix another one into the folder
+    pub workspace_id: Option<String>,
+}
+
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct VaultSyncReport {
+    /// Files written or rewritten from the app
+    pub written: u32,
+    /// Documents updated from edited files
+    pub updated: u32,
+    /// New files imported as drafts
+    pub imported: u32,
+    /// Files removed after their document was deleted
+    pub removed: u32,
+    /// Documents edited on both sides since the last sync
+    pub conflicts: u32,
+}
+
+impl VaultSyncReport {
+    fn is_empty(&self) -> bool {
+        self.written + self.updated + self.imported + self.removed + self.conflicts == 0
+    }
+}
+
+struct VaultFile {
+    document_id: String,
+    path: String,
+    content_hash: String,
+    document_version: i64,
+    modified_ms: i64,
+}
+
+struct VaultDocument {
+    title: String,
+    html: String,
+    status: String,
+    version: i64,
+}
+
+/// The workspace a config syncs; configs saved before workspaces use the
+/// active one.
+fn vault_workspace(app: &AppHandle, config: &VaultSyncConfig) -> String {
+    config.workspace_id.clone().unwrap_or_else(|| workspaces::current(app))
+}
+
+fn vault_config(app: &AppHandle) -> VaultSyncConfig {
+    settings::get_setting(app, VAULT_SETTING).unwrap_or_default()
+}
+
+fn content_hash(bytes: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(bytes))
+}
+
+fn modified_ms(path: &Path) -> i64 {
+    std::fs::metadata(path)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or(0)
+}
+
+/// `id:` from a file's front matter.
+fn front_matter_id(markdown: &str) -> Option<String> {
+    let rest = markdown.strip_prefix("---\n").or_else(|| markdown.strip_prefix("---\r\n"))?;
+    let end = rest.find("\n---")?;
+    rest[..end].lines().find_map(|line| {
+        let id = line.strip_prefix("id:")?.trim().trim_matches(['"', '\'']);
+        (!id.is_empty()).then(|| id.to_string())
+    })
+}
+
+/// `markdown` with `id` set in its front matter, leaving the rest as the
+/// user wrote it.
+fn with_front_matter_id(markdown: &str, id: &str) -> String {
+    for opener in ["---\n", "---\r\n"] {
+        let Some(rest) = markdown.strip_prefix(opener) else { continue };
+        let Some(end) = rest.find("\n---") else { continue };
+        let fields: Vec<&str> = rest[..end].lines().filter(|line| !line.starts_with("id:")).collect();
+        let mut front = format!("id: {}", id);
+        for field in fields {
+            front.push('\n');
+            front.push_str(field);
+        }
+        return format!("{}{}{}", opener, front, &rest[end..]);
+    }
+    format!("---\nid: {}\n---\n\n{}", id, markdown)
+}
+
+fn vault_markdown(id: &str, doc: &VaultDocument) -> String {
+    format!(
+        "---\ntitle: {}\nid: {}\nstatus: {}\n---\n\n{}\n",
+        serde_json::to_string(doc.title.trim()).unwrap_or_else(|_| "\"\"".to_string()),
+        id,
+        doc.status,
+        export::html_to_markdown(&doc.html)
+    )
+}
+
+/// File name for a new document, unique among `taken` (lowercased).
+fn file_name_for(title: &str, taken: &HashSet<String>) -> String {
+    let mut stem: String = title
+        .trim()
+        .chars()
+        .map(|c| if c.is_alphanumeric() || c == ' ' || c == '-' || c == '_' { c } else { ' ' })
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+    stem = stem.chars().take(80).collect::<String>().trim().to_string();
+    if stem.is_empty() {
+        stem = "Untitled".to_string();
+    }
+    let mut name = format!("{}.md", stem);
+    let mut n = 2;
+    while taken.contains(&name.to_lowercase()) {
+        name = format!("{} {}.md", stem, n);
+        n += 1;
+    }
+    name
+}
+
+/// Relative paths ('/'-separated) of the Markdown files under `root`,
+/// skipping hidden folders such as .obsidian and .trash.
+fn markdown_files(root: &Path) -> Vec<String> {
+    let mut files = Vec::new();
+    let mut dirs = vec![root.to_path_buf()];
+    while let Some(dir) = dirs.pop() {
+        let Ok(entries) = std::fs::read_dir(&dir) else { continue };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let name = entry.file_name().to_string_lossy().to_string();
+            if name.starts_with('.') {
+                continue;
+            }
+            if path.is_dir() {
+                dirs.push(path);
+            } else if name.to_lowercase().ends_with(".md") {
+                if let Ok(relative) = path.strip_prefix(root) {
+                    let parts: Vec<String> =
+                        relative.components().map(|c| c.as_os_str().to_string_lossy().to_string()).collect();
+                    files.push(parts.join("/"));
+                }
+            }
+        }
+    }
+    files.sort();
+    files
+}
+
+fn load_vault_files(conn: &Connection) -> Result<Vec<VaultFile>, AppError> {
+    let mut stmt =
+        conn.prepare("SELECT document_id, path, content_hash, document_version, modified_ms FROM vault_files")?;
+    let rows = stmt.query_map([], |row| {
+        Ok(VaultFile {
+            document_id: row.get(0)?,
+            path: row.get(1)?,
+            content_hash: row.get(2)?,
+            document_version: row.get(3)?,
+            modified_ms: row.get(4)?,
+        })
+    })?;
+    Ok(rows.filter_map(|r| r.ok()).collect())
+}
+
+fn load_document(conn: &Connection, id: &str) -> Option<VaultDocument> {
+    conn.query_row(
+        "SELECT title, html_content, status, COALESCE(version, 0) FROM documents WHERE id = ?1",
+        rusqlite::params![id],
+        |row| Ok(VaultDocument { title: row.get(0)?, html: row.get(1)?, status: row.get(2)?, version: row.get(3)? }),
+    )
+    .ok()
+}
+
+fn remember(conn: &Connection, root: &Path, document_id: &str, path: &str, hash: &str, version: i64) -> Result<(), AppError> {
+    conn.execute(
+        "INSERT INTO vault_files (document_id, path, content_hash, document_version, modified_ms, synced_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+         ON CONFLICT(document_id) DO UPDATE SET path = excluded.path, content_hash = excluded.content_hash,
+             document_version = excluded.document_version, modified_ms = excluded.modified_ms, synced_at = excluded.synced_at",
+        rusqlite::params![
+            document_id,
+            path,
+            hash,
+            version,
+            modified_ms(&root.join(path)),
+            chrono::Utc::now().to_rfc3339()
+        ],
+    )?;
+    Ok(())
+}
+
+fn write_file(conn: &Connection, root: &Path, id: &str, path: &str, doc: &VaultDocument) -> Result<(), AppError> {
+    let markdown = vault_markdown(id, doc);
+    let full = root.join(path);
+    if let Some(parent) = full.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    std::fs::write(&full, &markdown).map_err(|e| format!("Failed to write {}: {}", full.display(), e))?;
+    remember(conn, root, id, path, &content_hash(markdown.as_bytes()), doc.version)
+}
+
+fn snapshot(conn: &Connection, app: &AppHandle, id: &str, title: &str, html: &str, version: i64) {
+    let now = chrono::Utc::now().to_rfc3339();
+    conn.execute(
+        "INSERT INTO document_versions (document_id, title, content, html_content, version, created_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        rusqlite::params![id, title, export::html_to_plain_text(html), html, version, now],
+    )
+    .ok();
+    conn.execute(
+        "DELETE FROM document_versions WHERE document_id = ?1 AND id NOT IN (SELECT id FROM document_versions WHERE document_id = ?1 ORDER BY version DESC LIMIT ?2)",
+        rusqlite::params![id, storage::max_versions(app)],
+    )
+    .ok();
+}
+
+/// Save an edited file's text as the document's next version. With
+/// `conflict`, the app's unsynced text is snapshotted first.
+fn apply_file_edit(
+    conn: &Connection,
+    app: &AppHandle,
+    root: &Path,
+    file: &VaultFile,
+    doc: &VaultDocument,
+    markdown: &str,
+    conflict: bool,
+) -> Result<i64, AppError> {
+    if conflict {
+        snapshot(conn, app, &file.document_id, &doc.title, &doc.html, doc.version);
+    }
+    let import = export::parse_markdown(markdown);
+    let title = import.title.unwrap_or_else(|| doc.title.clone());
+    let html = import.html_content;
+    let version = doc.version + 1;
+    let word_count = text::count_html(&html, text::count_mode(app)) as i64;
+    conn.execute(
+        "UPDATE documents SET title = ?1, content = ?2, html_content = ?3, word_count = ?4, version = ?5, updated_at = ?6
+         WHERE id = ?7",
+        rusqlite::params![
+            title,
+            export::html_to_plain_text(&html),
+            html,
+            word_count,
+            version,
+            chrono::Utc::now().to_rfc3339(),
+            file.document_id
+        ],
+    )?;
+    snapshot(conn, app, &file.document_id, &title, &html, version);
+    links::index_document(conn, &file.document_id, &html);
+    remember(conn, root, &file.document_id, &file.path, &content_hash(markdown.as_bytes()), version)?;
+    db::log_activity(
+        conn,
+        if conflict { "vault.conflict" } else { "vault.updated" },
+        "document",
+        Some(&file.document_id),
+        Some(&file.path),
+    );
+    windows::document_changed(app, &file.document_id, "saved", Some(version), None);
+    Ok(version)
+}
+
+/// Reconcile mapped files and in-scope documents. Returns the unmapped
+/// files left to import.
+fn sync_known(
+    conn: &Connection,
+    app: &AppHandle,
+    root: &Path,
+    config: &VaultSyncConfig,
+    report: &mut VaultSyncReport,
+) -> Result<Vec<String>, AppError> {
+    let on_disk = markdown_files(root);
+    let mut known = load_vault_files(conn)?;
+
+    // A mapped file that's gone but whose id turns up in another file was
+    // renamed or moved
+    let known_paths: HashSet<String> = known.iter().map(|f| f.path.clone()).collect();
+    let mut unmapped: Vec<String> = Vec::new();
+    for path in on_disk.iter().filter(|p| !known_paths.contains(*p)) {
+        let id = std::fs::read_to_string(root.join(path)).ok().and_then(|m| front_matter_id(&m));
+        let moved = id.and_then(|id| {
+            known.iter_mut().find(|f| f.document_id == id && !root.join(&f.path).exists())
+        });
+        match moved {
+            Some(file) => {
+                conn.execute(
+                    "UPDATE vault_files SET path = ?1 WHERE document_id = ?2",
+                    rusqlite::params![path, file.document_id],
+                )?;
+                file.path = path.clone();
+                // Force a content check on the new path
+                file.modified_ms = -1;
+            }
+            None => unmapped.push(path.clone()),
+        }
+    }
+
+    for file in &known {
+        let full = root.join(&file.path);
+        let Some(doc) = load_document(conn, &file.document_id) else {
+            // Deleted in the app: remove the mirror unless it has edits of its own
+            if let Ok(bytes) = std::fs::read(&full) {
+                if content_hash(&bytes) == file.content_hash {
+                    std::fs::remove_file(&full).ok();
+                    report.removed += 1;
+                } else {
+                    unmapped.push(file.path.clone());
+                }
+            }
+            conn.execute("DELETE FROM vault_files WHERE document_id = ?1", rusqlite::params![file.document_id])?;
+            continue;
+        };
+        let app_changed = doc.version != file.document_version;
+        if full.is_file() && modified_ms(&full) == file.modified_ms {
+            // Untouched since the last pass, so there's nothing to read
+            if app_changed {
+                write_file(conn, root, &file.document_id, &file.path, &doc)?;
+                report.written += 1;
+            }
+            continue;
+        }
+        let Ok(bytes) = std::fs::read(&full) else {
+            write_file(conn, root, &file.document_id, &file.path, &doc)?;
+            report.written += 1;
+            continue;
+        };
+        let file_changed = content_hash(&bytes) != file.content_hash;
+        match (file_changed, app_changed) {
+            (false, false) => {
+                // Touched but not edited: remember the new time
+                remember(conn, root, &file.document_id, &file.path, &file.content_hash, file.document_version)?;
+            }
+            (false, true) => {
+                write_file(conn, root, &file.document_id, &file.path, &doc)?;
+                report.written += 1;
+            }
+            (true, conflict) => {
+                let markdown = String::from_utf8_lossy(&bytes);
+                apply_file_edit(conn, app, root, file, &doc, &markdown, conflict)?;
+                report.updated += 1;
+                if conflict {
+                    report.conflicts += 1;
+                }
+            }
+        }
+    }
+
+    // Documents not mirrored yet
+    let mut taken: HashSet<String> = on_disk.iter().map(|p| p.to_lowercase()).collect();
+    let mut stmt = conn.prepare(
+        "SELECT id, title, html_content, status, COALESCE(version, 0) FROM documents
+         WHERE id NOT IN (SELECT document_id FROM vault_files) AND workspace_id = ?2
+           AND (?1 IS NULL OR project_id = ?1)
+         ORDER BY created_at",
+    )?;
+    let missing: Vec<(String, VaultDocument)> = stmt
+        .query_map(rusqlite::params![config.project_id, vault_workspace(app, config)], |row| {
+            Ok((
+                row.get(0)?,
+                VaultDocument { title: row.get(1)?, html: row.get(2)?, status: row.get(3)?, version: row.get(4)? },
+            ))
+        })?
+        .filter_map(|r| r.ok())
+        .collect();
+    for (id, doc) in missing {
+        let name = file_name_for(&doc.title, &taken);
+        taken.insert(name.to_lowercase());
+        write_file(conn, root, &id, &name, &doc)?;
+        report.written += 1;
+    }
+    Ok(unmapped)
+}
+
+/// One sync pass. Does nothing while sync is off.
+pub(crate) fn sync(app: &AppHandle) -> Result<VaultSyncReport, AppError> {
+    let config = vault_config(app);
+    let mut report = VaultSyncReport::default();
+    let Some(folder) = config.folder.as_deref().filter(|f| config.enabled && !f.trim().is_empty()) else {
+        return Ok(report);
+    };
+    let _guard = SYNC_LOCK.lock().map_err(|_| "Vault sync state unavailable".to_string())?;
+    let root = PathBuf::from(folder);
+    std::fs::create_dir_all(&root).map_err(|e| format!("Failed to create {}: {}", folder, e))?;
+
+    let unmapped = {
+        let conn = db::get_db(app)?;
+        sync_known(&conn, app, &root, &config, &mut report)?
+    };
+
+    // New files become drafts; their front matter gets the new id so
+    // renames are followed from here on
+    for path in unmapped {
+        let full = root.join(&path);
+        let Ok(markdown) = std::fs::read_to_string(&full) else { continue };
+        let import = export::parse_markdown(&markdown);
+        let fallback = path.rsplit('/').next().unwrap_or(&path).trim_end_matches(".md").to_string();
+        let title = import.title.unwrap_or(fallback);
+        let meta: DocumentMeta =
+            export::create_imported_document(app, &title, &import.html_content, config.project_id.clone(), &path)?;
+        let tagged = with_front_matter_id(&markdown, &meta.id);
+        std::fs::write(&full, &tagged).map_err(|e| format!("Failed to write {}: {}", full.display(), e))?;
+        let conn = db::get_db(app)?;
+        // Imported into the active workspace; move it to the vault's
+        conn.execute(
+            "UPDATE documents SET workspace_id = ?1 WHERE id = ?2",
+            rusqlite::params![vault_workspace(app, &config), meta.id],
+        )?;
+        remember(&conn, &root, &meta.id, &path, &content_hash(tagged.as_bytes()), 1)?;
+        report.imported += 1;
+    }
+
+    if !report.is_empty() {
+        let conn = db::get_db(app)?;
+        db::log_activity(
+            &conn,
+            "vault.synced",
+            "vault",
+            None,
+            Some(&format!(
+                "{} written, {} updated, {} imported, {} removed, {} conflicts",
+                report.written, report.updated, report.imported, report.removed, report.conflicts
+            )),
+        );
+        app.emit("vault:synced", &report).ok();
+    }
+    Ok(report)
+}
+
+/// Poll the vault folder while sync is enabled.
+pub fn start_watcher(app: AppHandle) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(POLL_INTERVAL);
+        loop {
+            interval.tick().await;
+            if let Err(e) = sync(&app) {
+                crate::trace::log("Vault", &format!("Sync failed: {}", e.message));
+            }
+        }
+    });
+}
+
+#[tauri::command]
+pub async fn get_vault_sync_config(app: AppHandle) -> Result<VaultSyncConfig, AppError> {
+    Ok(vault_config(&app))
+}
+
+/// Save the sync settings (owner only, as it picks a folder the app reads
+/// and writes). Pointing sync at a different folder starts that folder's
+/// mirror afresh.
+#[tauri::command]
+pub async fn save_vault_sync_config(app: AppHandle, config: VaultSyncConfig) -> Result<(), AppError> {
+    permissions::require(&app, Permission::ManageCredentials)?;
+    let folder = config.folder.as_deref().map(str::trim).filter(|f| !f.is_empty());
+    if config.enabled && folder.is_none() {
+        return Err(AppError::validation("Choose a folder to sync with"));
+    }
+    if let Some(folder) = folder {
+        if !Path::new(folder).is_absolute() {
+            return Err(AppError::validation("The vault folder must be an absolute path"));
+        }
+    }
+    let previous = vault_config(&app);
+    if previous.folder.as_deref().map(str::trim) != folder {
+        let conn = db::get_db(&app)?;
+        conn.execute("DELETE FROM vault_files", [])?;
+    }
+    let workspace_id = match &config.project_id {
+        Some(project_id) => {
+            let conn = db::get_db(&app)?;
+            conn.query_row(
+                "SELECT workspace_id FROM projects WHERE id = ?1",
+                rusqlite::params![project_id],
+                |row| row.get(0),
+            )
+            .map_err(|_| AppError::not_found(format!("Project '{}' not found", project_id)))?
+        }
+        None => workspaces::current(&app),
+    };
+    let config = VaultSyncConfig { folder: folder.map(str::to_string), workspace_id: Some(workspace_id), ..config };
+    settings::set_setting(&app, VAULT_SETTING, serde_json::to_value(&config)?)?;
+    Ok(())
+}
+
+/// Run a sync pass now instead of waiting for the next poll.
+#[tauri::command]
+pub async fn sync_vault_now(app: AppHandle) -> Result<VaultSyncReport, AppError> {
+    sync(&app)
+}
+
+/// Files currently mirrored, keyed by document id.
+#[tauri::command]
+pub async fn list_vault_files(app: AppHandle) -> Result<HashMap<String, String>, AppError> {
+    let conn = db::get_db(&app)?;
+    Ok(load_vault_files(&conn)?.into_iter().map(|f| (f.document_id, f.path)).collect())
+}
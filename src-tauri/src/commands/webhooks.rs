@@ -0,0 +1,268 @@
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use rusqlite::{Connection, OptionalExtension};
+use serde::Serialize;
+use sha2::Sha256;
+use tauri::AppHandle;
+
+use crate::commands::{audit, windows};
+use crate::db;
+use crate::error::AppError;
+use crate::listener::{self, Request, Response};
+use crate::permissions::{self, Permission};
+
+// ─── Publish webhooks ───────────────────────────────────────────
+//
+// For flows where an outside tool (a review app, a CI job) gives the final
+// go-ahead. Arming a document holds its pending scheduled posts as
+// 'awaiting_approval'; a POST to the webhook's URL on the local listener,
+// signed with its secret, releases them to the scheduler: overdue posts
+// publish right away, later ones at their scheduled time. Each webhook
+// fires once; arming the document again makes a new one.
+//
+// The signature is GitHub's scheme: an `X-Signature-256` header of
+// "sha256=" and the hex HMAC-SHA256 of the raw body.
+
+const SIGNATURE_HEADER: &str = "x-signature-256";
+const ROUTE: &str = "/webhooks/publish/";
+
+type HmacSha256 = Hmac<Sha256>;
+
+#[derive(Debug, Serialize, Clone)]
+pub struct PublishWebhook {
+    pub id: String,
+    pub document_id: String,
+    /// Where the go-ahead is POSTed, from the listener's base URL
+    pub url: String,
+    /// Only returned when the webhook is created
+    pub secret: Option<String>,
+    pub created_at: String,
+    pub triggered_at: Option<String>,
+}
+
+fn webhook_from_row(app: &AppHandle, row: &rusqlite::Row) -> rusqlite::Result<PublishWebhook> {
+    let id: String = row.get(0)?;
+    Ok(PublishWebhook {
+        url: format!("{}{}{}", listener::base_url(app), ROUTE, id),
+        id,
+        document_id: row.get(1)?,
+        secret: None,
+        created_at: row.get(2)?,
+        triggered_at: row.get(3)?,
+    })
+}
+
+/// Whether `signature` ("sha256=<hex>") is `body` signed with `secret`.
+fn verify_signature(secret: &str, body: &[u8], signature: &str) -> bool {
+    let Some(hex) = signature.trim().strip_prefix("sha256=") else {
+        return false;
+    };
+    if hex.len() != 64 || !hex.is_ascii() {
+        return false;
+    }
+    let mut expected = Vec::with_capacity(32);
+    for i in (0..hex.len()).step_by(2) {
+        match u8::from_str_radix(&hex[i..i + 2], 16) {
+            Ok(byte) => expected.push(byte),
+            Err(_) => return false,
+        }
+    }
+    let Ok(mut mac) = HmacSha256::new_from_slice(secret.as_bytes()) else {
+        return false;
+    };
+    mac.update(body);
+    mac.verify_slice(&expected).is_ok()
+}
+
+/// Hand the document's held posts to the scheduler: posts whose time has
+/// passed go out now, later ones keep their time. Returns their ids, or
+/// why the webhook can't fire.
+fn release(conn: &Connection, webhook_id: &str, body: &[u8], signature: &str) -> Result<(String, Vec<String>), Response> {
+    let error = |status: u16, message: &str| Response::json(status, &serde_json::json!({ "error": message }));
+    let webhook: Option<(String, String, Option<String>)> = conn
+        .query_row(
+            "SELECT document_id, secret, triggered_at FROM publish_webhooks WHERE id = ?1",
+            rusqlite::params![webhook_id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )
+        .optional()
+        .map_err(|e| error(500, &e.to_string()))?;
+    let Some((document_id, secret, triggered_at)) = webhook else {
+        return Err(error(404, "Unknown webhook"));
+    };
+    // Checked before anything about the webhook's state is given away
+    if !verify_signature(&secret, body, signature) {
+        return Err(error(401, "Invalid signature"));
+    }
+    if triggered_at.is_some() {
+        return Err(error(410, "This webhook has already been used"));
+    }
+
+    let mut stmt = conn
+        .prepare("SELECT id FROM scheduled_posts WHERE document_id = ?1 AND status = 'awaiting_approval'")
+        .map_err(|e| error(500, &e.to_string()))?;
+    let post_ids: Vec<String> = stmt
+        .query_map(rusqlite::params![document_id], |row| row.get(0))
+        .map_err(|e| error(500, &e.to_string()))?
+        .filter_map(|r| r.ok())
+        .collect();
+    if post_ids.is_empty() {
+        return Err(error(409, "No posts are waiting for approval"));
+    }
+    let now = Utc::now().to_rfc3339();
+    conn.execute(
+        "UPDATE scheduled_posts SET status = 'pending', scheduled_at = MAX(COALESCE(scheduled_at, ?1), ?1), updated_at = ?1
+         WHERE document_id = ?2 AND status = 'awaiting_approval'",
+        rusqlite::params![now, document_id],
+    )
+    .map_err(|e| error(500, &e.to_string()))?;
+    conn.execute("UPDATE publish_webhooks SET triggered_at = ?1 WHERE id = ?2", rusqlite::params![now, webhook_id])
+        .map_err(|e| error(500, &e.to_string()))?;
+    db::log_activity(
+        conn,
+        "document.publish_approved",
+        "document",
+        Some(&document_id),
+        Some(&format!("Released {} post(s) by webhook", post_ids.len())),
+    );
+    Ok((document_id, post_ids))
+}
+
+/// Listener route: POST /webhooks/publish/<id>.
+pub(crate) async fn handle(app: &AppHandle, request: &Request) -> Option<Response> {
+    let webhook_id = request.path.strip_prefix(ROUTE)?;
+    if request.method != "POST" {
+        return Some(Response::json(400, &serde_json::json!({ "error": "Use POST" })));
+    }
+    let signature = request.headers.get(SIGNATURE_HEADER).map(String::as_str).unwrap_or("");
+    let released = match db::get_db(app) {
+        Ok(conn) => release(&conn, webhook_id, &request.body, signature),
+        Err(e) => Err(Response::json(500, &serde_json::json!({ "error": e }))),
+    };
+    Some(match released {
+        Ok((document_id, post_ids)) => {
+            windows::schedule_changed(app, Some(&document_id), post_ids.clone(), "approved");
+            Response::json(202, &serde_json::json!({ "document_id": document_id, "released": post_ids }))
+        }
+        Err(response) => response,
+    })
+}
+
+/// Hold the document's pending scheduled posts until the returned webhook
+/// is called. Replaces the document's previous webhook; the secret is
+/// only shown here. Posts scheduled afterwards aren't held.
+#[tauri::command]
+pub async fn create_publish_webhook(app: AppHandle, document_id: String) -> Result<PublishWebhook, AppError> {
+    permissions::require(&app, Permission::ManageCredentials)?;
+    let conn = db::get_db(&app)?;
+    let now = Utc::now().to_rfc3339();
+    let held = conn.execute(
+        "UPDATE scheduled_posts SET status = 'awaiting_approval', updated_at = ?1
+         WHERE document_id = ?2 AND status = 'pending'",
+        rusqlite::params![now, document_id],
+    )?;
+    let waiting: i64 = conn.query_row(
+        "SELECT COUNT(*) FROM scheduled_posts WHERE document_id = ?1 AND status = 'awaiting_approval'",
+        rusqlite::params![document_id],
+        |row| row.get(0),
+    )?;
+    if waiting == 0 {
+        return Err(AppError::validation("Schedule the document before holding it for a webhook"));
+    }
+
+    let id = uuid::Uuid::new_v4().simple().to_string();
+    let secret = format!("{}{}", uuid::Uuid::new_v4().simple(), uuid::Uuid::new_v4().simple());
+    conn.execute(
+        "INSERT INTO publish_webhooks (id, document_id, secret, created_at) VALUES (?1, ?2, ?3, ?4)
+         ON CONFLICT(document_id) DO UPDATE SET id = excluded.id, secret = excluded.secret,
+             created_at = excluded.created_at, triggered_at = NULL",
+        rusqlite::params![id, document_id, secret, now],
+    )?;
+    audit::record(&app, &conn, "webhook.created", "document", Some(&document_id), None)?;
+    if held > 0 {
+        windows::schedule_changed(&app, Some(&document_id), Vec::new(), "awaiting_approval");
+    }
+    Ok(PublishWebhook {
+        url: format!("{}{}{}", listener::base_url(&app), ROUTE, id),
+        id,
+        document_id,
+        secret: Some(secret),
+        created_at: now,
+        triggered_at: None,
+    })
+}
+
+/// Webhooks, newest first; `document_id` narrows them to one document.
+#[tauri::command]
+pub async fn list_publish_webhooks(app: AppHandle, document_id: Option<String>) -> Result<Vec<PublishWebhook>, AppError> {
+    let conn = db::get_db(&app)?;
+    let mut stmt = conn.prepare(
+        "SELECT id, document_id, created_at, triggered_at FROM publish_webhooks
+         WHERE ?1 IS NULL OR document_id = ?1 ORDER BY created_at DESC",
+    )?;
+    let rows = stmt.query_map(rusqlite::params![document_id], |row| webhook_from_row(&app, row))?;
+    Ok(rows.filter_map(|r| r.ok()).collect())
+}
+
+/// Remove a webhook. Posts it still held go back to publishing at their
+/// scheduled time.
+#[tauri::command]
+pub async fn delete_publish_webhook(app: AppHandle, id: String) -> Result<(), AppError> {
+    permissions::require(&app, Permission::ManageCredentials)?;
+    let conn = db::get_db(&app)?;
+    let document_id: Option<String> = conn
+        .query_row("SELECT document_id FROM publish_webhooks WHERE id = ?1", rusqlite::params![id], |row| row.get(0))
+        .optional()?;
+    let Some(document_id) = document_id else {
+        return Err(AppError::not_found(format!("Webhook '{}' not found", id)));
+    };
+    conn.execute("DELETE FROM publish_webhooks WHERE id = ?1", rusqlite::params![id])?;
+    let released = conn.execute(
+        "UPDATE scheduled_posts SET status = 'pending', updated_at = ?1
+         WHERE document_id = ?2 AND status = 'awaiting_approval'",
+        rusqlite::params![Utc::now().to_rfc3339(), document_id],
+    )?;
+    if released > 0 {
+        windows::schedule_changed(&app, Some(&document_id), Vec::new(), "rescheduled");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sign(secret: &str, body: &[u8]) -> String {
+        let mut mac = HmacSha256::new_from_slice(secret.as_bytes()).unwrap();
+        mac.update(body);
+        let hex: String = mac.finalize().into_bytes().iter().map(|b| format!("{:02x}", b)).collect();
+        format!("sha256={}", hex)
+    }
+
+    #[test]
+    fn accepts_a_matching_signature() {
+        let signature = sign("secret", b"{}");
+        assert!(verify_signature("secret", b"{}", &signature));
+        assert!(verify_signature("secret", b"{}", &format!(" {} ", signature)));
+        assert!(!verify_signature("other", b"{}", &signature));
+        assert!(!verify_signature("secret", b"{ }", &signature));
+    }
+
+    #[test]
+    fn rejects_bad_hex() {
+        let signature = sign("secret", b"{}");
+        let bad = format!("sha256=zz{}", &signature["sha256=".len() + 2..]);
+        assert!(!verify_signature("secret", b"{}", &bad));
+        assert!(!verify_signature("secret", b"{}", signature.trim_start_matches("sha256=")));
+    }
+
+    #[test]
+    fn rejects_the_wrong_length() {
+        let signature = sign("secret", b"{}");
+        assert!(!verify_signature("secret", b"{}", &signature[..signature.len() - 2]));
+        assert!(!verify_signature("secret", b"{}", &format!("{}00", signature)));
+        assert!(!verify_signature("secret", b"{}", "sha256="));
+        // 64 bytes but not 64 hex digits; must not split a character
+        assert!(!verify_signature("secret", b"{}", &format!("sha256={}é", "0".repeat(62))));
+    }
+}
@@ -0,0 +1,100 @@
+use crate::db;
+use crate::error::AppError;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+
+// ─── Document windows ───────────────────────────────────────────
+//
+// A document can be opened in its own window next to the main one. Every
+// window receives "document:changed" and "schedule:changed", which the
+// commands that touch a document or its schedule broadcast, so a save in
+// one window shows up in the others instead of being clobbered later.
+
+pub const DOCUMENT_CHANGED: &str = "document:changed";
+pub const SCHEDULE_CHANGED: &str = "schedule:changed";
+
+#[derive(Debug, Serialize, Clone)]
+pub struct DocumentChange {
+    pub document_id: String,
+    /// "saved" | "autosaved" | "restored" | "status" | "moved" | "deleted" | "archived" | "unarchived" | "replaced"
+    pub change: String,
+    /// Edit version after the change, when it has one
+    pub version: Option<i64>,
+    pub status: Option<String>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ScheduleChange {
+    pub document_id: Option<String>,
+    pub post_ids: Vec<String>,
+    /// "scheduled" | "cancelled" | "rescheduled" | "publish_now"
+    pub change: String,
+}
+
+pub(crate) fn document_changed(app: &AppHandle, document_id: &str, change: &str, version: Option<i64>, status: Option<&str>) {
+    let _ = app.emit(
+        DOCUMENT_CHANGED,
+        DocumentChange {
+            document_id: document_id.to_string(),
+            change: change.to_string(),
+            version,
+            status: status.map(str::to_string),
+        },
+    );
+}
+
+pub(crate) fn schedule_changed(app: &AppHandle, document_id: Option<&str>, post_ids: Vec<String>, change: &str) {
+    let _ = app.emit(
+        SCHEDULE_CHANGED,
+        ScheduleChange {
+            document_id: document_id.map(str::to_string),
+            post_ids,
+            change: change.to_string(),
+        },
+    );
+}
+
+/// Window label for a document. Labels only allow a small character set,
+/// which document ids (UUIDs) already fit.
+fn window_label(document_id: &str) -> Result<String, AppError> {
+    if document_id.is_empty() || !document_id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+        return Err(AppError::validation(format!("Invalid document id '{}'", document_id)));
+    }
+    Ok(format!("document-{}", document_id))
+}
+
+/// Open the document in a dedicated window, or focus the one already
+/// showing it. Returns the window label.
+#[tauri::command]
+pub async fn open_document_window(app: AppHandle, document_id: String) -> Result<String, AppError> {
+    let label = window_label(&document_id)?;
+    let title: String = {
+        let conn = db::get_db(&app)?;
+        conn.query_row(
+            "SELECT title FROM documents WHERE id = ?1",
+            rusqlite::params![document_id],
+            |row| row.get(0),
+        )
+        .map_err(|_| AppError::not_found(format!("Document '{}' not found", document_id)))?
+    };
+
+    if let Some(window) = app.get_webview_window(&label) {
+        let _ = window.unminimize();
+        window
+            .set_focus()
+            .map_err(|e| format!("Failed to focus window: {}", e))?;
+        return Ok(label);
+    }
+
+    let url = tauri::WebviewUrl::App(format!("index.html?document={}", document_id).into());
+    tauri::WebviewWindowBuilder::new(&app, &label, url)
+        .title(if title.is_empty() { "Untitled".to_string() } else { title })
+        .inner_size(1000.0, 800.0)
+        .min_inner_size(700.0, 500.0)
+        .decorations(false)
+        .center()
+        .build()
+        .map_err(|e| format!("Failed to open window: {}", e))?;
+
+    Ok(label)
+}
@@ -0,0 +1,165 @@
+use chrono::Utc;
+use rusqlite::{Connection, OptionalExtension};
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+use tauri_plugin_store::StoreExt;
+
+use crate::commands::credentials::StoredCredential;
+use crate::commands::{audit, settings};
+use crate::db;
+use crate::error::AppError;
+
+// ─── Workspaces ─────────────────────────────────────────────────
+//
+// One install can run several newsletters. Documents, projects,
+// subscribers, revenue and connected accounts each belong to a workspace,
+// and lists, searches and dashboards only cover the active one. The active
+// workspace lives in settings.json under `workspace`; with nothing stored
+// it's 'default', which holds everything from before workspaces existed.
+// Records opened by id still work from any workspace, and the scheduler
+// publishes every workspace's posts.
+
+pub const WORKSPACE_SETTING: &str = "workspace";
+pub const DEFAULT_WORKSPACE: &str = "default";
+/// Emitted with the new workspace's id after a switch
+pub const WORKSPACE_CHANGED: &str = "workspace:changed";
+
+#[derive(Debug, Serialize, Clone)]
+pub struct Workspace {
+    pub id: String,
+    pub name: String,
+    pub color: String,
+    pub created_at: String,
+    pub updated_at: String,
+    pub active: bool,
+    pub document_count: i64,
+    pub subscriber_count: i64,
+}
+
+const WORKSPACE_COLUMNS: &str = "w.id, w.name, w.color, w.created_at, w.updated_at,
+    (SELECT COUNT(*) FROM documents d WHERE d.workspace_id = w.id),
+    (SELECT COUNT(*) FROM subscribers s WHERE s.workspace_id = w.id)";
+
+fn workspace_from_row(row: &rusqlite::Row, active: &str) -> rusqlite::Result<Workspace> {
+    let id: String = row.get(0)?;
+    Ok(Workspace {
+        active: id == active,
+        id,
+        name: row.get(1)?,
+        color: row.get(2)?,
+        created_at: row.get(3)?,
+        updated_at: row.get(4)?,
+        document_count: row.get(5)?,
+        subscriber_count: row.get(6)?,
+    })
+}
+
+fn get_workspace(conn: &Connection, id: &str, active: &str) -> Result<Option<Workspace>, AppError> {
+    Ok(conn
+        .query_row(
+            &format!("SELECT {} FROM workspaces w WHERE w.id = ?1", WORKSPACE_COLUMNS),
+            rusqlite::params![id],
+            |row| workspace_from_row(row, active),
+        )
+        .optional()?)
+}
+
+/// The active workspace's id.
+pub fn current(app: &AppHandle) -> String {
+    settings::get_setting(app, WORKSPACE_SETTING).unwrap_or_else(|| DEFAULT_WORKSPACE.to_string())
+}
+
+/// What a dashboard query needs to stay within the active workspace.
+/// Analytics snapshots are per platform account, and accounts live in the
+/// credential store rather than the database, so they're matched against
+/// `accounts`, a JSON array of "platform:account_id" for `json_each`.
+#[derive(Debug, Serialize, Clone)]
+pub(crate) struct Scope {
+    pub workspace_id: String,
+    pub accounts: String,
+}
+
+pub(crate) fn scope(app: &AppHandle) -> Scope {
+    let workspace_id = current(app);
+    let accounts: Vec<String> = app
+        .store("credentials.json")
+        .map(|store| {
+            store
+                .entries()
+                .into_iter()
+                .filter_map(|(key, value)| {
+                    let cred = serde_json::from_value::<StoredCredential>(value).ok()?;
+                    (cred.workspace_id == workspace_id).then_some(key)
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    Scope { accounts: serde_json::to_string(&accounts).unwrap_or_else(|_| "[]".to_string()), workspace_id }
+}
+
+/// The workspace a connected account belongs to, for data synced or
+/// imported from it; the active one when the account isn't stored.
+pub(crate) fn of_account(app: &AppHandle, platform: &str, account_id: &str) -> String {
+    app.store("credentials.json")
+        .ok()
+        .and_then(|store| store.get(format!("{}:{}", platform, account_id)))
+        .and_then(|value| serde_json::from_value::<StoredCredential>(value).ok())
+        .map(|cred| cred.workspace_id)
+        .unwrap_or_else(|| current(app))
+}
+
+#[tauri::command]
+pub async fn list_workspaces(app: AppHandle) -> Result<Vec<Workspace>, AppError> {
+    let active = current(&app);
+    let conn = db::get_db(&app)?;
+    let mut stmt =
+        conn.prepare(&format!("SELECT {} FROM workspaces w ORDER BY w.created_at ASC", WORKSPACE_COLUMNS))?;
+    let rows = stmt.query_map([], |row| workspace_from_row(row, &active))?;
+    Ok(rows.filter_map(|r| r.ok()).collect())
+}
+
+/// Add an empty workspace. It becomes active only through
+/// `switch_workspace`.
+#[tauri::command]
+pub async fn create_workspace(app: AppHandle, name: String, color: Option<String>) -> Result<Workspace, AppError> {
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err(AppError::validation("Workspace name cannot be empty"));
+    }
+    let active = current(&app);
+    let conn = db::get_db(&app)?;
+    let taken: bool = conn.query_row(
+        "SELECT EXISTS (SELECT 1 FROM workspaces WHERE name = ?1 COLLATE NOCASE)",
+        rusqlite::params![name],
+        |row| row.get(0),
+    )?;
+    if taken {
+        return Err(AppError::validation(format!("A workspace named '{}' already exists", name)));
+    }
+
+    let id = uuid::Uuid::new_v4().to_string();
+    let now = Utc::now().to_rfc3339();
+    conn.execute(
+        "INSERT INTO workspaces (id, name, color, created_at, updated_at) VALUES (?1, ?2, ?3, ?4, ?4)",
+        rusqlite::params![id, name, color.unwrap_or_else(|| "#7c3aed".to_string()), now],
+    )?;
+    audit::record(&app, &conn, "workspace.created", "workspace", Some(&id), Some(&name))?;
+    get_workspace(&conn, &id, &active)?.ok_or_else(|| AppError::not_found("Workspace vanished after insert"))
+}
+
+/// Make `id` the active workspace. Windows get `workspace:changed` and
+/// reload what they show.
+#[tauri::command]
+pub async fn switch_workspace(app: AppHandle, id: String) -> Result<Workspace, AppError> {
+    let workspace = {
+        let conn = db::get_db(&app)?;
+        let Some(workspace) = get_workspace(&conn, &id, &id)? else {
+            return Err(AppError::not_found(format!("Workspace '{}' not found", id)));
+        };
+        db::log_activity(&conn, "workspace.switched", "workspace", Some(&id), Some(&workspace.name));
+        workspace
+    };
+    settings::set_setting(&app, WORKSPACE_SETTING, serde_json::json!(id))?;
+    let _ = app.emit(WORKSPACE_CHANGED, &id);
+    Ok(workspace)
+}
@@ -0,0 +1,78 @@
+use crate::commands::offline;
+use crate::commands::settings;
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+
+// ─── Connectivity monitor ───────────────────────────────────────
+//
+// Probes a lightweight endpoint every 15 seconds. While offline, publish
+// and sync commands queue into pending_operations (see commands/offline.rs)
+// and the scheduler leaves due posts pending; once a probe succeeds
+// again the queue is flushed.
+
+const CHECK_URL_SETTING: &str = "connectivity.check_url";
+const DEFAULT_CHECK_URL: &str = "https://www.gstatic.com/generate_204";
+
+static ONLINE: AtomicBool = AtomicBool::new(true);
+
+#[derive(Clone, Serialize)]
+struct ConnectivityEvent {
+    online: bool,
+}
+
+pub fn is_online() -> bool {
+    ONLINE.load(Ordering::SeqCst)
+}
+
+/// Record the connectivity state, emitting "connectivity:changed" and
+/// flushing the offline queue when it flips back online.
+pub fn set_online(app: &AppHandle, online: bool) {
+    let was = ONLINE.swap(online, Ordering::SeqCst);
+    if was == online {
+        return;
+    }
+    crate::trace::log("Connectivity", if online { "Back online" } else { "Offline" });
+    let _ = app.emit("connectivity:changed", ConnectivityEvent { online });
+    if online {
+        let app = app.clone();
+        tokio::spawn(async move {
+            offline::flush_queue(&app).await;
+        });
+    }
+}
+
+pub fn start_monitor(app: AppHandle) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(15));
+        let mut first = true;
+        loop {
+            interval.tick().await;
+            let online = probe(&app).await;
+            set_online(&app, online);
+            // Operations queued in an earlier session go out on the first
+            // successful probe.
+            if first && online {
+                offline::flush_queue(&app).await;
+            }
+            first = false;
+        }
+    });
+}
+
+/// Any HTTP response counts as online; only transport errors count as
+/// offline. A client that can't be built (bad network settings) doesn't mark
+/// the app offline.
+async fn probe(app: &AppHandle) -> bool {
+    let url: String = settings::get_setting(app, CHECK_URL_SETTING)
+        .unwrap_or_else(|| DEFAULT_CHECK_URL.to_string());
+    // Probe through the configured proxy, with a short timeout of its own
+    let client = match crate::http::builder("connectivity")
+        .and_then(|b| b.timeout(Duration::from_secs(5)).build().map_err(|e| e.to_string()))
+    {
+        Ok(c) => c,
+        Err(_) => return true,
+    };
+    client.head(&url).send().await.is_ok()
+}
@@ -1,6 +1,6 @@
 use rusqlite::Connection;
 use std::fs;
-use std::path::PathBuf;
+use std::path::Path;
 use std::sync::Mutex;
 use tauri::Manager;
 
@@ -53,12 +53,24 @@ pub fn init_db(app: &tauri::AppHandle) -> Result<DbState, String> {
     conn.execute_batch("PRAGMA journal_mode=WAL;")
         .map_err(|e| format!("Failed to set WAL mode: {}", e))?;
 
+    // Keep a copy of an existing database before its schema changes
+    if migrations_pending(&conn) {
+        let dir = crate::commands::snapshots::snapshots_dir(&base);
+        match crate::commands::snapshots::write_snapshot(&conn, &dir, "migration") {
+            Ok(_) => crate::commands::snapshots::prune(&dir, crate::commands::snapshots::DEFAULT_MAX_SNAPSHOTS),
+            Err(e) => crate::trace::log("Database", &format!("Pre-migration snapshot failed: {}", e)),
+        }
+    }
+
     // Run migrations
     run_migrations(&conn)?;
 
     // Migrate from .stn files if needed
     migrate_from_files(&conn, &base)?;
 
+    // Invalidate cached dashboard queries on writes
+    crate::query_cache::watch(&conn);
+
     Ok(DbState {
         conn: Mutex::new(conn),
     })
@@ -68,7 +80,24 @@ pub fn init_db(app: &tauri::AppHandle) -> Result<DbState, String> {
 // Migrations
 // ---------------------------------------------------------------------------
 
-fn run_migrations(conn: &Connection) -> Result<(), String> {
+/// Whether a database that already has a schema is behind the newest
+/// migration. A brand-new database has nothing worth snapshotting.
+fn migrations_pending(conn: &Connection) -> bool {
+    let current: i64 = conn
+        .query_row("SELECT COALESCE(MAX(version), 0) FROM _migrations", [], |row| row.get(0))
+        .unwrap_or(0);
+    let latest = MIGRATIONS.last().map(|(v, _)| *v).unwrap_or(0);
+    current > 0 && current < latest
+}
+
+pub(crate) fn run_migrations(conn: &Connection) -> Result<(), String> {
+    apply_migrations(conn, MIGRATIONS)
+}
+
+/// Apply the `migrations` newer than the recorded version. Each runs in a
+/// transaction together with its `_migrations` row, so one failing halfway
+/// leaves the schema as it was and is simply retried on the next launch.
+fn apply_migrations(conn: &Connection, migrations: &[(i64, &str)]) -> Result<(), String> {
     conn.execute_batch(
         "CREATE TABLE IF NOT EXISTS _migrations (
             version INTEGER PRIMARY KEY,
@@ -85,19 +114,72 @@ fn run_migrations(conn: &Connection) -> Result<(), String> {
         )
         .unwrap_or(0);
 
-    if current_version < 1 {
-        conn.execute_batch(MIGRATION_001)
-            .map_err(|e| format!("Migration 001 failed: {}", e))?;
-        conn.execute(
-            "INSERT INTO _migrations (version, applied_at) VALUES (1, datetime('now'))",
-            [],
-        )
-        .map_err(|e| format!("Failed to record migration 001: {}", e))?;
+    for (version, sql) in migrations {
+        if current_version < *version {
+            let tx = conn
+                .unchecked_transaction()
+                .map_err(|e| format!("Migration {:03} failed to start: {}", version, e))?;
+            tx.execute_batch(sql)
+                .map_err(|e| format!("Migration {:03} failed: {}", version, e))?;
+            tx.execute(
+                "INSERT INTO _migrations (version, applied_at) VALUES (?1, datetime('now'))",
+                rusqlite::params![version],
+            )
+            .map_err(|e| format!("Failed to record migration {:03}: {}", version, e))?;
+            tx.commit()
+                .map_err(|e| format!("Failed to commit migration {:03}: {}", version, e))?;
+        }
     }
 
     Ok(())
 }
 
+/// Ordered list of schema migrations. Append new entries; never edit old ones.
+const MIGRATIONS: &[(i64, &str)] = &[
+    (1, MIGRATION_001),
+    (2, MIGRATION_002),
+    (3, MIGRATION_003),
+    (4, MIGRATION_004),
+    (5, MIGRATION_005),
+    (6, MIGRATION_006),
+    (7, MIGRATION_007),
+    (8, MIGRATION_008),
+    (9, MIGRATION_009),
+    (10, MIGRATION_010),
+    (11, MIGRATION_011),
+    (12, MIGRATION_012),
+    (13, MIGRATION_013),
+    (14, MIGRATION_014),
+    (15, MIGRATION_015),
+    (16, MIGRATION_016),
+    (17, MIGRATION_017),
+    (18, MIGRATION_018),
+    (19, MIGRATION_019),
+    (20, MIGRATION_020),
+    (21, MIGRATION_021),
+    (22, MIGRATION_022),
+    (23, MIGRATION_023),
+    (24, MIGRATION_024),
+    (25, MIGRATION_025),
+    (26, MIGRATION_026),
+    (27, MIGRATION_027),
+    (28, MIGRATION_028),
+    (29, MIGRATION_029),
+    (30, MIGRATION_030),
+    (31, MIGRATION_031),
+    (32, MIGRATION_032),
+    (33, MIGRATION_033),
+    (34, MIGRATION_034),
+    (35, MIGRATION_035),
+    (36, MIGRATION_036),
+    (37, MIGRATION_037),
+    (38, MIGRATION_038),
+    (39, MIGRATION_039),
+    (40, MIGRATION_040),
+    (41, MIGRATION_041),
+    (42, MIGRATION_042),
+];
+
 const MIGRATION_001: &str = "
 -- Core: Documents
 CREATE TABLE IF NOT EXISTS documents (
@@ -236,6 +318,636 @@ CREATE TABLE IF NOT EXISTS activity_log (
 CREATE INDEX IF NOT EXISTS idx_activity_time ON activity_log(created_at DESC);
 ";
 
+const MIGRATION_002: &str = "
+-- Revenue: refund linkage and external (e.g. Stripe) ids for import dedupe
+ALTER TABLE revenue_entries ADD COLUMN refund_of TEXT;
+ALTER TABLE revenue_entries ADD COLUMN external_id TEXT;
+CREATE INDEX IF NOT EXISTS idx_revenue_refund_of ON revenue_entries(refund_of);
+CREATE UNIQUE INDEX IF NOT EXISTS idx_revenue_external ON revenue_entries(source, external_id);
+";
+
+const MIGRATION_003: &str = "
+-- Scheduling: reusable publishing presets per project
+CREATE TABLE IF NOT EXISTS publishing_presets (
+    id TEXT PRIMARY KEY,
+    project_id TEXT,
+    name TEXT NOT NULL,
+    targets_json TEXT NOT NULL DEFAULT '[]',
+    default_time TEXT,
+    publish_status TEXT NOT NULL DEFAULT 'draft',
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS idx_presets_project ON publishing_presets(project_id);
+
+-- Status sent to the platform when the scheduler publishes (draft/published)
+ALTER TABLE scheduled_posts ADD COLUMN publish_status TEXT NOT NULL DEFAULT 'draft';
+";
+
+const MIGRATION_004: &str = "
+-- Scheduling: weekly recurrence for presets (comma-separated mon..sun)
+ALTER TABLE publishing_presets ADD COLUMN recurrence_days TEXT NOT NULL DEFAULT '';
+
+-- Planning: idea backlog
+CREATE TABLE IF NOT EXISTS ideas (
+    id TEXT PRIMARY KEY,
+    title TEXT NOT NULL,
+    notes TEXT NOT NULL DEFAULT '',
+    project_id TEXT,
+    target_date TEXT,
+    status TEXT NOT NULL DEFAULT 'open',
+    document_id TEXT,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS idx_ideas_target ON ideas(target_date);
+";
+
+const MIGRATION_005: &str = "
+-- Scheduling: blackout periods (UTC RFC 3339 bounds, end exclusive)
+CREATE TABLE IF NOT EXISTS blackout_periods (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    starts_at TEXT NOT NULL,
+    ends_at TEXT NOT NULL,
+    created_at TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS idx_blackout_range ON blackout_periods(starts_at, ends_at);
+";
+
+const MIGRATION_006: &str = "
+-- Scheduling: per-platform publish options (serialised PublishOptions)
+ALTER TABLE scheduled_posts ADD COLUMN options_json TEXT NOT NULL DEFAULT '{}';
+";
+
+const MIGRATION_007: &str = "
+-- Scheduling: group posts fanned out to several publications in one send
+ALTER TABLE scheduled_posts ADD COLUMN group_id TEXT;
+CREATE INDEX IF NOT EXISTS idx_scheduled_group ON scheduled_posts(group_id);
+";
+
+const MIGRATION_008: &str = "
+-- Import: platform post id -> local document, for archive backfill dedupe
+CREATE TABLE IF NOT EXISTS platform_posts (
+    platform TEXT NOT NULL,
+    account_id TEXT NOT NULL,
+    platform_post_id TEXT NOT NULL,
+    document_id TEXT NOT NULL,
+    url TEXT,
+    published_at TEXT,
+    imported_at TEXT NOT NULL,
+    PRIMARY KEY (platform, account_id, platform_post_id)
+);
+CREATE INDEX IF NOT EXISTS idx_platform_posts_doc ON platform_posts(document_id);
+
+-- Analytics: point-in-time platform readings
+CREATE TABLE IF NOT EXISTS analytics_snapshots (
+    id TEXT PRIMARY KEY,
+    platform TEXT NOT NULL,
+    account_id TEXT NOT NULL,
+    publication_id TEXT,
+    total_subscribers INTEGER NOT NULL DEFAULT 0,
+    open_rate REAL NOT NULL DEFAULT 0.0,
+    click_rate REAL NOT NULL DEFAULT 0.0,
+    data_json TEXT NOT NULL DEFAULT '{}',
+    captured_at TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS idx_snapshots_account ON analytics_snapshots(platform, account_id, captured_at);
+";
+
+const MIGRATION_009: &str = "
+-- Tracing: one row per publish call, keyed by the trace id that also tags
+-- activity_log rows, log lines and emitted events
+ALTER TABLE activity_log ADD COLUMN trace_id TEXT;
+CREATE INDEX IF NOT EXISTS idx_activity_trace ON activity_log(trace_id);
+
+CREATE TABLE IF NOT EXISTS publish_attempts (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    trace_id TEXT,
+    scheduled_post_id TEXT,
+    platform TEXT NOT NULL,
+    account_id TEXT NOT NULL,
+    publication_id TEXT,
+    status TEXT NOT NULL DEFAULT 'started',
+    post_id TEXT,
+    error_kind TEXT,
+    error_message TEXT,
+    started_at TEXT NOT NULL,
+    finished_at TEXT
+);
+CREATE INDEX IF NOT EXISTS idx_attempts_post ON publish_attempts(scheduled_post_id, started_at);
+CREATE INDEX IF NOT EXISTS idx_attempts_trace ON publish_attempts(trace_id);
+";
+
+const MIGRATION_010: &str = "
+-- Offline queue: commands captured while offline, replayed on reconnect
+CREATE TABLE IF NOT EXISTS pending_operations (
+    id TEXT PRIMARY KEY,
+    kind TEXT NOT NULL,
+    payload_json TEXT NOT NULL,
+    status TEXT NOT NULL DEFAULT 'queued',
+    attempts INTEGER NOT NULL DEFAULT 0,
+    last_error TEXT,
+    trace_id TEXT,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS idx_pending_ops_status ON pending_operations(status, created_at);
+";
+
+const MIGRATION_011: &str = "
+-- Publications per connected account, so pickers don't wait on the network
+CREATE TABLE IF NOT EXISTS publications (
+    platform TEXT NOT NULL,
+    account_id TEXT NOT NULL,
+    publication_id TEXT NOT NULL,
+    name TEXT NOT NULL,
+    url TEXT NOT NULL,
+    subscriber_count INTEGER,
+    description TEXT,
+    fetched_at TEXT NOT NULL,
+    PRIMARY KEY (platform, account_id, publication_id)
+);
+";
+
+const MIGRATION_012: &str = "
+-- Milestones: each threshold is recorded once, when first crossed
+CREATE TABLE IF NOT EXISTS milestones (
+    id TEXT PRIMARY KEY,
+    kind TEXT NOT NULL,
+    threshold INTEGER NOT NULL,
+    value INTEGER NOT NULL,
+    achieved_at TEXT NOT NULL,
+    UNIQUE (kind, threshold)
+);
+";
+
+const MIGRATION_013: &str = "
+-- Platform usage per account, one row per metric per day and per month
+CREATE TABLE IF NOT EXISTS usage_counters (
+    platform TEXT NOT NULL,
+    account_id TEXT NOT NULL,
+    metric TEXT NOT NULL,
+    period TEXT NOT NULL,
+    period_start TEXT NOT NULL,
+    count INTEGER NOT NULL DEFAULT 0,
+    updated_at TEXT NOT NULL,
+    PRIMARY KEY (platform, account_id, metric, period, period_start)
+);
+";
+
+const MIGRATION_014: &str = "
+-- Files attached to documents, stored under app data/attachments
+CREATE TABLE IF NOT EXISTS attachments (
+    id TEXT PRIMARY KEY,
+    document_id TEXT NOT NULL,
+    filename TEXT NOT NULL,
+    stored_name TEXT NOT NULL,
+    mime_type TEXT NOT NULL,
+    size INTEGER NOT NULL DEFAULT 0,
+    created_at TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS idx_attachments_document ON attachments(document_id, created_at);
+";
+
+const MIGRATION_015: &str = "
+-- Podcast episodes: a document (the show notes) with an audio attachment
+CREATE TABLE IF NOT EXISTS podcast_episodes (
+    document_id TEXT PRIMARY KEY,
+    audio_attachment_id TEXT NOT NULL,
+    season INTEGER,
+    episode_number INTEGER,
+    episode_type TEXT NOT NULL DEFAULT 'full',
+    explicit INTEGER NOT NULL DEFAULT 0,
+    duration_secs INTEGER,
+    audio_url TEXT,
+    summary TEXT,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL
+);
+";
+
+const MIGRATION_016: &str = "
+-- Transcript document generated from an audio or video attachment
+ALTER TABLE attachments ADD COLUMN transcript_document_id TEXT;
+";
+
+const MIGRATION_017: &str = "
+-- Contacts: sponsors, editors and guests, with their sponsorship deals
+CREATE TABLE IF NOT EXISTS contacts (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    company TEXT,
+    email TEXT,
+    role TEXT NOT NULL DEFAULT 'sponsor',
+    notes TEXT NOT NULL DEFAULT '',
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS idx_contacts_role ON contacts(role, name);
+
+CREATE TABLE IF NOT EXISTS sponsorship_deals (
+    id TEXT PRIMARY KEY,
+    contact_id TEXT NOT NULL,
+    title TEXT NOT NULL,
+    amount_cents INTEGER NOT NULL DEFAULT 0,
+    currency TEXT NOT NULL DEFAULT 'USD',
+    status TEXT NOT NULL DEFAULT 'prospect',
+    document_id TEXT,
+    run_date TEXT,
+    notes TEXT NOT NULL DEFAULT '',
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS idx_deals_contact ON sponsorship_deals(contact_id, created_at);
+";
+
+const MIGRATION_018: &str = "
+-- Reusable HTML blocks (sponsor slot, footer, ...) merged in at publish time
+CREATE TABLE IF NOT EXISTS content_blocks (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    kind TEXT NOT NULL DEFAULT 'custom',
+    html TEXT NOT NULL DEFAULT '',
+    position TEXT NOT NULL DEFAULT 'bottom',
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL
+);
+";
+
+const MIGRATION_019: &str = "
+-- Per-project publish defaults (targets, tags, brand kit, SEO) as JSON
+ALTER TABLE projects ADD COLUMN defaults_json TEXT NOT NULL DEFAULT '{}';
+";
+
+const MIGRATION_020: &str = "
+-- Archived projects are hidden from the default project list
+ALTER TABLE projects ADD COLUMN archived_at TEXT;
+";
+
+const MIGRATION_021: &str = "
+-- Links: outgoing <a href>s per document, keyed for backlink lookups
+CREATE TABLE IF NOT EXISTS document_links (
+    source_document_id TEXT NOT NULL,
+    url TEXT NOT NULL,
+    target_key TEXT NOT NULL,
+    anchor_text TEXT NOT NULL DEFAULT '',
+    PRIMARY KEY (source_document_id, target_key)
+);
+CREATE INDEX IF NOT EXISTS idx_links_target ON document_links(target_key);
+";
+
+const MIGRATION_022: &str = "
+-- Audit: hash-chained record of sensitive operations, kept apart from
+-- activity_log so storage pruning never touches it
+CREATE TABLE IF NOT EXISTS audit_log (
+    seq INTEGER PRIMARY KEY AUTOINCREMENT,
+    action TEXT NOT NULL,
+    entity_type TEXT NOT NULL,
+    entity_id TEXT,
+    details TEXT,
+    actor_role TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    trace_id TEXT,
+    prev_hash TEXT NOT NULL,
+    hash TEXT NOT NULL
+);
+";
+
+const MIGRATION_023: &str = "
+-- Deliverability: bounces and complaints per subscriber, from ESP syncs or
+-- exported reports; one row per event so re-imports are idempotent
+CREATE TABLE IF NOT EXISTS subscriber_events (
+    id TEXT PRIMARY KEY,
+    subscriber_id TEXT,
+    email TEXT NOT NULL,
+    event_type TEXT NOT NULL,
+    platform TEXT NOT NULL,
+    reason TEXT,
+    source TEXT NOT NULL,
+    occurred_at TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    UNIQUE (email, event_type, platform, occurred_at)
+);
+CREATE INDEX IF NOT EXISTS idx_subscriber_events_subscriber ON subscriber_events(subscriber_id, occurred_at);
+
+-- Addresses that must not be mailed again (hard bounces, manual entries)
+CREATE TABLE IF NOT EXISTS suppression_list (
+    email TEXT PRIMARY KEY,
+    reason TEXT NOT NULL,
+    platform TEXT,
+    created_at TEXT NOT NULL
+);
+";
+
+const MIGRATION_024: &str = "
+-- SMTP sending: one row per issue sent directly, one log row per recipient
+CREATE TABLE IF NOT EXISTS smtp_sends (
+    id TEXT PRIMARY KEY,
+    document_id TEXT NOT NULL,
+    subject TEXT NOT NULL,
+    status TEXT NOT NULL DEFAULT 'sending',
+    total INTEGER NOT NULL DEFAULT 0,
+    sent INTEGER NOT NULL DEFAULT 0,
+    failed INTEGER NOT NULL DEFAULT 0,
+    started_at TEXT NOT NULL,
+    finished_at TEXT
+);
+
+CREATE TABLE IF NOT EXISTS smtp_send_log (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    send_id TEXT NOT NULL,
+    email TEXT NOT NULL,
+    status TEXT NOT NULL,
+    error TEXT,
+    sent_at TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS idx_smtp_send_log_send ON smtp_send_log(send_id);
+
+-- Per-address unsubscribe tokens, stable across sends
+CREATE TABLE IF NOT EXISTS unsubscribe_tokens (
+    token TEXT PRIMARY KEY,
+    email TEXT NOT NULL UNIQUE,
+    created_at TEXT NOT NULL,
+    used_at TEXT
+);
+";
+
+const MIGRATION_025: &str = "
+-- Search: full-text index over documents, kept in step by triggers
+CREATE VIRTUAL TABLE IF NOT EXISTS documents_fts USING fts5(
+    title, content, html_content,
+    content = 'documents', content_rowid = 'rowid',
+    tokenize = 'porter unicode61 remove_diacritics 2'
+);
+
+CREATE TRIGGER IF NOT EXISTS documents_fts_insert AFTER INSERT ON documents BEGIN
+    INSERT INTO documents_fts (rowid, title, content, html_content)
+    VALUES (new.rowid, new.title, new.content, new.html_content);
+END;
+
+CREATE TRIGGER IF NOT EXISTS documents_fts_delete AFTER DELETE ON documents BEGIN
+    INSERT INTO documents_fts (documents_fts, rowid, title, content, html_content)
+    VALUES ('delete', old.rowid, old.title, old.content, old.html_content);
+END;
+
+CREATE TRIGGER IF NOT EXISTS documents_fts_update AFTER UPDATE OF title, content, html_content ON documents BEGIN
+    INSERT INTO documents_fts (documents_fts, rowid, title, content, html_content)
+    VALUES ('delete', old.rowid, old.title, old.content, old.html_content);
+    INSERT INTO documents_fts (rowid, title, content, html_content)
+    VALUES (new.rowid, new.title, new.content, new.html_content);
+END;
+
+INSERT INTO documents_fts (documents_fts) VALUES ('rebuild');
+";
+
+const MIGRATION_026: &str = "
+-- Signup form: double opt-in requests awaiting their confirmation click
+CREATE TABLE IF NOT EXISTS pending_signups (
+    token TEXT PRIMARY KEY,
+    email TEXT NOT NULL,
+    name TEXT,
+    source_tag TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    confirmed_at TEXT
+);
+CREATE INDEX IF NOT EXISTS idx_pending_signups_email ON pending_signups(email);
+";
+
+const MIGRATION_027: &str = "
+-- Vault sync: the Markdown file mirroring each document, with the file hash
+-- and document version as of the last sync
+CREATE TABLE IF NOT EXISTS vault_files (
+    document_id TEXT PRIMARY KEY,
+    path TEXT NOT NULL,
+    content_hash TEXT NOT NULL,
+    document_version INTEGER NOT NULL,
+    modified_ms INTEGER NOT NULL DEFAULT 0,
+    synced_at TEXT NOT NULL
+);
+CREATE UNIQUE INDEX IF NOT EXISTS idx_vault_files_path ON vault_files(path);
+";
+
+const MIGRATION_028: &str = "
+-- Archived documents are hidden from the default document list
+ALTER TABLE documents ADD COLUMN archived_at TEXT;
+CREATE INDEX IF NOT EXISTS idx_documents_updated ON documents(archived_at, updated_at);
+";
+
+const MIGRATION_029: &str = "
+-- Spellcheck: words the user accepts, per dictionary language
+CREATE TABLE IF NOT EXISTS user_dictionary (
+    word TEXT NOT NULL,
+    language TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    PRIMARY KEY (word, language)
+);
+";
+
+const MIGRATION_030: &str = "
+-- Per-document word count goals; goal_reached_at marks the first save
+-- that met the current goal
+ALTER TABLE documents ADD COLUMN target_words INTEGER;
+ALTER TABLE documents ADD COLUMN goal_reached_at TEXT;
+";
+
+const MIGRATION_031: &str = "
+-- Net words written per UTC day and document, bumped on every save. Rows
+-- outlive their document so past output still counts.
+CREATE TABLE IF NOT EXISTS writing_stats (
+    date TEXT NOT NULL,
+    document_id TEXT NOT NULL,
+    words_delta INTEGER NOT NULL DEFAULT 0,
+    updated_at TEXT NOT NULL,
+    PRIMARY KEY (date, document_id)
+);
+";
+
+const MIGRATION_032: &str = "
+-- History of queued multi-document exports
+CREATE TABLE IF NOT EXISTS export_jobs (
+    id TEXT PRIMARY KEY,
+    kind TEXT NOT NULL,
+    label TEXT NOT NULL DEFAULT '',
+    format TEXT NOT NULL,
+    status TEXT NOT NULL DEFAULT 'queued',
+    done INTEGER NOT NULL DEFAULT 0,
+    total INTEGER NOT NULL DEFAULT 0,
+    error TEXT,
+    created_at TEXT NOT NULL,
+    started_at TEXT,
+    finished_at TEXT
+);
+CREATE INDEX IF NOT EXISTS idx_export_jobs_created ON export_jobs(created_at);
+";
+
+const MIGRATION_033: &str = "
+-- Reusable HTML snippets expanded into the editor, with {{placeholders}}
+CREATE TABLE IF NOT EXISTS snippets (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    shortcut TEXT UNIQUE,
+    html TEXT NOT NULL DEFAULT '',
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL
+);
+";
+
+const MIGRATION_034: &str = "
+-- Reader replies and quotes for landing pages and sponsor decks
+CREATE TABLE IF NOT EXISTS testimonials (
+    id TEXT PRIMARY KEY,
+    quote TEXT NOT NULL,
+    author_name TEXT NOT NULL DEFAULT '',
+    author_handle TEXT,
+    source TEXT NOT NULL DEFAULT 'manual',
+    source_url TEXT,
+    external_id TEXT,
+    document_id TEXT,
+    rating INTEGER,
+    featured INTEGER NOT NULL DEFAULT 0,
+    approved INTEGER NOT NULL DEFAULT 1,
+    received_at TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL,
+    UNIQUE (source, external_id)
+);
+CREATE INDEX IF NOT EXISTS idx_testimonials_document ON testimonials(document_id);
+";
+
+const MIGRATION_035: &str = "
+-- Timed writing sessions with a minutes and/or words goal. active_seconds
+-- is time banked before resumed_at; a running session also counts the
+-- time since. updated_at is the last tick, so a session cut short by
+-- quitting is paused as of then.
+CREATE TABLE IF NOT EXISTS focus_sessions (
+    id TEXT PRIMARY KEY,
+    document_id TEXT,
+    goal_minutes INTEGER,
+    goal_words INTEGER,
+    status TEXT NOT NULL DEFAULT 'active',
+    active_seconds INTEGER NOT NULL DEFAULT 0,
+    resumed_at TEXT,
+    words_written INTEGER NOT NULL DEFAULT 0,
+    started_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL,
+    ended_at TEXT
+);
+CREATE INDEX IF NOT EXISTS idx_focus_sessions_started ON focus_sessions(started_at);
+";
+
+const MIGRATION_036: &str = "
+-- Per-document, per-platform tags, slug and canonical URL for cross-posting;
+-- tags is a JSON array of names
+CREATE TABLE IF NOT EXISTS publish_profiles (
+    document_id TEXT NOT NULL,
+    platform TEXT NOT NULL,
+    tags TEXT NOT NULL DEFAULT '[]',
+    slug TEXT,
+    canonical_url TEXT,
+    canonical_platform TEXT,
+    updated_at TEXT NOT NULL,
+    PRIMARY KEY (document_id, platform)
+);
+";
+
+const MIGRATION_037: &str = "
+-- Time-limited posts: the scheduler takes a published post down at
+-- unpublish_at and records when in unpublished_at
+ALTER TABLE scheduled_posts ADD COLUMN unpublish_at TEXT;
+ALTER TABLE scheduled_posts ADD COLUMN unpublished_at TEXT;
+CREATE INDEX IF NOT EXISTS idx_scheduled_unpublish ON scheduled_posts(unpublish_at, status);
+";
+
+const MIGRATION_038: &str = "
+-- A document's scheduled posts held as 'awaiting_approval' until a POST to
+-- /webhooks/publish/<id>, signed with secret, releases them; triggered_at
+-- is set once it has
+CREATE TABLE IF NOT EXISTS publish_webhooks (
+    id TEXT PRIMARY KEY,
+    document_id TEXT NOT NULL UNIQUE,
+    secret TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    triggered_at TEXT
+);
+";
+
+const MIGRATION_039: &str = "
+-- Audience: ISO country code, from platforms that report one (Ghost's member
+-- geolocation, Mailchimp's CC column)
+ALTER TABLE subscribers ADD COLUMN country TEXT;
+CREATE INDEX IF NOT EXISTS idx_subscribers_country ON subscribers(country);
+";
+
+const MIGRATION_040: &str = "
+-- Documents: pinned drafts list first; sort_index is the manual order within
+-- the document's project (NULL falls back to most recently updated)
+ALTER TABLE documents ADD COLUMN pinned_at TEXT;
+ALTER TABLE documents ADD COLUMN sort_index INTEGER;
+";
+
+const MIGRATION_041: &str = "
+-- Export: every DOCX/PDF export with the settings it was made with (JSON)
+-- and a hash of the exported HTML, for re_export
+CREATE TABLE IF NOT EXISTS export_history (
+    id TEXT PRIMARY KEY,
+    document_id TEXT,
+    format TEXT NOT NULL,
+    title TEXT NOT NULL,
+    options TEXT NOT NULL,
+    content_hash TEXT NOT NULL,
+    size_bytes INTEGER NOT NULL,
+    created_at TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS idx_export_history_document ON export_history(document_id, created_at);
+";
+
+const MIGRATION_042: &str = "
+-- Workspaces: separate newsletters sharing one install. Everything that
+-- existed before lands in 'default'.
+CREATE TABLE IF NOT EXISTS workspaces (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    color TEXT NOT NULL DEFAULT '#7c3aed',
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL
+);
+INSERT OR IGNORE INTO workspaces (id, name, created_at, updated_at)
+VALUES ('default', 'Default', strftime('%Y-%m-%dT%H:%M:%SZ', 'now'), strftime('%Y-%m-%dT%H:%M:%SZ', 'now'));
+
+ALTER TABLE documents ADD COLUMN workspace_id TEXT NOT NULL DEFAULT 'default';
+ALTER TABLE projects ADD COLUMN workspace_id TEXT NOT NULL DEFAULT 'default';
+ALTER TABLE revenue_entries ADD COLUMN workspace_id TEXT NOT NULL DEFAULT 'default';
+ALTER TABLE pending_signups ADD COLUMN workspace_id TEXT NOT NULL DEFAULT 'default';
+CREATE INDEX IF NOT EXISTS idx_documents_workspace ON documents(workspace_id);
+CREATE INDEX IF NOT EXISTS idx_projects_workspace ON projects(workspace_id);
+CREATE INDEX IF NOT EXISTS idx_revenue_workspace ON revenue_entries(workspace_id, recorded_at);
+
+-- Subscribers: the same address can subscribe to two newsletters, so email
+-- is unique per workspace. Rebuilt since SQLite can't alter a constraint.
+CREATE TABLE subscribers_new (
+    id TEXT PRIMARY KEY,
+    workspace_id TEXT NOT NULL DEFAULT 'default',
+    email TEXT NOT NULL,
+    name TEXT,
+    first_seen_at TEXT NOT NULL,
+    last_seen_at TEXT NOT NULL,
+    engagement_score REAL NOT NULL DEFAULT 0.0,
+    total_opens INTEGER NOT NULL DEFAULT 0,
+    total_clicks INTEGER NOT NULL DEFAULT 0,
+    country TEXT,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL,
+    UNIQUE (workspace_id, email)
+);
+INSERT INTO subscribers_new (id, email, name, first_seen_at, last_seen_at, engagement_score, total_opens, total_clicks, country, created_at, updated_at)
+SELECT id, email, name, first_seen_at, last_seen_at, engagement_score, total_opens, total_clicks, country, created_at, updated_at
+FROM subscribers;
+DROP TABLE subscribers;
+ALTER TABLE subscribers_new RENAME TO subscribers;
+CREATE INDEX IF NOT EXISTS idx_subscribers_country ON subscribers(country);
+";
+
 // ---------------------------------------------------------------------------
 // File migration (.stn → SQLite)
 // ---------------------------------------------------------------------------
@@ -252,7 +964,7 @@ struct LegacyDocument {
     updated_at: String,
 }
 
-fn migrate_from_files(conn: &Connection, base: &PathBuf) -> Result<(), String> {
+fn migrate_from_files(conn: &Connection, base: &Path) -> Result<(), String> {
     let docs_dir = base.join("documents");
     if !docs_dir.exists() {
         return Ok(());
@@ -266,11 +978,9 @@ fn migrate_from_files(conn: &Connection, base: &PathBuf) -> Result<(), String> {
     let entries = fs::read_dir(&docs_dir).map_err(|e| format!("Cannot read documents dir: {}", e))?;
     let mut stn_files: Vec<_> = Vec::new();
 
-    for entry in entries {
-        if let Ok(entry) = entry {
-            if entry.path().extension().and_then(|e| e.to_str()) == Some("stn") {
-                stn_files.push(entry.path());
-            }
+    for entry in entries.flatten() {
+        if entry.path().extension().and_then(|e| e.to_str()) == Some("stn") {
+            stn_files.push(entry.path());
         }
     }
 
@@ -288,7 +998,7 @@ fn migrate_from_files(conn: &Connection, base: &PathBuf) -> Result<(), String> {
     for path in &stn_files {
         if let Ok(contents) = fs::read_to_string(path) {
             if let Ok(doc) = serde_json::from_str::<LegacyDocument>(&contents) {
-                let word_count = count_words_simple(&doc.html_content);
+                let word_count = crate::text::count_html(&doc.html_content, crate::text::CountMode::Auto) as i64;
                 conn.execute(
                     "INSERT OR IGNORE INTO documents (id, title, content, html_content, status, word_count, character_count, version, created_at, updated_at)
                      VALUES (?1, ?2, ?3, ?4, 'draft', ?5, 0, 1, ?6, ?7)",
@@ -315,22 +1025,6 @@ fn migrate_from_files(conn: &Connection, base: &PathBuf) -> Result<(), String> {
     Ok(())
 }
 
-fn count_words_simple(html: &str) -> i64 {
-    let mut in_tag = false;
-    let mut text = String::new();
-    for ch in html.chars() {
-        if ch == '<' {
-            in_tag = true;
-            text.push(' ');
-        } else if ch == '>' {
-            in_tag = false;
-        } else if !in_tag {
-            text.push(ch);
-        }
-    }
-    text.split_whitespace().count() as i64
-}
-
 // ---------------------------------------------------------------------------
 // Activity logging helper
 // ---------------------------------------------------------------------------
@@ -344,8 +1038,202 @@ pub fn log_activity(
 ) {
     let now = chrono::Utc::now().to_rfc3339();
     let _ = conn.execute(
-        "INSERT INTO activity_log (action, entity_type, entity_id, details, created_at)
-         VALUES (?1, ?2, ?3, ?4, ?5)",
-        rusqlite::params![action, entity_type, entity_id, details, now],
+        "INSERT INTO activity_log (action, entity_type, entity_id, details, created_at, trace_id)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        rusqlite::params![action, entity_type, entity_id, details, now, crate::trace::current()],
+    );
+}
+
+// ---------------------------------------------------------------------------
+// Audit logging helper
+// ---------------------------------------------------------------------------
+
+/// `prev_hash` of the first audit row
+pub const AUDIT_GENESIS: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+/// Hash of one audit row: SHA-256 over the previous row's hash and this
+/// row's fields, so editing, inserting or removing a row breaks the chain
+/// from there on.
+#[allow(clippy::too_many_arguments)]
+pub fn audit_hash(
+    prev_hash: &str,
+    seq: i64,
+    action: &str,
+    entity_type: &str,
+    entity_id: Option<&str>,
+    details: Option<&str>,
+    actor_role: &str,
+    created_at: &str,
+) -> String {
+    use sha2::{Digest, Sha256};
+    let mut hasher = Sha256::new();
+    for field in [
+        prev_hash,
+        &seq.to_string(),
+        action,
+        entity_type,
+        entity_id.unwrap_or(""),
+        details.unwrap_or(""),
+        actor_role,
+        created_at,
+    ] {
+        hasher.update(field.as_bytes());
+        hasher.update([0u8]);
+    }
+    hasher.finalize().iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Append to the audit log. Unlike `log_activity` a failure is returned:
+/// callers doing something sensitive shouldn't go ahead unrecorded.
+pub fn log_audit(
+    conn: &Connection,
+    action: &str,
+    entity_type: &str,
+    entity_id: Option<&str>,
+    details: Option<&str>,
+    actor_role: &str,
+) -> Result<(), rusqlite::Error> {
+    let (last_seq, prev_hash): (i64, String) = conn
+        .query_row("SELECT seq, hash FROM audit_log ORDER BY seq DESC LIMIT 1", [], |row| {
+            Ok((row.get(0)?, row.get(1)?))
+        })
+        .unwrap_or((0, AUDIT_GENESIS.to_string()));
+    let seq = last_seq + 1;
+    let now = chrono::Utc::now().to_rfc3339();
+    let hash = audit_hash(&prev_hash, seq, action, entity_type, entity_id, details, actor_role, &now);
+    conn.execute(
+        "INSERT INTO audit_log (seq, action, entity_type, entity_id, details, actor_role, created_at, trace_id, prev_hash, hash)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+        rusqlite::params![seq, action, entity_type, entity_id, details, actor_role, now, crate::trace::current(), prev_hash, hash],
+    )?;
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// Publish attempt helpers
+// ---------------------------------------------------------------------------
+
+/// Record the start of a publish call under the current trace id.
+/// Returns the attempt row id for `finish_publish_attempt`.
+pub fn start_publish_attempt(
+    conn: &Connection,
+    scheduled_post_id: Option<&str>,
+    platform: &str,
+    account_id: &str,
+    publication_id: Option<&str>,
+) -> i64 {
+    let now = chrono::Utc::now().to_rfc3339();
+    let _ = conn.execute(
+        "INSERT INTO publish_attempts (trace_id, scheduled_post_id, platform, account_id, publication_id, started_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        rusqlite::params![crate::trace::current(), scheduled_post_id, platform, account_id, publication_id, now],
+    );
+    conn.last_insert_rowid()
+}
+
+pub fn finish_publish_attempt(
+    conn: &Connection,
+    attempt_id: i64,
+    result: &Result<String, crate::error::AppError>,
+) {
+    let now = chrono::Utc::now().to_rfc3339();
+    let (status, post_id, kind, message) = match result {
+        Ok(post_id) => ("published", Some(post_id.as_str()), None, None),
+        Err(e) => (
+            "failed",
+            None,
+            Some(e.kind.as_str()),
+            Some(e.message.as_str()),
+        ),
+    };
+    let _ = conn.execute(
+        "UPDATE publish_attempts SET status = ?1, post_id = ?2, error_kind = ?3, error_message = ?4, finished_at = ?5
+         WHERE id = ?6",
+        rusqlite::params![status, post_id, kind, message, now, attempt_id],
     );
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn version(conn: &Connection) -> i64 {
+        conn.query_row("SELECT COALESCE(MAX(version), 0) FROM _migrations", [], |row| row.get(0)).unwrap()
+    }
+
+    #[test]
+    fn failed_migration_is_rolled_back_and_retried() {
+        let conn = Connection::open_in_memory().unwrap();
+        let broken: &[(i64, &str)] = &[
+            (1, "CREATE TABLE t (id INTEGER PRIMARY KEY);"),
+            (2, "ALTER TABLE t ADD COLUMN a TEXT; INSERT INTO missing VALUES (1);"),
+        ];
+        assert!(apply_migrations(&conn, broken).unwrap_err().contains("Migration 002"));
+        assert_eq!(version(&conn), 1);
+
+        // The column wasn't left behind, so the corrected migration applies
+        let fixed: &[(i64, &str)] =
+            &[(1, "CREATE TABLE t (id INTEGER PRIMARY KEY);"), (2, "ALTER TABLE t ADD COLUMN a TEXT;")];
+        apply_migrations(&conn, fixed).unwrap();
+        assert_eq!(version(&conn), 2);
+    }
+
+    #[test]
+    fn all_migrations_apply_to_an_empty_database() {
+        let conn = Connection::open_in_memory().unwrap();
+        run_migrations(&conn).unwrap();
+        assert_eq!(version(&conn), MIGRATIONS.last().unwrap().0);
+        // Running again is a no-op
+        run_migrations(&conn).unwrap();
+    }
+
+    #[test]
+    fn workspace_migration_rebuilds_subscribers_without_losing_rows() {
+        let conn = Connection::open_in_memory().unwrap();
+        let before: Vec<(i64, &str)> = MIGRATIONS.iter().copied().filter(|(v, _)| *v < 42).collect();
+        apply_migrations(&conn, &before).unwrap();
+        conn.execute_batch(
+            "INSERT INTO subscribers (id, email, name, first_seen_at, last_seen_at, engagement_score, total_opens,
+                 total_clicks, country, created_at, updated_at)
+             VALUES ('s1', 'ann@example.com', 'Ann', '2025-01-01', '2025-02-01', 0.5, 3, 1, 'NZ', '2025-01-01', '2025-02-01');
+             INSERT INTO subscriber_tags (subscriber_id, tag) VALUES ('s1', 'vip');",
+        )
+        .unwrap();
+
+        apply_migrations(&conn, MIGRATIONS).unwrap();
+        let row: (String, String, i64, String) = conn
+            .query_row("SELECT workspace_id, name, total_opens, country FROM subscribers WHERE id = 's1'", [], |row| {
+                Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))
+            })
+            .unwrap();
+        assert_eq!(row, ("default".to_string(), "Ann".to_string(), 3, "NZ".to_string()));
+        let tag: String = conn
+            .query_row(
+                "SELECT t.tag FROM subscriber_tags t JOIN subscribers s ON s.id = t.subscriber_id
+                 WHERE s.email = 'ann@example.com'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(tag, "vip");
+        let indexed: bool = conn
+            .query_row(
+                "SELECT EXISTS(SELECT 1 FROM sqlite_master WHERE type = 'index' AND name = 'idx_subscribers_country')",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert!(indexed);
+
+        // The same address may join another workspace, but not twice the same one
+        let insert = |id: &str, workspace: &str| {
+            conn.execute(
+                "INSERT INTO subscribers (id, workspace_id, email, first_seen_at, last_seen_at, created_at, updated_at)
+                 VALUES (?1, ?2, 'ann@example.com', '2025-03-01', '2025-03-01', '2025-03-01', '2025-03-01')",
+                rusqlite::params![id, workspace],
+            )
+        };
+        assert!(insert("s2", "other").is_ok());
+        assert!(insert("s3", "default").is_err());
+    }
+}
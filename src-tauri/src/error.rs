@@ -0,0 +1,186 @@
+use serde::Serialize;
+
+// ─── Application error type ─────────────────────────────────────
+//
+// Commands return `AppError` so the frontend can branch on `kind` (prompt
+// for a new key on `auth`, back off on `rate_limited`, show inline
+// validation) instead of pattern-matching message strings. It serialises
+// as `{ kind, message, retryable, platform, trace_id }`, plus `details`
+// when set; `message` stays the same human-readable text commands have
+// always returned.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorKind {
+    /// Missing, invalid or expired credentials, or insufficient permission
+    Auth,
+    /// The platform asked us to slow down
+    RateLimited,
+    /// Bad input from the user or caller
+    Validation,
+    NotFound,
+    /// Couldn't reach the platform (DNS, TLS, timeout, connection reset)
+    Network,
+    /// The platform responded with an error we don't classify further
+    Platform,
+    Database,
+    Internal,
+    /// Not a failure: the app is offline and the work was queued to run
+    /// once it reconnects
+    Queued,
+    /// The record changed since the caller last read it; `details` says how
+    Conflict,
+    /// Not a failure: the user cancelled a queued job
+    Cancelled,
+}
+
+impl ErrorKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ErrorKind::Auth => "auth",
+            ErrorKind::RateLimited => "rate_limited",
+            ErrorKind::Validation => "validation",
+            ErrorKind::NotFound => "not_found",
+            ErrorKind::Network => "network",
+            ErrorKind::Platform => "platform",
+            ErrorKind::Database => "database",
+            ErrorKind::Internal => "internal",
+            ErrorKind::Queued => "queued",
+            ErrorKind::Conflict => "conflict",
+            ErrorKind::Cancelled => "cancelled",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, thiserror::Error)]
+#[error("{message}")]
+pub struct AppError {
+    pub kind: ErrorKind,
+    pub message: String,
+    /// Whether retrying the same call later may succeed
+    pub retryable: bool,
+    /// Platform the error came from, when it came from one
+    pub platform: Option<String>,
+    /// Trace id of the command or job that failed (see `crate::trace`)
+    pub trace_id: Option<String>,
+    /// Structured context for kinds the frontend resolves itself (conflicts)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub details: Option<serde_json::Value>,
+    /// Set when the request never reached the platform (connection or DNS
+    /// failure), so sending it again can't act twice
+    #[serde(skip)]
+    pub unsent: bool,
+}
+
+impl AppError {
+    pub fn new(kind: ErrorKind, message: impl Into<String>) -> Self {
+        AppError {
+            kind,
+            message: message.into(),
+            retryable: matches!(kind, ErrorKind::RateLimited | ErrorKind::Network),
+            platform: None,
+            trace_id: crate::trace::current(),
+            details: None,
+            unsent: false,
+        }
+    }
+
+    pub fn validation(message: impl Into<String>) -> Self {
+        Self::new(ErrorKind::Validation, message)
+    }
+
+    pub fn not_found(message: impl Into<String>) -> Self {
+        Self::new(ErrorKind::NotFound, message)
+    }
+
+    pub fn auth(message: impl Into<String>) -> Self {
+        Self::new(ErrorKind::Auth, message)
+    }
+
+    /// Attach structured context the frontend can act on.
+    pub fn with_details(mut self, details: serde_json::Value) -> Self {
+        self.details = Some(details);
+        self
+    }
+
+    /// Tag the error with its platform, keeping an existing tag.
+    pub fn with_platform(mut self, platform: &str) -> Self {
+        if self.platform.is_none() {
+            self.platform = Some(platform.to_string());
+        }
+        self
+    }
+
+    /// Classify a non-success HTTP status from a platform API.
+    pub fn from_status(platform: &str, status: reqwest::StatusCode, message: impl Into<String>) -> Self {
+        let kind = match status.as_u16() {
+            401 | 403 => ErrorKind::Auth,
+            404 => ErrorKind::NotFound,
+            429 => ErrorKind::RateLimited,
+            408 => ErrorKind::Network,
+            400 | 409 | 422 => ErrorKind::Validation,
+            _ => ErrorKind::Platform,
+        };
+        let mut err = Self::new(kind, message).with_platform(platform);
+        err.retryable = err.retryable || status.is_server_error();
+        err
+    }
+
+    /// Build an error from a failed response, reading its body for context.
+    /// The message reads "`context`: `status` - `body`".
+    pub async fn from_response(platform: &str, context: &str, resp: reqwest::Response) -> Self {
+        let status = resp.status();
+        let body = resp.text().await.unwrap_or_default();
+        let body = body.trim();
+        let message = if body.is_empty() {
+            format!("{}: {}", context, status)
+        } else {
+            let snippet: String = body.chars().take(500).collect();
+            format!("{}: {} - {}", context, status, snippet)
+        };
+        crate::trace::log("HTTP", &format!("{} responded {}", platform, status));
+        Self::from_status(platform, status, message)
+    }
+
+    /// A request that never got a response. The message reads "`context`: `err`".
+    pub fn network(platform: &str, context: &str, err: reqwest::Error) -> Self {
+        let kind = if err.is_timeout() || err.is_connect() || err.is_request() {
+            ErrorKind::Network
+        } else {
+            ErrorKind::Platform
+        };
+        crate::trace::log("HTTP", &format!("{} request failed: {}", platform, err));
+        let mut error = Self::new(kind, format!("{}: {}", context, err)).with_platform(platform);
+        error.unsent = err.is_connect();
+        error
+    }
+}
+
+/// Plain string errors from helpers keep their message and are treated as
+/// internal unless a caller classifies them.
+impl From<String> for AppError {
+    fn from(message: String) -> Self {
+        Self::new(ErrorKind::Internal, message)
+    }
+}
+
+impl From<&str> for AppError {
+    fn from(message: &str) -> Self {
+        Self::new(ErrorKind::Internal, message)
+    }
+}
+
+impl From<rusqlite::Error> for AppError {
+    fn from(err: rusqlite::Error) -> Self {
+        match err {
+            rusqlite::Error::QueryReturnedNoRows => Self::not_found("Record not found"),
+            other => Self::new(ErrorKind::Database, format!("Database error: {}", other)),
+        }
+    }
+}
+
+impl From<serde_json::Error> for AppError {
+    fn from(err: serde_json::Error) -> Self {
+        Self::new(ErrorKind::Internal, err.to_string())
+    }
+}
@@ -0,0 +1,416 @@
+use std::collections::{HashMap, HashSet};
+
+// ─── Hunspell dictionaries ──────────────────────────────────────
+//
+// Reads the .aff/.dic pairs shipped by LibreOffice, Firefox and most
+// Linux distributions. Words are accepted when they're in the word list
+// or derive from an entry through one suffix, one prefix, or a prefix and
+// suffix that both allow cross products. Compounding, continuation
+// classes and morphology are not supported, which costs some coverage in
+// heavily compounding languages (German, Hungarian) but none in English.
+
+const MAX_SUGGESTIONS: usize = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FlagMode {
+    /// One character per flag (the default)
+    Char,
+    /// Two characters per flag
+    Long,
+    /// Comma-separated decimal numbers
+    Num,
+}
+
+#[derive(Debug, Clone)]
+enum Condition {
+    Any,
+    Char(char),
+    Set(Vec<char>),
+    NotSet(Vec<char>),
+}
+
+impl Condition {
+    fn parse(pattern: &str) -> Vec<Condition> {
+        if pattern == "." {
+            return Vec::new();
+        }
+        let mut out = Vec::new();
+        let mut chars = pattern.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '.' => out.push(Condition::Any),
+                '[' => {
+                    let mut set: Vec<char> = Vec::new();
+                    let mut negated = false;
+                    for (i, c) in chars.by_ref().enumerate() {
+                        match c {
+                            ']' => break,
+                            '^' if i == 0 => negated = true,
+                            c => set.push(c),
+                        }
+                    }
+                    out.push(if negated { Condition::NotSet(set) } else { Condition::Set(set) });
+                }
+                c => out.push(Condition::Char(c)),
+            }
+        }
+        out
+    }
+
+    fn matches(&self, c: char) -> bool {
+        match self {
+            Condition::Any => true,
+            Condition::Char(x) => *x == c,
+            Condition::Set(set) => set.contains(&c),
+            Condition::NotSet(set) => !set.contains(&c),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Affix {
+    flag: u32,
+    cross_product: bool,
+    strip: String,
+    add: String,
+    condition: Vec<Condition>,
+}
+
+impl Affix {
+    /// Whether the condition holds at the end (suffix) or start (prefix)
+    /// of `base`.
+    fn condition_holds(&self, base: &str, suffix: bool) -> bool {
+        let chars: Vec<char> = base.chars().collect();
+        if chars.len() < self.condition.len() {
+            return false;
+        }
+        let offset = if suffix { chars.len() - self.condition.len() } else { 0 };
+        self.condition.iter().enumerate().all(|(i, cond)| cond.matches(chars[offset + i]))
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Dictionary {
+    words: HashMap<String, Vec<u32>>,
+    /// Suffix rules keyed by the text they add
+    suffixes: HashMap<String, Vec<Affix>>,
+    prefixes: HashMap<String, Vec<Affix>>,
+    try_chars: Vec<char>,
+    replacements: Vec<(String, String)>,
+    forbidden: Option<u32>,
+    need_affix: Option<u32>,
+    no_suggest: Option<u32>,
+    only_in_compound: Option<u32>,
+}
+
+/// Text of a dictionary file in the encoding its .aff declares. Only UTF-8
+/// and ISO 8859-1 (by far the most common legacy one) are decoded exactly.
+pub fn decode(bytes: &[u8], aff: &[u8]) -> String {
+    let latin1 = String::from_utf8_lossy(aff)
+        .lines()
+        .find_map(|l| l.trim().strip_prefix("SET ").map(|e| e.trim().to_ascii_uppercase()))
+        .is_some_and(|e| e == "ISO8859-1" || e == "ISO-8859-1");
+    if latin1 {
+        bytes.iter().map(|b| *b as char).collect()
+    } else {
+        String::from_utf8_lossy(bytes).into_owned()
+    }
+}
+
+fn parse_flags(text: &str, mode: FlagMode, aliases: &[Vec<u32>]) -> Vec<u32> {
+    // AF aliases replace the flag string with its 1-based index
+    if !aliases.is_empty() {
+        if let Ok(n) = text.parse::<usize>() {
+            return aliases.get(n.wrapping_sub(1)).cloned().unwrap_or_default();
+        }
+    }
+    match mode {
+        FlagMode::Char => text.chars().map(|c| c as u32).collect(),
+        FlagMode::Long => {
+            let chars: Vec<char> = text.chars().collect();
+            chars.chunks(2).map(|pair| pair.iter().fold(0u32, |acc, c| (acc << 16) | *c as u32)).collect()
+        }
+        FlagMode::Num => text.split(',').filter_map(|n| n.trim().parse().ok()).collect(),
+    }
+}
+
+fn single_flag(text: &str, mode: FlagMode) -> Option<u32> {
+    parse_flags(text, mode, &[]).first().copied()
+}
+
+/// "0" stands for the empty string in affix rules.
+fn affix_text(text: &str) -> String {
+    if text == "0" {
+        String::new()
+    } else {
+        text.to_string()
+    }
+}
+
+impl Dictionary {
+    pub fn parse(aff: &str, dic: &str) -> Result<Dictionary, String> {
+        let mut dict = Dictionary::default();
+        let mut mode = FlagMode::Char;
+        let mut aliases: Vec<Vec<u32>> = Vec::new();
+        // Headers of affix groups seen so far, as (flag, cross product)
+        let mut groups: HashMap<(bool, u32), bool> = HashMap::new();
+
+        // Flag-valued directives are resolved once FLAG is known
+        let lines: Vec<Vec<&str>> = aff
+            .lines()
+            .map(|l| l.split('#').next().unwrap_or("").split_whitespace().collect::<Vec<_>>())
+            .filter(|parts| !parts.is_empty())
+            .collect();
+        if let Some(parts) = lines.iter().find(|p| p[0] == "FLAG") {
+            mode = match parts.get(1).copied() {
+                Some("long") => FlagMode::Long,
+                Some("num") => FlagMode::Num,
+                _ => FlagMode::Char,
+            };
+        }
+
+        for parts in &lines {
+            match parts[0] {
+                "AF" if parts.len() >= 2 && parts[1].parse::<usize>().is_err() => {
+                    aliases.push(parse_flags(parts[1], mode, &[]));
+                }
+                "TRY" if parts.len() >= 2 => dict.try_chars = parts[1].chars().collect(),
+                "REP" if parts.len() >= 3 => {
+                    dict.replacements.push((parts[1].replace('_', " "), parts[2].replace('_', " ")));
+                }
+                "FORBIDDENWORD" if parts.len() >= 2 => dict.forbidden = single_flag(parts[1], mode),
+                "NEEDAFFIX" | "PSEUDOROOT" if parts.len() >= 2 => dict.need_affix = single_flag(parts[1], mode),
+                "NOSUGGEST" if parts.len() >= 2 => dict.no_suggest = single_flag(parts[1], mode),
+                "ONLYINCOMPOUND" if parts.len() >= 2 => dict.only_in_compound = single_flag(parts[1], mode),
+                "PFX" | "SFX" if parts.len() >= 4 => {
+                    let suffix = parts[0] == "SFX";
+                    let Some(flag) = single_flag(parts[1], mode) else { continue };
+                    let key = (suffix, flag);
+                    if let std::collections::hash_map::Entry::Vacant(e) = groups.entry(key) {
+                        // Group header: PFX flag Y|N count
+                        e.insert(parts[2] == "Y");
+                        continue;
+                    }
+                    let add_field = parts[3];
+                    let add = affix_text(add_field.split('/').next().unwrap_or(""));
+                    let rule = Affix {
+                        flag,
+                        cross_product: groups[&key],
+                        strip: affix_text(parts[2]),
+                        add: add.clone(),
+                        condition: Condition::parse(parts.get(4).copied().unwrap_or(".")),
+                    };
+                    let table = if suffix { &mut dict.suffixes } else { &mut dict.prefixes };
+                    table.entry(add).or_default().push(rule);
+                }
+                _ => {}
+            }
+        }
+
+        let mut entries = dic.lines();
+        // The first line is an (approximate) word count
+        entries.next();
+        for line in entries {
+            let line = line.trim_end();
+            if line.is_empty() || line.starts_with('\t') {
+                continue;
+            }
+            // Morphological fields follow a tab or space-separated "xx:" field
+            let entry = line.split('\t').next().unwrap_or("");
+            let entry = entry.split(' ').next().unwrap_or("");
+            let (word, flags) = match entry.split_once('/') {
+                Some((word, flags)) if !word.is_empty() => (word, parse_flags(flags, mode, &aliases)),
+                _ => (entry, Vec::new()),
+            };
+            if word.is_empty() {
+                continue;
+            }
+            dict.words.entry(word.to_string()).or_default().extend(flags);
+        }
+        if dict.words.is_empty() {
+            return Err("The dictionary has no words".to_string());
+        }
+        Ok(dict)
+    }
+
+    fn has_flag(flags: &[u32], flag: Option<u32>) -> bool {
+        flag.is_some_and(|f| flags.contains(&f))
+    }
+
+    /// A stem usable on its own or with affixes.
+    fn stem(&self, word: &str) -> Option<&Vec<u32>> {
+        let flags = self.words.get(word)?;
+        (!Self::has_flag(flags, self.forbidden) && !Self::has_flag(flags, self.only_in_compound)).then_some(flags)
+    }
+
+    /// Stems `word` could be `rule` applied to.
+    fn strip_suffix(word: &str, rule: &Affix) -> Option<String> {
+        let rest = word.strip_suffix(rule.add.as_str())?;
+        if rest.is_empty() {
+            return None;
+        }
+        let base = format!("{}{}", rest, rule.strip);
+        rule.condition_holds(&base, true).then_some(base)
+    }
+
+    fn strip_prefix(word: &str, rule: &Affix) -> Option<String> {
+        let rest = word.strip_prefix(rule.add.as_str())?;
+        if rest.is_empty() {
+            return None;
+        }
+        let base = format!("{}{}", rule.strip, rest);
+        rule.condition_holds(&base, false).then_some(base)
+    }
+
+    /// Suffix rules whose added text ends `word`.
+    fn suffix_rules<'a>(&'a self, word: &'a str) -> impl Iterator<Item = &'a Affix> + 'a {
+        word.char_indices()
+            .map(|(i, _)| &word[i..])
+            .chain(std::iter::once(""))
+            .filter_map(|tail| self.suffixes.get(tail))
+            .flatten()
+    }
+
+    fn prefix_rules<'a>(&'a self, word: &'a str) -> impl Iterator<Item = &'a Affix> + 'a {
+        word.char_indices()
+            .map(|(i, c)| &word[..i + c.len_utf8()])
+            .chain(std::iter::once(""))
+            .filter_map(|head| self.prefixes.get(head))
+            .flatten()
+    }
+
+    fn check_suffixed(&self, word: &str, prefix: Option<&Affix>) -> bool {
+        self.suffix_rules(word).any(|rule| {
+            if prefix.is_some() && !rule.cross_product {
+                return false;
+            }
+            let Some(base) = Self::strip_suffix(word, rule) else { return false };
+            self.stem(&base).is_some_and(|flags| {
+                flags.contains(&rule.flag) && prefix.is_none_or(|p| flags.contains(&p.flag))
+            })
+        })
+    }
+
+    fn check_exact(&self, word: &str) -> bool {
+        if let Some(flags) = self.stem(word) {
+            if !Self::has_flag(flags, self.need_affix) {
+                return true;
+            }
+        }
+        if self.words.get(word).is_some_and(|flags| Self::has_flag(flags, self.forbidden)) {
+            return false;
+        }
+        if self.check_suffixed(word, None) {
+            return true;
+        }
+        self.prefix_rules(word).any(|rule| {
+            let Some(base) = Self::strip_prefix(word, rule) else { return false };
+            if self.stem(&base).is_some_and(|flags| flags.contains(&rule.flag)) {
+                return true;
+            }
+            rule.cross_product && self.check_suffixed(&base, Some(rule))
+        })
+    }
+
+    /// Whether `word` is spelled correctly, allowing the capitalisation of
+    /// a sentence start or an all-caps heading.
+    pub fn check(&self, word: &str) -> bool {
+        let word = word.trim_matches(|c: char| c == '\'' || c == '\u{2019}');
+        if word.is_empty() || self.check_exact(word) {
+            return true;
+        }
+        // Typographic apostrophes are spelled ' in dictionaries
+        if word.contains('\u{2019}') {
+            return self.check(&word.replace('\u{2019}', "'"));
+        }
+        let lower = word.to_lowercase();
+        let mut chars = word.chars();
+        let first_upper = chars.next().is_some_and(char::is_uppercase);
+        let all_upper = word.chars().filter(|c| c.is_alphabetic()).all(char::is_uppercase);
+        if all_upper {
+            return self.check_exact(&lower) || self.check_exact(&capitalize(&lower));
+        }
+        first_upper && chars.all(|c| !c.is_uppercase()) && self.check_exact(&lower)
+    }
+
+    /// Close spellings that check as correct, best first.
+    pub fn suggest(&self, word: &str) -> Vec<String> {
+        let lower = word.to_lowercase();
+        let capitalised = word.chars().next().is_some_and(char::is_uppercase);
+        let mut seen: HashSet<String> = HashSet::new();
+        let mut out: Vec<String> = Vec::new();
+        let mut consider = |candidate: String, out: &mut Vec<String>| {
+            if out.len() >= MAX_SUGGESTIONS || candidate == lower || !seen.insert(candidate.clone()) {
+                return;
+            }
+            let suggestible = candidate.split(' ').all(|part| {
+                self.check_exact(part) && !self.words.get(part).is_some_and(|f| Self::has_flag(f, self.no_suggest))
+            });
+            if suggestible {
+                out.push(candidate);
+            }
+        };
+
+        for (from, to) in &self.replacements {
+            let mut start = 0;
+            while let Some(i) = lower[start..].find(from.as_str()) {
+                let at = start + i;
+                consider(format!("{}{}{}", &lower[..at], to, &lower[at + from.len()..]), &mut out);
+                start = at + from.len().max(1);
+            }
+        }
+
+        let chars: Vec<char> = lower.chars().collect();
+        let try_chars: Vec<char> = if self.try_chars.is_empty() {
+            ('a'..='z').collect()
+        } else {
+            self.try_chars.iter().flat_map(|c| c.to_lowercase()).collect()
+        };
+        let rebuild = |chars: &[char]| chars.iter().collect::<String>();
+        // Swapped neighbours, then one letter missing, extra or wrong
+        for i in 0..chars.len().saturating_sub(1) {
+            let mut c = chars.clone();
+            c.swap(i, i + 1);
+            consider(rebuild(&c), &mut out);
+        }
+        for i in 0..=chars.len() {
+            for t in &try_chars {
+                let mut c = chars.clone();
+                c.insert(i, *t);
+                consider(rebuild(&c), &mut out);
+            }
+        }
+        for i in 0..chars.len() {
+            let mut c = chars.clone();
+            c.remove(i);
+            consider(rebuild(&c), &mut out);
+        }
+        for i in 0..chars.len() {
+            for t in &try_chars {
+                if *t != chars[i] {
+                    let mut c = chars.clone();
+                    c[i] = *t;
+                    consider(rebuild(&c), &mut out);
+                }
+            }
+        }
+        // Two words run together
+        for i in 1..chars.len() {
+            consider(format!("{} {}", rebuild(&chars[..i]), rebuild(&chars[i..])), &mut out);
+        }
+
+        if capitalised {
+            out.iter().map(|s| capitalize(s)).collect()
+        } else {
+            out
+        }
+    }
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
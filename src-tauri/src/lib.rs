@@ -1,17 +1,68 @@
 pub mod commands;
+pub mod connectivity;
 pub mod db;
+pub mod error;
+pub mod http;
+pub mod hunspell;
+pub mod listener;
+pub mod permissions;
+pub mod query_cache;
+pub mod sanitize;
 pub mod scheduler;
 pub mod services;
+pub mod text;
+pub mod trace;
 
 use tauri::Manager;
+use commands::accessibility;
 use commands::ai;
+use commands::analytics;
+use commands::archives;
+use commands::attachments;
+use commands::audit;
 use commands::audience;
+use commands::backfill;
+use commands::blocks;
+use commands::charts;
+use commands::compare;
+use commands::contacts;
 use commands::credentials;
+use commands::deliverability;
 use commands::export;
+use commands::export_history;
+use commands::export_jobs;
+use commands::focus;
+use commands::google;
+use commands::ideas;
 use commands::images;
+use commands::linkpage;
+use commands::links;
+use commands::mediakit;
+use commands::milestones;
+use commands::offline;
 use commands::platform;
+use commands::podcast;
+use commands::publish_profiles;
+use commands::replace;
 use commands::revenue;
 use commands::scheduler as scheduler_cmds;
+use commands::settings;
+use commands::signup;
+use commands::site;
+use commands::similarity;
+use commands::smtp;
+use commands::snapshots;
+use commands::snippets;
+use commands::spelling;
+use commands::storage;
+use commands::style;
+use commands::testimonials;
+use commands::transcription;
+use commands::usage;
+use commands::vault;
+use commands::webhooks;
+use commands::windows;
+use commands::workspaces;
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
@@ -23,12 +74,33 @@ pub fn run() {
         .setup(|app| {
             // Initialize SQLite database
             let db_state =
-                db::init_db(&app.handle()).expect("Failed to initialize database");
+                db::init_db(app.handle()).expect("Failed to initialize database");
             app.manage(db_state);
 
+            // Proxy / CA / timeout settings for all outbound HTTP
+            http::init(app.handle());
+
+            // TTLs for memoized dashboard queries
+            query_cache::init(app.handle());
+
             // Start background scheduler
             scheduler::start_scheduler(app.handle().clone());
 
+            // Requeue offline operations a quit left running
+            offline::recover_interrupted(app.handle());
+
+            // Watch connectivity and flush the offline queue on reconnect
+            connectivity::start_monitor(app.handle().clone());
+
+            // Local HTTP listener for the signup form, when enabled
+            listener::init(app.handle().clone());
+
+            // Mirror documents into the vault folder, when sync is enabled
+            vault::start_watcher(app.handle().clone());
+
+            // A focus session left running at quit stays paused until resumed
+            focus::pause_interrupted(app.handle());
+
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
@@ -40,27 +112,107 @@ pub fn run() {
             // Platform
             platform::connect_platform,
             platform::disconnect_platform,
+            platform::get_platform_capabilities,
             platform::get_publications,
+            platform::refresh_publications,
             platform::get_subscribers,
             platform::get_analytics,
             platform::publish_post,
+            platform::publish_post_multi,
+            publish_profiles::list_publish_profiles,
+            publish_profiles::save_publish_profile,
+            publish_profiles::delete_publish_profile,
+            compare::compare_with_published,
             platform::import_posts,
             platform::post_tweet,
             platform::post_thread,
             platform::post_linkedin,
+            platform::check_substack_session,
+            usage::get_platform_usage,
+            backfill::backfill_account,
+            archives::import_esp_archive,
+            // SMTP sending
+            smtp::get_smtp_settings,
+            smtp::save_smtp_settings,
+            smtp::test_smtp_connection,
+            smtp::send_smtp_test,
+            smtp::send_issue_smtp,
+            smtp::list_smtp_sends,
+            smtp::get_smtp_send_log,
+            smtp::process_unsubscribe,
+            // Offline queue
+            offline::get_connectivity_status,
+            offline::list_pending_operations,
+            offline::cancel_pending_operation,
+            offline::flush_pending_operations,
             // Export / Documents
             export::export_docx,
             export::export_pdf,
+            export_history::list_exports,
+            export_history::re_export,
+            export::preview_export,
+            export::export_epub,
+            export::export_project_archive,
+            export::export_documents_archive,
+            export_jobs::list_export_jobs,
+            export_jobs::cancel_export_job,
+            export::import_docx,
+            // Google Docs
+            google::start_google_auth,
+            google::export_to_google_doc,
+            google::import_google_doc,
+            export::export_markdown,
+            export::import_markdown,
+            export::export_email_html,
+            export::export_plaintext,
             export::save_document,
             export::load_document,
             export::list_documents,
+            export::archive_document,
+            export::pin_document,
+            export::reorder_documents,
+            export::set_document_goal,
+            export::search_documents,
+            replace::find_in_documents,
+            replace::replace_in_documents,
             export::delete_document,
             export::auto_save,
+            // Vault sync
+            vault::get_vault_sync_config,
+            vault::save_vault_sync_config,
+            vault::sync_vault_now,
+            vault::list_vault_files,
+            // Attachments
+            attachments::attach_file,
+            attachments::list_attachments,
+            attachments::delete_attachment,
+            transcription::transcribe_audio,
+            transcription::save_whisper_cpp_settings,
+            // Podcast
+            podcast::set_podcast_episode,
+            podcast::get_podcast_episode,
+            podcast::remove_podcast_episode,
+            podcast::get_episode_enclosure,
+            podcast::publish_podcast_episode,
+            podcast::summarize_podcast_episode,
+            // Link page
+            linkpage::get_link_page_config,
+            linkpage::save_link_page_config,
+            linkpage::generate_link_page,
+            linkpage::export_link_page,
+            linkpage::publish_link_page,
+            // Archive site
+            site::generate_static_site,
+            // Windows
+            windows::open_document_window,
             // Projects
             export::create_project,
             export::list_projects,
             export::update_project,
             export::delete_project,
+            export::get_project_detail,
+            export::archive_project,
+            export::create_project_from_template,
             export::move_document_to_project,
             export::set_document_status,
             export::add_document_tags,
@@ -68,15 +220,61 @@ pub fn run() {
             // Document versions
             export::get_document_versions,
             export::restore_document_version,
+            style::get_style_metrics,
+            similarity::find_similar_passages,
+            similarity::suggest_related_documents,
+            // Links
+            links::get_backlinks,
+            links::get_outgoing_links,
+            links::get_link_graph,
+            links::rebuild_link_index,
             // Activity
             export::get_recent_activity,
+            // Audit
+            audit::list_audit_log,
+            audit::verify_audit_log,
             // Scheduler
             scheduler_cmds::schedule_post,
             scheduler_cmds::list_scheduled_posts,
             scheduler_cmds::cancel_scheduled_post,
             scheduler_cmds::reschedule_post,
+            scheduler_cmds::reschedule_posts_batch,
             scheduler_cmds::publish_scheduled_now,
+            scheduler_cmds::set_unpublish_at,
+            webhooks::create_publish_webhook,
+            webhooks::list_publish_webhooks,
+            webhooks::delete_publish_webhook,
+            scheduler_cmds::list_publish_attempts,
+            scheduler_cmds::get_failure_summary,
+            scheduler_cmds::retry_failed_posts,
             scheduler_cmds::get_calendar_events,
+            scheduler_cmds::get_calendar_month_view,
+            scheduler_cmds::get_calendar_week_view,
+            scheduler_cmds::create_publishing_preset,
+            scheduler_cmds::list_publishing_presets,
+            scheduler_cmds::update_publishing_preset,
+            scheduler_cmds::delete_publishing_preset,
+            scheduler_cmds::create_blackout,
+            scheduler_cmds::list_blackouts,
+            scheduler_cmds::delete_blackout,
+            scheduler_cmds::pause_scheduler,
+            scheduler_cmds::resume_scheduler,
+            scheduler_cmds::get_scheduler_pause_state,
+            // Ideas
+            ideas::create_idea,
+            ideas::list_ideas,
+            ideas::update_idea,
+            ideas::delete_idea,
+            // Contacts
+            contacts::create_contact,
+            contacts::list_contacts,
+            contacts::get_contact,
+            contacts::update_contact,
+            contacts::delete_contact,
+            contacts::create_sponsorship_deal,
+            contacts::list_sponsorship_deals,
+            contacts::update_sponsorship_deal,
+            contacts::delete_sponsorship_deal,
             // Audience
             audience::sync_subscribers,
             audience::get_unified_subscribers,
@@ -85,22 +283,114 @@ pub fn run() {
             audience::untag_subscribers,
             audience::get_audience_stats,
             audience::get_audience_segments,
+            audience::get_audience_demographics,
+            deliverability::ingest_bounce_events,
+            deliverability::import_bounce_csv,
+            deliverability::list_subscriber_events,
+            deliverability::list_suppressions,
+            deliverability::add_suppression,
+            deliverability::remove_suppression,
+            signup::get_signup_form_config,
+            signup::save_signup_form_config,
+            signup::generate_signup_form,
+            signup::export_signup_form,
+            signup::list_pending_signups,
+            // Analytics
+            analytics::get_analytics_comparison,
+            analytics::get_publishing_heatmap,
+            analytics::get_writing_stats,
+            focus::start_focus_session,
+            focus::pause_focus_session,
+            focus::resume_focus_session,
+            focus::end_focus_session,
+            focus::get_active_focus_session,
+            focus::list_focus_sessions,
+            focus::get_focus_stats,
+            analytics::generate_year_review,
+            charts::render_chart,
+            analytics::build_digest,
+            milestones::list_milestones,
+            milestones::evaluate_milestones,
+            // Media kit
+            mediakit::get_media_kit_settings,
+            mediakit::save_media_kit_settings,
+            mediakit::generate_media_kit_pdf,
             // Revenue
             revenue::add_revenue_entry,
             revenue::list_revenue_entries,
             revenue::get_revenue_stats,
             revenue::delete_revenue_entry,
+            revenue::get_net_revenue,
+            revenue::import_stripe_revenue,
+            revenue::import_paddle_revenue,
+            revenue::import_substack_revenue_csv,
+            revenue::get_fee_rules,
+            revenue::save_fee_rules,
+            revenue::get_subscriber_ltv_stats,
+            // Testimonials
+            testimonials::save_testimonial,
+            testimonials::list_testimonials,
+            testimonials::delete_testimonial,
+            testimonials::import_social_mentions,
+            testimonials::get_best_testimonials,
+            // Content blocks
+            blocks::create_content_block,
+            blocks::list_content_blocks,
+            blocks::update_content_block,
+            blocks::delete_content_block,
+            blocks::assemble_issue,
+            // Snippets
+            snippets::save_snippet,
+            snippets::list_snippets,
+            snippets::delete_snippet,
+            snippets::render_snippet,
             // Templates
             export::save_user_template,
+            export::regenerate_thumbnails,
             export::list_user_templates,
             export::delete_user_template,
             export::increment_template_usage,
+            // Settings
+            settings::get_app_settings,
+            settings::set_app_setting,
+            settings::get_network_settings,
+            settings::save_network_settings,
+            settings::get_cache_settings,
+            settings::save_cache_settings,
+            settings::clear_query_cache,
+            settings::get_listener_info,
+            settings::save_listener_settings,
+            settings::get_access_info,
+            settings::set_access_role,
+            settings::set_owner_passphrase,
+            // Workspaces
+            workspaces::list_workspaces,
+            workspaces::create_workspace,
+            workspaces::switch_workspace,
+            // Spellcheck
+            spelling::check_spelling,
+            spelling::install_dictionary,
+            spelling::list_dictionaries,
+            spelling::add_to_dictionary,
+            spelling::remove_from_dictionary,
+            spelling::list_user_dictionary,
+            // Storage
+            storage::get_storage_breakdown,
+            storage::prune_storage,
+            storage::get_retention_policy,
+            storage::save_retention_policy,
+            storage::preview_retention,
+            storage::apply_retention_now,
+            snapshots::list_safety_snapshots,
+            snapshots::rollback_to_snapshot,
             // AI
             ai::save_ai_provider,
             ai::get_ai_providers,
             ai::delete_ai_provider,
             ai::ai_chat,
             ai::ai_chat_stream,
+            ai::ai_generate_alt_text,
+            accessibility::check_alt_text,
             // Images
             images::upload_image,
             images::list_images,
@@ -0,0 +1,310 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::{LazyLock, Mutex};
+use std::time::Duration;
+use tauri::AppHandle;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+use crate::commands::{google, settings, signup, webhooks};
+
+// ─── Local HTTP listener ────────────────────────────────────────
+//
+// A deliberately small HTTP/1.1 server for the few pages the app serves
+// itself (signup form, confirmation links, the Google OAuth redirect) and
+// the signed webhooks that release held publishes.
+// One request per connection, bodies capped at 64 KB, and a connection
+// that hasn't sent its whole request within ten seconds is dropped. It binds to
+// localhost unless the settings say otherwise; `public_url` is what links
+// point at when a tunnel or reverse proxy makes it reachable from outside.
+
+pub const LISTENER_SETTING: &str = "listener";
+const MAX_REQUEST_BYTES: usize = 64 * 1024;
+const READ_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ListenerSettings {
+    /// Start with the app
+    pub enabled: bool,
+    pub port: u16,
+    /// "127.0.0.1", or "0.0.0.0" to accept connections from the network
+    pub bind_address: String,
+    /// Externally reachable base URL, e.g. "https://signup.example.com"
+    pub public_url: Option<String>,
+}
+
+impl Default for ListenerSettings {
+    fn default() -> Self {
+        ListenerSettings {
+            enabled: false,
+            port: 8741,
+            bind_address: "127.0.0.1".to_string(),
+            public_url: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ListenerStatus {
+    pub running: bool,
+    pub port: Option<u16>,
+    /// Base URL links are built from
+    pub base_url: String,
+}
+
+pub struct Request {
+    pub method: String,
+    pub path: String,
+    /// Address of the connecting client
+    pub peer: Option<IpAddr>,
+    pub query: HashMap<String, String>,
+    /// Fields of an application/x-www-form-urlencoded body
+    pub form: HashMap<String, String>,
+    /// Header values by lowercased name
+    pub headers: HashMap<String, String>,
+    pub body: Vec<u8>,
+}
+
+pub struct Response {
+    pub status: u16,
+    pub content_type: &'static str,
+    pub body: String,
+}
+
+impl Response {
+    pub fn html(status: u16, body: String) -> Self {
+        Response { status, content_type: "text/html; charset=utf-8", body }
+    }
+
+    pub fn json(status: u16, body: &serde_json::Value) -> Self {
+        Response { status, content_type: "application/json", body: body.to_string() }
+    }
+}
+
+static RUNNING: LazyLock<Mutex<Option<(u16, tokio::task::AbortHandle)>>> = LazyLock::new(|| Mutex::new(None));
+
+pub fn listener_settings(app: &AppHandle) -> ListenerSettings {
+    settings::get_setting(app, LISTENER_SETTING).unwrap_or_default()
+}
+
+/// Base URL for links to the listener: `public_url`, else localhost.
+pub fn base_url(app: &AppHandle) -> String {
+    let config = listener_settings(app);
+    match config.public_url.as_deref().map(str::trim).filter(|u| !u.is_empty()) {
+        Some(url) => url.trim_end_matches('/').to_string(),
+        None => format!("http://127.0.0.1:{}", config.port),
+    }
+}
+
+pub fn status(app: &AppHandle) -> ListenerStatus {
+    let port = RUNNING.lock().ok().and_then(|r| r.as_ref().map(|(port, _)| *port));
+    ListenerStatus { running: port.is_some(), port, base_url: base_url(app) }
+}
+
+/// Bind and serve in the background, replacing a listener already running.
+pub async fn start(app: &AppHandle) -> Result<(), String> {
+    stop();
+    let config = listener_settings(app);
+    let listener = TcpListener::bind((config.bind_address.as_str(), config.port))
+        .await
+        .map_err(|e| format!("Cannot listen on {}:{}: {}", config.bind_address, config.port, e))?;
+    let app = app.clone();
+    let task = tokio::spawn(async move {
+        loop {
+            let Ok((stream, _)) = listener.accept().await else {
+                continue;
+            };
+            let app = app.clone();
+            tokio::spawn(async move {
+                if let Err(e) = serve(&app, stream).await {
+                    crate::trace::log("Listener", &format!("Request failed: {}", e));
+                }
+            });
+        }
+    });
+    if let Ok(mut running) = RUNNING.lock() {
+        *running = Some((config.port, task.abort_handle()));
+    }
+    crate::trace::log("Listener", &format!("Listening on {}:{}", config.bind_address, config.port));
+    Ok(())
+}
+
+pub fn stop() {
+    if let Some((_, handle)) = RUNNING.lock().ok().and_then(|mut r| r.take()) {
+        handle.abort();
+    }
+}
+
+/// Start at launch when enabled in settings.
+pub fn init(app: AppHandle) {
+    if !listener_settings(&app).enabled {
+        return;
+    }
+    tokio::spawn(async move {
+        if let Err(e) = start(&app).await {
+            crate::trace::log("Listener", &e);
+        }
+    });
+}
+
+/// `a=1&b=two+words` as a map, percent-decoded.
+pub fn parse_urlencoded(input: &str) -> HashMap<String, String> {
+    input
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            (url_decode(key), url_decode(value))
+        })
+        .collect()
+}
+
+fn url_decode(input: &str) -> String {
+    let bytes = input.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        // Two hex digits exactly; `from_str_radix` alone would take "%+1"
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .filter(|h| h.iter().all(u8::is_ascii_hexdigit))
+            .and_then(|h| std::str::from_utf8(h).ok());
+        match (bytes[i], hex.and_then(|h| u8::from_str_radix(h, 16).ok())) {
+            (b'+', _) => out.push(b' '),
+            (b'%', Some(b)) => {
+                out.push(b);
+                i += 2;
+            }
+            (b, _) => out.push(b),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).to_string()
+}
+
+async fn read_request(stream: &mut TcpStream) -> Result<Option<Request>, String> {
+    let mut buf = Vec::new();
+    let mut chunk = [0u8; 4096];
+    let header_end = loop {
+        let n = stream.read(&mut chunk).await.map_err(|e| e.to_string())?;
+        if n == 0 {
+            return Ok(None);
+        }
+        buf.extend_from_slice(&chunk[..n]);
+        if let Some(i) = buf.windows(4).position(|w| w == b"\r\n\r\n") {
+            break i;
+        }
+        if buf.len() > MAX_REQUEST_BYTES {
+            return Err("Request headers too large".to_string());
+        }
+    };
+    let head = String::from_utf8_lossy(&buf[..header_end]).to_string();
+    let mut lines = head.lines();
+    let mut request_line = lines.next().unwrap_or("").split_whitespace();
+    let method = request_line.next().unwrap_or("").to_uppercase();
+    let target = request_line.next().unwrap_or("/").to_string();
+    let headers: HashMap<String, String> = lines
+        .filter_map(|l| l.split_once(':'))
+        .map(|(name, value)| (name.trim().to_ascii_lowercase(), value.trim().to_string()))
+        .collect();
+    let content_length = headers.get("content-length").and_then(|value| value.parse::<usize>().ok()).unwrap_or(0);
+    if content_length > MAX_REQUEST_BYTES {
+        return Err("Request body too large".to_string());
+    }
+
+    let mut body = buf[header_end + 4..].to_vec();
+    while body.len() < content_length {
+        let n = stream.read(&mut chunk).await.map_err(|e| e.to_string())?;
+        if n == 0 {
+            break;
+        }
+        body.extend_from_slice(&chunk[..n]);
+    }
+    body.truncate(content_length);
+
+    let (path, query) = target.split_once('?').unwrap_or((&target, ""));
+    Ok(Some(Request {
+        method,
+        path: path.to_string(),
+        peer: stream.peer_addr().ok().map(|addr| addr.ip()),
+        query: parse_urlencoded(query),
+        form: parse_urlencoded(&String::from_utf8_lossy(&body)),
+        headers,
+        body,
+    }))
+}
+
+async fn serve(app: &AppHandle, mut stream: TcpStream) -> Result<(), String> {
+    let read = tokio::time::timeout(READ_TIMEOUT, read_request(&mut stream)).await;
+    let Some(request) = read.map_err(|_| "Timed out reading request".to_string())?? else {
+        return Ok(());
+    };
+    let response = match signup::handle(app, &request).await {
+        Some(response) => response,
+        None => match google::handle(app, &request).await {
+            Some(response) => response,
+            None => match webhooks::handle(app, &request).await {
+                Some(response) => response,
+                None => Response::html(404, "<h1>Not found</h1>".to_string()),
+            },
+        },
+    };
+    let reason = match response.status {
+        200 => "OK",
+        202 => "Accepted",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        404 => "Not Found",
+        409 => "Conflict",
+        410 => "Gone",
+        429 => "Too Many Requests",
+        _ => "Error",
+    };
+    let head = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        response.status,
+        reason,
+        response.content_type,
+        response.body.len()
+    );
+    stream.write_all(head.as_bytes()).await.map_err(|e| e.to_string())?;
+    stream.write_all(response.body.as_bytes()).await.map_err(|e| e.to_string())?;
+    stream.shutdown().await.ok();
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_escapes_and_plus() {
+        assert_eq!(url_decode("two+words%21"), "two words!");
+        assert_eq!(url_decode("caf%C3%A9"), "café");
+    }
+
+    #[test]
+    fn keeps_truncated_escapes_literally() {
+        assert_eq!(url_decode("100%"), "100%");
+        assert_eq!(url_decode("%4"), "%4");
+        assert_eq!(url_decode("a%2"), "a%2");
+        assert_eq!(url_decode("%%41"), "%A");
+    }
+
+    #[test]
+    fn keeps_invalid_escapes_literally() {
+        assert_eq!(url_decode("%zz"), "%zz");
+        assert_eq!(url_decode("%+1"), "% 1");
+        assert_eq!(url_decode("%é"), "%é");
+    }
+
+    #[test]
+    fn parses_form_bodies() {
+        let form = parse_urlencoded("email=a%40b.com&name=Ann+Lee&flag&=x");
+        assert_eq!(form.get("email").map(String::as_str), Some("a@b.com"));
+        assert_eq!(form.get("name").map(String::as_str), Some("Ann Lee"));
+        assert_eq!(form.get("flag").map(String::as_str), Some(""));
+    }
+}
@@ -0,0 +1,271 @@
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::Argon2;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tauri::AppHandle;
+
+use crate::commands::settings;
+use crate::error::AppError;
+
+// ─── Roles and permissions ──────────────────────────────────────
+//
+// Groundwork for shared machines and a later collaboration mode. The active
+// role lives in settings.json under `access`; with nothing stored the app
+// runs as owner, exactly as it did before roles existed. Sensitive commands
+// call `require` first. Returning to owner needs the owner passphrase once
+// one is set, otherwise a viewer could simply switch back. The webview has
+// no store or app-data write access, so it can't edit `access` directly.
+
+pub const ACCESS_SETTING: &str = "access";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum Role {
+    #[default]
+    Owner,
+    /// Writes and publishes; no credentials or revenue changes
+    Editor,
+    /// Reads content only
+    Viewer,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Permission {
+    /// Read, add or remove platform API keys and AI provider keys
+    ManageCredentials,
+    ViewRevenue,
+    /// Record, import or delete revenue, edit fee rules
+    ManageRevenue,
+    /// Delete documents, projects, contacts and other records
+    DeleteContent,
+    ViewAuditLog,
+    /// Mail issues to the subscriber list
+    SendEmail,
+}
+
+impl Permission {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Permission::ManageCredentials => "manage_credentials",
+            Permission::ViewRevenue => "view_revenue",
+            Permission::ManageRevenue => "manage_revenue",
+            Permission::DeleteContent => "delete_content",
+            Permission::ViewAuditLog => "view_audit_log",
+            Permission::SendEmail => "send_email",
+        }
+    }
+}
+
+pub const ALL_PERMISSIONS: &[Permission] = &[
+    Permission::ManageCredentials,
+    Permission::ViewRevenue,
+    Permission::ManageRevenue,
+    Permission::DeleteContent,
+    Permission::ViewAuditLog,
+    Permission::SendEmail,
+];
+
+impl Role {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Role::Owner => "owner",
+            Role::Editor => "editor",
+            Role::Viewer => "viewer",
+        }
+    }
+
+    pub fn allows(self, permission: Permission) -> bool {
+        match self {
+            Role::Owner => true,
+            Role::Editor => matches!(
+                permission,
+                Permission::ViewRevenue | Permission::DeleteContent | Permission::SendEmail
+            ),
+            Role::Viewer => false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AccessSettings {
+    #[serde(default)]
+    pub role: Role,
+    /// Argon2id PHC string. Hashes from before Argon2 are hex SHA-256 of
+    /// `salt` + passphrase and are rehashed on the next correct entry.
+    #[serde(default)]
+    pub owner_passphrase_hash: Option<String>,
+    /// Only set alongside a legacy SHA-256 hash
+    #[serde(default)]
+    pub salt: Option<String>,
+}
+
+pub fn load(app: &AppHandle) -> AccessSettings {
+    settings::get_setting(app, ACCESS_SETTING).unwrap_or_default()
+}
+
+pub fn save(app: &AppHandle, access: &AccessSettings) -> Result<(), AppError> {
+    let value = serde_json::to_value(access)?;
+    Ok(settings::set_setting(app, ACCESS_SETTING, value)?)
+}
+
+pub fn current_role(app: &AppHandle) -> Role {
+    load(app).role
+}
+
+/// Fail with an `auth` error unless the active role has `permission`.
+pub fn require(app: &AppHandle, permission: Permission) -> Result<(), AppError> {
+    let role = current_role(app);
+    if role.allows(permission) {
+        return Ok(());
+    }
+    Err(AppError::auth(format!(
+        "The {} role doesn't have the {} permission",
+        role.as_str(),
+        permission.as_str()
+    ))
+    .with_details(serde_json::json!({ "role": role, "permission": permission })))
+}
+
+/// Argon2id hash of `passphrase` with a fresh salt, as a PHC string.
+pub fn hash_passphrase(passphrase: &str) -> Result<String, AppError> {
+    // A v4 UUID is 16 random bytes from the OS
+    let salt = SaltString::encode_b64(uuid::Uuid::new_v4().as_bytes())
+        .map_err(|e| AppError::from(format!("Failed to hash passphrase: {}", e)))?;
+    Argon2::default()
+        .hash_password(passphrase.as_bytes(), &salt)
+        .map(|hash| hash.to_string())
+        .map_err(|e| AppError::from(format!("Failed to hash passphrase: {}", e)))
+}
+
+fn legacy_hash(salt: &str, passphrase: &str) -> String {
+    let digest = Sha256::digest(format!("{}{}", salt, passphrase).as_bytes());
+    digest.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Whether `passphrase` unlocks owner; always true when none is set.
+pub fn check_passphrase(access: &AccessSettings, passphrase: Option<&str>) -> bool {
+    let Some(hash) = &access.owner_passphrase_hash else {
+        return true;
+    };
+    let Some(passphrase) = passphrase else {
+        return false;
+    };
+    match &access.salt {
+        Some(salt) => legacy_hash(salt, passphrase) == *hash,
+        None => PasswordHash::new(hash)
+            .is_ok_and(|parsed| Argon2::default().verify_password(passphrase.as_bytes(), &parsed).is_ok()),
+    }
+}
+
+/// Set the owner passphrase, or clear it with `None`.
+pub fn set_passphrase(access: &mut AccessSettings, passphrase: Option<&str>) -> Result<(), AppError> {
+    access.owner_passphrase_hash = passphrase.map(hash_passphrase).transpose()?;
+    access.salt = None;
+    Ok(())
+}
+
+/// Move `access` to `role`. Becoming owner from another role needs the
+/// passphrase; a pre-Argon2 hash is upgraded only once the given
+/// passphrase has been checked against it.
+pub fn switch_role(access: &mut AccessSettings, role: Role, passphrase: Option<&str>) -> Result<(), AppError> {
+    let unlocked = access.owner_passphrase_hash.is_some() && check_passphrase(access, passphrase);
+    if role == Role::Owner && access.role != Role::Owner && !check_passphrase(access, passphrase) {
+        return Err(AppError::auth("Incorrect owner passphrase"));
+    }
+    if access.salt.is_some() && unlocked {
+        set_passphrase(access, passphrase)?;
+    }
+    access.role = role;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn with_passphrase(passphrase: &str) -> AccessSettings {
+        let mut access = AccessSettings::default();
+        set_passphrase(&mut access, Some(passphrase)).unwrap();
+        access
+    }
+
+    #[test]
+    fn argon2_passphrase_round_trips() {
+        let access = with_passphrase("correct horse");
+        assert!(access.owner_passphrase_hash.as_deref().unwrap().starts_with("$argon2id$"));
+        assert!(check_passphrase(&access, Some("correct horse")));
+        assert!(!check_passphrase(&access, Some("correct horsf")));
+        assert!(!check_passphrase(&access, None));
+    }
+
+    #[test]
+    fn same_passphrase_hashes_differently() {
+        assert_ne!(with_passphrase("pass").owner_passphrase_hash, with_passphrase("pass").owner_passphrase_hash);
+    }
+
+    #[test]
+    fn legacy_sha256_hash_still_unlocks() {
+        let access = AccessSettings {
+            role: Role::Viewer,
+            owner_passphrase_hash: Some(legacy_hash("abc", "pass")),
+            salt: Some("abc".to_string()),
+        };
+        assert!(check_passphrase(&access, Some("pass")));
+        assert!(!check_passphrase(&access, Some("nope")));
+    }
+
+    fn legacy(role: Role, passphrase: &str) -> AccessSettings {
+        AccessSettings {
+            role,
+            owner_passphrase_hash: Some(legacy_hash("abc", passphrase)),
+            salt: Some("abc".to_string()),
+        }
+    }
+
+    #[test]
+    fn stepping_down_with_a_guess_keeps_the_legacy_hash() {
+        let mut access = legacy(Role::Viewer, "pass");
+        switch_role(&mut access, Role::Viewer, Some("x")).unwrap();
+        assert!(access.salt.is_some());
+        assert!(check_passphrase(&access, Some("pass")));
+        assert!(switch_role(&mut access, Role::Owner, Some("x")).is_err());
+        assert_eq!(access.role, Role::Viewer);
+    }
+
+    #[test]
+    fn owner_reselecting_owner_cannot_overwrite_the_passphrase() {
+        let mut access = legacy(Role::Owner, "pass");
+        switch_role(&mut access, Role::Owner, Some("anything")).unwrap();
+        assert!(check_passphrase(&access, Some("pass")));
+        assert!(!check_passphrase(&access, Some("anything")));
+    }
+
+    #[test]
+    fn correct_passphrase_upgrades_a_legacy_hash() {
+        let mut access = legacy(Role::Viewer, "pass");
+        switch_role(&mut access, Role::Owner, Some("pass")).unwrap();
+        assert_eq!(access.role, Role::Owner);
+        assert!(access.salt.is_none());
+        assert!(access.owner_passphrase_hash.as_deref().unwrap().starts_with("$argon2id$"));
+        assert!(check_passphrase(&access, Some("pass")));
+    }
+
+    #[test]
+    fn a_stray_salt_without_a_hash_sets_no_passphrase() {
+        let mut access = AccessSettings { role: Role::Viewer, salt: Some("abc".to_string()), ..Default::default() };
+        switch_role(&mut access, Role::Viewer, Some("x")).unwrap();
+        assert!(access.owner_passphrase_hash.is_none());
+    }
+
+    #[test]
+    fn no_passphrase_always_unlocks() {
+        assert!(check_passphrase(&AccessSettings::default(), None));
+    }
+
+    #[test]
+    fn malformed_hash_never_unlocks() {
+        let access = AccessSettings { owner_passphrase_hash: Some("garbage".to_string()), ..Default::default() };
+        assert!(!check_passphrase(&access, Some("garbage")));
+    }
+}
@@ -0,0 +1,164 @@
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use std::any::Any;
+use std::collections::HashMap;
+use std::sync::{Arc, LazyLock, Mutex};
+use std::time::{Duration, Instant};
+use tauri::AppHandle;
+
+use crate::commands::settings;
+use crate::error::AppError;
+
+// ─── Dashboard query cache ──────────────────────────────────────
+//
+// Audience, revenue and analytics aggregates scan whole tables every time a
+// dashboard opens. Their results are memoized here, keyed by command and
+// parameters, together with the write counter of each table they read.
+// An SQLite update hook on the app's connection bumps a table's counter on
+// every row written, so a result is reused only while nothing it was
+// computed from has changed, and for no longer than its TTL: most results
+// also depend on today's date. Settings a command reads belong in its key.
+
+pub const CACHE_SETTING: &str = "query_cache";
+
+/// Results kept beyond this are dropped oldest first
+const MAX_ENTRIES: usize = 256;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CacheSettings {
+    pub enabled: bool,
+    /// Seconds a result is reused for commands without an override
+    pub default_ttl_secs: u64,
+    /// Per-command TTLs in seconds, keyed by command name
+    /// ("get_revenue_stats", ...); 0 turns caching off for that command
+    pub ttls: HashMap<String, u64>,
+}
+
+impl Default for CacheSettings {
+    fn default() -> Self {
+        CacheSettings { enabled: true, default_ttl_secs: 300, ttls: HashMap::new() }
+    }
+}
+
+impl CacheSettings {
+    fn ttl_for(&self, command: &str) -> Duration {
+        let secs = if self.enabled { self.ttls.get(command).copied().unwrap_or(self.default_ttl_secs) } else { 0 };
+        Duration::from_secs(secs)
+    }
+}
+
+struct Entry {
+    versions: Vec<u64>,
+    stored_at: Instant,
+    value: Arc<dyn Any + Send + Sync>,
+}
+
+struct Cache {
+    settings: CacheSettings,
+    entries: HashMap<String, Entry>,
+}
+
+static CACHE: LazyLock<Mutex<Cache>> =
+    LazyLock::new(|| Mutex::new(Cache { settings: CacheSettings::default(), entries: HashMap::new() }));
+
+/// Writes per table since startup. Kept apart from `CACHE` since the update
+/// hook runs on every row, with the database lock held.
+static VERSIONS: LazyLock<Mutex<HashMap<String, u64>>> = LazyLock::new(|| Mutex::new(HashMap::new()));
+
+fn versions_of(tables: &[&str]) -> Vec<u64> {
+    let Ok(versions) = VERSIONS.lock() else {
+        return Vec::new();
+    };
+    tables.iter().map(|t| versions.get(*t).copied().unwrap_or(0)).collect()
+}
+
+/// Count writes on `conn`. Set on every connection the app opens to its
+/// database, including the one reopened after a snapshot restore.
+pub fn watch(conn: &Connection) {
+    conn.update_hook(Some(|_, _: &str, table: &str, _| {
+        if let Ok(mut versions) = VERSIONS.lock() {
+            *versions.entry(table.to_string()).or_insert(0) += 1;
+        }
+    }));
+}
+
+/// A command's cache key: its name and the JSON of its parameters.
+pub struct Key {
+    command: &'static str,
+    key: String,
+}
+
+impl Key {
+    pub fn new(command: &'static str, params: &impl Serialize) -> Self {
+        let params = serde_json::to_string(params).unwrap_or_default();
+        Key { command, key: format!("{}:{}", command, params) }
+    }
+}
+
+/// The cached result for `key` if none of `tables` has been written since
+/// and its TTL hasn't passed, else `compute()`'s, which is stored when Ok.
+/// Versions are read before computing so a write made meanwhile isn't
+/// masked.
+pub fn cached<T, F>(key: Key, tables: &[&str], compute: F) -> Result<T, AppError>
+where
+    T: Clone + Send + Sync + 'static,
+    F: FnOnce() -> Result<T, AppError>,
+{
+    let versions = versions_of(tables);
+    let ttl = {
+        let Ok(cache) = CACHE.lock() else {
+            return compute();
+        };
+        let ttl = cache.settings.ttl_for(key.command);
+        if let Some(entry) = cache.entries.get(&key.key) {
+            if entry.versions == versions && entry.stored_at.elapsed() < ttl {
+                if let Some(value) = entry.value.downcast_ref::<T>() {
+                    return Ok(value.clone());
+                }
+            }
+        }
+        ttl
+    };
+    if ttl.is_zero() {
+        return compute();
+    }
+
+    let value = compute()?;
+    if let Ok(mut cache) = CACHE.lock() {
+        if cache.entries.len() >= MAX_ENTRIES && !cache.entries.contains_key(&key.key) {
+            let oldest = cache.entries.iter().min_by_key(|(_, e)| e.stored_at).map(|(k, _)| k.clone());
+            if let Some(oldest) = oldest {
+                cache.entries.remove(&oldest);
+            }
+        }
+        cache.entries.insert(key.key, Entry { versions, stored_at: Instant::now(), value: Arc::new(value.clone()) });
+    }
+    Ok(value)
+}
+
+/// Drop every cached result.
+pub fn clear() {
+    if let Ok(mut cache) = CACHE.lock() {
+        cache.entries.clear();
+    }
+}
+
+pub fn current_settings() -> CacheSettings {
+    CACHE.lock().map(|c| c.settings.clone()).unwrap_or_default()
+}
+
+/// Activate `settings`; results cached under the old TTLs are dropped.
+pub fn configure(settings: CacheSettings) {
+    if let Ok(mut cache) = CACHE.lock() {
+        cache.settings = settings;
+        cache.entries.clear();
+    }
+}
+
+/// Load saved cache settings at startup.
+pub fn init(app: &AppHandle) {
+    if let Some(saved) = settings::get_setting::<CacheSettings>(app, CACHE_SETTING) {
+        configure(saved);
+    }
+}
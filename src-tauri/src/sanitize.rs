@@ -0,0 +1,520 @@
+// ─── Import sanitizer ───────────────────────────────────────────
+//
+// Every import path (platform posts, ESP archives, DOCX, Google Docs,
+// Markdown and vault files) runs its HTML through `sanitize_html` before
+// it reaches the documents table. It keeps the structure the editor
+// understands and drops the rest:
+//
+// - tags outside the allowlist are unwrapped, keeping their text; scripts,
+//   styles, forms, embeds and the like go with their content
+// - attributes are allowlisted per tag, and URLs limited to http(s),
+//   mailto, in-page anchors and inline raster images
+// - tracking pixels (1×1 or hidden images, open-tracking paths), the
+//   platforms' subscribe and share widgets, and utm_*-style link
+//   parameters are removed
+// - relative URLs are resolved against the post's own URL when known
+
+/// Tags kept as they are.
+const ALLOWED_TAGS: &[&str] = &[
+    "a", "b", "blockquote", "br", "code", "del", "em", "figcaption", "figure", "h1", "h2", "h3", "h4", "h5", "h6",
+    "hr", "i", "img", "li", "mark", "ol", "p", "pre", "s", "strong", "sub", "sup", "table", "tbody", "td", "tfoot",
+    "th", "thead", "tr", "u", "ul",
+];
+
+/// Tags dropped together with everything inside them.
+const DROPPED_TAGS: &[&str] = &[
+    "audio", "button", "canvas", "embed", "form", "head", "iframe", "input", "math", "noscript", "object", "script",
+    "select", "style", "svg", "template", "textarea", "title", "video",
+];
+
+const VOID_TAGS: &[&str] = &["area", "base", "br", "col", "hr", "img", "input", "link", "meta", "source", "wbr"];
+
+/// Class names of subscribe boxes, share bars and CTA buttons that
+/// Substack, Ghost, beehiiv and Kit wrap around posts.
+const WIDGET_CLASSES: &[&str] = &[
+    "subscription-widget",
+    "subscribe-widget",
+    "share-dialog",
+    "button-wrapper",
+    "post-ufi",
+    "kg-signup-card",
+    "kg-cta-card",
+    "beehiiv__footer",
+    "formkit-form",
+];
+
+/// Link parameters that only identify the campaign or click.
+const TRACKING_PARAMS: &[&str] = &["fbclid", "gclid", "mc_cid", "mc_eid", "_hsenc", "_hsmi", "mkt_tok", "ck_subscriber_id"];
+
+/// Path fragments of open-tracking pixels.
+const TRACKER_PATHS: &[&str] = &["/open", "/track", "/pixel", "/beacon", "/wf/open", "/e/o/"];
+
+struct Tag {
+    name: String,
+    closing: bool,
+    self_closing: bool,
+    attrs: Vec<(String, String)>,
+    /// Byte offset just past the '>'
+    end: usize,
+}
+
+fn decode_entities(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(i) = rest.find('&') {
+        out.push_str(&rest[..i]);
+        rest = &rest[i..];
+        let Some(semi) = rest[1..].find(';').map(|s| s + 1).filter(|s| *s <= 10) else {
+            out.push('&');
+            rest = &rest[1..];
+            continue;
+        };
+        let entity = &rest[1..semi];
+        let decoded = match entity {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            "nbsp" => Some('\u{a0}'),
+            _ => entity
+                .strip_prefix("#x")
+                .or_else(|| entity.strip_prefix("#X"))
+                .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                .or_else(|| entity.strip_prefix('#').and_then(|dec| dec.parse().ok()))
+                .and_then(char::from_u32),
+        };
+        match decoded {
+            Some(c) => {
+                out.push(c);
+                rest = &rest[semi + 1..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+fn escape_attr(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+/// The tag starting at `start` (a '<'), or None when it isn't one.
+fn read_tag(html: &str, start: usize) -> Option<Tag> {
+    let bytes = html.as_bytes();
+    let mut i = start + 1;
+    let closing = bytes.get(i) == Some(&b'/');
+    if closing {
+        i += 1;
+    }
+    let name_start = i;
+    while i < bytes.len() && (bytes[i].is_ascii_alphanumeric() || bytes[i] == b'-' || bytes[i] == b':') {
+        i += 1;
+    }
+    if i == name_start {
+        return None;
+    }
+    let name = html[name_start..i].to_ascii_lowercase();
+    let mut attrs = Vec::new();
+    let mut self_closing = false;
+    loop {
+        while i < bytes.len() && bytes[i].is_ascii_whitespace() {
+            i += 1;
+        }
+        match bytes.get(i)? {
+            b'>' => return Some(Tag { name, closing, self_closing, attrs, end: i + 1 }),
+            b'/' => {
+                self_closing = true;
+                i += 1;
+                continue;
+            }
+            _ => {}
+        }
+        let attr_start = i;
+        while i < bytes.len() && !bytes[i].is_ascii_whitespace() && !matches!(bytes[i], b'=' | b'>' | b'/') {
+            i += 1;
+        }
+        let attr = html[attr_start..i].to_ascii_lowercase();
+        while i < bytes.len() && bytes[i].is_ascii_whitespace() {
+            i += 1;
+        }
+        let mut value = String::new();
+        if bytes.get(i) == Some(&b'=') {
+            i += 1;
+            while i < bytes.len() && bytes[i].is_ascii_whitespace() {
+                i += 1;
+            }
+            match bytes.get(i)? {
+                quote @ (b'"' | b'\'') => {
+                    let close = html[i + 1..].find(*quote as char)? + i + 1;
+                    value = decode_entities(&html[i + 1..close]);
+                    i = close + 1;
+                }
+                _ => {
+                    let value_start = i;
+                    while i < bytes.len() && !bytes[i].is_ascii_whitespace() && bytes[i] != b'>' {
+                        i += 1;
+                    }
+                    value = decode_entities(&html[value_start..i]);
+                }
+            }
+        }
+        if !attr.is_empty() {
+            self_closing = false;
+            attrs.push((attr, value));
+        } else if i == attr_start {
+            // Nothing consumed; step over one whole character
+            i += html[i..].chars().next().map_or(1, char::len_utf8);
+        }
+    }
+}
+
+fn attr<'a>(tag: &'a Tag, name: &str) -> Option<&'a str> {
+    tag.attrs.iter().find(|(n, _)| n == name).map(|(_, v)| v.as_str())
+}
+
+/// `href` against `base`, e.g. "/p/post" on "https://x.substack.com/p/a".
+pub fn resolve_url(base: Option<&str>, href: &str) -> String {
+    let href = href.trim();
+    let Some(base) = base.filter(|b| b.starts_with("http://") || b.starts_with("https://")) else {
+        return href.to_string();
+    };
+    if href.is_empty() || href.starts_with('#') || has_scheme(href) {
+        return href.to_string();
+    }
+    let scheme_end = base.find("://").map(|i| i + 3).unwrap_or(0);
+    let origin_end = base[scheme_end..].find('/').map(|i| i + scheme_end).unwrap_or(base.len());
+    let origin = &base[..origin_end];
+    if let Some(rest) = href.strip_prefix("//") {
+        return format!("{}//{}", &base[..scheme_end - 2], rest);
+    }
+    let base_path = base[origin_end..].split(['?', '#']).next().unwrap_or("");
+    if href.starts_with('?') {
+        return format!("{}{}{}", origin, if base_path.is_empty() { "/" } else { base_path }, href);
+    }
+    let joined = if href.starts_with('/') {
+        href.to_string()
+    } else {
+        let dir = &base_path[..base_path.rfind('/').map(|i| i + 1).unwrap_or(0)];
+        format!("{}{}", if dir.is_empty() { "/" } else { dir }, href)
+    };
+    // Collapse "." and ".." segments in the path (not the query)
+    let (path, query) = match joined.find(['?', '#']) {
+        Some(i) => joined.split_at(i),
+        None => (joined.as_str(), ""),
+    };
+    let mut segments: Vec<&str> = Vec::new();
+    for segment in path.split('/').skip(1) {
+        match segment {
+            "." => {}
+            ".." => {
+                segments.pop();
+            }
+            s => segments.push(s),
+        }
+    }
+    let trailing = path.ends_with("/.") || path.ends_with("/..");
+    format!("{}/{}{}{}", origin, segments.join("/"), if trailing { "/" } else { "" }, query)
+}
+
+fn has_scheme(url: &str) -> bool {
+    url.split_once(':').is_some_and(|(scheme, _)| {
+        !scheme.is_empty()
+            && scheme.chars().next().is_some_and(|c| c.is_ascii_alphabetic())
+            && scheme.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+    })
+}
+
+/// A URL that's safe to keep, made absolute. `image` also allows inline
+/// raster images.
+fn safe_url(base: Option<&str>, url: &str, image: bool) -> Option<String> {
+    // Browsers ignore control characters and whitespace inside schemes
+    let compact: String = url.chars().filter(|c| !c.is_control() && !c.is_whitespace()).collect();
+    let lower = compact.to_ascii_lowercase();
+    if image && lower.starts_with("data:image/") && !lower.starts_with("data:image/svg") {
+        return Some(compact);
+    }
+    let resolved = resolve_url(base, &compact);
+    let lower = resolved.to_ascii_lowercase();
+    let allowed = lower.starts_with("http://")
+        || lower.starts_with("https://")
+        || (!image && lower.starts_with("mailto:"))
+        || (!image && resolved.starts_with('#'))
+        // Relative URLs with no base to resolve against
+        || !has_scheme(&resolved);
+    allowed.then(|| strip_tracking_params(&resolved))
+}
+
+fn strip_tracking_params(url: &str) -> String {
+    let (rest, fragment) = match url.find('#') {
+        Some(i) => url.split_at(i),
+        None => (url, ""),
+    };
+    let Some((path, query)) = rest.split_once('?') else {
+        return url.to_string();
+    };
+    let kept: Vec<&str> = query
+        .split('&')
+        .filter(|pair| {
+            let key = pair.split('=').next().unwrap_or("").to_ascii_lowercase();
+            !key.is_empty() && !key.starts_with("utm_") && !TRACKING_PARAMS.contains(&key.as_str())
+        })
+        .collect();
+    if kept.is_empty() {
+        format!("{}{}", path, fragment)
+    } else {
+        format!("{}?{}{}", path, kept.join("&"), fragment)
+    }
+}
+
+fn hidden(tag: &Tag) -> bool {
+    let style = attr(tag, "style").unwrap_or("").to_ascii_lowercase().replace(' ', "");
+    style.contains("display:none") || style.contains("visibility:hidden") || attr(tag, "hidden").is_some()
+}
+
+fn is_widget(tag: &Tag) -> bool {
+    attr(tag, "class").is_some_and(|class| {
+        class.split_whitespace().any(|c| WIDGET_CLASSES.iter().any(|w| c.starts_with(w)))
+    })
+}
+
+fn is_tracking_pixel(tag: &Tag, src: &str) -> bool {
+    let tiny = |name: &str| attr(tag, name).and_then(|v| v.trim_end_matches("px").trim().parse::<u32>().ok()).is_some_and(|n| n <= 1);
+    let path = src.split('?').next().unwrap_or("").to_ascii_lowercase();
+    tiny("width") || tiny("height") || hidden(tag) || TRACKER_PATHS.iter().any(|p| path.contains(p))
+}
+
+/// The kept attributes of an allowed tag, rendered.
+fn render_attrs(tag: &Tag, base: Option<&str>) -> Option<String> {
+    let mut out = String::new();
+    let mut push = |name: &str, value: &str| out.push_str(&format!(" {}=\"{}\"", name, escape_attr(value)));
+    match tag.name.as_str() {
+        "a" => {
+            if let Some(href) = attr(tag, "href").and_then(|h| safe_url(base, h, false)) {
+                push("href", &href);
+            }
+            if let Some(title) = attr(tag, "title") {
+                push("title", title);
+            }
+        }
+        "img" => {
+            // Lazy-loading themes keep the real source in data-src
+            let src = attr(tag, "src")
+                .filter(|s| !s.trim().is_empty() && !s.starts_with("data:image/gif"))
+                .or_else(|| attr(tag, "data-src"))?;
+            let src = safe_url(base, src, true)?;
+            if is_tracking_pixel(tag, &src) {
+                return None;
+            }
+            push("src", &src);
+            push("alt", attr(tag, "alt").unwrap_or(""));
+            for name in ["title", "width", "height"] {
+                if let Some(value) = attr(tag, name) {
+                    push(name, value);
+                }
+            }
+        }
+        "td" | "th" => {
+            for name in ["colspan", "rowspan"] {
+                if let Some(value) = attr(tag, name).filter(|v| v.parse::<u32>().is_ok()) {
+                    push(name, value);
+                }
+            }
+        }
+        "ol" => {
+            if let Some(start) = attr(tag, "start").filter(|v| v.parse::<i64>().is_ok()) {
+                push("start", start);
+            }
+        }
+        "code" | "pre" => {
+            let language = attr(tag, "class").and_then(|class| {
+                class.split_whitespace().find(|c| c.starts_with("language-") && c.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '+' || c == '#'))
+            });
+            if let Some(language) = language {
+                push("class", language);
+            }
+        }
+        _ => {}
+    }
+    Some(out)
+}
+
+/// Index just past the element closing `name`, counting nested ones of
+/// the same name; the end of input when it's never closed.
+fn skip_element(html: &str, from: usize, name: &str) -> usize {
+    let lower = html.to_ascii_lowercase();
+    let raw_text = matches!(name, "script" | "style" | "textarea" | "title" | "noscript" | "template");
+    let open = format!("<{}", name);
+    let close = format!("</{}", name);
+    let mut depth = 1;
+    let mut i = from;
+    while i < lower.len() {
+        let next_close = lower[i..].find(&close).map(|p| p + i);
+        let next_open = if raw_text { None } else { lower[i..].find(&open).map(|p| p + i) };
+        match (next_open, next_close) {
+            (Some(o), Some(c)) if o < c => {
+                let boundary = lower.as_bytes().get(o + open.len()).is_none_or(|b| !b.is_ascii_alphanumeric());
+                if boundary {
+                    depth += 1;
+                }
+                i = o + open.len();
+            }
+            (_, Some(c)) => {
+                let end = lower[c..].find('>').map(|p| p + c + 1).unwrap_or(lower.len());
+                depth -= 1;
+                if depth == 0 {
+                    return end;
+                }
+                i = end;
+            }
+            (_, None) => return lower.len(),
+        }
+    }
+    lower.len()
+}
+
+/// Close the elements an opening `name` ends implicitly, as browsers do
+/// for unclosed `<li>`, `<p>`, `<tr>` and cells.
+fn close_implied(out: &mut String, open: &mut Vec<String>, name: &str) {
+    let (closes, scope): (&[&str], &[&str]) = match name {
+        "li" => (&["li"], &["ul", "ol"]),
+        "tr" => (&["tr", "td", "th"], &["table", "thead", "tbody", "tfoot"]),
+        "td" | "th" => (&["td", "th"], &["tr", "table"]),
+        "p" | "h1" | "h2" | "h3" | "h4" | "h5" | "h6" | "ul" | "ol" | "pre" | "blockquote" | "table" | "hr" => {
+            (&["p"], &["li", "td", "th", "blockquote", "figure"])
+        }
+        _ => return,
+    };
+    let Some(pos) = open.iter().rposition(|t| closes.contains(&t.as_str()) || scope.contains(&t.as_str())) else {
+        return;
+    };
+    if closes.contains(&open[pos].as_str()) {
+        for name in open.drain(pos..).rev() {
+            out.push_str(&format!("</{}>", name));
+        }
+    }
+}
+
+/// Clean imported HTML (see the module comment). `base_url` is the page
+/// the HTML came from, for resolving relative links and images.
+pub fn sanitize_html(html: &str, base_url: Option<&str>) -> String {
+    let mut out = String::with_capacity(html.len());
+    let mut open: Vec<String> = Vec::new();
+    let mut i = 0;
+    while i < html.len() {
+        let Some(offset) = html[i..].find('<') else {
+            out.push_str(&html[i..].replace('>', "&gt;"));
+            break;
+        };
+        out.push_str(&html[i..i + offset].replace('>', "&gt;"));
+        i += offset;
+
+        let rest = &html[i..];
+        if rest.starts_with("<!--") {
+            i = rest.find("-->").map(|p| i + p + 3).unwrap_or(html.len());
+            continue;
+        }
+        if rest.starts_with("<!") || rest.starts_with("<?") {
+            i = rest.find('>').map(|p| i + p + 1).unwrap_or(html.len());
+            continue;
+        }
+        let Some(tag) = read_tag(html, i) else {
+            out.push_str("&lt;");
+            i += 1;
+            continue;
+        };
+        i = tag.end;
+        let void = VOID_TAGS.contains(&tag.name.as_str()) || tag.self_closing;
+
+        if tag.closing {
+            if let Some(pos) = open.iter().rposition(|t| *t == tag.name) {
+                for name in open.drain(pos..).rev() {
+                    out.push_str(&format!("</{}>", name));
+                }
+            }
+            continue;
+        }
+        if DROPPED_TAGS.contains(&tag.name.as_str()) || ((is_widget(&tag) || hidden(&tag)) && tag.name != "img") {
+            if !void {
+                i = skip_element(html, i, &tag.name);
+            }
+            continue;
+        }
+        if !ALLOWED_TAGS.contains(&tag.name.as_str()) {
+            continue;
+        }
+        let Some(attrs) = render_attrs(&tag, base_url) else { continue };
+        close_implied(&mut out, &mut open, &tag.name);
+        out.push_str(&format!("<{}{}>", tag.name, attrs));
+        if !VOID_TAGS.contains(&tag.name.as_str()) {
+            if tag.self_closing {
+                out.push_str(&format!("</{}>", tag.name));
+            } else {
+                open.push(tag.name.clone());
+            }
+        }
+    }
+    for name in open.into_iter().rev() {
+        out.push_str(&format!("</{}>", name));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nameless_attributes_before_non_ascii_text_do_not_panic() {
+        assert_eq!(sanitize_html("<p =\"x\"é>hi</p>", None), "<p>hi</p>");
+        assert_eq!(sanitize_html("<p ='a'日本>x", None), "<p>x</p>");
+        assert_eq!(sanitize_html("<p =>ünïcödé</p>", None), "<p>ünïcödé</p>");
+    }
+
+    #[test]
+    fn only_allowlisted_url_schemes_survive() {
+        assert_eq!(sanitize_html("<a href=\"javascript:alert(1)\">x</a>", None), "<a>x</a>");
+        assert_eq!(sanitize_html("<a href=\"java\tscript:alert(1)\">x</a>", None), "<a>x</a>");
+        assert_eq!(sanitize_html("<a href=\"mailto:a@b.com\">x</a>", None), "<a href=\"mailto:a@b.com\">x</a>");
+        assert_eq!(sanitize_html("<img src=\"data:image/svg+xml,<svg/>\">", None), "");
+        assert_eq!(
+            sanitize_html("<img src=\"data:image/png;base64,AAAA\">", None),
+            "<img src=\"data:image/png;base64,AAAA\" alt=\"\">"
+        );
+    }
+
+    #[test]
+    fn links_are_resolved_and_stripped_of_tracking_params() {
+        let html = sanitize_html("<a href=\"/p/next?utm_source=x&amp;id=2\">next</a>", Some("https://x.substack.com/p/a"));
+        assert_eq!(html, "<a href=\"https://x.substack.com/p/next?id=2\">next</a>");
+    }
+
+    #[test]
+    fn tracking_pixels_are_removed() {
+        assert_eq!(sanitize_html("<img src=\"https://x.com/a.png\" width=\"1\" height=\"1\">", None), "");
+        assert_eq!(sanitize_html("<img src=\"https://mail.x.com/wf/open?u=1\">", None), "");
+        assert_eq!(sanitize_html("<img src=\"https://x.com/a.png\" style=\"display: none\">", None), "");
+        assert_eq!(
+            sanitize_html("<img src=\"https://x.com/a.png\" alt=\"Chart\">", None),
+            "<img src=\"https://x.com/a.png\" alt=\"Chart\">"
+        );
+    }
+
+    #[test]
+    fn dropped_elements_skip_nested_content() {
+        let html = "<div class=\"subscription-widget\"><div>inner</div>tail</div><p>after</p>";
+        assert_eq!(sanitize_html(html, None), "<p>after</p>");
+        assert_eq!(sanitize_html("<script>var s = \"<script>\";</script>ok", None), "ok");
+        assert_eq!(sanitize_html("<svg><svg></svg>still</svg><b>kept</b>", None), "<b>kept</b>");
+    }
+
+    #[test]
+    fn unknown_tags_are_unwrapped_and_attributes_escaped() {
+        assert_eq!(sanitize_html("<span onclick=\"x()\">hi</span>", None), "hi");
+        assert_eq!(sanitize_html("<a title='say \"hi\"'>x</a>", None), "<a title=\"say &quot;hi&quot;\">x</a>");
+    }
+}
@@ -1,5 +1,11 @@
-use crate::commands::platform::PublishRequest;
+use crate::commands::platform::{PublishOptions, PublishRequest};
+use crate::commands::publish_profiles;
+use crate::commands::scheduler as scheduler_cmds;
+use crate::commands::storage;
+use crate::commands::usage;
 use crate::db;
+use crate::error::AppError;
+use crate::services::twitter::{self, TwitterService};
 use crate::services::PlatformService;
 use chrono::Utc;
 use serde::Serialize;
@@ -14,32 +20,71 @@ struct ScheduleEvent {
     platform: String,
     status: String,
     message: String,
+    trace_id: Option<String>,
 }
 
+/// Ticks (30s each) between Substack session checks: every 6 hours
+const SESSION_CHECK_TICKS: u64 = 720;
+
+/// Ticks between retention runs (see commands::storage): once a day
+const RETENTION_TICKS: u64 = 2880;
+
+/// Warn this many days ahead of a session cookie lapsing
+const SESSION_WARNING_DAYS_SETTING: &str = "substack.session_warning_days";
+const DEFAULT_SESSION_WARNING_DAYS: i64 = 3;
+
+/// (id, document_id, platform, account_id, publication_id, title, publish_status, options_json)
+type DuePost = (String, String, String, String, Option<String>, String, String, String);
+
+/// (id, document_id, platform, account_id, publication_id, platform post id)
+type ExpiredPost = (String, String, String, String, Option<String>, Option<String>);
+
 pub fn start_scheduler(app: AppHandle) {
     tokio::spawn(async move {
         // Wait 5 seconds after startup before first check
         tokio::time::sleep(Duration::from_secs(5)).await;
 
         let mut interval = tokio::time::interval(Duration::from_secs(30));
+        let mut ticks: u64 = 0;
         loop {
             interval.tick().await;
             if let Err(e) = check_and_publish(&app).await {
                 eprintln!("[Scheduler] Error: {}", e);
             }
+            if ticks % SESSION_CHECK_TICKS == 0 && crate::connectivity::is_online() {
+                if let Err(e) = warn_expiring_substack_sessions(&app).await {
+                    crate::trace::log("Scheduler", &format!("Substack session check failed: {}", e));
+                }
+            }
+            if ticks % RETENTION_TICKS == 0 {
+                if let Err(e) = storage::apply_retention(&app) {
+                    crate::trace::log("Scheduler", &format!("Retention run failed: {}", e));
+                }
+            }
+            ticks += 1;
         }
     });
 }
 
 async fn check_and_publish(app: &AppHandle) -> Result<(), String> {
+    // Leave due posts pending while offline; they go out on the first tick
+    // after reconnecting.
+    if !crate::connectivity::is_online() {
+        return Ok(());
+    }
+    // Paused posts also stay pending, so resuming picks them straight up.
+    let pause = scheduler_cmds::pause_state(app);
+    if pause.paused {
+        return Ok(());
+    }
     let now = Utc::now().to_rfc3339();
 
     // Get all due posts
-    let due_posts: Vec<(String, String, String, String, Option<String>, String)> = {
+    let due_posts: Vec<DuePost> = {
         let conn = db::get_db(app)?;
         let mut stmt = conn
             .prepare(
-                "SELECT id, document_id, platform, account_id, publication_id, title
+                "SELECT id, document_id, platform, account_id, publication_id, title, publish_status, options_json
                  FROM scheduled_posts
                  WHERE scheduled_at <= ?1 AND status = 'pending'
                  ORDER BY scheduled_at ASC",
@@ -55,6 +100,8 @@ async fn check_and_publish(app: &AppHandle) -> Result<(), String> {
                     row.get::<_, String>(3)?,
                     row.get::<_, Option<String>>(4)?,
                     row.get::<_, String>(5)?,
+                    row.get::<_, String>(6)?,
+                    row.get::<_, String>(7)?,
                 ))
             })
             .map_err(|e| format!("Query map failed: {}", e))?;
@@ -62,136 +109,407 @@ async fn check_and_publish(app: &AppHandle) -> Result<(), String> {
         rows.filter_map(|r| r.ok()).collect()
     };
 
-    for (post_id, document_id, platform, account_id, publication_id, title) in due_posts {
-        // Mark as publishing
-        {
-            let conn = db::get_db(app)?;
-            conn.execute(
-                "UPDATE scheduled_posts SET status = 'publishing', updated_at = ?1 WHERE id = ?2",
-                rusqlite::params![now, post_id],
-            )
-            .ok();
+    for post in due_posts {
+        if pause.paused_platforms.contains(&post.2) {
+            continue;
         }
+        // Each post gets its own trace id so one failed publish can be
+        // followed through the logs, publish_attempts and activity_log.
+        crate::trace::scope(crate::trace::new_id(), publish_due_post(app, post, &now)).await?;
+    }
 
-        // Load document content
-        let html_content: String = {
-            let conn = db::get_db(app)?;
-            conn.query_row(
-                "SELECT html_content FROM documents WHERE id = ?1",
-                rusqlite::params![document_id],
-                |row| row.get(0),
+    check_and_unpublish(app, &pause.paused_platforms).await
+}
+
+/// Take down published posts whose `unpublish_at` has passed. Pauses
+/// apply as they do to publishing.
+async fn check_and_unpublish(app: &AppHandle, paused_platforms: &[String]) -> Result<(), String> {
+    let now = Utc::now().to_rfc3339();
+    let expired: Vec<ExpiredPost> = {
+        let conn = db::get_db(app)?;
+        // The platform's post id is what the publish attempt returned;
+        // older posts only have it in published_url
+        let mut stmt = conn
+            .prepare(
+                "SELECT p.id, p.document_id, p.platform, p.account_id, p.publication_id,
+                        COALESCE((SELECT a.post_id FROM publish_attempts a
+                                  WHERE a.scheduled_post_id = p.id AND a.status = 'published'
+                                  ORDER BY a.started_at DESC LIMIT 1), p.published_url)
+                 FROM scheduled_posts p
+                 WHERE p.unpublish_at IS NOT NULL AND p.unpublish_at <= ?1 AND p.status = 'published'
+                 ORDER BY p.unpublish_at ASC",
             )
-            .unwrap_or_default()
-        };
+            .map_err(|e| format!("Query failed: {}", e))?;
+        let rows = stmt
+            .query_map(rusqlite::params![now], |row| {
+                Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?, row.get(5)?))
+            })
+            .map_err(|e| format!("Query map failed: {}", e))?;
+        rows.filter_map(|r| r.ok()).collect()
+    };
 
-        if html_content.is_empty() {
-            // Mark as failed
-            let conn = db::get_db(app)?;
-            conn.execute(
-                "UPDATE scheduled_posts SET status = 'failed', error_message = 'Document content is empty', updated_at = ?1 WHERE id = ?2",
-                rusqlite::params![now, post_id],
-            ).ok();
+    for post in expired {
+        if paused_platforms.contains(&post.2) {
             continue;
         }
+        crate::trace::scope(crate::trace::new_id(), unpublish_expired_post(app, post)).await?;
+    }
+    Ok(())
+}
 
-        // Get API key
-        let api_key = {
-            let store = app
-                .store("credentials.json")
-                .map_err(|e| format!("Store error: {}", e))?;
-            let key = format!("{}:{}", platform, account_id);
-            match store.get(&key) {
-                Some(val) => {
-                    let cred: Option<crate::commands::credentials::StoredCredential> =
-                        serde_json::from_value(val.clone()).ok();
-                    cred.map(|c| c.api_key).unwrap_or_default()
-                }
-                None => String::new(),
+/// Unpublish one expired post and record the outcome on it.
+async fn unpublish_expired_post(app: &AppHandle, post: ExpiredPost) -> Result<(), String> {
+    let (post_id, document_id, platform, account_id, publication_id, platform_post_id) = post;
+    crate::trace::log("Scheduler", &format!("Unpublishing post {} from {}", post_id, platform));
+
+    let api_key = stored_api_key(app, &platform, &account_id)?;
+    let result = match (platform_post_id.as_deref().filter(|id| !id.is_empty()), api_key.as_str()) {
+        (None, _) => Err(AppError::validation("The platform's id for this post wasn't recorded")),
+        (_, "") => Err(AppError::validation("No API key found for account")),
+        (Some(id), key) => match platform.as_str() {
+            "ghost" => crate::services::ghost::GhostService::unpublish(key, id).await,
+            "beehiiv" => {
+                let pub_id = publication_id.as_deref().unwrap_or("default");
+                crate::services::beehiiv::BeehiivService::delete_post(key, pub_id, id).await
             }
-        };
+            "kit" => crate::services::kit::KitService::unpublish(key, id).await,
+            _ => Err(AppError::validation(format!("Posts on {} can't be unpublished automatically", platform))),
+        },
+    };
 
-        if api_key.is_empty() {
-            let conn = db::get_db(app)?;
+    let updated_now = Utc::now().to_rfc3339();
+    let conn = db::get_db(app)?;
+    usage::record(&conn, &platform, &account_id, usage::API_CALLS, 1);
+    let (status, message) = match result {
+        Ok(()) => {
             conn.execute(
-                "UPDATE scheduled_posts SET status = 'failed', error_message = 'No API key found for account', updated_at = ?1 WHERE id = ?2",
-                rusqlite::params![now, post_id],
+                "UPDATE scheduled_posts SET status = 'unpublished', unpublished_at = ?1, updated_at = ?1 WHERE id = ?2",
+                rusqlite::params![updated_now, post_id],
             ).ok();
-            continue;
+            let message = format!("Unpublished from {}", platform);
+            db::log_activity(&conn, "post.unpublished", "scheduled_post", Some(&post_id), Some(&message));
+            ("unpublished", message)
         }
+        Err(e) => {
+            let e = e.to_string();
+            crate::trace::log("Scheduler", &format!("Unpublishing post {} failed: {}", post_id, e));
+            conn.execute(
+                "UPDATE scheduled_posts SET status = 'unpublish_failed', error_message = ?1, updated_at = ?2 WHERE id = ?3",
+                rusqlite::params![e, updated_now, post_id],
+            ).ok();
+            db::log_activity(&conn, "post.unpublish_failed", "scheduled_post", Some(&post_id), Some(&e));
+            ("unpublish_failed", e)
+        }
+    };
 
-        // Publish via platform service
-        let pub_id = publication_id.as_deref().unwrap_or("default");
-        let request = PublishRequest {
-            title: title.clone(),
-            html_content: html_content.clone(),
-            subtitle: None,
-            preview_text: None,
-            status: "draft".to_string(),
-        };
-        let result = match platform.as_str() {
-            "beehiiv" => {
-                crate::services::beehiiv::BeehiivService::publish(&api_key, pub_id, request).await
-            }
-            "substack" => {
-                crate::services::substack::SubstackService::publish(&api_key, pub_id, request).await
-            }
-            "kit" => {
-                crate::services::kit::KitService::publish(&api_key, pub_id, request).await
-            }
-            "ghost" => {
-                crate::services::ghost::GhostService::publish(&api_key, pub_id, request).await
+    let _ = app.emit(
+        &format!("schedule:{}", status),
+        ScheduleEvent {
+            id: post_id,
+            document_id,
+            platform,
+            status: status.to_string(),
+            message,
+            trace_id: crate::trace::current(),
+        },
+    );
+    Ok(())
+}
+
+/// Emit "substack:session_warning" for every Substack account with
+/// scheduled posts whose session is dead, or will lapse before those posts
+/// go out or within the warning window.
+async fn warn_expiring_substack_sessions(app: &AppHandle) -> Result<(), String> {
+    let accounts: Vec<String> = {
+        let conn = db::get_db(app)?;
+        let mut stmt = conn
+            .prepare(
+                "SELECT DISTINCT account_id FROM scheduled_posts
+                 WHERE platform = 'substack' AND status IN ('pending', 'held')",
+            )
+            .map_err(|e| format!("Query failed: {}", e))?;
+        let rows = stmt
+            .query_map([], |row| row.get(0))
+            .map_err(|e| format!("Query map failed: {}", e))?;
+        rows.filter_map(|r| r.ok()).collect()
+    };
+    let warning_days = crate::commands::settings::get_setting::<i64>(app, SESSION_WARNING_DAYS_SETTING)
+        .unwrap_or(DEFAULT_SESSION_WARNING_DAYS);
+
+    for account_id in accounts {
+        let status = match crate::commands::platform::substack_session_status(app, &account_id).await {
+            Ok(status) => status,
+            Err(e) => {
+                crate::trace::log("Scheduler", &format!("Substack session check for {} failed: {}", account_id, e));
+                continue;
             }
-            _ => Err(format!("Unsupported platform: {}", platform)),
         };
+        let expiring = status.days_remaining.is_some_and(|d| d <= warning_days);
+        if status.valid && status.posts_at_risk == 0 && !expiring {
+            continue;
+        }
 
-        let updated_now = Utc::now().to_rfc3339();
-        match result {
-            Ok(url) => {
-                let conn = db::get_db(app)?;
-                conn.execute(
-                    "UPDATE scheduled_posts SET status = 'published', published_url = ?1, updated_at = ?2 WHERE id = ?3",
-                    rusqlite::params![url, updated_now, post_id],
-                ).ok();
+        if let Ok(conn) = db::get_db(app) {
+            db::log_activity(&conn, "substack.session_warning", "account", Some(&account_id), Some(&status.message));
+        }
+        let _ = app.emit("substack:session_warning", &status);
+    }
+    Ok(())
+}
 
-                // Update document status
-                conn.execute(
-                    "UPDATE documents SET status = 'published', published_at = ?1, updated_at = ?1 WHERE id = ?2",
-                    rusqlite::params![updated_now, document_id],
-                ).ok();
+/// The account's API key, or "" when none is stored.
+fn stored_api_key(app: &AppHandle, platform: &str, account_id: &str) -> Result<String, String> {
+    let store = app
+        .store("credentials.json")
+        .map_err(|e| format!("Store error: {}", e))?;
+    let key = format!("{}:{}", platform, account_id);
+    Ok(match store.get(&key) {
+        Some(val) => {
+            let cred: Option<crate::commands::credentials::StoredCredential> =
+                serde_json::from_value(val.clone()).ok();
+            cred.map(|c| c.api_key).unwrap_or_default()
+        }
+        None => String::new(),
+    })
+}
 
-                db::log_activity(&conn, "post.published", "scheduled_post", Some(&post_id), Some(&format!("Published to {} via scheduler", platform)));
-
-                let _ = app.emit(
-                    "schedule:published",
-                    ScheduleEvent {
-                        id: post_id,
-                        document_id,
-                        platform: platform.clone(),
-                        status: "published".to_string(),
-                        message: format!("Published to {}", platform),
-                    },
-                );
-            }
+/// Publish one due post. Runs inside the post's trace scope.
+async fn publish_due_post(app: &AppHandle, post: DuePost, now: &str) -> Result<(), String> {
+    let (post_id, document_id, platform, account_id, publication_id, title, publish_status, options_json) = post;
+    crate::trace::log("Scheduler", &format!("Publishing post {} to {}", post_id, platform));
+
+    // Hold (rather than fail) anything that would go out during a
+    // blackout, including blackouts added after the post was scheduled.
+    // Rescheduling puts it back to pending.
+    let blackout = {
+        let conn = db::get_db(app)?;
+        scheduler_cmds::blackout_at(&conn, now)
+    };
+    if let Some(blackout) = blackout {
+        let message = format!("Held: blackout '{}' until {}", blackout.name, blackout.ends_at);
+        let conn = db::get_db(app)?;
+        conn.execute(
+            "UPDATE scheduled_posts SET status = 'held', error_message = ?1, updated_at = ?2 WHERE id = ?3",
+            rusqlite::params![message, now, post_id],
+        ).ok();
+
+        let _ = app.emit(
+            "schedule:held",
+            ScheduleEvent {
+                id: post_id,
+                document_id,
+                platform,
+                status: "held".to_string(),
+                message,
+                trace_id: crate::trace::current(),
+            },
+        );
+        return Ok(());
+    }
+
+    // Mark as publishing
+    {
+        let conn = db::get_db(app)?;
+        conn.execute(
+            "UPDATE scheduled_posts SET status = 'publishing', updated_at = ?1 WHERE id = ?2",
+            rusqlite::params![now, post_id],
+        )
+        .ok();
+    }
+
+    // Load document content
+    let html_content: String = {
+        let conn = db::get_db(app)?;
+        conn.query_row(
+            "SELECT html_content FROM documents WHERE id = ?1",
+            rusqlite::params![document_id],
+            |row| row.get(0),
+        )
+        .unwrap_or_default()
+    };
+
+    if html_content.is_empty() {
+        // Mark as failed
+        let conn = db::get_db(app)?;
+        conn.execute(
+            "UPDATE scheduled_posts SET status = 'failed', error_message = 'Document content is empty', updated_at = ?1 WHERE id = ?2",
+            rusqlite::params![now, post_id],
+        ).ok();
+        return Ok(());
+    }
+
+    let api_key = stored_api_key(app, &platform, &account_id)?;
+
+    if api_key.is_empty() {
+        let conn = db::get_db(app)?;
+        conn.execute(
+            "UPDATE scheduled_posts SET status = 'failed', error_message = 'No API key found for account', updated_at = ?1 WHERE id = ?2",
+            rusqlite::params![now, post_id],
+        ).ok();
+        return Ok(());
+    }
+
+    // Publish via platform service
+    let pub_id = publication_id.as_deref().unwrap_or("default");
+    let options: PublishOptions = serde_json::from_str(&options_json).unwrap_or_default();
+    let html_content = if options.block_ids.is_empty() {
+        html_content
+    } else {
+        let conn = db::get_db(app)?;
+        match crate::commands::blocks::assemble(&conn, &document_id, &html_content, &options.block_ids, &Default::default()) {
+            Ok(assembled) => assembled.html,
             Err(e) => {
-                let conn = db::get_db(app)?;
                 conn.execute(
                     "UPDATE scheduled_posts SET status = 'failed', error_message = ?1, updated_at = ?2 WHERE id = ?3",
-                    rusqlite::params![e, updated_now, post_id],
+                    rusqlite::params![e.message, now, post_id],
                 ).ok();
+                return Ok(());
+            }
+        }
+    };
+    let thread = options.twitter.as_ref().is_some_and(|t| t.thread);
+    let mut request = PublishRequest {
+        title: title.clone(),
+        html_content: html_content.clone(),
+        subtitle: None,
+        preview_text: None,
+        status: publish_status,
+        options,
+        document_id: Some(document_id.clone()),
+        attachments: {
+            let conn = db::get_db(app)?;
+            crate::commands::attachments::for_publish(app, &conn, &document_id)
+        },
+    };
+    {
+        let conn = db::get_db(app)?;
+        publish_profiles::apply(&conn, &platform, &mut request).map_err(|e| e.message)?;
+    }
+    // A post over a usage limit is held like a blackout; reschedule it once
+    // the quota resets.
+    let planned = {
+        let conn = db::get_db(app)?;
+        let posts = if platform == "twitter" && thread {
+            twitter::split_thread(&crate::commands::export::html_to_plain_text(&html_content)).len() as i64
+        } else {
+            1
+        };
+        let planned = usage::publish_usage(&conn, &platform, &account_id, publication_id.as_deref(), &request.status, posts);
+        if let Err(e) = usage::check_limits(app, &conn, &platform, &account_id, &planned) {
+            let message = format!("Held: {}", e.message);
+            conn.execute(
+                "UPDATE scheduled_posts SET status = 'held', error_message = ?1, updated_at = ?2 WHERE id = ?3",
+                rusqlite::params![message, now, post_id],
+            ).ok();
+            let _ = app.emit(
+                "schedule:held",
+                ScheduleEvent {
+                    id: post_id,
+                    document_id,
+                    platform,
+                    status: "held".to_string(),
+                    message,
+                    trace_id: crate::trace::current(),
+                },
+            );
+            return Ok(());
+        }
+        planned
+    };
+    let attempt_id = {
+        let conn = db::get_db(app)?;
+        db::start_publish_attempt(&conn, Some(&post_id), &platform, &account_id, publication_id.as_deref())
+    };
+    let result = match platform.as_str() {
+        "beehiiv" => {
+            crate::services::beehiiv::BeehiivService::publish(&api_key, pub_id, request).await
+        }
+        "substack" => {
+            crate::services::substack::SubstackService::publish(&api_key, pub_id, request).await
+        }
+        "kit" => {
+            crate::services::kit::KitService::publish(&api_key, pub_id, request).await
+        }
+        "ghost" => {
+            crate::services::ghost::GhostService::publish(&api_key, pub_id, request).await
+        }
+        "twitter" => {
+            let text = crate::commands::export::html_to_plain_text(&html_content);
+            if thread {
+                TwitterService::post_thread(&api_key, twitter::split_thread(&text))
+                    .await
+                    .map(|ids| ids.into_iter().next().unwrap_or_default())
+            } else {
+                TwitterService::post_tweet(&api_key, &twitter::truncate_tweet(&text)).await
+            }
+        }
+        _ => Err(AppError::validation(format!("Unsupported platform: {}", platform))),
+    };
 
-                let _ = app.emit(
-                    "schedule:failed",
-                    ScheduleEvent {
-                        id: post_id,
-                        document_id,
-                        platform: platform.clone(),
-                        status: "failed".to_string(),
-                        message: e,
-                    },
-                );
+    {
+        let conn = db::get_db(app)?;
+        db::finish_publish_attempt(&conn, attempt_id, &result);
+        usage::record(&conn, &platform, &account_id, usage::API_CALLS, 1);
+        if result.is_ok() {
+            for (metric, amount) in &planned {
+                usage::record(&conn, &platform, &account_id, metric, *amount);
             }
         }
     }
 
+    let updated_now = Utc::now().to_rfc3339();
+    match result {
+        Ok(url) => {
+            let conn = db::get_db(app)?;
+            conn.execute(
+                "UPDATE scheduled_posts SET status = 'published', published_url = ?1, updated_at = ?2 WHERE id = ?3",
+                rusqlite::params![url, updated_now, post_id],
+            ).ok();
+
+            // Update document status
+            conn.execute(
+                "UPDATE documents SET status = 'published', published_at = ?1, updated_at = ?1 WHERE id = ?2",
+                rusqlite::params![updated_now, document_id],
+            ).ok();
+
+            db::log_activity(&conn, "post.published", "scheduled_post", Some(&post_id), Some(&format!("Published to {} via scheduler", platform)));
+            crate::commands::windows::document_changed(app, &document_id, "status", None, Some("published"));
+
+            let _ = app.emit(
+                "schedule:published",
+                ScheduleEvent {
+                    id: post_id,
+                    document_id,
+                    platform: platform.clone(),
+                    status: "published".to_string(),
+                    message: format!("Published to {}", platform),
+                    trace_id: crate::trace::current(),
+                },
+            );
+        }
+        Err(e) => {
+            let e = e.to_string();
+            crate::trace::log("Scheduler", &format!("Post {} failed: {}", post_id, e));
+            let conn = db::get_db(app)?;
+            conn.execute(
+                "UPDATE scheduled_posts SET status = 'failed', error_message = ?1, updated_at = ?2 WHERE id = ?3",
+                rusqlite::params![e, updated_now, post_id],
+            ).ok();
+            db::log_activity(&conn, "post.failed", "scheduled_post", Some(&post_id), Some(&e));
+
+            let _ = app.emit(
+                "schedule:failed",
+                ScheduleEvent {
+                    id: post_id,
+                    document_id,
+                    platform: platform.clone(),
+                    status: "failed".to_string(),
+                    message: e,
+                    trace_id: crate::trace::current(),
+                },
+            );
+        }
+    }
+
     Ok(())
 }
@@ -4,7 +4,8 @@ use serde::Deserialize;
 use crate::commands::platform::{
     AnalyticsData, ImportedPost, PostPerformance, Publication, PublishRequest, Subscriber,
 };
-use crate::services::PlatformService;
+use crate::error::AppError;
+use crate::services::{api_base, PlatformService};
 
 const BASE_URL: &str = "https://api.beehiiv.com/v2";
 
@@ -24,6 +25,14 @@ struct BeehiivPublication {
     url: Option<String>,
     #[serde(default)]
     description: Option<String>,
+    /// Present when requested with `expand[]=stats`
+    #[serde(default)]
+    stats: Option<BeehiivPublicationStats>,
+}
+
+#[derive(Deserialize)]
+struct BeehiivPublicationStats {
+    active_subscriptions: Option<u64>,
 }
 
 #[derive(Deserialize)]
@@ -59,7 +68,7 @@ struct BeehiivSingleResponse<T> {
 }
 
 fn client(api_key: &str) -> Result<Client, String> {
-    Client::builder()
+    crate::http::builder("beehiiv")?
         .default_headers({
             let mut h = reqwest::header::HeaderMap::new();
             h.insert(
@@ -77,26 +86,27 @@ fn client(api_key: &str) -> Result<Client, String> {
 }
 
 impl PlatformService for BeehiivService {
-    async fn validate_connection(api_key: &str) -> Result<bool, String> {
+    async fn validate_connection(api_key: &str) -> Result<bool, AppError> {
         let c = client(api_key)?;
         let resp = c
-            .get(format!("{}/publications", BASE_URL))
+            .get(format!("{}/publications", api_base(BASE_URL)))
             .send()
             .await
-            .map_err(|e| e.to_string())?;
+            .map_err(|e| AppError::network("beehiiv", "Beehiiv request failed", e))?;
         Ok(resp.status().is_success())
     }
 
-    async fn get_publications(api_key: &str) -> Result<Vec<Publication>, String> {
+    async fn get_publications(api_key: &str) -> Result<Vec<Publication>, AppError> {
         let c = client(api_key)?;
         let resp = c
-            .get(format!("{}/publications", BASE_URL))
+            .get(format!("{}/publications", api_base(BASE_URL)))
+            .query(&[("expand[]", "stats")])
             .send()
             .await
-            .map_err(|e| e.to_string())?;
+            .map_err(|e| AppError::network("beehiiv", "Beehiiv request failed", e))?;
 
         if !resp.status().is_success() {
-            return Err(format!("Beehiiv API error: {}", resp.status()));
+            return Err(AppError::from_response("beehiiv", "Beehiiv API error", resp).await);
         }
 
         let body: BeehiivListResponse<BeehiivPublication> =
@@ -110,7 +120,7 @@ impl PlatformService for BeehiivService {
                 name: p.name,
                 url: p.url.unwrap_or_default(),
                 platform: "beehiiv".to_string(),
-                subscriber_count: None,
+                subscriber_count: p.stats.and_then(|s| s.active_subscriptions),
                 description: p.description,
             })
             .collect())
@@ -119,20 +129,20 @@ impl PlatformService for BeehiivService {
     async fn get_subscribers(
         api_key: &str,
         publication_id: Option<&str>,
-    ) -> Result<Vec<Subscriber>, String> {
+    ) -> Result<Vec<Subscriber>, AppError> {
         let pub_id = publication_id.ok_or("Publication ID required for Beehiiv")?;
         let c = client(api_key)?;
         let resp = c
             .get(format!(
                 "{}/publications/{}/subscriptions",
-                BASE_URL, pub_id
+                api_base(BASE_URL), pub_id
             ))
             .send()
             .await
-            .map_err(|e| e.to_string())?;
+            .map_err(|e| AppError::network("beehiiv", "Beehiiv request failed", e))?;
 
         if !resp.status().is_success() {
-            return Err(format!("Beehiiv API error: {}", resp.status()));
+            return Err(AppError::from_response("beehiiv", "Beehiiv API error", resp).await);
         }
 
         let body: BeehiivListResponse<BeehiivSubscription> =
@@ -154,6 +164,7 @@ impl PlatformService for BeehiivService {
                     })
                     .unwrap_or_default(),
                 platform: "beehiiv".to_string(),
+                country: None,
             })
             .collect())
     }
@@ -161,17 +172,17 @@ impl PlatformService for BeehiivService {
     async fn get_analytics(
         api_key: &str,
         publication_id: Option<&str>,
-    ) -> Result<AnalyticsData, String> {
+    ) -> Result<AnalyticsData, AppError> {
         let pub_id = publication_id.ok_or("Publication ID required for Beehiiv")?;
         let c = client(api_key)?;
 
         // Fetch posts for analytics
         let posts_resp = c
-            .get(format!("{}/publications/{}/posts", BASE_URL, pub_id))
+            .get(format!("{}/publications/{}/posts", api_base(BASE_URL), pub_id))
             .query(&[("status", "confirmed"), ("limit", "50")])
             .send()
             .await
-            .map_err(|e| e.to_string())?;
+            .map_err(|e| AppError::network("beehiiv", "Beehiiv request failed", e))?;
 
         let posts_data: BeehiivListResponse<BeehiivPost> = if posts_resp.status().is_success() {
             posts_resp.json().await.map_err(|e| e.to_string())?
@@ -183,12 +194,12 @@ impl PlatformService for BeehiivService {
         let subs_resp = c
             .get(format!(
                 "{}/publications/{}/subscriptions",
-                BASE_URL, pub_id
+                api_base(BASE_URL), pub_id
             ))
             .query(&[("limit", "1")])
             .send()
             .await
-            .map_err(|e| e.to_string())?;
+            .map_err(|e| AppError::network("beehiiv", "Beehiiv request failed", e))?;
 
         let total_subscribers: u64 = if subs_resp.status().is_success() {
             // Beehiiv returns total_count in the response
@@ -259,30 +270,42 @@ impl PlatformService for BeehiivService {
         api_key: &str,
         publication_id: &str,
         request: PublishRequest,
-    ) -> Result<String, String> {
+    ) -> Result<String, AppError> {
         let c = client(api_key)?;
 
-        let body = serde_json::json!({
+        let mut body = serde_json::json!({
             "content_html": request.html_content,
             "title": request.title,
             "subtitle": request.subtitle.unwrap_or_default(),
             "preview_text": request.preview_text.unwrap_or_default(),
             "status": request.status,
         });
+        if let Some(opts) = request.options.beehiiv {
+            if let Some(audience) = opts.audience {
+                body["audience"] = serde_json::json!(audience);
+            }
+            if !opts.segment_ids.is_empty() {
+                body["recipients"] = serde_json::json!({
+                    "email": { "include_segment_ids": opts.segment_ids }
+                });
+            }
+            if !opts.content_tags.is_empty() {
+                body["content_tags"] = serde_json::json!(opts.content_tags);
+            }
+        }
 
         let resp = c
             .post(format!(
                 "{}/publications/{}/posts",
-                BASE_URL, publication_id
+                api_base(BASE_URL), publication_id
             ))
             .json(&body)
             .send()
             .await
-            .map_err(|e| e.to_string())?;
+            .map_err(|e| AppError::network("beehiiv", "Beehiiv request failed", e))?;
 
         if !resp.status().is_success() {
-            let err_text = resp.text().await.unwrap_or_else(|_| "Unknown error".to_string());
-            return Err(format!("Beehiiv publish error: {}", err_text));
+            return Err(AppError::from_response("beehiiv", "Beehiiv publish error", resp).await);
         }
 
         let result: BeehiivSingleResponse<BeehiivPost> =
@@ -297,19 +320,19 @@ impl BeehiivService {
     pub async fn import_posts(
         api_key: &str,
         publication_id: Option<&str>,
-    ) -> Result<Vec<ImportedPost>, String> {
+    ) -> Result<Vec<ImportedPost>, AppError> {
         let pub_id = publication_id.ok_or("Publication ID required for Beehiiv import")?;
         let c = client(api_key)?;
 
         let resp = c
-            .get(format!("{}/publications/{}/posts", BASE_URL, pub_id))
+            .get(format!("{}/publications/{}/posts", api_base(BASE_URL), pub_id))
             .query(&[("status", "confirmed"), ("limit", "50"), ("expand", "free_web_content")])
             .send()
             .await
-            .map_err(|e| e.to_string())?;
+            .map_err(|e| AppError::network("beehiiv", "Beehiiv request failed", e))?;
 
         if !resp.status().is_success() {
-            return Err(format!("Beehiiv import error: {}", resp.status()));
+            return Err(AppError::from_response("beehiiv", "Beehiiv import error", resp).await);
         }
 
         let body: BeehiivListResponse<BeehiivPost> =
@@ -333,3 +356,64 @@ impl BeehiivService {
             .collect())
     }
 }
+
+// ─── Unpublish (standalone, not on trait) ───────────────────────
+
+impl BeehiivService {
+    /// Delete a post. Beehiiv archives posts that were already sent rather
+    /// than removing them, which takes them off the web. A post that's
+    /// already gone counts as done.
+    pub async fn delete_post(api_key: &str, publication_id: &str, post_id: &str) -> Result<(), AppError> {
+        let c = client(api_key)?;
+        let resp = c
+            .delete(format!("{}/publications/{}/posts/{}", api_base(BASE_URL), publication_id, post_id))
+            .send()
+            .await
+            .map_err(|e| AppError::network("beehiiv", "Beehiiv request failed", e))?;
+
+        if resp.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(());
+        }
+        if !resp.status().is_success() {
+            return Err(AppError::from_response("beehiiv", "Beehiiv delete error", resp).await);
+        }
+        Ok(())
+    }
+}
+
+// ─── Single post (standalone, not on trait) ─────────────────────
+
+impl BeehiivService {
+    /// One post with its web HTML, or None if the publication doesn't
+    /// have it.
+    pub async fn get_post(api_key: &str, publication_id: &str, post_id: &str) -> Result<Option<ImportedPost>, AppError> {
+        let c = client(api_key)?;
+        let resp = c
+            .get(format!("{}/publications/{}/posts/{}", api_base(BASE_URL), publication_id, post_id))
+            .query(&[("expand", "free_web_content")])
+            .send()
+            .await
+            .map_err(|e| AppError::network("beehiiv", "Beehiiv request failed", e))?;
+        if resp.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if !resp.status().is_success() {
+            return Err(AppError::from_response("beehiiv", "Beehiiv post lookup error", resp).await);
+        }
+
+        let body: BeehiivSingleResponse<BeehiivPost> = resp.json().await.map_err(|e| e.to_string())?;
+        let p = body.data;
+        Ok(Some(ImportedPost {
+            id: p.id,
+            title: p.title.unwrap_or_else(|| "Untitled".to_string()),
+            html_content: p.content_html.unwrap_or_default(),
+            published_at: p.publish_date.map(|t| {
+                chrono::DateTime::from_timestamp(t, 0)
+                    .map(|dt| dt.format("%Y-%m-%dT%H:%M:%SZ").to_string())
+                    .unwrap_or_default()
+            }),
+            url: p.web_url,
+            platform: "beehiiv".to_string(),
+        }))
+    }
+}
@@ -0,0 +1,1038 @@
+//! Contract tests: every PlatformService method (and the revenue
+//! connectors) against recorded API responses on a mock server.
+
+use crate::commands::platform::{GhostPublishOptions, PublishAttachment, PublishOptions, PublishRequest};
+use crate::error::ErrorKind;
+use crate::services::beehiiv::BeehiivService;
+use crate::services::ghost::GhostService;
+use crate::services::google::{self, GoogleService};
+use crate::services::kit::KitService;
+use crate::services::mastodon::MastodonService;
+use crate::services::paddle::{self, PaddleService};
+use crate::services::stripe::StripeService;
+use crate::services::substack::SubstackService;
+use crate::services::test_support::{fixture, MockApi};
+use crate::services::twitter::TwitterService;
+use crate::services::wordpress::WordPressService;
+use crate::services::PlatformService;
+use wiremock::matchers::{method, path, query_param};
+use wiremock::{Mock, ResponseTemplate};
+
+fn publish_request(status: &str) -> PublishRequest {
+    PublishRequest {
+        title: "New issue".to_string(),
+        html_content: "<p>Body</p>".to_string(),
+        subtitle: Some("Sub".to_string()),
+        preview_text: Some("Preview".to_string()),
+        status: status.to_string(),
+        options: PublishOptions::default(),
+        document_id: None,
+        attachments: vec![],
+    }
+}
+
+// ─── Beehiiv ────────────────────────────────────────────────────
+
+mod beehiiv {
+    use super::*;
+
+    const PUB: &str = "/publications/pub_1";
+
+    #[tokio::test]
+    async fn validate_connection_reflects_status() {
+        let api = MockApi::start("/v2").await;
+        api.respond("GET", "/publications", "beehiiv", "publications").await;
+        assert!(BeehiivService::validate_connection("key").await.unwrap());
+
+        let api = MockApi::start("/v2").await;
+        api.respond_status("GET", "/publications", 401).await;
+        assert!(!BeehiivService::validate_connection("bad").await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn get_publications_maps_fields() {
+        let api = MockApi::start("/v2").await;
+        api.respond("GET", "/publications", "beehiiv", "publications").await;
+
+        let pubs = BeehiivService::get_publications("key").await.unwrap();
+        assert_eq!(pubs.len(), 1);
+        assert_eq!(pubs[0].name, "The Weekly Draft");
+        assert_eq!(pubs[0].platform, "beehiiv");
+        assert_eq!(pubs[0].url, "https://weeklydraft.beehiiv.com");
+        assert_eq!(pubs[0].subscriber_count, Some(1250));
+    }
+
+    #[tokio::test]
+    async fn get_publications_surfaces_errors() {
+        let api = MockApi::start("/v2").await;
+        api.respond_status("GET", "/publications", 500).await;
+        assert!(BeehiivService::get_publications("key").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn get_subscribers_requires_publication() {
+        assert!(BeehiivService::get_subscribers("key", None).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn get_subscribers_maps_fields() {
+        let api = MockApi::start("/v2").await;
+        api.respond("GET", &format!("{}/subscriptions", PUB), "beehiiv", "subscriptions")
+            .await;
+
+        let subs = BeehiivService::get_subscribers("key", Some("pub_1")).await.unwrap();
+        assert_eq!(subs.len(), 2);
+        assert_eq!(subs[0].email, "ada@example.com");
+        assert_eq!(subs[0].created_at, "2024-01-01");
+        assert_eq!(subs[1].platform, "beehiiv");
+    }
+
+    #[tokio::test]
+    async fn get_analytics_computes_rates() {
+        let api = MockApi::start("/v2").await;
+        api.respond("GET", &format!("{}/posts", PUB), "beehiiv", "posts").await;
+        api.respond("GET", &format!("{}/subscriptions", PUB), "beehiiv", "subscriptions")
+            .await;
+
+        let data = BeehiivService::get_analytics("key", Some("pub_1")).await.unwrap();
+        assert_eq!(data.total_subscribers, 2);
+        assert_eq!(data.recent_posts.len(), 2);
+        // 200 opens over 2 subscribers x 2 posts
+        assert!((data.open_rate - 5000.0).abs() < 1e-6);
+        // 40 clicks / 200 opens
+        assert!((data.click_rate - 20.0).abs() < 1e-6);
+    }
+
+    #[tokio::test]
+    async fn publish_sends_payload_and_returns_id() {
+        let api = MockApi::start("/v2").await;
+        api.respond("POST", &format!("{}/posts", PUB), "beehiiv", "post_created").await;
+
+        let id = BeehiivService::publish("key", "pub_1", publish_request("draft"))
+            .await
+            .unwrap();
+        assert_eq!(id, "post_00000000-0000-0000-0000-000000000099");
+
+        let bodies = api.request_bodies().await;
+        assert_eq!(bodies[0]["title"], "New issue");
+        assert_eq!(bodies[0]["content_html"], "<p>Body</p>");
+        assert_eq!(bodies[0]["status"], "draft");
+    }
+
+    #[tokio::test]
+    async fn delete_post_treats_missing_post_as_done() {
+        let api = MockApi::start("/v2").await;
+        api.respond_with("DELETE", &format!("{}/posts/post_9", PUB), ResponseTemplate::new(204)).await;
+        BeehiivService::delete_post("key", "pub_1", "post_9").await.unwrap();
+
+        let api = MockApi::start("/v2").await;
+        api.respond_status("DELETE", &format!("{}/posts/gone", PUB), 404).await;
+        BeehiivService::delete_post("key", "pub_1", "gone").await.unwrap();
+
+        let api = MockApi::start("/v2").await;
+        api.respond_status("DELETE", &format!("{}/posts/post_9", PUB), 403).await;
+        assert!(BeehiivService::delete_post("key", "pub_1", "post_9").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn import_posts_maps_archive() {
+        let api = MockApi::start("/v2").await;
+        api.respond("GET", &format!("{}/posts", PUB), "beehiiv", "posts").await;
+
+        let posts = BeehiivService::import_posts("key", Some("pub_1")).await.unwrap();
+        assert_eq!(posts.len(), 2);
+        assert_eq!(posts[0].title, "Issue #12: Drafting in public");
+    }
+}
+
+// ─── Kit ────────────────────────────────────────────────────────
+
+mod kit {
+    use super::*;
+
+    #[tokio::test]
+    async fn validate_connection_reflects_status() {
+        let api = MockApi::start("/v4").await;
+        api.respond("GET", "/account", "kit", "account").await;
+        assert!(KitService::validate_connection("key").await.unwrap());
+
+        let api = MockApi::start("/v4").await;
+        api.respond_status("GET", "/account", 401).await;
+        assert!(!KitService::validate_connection("bad").await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn get_publications_uses_account() {
+        let api = MockApi::start("/v4").await;
+        api.respond("GET", "/account", "kit", "account").await;
+
+        let pubs = KitService::get_publications("key").await.unwrap();
+        assert_eq!(pubs.len(), 1);
+        assert_eq!(pubs[0].id, "default");
+        assert_eq!(pubs[0].name, "Writer Co");
+    }
+
+    #[tokio::test]
+    async fn get_subscribers_maps_fields() {
+        let api = MockApi::start("/v4").await;
+        api.respond("GET", "/subscribers", "kit", "subscribers").await;
+
+        let subs = KitService::get_subscribers("key", None).await.unwrap();
+        assert_eq!(subs.len(), 2);
+        assert_eq!(subs[0].id, "1001");
+        assert_eq!(subs[1].status, "inactive");
+    }
+
+    #[tokio::test]
+    async fn get_subscribers_surfaces_errors() {
+        let api = MockApi::start("/v4").await;
+        api.respond_status("GET", "/subscribers", 429).await;
+        assert!(KitService::get_subscribers("key", None).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn get_subscribers_follows_cursors() {
+        let api = MockApi::start("/v4").await;
+        Mock::given(method("GET"))
+            .and(path("/v4/subscribers"))
+            .and(query_param("after", "WzBd"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(fixture("kit", "subscribers")))
+            .with_priority(1)
+            .mount(&api.server)
+            .await;
+        api.respond("GET", "/subscribers", "kit", "subscribers_page1").await;
+
+        let subs = KitService::get_subscribers("key", None).await.unwrap();
+        assert_eq!(subs.len(), 3);
+        assert_eq!(subs[0].id, "1000");
+        assert_eq!(subs[2].id, "1002");
+    }
+
+    #[tokio::test]
+    async fn rate_limited_requests_are_retried() {
+        let api = MockApi::start("/v4").await;
+        Mock::given(method("GET"))
+            .and(path("/v4/account"))
+            .respond_with(ResponseTemplate::new(429).insert_header("Retry-After", "0"))
+            .up_to_n_times(1)
+            .with_priority(1)
+            .mount(&api.server)
+            .await;
+        api.respond("GET", "/account", "kit", "account").await;
+
+        assert!(KitService::validate_connection("key").await.unwrap());
+        assert_eq!(api.server.received_requests().await.unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn get_analytics_averages_broadcast_rates() {
+        let api = MockApi::start("/v4").await;
+        api.respond("GET", "/subscribers", "kit", "subscribers").await;
+        api.respond("GET", "/broadcasts", "kit", "broadcasts").await;
+
+        let data = KitService::get_analytics("key", None).await.unwrap();
+        assert_eq!(data.total_subscribers, 2);
+        assert_eq!(data.recent_posts.len(), 2);
+        assert!((data.open_rate - 40.0).abs() < 1e-6);
+        assert!((data.click_rate - 7.0).abs() < 1e-6);
+    }
+
+    #[tokio::test]
+    async fn publish_creates_broadcast() {
+        let api = MockApi::start("/v4").await;
+        api.respond("POST", "/broadcasts", "kit", "broadcast_created").await;
+
+        let id = KitService::publish("key", "default", publish_request("published"))
+            .await
+            .unwrap();
+        assert_eq!(id, "777");
+
+        let bodies = api.request_bodies().await;
+        assert_eq!(bodies[0]["broadcast"]["subject"], "New issue");
+        assert_eq!(bodies[0]["broadcast"]["public"], true);
+    }
+
+    #[tokio::test]
+    async fn import_posts_maps_broadcasts() {
+        let api = MockApi::start("/v4").await;
+        api.respond("GET", "/broadcasts", "kit", "broadcasts").await;
+
+        let posts = KitService::import_posts("key").await.unwrap();
+        assert_eq!(posts.len(), 2);
+        assert_eq!(posts[0].id, "501");
+    }
+}
+
+// ─── Ghost ──────────────────────────────────────────────────────
+
+mod ghost {
+    use super::*;
+
+    /// Ghost takes its root from the credential config rather than a const.
+    fn config(api: &MockApi) -> String {
+        serde_json::json!({
+            "api_url": api.uri(),
+            "api_key": "65f0000000000000000000aa:0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef",
+        })
+        .to_string()
+    }
+
+    const ADMIN: &str = "/ghost/api/admin";
+
+    #[tokio::test]
+    async fn validate_connection_reflects_status() {
+        let api = MockApi::start("").await;
+        api.respond("GET", &format!("{}/site/", ADMIN), "ghost", "site").await;
+        assert!(GhostService::validate_connection(&config(&api)).await.unwrap());
+
+        let api = MockApi::start("").await;
+        api.respond_status("GET", &format!("{}/site/", ADMIN), 403).await;
+        assert!(!GhostService::validate_connection(&config(&api)).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn rejects_malformed_config() {
+        assert!(GhostService::validate_connection("not json").await.is_err());
+        let bad_key = serde_json::json!({ "api_url": "http://x", "api_key": "nocolon" }).to_string();
+        assert!(GhostService::validate_connection(&bad_key).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn get_publications_uses_site() {
+        let api = MockApi::start("").await;
+        api.respond("GET", &format!("{}/site/", ADMIN), "ghost", "site").await;
+
+        let pubs = GhostService::get_publications(&config(&api)).await.unwrap();
+        assert_eq!(pubs[0].name, "Field Notes");
+        assert_eq!(pubs[0].url, "https://fieldnotes.example.com/");
+    }
+
+    #[tokio::test]
+    async fn get_subscribers_maps_members() {
+        let api = MockApi::start("").await;
+        api.respond("GET", &format!("{}/members/", ADMIN), "ghost", "members").await;
+
+        let subs = GhostService::get_subscribers(&config(&api), None).await.unwrap();
+        assert_eq!(subs.len(), 2);
+        assert_eq!(subs[0].status, "paid");
+        assert_eq!(subs[0].country.as_deref(), Some("GB"));
+        assert_eq!(subs[1].country, None);
+    }
+
+    #[tokio::test]
+    async fn get_analytics_reads_pagination_total() {
+        let api = MockApi::start("").await;
+        api.respond("GET", &format!("{}/members/", ADMIN), "ghost", "members").await;
+        api.respond("GET", &format!("{}/posts/", ADMIN), "ghost", "posts").await;
+
+        let data = GhostService::get_analytics(&config(&api), None).await.unwrap();
+        assert_eq!(data.total_subscribers, 2);
+        assert_eq!(data.recent_posts.len(), 1);
+        assert_eq!(data.recent_posts[0].title, "On revision");
+    }
+
+    #[tokio::test]
+    async fn publish_returns_post_id() {
+        let api = MockApi::start("").await;
+        api.respond("POST", &format!("{}/posts/", ADMIN), "ghost", "post_created").await;
+
+        let id = GhostService::publish(&config(&api), "default", publish_request("draft"))
+            .await
+            .unwrap();
+        assert_eq!(id, "65f1000000000000000000ff");
+
+        let bodies = api.request_bodies().await;
+        assert_eq!(bodies[0]["posts"][0]["title"], "New issue");
+    }
+
+    #[tokio::test]
+    async fn publish_sends_seo_fields() {
+        let api = MockApi::start("").await;
+        api.respond("POST", &format!("{}/posts/", ADMIN), "ghost", "post_created").await;
+
+        let mut request = publish_request("draft");
+        request.options.ghost = Some(GhostPublishOptions {
+            meta_title: Some("New issue | Field Notes".to_string()),
+            meta_description: Some("Weekly notes".to_string()),
+            ..Default::default()
+        });
+        GhostService::publish(&config(&api), "default", request).await.unwrap();
+
+        let bodies = api.request_bodies().await;
+        let post = &bodies[0]["posts"][0];
+        assert_eq!(post["meta_title"], "New issue | Field Notes");
+        assert_eq!(post["meta_description"], "Weekly notes");
+        assert!(post.get("og_image").is_none());
+    }
+
+    #[tokio::test]
+    async fn publish_sends_slug_and_canonical_url() {
+        let api = MockApi::start("").await;
+        api.respond("POST", &format!("{}/posts/", ADMIN), "ghost", "post_created").await;
+
+        let mut request = publish_request("published");
+        request.options.ghost = Some(GhostPublishOptions {
+            slug: Some("new-issue".to_string()),
+            canonical_url: Some("https://fieldnotes.beehiiv.com/p/new-issue".to_string()),
+            ..Default::default()
+        });
+        GhostService::publish(&config(&api), "default", request).await.unwrap();
+
+        let bodies = api.request_bodies().await;
+        let post = &bodies[0]["posts"][0];
+        assert_eq!(post["slug"], "new-issue");
+        assert_eq!(post["canonical_url"], "https://fieldnotes.beehiiv.com/p/new-issue");
+    }
+
+    #[tokio::test]
+    async fn publish_uploads_attachments_and_links_them() {
+        let api = MockApi::start("").await;
+        api.respond("POST", &format!("{}/media/upload/", ADMIN), "ghost", "media_uploaded").await;
+        api.respond("POST", &format!("{}/posts/", ADMIN), "ghost", "post_created").await;
+
+        let file = std::env::temp_dir().join(format!("station-attachment-{}.mp3", uuid::Uuid::new_v4()));
+        std::fs::write(&file, b"ID3").unwrap();
+        let mut request = publish_request("draft");
+        request.attachments = vec![PublishAttachment {
+            filename: "interview.mp3".to_string(),
+            mime_type: "audio/mpeg".to_string(),
+            path: file.to_string_lossy().to_string(),
+        }];
+
+        GhostService::publish(&config(&api), "default", request).await.unwrap();
+        std::fs::remove_file(&file).ok();
+
+        let bodies = api.request_bodies().await;
+        let html = bodies[0]["posts"][0]["html"].as_str().unwrap();
+        assert!(html.contains("https://fieldnotes.example.com/content/media/2026/10/interview.mp3"));
+        assert!(html.contains(">interview.mp3</a>"));
+    }
+
+    #[tokio::test]
+    async fn publish_surfaces_errors() {
+        let api = MockApi::start("").await;
+        api.respond_status("POST", &format!("{}/posts/", ADMIN), 422).await;
+        assert!(GhostService::publish(&config(&api), "default", publish_request("draft"))
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn import_posts_maps_archive() {
+        let api = MockApi::start("").await;
+        api.respond("GET", &format!("{}/posts/", ADMIN), "ghost", "posts").await;
+
+        let posts = GhostService::import_posts(&config(&api)).await.unwrap();
+        assert_eq!(posts.len(), 1);
+        assert_eq!(
+            posts[0].url.as_deref(),
+            Some("https://fieldnotes.example.com/on-revision/")
+        );
+    }
+
+    #[tokio::test]
+    async fn get_post_returns_html_or_none() {
+        let api = MockApi::start("").await;
+        api.respond("GET", &format!("{}/posts/65f1000000000000000000ff/", ADMIN), "ghost", "post").await;
+        let post = GhostService::get_post(&config(&api), "65f1000000000000000000ff").await.unwrap().unwrap();
+        assert_eq!(post.url.as_deref(), Some("https://fieldnotes.example.com/new-issue/"));
+        assert_eq!(post.html_content, "<p>Body</p>");
+
+        let api = MockApi::start("").await;
+        api.respond_status("GET", &format!("{}/posts/gone/", ADMIN), 404).await;
+        assert!(GhostService::get_post(&config(&api), "gone").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn unpublish_reverts_post_to_draft() {
+        let api = MockApi::start("").await;
+        let post = format!("{}/posts/65f1000000000000000000ff/", ADMIN);
+        api.respond("GET", &post, "ghost", "post").await;
+        api.respond("PUT", &post, "ghost", "post").await;
+        GhostService::unpublish(&config(&api), "65f1000000000000000000ff").await.unwrap();
+
+        let bodies = api.request_bodies().await;
+        assert_eq!(bodies[0]["posts"][0]["status"], "draft");
+        assert_eq!(bodies[0]["posts"][0]["updated_at"], "2026-10-02T08:30:00.000Z");
+
+        let api = MockApi::start("").await;
+        api.respond_status("GET", &format!("{}/posts/gone/", ADMIN), 404).await;
+        GhostService::unpublish(&config(&api), "gone").await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn upsert_page_creates_then_updates() {
+        let api = MockApi::start("").await;
+        api.respond("POST", &format!("{}/pages/", ADMIN), "ghost", "page").await;
+        let (id, url) = GhostService::upsert_page(&config(&api), None, "Links", "<p>Links</p>").await.unwrap();
+        assert_eq!(id, "65f2000000000000000000aa");
+        assert_eq!(url.as_deref(), Some("https://fieldnotes.example.com/links/"));
+
+        let api = MockApi::start("").await;
+        let page = format!("{}/pages/{}/", ADMIN, id);
+        api.respond("GET", &page, "ghost", "page").await;
+        api.respond("PUT", &page, "ghost", "page").await;
+        GhostService::upsert_page(&config(&api), Some(&id), "Links", "<p>Links</p>").await.unwrap();
+
+        let bodies = api.request_bodies().await;
+        assert_eq!(bodies[0]["pages"][0]["updated_at"], "2026-10-01T09:00:00.000Z");
+    }
+
+    #[tokio::test]
+    async fn upsert_page_recreates_deleted_page() {
+        let api = MockApi::start("").await;
+        api.respond_status("GET", &format!("{}/pages/gone/", ADMIN), 404).await;
+        api.respond("POST", &format!("{}/pages/", ADMIN), "ghost", "page").await;
+        let (id, _) = GhostService::upsert_page(&config(&api), Some("gone"), "Links", "<p>Links</p>").await.unwrap();
+        assert_eq!(id, "65f2000000000000000000aa");
+    }
+
+    const CONTENT: &str = "/ghost/api/content";
+
+    fn content_config(api: &MockApi) -> String {
+        serde_json::json!({ "api_url": api.uri(), "api_key": "22444f78447824223cefc48062" }).to_string()
+    }
+
+    #[tokio::test]
+    async fn content_key_reads_settings_and_posts() {
+        let api = MockApi::start("").await;
+        api.respond("GET", &format!("{}/settings/", CONTENT), "ghost", "settings").await;
+        api.respond("GET", &format!("{}/posts/", CONTENT), "ghost", "posts").await;
+
+        assert!(GhostService::validate_connection(&content_config(&api)).await.unwrap());
+        let pubs = GhostService::get_publications(&content_config(&api)).await.unwrap();
+        assert_eq!(pubs[0].name, "Field Notes");
+        let posts = GhostService::import_posts(&content_config(&api)).await.unwrap();
+        assert_eq!(posts.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn content_key_is_read_only() {
+        let api = MockApi::start("").await;
+        let err = GhostService::publish(&content_config(&api), "default", publish_request("draft"))
+            .await
+            .unwrap_err();
+        assert_eq!(err.kind, ErrorKind::Auth);
+        assert!(GhostService::get_subscribers(&content_config(&api), None).await.is_err());
+
+        let caps = GhostService::capabilities(&content_config(&api)).await.unwrap();
+        assert!(caps.read_only);
+        assert!(!caps.can_publish);
+        assert!(caps.can_read_posts);
+    }
+
+    #[tokio::test]
+    async fn integration_key_has_full_capabilities() {
+        let api = MockApi::start("").await;
+        api.respond_status("GET", &format!("{}/users/me/", ADMIN), 403).await;
+
+        let caps = GhostService::capabilities(&config(&api)).await.unwrap();
+        assert_eq!(caps.credential_type, "admin_api_key");
+        assert!(caps.can_publish && caps.can_read_subscribers);
+    }
+}
+
+// ─── Substack ───────────────────────────────────────────────────
+
+mod substack {
+    use super::*;
+
+    const API: &str = "/api/v1";
+
+    fn config(cookie: Option<&str>) -> String {
+        serde_json::json!({ "subdomain": "marginalia", "cookie": cookie }).to_string()
+    }
+
+    #[tokio::test]
+    async fn validate_connection_reflects_status() {
+        let api = MockApi::start(API).await;
+        api.respond("GET", "/archive", "substack", "archive").await;
+        assert!(SubstackService::validate_connection(&config(None)).await.unwrap());
+
+        let api = MockApi::start(API).await;
+        api.respond_status("GET", "/archive", 404).await;
+        assert!(!SubstackService::validate_connection(&config(None)).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn rejects_config_without_subdomain() {
+        assert!(SubstackService::validate_connection("{}").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn get_publications_uses_subdomain() {
+        let pubs = SubstackService::get_publications(&config(None)).await.unwrap();
+        assert_eq!(pubs.len(), 1);
+        assert_eq!(pubs[0].id, "marginalia");
+        assert_eq!(pubs[0].url, "https://marginalia.substack.com");
+        assert_eq!(pubs[0].platform, "substack");
+    }
+
+    #[tokio::test]
+    async fn get_subscribers_requires_cookie() {
+        assert!(SubstackService::get_subscribers(&config(None), None).await.is_err());
+
+        let api = MockApi::start(API).await;
+        api.respond("GET", "/subscriber_count", "substack", "subscriber_count").await;
+        let subs = SubstackService::get_subscribers(&config(Some("substack.sid=abc")), None)
+            .await
+            .unwrap();
+        assert!(subs.is_empty());
+
+        let requests = api.server.received_requests().await.unwrap();
+        assert_eq!(requests[0].headers.get("cookie").unwrap(), "substack.sid=abc");
+    }
+
+    #[tokio::test]
+    async fn get_subscribers_surfaces_errors() {
+        let api = MockApi::start(API).await;
+        api.respond_status("GET", "/subscriber_count", 403).await;
+        let err = SubstackService::get_subscribers(&config(Some("substack.sid=abc")), None)
+            .await
+            .unwrap_err();
+        assert!(err.message.contains("unofficial API may have changed"));
+    }
+
+    #[tokio::test]
+    async fn get_analytics_maps_archive() {
+        let api = MockApi::start(API).await;
+        api.respond("GET", "/archive", "substack", "archive").await;
+
+        let data = SubstackService::get_analytics(&config(None), None).await.unwrap();
+        assert_eq!(data.total_subscribers, 0);
+        assert_eq!(data.recent_posts.len(), 2);
+        assert_eq!(data.recent_posts[0].id, "140001");
+        assert_eq!(data.recent_posts[0].opens, 310);
+        assert_eq!(data.recent_posts[0].clicks, 42);
+        assert_eq!(data.recent_posts[1].opens, 0);
+    }
+
+    #[tokio::test]
+    async fn get_analytics_reads_subscriber_count_with_cookie() {
+        let api = MockApi::start(API).await;
+        api.respond("GET", "/archive", "substack", "archive").await;
+        api.respond("GET", "/subscriber_count", "substack", "subscriber_count").await;
+
+        let data = SubstackService::get_analytics(&config(Some("substack.sid=abc")), None)
+            .await
+            .unwrap();
+        assert_eq!(data.total_subscribers, 1432);
+    }
+
+    #[tokio::test]
+    async fn publish_creates_draft() {
+        assert!(SubstackService::publish(&config(None), "marginalia", publish_request("draft"))
+            .await
+            .is_err());
+
+        let api = MockApi::start(API).await;
+        api.respond("POST", "/drafts", "substack", "draft_created").await;
+        let id = SubstackService::publish(&config(Some("substack.sid=abc")), "marginalia", publish_request("draft"))
+            .await
+            .unwrap();
+        assert_eq!(id, "150123");
+
+        let bodies = api.request_bodies().await;
+        assert_eq!(bodies[0]["draft_title"], "New issue");
+        assert_eq!(bodies[0]["draft_subtitle"], "Sub");
+        assert_eq!(bodies[0]["draft_body"]["content"][0]["content"], "<p>Body</p>");
+    }
+
+    #[tokio::test]
+    async fn publish_surfaces_errors() {
+        let api = MockApi::start(API).await;
+        api.respond_status("POST", "/drafts", 500).await;
+        assert!(SubstackService::publish(&config(Some("substack.sid=abc")), "marginalia", publish_request("draft"))
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn import_posts_maps_archive() {
+        let api = MockApi::start(API).await;
+        api.respond("GET", "/archive", "substack", "archive").await;
+
+        let posts = SubstackService::import_posts(&config(None)).await.unwrap();
+        assert_eq!(posts.len(), 2);
+        assert_eq!(posts[0].title, "Letters from the margin");
+        assert_eq!(posts[1].html_content, "");
+        assert_eq!(
+            posts[1].url.as_deref(),
+            Some("https://marginalia.substack.com/p/a-first-issue")
+        );
+    }
+
+    #[tokio::test]
+    async fn check_session_reflects_profile_status() {
+        let check = SubstackService::check_session(&config(None)).await.unwrap();
+        assert!(!check.has_cookie && !check.valid);
+
+        let api = MockApi::start(API).await;
+        api.respond("GET", "/user/profile/self", "substack", "profile").await;
+        let check = SubstackService::check_session(&config(Some("substack.sid=abc"))).await.unwrap();
+        assert!(check.has_cookie && check.valid);
+
+        let api = MockApi::start(API).await;
+        api.respond_status("GET", "/user/profile/self", 401).await;
+        let check = SubstackService::check_session(&config(Some("substack.sid=abc"))).await.unwrap();
+        assert!(check.has_cookie && !check.valid);
+
+        let api = MockApi::start(API).await;
+        api.respond_status("GET", "/user/profile/self", 500).await;
+        assert!(SubstackService::check_session(&config(Some("substack.sid=abc"))).await.is_err());
+    }
+}
+
+// ─── Google ─────────────────────────────────────────────────────
+
+mod google_drive {
+    use super::*;
+
+    fn credential() -> String {
+        serde_json::json!({ "client_id": "cid", "client_secret": "secret", "refresh_token": "1//test-refresh" }).to_string()
+    }
+
+    #[tokio::test]
+    async fn access_token_uses_refresh_grant() {
+        let api = MockApi::start("").await;
+        api.respond("POST", "/token", "google", "token").await;
+
+        let token = GoogleService::access_token(&credential()).await.unwrap();
+        assert_eq!(token, "ya29.test-access");
+        let requests = api.server.received_requests().await.unwrap();
+        let form = String::from_utf8_lossy(&requests[0].body).to_string();
+        assert!(form.contains("grant_type=refresh_token"));
+        assert!(form.contains("refresh_token=1%2F%2Ftest-refresh"));
+    }
+
+    #[tokio::test]
+    async fn exchange_code_returns_refresh_token() {
+        let api = MockApi::start("").await;
+        api.respond("POST", "/token", "google", "token").await;
+
+        let tokens = GoogleService::exchange_code("cid", "secret", "code", "http://127.0.0.1:8741/oauth/google", "verifier")
+            .await
+            .unwrap();
+        assert_eq!(tokens.refresh_token.as_deref(), Some("1//test-refresh"));
+    }
+
+    #[tokio::test]
+    async fn revoked_refresh_token_is_auth_error() {
+        let api = MockApi::start("").await;
+        api.respond_status("POST", "/token", 401).await;
+        let err = GoogleService::access_token(&credential()).await.unwrap_err();
+        assert_eq!(err.kind, ErrorKind::Auth);
+        assert_eq!(err.platform.as_deref(), Some("google"));
+    }
+
+    #[tokio::test]
+    async fn account_reads_drive_user() {
+        let api = MockApi::start("").await;
+        api.respond("GET", "/drive/v3/about", "google", "about").await;
+
+        let (name, email) = GoogleService::account("token").await.unwrap();
+        assert_eq!(name, "Sam Writer");
+        assert_eq!(email, "sam@example.com");
+    }
+
+    #[tokio::test]
+    async fn create_doc_uploads_docx_for_conversion() {
+        let api = MockApi::start("").await;
+        api.respond("POST", "/upload/drive/v3/files", "google", "file_created").await;
+
+        let file = GoogleService::create_doc_from_docx("token", "New issue", b"PK-docx").await.unwrap();
+        assert_eq!(file.id, "1AbCdEfGhIjKlMnOpQrStUvWxYz");
+        assert!(file.web_view_link.unwrap().starts_with("https://docs.google.com/document/d/"));
+
+        let requests = api.server.received_requests().await.unwrap();
+        let body = String::from_utf8_lossy(&requests[0].body).to_string();
+        assert!(body.contains("\"mimeType\":\"application/vnd.google-apps.document\""));
+        assert!(body.contains("PK-docx"));
+    }
+
+    #[tokio::test]
+    async fn export_doc_downloads_docx() {
+        let api = MockApi::start("").await;
+        let file = "/drive/v3/files/1AbCdEfGhIjKlMnOpQrStUvWxYz";
+        api.respond("GET", file, "google", "file_meta").await;
+        api.respond_with("GET", &format!("{}/export", file), ResponseTemplate::new(200).set_body_bytes(b"PK-docx".to_vec()))
+            .await;
+
+        let (name, bytes) = GoogleService::export_doc_as_docx("token", "1AbCdEfGhIjKlMnOpQrStUvWxYz").await.unwrap();
+        assert_eq!(name, "Draft for review");
+        assert_eq!(bytes, b"PK-docx");
+    }
+
+    #[test]
+    fn doc_id_from_url_accepts_links_and_ids() {
+        assert_eq!(
+            google::doc_id_from_url("https://docs.google.com/document/d/1AbC-d_9/edit?usp=sharing").as_deref(),
+            Some("1AbC-d_9")
+        );
+        assert_eq!(google::doc_id_from_url("1AbC-d_9").as_deref(), Some("1AbC-d_9"));
+        assert_eq!(google::doc_id_from_url("https://example.com/page"), None);
+    }
+}
+
+// ─── WordPress ──────────────────────────────────────────────────
+
+mod wordpress {
+    use super::*;
+
+    fn config(api: &MockApi) -> String {
+        serde_json::json!({ "site_url": api.uri(), "username": "editor", "app_password": "abcd efgh ijkl" }).to_string()
+    }
+
+    #[tokio::test]
+    async fn upsert_page_returns_id_and_link() {
+        let api = MockApi::start("").await;
+        api.respond("POST", "/wp-json/wp/v2/pages", "wordpress", "page").await;
+
+        let (id, link) = WordPressService::upsert_page(&config(&api), None, "Links", "<p>Links</p>").await.unwrap();
+        assert_eq!(id, "42");
+        assert_eq!(link.as_deref(), Some("https://blog.example.com/links/"));
+        let bodies = api.request_bodies().await;
+        assert_eq!(bodies[0]["status"], "publish");
+    }
+
+    #[tokio::test]
+    async fn upsert_page_recreates_deleted_page() {
+        let api = MockApi::start("").await;
+        api.respond_status("POST", "/wp-json/wp/v2/pages/7", 404).await;
+        api.respond("POST", "/wp-json/wp/v2/pages", "wordpress", "page").await;
+
+        let (id, _) = WordPressService::upsert_page(&config(&api), Some("7"), "Links", "<p>Links</p>").await.unwrap();
+        assert_eq!(id, "42");
+    }
+
+    #[tokio::test]
+    async fn find_post_by_title_matches_exactly() {
+        let api = MockApi::start("").await;
+        api.respond("GET", "/wp-json/wp/v2/posts", "wordpress", "posts").await;
+
+        let post = WordPressService::find_post_by_title(&config(&api), "Notes & drafts").await.unwrap().unwrap();
+        assert_eq!(post.id, "88");
+        assert_eq!(post.html_content, "<p>Body</p>\n");
+        assert_eq!(post.published_at.as_deref(), Some("2026-09-30T07:00:00Z"));
+        assert!(WordPressService::find_post_by_title(&config(&api), "Notes").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn auth_failure_is_an_error() {
+        let api = MockApi::start("").await;
+        api.respond_status("POST", "/wp-json/wp/v2/pages", 401).await;
+        let err = WordPressService::upsert_page(&config(&api), None, "Links", "").await.unwrap_err();
+        assert_eq!(err.kind, ErrorKind::Auth);
+    }
+}
+
+// ─── Twitter ────────────────────────────────────────────────────
+
+mod twitter {
+    use super::*;
+
+    const CONFIG: &str = r#"{"api_key":"ck","api_secret":"cs","access_token":"at","access_secret":"as"}"#;
+    const MENTIONS: &str = "/2/users/1460000000000000001/mentions";
+
+    #[tokio::test]
+    async fn mentions_join_authors_and_expand_links() {
+        let api = MockApi::start("").await;
+        api.respond("GET", "/2/users/me", "twitter", "me").await;
+        api.respond("GET", MENTIONS, "twitter", "mentions").await;
+
+        let mentions = TwitterService::mentions(CONFIG, None).await.unwrap();
+        assert_eq!(mentions.len(), 2);
+        assert_eq!(mentions[0].id, "1790000000000000002");
+        assert_eq!(mentions[0].author_name, "Ana Ruiz");
+        assert_eq!(mentions[0].author_handle, "@anaruiz");
+        assert_eq!(mentions[0].url.as_deref(), Some("https://x.com/anaruiz/status/1790000000000000002"));
+        assert_eq!(mentions[0].links, vec!["https://weeklydraft.beehiiv.com/p/pricing-for-writers"]);
+        assert!(mentions[1].links.is_empty());
+    }
+
+    #[tokio::test]
+    async fn mentions_pass_since_id() {
+        let api = MockApi::start("").await;
+        api.respond("GET", "/2/users/me", "twitter", "me").await;
+        Mock::given(method("GET"))
+            .and(path(MENTIONS))
+            .and(query_param("since_id", "1790000000000000001"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({ "meta": { "result_count": 0 } })))
+            .mount(&api.server)
+            .await;
+
+        let mentions = TwitterService::mentions(CONFIG, Some("1790000000000000001")).await.unwrap();
+        assert!(mentions.is_empty());
+    }
+
+    #[tokio::test]
+    async fn auth_failure_is_an_error() {
+        let api = MockApi::start("").await;
+        api.respond_status("GET", "/2/users/me", 401).await;
+        let err = TwitterService::mentions(CONFIG, None).await.unwrap_err();
+        assert_eq!(err.kind, ErrorKind::Auth);
+        assert_eq!(err.platform.as_deref(), Some("twitter"));
+    }
+}
+
+// ─── Mastodon ───────────────────────────────────────────────────
+
+mod mastodon {
+    use super::*;
+
+    /// Like Ghost, the instance comes from the credential config.
+    fn config(api: &MockApi) -> String {
+        serde_json::json!({ "instance_url": format!("{}/", api.uri()), "access_token": "token" }).to_string()
+    }
+
+    #[tokio::test]
+    async fn validate_reflects_status() {
+        let api = MockApi::start("").await;
+        api.respond("GET", "/api/v1/accounts/verify_credentials", "mastodon", "account").await;
+        assert!(MastodonService::validate(&config(&api)).await.unwrap());
+
+        let api = MockApi::start("").await;
+        api.respond_status("GET", "/api/v1/accounts/verify_credentials", 401).await;
+        assert!(!MastodonService::validate(&config(&api)).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn mentions_map_statuses() {
+        let api = MockApi::start("").await;
+        api.respond("GET", "/api/v1/notifications", "mastodon", "notifications").await;
+
+        let mentions = MastodonService::mentions(&config(&api)).await.unwrap();
+        assert_eq!(mentions.len(), 2);
+        assert_eq!(mentions[0].author_name, "Mira");
+        assert_eq!(mentions[0].author_handle, "@mira@hachyderm.io");
+        assert!(mentions[0].text.contains("Loved this one & sent it to my team"));
+        assert_eq!(
+            mentions[0].links,
+            vec!["https://mastodon.social/@fieldnotes", "https://fieldnotes.example.com/slow-mornings/?ref=masto"]
+        );
+        // No display name falls back to the account
+        assert_eq!(mentions[1].author_name, "tomas");
+        assert!(mentions[1].url.is_none());
+    }
+}
+
+// ─── Paddle ─────────────────────────────────────────────────────
+
+mod paddle_billing {
+    use super::*;
+
+    #[tokio::test]
+    async fn fetch_transactions_follows_next_cursor() {
+        let api = MockApi::start("").await;
+        Mock::given(method("GET"))
+            .and(path("/transactions"))
+            .and(query_param("after", "txn_01h000000000000000000001"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(fixture("paddle", "transactions")))
+            .with_priority(1)
+            .mount(&api.server)
+            .await;
+        api.respond("GET", "/transactions", "paddle", "transactions_page1").await;
+
+        let transactions = PaddleService::fetch_transactions("pdl_key", 1).await.unwrap();
+        assert_eq!(transactions.len(), 2);
+        assert_eq!(transactions[0].customer.as_ref().and_then(|c| c.email.as_deref()), Some("ada@example.com"));
+        assert_eq!(paddle::amount_cents(transactions[1].details.as_ref().and_then(|d| d.totals.as_ref())), 4500);
+    }
+
+    #[tokio::test]
+    async fn fetch_refunds_stops_without_more_pages() {
+        let api = MockApi::start("").await;
+        api.respond("GET", "/adjustments", "paddle", "adjustments").await;
+
+        let refunds = PaddleService::fetch_refunds("pdl_key", 200).await.unwrap();
+        assert_eq!(refunds.len(), 1);
+        assert_eq!(refunds[0].transaction_id, "txn_01h000000000000000000001");
+        assert_eq!(api.server.received_requests().await.unwrap().len(), 1);
+    }
+}
+
+// ─── Stripe ─────────────────────────────────────────────────────
+
+mod stripe {
+    use super::*;
+
+    #[tokio::test]
+    async fn fetch_charges_parses_list() {
+        let api = MockApi::start("/v1").await;
+        api.respond("GET", "/charges", "stripe", "charges").await;
+
+        let charges = StripeService::fetch_charges("sk_test", 100).await.unwrap();
+        assert_eq!(charges.len(), 2);
+        assert_eq!(charges[0].amount, 800);
+        assert_eq!(charges[0].invoice.as_deref(), Some("in_1Ot000000000000000000001"));
+        assert!(charges[1].receipt_email.is_none());
+    }
+
+    #[tokio::test]
+    async fn fetch_charges_follows_starting_after() {
+        let api = MockApi::start("/v1").await;
+        Mock::given(method("GET"))
+            .and(path("/v1/charges"))
+            .and(query_param("starting_after", "ch_3Ot000000000000000000000"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(fixture("stripe", "charges")))
+            .with_priority(1)
+            .mount(&api.server)
+            .await;
+        api.respond("GET", "/charges", "stripe", "charges_page1").await;
+
+        let charges = StripeService::fetch_charges("sk_test", 100).await.unwrap();
+        assert_eq!(charges.len(), 3);
+        assert_eq!(charges[0].id, "ch_3Ot000000000000000000000");
+        assert_eq!(charges[2].id, "ch_3Ot000000000000000000002");
+    }
+
+    #[tokio::test]
+    async fn fetch_disputes_marks_withdrawn_funds() {
+        let api = MockApi::start("/v1").await;
+        api.respond("GET", "/disputes", "stripe", "disputes").await;
+
+        let disputes = StripeService::fetch_disputes("sk_test", 100).await.unwrap();
+        assert_eq!(disputes.len(), 2);
+        assert_eq!(disputes[0].charge.as_deref(), Some("ch_3Ot000000000000000000002"));
+        assert!(disputes[0].funds_withdrawn());
+        // An inquiry closed without a chargeback
+        assert!(!disputes[1].funds_withdrawn());
+    }
+
+    #[tokio::test]
+    async fn fetch_refunds_links_charge() {
+        let api = MockApi::start("/v1").await;
+        api.respond("GET", "/refunds", "stripe", "refunds").await;
+
+        let refunds = StripeService::fetch_refunds("sk_test", 100).await.unwrap();
+        assert_eq!(refunds[0].charge.as_deref(), Some("ch_3Ot000000000000000000001"));
+    }
+
+    #[tokio::test]
+    async fn fetch_subscriptions_parses_plan() {
+        let api = MockApi::start("/v1").await;
+        api.respond("GET", "/subscriptions", "stripe", "subscriptions").await;
+
+        let subs = StripeService::fetch_subscriptions("sk_test", 100).await.unwrap();
+        assert_eq!(subs[0].plan.as_ref().and_then(|p| p.amount), Some(800));
+    }
+
+    #[tokio::test]
+    async fn auth_failure_is_an_error() {
+        let api = MockApi::start("/v1").await;
+        api.respond_status("GET", "/charges", 401).await;
+        let err = StripeService::fetch_charges("sk_bad", 100).await.unwrap_err();
+        assert!(err.message.contains("401"));
+        assert_eq!(err.kind, ErrorKind::Auth);
+        assert_eq!(err.platform.as_deref(), Some("stripe"));
+    }
+
+    #[tokio::test]
+    async fn rate_limit_is_retryable() {
+        let api = MockApi::start("/v1").await;
+        api.respond_status("GET", "/charges", 429).await;
+        let err = StripeService::fetch_charges("sk_test", 100).await.unwrap_err();
+        assert_eq!(err.kind, ErrorKind::RateLimited);
+        assert!(err.retryable);
+    }
+}
@@ -2,8 +2,10 @@ use reqwest::Client;
 use serde::Deserialize;
 
 use crate::commands::platform::{
-    AnalyticsData, ImportedPost, PostPerformance, Publication, PublishRequest, Subscriber,
+    AnalyticsData, ImportedPost, PlatformCapabilities, PostPerformance, Publication,
+    PublishAttachment, PublishRequest, Subscriber,
 };
+use crate::error::{AppError, ErrorKind};
 use crate::services::PlatformService;
 
 pub struct GhostService;
@@ -13,7 +15,42 @@ pub struct GhostService;
 #[derive(Deserialize)]
 struct GhostConfig {
     api_url: String,
-    api_key: String, // "{key_id}:{hex_secret}"
+    /// Admin API key or staff access token ("{key_id}:{hex_secret}"), or a
+    /// 26-character Content API key
+    api_key: String,
+    /// "admin" | "staff" | "content"; detected from the key when absent
+    #[serde(default)]
+    credential_type: Option<String>,
+}
+
+/// Admin API keys and staff access tokens both sign a JWT for the Admin
+/// API. A Content API key can only read published posts and site settings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum GhostCredential {
+    AdminKey,
+    StaffToken,
+    ContentKey,
+}
+
+#[derive(Deserialize)]
+struct GhostSettingsResponse {
+    settings: GhostSite,
+}
+
+#[derive(Deserialize)]
+struct GhostUsersResponse {
+    users: Vec<GhostUser>,
+}
+
+#[derive(Deserialize)]
+struct GhostUser {
+    #[serde(default)]
+    roles: Vec<GhostRole>,
+}
+
+#[derive(Deserialize)]
+struct GhostRole {
+    name: String,
 }
 
 #[derive(Deserialize)]
@@ -44,6 +81,8 @@ struct GhostPost {
     status: Option<String>,
     published_at: Option<String>,
     url: Option<String>,
+    #[serde(default)]
+    updated_at: Option<String>,
 }
 
 #[derive(Deserialize)]
@@ -58,6 +97,8 @@ struct GhostMember {
     email: String,
     status: Option<String>,
     created_at: Option<String>,
+    /// JSON-encoded lookup of the signup IP, e.g. `{"country_code":"GB",...}`
+    geolocation: Option<String>,
 }
 
 #[derive(Deserialize)]
@@ -80,6 +121,18 @@ struct GhostCreatedPost {
     id: String,
 }
 
+#[derive(Deserialize)]
+struct GhostPagesResponse {
+    pages: Vec<GhostPage>,
+}
+
+#[derive(Deserialize)]
+struct GhostPage {
+    id: String,
+    url: Option<String>,
+    updated_at: Option<String>,
+}
+
 // ─── Helpers ────────────────────────────────────────────────────
 
 fn hex_decode(hex: &str) -> Result<Vec<u8>, String> {
@@ -97,6 +150,64 @@ fn parse_config(api_key: &str) -> Result<GhostConfig, String> {
         .map_err(|_| "Invalid Ghost config. Expected JSON with 'api_url' and 'api_key'.".to_string())
 }
 
+fn is_content_key(key: &str) -> bool {
+    key.len() == 26 && key.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+fn credential(config: &GhostConfig) -> Result<GhostCredential, String> {
+    match config.credential_type.as_deref() {
+        Some("admin") => Ok(GhostCredential::AdminKey),
+        Some("staff") => Ok(GhostCredential::StaffToken),
+        Some("content") => Ok(GhostCredential::ContentKey),
+        Some(other) => Err(format!("Unknown Ghost credential type '{}'", other)),
+        None if config.api_key.contains(':') => Ok(GhostCredential::AdminKey),
+        None if is_content_key(&config.api_key) => Ok(GhostCredential::ContentKey),
+        None => Err(
+            "Invalid Ghost API key format. Expected an Admin API key or staff token ('id:secret') or a Content API key."
+                .to_string(),
+        ),
+    }
+}
+
+fn site_root(config: &GhostConfig) -> &str {
+    config.api_url.trim_end_matches('/')
+}
+
+/// Client for the Admin API, signed with the account's key or staff token.
+fn admin_client(config: &GhostConfig) -> Result<Client, String> {
+    let jwt = generate_jwt(&config.api_key)?;
+    ghost_client(&jwt)
+}
+
+/// GET against the Content API, authenticated with the `key` query param.
+fn content_get(config: &GhostConfig, path: &str) -> Result<reqwest::RequestBuilder, String> {
+    let c = crate::http::client("ghost")?;
+    Ok(c
+        .get(format!("{}/ghost/api/content/{}", site_root(config), path))
+        .query(&[("key", config.api_key.as_str())]))
+}
+
+fn read_only(action: &str) -> AppError {
+    AppError::auth(format!(
+        "{} needs a Ghost Admin API key or staff access token. This account is connected with a Content API key, which is read-only.",
+        action
+    ))
+    .with_platform("ghost")
+}
+
+async fn content_posts(config: &GhostConfig, limit: &str) -> Result<Vec<GhostPost>, AppError> {
+    let resp = content_get(config, "posts/")?
+        .query(&[("limit", limit), ("order", "published_at desc"), ("formats", "html")])
+        .send()
+        .await
+        .map_err(|e| AppError::network("ghost", "Ghost request failed", e))?;
+    if !resp.status().is_success() {
+        return Err(AppError::from_response("ghost", "Ghost Content API error", resp).await);
+    }
+    let body: GhostPostsResponse = resp.json().await.map_err(|e| e.to_string())?;
+    Ok(body.posts)
+}
+
 fn generate_jwt(ghost_api_key: &str) -> Result<String, String> {
     let parts: Vec<&str> = ghost_api_key.splitn(2, ':').collect();
     if parts.len() != 2 {
@@ -129,7 +240,7 @@ fn generate_jwt(ghost_api_key: &str) -> Result<String, String> {
 }
 
 fn ghost_client(jwt: &str) -> Result<Client, String> {
-    Client::builder()
+    crate::http::builder("ghost")?
         .default_headers({
             let mut h = reqwest::header::HeaderMap::new();
             h.insert(
@@ -146,13 +257,100 @@ fn ghost_client(jwt: &str) -> Result<Client, String> {
         .map_err(|e| e.to_string())
 }
 
+/// Upload one attachment and return its hosted URL. Audio and video go to
+/// the media endpoint so Ghost serves them with a player, images to the
+/// image store and everything else to files.
+async fn upload_attachment(c: &Client, config: &GhostConfig, attachment: &PublishAttachment) -> Result<String, AppError> {
+    let (endpoint, key) = if attachment.mime_type.starts_with("audio/") || attachment.mime_type.starts_with("video/") {
+        ("media", "media")
+    } else if attachment.mime_type.starts_with("image/") {
+        ("images", "images")
+    } else {
+        ("files", "files")
+    };
+    let bytes = std::fs::read(&attachment.path)
+        .map_err(|e| format!("Failed to read attachment '{}': {}", attachment.filename, e))?;
+    let (content_type, body) =
+        crate::http::multipart_form(&[], "file", &attachment.filename, &attachment.mime_type, &bytes);
+
+    let resp = c
+        .post(format!("{}/ghost/api/admin/{}/upload/", site_root(config), endpoint))
+        .header(reqwest::header::CONTENT_TYPE, content_type)
+        .body(body)
+        .send()
+        .await
+        .map_err(|e| AppError::network("ghost", "Ghost upload failed", e))?;
+    if !resp.status().is_success() {
+        return Err(AppError::from_response("ghost", "Ghost upload error", resp).await);
+    }
+    let result: serde_json::Value = resp.json().await.map_err(|e| e.to_string())?;
+    result[key][0]["url"]
+        .as_str()
+        .map(str::to_string)
+        .ok_or_else(|| AppError::new(ErrorKind::Platform, "No URL returned for Ghost upload").with_platform("ghost"))
+}
+
+/// Links to uploaded attachments, appended after the post body.
+fn attachment_links(uploaded: &[(String, String)]) -> String {
+    let items: String = uploaded
+        .iter()
+        .map(|(name, url)| format!("<li><a href=\"{}\">{}</a></li>", url, html_escape(name)))
+        .collect();
+    format!("<h3>Attachments</h3><ul>{}</ul>", items)
+}
+
+fn html_escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+/// Member total from the pagination meta of a one-row page. `None` when
+/// the credential can't read members.
+async fn member_total(c: &Client, config: &GhostConfig) -> Option<u64> {
+    let resp = c
+        .get(format!("{}/ghost/api/admin/members/", site_root(config)))
+        .query(&[("limit", "1")])
+        .send()
+        .await
+        .ok()?;
+    if !resp.status().is_success() {
+        return None;
+    }
+    let body: GhostMembersResponse = resp.json().await.ok()?;
+    body.meta.and_then(|m| m.pagination).and_then(|p| p.total)
+}
+
+/// A member's country code from their `geolocation`.
+fn member_country(geolocation: Option<&str>) -> Option<String> {
+    let location: serde_json::Value = serde_json::from_str(geolocation?).ok()?;
+    let code = location.get("country_code")?.as_str()?.trim();
+    (code.len() == 2 && code.chars().all(|c| c.is_ascii_alphabetic())).then(|| code.to_ascii_uppercase())
+}
+
+fn post_performance(post: GhostPost) -> PostPerformance {
+    PostPerformance {
+        id: post.id,
+        title: post.title.unwrap_or_else(|| "Untitled".to_string()),
+        published_at: post.published_at.unwrap_or_default(),
+        opens: 0, // Ghost doesn't expose email open stats via the API
+        clicks: 0,
+        unsubscribes: 0,
+        platform: "ghost".to_string(),
+    }
+}
+
 // ─── PlatformService implementation ─────────────────────────────
 
 impl PlatformService for GhostService {
-    async fn validate_connection(api_key: &str) -> Result<bool, String> {
+    async fn validate_connection(api_key: &str) -> Result<bool, AppError> {
         let config = parse_config(api_key)?;
-        let jwt = generate_jwt(&config.api_key)?;
-        let c = ghost_client(&jwt)?;
+        if credential(&config)? == GhostCredential::ContentKey {
+            let resp = content_get(&config, "settings/")?
+                .send()
+                .await
+                .map_err(|e| AppError::network("ghost", "Ghost request failed", e))?;
+            return Ok(resp.status().is_success());
+        }
+        let c = admin_client(&config)?;
 
         let resp = c
             .get(format!(
@@ -161,51 +359,63 @@ impl PlatformService for GhostService {
             ))
             .send()
             .await
-            .map_err(|e| e.to_string())?;
+            .map_err(|e| AppError::network("ghost", "Ghost request failed", e))?;
 
         Ok(resp.status().is_success())
     }
 
-    async fn get_publications(api_key: &str) -> Result<Vec<Publication>, String> {
+    async fn get_publications(api_key: &str) -> Result<Vec<Publication>, AppError> {
         let config = parse_config(api_key)?;
-        let jwt = generate_jwt(&config.api_key)?;
-        let c = ghost_client(&jwt)?;
-
-        let resp = c
-            .get(format!(
-                "{}/ghost/api/admin/site/",
-                config.api_url.trim_end_matches('/')
-            ))
-            .send()
-            .await
-            .map_err(|e| e.to_string())?;
+        let mut subscriber_count = None;
+        let site = if credential(&config)? == GhostCredential::ContentKey {
+            let resp = content_get(&config, "settings/")?
+                .send()
+                .await
+                .map_err(|e| AppError::network("ghost", "Ghost request failed", e))?;
+            if !resp.status().is_success() {
+                return Err(AppError::from_response("ghost", "Ghost Content API error", resp).await);
+            }
+            let body: GhostSettingsResponse = resp.json().await.map_err(|e| e.to_string())?;
+            body.settings
+        } else {
+            let c = admin_client(&config)?;
+            let resp = c
+                .get(format!(
+                    "{}/ghost/api/admin/site/",
+                    config.api_url.trim_end_matches('/')
+                ))
+                .send()
+                .await
+                .map_err(|e| AppError::network("ghost", "Ghost request failed", e))?;
 
-        if !resp.status().is_success() {
-            return Err(format!("Ghost API error: {}", resp.status()));
-        }
+            if !resp.status().is_success() {
+                return Err(AppError::from_response("ghost", "Ghost API error", resp).await);
+            }
 
-        let body: GhostSiteResponse = resp.json().await.map_err(|e| e.to_string())?;
+            let body: GhostSiteResponse = resp.json().await.map_err(|e| e.to_string())?;
+            subscriber_count = member_total(&c, &config).await;
+            body.site
+        };
 
         Ok(vec![Publication {
             id: "default".to_string(),
-            name: body.site.title.unwrap_or_else(|| "Ghost Blog".to_string()),
-            url: body
-                .site
-                .url
-                .unwrap_or_else(|| config.api_url.clone()),
+            name: site.title.unwrap_or_else(|| "Ghost Blog".to_string()),
+            url: site.url.unwrap_or_else(|| config.api_url.clone()),
             platform: "ghost".to_string(),
-            subscriber_count: None,
-            description: body.site.description,
+            subscriber_count,
+            description: site.description,
         }])
     }
 
     async fn get_subscribers(
         api_key: &str,
         _publication_id: Option<&str>,
-    ) -> Result<Vec<Subscriber>, String> {
+    ) -> Result<Vec<Subscriber>, AppError> {
         let config = parse_config(api_key)?;
-        let jwt = generate_jwt(&config.api_key)?;
-        let c = ghost_client(&jwt)?;
+        if credential(&config)? == GhostCredential::ContentKey {
+            return Err(read_only("Reading members"));
+        }
+        let c = admin_client(&config)?;
 
         let resp = c
             .get(format!(
@@ -215,10 +425,10 @@ impl PlatformService for GhostService {
             .query(&[("limit", "100")])
             .send()
             .await
-            .map_err(|e| e.to_string())?;
+            .map_err(|e| AppError::network("ghost", "Ghost request failed", e))?;
 
         if !resp.status().is_success() {
-            return Err(format!("Ghost API error: {}", resp.status()));
+            return Err(AppError::from_response("ghost", "Ghost API error", resp).await);
         }
 
         let body: GhostMembersResponse = resp.json().await.map_err(|e| e.to_string())?;
@@ -227,6 +437,7 @@ impl PlatformService for GhostService {
             .members
             .into_iter()
             .map(|m| Subscriber {
+                country: member_country(m.geolocation.as_deref()),
                 id: m.id,
                 email: m.email,
                 status: m.status.unwrap_or_else(|| "free".to_string()),
@@ -239,10 +450,20 @@ impl PlatformService for GhostService {
     async fn get_analytics(
         api_key: &str,
         _publication_id: Option<&str>,
-    ) -> Result<AnalyticsData, String> {
+    ) -> Result<AnalyticsData, AppError> {
         let config = parse_config(api_key)?;
-        let jwt = generate_jwt(&config.api_key)?;
-        let c = ghost_client(&jwt)?;
+        if credential(&config)? == GhostCredential::ContentKey {
+            // Member counts need the Admin API; post list only
+            let posts = content_posts(&config, "50").await?;
+            return Ok(AnalyticsData {
+                total_subscribers: 0,
+                open_rate: 0.0,
+                click_rate: 0.0,
+                subscriber_growth: vec![],
+                recent_posts: posts.into_iter().map(post_performance).collect(),
+            });
+        }
+        let c = admin_client(&config)?;
 
         // Get member count
         let members_resp = c
@@ -253,7 +474,7 @@ impl PlatformService for GhostService {
             .query(&[("limit", "1")])
             .send()
             .await
-            .map_err(|e| e.to_string())?;
+            .map_err(|e| AppError::network("ghost", "Ghost request failed", e))?;
 
         let total_subscribers: u64 = if members_resp.status().is_success() {
             let body: GhostMembersResponse =
@@ -275,23 +496,13 @@ impl PlatformService for GhostService {
             .query(&[("limit", "50"), ("order", "published_at desc")])
             .send()
             .await
-            .map_err(|e| e.to_string())?;
+            .map_err(|e| AppError::network("ghost", "Ghost request failed", e))?;
 
         let mut recent_posts = Vec::new();
 
         if posts_resp.status().is_success() {
             let body: GhostPostsResponse = posts_resp.json().await.map_err(|e| e.to_string())?;
-            for post in body.posts {
-                recent_posts.push(PostPerformance {
-                    id: post.id,
-                    title: post.title.unwrap_or_else(|| "Untitled".to_string()),
-                    published_at: post.published_at.unwrap_or_default(),
-                    opens: 0,     // Ghost doesn't expose email open stats via Admin API
-                    clicks: 0,
-                    unsubscribes: 0,
-                    platform: "ghost".to_string(),
-                });
-            }
+            recent_posts.extend(body.posts.into_iter().map(post_performance));
         }
 
         Ok(AnalyticsData {
@@ -307,35 +518,71 @@ impl PlatformService for GhostService {
         api_key: &str,
         _publication_id: &str,
         request: PublishRequest,
-    ) -> Result<String, String> {
+    ) -> Result<String, AppError> {
         let config = parse_config(api_key)?;
-        let jwt = generate_jwt(&config.api_key)?;
-        let c = ghost_client(&jwt)?;
+        if credential(&config)? == GhostCredential::ContentKey {
+            return Err(read_only("Publishing"));
+        }
+        let c = admin_client(&config)?;
+
+        let mut html = request.html_content;
+        if !request.attachments.is_empty() {
+            let mut uploaded = Vec::new();
+            for attachment in &request.attachments {
+                uploaded.push((attachment.filename.clone(), upload_attachment(&c, &config, attachment).await?));
+            }
+            html.push_str(&attachment_links(&uploaded));
+        }
 
-        let body = serde_json::json!({
+        let opts = request.options.ghost.unwrap_or_default();
+        let tags: Vec<serde_json::Value> = opts
+            .tags
+            .iter()
+            .map(|t| serde_json::json!({ "name": t }))
+            .collect();
+        let mut body = serde_json::json!({
             "posts": [{
                 "title": request.title,
-                "html": request.html_content,
+                "html": html,
                 "status": request.status,
+                "tags": tags,
             }]
         });
+        for (field, value) in [
+            ("meta_title", &opts.meta_title),
+            ("meta_description", &opts.meta_description),
+            ("og_image", &opts.og_image),
+            ("slug", &opts.slug),
+            ("canonical_url", &opts.canonical_url),
+        ] {
+            if let Some(value) = value {
+                body["posts"][0][field] = serde_json::json!(value);
+            }
+        }
+
+        // Ghost only emails a post when it is published with a newsletter
+        // (and optional member segment) given as query params.
+        let mut query: Vec<(&str, String)> = vec![("source", "html".to_string())];
+        if request.status == "published" {
+            if let Some(newsletter) = opts.newsletter {
+                query.push(("newsletter", newsletter));
+                query.push(("email_segment", opts.email_segment.unwrap_or_else(|| "all".to_string())));
+            }
+        }
 
         let resp = c
             .post(format!(
                 "{}/ghost/api/admin/posts/",
                 config.api_url.trim_end_matches('/')
             ))
+            .query(&query)
             .json(&body)
             .send()
             .await
-            .map_err(|e| e.to_string())?;
+            .map_err(|e| AppError::network("ghost", "Ghost request failed", e))?;
 
         if !resp.status().is_success() {
-            let err_text = resp
-                .text()
-                .await
-                .unwrap_or_else(|_| "Unknown error".to_string());
-            return Err(format!("Ghost publish error: {}", err_text));
+            return Err(AppError::from_response("ghost", "Ghost publish error", resp).await);
         }
 
         let result: GhostCreateResponse = resp.json().await.map_err(|e| e.to_string())?;
@@ -343,37 +590,41 @@ impl PlatformService for GhostService {
             .posts
             .first()
             .map(|p| p.id.clone())
-            .ok_or_else(|| "No post returned from Ghost".to_string())
+            .ok_or_else(|| {
+                AppError::new(ErrorKind::Platform, "No post returned from Ghost").with_platform("ghost")
+            })
     }
 }
 
 // ─── Import (standalone, not on trait) ──────────────────────────
 
 impl GhostService {
-    pub async fn import_posts(api_key: &str) -> Result<Vec<ImportedPost>, String> {
+    pub async fn import_posts(api_key: &str) -> Result<Vec<ImportedPost>, AppError> {
         let config = parse_config(api_key)?;
-        let jwt = generate_jwt(&config.api_key)?;
-        let c = ghost_client(&jwt)?;
-
-        let resp = c
-            .get(format!(
-                "{}/ghost/api/admin/posts/",
-                config.api_url.trim_end_matches('/')
-            ))
-            .query(&[("limit", "all"), ("formats", "html")])
-            .send()
-            .await
-            .map_err(|e| e.to_string())?;
+        let posts = if credential(&config)? == GhostCredential::ContentKey {
+            // Published posts only; drafts aren't visible to the Content API
+            content_posts(&config, "all").await?
+        } else {
+            let c = admin_client(&config)?;
+            let resp = c
+                .get(format!(
+                    "{}/ghost/api/admin/posts/",
+                    config.api_url.trim_end_matches('/')
+                ))
+                .query(&[("limit", "all"), ("formats", "html")])
+                .send()
+                .await
+                .map_err(|e| AppError::network("ghost", "Ghost request failed", e))?;
 
-        if !resp.status().is_success() {
-            let err = resp.text().await.unwrap_or_default();
-            return Err(format!("Ghost import error: {}", err));
-        }
+            if !resp.status().is_success() {
+                return Err(AppError::from_response("ghost", "Ghost import error", resp).await);
+            }
 
-        let body: GhostPostsResponse = resp.json().await.map_err(|e| e.to_string())?;
+            let body: GhostPostsResponse = resp.json().await.map_err(|e| e.to_string())?;
+            body.posts
+        };
 
-        Ok(body
-            .posts
+        Ok(posts
             .into_iter()
             .map(|p| ImportedPost {
                 id: p.id,
@@ -386,3 +637,234 @@ impl GhostService {
             .collect())
     }
 }
+
+// ─── Unpublish (standalone, not on trait) ───────────────────────
+
+impl GhostService {
+    /// Take a post down by reverting it to a draft; the post and its
+    /// content stay in Ghost. A post that's already gone counts as done.
+    pub async fn unpublish(api_key: &str, post_id: &str) -> Result<(), AppError> {
+        let config = parse_config(api_key)?;
+        if credential(&config)? == GhostCredential::ContentKey {
+            return Err(read_only("Unpublishing"));
+        }
+        let c = admin_client(&config)?;
+        let post_url = format!("{}/ghost/api/admin/posts/{}/", site_root(&config), post_id);
+
+        // Ghost refuses an edit without the post's current updated_at
+        let resp = c
+            .get(&post_url)
+            .send()
+            .await
+            .map_err(|e| AppError::network("ghost", "Ghost request failed", e))?;
+        if resp.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(());
+        }
+        if !resp.status().is_success() {
+            return Err(AppError::from_response("ghost", "Ghost post lookup error", resp).await);
+        }
+        let body: GhostPostsResponse = resp.json().await.map_err(|e| e.to_string())?;
+        let current = body.posts.into_iter().next().ok_or_else(|| {
+            AppError::new(ErrorKind::Platform, "No post returned from Ghost").with_platform("ghost")
+        })?;
+
+        let resp = c
+            .put(&post_url)
+            .json(&serde_json::json!({
+                "posts": [{ "status": "draft", "updated_at": current.updated_at }]
+            }))
+            .send()
+            .await
+            .map_err(|e| AppError::network("ghost", "Ghost request failed", e))?;
+        if !resp.status().is_success() {
+            return Err(AppError::from_response("ghost", "Ghost unpublish error", resp).await);
+        }
+        Ok(())
+    }
+}
+
+// ─── Capabilities ───────────────────────────────────────────────
+
+impl GhostService {
+    /// Work out what the connected credential can do. Admin API keys and
+    /// staff tokens look identical, so a JWT credential is probed against
+    /// `users/me`: only staff tokens belong to a user, and the user's role
+    /// decides what they may publish.
+    pub async fn capabilities(api_key: &str) -> Result<PlatformCapabilities, AppError> {
+        let config = parse_config(api_key)?;
+        let mut caps = PlatformCapabilities::full("ghost", "admin_api_key");
+
+        match credential(&config)? {
+            GhostCredential::ContentKey => {
+                caps.credential_type = "content_api_key".to_string();
+                caps.can_publish = false;
+                caps.can_read_subscribers = false;
+                caps.read_only = true;
+                caps.notes.push(
+                    "Content API keys are read-only: posts and site details can be imported, but publishing and members need an Admin API key or staff token.".to_string(),
+                );
+                return Ok(caps);
+            }
+            GhostCredential::AdminKey | GhostCredential::StaffToken => {}
+        }
+
+        let c = admin_client(&config)?;
+        let resp = c
+            .get(format!("{}/ghost/api/admin/users/me/", site_root(&config)))
+            .query(&[("include", "roles")])
+            .send()
+            .await
+            .map_err(|e| AppError::network("ghost", "Ghost request failed", e))?;
+
+        // Integration keys have no user behind them
+        if !resp.status().is_success() {
+            if config.credential_type.as_deref() == Some("staff") {
+                return Err(AppError::from_response("ghost", "Ghost staff token check failed", resp).await);
+            }
+            return Ok(caps);
+        }
+
+        let body: GhostUsersResponse = resp.json().await.map_err(|e| e.to_string())?;
+        let role = body
+            .users
+            .first()
+            .and_then(|u| u.roles.first())
+            .map(|r| r.name.clone())
+            .unwrap_or_default();
+
+        caps.credential_type = "staff_token".to_string();
+        match role.as_str() {
+            "Owner" | "Administrator" => {}
+            "Editor" | "Author" => {
+                caps.can_read_subscribers = false;
+                caps.notes.push(format!("{} staff tokens can't read members.", role));
+            }
+            _ => {
+                caps.can_publish = false;
+                caps.drafts_only = true;
+                caps.can_read_subscribers = false;
+                caps.notes.push(format!(
+                    "{} staff tokens can only save drafts for review.",
+                    if role.is_empty() { "Contributor" } else { role.as_str() }
+                ));
+            }
+        }
+        Ok(caps)
+    }
+}
+
+// ─── Uploads (standalone, not on trait) ─────────────────────────
+
+impl GhostService {
+    /// Upload one file and return its hosted URL, for callers that need
+    /// the URL itself (podcast enclosures) rather than a link in a post.
+    pub async fn upload_file(api_key: &str, attachment: &PublishAttachment) -> Result<String, AppError> {
+        let config = parse_config(api_key)?;
+        if credential(&config)? == GhostCredential::ContentKey {
+            return Err(read_only("Uploading files"));
+        }
+        let c = admin_client(&config)?;
+        upload_attachment(&c, &config, attachment).await
+    }
+}
+
+// ─── Pages (standalone, not on trait) ───────────────────────────
+
+impl GhostService {
+    /// Publish `html` as a page, updating `page_id` when given and still
+    /// present. Returns the page id and its public URL.
+    pub async fn upsert_page(
+        api_key: &str,
+        page_id: Option<&str>,
+        title: &str,
+        html: &str,
+    ) -> Result<(String, Option<String>), AppError> {
+        let config = parse_config(api_key)?;
+        if credential(&config)? == GhostCredential::ContentKey {
+            return Err(read_only("Publishing pages"));
+        }
+        let c = admin_client(&config)?;
+        let pages = format!("{}/ghost/api/admin/pages/", site_root(&config));
+        let mut page = serde_json::json!({ "title": title, "html": html, "status": "published" });
+
+        // Ghost refuses an edit without the page's current updated_at
+        let existing = match page_id {
+            Some(id) => {
+                let resp = c
+                    .get(format!("{}{}/", pages, id))
+                    .send()
+                    .await
+                    .map_err(|e| AppError::network("ghost", "Ghost request failed", e))?;
+                if resp.status() == reqwest::StatusCode::NOT_FOUND {
+                    None
+                } else if !resp.status().is_success() {
+                    return Err(AppError::from_response("ghost", "Ghost page lookup error", resp).await);
+                } else {
+                    let body: GhostPagesResponse = resp.json().await.map_err(|e| e.to_string())?;
+                    body.pages.into_iter().next()
+                }
+            }
+            None => None,
+        };
+
+        let request = match existing {
+            Some(current) => {
+                page["updated_at"] = serde_json::json!(current.updated_at);
+                c.put(format!("{}{}/", pages, current.id))
+            }
+            None => c.post(&pages),
+        };
+        let resp = request
+            .query(&[("source", "html")])
+            .json(&serde_json::json!({ "pages": [page] }))
+            .send()
+            .await
+            .map_err(|e| AppError::network("ghost", "Ghost request failed", e))?;
+        if !resp.status().is_success() {
+            return Err(AppError::from_response("ghost", "Ghost page publish error", resp).await);
+        }
+
+        let body: GhostPagesResponse = resp.json().await.map_err(|e| e.to_string())?;
+        body.pages
+            .into_iter()
+            .next()
+            .map(|p| (p.id, p.url))
+            .ok_or_else(|| AppError::new(ErrorKind::Platform, "No page returned from Ghost").with_platform("ghost"))
+    }
+}
+
+// ─── Single post (standalone, not on trait) ─────────────────────
+
+impl GhostService {
+    /// One post with its rendered HTML, or None if it no longer exists.
+    /// Content API keys only see published posts.
+    pub async fn get_post(api_key: &str, post_id: &str) -> Result<Option<ImportedPost>, AppError> {
+        let config = parse_config(api_key)?;
+        let request = if credential(&config)? == GhostCredential::ContentKey {
+            content_get(&config, &format!("posts/{}/", post_id))?
+        } else {
+            admin_client(&config)?.get(format!("{}/ghost/api/admin/posts/{}/", site_root(&config), post_id))
+        };
+        let resp = request
+            .query(&[("formats", "html")])
+            .send()
+            .await
+            .map_err(|e| AppError::network("ghost", "Ghost request failed", e))?;
+        if resp.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if !resp.status().is_success() {
+            return Err(AppError::from_response("ghost", "Ghost post lookup error", resp).await);
+        }
+
+        let body: GhostPostsResponse = resp.json().await.map_err(|e| e.to_string())?;
+        Ok(body.posts.into_iter().next().map(|p| ImportedPost {
+            id: p.id,
+            title: p.title.unwrap_or_else(|| "Untitled".to_string()),
+            html_content: p.html.unwrap_or_default(),
+            published_at: p.published_at,
+            url: p.url,
+            platform: "ghost".to_string(),
+        }))
+    }
+}
@@ -0,0 +1,234 @@
+use serde::{Deserialize, Serialize};
+
+use crate::error::{AppError, ErrorKind};
+use crate::services::api_base;
+
+// ─── Google Drive / Docs ────────────────────────────────────────
+//
+// Documents travel as DOCX: Drive converts an uploaded .docx into a Google
+// Doc and exports a Doc back to .docx, so the app's own DOCX writer and
+// reader do the HTML mapping in both directions. Auth is the installed-app
+// OAuth flow with PKCE against the user's own OAuth client.
+
+const AUTH_URL: &str = "https://accounts.google.com/o/oauth2/v2/auth";
+const TOKEN_URL: &str = "https://oauth2.googleapis.com";
+const DRIVE_URL: &str = "https://www.googleapis.com";
+pub const SCOPES: &str = "https://www.googleapis.com/auth/drive.file https://www.googleapis.com/auth/drive.readonly";
+const DOCX_MIME: &str = "application/vnd.openxmlformats-officedocument.wordprocessingml.document";
+const GOOGLE_DOC_MIME: &str = "application/vnd.google-apps.document";
+
+pub struct GoogleService;
+
+/// Stored as the account's api_key.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GoogleCredential {
+    pub client_id: String,
+    pub client_secret: String,
+    pub refresh_token: String,
+}
+
+#[derive(Deserialize)]
+pub struct GoogleTokens {
+    pub access_token: String,
+    pub refresh_token: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DriveFile {
+    pub id: String,
+    pub name: Option<String>,
+    pub web_view_link: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct DriveAbout {
+    user: DriveUser,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DriveUser {
+    display_name: Option<String>,
+    email_address: Option<String>,
+}
+
+fn parse_credential(api_key: &str) -> Result<GoogleCredential, String> {
+    serde_json::from_str(api_key).map_err(|_| {
+        "Invalid Google credential. Reconnect the Google account from Settings.".to_string()
+    })
+}
+
+fn url_encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => (b as char).to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+async fn token_request(form: &[(&str, &str)]) -> Result<GoogleTokens, AppError> {
+    let body = form
+        .iter()
+        .map(|(k, v)| format!("{}={}", k, url_encode(v)))
+        .collect::<Vec<_>>()
+        .join("&");
+    let resp = crate::http::client("google")?
+        .post(format!("{}/token", api_base(TOKEN_URL)))
+        .header(reqwest::header::CONTENT_TYPE, "application/x-www-form-urlencoded")
+        .body(body)
+        .send()
+        .await
+        .map_err(|e| AppError::network("google", "Google sign-in failed", e))?;
+    if !resp.status().is_success() {
+        return Err(AppError::from_response("google", "Google token error", resp).await);
+    }
+    Ok(resp.json().await.map_err(|e| e.to_string())?)
+}
+
+impl GoogleService {
+    /// Consent page URL for the browser. `challenge` is the S256 PKCE
+    /// challenge of the verifier later passed to `exchange_code`.
+    pub fn authorize_url(client_id: &str, redirect_uri: &str, state: &str, challenge: &str) -> String {
+        let params = [
+            ("client_id", client_id),
+            ("redirect_uri", redirect_uri),
+            ("response_type", "code"),
+            ("scope", SCOPES),
+            ("state", state),
+            ("code_challenge", challenge),
+            ("code_challenge_method", "S256"),
+            // A refresh token is only issued with offline access and consent
+            ("access_type", "offline"),
+            ("prompt", "consent"),
+        ];
+        let query: Vec<String> = params.iter().map(|(k, v)| format!("{}={}", k, url_encode(v))).collect();
+        format!("{}?{}", AUTH_URL, query.join("&"))
+    }
+
+    pub async fn exchange_code(
+        client_id: &str,
+        client_secret: &str,
+        code: &str,
+        redirect_uri: &str,
+        verifier: &str,
+    ) -> Result<GoogleTokens, AppError> {
+        token_request(&[
+            ("grant_type", "authorization_code"),
+            ("client_id", client_id),
+            ("client_secret", client_secret),
+            ("code", code),
+            ("redirect_uri", redirect_uri),
+            ("code_verifier", verifier),
+        ])
+        .await
+    }
+
+    /// A fresh access token for a stored credential.
+    pub async fn access_token(api_key: &str) -> Result<String, AppError> {
+        let credential = parse_credential(api_key)?;
+        let tokens = token_request(&[
+            ("grant_type", "refresh_token"),
+            ("client_id", &credential.client_id),
+            ("client_secret", &credential.client_secret),
+            ("refresh_token", &credential.refresh_token),
+        ])
+        .await?;
+        Ok(tokens.access_token)
+    }
+
+    /// (display name, email) of the signed-in user.
+    pub async fn account(access_token: &str) -> Result<(String, String), AppError> {
+        let resp = crate::http::client("google")?
+            .get(format!("{}/drive/v3/about", api_base(DRIVE_URL)))
+            .query(&[("fields", "user(displayName,emailAddress)")])
+            .bearer_auth(access_token)
+            .send()
+            .await
+            .map_err(|e| AppError::network("google", "Google Drive request failed", e))?;
+        if !resp.status().is_success() {
+            return Err(AppError::from_response("google", "Google Drive error", resp).await);
+        }
+        let about: DriveAbout = resp.json().await.map_err(|e| e.to_string())?;
+        let email = about.user.email_address.unwrap_or_default();
+        Ok((about.user.display_name.unwrap_or_else(|| email.clone()), email))
+    }
+
+    /// Upload a .docx and have Drive convert it to a Google Doc.
+    pub async fn create_doc_from_docx(access_token: &str, title: &str, docx: &[u8]) -> Result<DriveFile, AppError> {
+        let boundary = format!("station-{}", uuid::Uuid::new_v4().simple());
+        let metadata = serde_json::json!({ "name": title, "mimeType": GOOGLE_DOC_MIME });
+        let mut body = format!(
+            "--{b}\r\nContent-Type: application/json; charset=UTF-8\r\n\r\n{meta}\r\n--{b}\r\nContent-Type: {mime}\r\n\r\n",
+            b = boundary,
+            meta = metadata,
+            mime = DOCX_MIME
+        )
+        .into_bytes();
+        body.extend_from_slice(docx);
+        body.extend_from_slice(format!("\r\n--{}--\r\n", boundary).as_bytes());
+
+        let resp = crate::http::client("google")?
+            .post(format!("{}/upload/drive/v3/files", api_base(DRIVE_URL)))
+            .query(&[("uploadType", "multipart"), ("fields", "id,name,webViewLink")])
+            .bearer_auth(access_token)
+            .header(reqwest::header::CONTENT_TYPE, format!("multipart/related; boundary={}", boundary))
+            .body(body)
+            .send()
+            .await
+            .map_err(|e| AppError::network("google", "Google Drive upload failed", e))?;
+        if !resp.status().is_success() {
+            return Err(AppError::from_response("google", "Google Drive upload error", resp).await);
+        }
+        Ok(resp.json().await.map_err(|e| e.to_string())?)
+    }
+
+    /// A Google Doc's name and its content exported as .docx.
+    pub async fn export_doc_as_docx(access_token: &str, file_id: &str) -> Result<(String, Vec<u8>), AppError> {
+        let c = crate::http::client("google")?;
+        let files = format!("{}/drive/v3/files/{}", api_base(DRIVE_URL), url_encode(file_id));
+        let resp = c
+            .get(&files)
+            .query(&[("fields", "id,name,mimeType")])
+            .bearer_auth(access_token)
+            .send()
+            .await
+            .map_err(|e| AppError::network("google", "Google Drive request failed", e))?;
+        if !resp.status().is_success() {
+            return Err(AppError::from_response("google", "Google Drive error", resp).await);
+        }
+        let meta: serde_json::Value = resp.json().await.map_err(|e| e.to_string())?;
+        if meta["mimeType"].as_str() != Some(GOOGLE_DOC_MIME) {
+            return Err(AppError::new(ErrorKind::Validation, "That file is not a Google Doc").with_platform("google"));
+        }
+
+        let resp = c
+            .get(format!("{}/export", files))
+            .query(&[("mimeType", DOCX_MIME)])
+            .bearer_auth(access_token)
+            .send()
+            .await
+            .map_err(|e| AppError::network("google", "Google Drive export failed", e))?;
+        if !resp.status().is_success() {
+            return Err(AppError::from_response("google", "Google Drive export error", resp).await);
+        }
+        let bytes = resp.bytes().await.map_err(|e| e.to_string())?;
+        let name = meta["name"].as_str().unwrap_or("Untitled").to_string();
+        Ok((name, bytes.to_vec()))
+    }
+}
+
+/// File id from a Docs URL ("https://docs.google.com/document/d/<id>/edit")
+/// or a bare id.
+pub fn doc_id_from_url(url: &str) -> Option<String> {
+    let url = url.trim();
+    let id = match url.split_once("/d/") {
+        Some((_, rest)) => rest.split(['/', '?', '#']).next().unwrap_or(""),
+        None if !url.contains('/') => url,
+        None => return None,
+    };
+    let valid = !id.is_empty() && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    valid.then(|| id.to_string())
+}
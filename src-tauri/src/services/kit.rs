@@ -1,23 +1,38 @@
-use reqwest::Client;
+use std::time::Duration;
+
+use reqwest::header::HeaderMap;
+use reqwest::{Client, RequestBuilder, Response, StatusCode};
+use serde::de::DeserializeOwned;
 use serde::Deserialize;
 
 use crate::commands::platform::{
     AnalyticsData, ImportedPost, PostPerformance, Publication, PublishRequest, Subscriber,
 };
-use crate::services::PlatformService;
+use crate::error::AppError;
+use crate::services::{api_base, PlatformService};
 
 const BASE_URL: &str = "https://api.convertkit.com/v4";
 
+/// Largest page Kit v4 serves for list endpoints
+const PAGE_SIZE: &str = "500";
+
+/// How many times a 429 is retried before giving up
+const MAX_RETRIES: u32 = 3;
+
+/// Start slowing down once fewer than this many requests remain in the
+/// current rate-limit window
+const THROTTLE_BELOW: u64 = 10;
+
 pub struct KitService;
 
 // ─── Kit (ConvertKit) API v4 response types ─────────────────────
 
-#[derive(Deserialize)]
-#[allow(dead_code)]
-struct KitPaginatedResponse<T> {
-    data: Option<Vec<T>>,
-    subscribers: Option<Vec<T>>,
-    broadcasts: Option<Vec<T>>,
+#[derive(Deserialize, Default)]
+struct KitPagination {
+    #[serde(default)]
+    has_next_page: bool,
+    end_cursor: Option<String>,
+    total_count: Option<u64>,
 }
 
 #[derive(Deserialize)]
@@ -55,7 +70,7 @@ struct KitBroadcastStats {
 }
 
 fn client(api_key: &str) -> Result<Client, String> {
-    Client::builder()
+    crate::http::builder("kit")?
         .default_headers({
             let mut h = reqwest::header::HeaderMap::new();
             h.insert(
@@ -72,30 +87,162 @@ fn client(api_key: &str) -> Result<Client, String> {
         .map_err(|e| e.to_string())
 }
 
-impl PlatformService for KitService {
-    async fn validate_connection(api_key: &str) -> Result<bool, String> {
-        let c = client(api_key)?;
-        let resp = c
-            .get(format!("{}/account", BASE_URL))
+// ─── Rate limits ────────────────────────────────────────────────
+//
+// Kit allows 120 requests per rolling minute per API key and reports the
+// window in X-RateLimit-* headers. Requests are spaced out as the window
+// runs low, and a 429 waits out Retry-After (or the reset) before retrying.
+
+fn header_u64(headers: &HeaderMap, name: &str) -> Option<u64> {
+    headers.get(name)?.to_str().ok()?.trim().parse().ok()
+}
+
+/// Seconds until the rate-limit window resets. Kit sends either a delta or
+/// a Unix timestamp; anything past 2001 is treated as the latter.
+fn reset_secs(headers: &HeaderMap) -> Option<u64> {
+    let reset = header_u64(headers, "x-ratelimit-reset")?;
+    if reset > 1_000_000_000 {
+        Some(reset.saturating_sub(chrono::Utc::now().timestamp().max(0) as u64))
+    } else {
+        Some(reset)
+    }
+}
+
+/// Delay before the next request so the remaining budget lasts until reset.
+fn adaptive_delay(headers: &HeaderMap) -> Option<Duration> {
+    let remaining = header_u64(headers, "x-ratelimit-remaining")?;
+    if remaining >= THROTTLE_BELOW {
+        return None;
+    }
+    let window = reset_secs(headers).unwrap_or(60) as f64;
+    let secs = (window / (remaining.max(1) as f64)).min(30.0);
+    Some(Duration::from_secs_f64(secs))
+}
+
+fn retry_delay(headers: &HeaderMap, attempt: u32) -> Duration {
+    header_u64(headers, "retry-after")
+        .or_else(|| reset_secs(headers))
+        .map(|s| Duration::from_secs(s.min(60)))
+        .unwrap_or_else(|| Duration::from_millis(250 * 2u64.pow(attempt)))
+}
+
+/// Send a request, honouring Kit's rate-limit headers.
+async fn send(req: RequestBuilder) -> Result<Response, AppError> {
+    let mut attempt = 0;
+    loop {
+        let resp = req
+            .try_clone()
+            .ok_or_else(|| AppError::from("Kit request body can't be retried"))?
             .send()
             .await
-            .map_err(|e| e.to_string())?;
+            .map_err(|e| AppError::network("kit", "Kit request failed", e))?;
+
+        if resp.status() == StatusCode::TOO_MANY_REQUESTS && attempt < MAX_RETRIES {
+            let wait = retry_delay(resp.headers(), attempt);
+            crate::trace::log("Kit", &format!("Rate limited; retrying in {:?}", wait));
+            tokio::time::sleep(wait).await;
+            attempt += 1;
+            continue;
+        }
+        if let Some(wait) = adaptive_delay(resp.headers()) {
+            tokio::time::sleep(wait).await;
+        }
+        return Ok(resp);
+    }
+}
+
+// ─── Pagination ─────────────────────────────────────────────────
+
+/// One page of a list endpoint: the items under `key`, plus pagination.
+async fn fetch_page<T: DeserializeOwned>(
+    c: &Client,
+    path: &str,
+    key: &str,
+    query: &[(&str, &str)],
+    after: Option<&str>,
+) -> Result<(Vec<T>, KitPagination), AppError> {
+    let mut req = c.get(format!("{}/{}", api_base(BASE_URL), path)).query(query);
+    if let Some(cursor) = after {
+        req = req.query(&[("after", cursor)]);
+    }
+    let resp = send(req).await?;
+    if !resp.status().is_success() {
+        return Err(AppError::from_response("kit", "Kit API error", resp).await);
+    }
+
+    let mut body: serde_json::Value = resp.json().await.map_err(|e| e.to_string())?;
+    let items: Vec<T> = serde_json::from_value(
+        body.get_mut(key)
+            .map(serde_json::Value::take)
+            .unwrap_or(serde_json::Value::Array(vec![])),
+    )?;
+    let mut pagination: KitPagination = body
+        .get_mut("pagination")
+        .map(serde_json::Value::take)
+        .and_then(|v| serde_json::from_value(v).ok())
+        .unwrap_or_default();
+    // Older responses put the total at the top level
+    if pagination.total_count.is_none() {
+        pagination.total_count = body
+            .get("total_count")
+            .or_else(|| body.get("total_subscribers"))
+            .and_then(|v| v.as_u64());
+    }
+    Ok((items, pagination))
+}
+
+/// Follow `end_cursor` until Kit reports no further pages.
+async fn fetch_all<T: DeserializeOwned>(
+    c: &Client,
+    path: &str,
+    key: &str,
+) -> Result<Vec<T>, AppError> {
+    let mut all = Vec::new();
+    let mut cursor: Option<String> = None;
+    loop {
+        let (items, page) =
+            fetch_page(c, path, key, &[("per_page", PAGE_SIZE)], cursor.as_deref()).await?;
+        all.extend(items);
+        match page.end_cursor {
+            Some(next) if page.has_next_page && cursor.as_deref() != Some(next.as_str()) => {
+                cursor = Some(next)
+            }
+            _ => break,
+        }
+    }
+    Ok(all)
+}
+
+/// Subscriber total from a one-row page; Kit only counts when asked.
+async fn subscriber_total(c: &Client) -> Result<u64, AppError> {
+    let (_, page) = fetch_page::<serde_json::Value>(
+        c,
+        "subscribers",
+        "subscribers",
+        &[("per_page", "1"), ("include_total_count", "true")],
+        None,
+    )
+    .await?;
+    Ok(page.total_count.unwrap_or(0))
+}
+
+impl PlatformService for KitService {
+    async fn validate_connection(api_key: &str) -> Result<bool, AppError> {
+        let c = client(api_key)?;
+        let resp = send(c.get(format!("{}/account", api_base(BASE_URL)))).await?;
         Ok(resp.status().is_success())
     }
 
-    async fn get_publications(api_key: &str) -> Result<Vec<Publication>, String> {
+    async fn get_publications(api_key: &str) -> Result<Vec<Publication>, AppError> {
         let c = client(api_key)?;
-        let resp = c
-            .get(format!("{}/account", BASE_URL))
-            .send()
-            .await
-            .map_err(|e| e.to_string())?;
+        let resp = send(c.get(format!("{}/account", api_base(BASE_URL)))).await?;
 
         if !resp.status().is_success() {
-            return Err(format!("Kit API error: {}", resp.status()));
+            return Err(AppError::from_response("kit", "Kit API error", resp).await);
         }
 
         let account: KitAccount = resp.json().await.map_err(|e| e.to_string())?;
+        let subscriber_count = subscriber_total(&c).await.ok();
 
         // Kit doesn't have separate "publications" — the account itself is the publication
         Ok(vec![Publication {
@@ -103,7 +250,7 @@ impl PlatformService for KitService {
             name: account.name.unwrap_or_else(|| "Kit Account".to_string()),
             url: "https://app.convertkit.com".to_string(),
             platform: "kit".to_string(),
-            subscriber_count: None,
+            subscriber_count,
             description: account.primary_email_address,
         }])
     }
@@ -111,26 +258,9 @@ impl PlatformService for KitService {
     async fn get_subscribers(
         api_key: &str,
         _publication_id: Option<&str>,
-    ) -> Result<Vec<Subscriber>, String> {
+    ) -> Result<Vec<Subscriber>, AppError> {
         let c = client(api_key)?;
-        let resp = c
-            .get(format!("{}/subscribers", BASE_URL))
-            .query(&[("per_page", "100")])
-            .send()
-            .await
-            .map_err(|e| e.to_string())?;
-
-        if !resp.status().is_success() {
-            return Err(format!("Kit API error: {}", resp.status()));
-        }
-
-        let body: serde_json::Value = resp.json().await.map_err(|e| e.to_string())?;
-        let subscribers: Vec<KitSubscriber> = serde_json::from_value(
-            body.get("subscribers")
-                .cloned()
-                .unwrap_or(serde_json::Value::Array(vec![])),
-        )
-        .map_err(|e| e.to_string())?;
+        let subscribers: Vec<KitSubscriber> = fetch_all(&c, "subscribers", "subscribers").await?;
 
         Ok(subscribers
             .into_iter()
@@ -140,6 +270,7 @@ impl PlatformService for KitService {
                 status: s.state,
                 created_at: s.created_at.unwrap_or_default(),
                 platform: "kit".to_string(),
+                country: None,
             })
             .collect())
     }
@@ -147,65 +278,41 @@ impl PlatformService for KitService {
     async fn get_analytics(
         api_key: &str,
         _publication_id: Option<&str>,
-    ) -> Result<AnalyticsData, String> {
+    ) -> Result<AnalyticsData, AppError> {
         let c = client(api_key)?;
 
-        // Get subscriber count
-        let subs_resp = c
-            .get(format!("{}/subscribers", BASE_URL))
-            .query(&[("per_page", "1")])
-            .send()
-            .await
-            .map_err(|e| e.to_string())?;
-
-        let total_subscribers: u64 = if subs_resp.status().is_success() {
-            let body: serde_json::Value = subs_resp.json().await.map_err(|e| e.to_string())?;
-            body.get("total_subscribers")
-                .or_else(|| body.get("total_count"))
-                .and_then(|v| v.as_u64())
-                .unwrap_or(0)
-        } else {
-            0
-        };
+        let total_subscribers = subscriber_total(&c).await?;
 
-        // Get broadcasts for post performance
-        let bc_resp = c
-            .get(format!("{}/broadcasts", BASE_URL))
-            .query(&[("per_page", "50")])
-            .send()
-            .await
-            .map_err(|e| e.to_string())?;
+        // Most recent page of broadcasts for post performance
+        let (broadcasts, _) = fetch_page::<KitBroadcast>(
+            &c,
+            "broadcasts",
+            "broadcasts",
+            &[("per_page", "50")],
+            None,
+        )
+        .await?;
 
         let mut recent_posts = Vec::new();
         let mut total_open_rate = 0.0;
         let mut total_click_rate = 0.0;
         let mut counted = 0u64;
 
-        if bc_resp.status().is_success() {
-            let body: serde_json::Value = bc_resp.json().await.map_err(|e| e.to_string())?;
-            let broadcasts: Vec<KitBroadcast> = serde_json::from_value(
-                body.get("broadcasts")
-                    .cloned()
-                    .unwrap_or(serde_json::Value::Array(vec![])),
-            )
-            .unwrap_or_default();
-
-            for bc in &broadcasts {
-                if let Some(stats) = &bc.stats {
-                    total_open_rate += stats.open_rate.unwrap_or(0.0);
-                    total_click_rate += stats.click_rate.unwrap_or(0.0);
-                    counted += 1;
-
-                    recent_posts.push(PostPerformance {
-                        id: bc.id.to_string(),
-                        title: bc.subject.clone().unwrap_or_else(|| "Untitled".to_string()),
-                        published_at: bc.created_at.clone().unwrap_or_default(),
-                        opens: stats.open_count.unwrap_or(0),
-                        clicks: stats.total_clicks.unwrap_or(0),
-                        unsubscribes: stats.unsubscribes.unwrap_or(0),
-                        platform: "kit".to_string(),
-                    });
-                }
+        for bc in &broadcasts {
+            if let Some(stats) = &bc.stats {
+                total_open_rate += stats.open_rate.unwrap_or(0.0);
+                total_click_rate += stats.click_rate.unwrap_or(0.0);
+                counted += 1;
+
+                recent_posts.push(PostPerformance {
+                    id: bc.id.to_string(),
+                    title: bc.subject.clone().unwrap_or_else(|| "Untitled".to_string()),
+                    published_at: bc.created_at.clone().unwrap_or_default(),
+                    opens: stats.open_count.unwrap_or(0),
+                    clicks: stats.total_clicks.unwrap_or(0),
+                    unsubscribes: stats.unsubscribes.unwrap_or(0),
+                    platform: "kit".to_string(),
+                });
             }
         }
 
@@ -233,10 +340,10 @@ impl PlatformService for KitService {
         api_key: &str,
         _publication_id: &str,
         request: PublishRequest,
-    ) -> Result<String, String> {
+    ) -> Result<String, AppError> {
         let c = client(api_key)?;
 
-        let body = serde_json::json!({
+        let mut body = serde_json::json!({
             "broadcast": {
                 "subject": request.title,
                 "content": request.html_content,
@@ -244,17 +351,23 @@ impl PlatformService for KitService {
                 "public": request.status == "published",
             }
         });
+        if let Some(opts) = request.options.kit {
+            let mut any = Vec::new();
+            if !opts.tag_ids.is_empty() {
+                any.push(serde_json::json!({ "type": "tag", "ids": opts.tag_ids }));
+            }
+            if !opts.segment_ids.is_empty() {
+                any.push(serde_json::json!({ "type": "segment", "ids": opts.segment_ids }));
+            }
+            if !any.is_empty() {
+                body["broadcast"]["subscriber_filter"] = serde_json::json!([{ "any": any }]);
+            }
+        }
 
-        let resp = c
-            .post(format!("{}/broadcasts", BASE_URL))
-            .json(&body)
-            .send()
-            .await
-            .map_err(|e| e.to_string())?;
+        let resp = send(c.post(format!("{}/broadcasts", api_base(BASE_URL))).json(&body)).await?;
 
         if !resp.status().is_success() {
-            let err_text = resp.text().await.unwrap_or_else(|_| "Unknown error".to_string());
-            return Err(format!("Kit publish error: {}", err_text));
+            return Err(AppError::from_response("kit", "Kit publish error", resp).await);
         }
 
         let result: serde_json::Value = resp.json().await.map_err(|e| e.to_string())?;
@@ -272,27 +385,9 @@ impl PlatformService for KitService {
 // ─── Import (standalone, not on trait) ──────────────────────────
 
 impl KitService {
-    pub async fn import_posts(api_key: &str) -> Result<Vec<ImportedPost>, String> {
+    pub async fn import_posts(api_key: &str) -> Result<Vec<ImportedPost>, AppError> {
         let c = client(api_key)?;
-
-        let resp = c
-            .get(format!("{}/broadcasts", BASE_URL))
-            .query(&[("per_page", "50")])
-            .send()
-            .await
-            .map_err(|e| e.to_string())?;
-
-        if !resp.status().is_success() {
-            return Err(format!("Kit import error: {}", resp.status()));
-        }
-
-        let body: serde_json::Value = resp.json().await.map_err(|e| e.to_string())?;
-        let broadcasts: Vec<KitBroadcast> = serde_json::from_value(
-            body.get("broadcasts")
-                .cloned()
-                .unwrap_or(serde_json::Value::Array(vec![])),
-        )
-        .unwrap_or_default();
+        let broadcasts: Vec<KitBroadcast> = fetch_all(&c, "broadcasts", "broadcasts").await?;
 
         Ok(broadcasts
             .into_iter()
@@ -307,3 +402,20 @@ impl KitService {
             .collect())
     }
 }
+
+// ─── Unpublish (standalone, not on trait) ───────────────────────
+
+impl KitService {
+    /// Take a broadcast off the creator profile's public archive. Emails
+    /// already sent can't be recalled.
+    pub async fn unpublish(api_key: &str, broadcast_id: &str) -> Result<(), AppError> {
+        let c = client(api_key)?;
+        let body = serde_json::json!({ "broadcast": { "public": false } });
+        let resp = send(c.put(format!("{}/broadcasts/{}", api_base(BASE_URL), broadcast_id)).json(&body)).await?;
+
+        if !resp.status().is_success() {
+            return Err(AppError::from_response("kit", "Kit unpublish error", resp).await);
+        }
+        Ok(())
+    }
+}
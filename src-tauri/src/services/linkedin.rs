@@ -1,5 +1,7 @@
 use serde::Deserialize;
 
+use crate::error::AppError;
+
 pub struct LinkedinService;
 
 #[derive(Deserialize)]
@@ -8,29 +10,29 @@ struct LinkedinUserInfo {
 }
 
 impl LinkedinService {
-    pub async fn validate(api_key: &str) -> Result<bool, String> {
-        let client = reqwest::Client::new();
+    pub async fn validate(api_key: &str) -> Result<bool, AppError> {
+        let client = crate::http::client("linkedin")?;
         let resp = client
             .get("https://api.linkedin.com/v2/userinfo")
             .header("Authorization", format!("Bearer {}", api_key))
             .send()
             .await
-            .map_err(|e| e.to_string())?;
+            .map_err(|e| AppError::network("linkedin", "LinkedIn request failed", e))?;
 
         Ok(resp.status().is_success())
     }
 
-    async fn get_person_urn(api_key: &str) -> Result<String, String> {
-        let client = reqwest::Client::new();
+    async fn get_person_urn(api_key: &str) -> Result<String, AppError> {
+        let client = crate::http::client("linkedin")?;
         let resp = client
             .get("https://api.linkedin.com/v2/userinfo")
             .header("Authorization", format!("Bearer {}", api_key))
             .send()
             .await
-            .map_err(|e| e.to_string())?;
+            .map_err(|e| AppError::network("linkedin", "LinkedIn request failed", e))?;
 
         if !resp.status().is_success() {
-            return Err(format!("LinkedIn API error: {}", resp.status()));
+            return Err(AppError::from_response("linkedin", "LinkedIn API error", resp).await);
         }
 
         let info: LinkedinUserInfo = resp.json().await.map_err(|e| e.to_string())?;
@@ -41,7 +43,7 @@ impl LinkedinService {
         api_key: &str,
         content: &str,
         article_url: Option<&str>,
-    ) -> Result<String, String> {
+    ) -> Result<String, AppError> {
         let author = Self::get_person_urn(api_key).await?;
 
         let mut share_content = serde_json::json!({
@@ -71,7 +73,7 @@ impl LinkedinService {
             }
         });
 
-        let client = reqwest::Client::new();
+        let client = crate::http::client("linkedin")?;
         let resp = client
             .post("https://api.linkedin.com/v2/ugcPosts")
             .header("Authorization", format!("Bearer {}", api_key))
@@ -80,11 +82,10 @@ impl LinkedinService {
             .json(&body)
             .send()
             .await
-            .map_err(|e| format!("LinkedIn post failed: {}", e))?;
+            .map_err(|e| AppError::network("linkedin", "LinkedIn request failed", e))?;
 
         if !resp.status().is_success() {
-            let err = resp.text().await.unwrap_or_default();
-            return Err(format!("LinkedIn API error: {}", err));
+            return Err(AppError::from_response("linkedin", "LinkedIn API error", resp).await);
         }
 
         // LinkedIn returns the post ID in the x-restli-id header or response body
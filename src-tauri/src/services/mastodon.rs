@@ -0,0 +1,107 @@
+use serde::Deserialize;
+
+use crate::commands::export::html_to_plain_text;
+use crate::commands::testimonials::SocialMention;
+use crate::error::AppError;
+
+// ─── Mastodon credential format ────────────────────────────────
+
+#[derive(Deserialize)]
+pub struct MastodonConfig {
+    /// e.g. "https://mastodon.social"
+    pub instance_url: String,
+    pub access_token: String,
+}
+
+pub struct MastodonService;
+
+// ─── Response types ─────────────────────────────────────────────
+
+#[derive(Deserialize)]
+struct Notification {
+    status: Option<Status>,
+}
+
+#[derive(Deserialize)]
+struct Status {
+    id: String,
+    url: Option<String>,
+    content: String,
+    created_at: String,
+    account: Account,
+}
+
+#[derive(Deserialize)]
+struct Account {
+    acct: String,
+    display_name: String,
+}
+
+fn parse_config(api_key: &str) -> Result<MastodonConfig, AppError> {
+    let config: MastodonConfig =
+        serde_json::from_str(api_key).map_err(|e| format!("Invalid Mastodon config: {}", e))?;
+    Ok(MastodonConfig {
+        instance_url: config.instance_url.trim_end_matches('/').to_string(),
+        ..config
+    })
+}
+
+/// `href` values of the links in a status's HTML.
+fn links(html: &str) -> Vec<String> {
+    html.split("href=\"")
+        .skip(1)
+        .filter_map(|rest| rest.split('"').next())
+        .map(|href| href.replace("&amp;", "&"))
+        .collect()
+}
+
+impl MastodonService {
+    pub async fn validate(api_key: &str) -> Result<bool, AppError> {
+        let config = parse_config(api_key)?;
+        let client = crate::http::client("mastodon")?;
+        let resp = client
+            .get(format!("{}/api/v1/accounts/verify_credentials", config.instance_url))
+            .bearer_auth(&config.access_token)
+            .send()
+            .await
+            .map_err(|e| AppError::network("mastodon", "Mastodon request failed", e))?;
+
+        Ok(resp.status().is_success())
+    }
+
+    /// The latest mentions of the account, newest first.
+    pub async fn mentions(api_key: &str) -> Result<Vec<SocialMention>, AppError> {
+        let config = parse_config(api_key)?;
+        let client = crate::http::client("mastodon")?;
+        let resp = client
+            .get(format!("{}/api/v1/notifications", config.instance_url))
+            .bearer_auth(&config.access_token)
+            .query(&[("types[]", "mention"), ("limit", "40")])
+            .send()
+            .await
+            .map_err(|e| AppError::network("mastodon", "Mastodon request failed", e))?;
+
+        if !resp.status().is_success() {
+            return Err(AppError::from_response("mastodon", "Mastodon API error", resp).await);
+        }
+
+        let notifications: Vec<Notification> = resp.json().await.map_err(|e| e.to_string())?;
+        Ok(notifications
+            .into_iter()
+            .filter_map(|n| n.status)
+            .map(|status| SocialMention {
+                links: links(&status.content),
+                text: html_to_plain_text(&status.content).trim().to_string(),
+                author_name: if status.account.display_name.trim().is_empty() {
+                    status.account.acct.clone()
+                } else {
+                    status.account.display_name
+                },
+                author_handle: format!("@{}", status.account.acct),
+                url: status.url,
+                created_at: status.created_at,
+                id: status.id,
+            })
+            .collect())
+    }
+}
@@ -1,31 +1,53 @@
 pub mod beehiiv;
 pub mod ghost;
+pub mod google;
 pub mod kit;
 pub mod linkedin;
+pub mod mastodon;
+pub mod paddle;
 pub mod stripe;
 pub mod substack;
 pub mod twitter;
+pub mod wordpress;
+
+#[cfg(test)]
+mod contract_tests;
+#[cfg(test)]
+pub(crate) mod test_support;
 
 use crate::commands::platform::{
     AnalyticsData, Publication, PublishRequest, Subscriber,
 };
+use crate::error::AppError;
 
 /// Trait that all newsletter platform services must implement
 #[allow(async_fn_in_trait)]
 pub trait PlatformService {
-    async fn validate_connection(api_key: &str) -> Result<bool, String>;
-    async fn get_publications(api_key: &str) -> Result<Vec<Publication>, String>;
+    async fn validate_connection(api_key: &str) -> Result<bool, AppError>;
+    async fn get_publications(api_key: &str) -> Result<Vec<Publication>, AppError>;
     async fn get_subscribers(
         api_key: &str,
         publication_id: Option<&str>,
-    ) -> Result<Vec<Subscriber>, String>;
+    ) -> Result<Vec<Subscriber>, AppError>;
     async fn get_analytics(
         api_key: &str,
         publication_id: Option<&str>,
-    ) -> Result<AnalyticsData, String>;
+    ) -> Result<AnalyticsData, AppError>;
     async fn publish(
         api_key: &str,
         publication_id: &str,
         request: PublishRequest,
-    ) -> Result<String, String>;
+    ) -> Result<String, AppError>;
+}
+
+/// API root for a service. Tests redirect this to a mock server via
+/// `test_support::MockApi`; release builds always use the real endpoint.
+#[cfg(not(test))]
+pub(crate) fn api_base(default: &str) -> String {
+    default.to_string()
+}
+
+#[cfg(test)]
+pub(crate) fn api_base(default: &str) -> String {
+    test_support::base_url_override().unwrap_or_else(|| default.to_string())
 }
@@ -0,0 +1,153 @@
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+
+use crate::error::AppError;
+use crate::services::api_base;
+
+const BASE_URL: &str = "https://api.paddle.com";
+
+// ─── Paddle Billing API response types ──────────────────────────
+
+#[derive(Debug, Deserialize)]
+pub struct PaddleTransaction {
+    pub id: String,
+    pub status: String,
+    pub currency_code: String,
+    pub subscription_id: Option<String>,
+    pub billed_at: Option<String>,
+    pub created_at: String,
+    pub billing_period: Option<PaddleBillingPeriod>,
+    pub details: Option<PaddleTransactionDetails>,
+    pub customer: Option<PaddleCustomer>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PaddleBillingPeriod {
+    pub starts_at: String,
+    pub ends_at: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PaddleTransactionDetails {
+    pub totals: Option<PaddleTotals>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PaddleTotals {
+    /// Amounts are strings in the lowest currency denomination ("1000" = $10.00)
+    pub total: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PaddleCustomer {
+    pub email: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PaddleAdjustment {
+    pub id: String,
+    pub action: String,
+    pub status: String,
+    pub transaction_id: String,
+    pub currency_code: String,
+    pub reason: Option<String>,
+    pub created_at: String,
+    pub totals: Option<PaddleTotals>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PaddleList<T> {
+    data: Vec<T>,
+    #[serde(default)]
+    meta: Option<PaddleMeta>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PaddleMeta {
+    pagination: Option<PaddlePagination>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PaddlePagination {
+    /// URL of the next page, with its `after` cursor
+    next: Option<String>,
+    #[serde(default)]
+    has_more: bool,
+}
+
+impl<T> PaddleList<T> {
+    /// The `after` cursor of the next page, when there is one.
+    fn next_cursor(&self) -> Option<String> {
+        let pagination = self.meta.as_ref()?.pagination.as_ref()?;
+        if !pagination.has_more {
+            return None;
+        }
+        let next = reqwest::Url::parse(pagination.next.as_deref()?).ok()?;
+        let after = next.query_pairs().find(|(key, _)| key == "after")?;
+        Some(after.1.into_owned())
+    }
+}
+
+/// Every item of a list endpoint, `limit` (at most 200) per page, following
+/// the `after` cursor of `meta.pagination.next` until Paddle reports no more.
+async fn fetch_all<T: DeserializeOwned>(
+    api_key: &str,
+    resource: &str,
+    query: &[(&str, &str)],
+    limit: u32,
+) -> Result<Vec<T>, AppError> {
+    let client = crate::http::client("paddle")?;
+    let per_page = limit.clamp(1, 200).to_string();
+    let mut all = Vec::new();
+    let mut cursor: Option<String> = None;
+    loop {
+        let mut req = client
+            .get(format!("{}/{}", api_base(BASE_URL), resource))
+            .query(query)
+            .query(&[("per_page", per_page.as_str())])
+            .bearer_auth(api_key);
+        if let Some(after) = &cursor {
+            req = req.query(&[("after", after)]);
+        }
+        let resp = req.send().await.map_err(|e| AppError::network("paddle", "Paddle request failed", e))?;
+        if !resp.status().is_success() {
+            return Err(AppError::from_response("paddle", "Paddle API", resp).await);
+        }
+
+        let list: PaddleList<T> =
+            resp.json().await.map_err(|e| format!("Failed to parse Paddle response: {}", e))?;
+        let next = list.next_cursor();
+        all.extend(list.data);
+        match next {
+            Some(next) if cursor.as_deref() != Some(next.as_str()) => cursor = Some(next),
+            _ => break,
+        }
+    }
+    Ok(all)
+}
+
+pub struct PaddleService;
+
+impl PaddleService {
+    /// Completed transactions with the customer expanded for email
+    /// matching, `limit` per request.
+    pub async fn fetch_transactions(
+        api_key: &str,
+        limit: u32,
+    ) -> Result<Vec<PaddleTransaction>, AppError> {
+        fetch_all(api_key, "transactions", &[("status", "completed"), ("include", "customer")], limit).await
+    }
+
+    /// Approved refund adjustments (Paddle's equivalent of Stripe refunds),
+    /// `limit` per request.
+    pub async fn fetch_refunds(api_key: &str, limit: u32) -> Result<Vec<PaddleAdjustment>, AppError> {
+        fetch_all(api_key, "adjustments", &[("action", "refund"), ("status", "approved")], limit).await
+    }
+}
+
+/// Parse a Paddle amount string into cents.
+pub fn amount_cents(totals: Option<&PaddleTotals>) -> i64 {
+    totals
+        .and_then(|t| t.total.parse::<i64>().ok())
+        .unwrap_or(0)
+}
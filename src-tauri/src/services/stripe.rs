@@ -1,8 +1,13 @@
 #[allow(dead_code)]
+use serde::de::DeserializeOwned;
 use serde::Deserialize;
 
+use crate::error::AppError;
+use crate::services::api_base;
+
+const BASE_URL: &str = "https://api.stripe.com/v1";
+
 #[derive(Debug, Deserialize)]
-#[allow(dead_code)]
 pub struct StripeCharge {
     pub id: String,
     pub amount: i64,
@@ -11,6 +16,43 @@ pub struct StripeCharge {
     pub created: i64,
     pub description: Option<String>,
     pub receipt_email: Option<String>,
+    /// Set when the charge was created by a subscription invoice.
+    #[serde(default)]
+    pub invoice: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct StripeRefund {
+    pub id: String,
+    pub amount: i64,
+    pub currency: String,
+    pub status: Option<String>,
+    pub created: i64,
+    /// The charge this refund was issued against.
+    pub charge: Option<String>,
+    pub reason: Option<String>,
+}
+
+/// A chargeback: the cardholder's bank pulling a charge back.
+#[derive(Debug, Deserialize)]
+pub struct StripeDispute {
+    pub id: String,
+    pub amount: i64,
+    pub currency: String,
+    /// "warning_*" for inquiries (no funds withdrawn), "needs_response",
+    /// "under_review", "won" (funds returned) or "lost"
+    pub status: String,
+    pub created: i64,
+    #[serde(default)]
+    pub charge: Option<String>,
+    pub reason: Option<String>,
+}
+
+impl StripeDispute {
+    /// Whether the disputed amount is currently withdrawn from the balance.
+    pub fn funds_withdrawn(&self) -> bool {
+        matches!(self.status.as_str(), "needs_response" | "under_review" | "lost")
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -33,62 +75,85 @@ pub struct StripePlan {
 }
 
 #[derive(Debug, Deserialize)]
-#[allow(dead_code)]
 struct StripeList<T> {
     data: Vec<T>,
     has_more: bool,
 }
 
-#[allow(dead_code)]
+/// Every item of a list endpoint, `limit` (at most 100) per page. Pages
+/// are chained with `starting_after`, the last item's `id`, until Stripe
+/// reports no more.
+async fn fetch_all<T: DeserializeOwned>(
+    api_key: &str,
+    resource: &str,
+    limit: u32,
+    id: fn(&T) -> &str,
+) -> Result<Vec<T>, AppError> {
+    let client = crate::http::client("stripe")?;
+    let mut all = Vec::new();
+    let mut cursor: Option<String> = None;
+    loop {
+        let mut req = client
+            .get(format!("{}/{}", api_base(BASE_URL), resource))
+            .query(&[("limit", limit.clamp(1, 100))])
+            .basic_auth(api_key, Option::<&str>::None);
+        if let Some(after) = &cursor {
+            req = req.query(&[("starting_after", after)]);
+        }
+        let resp = req.send().await.map_err(|e| AppError::network("stripe", "Stripe request failed", e))?;
+        if !resp.status().is_success() {
+            return Err(AppError::from_response("stripe", "Stripe API", resp).await);
+        }
+
+        let list: StripeList<T> =
+            resp.json().await.map_err(|e| format!("Failed to parse Stripe response: {}", e))?;
+        let last = list.data.last().map(|item| id(item).to_string());
+        all.extend(list.data);
+        match last {
+            Some(last) if list.has_more && cursor.as_deref() != Some(last.as_str()) => cursor = Some(last),
+            _ => break,
+        }
+    }
+    Ok(all)
+}
+
 pub struct StripeService;
 
-#[allow(dead_code)]
 impl StripeService {
-    pub async fn fetch_charges(api_key: &str, limit: u32) -> Result<Vec<StripeCharge>, String> {
-        let client = reqwest::Client::new();
-        let resp = client
-            .get(format!(
-                "https://api.stripe.com/v1/charges?limit={}",
-                limit.min(100)
-            ))
-            .basic_auth(api_key, Option::<&str>::None)
-            .send()
-            .await
-            .map_err(|e| format!("Stripe API error: {}", e))?;
-
-        if !resp.status().is_success() {
-            let status = resp.status();
-            let body = resp.text().await.unwrap_or_default();
-            return Err(format!("Stripe API {} - {}", status, body));
-        }
+    /// All charges, `limit` per request.
+    pub async fn fetch_charges(api_key: &str, limit: u32) -> Result<Vec<StripeCharge>, AppError> {
+        fetch_all(api_key, "charges", limit, |c: &StripeCharge| &c.id).await
+    }
 
-        let list: StripeList<StripeCharge> = resp
-            .json()
-            .await
-            .map_err(|e| format!("Failed to parse Stripe response: {}", e))?;
+    /// All refunds, `limit` per request.
+    pub async fn fetch_refunds(api_key: &str, limit: u32) -> Result<Vec<StripeRefund>, AppError> {
+        fetch_all(api_key, "refunds", limit, |r: &StripeRefund| &r.id).await
+    }
 
-        Ok(list.data)
+    /// All disputes, `limit` per request.
+    pub async fn fetch_disputes(api_key: &str, limit: u32) -> Result<Vec<StripeDispute>, AppError> {
+        fetch_all(api_key, "disputes", limit, |d: &StripeDispute| &d.id).await
     }
 
+    #[allow(dead_code)]
     pub async fn fetch_subscriptions(
         api_key: &str,
         limit: u32,
-    ) -> Result<Vec<StripeSubscription>, String> {
-        let client = reqwest::Client::new();
+    ) -> Result<Vec<StripeSubscription>, AppError> {
+        let client = crate::http::client("stripe")?;
         let resp = client
             .get(format!(
-                "https://api.stripe.com/v1/subscriptions?limit={}&status=active",
+                "{}/subscriptions?limit={}&status=active",
+                api_base(BASE_URL),
                 limit.min(100)
             ))
             .basic_auth(api_key, Option::<&str>::None)
             .send()
             .await
-            .map_err(|e| format!("Stripe API error: {}", e))?;
+            .map_err(|e| AppError::network("stripe", "Stripe request failed", e))?;
 
         if !resp.status().is_success() {
-            let status = resp.status();
-            let body = resp.text().await.unwrap_or_default();
-            return Err(format!("Stripe API {} - {}", status, body));
+            return Err(AppError::from_response("stripe", "Stripe API", resp).await);
         }
 
         let list: StripeList<StripeSubscription> = resp
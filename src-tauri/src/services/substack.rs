@@ -3,7 +3,8 @@ use reqwest::Client;
 use crate::commands::platform::{
     AnalyticsData, ImportedPost, PostPerformance, Publication, PublishRequest, Subscriber,
 };
-use crate::services::PlatformService;
+use crate::error::AppError;
+use crate::services::{api_base, PlatformService};
 
 pub struct SubstackService;
 
@@ -19,14 +20,26 @@ pub struct SubstackService;
 // 3. Session cookie-based auth for writing (captured from browser)
 //
 // The "api_key" field for Substack stores a JSON object:
-// { "subdomain": "mynewsletter", "cookie": "substack.sid=..." }
+// { "subdomain": "mynewsletter", "cookie": "substack.sid=...",
+//   "cookie_expires_at": "2026-01-31T00:00:00Z" }
 
+use chrono::{DateTime, Utc};
 use serde::Deserialize;
 
 #[derive(Deserialize)]
 struct SubstackConfig {
     subdomain: String,
     cookie: Option<String>,
+    /// When the session cookie lapses, if the user copied it down
+    #[serde(default)]
+    cookie_expires_at: Option<String>,
+}
+
+/// Result of probing the stored session cookie.
+pub struct SessionCheck {
+    pub has_cookie: bool,
+    pub valid: bool,
+    pub expires_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Deserialize)]
@@ -50,8 +63,35 @@ fn parse_config(api_key: &str) -> Result<SubstackConfig, String> {
         .map_err(|_| "Invalid Substack config. Expected JSON with 'subdomain' field.".to_string())
 }
 
+/// Expiry from the config, or from an `Expires=` attribute when a whole
+/// Set-Cookie line was pasted in.
+fn cookie_expiry(config: &SubstackConfig) -> Option<DateTime<Utc>> {
+    if let Some(at) = config.cookie_expires_at.as_deref() {
+        return DateTime::parse_from_rfc3339(at)
+            .map(|d| d.with_timezone(&Utc))
+            .ok()
+            .or_else(|| {
+                let day = chrono::NaiveDate::parse_from_str(at.trim(), "%Y-%m-%d").ok()?;
+                Some(day.and_hms_opt(0, 0, 0)?.and_utc())
+            });
+    }
+    let cookie = config.cookie.as_deref()?;
+    let lower = cookie.to_ascii_lowercase();
+    let start = lower.find("expires=")? + "expires=".len();
+    let value = cookie[start..].split(';').next()?.trim();
+    DateTime::parse_from_rfc2822(value).ok().map(|d| d.with_timezone(&Utc))
+}
+
+/// API root for a publication. Every endpoint but the session profile
+/// lives on the publication's own subdomain.
+fn publication_api(config: &SubstackConfig) -> String {
+    api_base(&format!("https://{}.substack.com/api/v1", config.subdomain))
+}
+
+const ACCOUNT_API: &str = "https://substack.com/api/v1";
+
 fn client_with_cookie(cookie: Option<&str>) -> Result<Client, String> {
-    let mut builder = Client::builder();
+    let mut builder = crate::http::builder("substack")?;
     if let Some(c) = cookie {
         let mut h = reqwest::header::HeaderMap::new();
         h.insert(reqwest::header::COOKIE, c.parse().unwrap());
@@ -61,21 +101,18 @@ fn client_with_cookie(cookie: Option<&str>) -> Result<Client, String> {
 }
 
 impl PlatformService for SubstackService {
-    async fn validate_connection(api_key: &str) -> Result<bool, String> {
+    async fn validate_connection(api_key: &str) -> Result<bool, AppError> {
         let config = parse_config(api_key)?;
-        let client = Client::new();
+        let client = crate::http::client("substack")?;
         let resp = client
-            .get(format!(
-                "https://{}.substack.com/api/v1/archive?limit=1",
-                config.subdomain
-            ))
+            .get(format!("{}/archive?limit=1", publication_api(&config)))
             .send()
             .await
-            .map_err(|e| e.to_string())?;
+            .map_err(|e| AppError::network("substack", "Substack request failed", e))?;
         Ok(resp.status().is_success())
     }
 
-    async fn get_publications(api_key: &str) -> Result<Vec<Publication>, String> {
+    async fn get_publications(api_key: &str) -> Result<Vec<Publication>, AppError> {
         let config = parse_config(api_key)?;
 
         // Substack public profile endpoint
@@ -96,7 +133,7 @@ impl PlatformService for SubstackService {
     async fn get_subscribers(
         api_key: &str,
         _publication_id: Option<&str>,
-    ) -> Result<Vec<Subscriber>, String> {
+    ) -> Result<Vec<Subscriber>, AppError> {
         let config = parse_config(api_key)?;
         let cookie = config.cookie.as_deref();
         let c = client_with_cookie(cookie)?;
@@ -105,24 +142,22 @@ impl PlatformService for SubstackService {
         if cookie.is_none() {
             return Err(
                 "Substack subscriber data requires authentication. Please add your session cookie."
-                    .to_string(),
+                    .into(),
             );
         }
 
         let resp = c
-            .get(format!(
-                "https://{}.substack.com/api/v1/subscriber_count",
-                config.subdomain
-            ))
+            .get(format!("{}/subscriber_count", publication_api(&config)))
             .send()
             .await
-            .map_err(|e| e.to_string())?;
+            .map_err(|e| AppError::network("substack", "Substack request failed", e))?;
 
         if !resp.status().is_success() {
-            return Err(format!(
-                "Substack API error: {}. The unofficial API may have changed.",
-                resp.status()
-            ));
+            let err = AppError::from_response("substack", "Substack API error", resp).await;
+            return Err(AppError {
+                message: format!("{}. The unofficial API may have changed.", err.message),
+                ..err
+            });
         }
 
         // Substack doesn't expose individual subscriber emails through any known API
@@ -133,19 +168,16 @@ impl PlatformService for SubstackService {
     async fn get_analytics(
         api_key: &str,
         _publication_id: Option<&str>,
-    ) -> Result<AnalyticsData, String> {
+    ) -> Result<AnalyticsData, AppError> {
         let config = parse_config(api_key)?;
-        let c = Client::new();
+        let c = crate::http::client("substack")?;
 
         // Fetch recent posts from public archive
         let resp = c
-            .get(format!(
-                "https://{}.substack.com/api/v1/archive?sort=new&limit=50",
-                config.subdomain
-            ))
+            .get(format!("{}/archive?sort=new&limit=50", publication_api(&config)))
             .send()
             .await
-            .map_err(|e| e.to_string())?;
+            .map_err(|e| AppError::network("substack", "Substack request failed", e))?;
 
         let mut recent_posts = Vec::new();
 
@@ -176,10 +208,7 @@ impl PlatformService for SubstackService {
         let total_subscribers = if config.cookie.is_some() {
             let c2 = client_with_cookie(config.cookie.as_deref())?;
             let resp = c2
-                .get(format!(
-                    "https://{}.substack.com/api/v1/subscriber_count",
-                    config.subdomain
-                ))
+                .get(format!("{}/subscriber_count", publication_api(&config)))
                 .send()
                 .await
                 .ok();
@@ -211,7 +240,7 @@ impl PlatformService for SubstackService {
         api_key: &str,
         _publication_id: &str,
         request: PublishRequest,
-    ) -> Result<String, String> {
+    ) -> Result<String, AppError> {
         let config = parse_config(api_key)?;
         let cookie = config
             .cookie
@@ -235,21 +264,18 @@ impl PlatformService for SubstackService {
         });
 
         let resp = c
-            .post(format!(
-                "https://{}.substack.com/api/v1/drafts",
-                config.subdomain
-            ))
+            .post(format!("{}/drafts", publication_api(&config)))
             .json(&body)
             .send()
             .await
-            .map_err(|e| e.to_string())?;
+            .map_err(|e| AppError::network("substack", "Substack request failed", e))?;
 
         if !resp.status().is_success() {
-            let err_text = resp.text().await.unwrap_or_else(|_| "Unknown error".to_string());
-            return Err(format!(
-                "Substack publish error: {}. The unofficial API may have changed.",
-                err_text
-            ));
+            let err = AppError::from_response("substack", "Substack publish error", resp).await;
+            return Err(AppError {
+                message: format!("{}. The unofficial API may have changed.", err.message),
+                ..err
+            });
         }
 
         let result: serde_json::Value = resp.json().await.map_err(|e| e.to_string())?;
@@ -266,21 +292,18 @@ impl PlatformService for SubstackService {
 // ─── Import (standalone, not on trait) ──────────────────────────
 
 impl SubstackService {
-    pub async fn import_posts(api_key: &str) -> Result<Vec<ImportedPost>, String> {
+    pub async fn import_posts(api_key: &str) -> Result<Vec<ImportedPost>, AppError> {
         let config = parse_config(api_key)?;
-        let c = Client::new();
+        let c = crate::http::client("substack")?;
 
         let resp = c
-            .get(format!(
-                "https://{}.substack.com/api/v1/archive?sort=new&limit=50",
-                config.subdomain
-            ))
+            .get(format!("{}/archive?sort=new&limit=50", publication_api(&config)))
             .send()
             .await
-            .map_err(|e| e.to_string())?;
+            .map_err(|e| AppError::network("substack", "Substack request failed", e))?;
 
         if !resp.status().is_success() {
-            return Err(format!("Substack import error: {}", resp.status()));
+            return Err(AppError::from_response("substack", "Substack import error", resp).await);
         }
 
         let posts: Vec<SubstackPost> = resp.json().await.unwrap_or_default();
@@ -298,3 +321,33 @@ impl SubstackService {
             .collect())
     }
 }
+
+// ─── Session health ─────────────────────────────────────────────
+
+impl SubstackService {
+    /// Check whether the session cookie is still accepted. Substack
+    /// answers 401/403 on the profile endpoint once a session has died.
+    pub async fn check_session(api_key: &str) -> Result<SessionCheck, AppError> {
+        let config = parse_config(api_key)?;
+        let expires_at = cookie_expiry(&config);
+        let Some(cookie) = config.cookie.as_deref() else {
+            return Ok(SessionCheck { has_cookie: false, valid: false, expires_at });
+        };
+
+        let c = client_with_cookie(Some(cookie))?;
+        let resp = c
+            .get(format!("{}/user/profile/self", api_base(ACCOUNT_API)))
+            .send()
+            .await
+            .map_err(|e| AppError::network("substack", "Substack request failed", e))?;
+
+        let status = resp.status();
+        if status.is_success() {
+            return Ok(SessionCheck { has_cookie: true, valid: true, expires_at });
+        }
+        if status == reqwest::StatusCode::UNAUTHORIZED || status == reqwest::StatusCode::FORBIDDEN {
+            return Ok(SessionCheck { has_cookie: true, valid: false, expires_at });
+        }
+        Err(AppError::from_response("substack", "Substack session check failed", resp).await)
+    }
+}
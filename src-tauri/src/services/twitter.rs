@@ -2,6 +2,10 @@ use hmac::{Hmac, Mac};
 use sha1::Sha1;
 use serde::Deserialize;
 
+use crate::commands::testimonials::SocialMention;
+use crate::error::AppError;
+use crate::services::api_base;
+
 type HmacSha1 = Hmac<Sha1>;
 
 // ─── Twitter credential format ─────────────────────────────────
@@ -29,7 +33,6 @@ struct TweetData {
 }
 
 #[derive(Deserialize)]
-#[allow(dead_code)]
 struct TwitterUser {
     data: TwitterUserData,
 }
@@ -41,6 +44,49 @@ struct TwitterUserData {
     name: String,
 }
 
+#[derive(Deserialize)]
+struct MentionsResponse {
+    #[serde(default)]
+    data: Vec<Mention>,
+    #[serde(default)]
+    includes: MentionIncludes,
+}
+
+#[derive(Deserialize)]
+struct Mention {
+    id: String,
+    text: String,
+    author_id: Option<String>,
+    created_at: Option<String>,
+    entities: Option<MentionEntities>,
+}
+
+#[derive(Deserialize)]
+struct MentionEntities {
+    #[serde(default)]
+    urls: Vec<MentionUrl>,
+}
+
+#[derive(Deserialize)]
+struct MentionUrl {
+    expanded_url: Option<String>,
+}
+
+#[derive(Deserialize, Default)]
+struct MentionIncludes {
+    #[serde(default)]
+    users: Vec<MentionAuthor>,
+}
+
+#[derive(Deserialize)]
+struct MentionAuthor {
+    id: String,
+    name: String,
+    username: String,
+}
+
+const API_ROOT: &str = "https://api.twitter.com";
+
 // ─── OAuth 1.0a signing ────────────────────────────────────────
 
 fn percent_encode(s: &str) -> String {
@@ -147,25 +193,25 @@ fn build_auth_header(
 }
 
 impl TwitterService {
-    pub async fn validate(api_key: &str) -> Result<bool, String> {
+    pub async fn validate(api_key: &str) -> Result<bool, AppError> {
         let config: TwitterConfig =
             serde_json::from_str(api_key).map_err(|e| format!("Invalid Twitter config: {}", e))?;
 
         let url = "https://api.twitter.com/2/users/me";
         let auth = build_auth_header("GET", url, &[], &config);
 
-        let client = reqwest::Client::new();
+        let client = crate::http::client("twitter")?;
         let resp = client
             .get(url)
             .header("Authorization", auth)
             .send()
             .await
-            .map_err(|e| e.to_string())?;
+            .map_err(|e| AppError::network("twitter", "Twitter request failed", e))?;
 
         Ok(resp.status().is_success())
     }
 
-    pub async fn post_tweet(api_key: &str, content: &str) -> Result<String, String> {
+    pub async fn post_tweet(api_key: &str, content: &str) -> Result<String, AppError> {
         let config: TwitterConfig =
             serde_json::from_str(api_key).map_err(|e| format!("Invalid Twitter config: {}", e))?;
 
@@ -175,7 +221,7 @@ impl TwitterService {
         // For JSON body requests, don't include body params in OAuth signature
         let auth = build_auth_header("POST", url, &[], &config);
 
-        let client = reqwest::Client::new();
+        let client = crate::http::client("twitter")?;
         let resp = client
             .post(url)
             .header("Authorization", auth)
@@ -183,26 +229,25 @@ impl TwitterService {
             .json(&body)
             .send()
             .await
-            .map_err(|e| format!("Twitter post failed: {}", e))?;
+            .map_err(|e| AppError::network("twitter", "Twitter request failed", e))?;
 
         if !resp.status().is_success() {
-            let err = resp.text().await.unwrap_or_default();
-            return Err(format!("Twitter API error: {}", err));
+            return Err(AppError::from_response("twitter", "Twitter API error", resp).await);
         }
 
         let result: TweetResponse = resp.json().await.map_err(|e| e.to_string())?;
         Ok(result.data.id)
     }
 
-    pub async fn post_thread(api_key: &str, tweets: Vec<String>) -> Result<Vec<String>, String> {
+    pub async fn post_thread(api_key: &str, tweets: Vec<String>) -> Result<Vec<String>, AppError> {
         if tweets.is_empty() {
-            return Err("Thread must have at least one tweet".to_string());
+            return Err(AppError::validation("Thread must have at least one tweet"));
         }
 
         let config: TwitterConfig =
             serde_json::from_str(api_key).map_err(|e| format!("Invalid Twitter config: {}", e))?;
 
-        let client = reqwest::Client::new();
+        let client = crate::http::client("twitter")?;
         let url = "https://api.twitter.com/2/tweets";
         let mut tweet_ids = Vec::new();
 
@@ -227,11 +272,11 @@ impl TwitterService {
                 .json(&body)
                 .send()
                 .await
-                .map_err(|e| format!("Twitter thread post {} failed: {}", i + 1, e))?;
+                .map_err(|e| AppError::network("twitter", "Twitter request failed", e))?;
 
             if !resp.status().is_success() {
-                let err = resp.text().await.unwrap_or_default();
-                return Err(format!("Twitter thread error on tweet {}: {}", i + 1, err));
+                let context = format!("Twitter thread error on tweet {}", i + 1);
+                return Err(AppError::from_response("twitter", &context, resp).await);
             }
 
             let result: TweetResponse = resp.json().await.map_err(|e| e.to_string())?;
@@ -241,3 +286,126 @@ impl TwitterService {
         Ok(tweet_ids)
     }
 }
+
+impl TwitterService {
+    async fn user_id(client: &reqwest::Client, config: &TwitterConfig) -> Result<String, AppError> {
+        let url = format!("{}/2/users/me", api_base(API_ROOT));
+        let resp = client
+            .get(&url)
+            .header("Authorization", build_auth_header("GET", &url, &[], config))
+            .send()
+            .await
+            .map_err(|e| AppError::network("twitter", "Twitter request failed", e))?;
+        if !resp.status().is_success() {
+            return Err(AppError::from_response("twitter", "Twitter API error", resp).await);
+        }
+        let user: TwitterUser = resp.json().await.map_err(|e| e.to_string())?;
+        Ok(user.data.id)
+    }
+
+    /// Tweets mentioning the account, newest first; only those after
+    /// `since_id` when given.
+    pub async fn mentions(api_key: &str, since_id: Option<&str>) -> Result<Vec<SocialMention>, AppError> {
+        let config: TwitterConfig =
+            serde_json::from_str(api_key).map_err(|e| format!("Invalid Twitter config: {}", e))?;
+        let client = crate::http::client("twitter")?;
+        let user_id = Self::user_id(&client, &config).await?;
+
+        let url = format!("{}/2/users/{}/mentions", api_base(API_ROOT), user_id);
+        let mut params: Vec<(String, String)> = vec![
+            ("max_results".to_string(), "100".to_string()),
+            ("tweet.fields".to_string(), "created_at,author_id,entities".to_string()),
+            ("expansions".to_string(), "author_id".to_string()),
+            ("user.fields".to_string(), "username,name".to_string()),
+        ];
+        if let Some(since_id) = since_id {
+            params.push(("since_id".to_string(), since_id.to_string()));
+        }
+        // Query parameters are part of the OAuth signature
+        let auth = build_auth_header("GET", &url, &params, &config);
+        let resp = client
+            .get(&url)
+            .header("Authorization", auth)
+            .query(&params)
+            .send()
+            .await
+            .map_err(|e| AppError::network("twitter", "Twitter request failed", e))?;
+        if !resp.status().is_success() {
+            return Err(AppError::from_response("twitter", "Twitter API error", resp).await);
+        }
+
+        let result: MentionsResponse = resp.json().await.map_err(|e| e.to_string())?;
+        let authors = result.includes.users;
+        Ok(result
+            .data
+            .into_iter()
+            .map(|tweet| {
+                let author = authors.iter().find(|u| Some(&u.id) == tweet.author_id.as_ref());
+                let handle = author.map(|a| a.username.clone()).unwrap_or_default();
+                SocialMention {
+                    url: (!handle.is_empty()).then(|| format!("https://x.com/{}/status/{}", handle, tweet.id)),
+                    author_name: author.map(|a| a.name.clone()).unwrap_or_else(|| handle.clone()),
+                    author_handle: format!("@{}", handle),
+                    links: tweet
+                        .entities
+                        .map(|e| e.urls.into_iter().filter_map(|u| u.expanded_url).collect())
+                        .unwrap_or_default(),
+                    created_at: tweet.created_at.unwrap_or_default(),
+                    text: tweet.text,
+                    id: tweet.id,
+                }
+            })
+            .collect())
+    }
+}
+
+const TWEET_LIMIT: usize = 280;
+
+/// Split plain text into tweet-sized chunks, breaking on paragraphs first
+/// and then on word boundaries. Chunks are numbered "1/n" when there is
+/// more than one.
+pub fn split_thread(text: &str) -> Vec<String> {
+    // Leave room for a " 10/12" style suffix
+    let budget = TWEET_LIMIT - 8;
+    let mut chunks: Vec<String> = Vec::new();
+    for para in text.split("\n\n").map(str::trim).filter(|p| !p.is_empty()) {
+        let mut current = String::new();
+        for word in para.split_whitespace() {
+            let needed = current.chars().count() + 1 + word.chars().count();
+            if needed > budget && !current.is_empty() {
+                chunks.push(std::mem::take(&mut current));
+            }
+            if !current.is_empty() {
+                current.push(' ');
+            }
+            // A single word longer than a tweet gets hard-cut
+            let w: String = word.chars().take(budget).collect();
+            current.push_str(&w);
+        }
+        if !current.is_empty() {
+            chunks.push(current);
+        }
+    }
+
+    let total = chunks.len();
+    if total > 1 {
+        for (i, chunk) in chunks.iter_mut().enumerate() {
+            chunk.push_str(&format!(" {}/{}", i + 1, total));
+        }
+    }
+    chunks
+}
+
+/// First tweet-sized slice of the text, ellipsised if it had to be cut.
+pub fn truncate_tweet(text: &str) -> String {
+    let flat = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if flat.chars().count() <= TWEET_LIMIT {
+        return flat;
+    }
+    let mut cut: String = flat.chars().take(TWEET_LIMIT - 1).collect();
+    if let Some(idx) = cut.rfind(' ') {
+        cut.truncate(idx);
+    }
+    cut.push('\u{2026}');
+    cut
+}
@@ -0,0 +1,144 @@
+use serde::Deserialize;
+
+use crate::commands::export::decode_html_entities;
+use crate::commands::platform::ImportedPost;
+use crate::error::AppError;
+
+// ─── WordPress pages ────────────────────────────────────────────
+//
+// Only what the link page and published-copy comparison need: create or
+// update one page, and read back posts, through the REST API,
+// authenticated with an application password. The credential is stored
+// like Ghost's, as JSON in the account's api_key.
+
+pub struct WordPressService;
+
+#[derive(Deserialize)]
+struct WordPressConfig {
+    site_url: String,
+    username: String,
+    app_password: String,
+}
+
+#[derive(Deserialize)]
+struct WordPressPage {
+    id: u64,
+    link: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct Rendered {
+    rendered: String,
+}
+
+#[derive(Deserialize)]
+struct WordPressPost {
+    id: u64,
+    link: Option<String>,
+    date_gmt: Option<String>,
+    title: Rendered,
+    content: Rendered,
+}
+
+impl WordPressPost {
+    fn into_imported(self) -> ImportedPost {
+        ImportedPost {
+            id: self.id.to_string(),
+            title: decode_html_entities(&self.title.rendered),
+            html_content: self.content.rendered,
+            published_at: self.date_gmt.map(|d| format!("{}Z", d)),
+            url: self.link,
+            platform: "wordpress".to_string(),
+        }
+    }
+}
+
+fn parse_config(api_key: &str) -> Result<WordPressConfig, String> {
+    serde_json::from_str(api_key).map_err(|_| {
+        "Invalid WordPress config. Expected JSON with 'site_url', 'username' and 'app_password'.".to_string()
+    })
+}
+
+impl WordPressService {
+    /// Publish `html` as a page, updating `page_id` when given and still
+    /// present. Returns the page id and its public URL.
+    pub async fn upsert_page(
+        api_key: &str,
+        page_id: Option<&str>,
+        title: &str,
+        html: &str,
+    ) -> Result<(String, Option<String>), AppError> {
+        let config = parse_config(api_key)?;
+        let c = crate::http::client("wordpress")?;
+        let pages = format!("{}/wp-json/wp/v2/pages", config.site_url.trim_end_matches('/'));
+        let body = serde_json::json!({ "title": title, "content": html, "status": "publish" });
+
+        let send = |url: String| {
+            c.post(url)
+                .basic_auth(&config.username, Some(&config.app_password))
+                .json(&body)
+                .send()
+        };
+        let url = page_id.map(|id| format!("{}/{}", pages, id)).unwrap_or_else(|| pages.clone());
+        let mut resp = send(url)
+            .await
+            .map_err(|e| AppError::network("wordpress", "WordPress request failed", e))?;
+        // The page was deleted on the site; start over with a new one
+        if page_id.is_some() && resp.status() == reqwest::StatusCode::NOT_FOUND {
+            resp = send(pages)
+                .await
+                .map_err(|e| AppError::network("wordpress", "WordPress request failed", e))?;
+        }
+        if !resp.status().is_success() {
+            return Err(AppError::from_response("wordpress", "WordPress page publish error", resp).await);
+        }
+
+        let page: WordPressPage = resp.json().await.map_err(|e| e.to_string())?;
+        Ok((page.id.to_string(), page.link))
+    }
+}
+
+// ─── Posts ──────────────────────────────────────────────────────
+
+impl WordPressService {
+    /// One post with its rendered content, or None if it no longer exists.
+    pub async fn get_post(api_key: &str, post_id: &str) -> Result<Option<ImportedPost>, AppError> {
+        let config = parse_config(api_key)?;
+        let c = crate::http::client("wordpress")?;
+        let resp = c
+            .get(format!("{}/wp-json/wp/v2/posts/{}", config.site_url.trim_end_matches('/'), post_id))
+            .basic_auth(&config.username, Some(&config.app_password))
+            .send()
+            .await
+            .map_err(|e| AppError::network("wordpress", "WordPress request failed", e))?;
+        if resp.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if !resp.status().is_success() {
+            return Err(AppError::from_response("wordpress", "WordPress post lookup error", resp).await);
+        }
+        let post: WordPressPost = resp.json().await.map_err(|e| e.to_string())?;
+        Ok(Some(post.into_imported()))
+    }
+
+    /// The most recent published post titled exactly `title`, ignoring case.
+    pub async fn find_post_by_title(api_key: &str, title: &str) -> Result<Option<ImportedPost>, AppError> {
+        let config = parse_config(api_key)?;
+        let c = crate::http::client("wordpress")?;
+        let resp = c
+            .get(format!("{}/wp-json/wp/v2/posts", config.site_url.trim_end_matches('/')))
+            .basic_auth(&config.username, Some(&config.app_password))
+            .query(&[("search", title), ("per_page", "20"), ("orderby", "date")])
+            .send()
+            .await
+            .map_err(|e| AppError::network("wordpress", "WordPress request failed", e))?;
+        if !resp.status().is_success() {
+            return Err(AppError::from_response("wordpress", "WordPress post search error", resp).await);
+        }
+        let posts: Vec<WordPressPost> = resp.json().await.map_err(|e| e.to_string())?;
+        Ok(posts
+            .into_iter()
+            .map(WordPressPost::into_imported)
+            .find(|p| p.title.trim().eq_ignore_ascii_case(title.trim())))
+    }
+}
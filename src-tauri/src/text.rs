@@ -0,0 +1,103 @@
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::commands::export::html_to_plain_text;
+use crate::commands::settings;
+
+// ─── Word counting ──────────────────────────────────────────────
+//
+// Every stored word count (saves, versions, imports, heatmaps) goes
+// through here. Words follow Unicode word boundaries (UAX #29), so emoji
+// and punctuation don't count and HTML entities are decoded first.
+// Chinese and Japanese aren't written with spaces, so in `auto` mode each
+// CJK character counts as one unit, the way word processors count them.
+
+pub const COUNT_MODE_SETTING: &str = "editor.word_count_mode";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CountMode {
+    /// Words for space-separated scripts, characters for CJK
+    #[default]
+    Auto,
+    /// Unicode words only, whatever the script
+    Words,
+    /// Visible characters (grapheme clusters), ignoring whitespace
+    Characters,
+}
+
+pub fn count_mode(app: &AppHandle) -> CountMode {
+    settings::get_setting(app, COUNT_MODE_SETTING).unwrap_or_default()
+}
+
+/// Han ideographs and kana, counted one per character. Hangul is written
+/// with spaces, so it counts as words.
+fn is_cjk(c: char) -> bool {
+    matches!(c as u32,
+        0x3040..=0x30FF      // Hiragana, Katakana
+        | 0x31F0..=0x31FF    // Katakana extensions
+        | 0x3400..=0x4DBF    // CJK Extension A
+        | 0x4E00..=0x9FFF    // CJK Unified Ideographs
+        | 0xF900..=0xFAFF    // CJK Compatibility Ideographs
+        | 0xFF66..=0xFF9F    // Half-width Katakana
+        | 0x20000..=0x3FFFF  // Extensions B onwards
+    )
+}
+
+/// Count plain text in the given mode.
+pub fn count_text(text: &str, mode: CountMode) -> u64 {
+    match mode {
+        CountMode::Characters => text.graphemes(true).filter(|g| !g.trim().is_empty()).count() as u64,
+        CountMode::Words => text.unicode_words().count() as u64,
+        CountMode::Auto => text
+            .unicode_words()
+            .map(|word| {
+                let cjk = word.chars().filter(|c| is_cjk(*c)).count() as u64;
+                let rest = word.chars().any(|c| !is_cjk(c) && c.is_alphanumeric());
+                cjk + rest as u64
+            })
+            .sum(),
+    }
+}
+
+/// Count an HTML document's text in the given mode.
+pub fn count_html(html: &str, mode: CountMode) -> u64 {
+    count_text(&html_to_plain_text(html), mode)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn auto_counts_each_cjk_character() {
+        assert_eq!(count_text("我爱你", CountMode::Auto), 3);
+        // Katakana runs are one Unicode word but several characters
+        assert_eq!(count_text("カタカナ", CountMode::Auto), 4);
+        assert_eq!(count_text("カタカナ", CountMode::Words), 1);
+        assert_eq!(count_text("I love 東京", CountMode::Auto), 4);
+    }
+
+    #[test]
+    fn hangul_counts_as_words() {
+        assert_eq!(count_text("안녕하세요 세계", CountMode::Auto), 2);
+    }
+
+    #[test]
+    fn emoji_and_punctuation_are_not_words() {
+        assert_eq!(count_text("Great job! 🎉🎉 — really.", CountMode::Auto), 3);
+        assert_eq!(count_text("🎉 👍", CountMode::Words), 0);
+    }
+
+    #[test]
+    fn characters_count_graphemes_not_code_points() {
+        // A ZWJ family sequence and a flag are one visible character each
+        assert_eq!(count_text("👨‍👩‍👧 🇯🇵 ok", CountMode::Characters), 4);
+    }
+
+    #[test]
+    fn html_entities_are_decoded_before_counting() {
+        assert_eq!(count_html("<p>Fish &amp; chips</p><p>to&nbsp;go</p>", CountMode::Words), 4);
+    }
+}
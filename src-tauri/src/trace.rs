@@ -0,0 +1,45 @@
+use std::future::Future;
+
+// ─── Request tracing ────────────────────────────────────────────
+//
+// A trace id follows one unit of work (a publish command, one scheduled post
+// going out, a backfill job) through the scheduler, services and HTTP calls.
+// It lives in a tokio task-local so services don't need an extra parameter;
+// log lines, activity_log rows, publish_attempts rows, AppErrors and emitted
+// events pick it up from `current()`.
+
+tokio::task_local! {
+    static TRACE_ID: String;
+}
+
+/// Short random id, e.g. "3f9c0a1b72de".
+pub fn new_id() -> String {
+    uuid::Uuid::new_v4().simple().to_string()[..12].to_string()
+}
+
+/// The trace id of the running task, if it is inside a traced scope.
+pub fn current() -> Option<String> {
+    TRACE_ID.try_with(|id| id.clone()).ok()
+}
+
+/// Run `f` under `id`.
+pub async fn scope<F: Future>(id: String, f: F) -> F::Output {
+    TRACE_ID.scope(id, f).await
+}
+
+/// Run `f` under the current trace id, or a fresh one when there is none,
+/// so nested calls (e.g. one target of a multi-publish) share their
+/// caller's id.
+pub async fn traced<F: Future>(f: F) -> F::Output {
+    let id = current().unwrap_or_else(new_id);
+    scope(id, f).await
+}
+
+/// Log a line tagged with the current trace id:
+/// "[Scheduler] [trace 3f9c0a1b72de] Publishing ...".
+pub fn log(target: &str, message: &str) {
+    match current() {
+        Some(id) => eprintln!("[{}] [trace {}] {}", target, id, message),
+        None => eprintln!("[{}] {}", target, message),
+    }
+}
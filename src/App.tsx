@@ -1,4 +1,5 @@
 import { useEffect, useState, lazy, Suspense } from "react";
+import { listen } from "@tauri-apps/api/event";
 import { AppShell } from "@/components/layout/AppShell";
 import { useThemeStore } from "@/stores/theme-store";
 import { useCommandStore } from "@/stores/command-store";
@@ -10,6 +11,7 @@ import { useCaptureStore } from "@/stores/capture-store";
 import { ErrorBoundary } from "@/components/shared/ErrorBoundary";
 import { ShortcutHelp } from "@/components/shared/ShortcutHelp";
 import { toast } from "@/stores/toast-store";
+import { documentConflict, errorMessage } from "@/lib/error-handler";
 import { Loader2 } from "lucide-react";
 
 const LandingPage = lazy(() => import("@/components/onboarding/LandingPage"));
@@ -301,12 +303,14 @@ export default function App() {
           store.setSaveStatus("saving");
           try {
             const { invoke } = await import("@tauri-apps/api/core");
-            await invoke("save_document", {
+            const version = await invoke<number>("save_document", {
               id: doc.id,
               title: doc.title || "Untitled",
               content: JSON.stringify(doc.content),
               htmlContent: doc.htmlContent,
+              expectedVersion: doc.version ?? null,
             });
+            store.setDocumentVersion(version);
             store.markClean();
             store.setSaveStatus("saved");
             store.setLastSavedAt(new Date().toISOString());
@@ -317,6 +321,14 @@ export default function App() {
               }
             }, 2000);
           } catch (e) {
+            const conflict = documentConflict(e);
+            if (conflict) {
+              store.setDocumentVersion(conflict.current_version);
+              store.markClean();
+              store.setSaveStatus("idle");
+              toast.warning(errorMessage(e), 8000);
+              return;
+            }
             store.setSaveStatus("error");
             toast.error("Save failed");
           }
@@ -325,6 +337,16 @@ export default function App() {
     ]);
   }, [registerCommands, setActiveWorkspace]);
 
+  // Celebrate subscriber / revenue milestones as they're crossed
+  useEffect(() => {
+    const unlisten = listen<{ title: string }>("milestone:reached", (e) => {
+      toast.success(`Milestone reached: ${e.payload.title}`, 8000);
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
   // Global keyboard shortcuts
   useEffect(() => {
     const handleKeyDown = (e: KeyboardEvent) => {
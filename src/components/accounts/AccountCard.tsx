@@ -15,7 +15,8 @@ interface Props {
 }
 
 export function AccountCard({ account }: Props) {
-  const { removeAccount, testConnection, refreshPublications } = useAccountsStore();
+  const { removeAccount, testConnection, refreshPublications, loadCapabilities } =
+    useAccountsStore();
   const platform = getPlatform(account.platform);
   const [isTesting, setIsTesting] = useState(false);
   const [isRemoving, setIsRemoving] = useState(false);
@@ -24,6 +25,7 @@ export function AccountCard({ account }: Props) {
     setIsTesting(true);
     await testConnection(account.platform, account.accountId);
     await refreshPublications(account.platform, account.accountId);
+    await loadCapabilities(account.platform, account.accountId);
     setIsTesting(false);
   };
 
@@ -69,8 +71,8 @@ export function AccountCard({ account }: Props) {
                   className="flex items-center gap-2 text-xs text-muted-foreground"
                 >
                   <span className="font-medium text-foreground">{pub.name}</span>
-                  {pub.subscriberCount !== null && (
-                    <span>· {pub.subscriberCount.toLocaleString()} subscribers</span>
+                  {pub.subscriber_count != null && (
+                    <span>· {pub.subscriber_count.toLocaleString()} subscribers</span>
                   )}
                   {pub.url && (
                     <a
@@ -87,6 +89,18 @@ export function AccountCard({ account }: Props) {
             </div>
           )}
 
+          {/* Credential capabilities */}
+          {account.capabilities?.read_only && (
+            <p className="text-[11px] text-yellow-500/80 mt-2">
+              Read-only connection: publishing and subscriber sync are unavailable.
+            </p>
+          )}
+          {account.capabilities?.notes.map((note) => (
+            <p key={note} className="text-[11px] text-muted-foreground mt-1">
+              {note}
+            </p>
+          ))}
+
           {/* Warning for Substack */}
           {account.platform === "substack" && (
             <p className="text-[11px] text-yellow-500/80 mt-2">
@@ -2,6 +2,7 @@ import { useState } from "react";
 import { useAccountsStore } from "@/stores/accounts-store";
 import { PLATFORMS, type PlatformId } from "@/lib/platforms";
 import { X, Loader2, ExternalLink } from "lucide-react";
+import { errorMessage } from "@/lib/error-handler";
 
 interface Props {
   onClose: () => void;
@@ -24,7 +25,7 @@ export function AddAccountDialog({ onClose }: Props) {
       await addAccount(selectedPlatform, fields);
       onClose();
     } catch (e) {
-      setError(String(e));
+      setError(errorMessage(e));
     } finally {
       setIsSubmitting(false);
     }
@@ -12,6 +12,7 @@ import {
   Zap,
   Key,
 } from "lucide-react";
+import { errorMessage } from "@/lib/error-handler";
 
 export function AiProviderSettings() {
   const {
@@ -79,7 +80,7 @@ export function AiProviderSettings() {
       setExpandedId(null);
       setFields({});
     } catch (e) {
-      setError(String(e));
+      setError(errorMessage(e));
     } finally {
       setSaving(false);
     }
@@ -89,7 +90,7 @@ export function AiProviderSettings() {
     try {
       await deleteProvider(providerId);
     } catch (e) {
-      setError(String(e));
+      setError(errorMessage(e));
     }
   };
 
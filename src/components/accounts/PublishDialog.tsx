@@ -325,6 +325,7 @@ export function PublishDialog({ onClose }: Props) {
                       {target.publishStatus === "publishing" && <Loader2 className="w-4 h-4 animate-spin text-primary" />}
                       {target.publishStatus === "success" && <CheckCircle2 className="w-4 h-4 text-green-500" />}
                       {target.publishStatus === "error" && <AlertCircle className="w-4 h-4 text-destructive" />}
+                      {target.publishStatus === "queued" && <Clock className="w-4 h-4 text-amber-500" />}
                       {target.publishStatus === "idle" && <div className="w-4 h-4 rounded-full border-2 border-muted-foreground/20" />}
                     </div>
                   );
@@ -340,7 +341,11 @@ export function PublishDialog({ onClose }: Props) {
                   return (
                     <div key={target.accountId} className={cn(
                       "p-3 rounded-lg border",
-                      target.publishStatus === "success" ? "border-green-500/30 bg-green-500/5" : "border-destructive/30 bg-destructive/5"
+                      target.publishStatus === "success"
+                        ? "border-green-500/30 bg-green-500/5"
+                        : target.publishStatus === "queued"
+                          ? "border-amber-500/30 bg-amber-500/5"
+                          : "border-destructive/30 bg-destructive/5"
                     )}>
                       <div className="flex items-center gap-2">
                         <div
@@ -352,6 +357,8 @@ export function PublishDialog({ onClose }: Props) {
                         <span className="text-[12px] font-medium text-foreground flex-1">{target.accountName}</span>
                         {target.publishStatus === "success" ? (
                           <CheckCircle2 className="w-4 h-4 text-green-500" />
+                        ) : target.publishStatus === "queued" ? (
+                          <Clock className="w-4 h-4 text-amber-500" />
                         ) : (
                           <AlertCircle className="w-4 h-4 text-destructive" />
                         )}
@@ -359,7 +366,11 @@ export function PublishDialog({ onClose }: Props) {
                       {target.resultMessage && (
                         <p className={cn(
                           "text-[10px] mt-1 ml-7",
-                          target.publishStatus === "success" ? "text-green-600/70" : "text-destructive/70"
+                          target.publishStatus === "success"
+                            ? "text-green-600/70"
+                            : target.publishStatus === "queued"
+                              ? "text-amber-600/70"
+                              : "text-destructive/70"
                         )}>
                           {target.resultMessage}
                         </p>
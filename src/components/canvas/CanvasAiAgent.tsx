@@ -26,6 +26,7 @@ import {
   Minimize2,
   Maximize2,
 } from "lucide-react";
+import { errorMessage } from "@/lib/error-handler";
 
 /* ─── Canvas-specific quick actions ─── */
 const CANVAS_ACTIONS: {
@@ -181,8 +182,8 @@ export function CanvasAiAgent({ isOpen, onClose }: CanvasAiAgentProps) {
       );
       addChatMessage({ role: "assistant", content: response.content });
     } catch (e) {
-      setError(String(e));
-      addChatMessage({ role: "assistant", content: `Error: ${String(e)}` });
+      setError(errorMessage(e));
+      addChatMessage({ role: "assistant", content: `Error: ${errorMessage(e)}` });
     }
   };
 
@@ -220,8 +221,8 @@ Be direct, concise, and visually creative. Reference specific elements by name w
       const response = await chat(messages, systemPrompt);
       addChatMessage({ role: "assistant", content: response.content });
     } catch (e) {
-      setError(String(e));
-      addChatMessage({ role: "assistant", content: `Error: ${String(e)}` });
+      setError(errorMessage(e));
+      addChatMessage({ role: "assistant", content: `Error: ${errorMessage(e)}` });
     }
   };
 
@@ -38,6 +38,7 @@ import { FacebookPreview } from "@/components/social-preview/FacebookPreview";
 import { EmailPreview } from "@/components/social-preview/EmailPreview";
 import { YouTubePreview } from "@/components/social-preview/YouTubePreview";
 import { TikTokPreview } from "@/components/social-preview/TikTokPreview";
+import { errorMessage } from "@/lib/error-handler";
 
 /* ─── Channel definitions ─── */
 const CHANNELS: {
@@ -153,7 +154,7 @@ export default function DistributeWorkspace() {
     try {
       await generateVariant(channel);
     } catch (e) {
-      setError(String(e));
+      setError(errorMessage(e));
     }
   };
 
@@ -162,7 +163,7 @@ export default function DistributeWorkspace() {
     try {
       await generateAll();
     } catch (e) {
-      setError(String(e));
+      setError(errorMessage(e));
     }
   };
 
@@ -178,7 +179,7 @@ export default function DistributeWorkspace() {
       const ids = await postToTwitter();
       toast.success(`Thread posted! ${ids.length} tweet${ids.length !== 1 ? "s" : ""}`);
     } catch (e) {
-      setError(String(e));
+      setError(errorMessage(e));
     }
   };
 
@@ -188,7 +189,7 @@ export default function DistributeWorkspace() {
       await postToLinkedin();
       toast.success("Posted to LinkedIn!");
     } catch (e) {
-      setError(String(e));
+      setError(errorMessage(e));
     }
   };
 
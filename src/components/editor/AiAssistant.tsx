@@ -24,6 +24,7 @@ import {
   MessageSquare,
   Gauge,
 } from "lucide-react";
+import { errorMessage } from "@/lib/error-handler";
 
 /* ─── Quick action definitions ─── */
 const QUICK_ACTIONS: {
@@ -120,8 +121,8 @@ export function AiAssistant({ isOpen, onClose }: AiAssistantProps) {
           .run();
       }
     } catch (e) {
-      setError(String(e));
-      addChatMessage({ role: "assistant", content: `Error: ${String(e)}` });
+      setError(errorMessage(e));
+      addChatMessage({ role: "assistant", content: `Error: ${errorMessage(e)}` });
     }
   };
 
@@ -148,8 +149,8 @@ export function AiAssistant({ isOpen, onClose }: AiAssistantProps) {
       const response = await chat(messages, systemPrompt);
       addChatMessage({ role: "assistant", content: response.content });
     } catch (e) {
-      setError(String(e));
-      addChatMessage({ role: "assistant", content: `Error: ${String(e)}` });
+      setError(errorMessage(e));
+      addChatMessage({ role: "assistant", content: `Error: ${errorMessage(e)}` });
     }
   };
 
@@ -22,12 +22,15 @@ import {
   ChevronDown,
   GripVertical,
   Tag,
+  ExternalLink,
 } from "lucide-react";
 import { ImportDialog } from "./ImportDialog";
 import { DocumentStatusBadge } from "./DocumentStatusBadge";
 import { TagFilter } from "./TagFilter";
 import { TagEditor } from "./TagEditor";
+import { searchDocuments } from "@/lib/search";
 import { cn } from "@/lib/utils";
+import { errorMessage } from "@/lib/error-handler";
 
 // ─── Types ───────────────────────────────────────────────────────────
 
@@ -132,6 +135,8 @@ export function DocumentList({ onClose }: { onClose: () => void }) {
   );
   const [showArchived, setShowArchived] = useState(false);
   const [searchQuery, setSearchQuery] = useState("");
+  /** Ids matching `searchQuery` in title or body; null until the index answers */
+  const [searchHits, setSearchHits] = useState<Set<string> | null>(null);
   const [sortKey, setSortKey] = useState<SortKey>("date");
   const [showSortMenu, setShowSortMenu] = useState(false);
   const [viewMode, setViewMode] = useState<ViewMode>("list");
@@ -283,6 +288,28 @@ export function DocumentList({ onClose }: { onClose: () => void }) {
     });
   }, [allDocs, activeFilter]);
 
+  // ── Full-text search, debounced ──
+
+  useEffect(() => {
+    const q = searchQuery.trim();
+    setSearchHits(null);
+    if (!q) return;
+    let cancelled = false;
+    const timer = setTimeout(() => {
+      searchDocuments(q, { limit: 500 })
+        .then((hits) => {
+          if (!cancelled) setSearchHits(new Set(hits.map((h) => h.document.id)));
+        })
+        .catch(() => {
+          // Not in the native app: title matching only
+        });
+    }, 200);
+    return () => {
+      cancelled = true;
+      clearTimeout(timer);
+    };
+  }, [searchQuery]);
+
   // ── Apply search, archive filter, sort, pin ordering ──
 
   const displayDocs = useMemo(() => {
@@ -298,10 +325,10 @@ export function DocumentList({ onClose }: { onClose: () => void }) {
       docs = docs.filter((d) => d.tags.includes(activeTag));
     }
 
-    // Search filter
+    // Search filter: full-text hits when the index has answered, titles meanwhile
     if (searchQuery.trim()) {
       const q = searchQuery.toLowerCase().trim();
-      docs = docs.filter((d) => d.title.toLowerCase().includes(q));
+      docs = docs.filter((d) => searchHits?.has(d.id) || d.title.toLowerCase().includes(q));
     }
 
     // Sort
@@ -332,6 +359,7 @@ export function DocumentList({ onClose }: { onClose: () => void }) {
     archivedIds,
     activeTag,
     searchQuery,
+    searchHits,
     sortKey,
     pinnedIds,
     manualOrder,
@@ -815,6 +843,21 @@ export function DocumentList({ onClose }: { onClose: () => void }) {
               >
                 <Tag className="w-2.5 h-2.5" />
               </button>
+              <button
+                onClick={async (e) => {
+                  e.stopPropagation();
+                  try {
+                    const { invoke } = await import("@tauri-apps/api/core");
+                    await invoke("open_document_window", { documentId: doc.id });
+                  } catch (err) {
+                    toast.error(`Could not open window: ${errorMessage(err)}`);
+                  }
+                }}
+                className="h-5 w-5 rounded flex items-center justify-center text-muted-foreground/50 hover:text-foreground hover:bg-accent"
+                title="Open in new window"
+              >
+                <ExternalLink className="w-2.5 h-2.5" />
+              </button>
               <button
                 onClick={(e) => {
                   e.stopPropagation();
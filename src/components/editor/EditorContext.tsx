@@ -1,11 +1,19 @@
 import { createContext, useContext, useRef, useCallback, useEffect, type ReactNode } from "react";
 import { useEditor, type Editor } from "@tiptap/react";
 import { getEditorExtensions } from "@/lib/editor-extensions";
-import { useEditorStore } from "@/stores/editor-store";
+import { useEditorStore, type DocumentStatus, type StoredDocument } from "@/stores/editor-store";
 import { useSocialStore } from "@/stores/social-store";
+import { useSettingsStore } from "@/stores/settings-store";
 import { toast } from "@/stores/toast-store";
+import { documentConflict, errorMessage } from "@/lib/error-handler";
 
-const AUTO_SAVE_DELAY = 30_000; // 30s idle
+/** Payload of the backend "document:changed" event */
+interface DocumentChange {
+  document_id: string;
+  change: "saved" | "autosaved" | "restored" | "status" | "moved" | "deleted" | "archived" | "unarchived" | "replaced";
+  version: number | null;
+  status: string | null;
+}
 
 const EditorContext = createContext<Editor | null>(null);
 
@@ -38,12 +46,14 @@ export function EditorProvider({ children }: { children: ReactNode }) {
     store.setSaveStatus("saving");
     try {
       const { invoke } = await import("@tauri-apps/api/core");
-      await invoke("auto_save", {
+      const version = await invoke<number>("auto_save", {
         id: doc.id,
         title: doc.title || "Untitled",
         content: JSON.stringify(doc.content),
         htmlContent: doc.htmlContent,
+        expectedVersion: doc.version ?? null,
       });
+      store.setDocumentVersion(version);
       store.markClean();
       store.setSaveStatus("saved");
       store.setLastSavedAt(new Date().toISOString());
@@ -54,6 +64,15 @@ export function EditorProvider({ children }: { children: ReactNode }) {
         }
       }, 2000);
     } catch (e) {
+      const conflict = documentConflict(e);
+      if (conflict) {
+        // Our edit is kept in the conflict copy; carry on from the newer version
+        store.setDocumentVersion(conflict.current_version);
+        store.markClean();
+        store.setSaveStatus("idle");
+        toast.warning(errorMessage(e), 8000);
+        return;
+      }
       console.error("Auto-save failed:", e);
       store.setSaveStatus("error");
       toast.error("Auto-save failed");
@@ -65,7 +84,7 @@ export function EditorProvider({ children }: { children: ReactNode }) {
     if (autoSaveTimer.current) clearTimeout(autoSaveTimer.current);
     autoSaveTimer.current = setTimeout(() => {
       performAutoSave();
-    }, AUTO_SAVE_DELAY);
+    }, useSettingsStore.getState().autoSaveInterval);
   }, [performAutoSave]);
 
   const editor = useEditor({
@@ -157,6 +176,84 @@ export function EditorProvider({ children }: { children: ReactNode }) {
     }
   }, [currentDocument.id, currentDocument.content, editor]);
 
+  // ─── Multi-window: open ?document=<id>, follow saves from other windows ───
+  useEffect(() => {
+    if (!editor) return;
+    let unlisten: (() => void) | null = null;
+
+    const reloadFromDisk = async (id: string) => {
+      const { invoke } = await import("@tauri-apps/api/core");
+      const raw = await invoke<string>("load_document", { id });
+      const stored: StoredDocument = JSON.parse(raw);
+      const sameDoc = useEditorStore.getState().currentDocument.id === stored.id;
+      useEditorStore.getState().openStoredDocument(stored);
+      if (sameDoc) {
+        // The id didn't change, so the sync effect above won't refresh the editor
+        isLoadingDocument.current = true;
+        editor.commands.setContent(useEditorStore.getState().currentDocument.content || stored.html_content || "<p></p>");
+        requestAnimationFrame(() => {
+          isLoadingDocument.current = false;
+        });
+      }
+    };
+
+    (async () => {
+      try {
+        const requested = new URLSearchParams(window.location.search).get("document");
+        if (requested) await reloadFromDisk(requested);
+
+        const { listen } = await import("@tauri-apps/api/event");
+        unlisten = await listen<DocumentChange>("document:changed", (e) => {
+          const change = e.payload;
+          const store = useEditorStore.getState();
+          if (change.document_id !== store.currentDocument.id) return;
+          if (change.change === "status" && change.status) {
+            useEditorStore.setState({
+              currentDocument: { ...store.currentDocument, status: change.status as DocumentStatus },
+            });
+            return;
+          }
+          // Our own saves echo back with the version we already hold
+          if (change.version == null || change.version <= (store.currentDocument.version ?? 0)) return;
+          if (store.isDirty) {
+            toast.warning("This document was saved in another window. Saving here will create a conflict copy.", 8000);
+            return;
+          }
+          reloadFromDisk(change.document_id).catch((err) => console.error("Reload failed:", err));
+        });
+      } catch {
+        // Not in Tauri — ignore
+      }
+    })();
+
+    return () => {
+      unlisten?.();
+    };
+  }, [editor]);
+
+  // ─── Storage budget: warn once per session when saves pass it ───
+  useEffect(() => {
+    let unlisten: (() => void) | null = null;
+    let warned = false;
+
+    (async () => {
+      try {
+        const { listen } = await import("@tauri-apps/api/event");
+        unlisten = await listen("storage:over_budget", () => {
+          if (warned) return;
+          warned = true;
+          toast.warning("Database is over its storage budget. Prune old versions in Settings → Storage.", 8000);
+        });
+      } catch {
+        // Not in Tauri — ignore
+      }
+    })();
+
+    return () => {
+      unlisten?.();
+    };
+  }, []);
+
   // Cleanup timer on unmount
   useEffect(() => {
     return () => {
@@ -7,6 +7,8 @@ import {
 } from "lucide-react";
 import { cn } from "@/lib/utils";
 import { analyzeContent } from "@/lib/readability";
+import { documentConflict, errorMessage } from "@/lib/error-handler";
+import { toast } from "@/stores/toast-store";
 
 interface HeadingItem {
   level: number;
@@ -58,13 +60,21 @@ export function EditorSidebar() {
   const handleSave = useCallback(async () => {
     try {
       const { invoke } = await import("@tauri-apps/api/core");
-      await invoke("save_document", {
+      const version = await invoke<number>("save_document", {
         id: currentDocument.id,
         title: currentDocument.title || "Untitled",
         content: JSON.stringify(currentDocument.content),
         htmlContent: currentDocument.htmlContent,
+        expectedVersion: currentDocument.version ?? null,
       });
+      useEditorStore.getState().setDocumentVersion(version);
     } catch (e) {
+      const conflict = documentConflict(e);
+      if (conflict) {
+        useEditorStore.getState().setDocumentVersion(conflict.current_version);
+        toast.warning(errorMessage(e), 8000);
+        return;
+      }
       console.error("Save failed:", e);
     }
   }, [currentDocument]);
@@ -20,6 +20,7 @@ import { PreFlightChecklist } from "./PreFlightChecklist";
 import { ImageUploadDialog } from "./ImageUploadDialog";
 import { TemplatePicker } from "./TemplatePicker";
 import { toast } from "@/stores/toast-store";
+import { exportMarkdown } from "@/lib/markdown";
 
 function ToolBtn({
   onClick, isActive = false, disabled = false, title, children,
@@ -64,6 +65,7 @@ export function EditorToolbar({ onToggleFind, onToggleAi, showAi, onToggleDocLis
   const [showPublish, setShowPublish] = useState(false);
   const [showPreflight, setShowPreflight] = useState(false);
   const [showExport, setShowExport] = useState(false);
+  const [highlightCode, setHighlightCode] = useState(true);
   const [showImageUpload, setShowImageUpload] = useState(false);
   const [showTemplatePicker, setShowTemplatePicker] = useState(false);
   const [showGoalPicker, setShowGoalPicker] = useState(false);
@@ -123,7 +125,7 @@ export function EditorToolbar({ onToggleFind, onToggleAi, showAi, onToggleDocLis
     editor.chain().focus().insertTable({ rows: 3, cols: 3, withHeaderRow: true }).run();
   };
 
-  const handleExport = async (format: "html" | "markdown" | "pdf" | "docx") => {
+  const handleExport = async (format: "html" | "markdown" | "pdf" | "docx" | "epub") => {
     setShowExport(false);
     const title = currentDocument.title || "Untitled";
     const html = currentDocument.htmlContent;
@@ -132,37 +134,28 @@ export function EditorToolbar({ onToggleFind, onToggleAi, showAi, onToggleDocLis
       const fullHtml = `<!DOCTYPE html><html><head><meta charset="utf-8"><title>${title}</title><style>body{font-family:system-ui,sans-serif;max-width:680px;margin:2rem auto;padding:0 1rem;line-height:1.7;color:#1a1a1a}h1{font-size:2rem;font-weight:700}h2{font-size:1.5rem;font-weight:600}h3{font-size:1.25rem;font-weight:600}img{max-width:100%;border-radius:8px}code{background:#f4f4f5;padding:2px 6px;border-radius:4px;font-size:0.9em}pre{background:#f4f4f5;padding:1rem;border-radius:8px;overflow-x:auto}blockquote{border-left:3px solid #d4d4d8;padding-left:1rem;color:#52525b}table{border-collapse:collapse;width:100%}th,td{border:1px solid #e4e4e7;padding:8px 12px;text-align:left}th{background:#f4f4f5;font-weight:600}</style></head><body>${html}</body></html>`;
       downloadBlob(new Blob([fullHtml], { type: "text/html" }), `${title}.html`);
     } else if (format === "markdown") {
-      let md = html
-        .replace(/<h1[^>]*>(.*?)<\/h1>/gi, "# $1\n\n")
-        .replace(/<h2[^>]*>(.*?)<\/h2>/gi, "## $1\n\n")
-        .replace(/<h3[^>]*>(.*?)<\/h3>/gi, "### $1\n\n")
-        .replace(/<strong>(.*?)<\/strong>/gi, "**$1**")
-        .replace(/<b>(.*?)<\/b>/gi, "**$1**")
-        .replace(/<em>(.*?)<\/em>/gi, "*$1*")
-        .replace(/<i>(.*?)<\/i>/gi, "*$1*")
-        .replace(/<s>(.*?)<\/s>/gi, "~~$1~~")
-        .replace(/<code>(.*?)<\/code>/gi, "`$1`")
-        .replace(/<a href="(.*?)">(.*?)<\/a>/gi, "[$2]($1)")
-        .replace(/<img[^>]*src="(.*?)"[^>]*\/?>/gi, "![]($1)")
-        .replace(/<br\s*\/?>/gi, "\n")
-        .replace(/<hr\s*\/?>/gi, "\n---\n\n")
-        .replace(/<li[^>]*>(.*?)<\/li>/gi, "- $1\n")
-        .replace(/<blockquote[^>]*>(.*?)<\/blockquote>/gi, "> $1\n\n")
-        .replace(/<p[^>]*>(.*?)<\/p>/gi, "$1\n\n")
-        .replace(/<[^>]+>/g, "")
-        .replace(/&amp;/g, "&").replace(/&lt;/g, "<").replace(/&gt;/g, ">").replace(/&nbsp;/g, " ")
-        .replace(/\n{3,}/g, "\n\n").trim();
-      downloadBlob(new Blob([md], { type: "text/markdown" }), `${title}.md`);
-    } else if (format === "pdf" || format === "docx") {
+      try {
+        const md = await exportMarkdown(title, html);
+        downloadBlob(new Blob([md], { type: "text/markdown" }), `${title}.md`);
+      } catch (e) {
+        console.error("Export to markdown failed:", e);
+        toast.error(`Export failed — make sure you're running the native app`);
+      }
+    } else if (format === "pdf" || format === "docx" || format === "epub") {
       toast.info(`Exporting ${format.toUpperCase()}...`);
       try {
         const { invoke } = await import("@tauri-apps/api/core");
-        const bytes = await invoke<number[]>(format === "pdf" ? "export_pdf" : "export_docx", {
+        const bytes = await invoke<number[]>(`export_${format}`, {
           title, htmlContent: html,
+          ...(format === "pdf" ? { options: { header_text: title, page_numbers: true, highlight_code: highlightCode } } : {}),
+          ...(format !== "epub" ? { documentId: currentDocument.id } : {}),
         });
-        const blob = new Blob([new Uint8Array(bytes)], {
-          type: format === "pdf" ? "application/pdf" : "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
-        });
+        const mimeTypes = {
+          pdf: "application/pdf",
+          docx: "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+          epub: "application/epub+zip",
+        };
+        const blob = new Blob([new Uint8Array(bytes)], { type: mimeTypes[format] });
         downloadBlob(blob, `${title}.${format}`);
         toast.success(`${format.toUpperCase()} exported successfully`);
       } catch (e) {
@@ -452,6 +445,14 @@ export function EditorToolbar({ onToggleFind, onToggleAi, showAi, onToggleDocLis
                     <button onClick={() => handleExport("docx")} className="flex items-center gap-2.5 w-full px-3 py-1.5 text-[12px] text-muted-foreground hover:text-foreground hover:bg-accent transition-colors">
                       <FileText className="w-3.5 h-3.5" /> DOCX
                     </button>
+                    <button onClick={() => handleExport("epub")} className="flex items-center gap-2.5 w-full px-3 py-1.5 text-[12px] text-muted-foreground hover:text-foreground hover:bg-accent transition-colors">
+                      <FileText className="w-3.5 h-3.5" /> EPUB
+                    </button>
+                    <div className="h-px bg-border/30 my-1 mx-2" />
+                    <label className="flex items-center gap-2.5 w-full px-3 py-1.5 text-[12px] text-muted-foreground hover:text-foreground cursor-pointer">
+                      <input type="checkbox" checked={highlightCode} onChange={(e) => setHighlightCode(e.target.checked)} className="w-3.5 h-3.5" />
+                      Highlight code in PDF
+                    </label>
                   </div>
                 </>
               )}
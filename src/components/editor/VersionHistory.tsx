@@ -51,12 +51,13 @@ export function VersionHistory({ onClose }: Props) {
     setIsRestoring(true);
     try {
       // Restore returns the updated document; reload via the store
-      await invoke("restore_document_version", {
+      const restored = await invoke<number>("restore_document_version", {
         documentId: currentDocument.id,
         version: version.version,
       });
       // Reload document from store (resets to restored version)
       useEditorStore.getState().loadDocument(currentDocument.id);
+      useEditorStore.getState().setDocumentVersion(restored);
       toast.success(`Restored to version ${version.version}`);
       setPreviewVersion(null);
     } catch {
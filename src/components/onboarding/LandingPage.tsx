@@ -31,6 +31,7 @@ import {
   Zap,
   Rocket,
 } from "lucide-react";
+import { errorMessage } from "@/lib/error-handler";
 
 /* ═══════════════════════════════════════════════════════
    Station Onboarding — Polished, Adobe/Spotify-tier
@@ -141,7 +142,7 @@ export default function LandingPage({ onComplete }: LandingPageProps) {
       setFields({});
     } catch (e) {
       setStatuses((prev) => ({ ...prev, [platform.id]: "error" }));
-      setErrors({ _global: String(e) });
+      setErrors({ _global: errorMessage(e) });
     }
   };
 
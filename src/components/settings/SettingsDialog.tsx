@@ -1,6 +1,14 @@
+import { useEffect, useState } from "react";
 import { useSettingsStore, ACCENT_PRESETS, THEME_PRESETS, type ThemeMode } from "@/stores/settings-store";
 import { X, Monitor, Sun, Moon, Check } from "lucide-react";
 import { cn } from "@/lib/utils";
+import { errorMessage } from "@/lib/error-handler";
+import { toast } from "@/stores/toast-store";
+import {
+  fetchStorageBreakdown, fetchStorageSettings, formatBytes, pruneStorage, saveStorageSetting,
+  MAX_DB_MB_SETTING, MAX_VERSIONS_SETTING, SNAPSHOT_INTERVAL_SETTING,
+  type PruneTarget, type StorageBreakdown, type StorageSettings,
+} from "@/lib/storage";
 
 const MODE_OPTIONS: { mode: ThemeMode; icon: typeof Sun; label: string }[] = [
   { mode: "light", icon: Sun, label: "Light" },
@@ -188,6 +196,11 @@ export function SettingsDialog({ onClose }: { onClose: () => void }) {
 
           <div className="h-px bg-border/30" />
 
+          {/* Storage */}
+          <StorageSection />
+
+          <div className="h-px bg-border/30" />
+
           {/* About */}
           <Section title="About">
             <div className="space-y-1.5">
@@ -239,6 +252,141 @@ function ThemeCard({
   );
 }
 
+const PRUNE_OPTIONS: { target: PruneTarget; label: string }[] = [
+  { target: "versions", label: "Trim old versions" },
+  { target: "activity", label: "Clear activity older than 90 days" },
+  { target: "caches", label: "Clear cached publications" },
+  { target: "images", label: "Delete unused images" },
+];
+
+const SELECT_CLASS =
+  "w-40 rounded-md border border-border bg-background px-2 py-1 text-xs text-foreground outline-none focus:ring-1 focus:ring-ring";
+
+function StorageSection() {
+  const [breakdown, setBreakdown] = useState<StorageBreakdown | null>(null);
+  const [settings, setSettings] = useState<StorageSettings | null>(null);
+  const [pruning, setPruning] = useState<PruneTarget | null>(null);
+
+  const refresh = () => {
+    fetchStorageBreakdown().then(setBreakdown).catch((e) => console.error("Storage breakdown failed:", e));
+  };
+
+  useEffect(() => {
+    refresh();
+    fetchStorageSettings().then(setSettings).catch((e) => console.error("Storage settings failed:", e));
+  }, []);
+
+  const update = async (key: string, field: keyof StorageSettings, value: number | null) => {
+    try {
+      await saveStorageSetting(key, value);
+      setSettings((s) => (s ? { ...s, [field]: value } : s));
+    } catch (e) {
+      toast.error(errorMessage(e));
+    }
+  };
+
+  const prune = async (target: PruneTarget) => {
+    setPruning(target);
+    try {
+      const result = await pruneStorage(target);
+      toast.success(`Removed ${result.removed} items, freed ${formatBytes(result.bytes_freed)}`);
+      refresh();
+    } catch (e) {
+      toast.error(errorMessage(e));
+    } finally {
+      setPruning(null);
+    }
+  };
+
+  const rows: [string, string][] = breakdown
+    ? [
+        ["Database", formatBytes(breakdown.database_bytes)],
+        ["Documents", formatBytes(breakdown.documents_bytes)],
+        ["Versions", `${formatBytes(breakdown.versions_bytes)} · ${breakdown.version_count}`],
+        ["Images", `${formatBytes(breakdown.images_bytes)} · ${breakdown.image_count}`],
+        ["Attachments", `${formatBytes(breakdown.attachments_bytes)} · ${breakdown.attachment_count}`],
+        ["Caches", formatBytes(breakdown.cache_bytes)],
+        ["Activity logs", formatBytes(breakdown.log_bytes)],
+      ]
+    : [];
+
+  return (
+    <Section title="Storage">
+      {settings && (
+        <>
+          <SettingRow label="Version snapshots">
+            <select
+              value={settings.snapshotIntervalMinutes}
+              onChange={(e) => update(SNAPSHOT_INTERVAL_SETTING, "snapshotIntervalMinutes", Number(e.target.value))}
+              className={SELECT_CLASS}
+            >
+              <option value={0}>Every save</option>
+              <option value={5}>Every 5 minutes</option>
+              <option value={15}>Every 15 minutes</option>
+              <option value={60}>Every hour</option>
+            </select>
+          </SettingRow>
+          <SettingRow label="Versions kept per document">
+            <select
+              value={settings.maxVersions}
+              onChange={(e) => update(MAX_VERSIONS_SETTING, "maxVersions", Number(e.target.value))}
+              className={SELECT_CLASS}
+            >
+              <option value={10}>10</option>
+              <option value={25}>25</option>
+              <option value={50}>50</option>
+              <option value={100}>100</option>
+              <option value={250}>250</option>
+            </select>
+          </SettingRow>
+          <SettingRow label="Database budget">
+            <select
+              value={settings.maxDbMb ?? 0}
+              onChange={(e) => update(MAX_DB_MB_SETTING, "maxDbMb", Number(e.target.value) || null)}
+              className={SELECT_CLASS}
+            >
+              <option value={0}>No limit</option>
+              <option value={100}>100 MB</option>
+              <option value={250}>250 MB</option>
+              <option value={500}>500 MB</option>
+              <option value={1024}>1 GB</option>
+            </select>
+          </SettingRow>
+        </>
+      )}
+
+      {breakdown && (
+        <div className="space-y-1 rounded-lg border border-border/50 p-2.5">
+          {rows.map(([label, value]) => (
+            <div key={label} className="flex items-center justify-between text-[11px]">
+              <span className="text-muted-foreground">{label}</span>
+              <span className="tabular-nums text-foreground/80">{value}</span>
+            </div>
+          ))}
+          {breakdown.over_budget && breakdown.budget_bytes !== null && (
+            <p className="pt-1 text-[10px] text-destructive">
+              Over the {formatBytes(breakdown.budget_bytes)} budget
+            </p>
+          )}
+        </div>
+      )}
+
+      <div className="grid grid-cols-2 gap-1.5">
+        {PRUNE_OPTIONS.map(({ target, label }) => (
+          <button
+            key={target}
+            onClick={() => prune(target)}
+            disabled={pruning !== null}
+            className="h-7 rounded-md border border-border/50 px-2 text-[11px] text-foreground/80 hover:bg-accent disabled:opacity-50 transition-colors"
+          >
+            {pruning === target ? "Pruning…" : label}
+          </button>
+        ))}
+      </div>
+    </Section>
+  );
+}
+
 function Section({ title, children }: { title: string; children: React.ReactNode }) {
   return (
     <div className="space-y-3">
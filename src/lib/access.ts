@@ -0,0 +1,33 @@
+import { invoke } from "@tauri-apps/api/core";
+
+export type Role = "owner" | "editor" | "viewer";
+
+export type Permission =
+  | "manage_credentials"
+  | "view_revenue"
+  | "manage_revenue"
+  | "delete_content"
+  | "view_audit_log"
+  | "send_email";
+
+/** `AccessInfo` from src-tauri/src/commands/settings.rs */
+export interface AccessInfo {
+  role: Role;
+  permissions: Permission[];
+  /** Whether switching back to owner asks for a passphrase */
+  passphrase_set: boolean;
+}
+
+export function getAccessInfo(): Promise<AccessInfo> {
+  return invoke<AccessInfo>("get_access_info");
+}
+
+/** Becoming owner needs `passphrase` once one is set. */
+export function setAccessRole(role: Role, passphrase?: string): Promise<AccessInfo> {
+  return invoke<AccessInfo>("set_access_role", { role, passphrase: passphrase ?? null });
+}
+
+/** Owner only; pass `passphrase: null` to clear it. */
+export function setOwnerPassphrase(current: string | null, passphrase: string | null): Promise<AccessInfo> {
+  return invoke<AccessInfo>("set_owner_passphrase", { current, passphrase });
+}
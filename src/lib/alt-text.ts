@@ -0,0 +1,25 @@
+import { invoke } from "@tauri-apps/api/core";
+
+/** Backend setting from src-tauri/src/commands/accessibility.rs: "warn" (default), "block" or "off" */
+export const ALT_TEXT_MODE_SETTING = "publishing.alt_text_mode";
+
+/** `MissingAltText` from src-tauri/src/commands/accessibility.rs */
+export interface MissingAltText {
+  /** Position among the document's images, from 0 */
+  index: number;
+  src: string;
+  reason: "missing" | "empty" | "file_name";
+}
+
+/** Images that need alt text before the issue goes out */
+export function checkAltText(htmlContent: string): Promise<MissingAltText[]> {
+  return invoke<MissingAltText[]>("check_alt_text", { htmlContent });
+}
+
+/**
+ * One sentence of alt text from a vision-capable model. `image` is the
+ * img src as it appears in the document; `context` is nearby text.
+ */
+export function generateAltText(providerId: string, image: string, context?: string): Promise<string> {
+  return invoke<string>("ai_generate_alt_text", { providerId, image, context: context ?? null });
+}
@@ -0,0 +1,29 @@
+import { invoke } from "@tauri-apps/api/core";
+
+export type ArchivePlatform = "mailchimp" | "beehiiv" | "kit";
+
+/** `ArchiveImportResult` from src-tauri/src/commands/archives.rs */
+export interface ArchiveImportResult {
+  platform: ArchivePlatform;
+  account_id: string;
+  files: { name: string; kind: "subscribers" | "campaigns" | "ignored"; rows: number }[];
+  subscribers_new: number;
+  subscribers_updated: number;
+  tags_applied: number;
+  campaigns_imported: number;
+  /** Rows without an email (subscribers) or title (campaigns) */
+  rows_skipped: number;
+}
+
+/** Import a Mailchimp, Beehiiv or Kit export (zip or CSV) */
+export function importEspArchive(
+  platform: ArchivePlatform,
+  filePath: string,
+  accountId?: string
+): Promise<ArchiveImportResult> {
+  return invoke<ArchiveImportResult>("import_esp_archive", {
+    platform,
+    filePath,
+    accountId: accountId ?? null,
+  });
+}
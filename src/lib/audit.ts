@@ -0,0 +1,32 @@
+import { invoke } from "@tauri-apps/api/core";
+
+/** `AuditEntry` from src-tauri/src/commands/audit.rs */
+export interface AuditEntry {
+  seq: number;
+  action: string;
+  entity_type: string;
+  entity_id: string | null;
+  details: string | null;
+  actor_role: string;
+  created_at: string;
+  trace_id: string | null;
+  hash: string;
+}
+
+export interface AuditVerification {
+  valid: boolean;
+  rows_checked: number;
+  /** First row whose hash or link doesn't match */
+  broken_at_seq: number | null;
+  /** Hash of the newest row that checks out */
+  head_hash: string;
+}
+
+/** Newest first; page with `beforeSeq`. */
+export function fetchAuditLog(limit?: number, beforeSeq?: number): Promise<AuditEntry[]> {
+  return invoke<AuditEntry[]>("list_audit_log", { limit: limit ?? null, beforeSeq: beforeSeq ?? null });
+}
+
+export function verifyAuditLog(): Promise<AuditVerification> {
+  return invoke<AuditVerification>("verify_audit_log");
+}
@@ -0,0 +1,32 @@
+import { invoke } from "@tauri-apps/api/core";
+
+/** `ChartSeries` from src-tauri/src/commands/charts.rs */
+export interface ChartSeries {
+  name: string;
+  /** One per label; missing values are drawn as zero */
+  values: number[];
+  /** "#rrggbb"; from the palette when unset */
+  color?: string;
+}
+
+/** `ChartSpec` from src-tauri/src/commands/charts.rs */
+export interface ChartSpec {
+  title?: string;
+  kind?: "bar" | "line";
+  /** One per point along the x axis, e.g. "Jan 2026" */
+  labels: string[];
+  series: ChartSeries[];
+  /** "dollars" values are in cents */
+  value_format?: "number" | "percent" | "dollars";
+  /** In px; 1200×480 by default */
+  width?: number;
+  height?: number;
+}
+
+/**
+ * Render a chart as a data URI for an `<img>`. PNG (the default) is what
+ * the PDF and Word exports can embed; SVG stays sharp in HTML.
+ */
+export function renderChart(spec: ChartSpec, format?: "png" | "svg"): Promise<string> {
+  return invoke<string>("render_chart", { spec, format: format ?? null });
+}
@@ -0,0 +1,45 @@
+import { invoke } from "@tauri-apps/api/core";
+
+/** `DiffSpan` from src-tauri/src/commands/compare.rs */
+export interface DiffSpan {
+  kind: "equal" | "removed" | "added";
+  text: string;
+}
+
+/** `BlockChange` from src-tauri/src/commands/compare.rs; indexes are block positions */
+export interface BlockChange {
+  /** "removed" is only in the draft, "added" only on the platform */
+  kind: "removed" | "added" | "changed";
+  local_index: number | null;
+  published_index: number | null;
+  /** Normalized Markdown of the block */
+  local: string | null;
+  published: string | null;
+  /** Word diff of a changed block */
+  spans: DiffSpan[];
+}
+
+/** `PublishedComparison` from src-tauri/src/commands/compare.rs */
+export interface PublishedComparison {
+  document_id: string;
+  platform: string;
+  post_id: string;
+  url: string | null;
+  fetched_at: string;
+  identical: boolean;
+  unchanged_blocks: number;
+  changes: BlockChange[];
+}
+
+/**
+ * Diff a document against its live copy on Ghost, Beehiiv or WordPress.
+ * WordPress posts that weren't published from here are found by title on
+ * `accountId`.
+ */
+export function compareWithPublished(
+  documentId: string,
+  platform: "ghost" | "beehiiv" | "wordpress",
+  accountId?: string,
+): Promise<PublishedComparison> {
+  return invoke<PublishedComparison>("compare_with_published", { documentId, platform, accountId: accountId ?? null });
+}
@@ -0,0 +1,68 @@
+import { invoke } from "@tauri-apps/api/core";
+
+export type BlockKind = "sponsor" | "footer" | "about" | "custom";
+export type BlockPosition = "top" | "after_intro" | "bottom";
+
+/** `ContentBlock` from src-tauri/src/commands/blocks.rs */
+export interface ContentBlock {
+  id: string;
+  name: string;
+  kind: BlockKind;
+  /** HTML with `{{title}}`, `{{date}}`, `{{sponsor.name}}`... placeholders */
+  html: string;
+  position: BlockPosition;
+  created_at: string;
+  updated_at: string;
+}
+
+export interface BlockInput {
+  name?: string;
+  html?: string;
+  kind?: BlockKind;
+  position?: BlockPosition;
+}
+
+export interface AssembledIssue {
+  html: string;
+  /** Placeholders with no value, dropped from `html` */
+  unresolved: string[];
+}
+
+export function listContentBlocks(kind?: BlockKind): Promise<ContentBlock[]> {
+  return invoke<ContentBlock[]>("list_content_blocks", { kind: kind ?? null });
+}
+
+export function createContentBlock(input: BlockInput & { name: string; html: string }): Promise<ContentBlock> {
+  return invoke<ContentBlock>("create_content_block", {
+    name: input.name,
+    html: input.html,
+    kind: input.kind ?? null,
+    position: input.position ?? null,
+  });
+}
+
+export function updateContentBlock(id: string, input: BlockInput): Promise<ContentBlock> {
+  return invoke<ContentBlock>("update_content_block", {
+    id,
+    name: input.name ?? null,
+    html: input.html ?? null,
+    kind: input.kind ?? null,
+    position: input.position ?? null,
+  });
+}
+
+export function deleteContentBlock(id: string): Promise<void> {
+  return invoke("delete_content_block", { id });
+}
+
+/**
+ * Preview the outgoing HTML with blocks merged in. Publishing merges the
+ * same way when `options.block_ids` is set; the draft is never modified.
+ */
+export function assembleIssue(
+  documentId: string,
+  blockIds: string[],
+  values?: Record<string, string>,
+): Promise<AssembledIssue> {
+  return invoke<AssembledIssue>("assemble_issue", { documentId, blockIds, values: values ?? null });
+}
@@ -0,0 +1,73 @@
+import { invoke } from "@tauri-apps/api/core";
+
+export type BounceEventType = "hard_bounce" | "soft_bounce" | "complaint";
+
+export interface BounceEventInput {
+  email: string;
+  event_type: BounceEventType;
+  reason?: string | null;
+  /** RFC 3339; defaults to now */
+  occurred_at?: string | null;
+}
+
+/** `SubscriberEvent` from src-tauri/src/commands/deliverability.rs */
+export interface SubscriberEvent {
+  id: string;
+  subscriber_id: string | null;
+  email: string;
+  event_type: BounceEventType;
+  platform: string;
+  reason: string | null;
+  source: "sync" | "api" | "csv";
+  occurred_at: string;
+}
+
+export interface Suppression {
+  email: string;
+  reason: string;
+  platform: string | null;
+  created_at: string;
+}
+
+export interface IngestResult {
+  recorded: number;
+  /** Already recorded by an earlier ingestion */
+  duplicates: number;
+  suppressed: number;
+  /** Events for addresses not in the unified audience; still recorded */
+  unknown_subscribers: number;
+  rows_skipped: number;
+}
+
+export function ingestBounceEvents(platform: string, events: BounceEventInput[]): Promise<IngestResult> {
+  return invoke<IngestResult>("ingest_bounce_events", { platform, events });
+}
+
+/** Import an exported bounce or complaint report (CSV) */
+export function importBounceCsv(platform: string, filePath: string): Promise<IngestResult> {
+  return invoke<IngestResult>("import_bounce_csv", { platform, filePath });
+}
+
+export function fetchSubscriberEvents(filter?: {
+  subscriberId?: string;
+  eventType?: BounceEventType;
+  limit?: number;
+}): Promise<SubscriberEvent[]> {
+  return invoke<SubscriberEvent[]>("list_subscriber_events", {
+    subscriberId: filter?.subscriberId ?? null,
+    eventType: filter?.eventType ?? null,
+    limit: filter?.limit ?? null,
+  });
+}
+
+export function fetchSuppressions(): Promise<Suppression[]> {
+  return invoke<Suppression[]>("list_suppressions");
+}
+
+export function addSuppression(email: string, reason?: string): Promise<void> {
+  return invoke("add_suppression", { email, reason: reason ?? null });
+}
+
+export function removeSuppression(email: string): Promise<void> {
+  return invoke("remove_suppression", { email });
+}
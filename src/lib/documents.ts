@@ -0,0 +1,61 @@
+import { invoke } from "@tauri-apps/api/core";
+import { listen, type UnlistenFn } from "@tauri-apps/api/event";
+import type { ImportedDocument } from "./markdown";
+
+/** `DocumentFilter` from src-tauri/src/commands/export.rs */
+export interface DocumentFilter {
+  status?: string;
+  project_id?: string;
+  tag?: string;
+  /** RFC 3339 bounds on updated_at, inclusive */
+  updated_after?: string;
+  updated_before?: string;
+  /** Only archived documents; unarchived ones otherwise */
+  archived?: boolean;
+  /** Archived and unarchived together, overriding `archived` */
+  include_archived?: boolean;
+  limit?: number;
+  offset?: number;
+}
+
+/**
+ * Pinned first, then most recently updated; within one project, in manual
+ * order. Archived documents only when the filter asks
+ */
+export function listDocuments(filter?: DocumentFilter): Promise<ImportedDocument[]> {
+  return invoke<ImportedDocument[]>("list_documents", { filter: filter ?? null });
+}
+
+/** Archive a document, or restore it with `archived` false */
+export function archiveDocument(id: string, archived = true): Promise<void> {
+  return invoke("archive_document", { id, archived });
+}
+
+/** Pin a document to the top of the list, or unpin it with `pinned` false */
+export function pinDocument(id: string, pinned = true): Promise<void> {
+  return invoke("pin_document", { id, pinned });
+}
+
+/** Set a project's manual document order, first to last */
+export function reorderDocuments(projectId: string, ids: string[]): Promise<void> {
+  return invoke("reorder_documents", { projectId, ids });
+}
+
+/** `GoalProgress` from src-tauri/src/commands/export.rs */
+export interface GoalProgress {
+  document_id: string;
+  word_count: number;
+  target_words: number;
+  percent: number;
+  reached: boolean;
+}
+
+/** Set a word count goal, or clear it with null */
+export function setDocumentGoal(documentId: string, targetWords: number | null): Promise<ImportedDocument> {
+  return invoke<ImportedDocument>("set_document_goal", { documentId, targetWords });
+}
+
+/** Fires after every save of a document that has a goal */
+export function onGoalProgress(handler: (progress: GoalProgress) => void): Promise<UnlistenFn> {
+  return listen<GoalProgress>("document:goal_progress", (event) => handler(event.payload));
+}
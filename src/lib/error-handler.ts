@@ -1,10 +1,87 @@
 // ─── Error Handling Utilities ───
 
+export type AppErrorKind =
+  | "auth"
+  | "rate_limited"
+  | "validation"
+  | "not_found"
+  | "network"
+  | "platform"
+  | "database"
+  | "internal"
+  | "queued"
+  | "conflict"
+  | "cancelled";
+
+/** Error shape returned by every Tauri command (`AppError` in src-tauri/src/error.rs). */
+export interface AppError {
+  kind: AppErrorKind;
+  message: string;
+  retryable: boolean;
+  platform: string | null;
+  /** Trace id of the failing command; matches log lines and publish_attempts rows */
+  trace_id: string | null;
+  /** Structured context, currently only set for `conflict` */
+  details?: Record<string, unknown>;
+}
+
+/** `details` of a `conflict` error from `save_document` / `auto_save`. */
+export interface DocumentConflict {
+  document_id: string;
+  expected_version: number;
+  current_version: number;
+  updated_at: string;
+  conflict_copy_id: string;
+  conflict_copy_title: string;
+}
+
+export function documentConflict(error: unknown): DocumentConflict | null {
+  if (!isAppError(error) || error.kind !== "conflict" || !error.details) return null;
+  return error.details as unknown as DocumentConflict;
+}
+
+export function isAppError(error: unknown): error is AppError {
+  return (
+    typeof error === "object" &&
+    error !== null &&
+    typeof (error as AppError).kind === "string" &&
+    typeof (error as AppError).message === "string"
+  );
+}
+
+/**
+ * Plain message for any thrown value — use instead of `String(e)`, which
+ * renders command errors as "[object Object]".
+ */
+export function errorMessage(error: unknown): string {
+  if (isAppError(error)) return error.message;
+  if (error instanceof Error) return error.message;
+  return String(error);
+}
+
 /**
  * Maps raw Tauri invoke / fetch errors into user-friendly messages.
  */
 export function handleTauriError(error: unknown, context: string): string {
-  const raw = error instanceof Error ? error.message : String(error);
+  if (isAppError(error)) {
+    switch (error.kind) {
+      case "auth":
+        return "Your API key may be expired or invalid. Check Accounts → Settings.";
+      case "rate_limited":
+        return "Rate limited — wait a moment, then try again.";
+      case "not_found":
+        return `${context}: resource not found. Check your configuration.`;
+      case "network":
+        return "Check your internet connection and try again.";
+      case "validation":
+      case "queued":
+      case "conflict":
+      case "cancelled":
+        return error.message;
+    }
+  }
+
+  const raw = errorMessage(error);
 
   // Network-level errors
   if (/failed to fetch|networkerror|network request/i.test(raw)) {
@@ -62,6 +139,8 @@ export async function withRetry<T>(
       return await fn();
     } catch (e) {
       lastError = e;
+      // Command errors say whether a retry can help; don't repeat bad input.
+      if (isAppError(e) && !e.retryable) break;
       if (attempt < retries) {
         await sleep(delay * Math.pow(2, attempt));
       }
@@ -0,0 +1,93 @@
+import { invoke } from "@tauri-apps/api/core";
+
+/** `FocusSession` from src-tauri/src/commands/focus.rs */
+export interface FocusSession {
+  id: string;
+  /** Only saves of this document count; any document's when null */
+  document_id: string | null;
+  goal_minutes: number | null;
+  goal_words: number | null;
+  /** "completed" met its goal; "ended" was stopped short */
+  status: "active" | "paused" | "completed" | "ended";
+  /** Time spent running, pauses excluded */
+  elapsed_seconds: number;
+  /** Net words saved during the session */
+  words_written: number;
+  /** Of whichever goal is closest, 0 to 100 */
+  percent: number;
+  started_at: string;
+  ended_at: string | null;
+}
+
+/** `FocusDay` from src-tauri/src/commands/focus.rs */
+export interface FocusDay {
+  date: string;
+  minutes: number;
+  words: number;
+  sessions: number;
+  completed: number;
+}
+
+/** `FocusStats` from src-tauri/src/commands/focus.rs */
+export interface FocusStats {
+  sessions: number;
+  completed: number;
+  /** Percent of sessions that met their goal */
+  completion_rate: number;
+  total_minutes: number;
+  total_words: number;
+  average_minutes: number;
+  longest_minutes: number;
+  /** Only days with a session */
+  days: FocusDay[];
+}
+
+/** Sent while a session runs, every few seconds and after each save */
+export const FOCUS_PROGRESS_EVENT = "focus:progress";
+/** Sent once when a session meets its goal; the session has ended */
+export const FOCUS_COMPLETED_EVENT = "focus:completed";
+
+/**
+ * Start a session that ends when either goal is met, e.g. 45 minutes or
+ * 800 words. Only saves of `documentId` count, if given.
+ */
+export function startFocusSession(
+  goals: { minutes?: number; words?: number },
+  documentId?: string,
+): Promise<FocusSession> {
+  return invoke<FocusSession>("start_focus_session", {
+    documentId: documentId ?? null,
+    goalMinutes: goals.minutes ?? null,
+    goalWords: goals.words ?? null,
+  });
+}
+
+export function pauseFocusSession(): Promise<FocusSession> {
+  return invoke<FocusSession>("pause_focus_session");
+}
+
+export function resumeFocusSession(): Promise<FocusSession> {
+  return invoke<FocusSession>("resume_focus_session");
+}
+
+/** Stop early; still "completed" if the goal was met */
+export function endFocusSession(): Promise<FocusSession> {
+  return invoke<FocusSession>("end_focus_session");
+}
+
+/** The running or paused session, e.g. after a reload */
+export function getActiveFocusSession(): Promise<FocusSession | null> {
+  return invoke<FocusSession | null>("get_active_focus_session");
+}
+
+/** Finished sessions started in `range`, newest first */
+export function listFocusSessions(
+  range: { from: string; to: string },
+  completedOnly?: boolean,
+): Promise<FocusSession[]> {
+  return invoke<FocusSession[]>("list_focus_sessions", { range, completedOnly: completedOnly ?? null });
+}
+
+export function getFocusStats(range: { from: string; to: string }): Promise<FocusStats> {
+  return invoke<FocusStats>("get_focus_stats", { range });
+}
@@ -0,0 +1,36 @@
+import { invoke } from "@tauri-apps/api/core";
+import { listen, type UnlistenFn } from "@tauri-apps/api/event";
+import type { ImportedDocument } from "./markdown";
+
+/** `GoogleConnected` from src-tauri/src/commands/google.rs */
+export interface GoogleConnected {
+  account_id: string;
+  email: string;
+}
+
+export interface GoogleDocExport {
+  file_id: string;
+  url: string | null;
+}
+
+/**
+ * Consent URL for the user's own OAuth client ("Desktop app" type). Open it
+ * in the browser; `onGoogleConnected` fires once the account is stored.
+ */
+export function startGoogleAuth(accountId: string, clientId: string, clientSecret: string): Promise<string> {
+  return invoke<string>("start_google_auth", { accountId, clientId, clientSecret });
+}
+
+export function onGoogleConnected(handler: (connected: GoogleConnected) => void): Promise<UnlistenFn> {
+  return listen<GoogleConnected>("google:connected", (event) => handler(event.payload));
+}
+
+/** Upload the document as a new Google Doc for commenting */
+export function exportToGoogleDoc(documentId: string, accountId: string): Promise<GoogleDocExport> {
+  return invoke<GoogleDocExport>("export_to_google_doc", { documentId, accountId });
+}
+
+/** Create a draft from a Google Docs link or file id */
+export function importGoogleDoc(docUrl: string, accountId: string, projectId?: string): Promise<ImportedDocument> {
+  return invoke<ImportedDocument>("import_google_doc", { docUrl, accountId, projectId: projectId ?? null });
+}
@@ -0,0 +1,47 @@
+import { invoke } from "@tauri-apps/api/core";
+
+export interface LinkPageLink {
+  label: string;
+  url: string;
+}
+
+/** `LinkPageConfig` from src-tauri/src/commands/linkpage.rs */
+export interface LinkPageConfig {
+  title: string;
+  bio: string | null;
+  links: LinkPageLink[];
+  /** Project whose brand kit styles the page */
+  project_id: string | null;
+  /** Latest published issues to list; 0 hides the section */
+  issue_count: number;
+  /** Page ids from earlier pushes, keyed "platform:account_id" */
+  published_pages: Record<string, string>;
+}
+
+export interface LinkPagePublishResult {
+  page_id: string;
+  url: string | null;
+}
+
+export function fetchLinkPageConfig(): Promise<LinkPageConfig> {
+  return invoke<LinkPageConfig>("get_link_page_config");
+}
+
+export function saveLinkPageConfig(config: LinkPageConfig): Promise<void> {
+  return invoke("save_link_page_config", { config });
+}
+
+/** Standalone page HTML; pass `config` to preview unsaved changes */
+export function generateLinkPage(config?: LinkPageConfig): Promise<string> {
+  return invoke<string>("generate_link_page", { config: config ?? null });
+}
+
+/** Write the page to `folder`/index.html; resolves to the file path */
+export function exportLinkPage(folder: string): Promise<string> {
+  return invoke<string>("export_link_page", { folder });
+}
+
+/** Create or update the page on a Ghost or WordPress account */
+export function publishLinkPage(platform: "ghost" | "wordpress", accountId: string): Promise<LinkPagePublishResult> {
+  return invoke<LinkPagePublishResult>("publish_link_page", { platform, accountId });
+}
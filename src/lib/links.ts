@@ -0,0 +1,46 @@
+import { invoke } from "@tauri-apps/api/core";
+
+/** Editor href for a link to another local document */
+export const LOCAL_LINK_PREFIX = "station://document/";
+
+export function localLink(documentId: string): string {
+  return `${LOCAL_LINK_PREFIX}${documentId}`;
+}
+
+/** `Backlink` from src-tauri/src/commands/links.rs */
+export interface Backlink {
+  source_document_id: string;
+  title: string;
+  status: string;
+  url: string;
+  anchor_text: string;
+}
+
+export interface OutgoingLink {
+  url: string;
+  anchor_text: string;
+  /** Local document the link resolves to, directly or by published URL */
+  target_document_id: string | null;
+}
+
+export interface LinkGraph {
+  nodes: { id: string; title: string; status: string; inbound: number; outbound: number }[];
+  edges: { source: string; target: string }[];
+}
+
+export function fetchBacklinks(documentId: string): Promise<Backlink[]> {
+  return invoke<Backlink[]>("get_backlinks", { documentId });
+}
+
+export function fetchOutgoingLinks(documentId: string): Promise<OutgoingLink[]> {
+  return invoke<OutgoingLink[]>("get_outgoing_links", { documentId });
+}
+
+export function fetchLinkGraph(projectId?: string): Promise<LinkGraph> {
+  return invoke<LinkGraph>("get_link_graph", { projectId: projectId ?? null });
+}
+
+/** Re-extract links from every document; resolves to the count indexed. */
+export function rebuildLinkIndex(): Promise<number> {
+  return invoke<number>("rebuild_link_index");
+}
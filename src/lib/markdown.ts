@@ -0,0 +1,197 @@
+import { invoke } from "@tauri-apps/api/core";
+
+/** `MarkdownImport` from src-tauri/src/commands/export.rs */
+export interface MarkdownImport {
+  /** From YAML front matter or a leading H1 */
+  title: string | null;
+  html_content: string;
+}
+
+/** CommonMark with GFM tables; the title goes in YAML front matter */
+export function exportMarkdown(title: string, htmlContent: string): Promise<string> {
+  return invoke<string>("export_markdown", { title, htmlContent });
+}
+
+/** Editor HTML from Markdown, e.g. an Obsidian note */
+export function importMarkdown(markdown: string): Promise<MarkdownImport> {
+  return invoke<MarkdownImport>("import_markdown", { markdown });
+}
+
+export type ProjectArchiveFormat = "docx" | "pdf" | "markdown";
+
+/**
+ * ZIP bytes holding every document in the project, oldest first. Runs on
+ * the export queue; pass `jobId` to follow "export:progress" or cancel.
+ */
+export function exportProjectArchive(
+  projectId: string,
+  format: ProjectArchiveFormat,
+  jobId?: string,
+): Promise<number[]> {
+  return invoke<number[]>("export_project_archive", { projectId, format, jobId: jobId ?? null });
+}
+
+/** ZIP bytes holding the given documents, in order */
+export function exportDocumentsArchive(
+  documentIds: string[],
+  format: ProjectArchiveFormat,
+  jobId?: string,
+): Promise<number[]> {
+  return invoke<number[]>("export_documents_archive", { documentIds, format, jobId: jobId ?? null });
+}
+
+/** `ExportJob` from src-tauri/src/commands/export_jobs.rs; also the "export:progress" payload */
+export interface ExportJob {
+  id: string;
+  kind: "project_archive" | "documents_archive" | "static_site";
+  label: string;
+  /** "html" for static sites */
+  format: ProjectArchiveFormat | "html";
+  status: "queued" | "running" | "completed" | "failed" | "cancelled" | "interrupted";
+  done: number;
+  total: number;
+  error: string | null;
+  created_at: string;
+  started_at: string | null;
+  finished_at: string | null;
+}
+
+/** Recent export jobs, newest first */
+export function listExportJobs(limit?: number): Promise<ExportJob[]> {
+  return invoke<ExportJob[]>("list_export_jobs", { limit: limit ?? null });
+}
+
+/** The export's promise then rejects with a "cancelled" error */
+export function cancelExportJob(jobId: string): Promise<void> {
+  return invoke("cancel_export_job", { jobId });
+}
+
+/** `ExportEntry` from src-tauri/src/commands/export_history.rs */
+export interface ExportEntry {
+  id: string;
+  document_id: string | null;
+  format: "docx" | "pdf";
+  title: string;
+  /** The settings the export was made with */
+  options: Record<string, unknown>;
+  size_bytes: number;
+  created_at: string;
+  /** Whether the document has changed since; null when it isn't saved or was deleted */
+  content_changed: boolean | null;
+}
+
+/** DOCX and PDF exports, newest first */
+export function listExports(documentId?: string, limit?: number): Promise<ExportEntry[]> {
+  return invoke<ExportEntry[]>("list_exports", { documentId: documentId ?? null, limit: limit ?? null });
+}
+
+/** Export the entry's document as it is now, with the entry's title and settings */
+export function reExport(entryId: string): Promise<number[]> {
+  return invoke<number[]>("re_export", { entryId });
+}
+
+/** `DocumentMeta` from src-tauri/src/commands/export.rs */
+export interface ImportedDocument {
+  id: string;
+  title: string;
+  created_at: string;
+  updated_at: string;
+  word_count: number;
+  project_id: string | null;
+  status: string;
+  character_count: number;
+  archived_at: string | null;
+  target_words: number | null;
+  /** Percent of target_words written, past 100 once exceeded */
+  goal_progress: number | null;
+  /** Set while the document is pinned */
+  pinned_at: string | null;
+  /** Position within its project, from reorderDocuments */
+  sort_index: number | null;
+}
+
+/** Create a draft from a .docx; embedded images are copied into the library */
+export function importDocx(filePath: string, projectId?: string): Promise<ImportedDocument> {
+  return invoke<ImportedDocument>("import_docx", { filePath, projectId: projectId ?? null });
+}
+
+/** `EmailTheme` from src-tauri/src/commands/export.rs; colours are hex values */
+export interface EmailTheme {
+  accent_color: string;
+  text_color: string;
+  background_color: string;
+  content_background: string;
+  font: "sans" | "serif" | "mono";
+  logo_url: string | null;
+  /** Colour code blocks by their `language-*` class */
+  highlight_code: boolean;
+}
+
+/** Table-based HTML with inlined styles, for pasting into any ESP */
+export function exportEmailHtml(title: string, htmlContent: string, theme?: Partial<EmailTheme>): Promise<string> {
+  return invoke<string>("export_email_html", { title, htmlContent, theme: theme ?? null });
+}
+
+/** Wrapped plain text with link URLs as footnotes; `width` 0 disables wrapping */
+export function exportPlaintext(htmlContent: string, width?: number): Promise<string> {
+  return invoke<string>("export_plaintext", { htmlContent, width: width ?? null });
+}
+
+/** `PdfExportOptions` from src-tauri/src/commands/export.rs */
+export interface PdfExportOptions {
+  header_text?: string;
+  footer_text?: string;
+  page_numbers?: boolean;
+  highlight_code?: boolean;
+  /** Title page with the subtitle, author, date and logo; no header or number */
+  cover?: boolean;
+  author?: string;
+  subtitle?: string;
+  /** Shown as given; today's date when unset */
+  date?: string;
+  /** An image file, or the name of one in the image library */
+  logo_path?: string;
+  keywords?: string[];
+  /** Contents page of the H1–H3 headings, linked, and the same as bookmarks */
+  toc?: boolean;
+  /** A4 by default; `page_width_mm` and `page_height_mm` together set a custom size */
+  page_size?: "a4" | "letter" | "legal";
+  page_width_mm?: number;
+  page_height_mm?: number;
+  /** In mm; 25 on each side unset */
+  margins?: Partial<{ top: number; right: number; bottom: number; left: number }>;
+}
+
+/** `ExportPreview` from src-tauri/src/commands/export.rs */
+export interface ExportPreview {
+  /** PNG bytes of the first page */
+  image: number[];
+  width: number;
+  height: number;
+  /** Pages in the full export */
+  page_count: number;
+}
+
+/**
+ * First page of a PDF or DOCX export as a PNG, to show before the save
+ * dialog. `font` and `options` are the ones `export_pdf` takes.
+ */
+export function previewExport(
+  htmlContent: string,
+  format: "pdf" | "docx",
+  options?: {
+    title?: string;
+    font?: { family?: string; regular_path?: string; bold_path?: string; italic_path?: string; bold_italic_path?: string };
+    pdf?: PdfExportOptions;
+    width?: number;
+  },
+): Promise<ExportPreview> {
+  return invoke<ExportPreview>("preview_export", {
+    htmlContent,
+    format,
+    title: options?.title ?? null,
+    font: options?.font ?? null,
+    options: options?.pdf ?? null,
+    width: options?.width ?? null,
+  });
+}
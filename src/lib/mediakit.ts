@@ -0,0 +1,37 @@
+import { invoke } from "@tauri-apps/api/core";
+
+/** `MediaKitField` from src-tauri/src/commands/mediakit.rs, e.g. "Ages 25–34" / "41%" */
+export interface MediaKitField {
+  label: string;
+  value: string;
+}
+
+/** `MediaKitSettings` from src-tauri/src/commands/mediakit.rs */
+export interface MediaKitSettings {
+  /** Heads the kit as "<name> media kit" */
+  publication_name: string;
+  tagline: string;
+  demographics: MediaKitField[];
+  /** Sponsorship placements and prices */
+  rates: MediaKitField[];
+  contact_email: string;
+  /** Testimonials quoted, best first; 4 when unset, 0 to 20 */
+  testimonial_count: number | null;
+}
+
+export function getMediaKitSettings(): Promise<MediaKitSettings> {
+  return invoke<MediaKitSettings>("get_media_kit_settings");
+}
+
+export function saveMediaKitSettings(settings: MediaKitSettings): Promise<void> {
+  return invoke("save_media_kit_settings", { settings });
+}
+
+/**
+ * A sponsor media kit as PDF bytes: audience, growth and open-rate charts,
+ * the demographics and rates from settings, and the best testimonials
+ * (only `projectId`'s, if given). `months` of growth, 12 by default.
+ */
+export function generateMediaKitPdf(projectId?: string, months?: number): Promise<number[]> {
+  return invoke<number[]>("generate_media_kit_pdf", { projectId: projectId ?? null, months: months ?? null });
+}
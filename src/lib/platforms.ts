@@ -46,6 +46,7 @@ export const PLATFORMS: PlatformDef[] = [
     fields: [
       { key: "subdomain", label: "Subdomain", placeholder: "yournewsletter (from yournewsletter.substack.com)", type: "text" },
       { key: "cookie", label: "Session Cookie (optional)", placeholder: "substack.sid=...", type: "textarea" },
+      { key: "cookie_expires_at", label: "Cookie Expiry (optional)", placeholder: "2026-01-31 (shown next to substack.sid in your browser)", type: "text" },
       { key: "account_name", label: "Publication Name", placeholder: "My Substack", type: "text" },
       { key: "email", label: "Account Email", placeholder: "you@example.com", type: "text" },
     ],
@@ -77,7 +78,7 @@ export const PLATFORMS: PlatformDef[] = [
     authType: "api_key",
     category: "newsletter",
     fields: [
-      { key: "api_key", label: "Admin API Key", placeholder: "Your Ghost Admin API key", type: "password" },
+      { key: "api_key", label: "Admin API Key, Staff Token, or Content API Key", placeholder: "id:secret, or a 26-character Content API key (read-only)", type: "password" },
       { key: "api_url", label: "Site URL", placeholder: "https://yoursite.ghost.io", type: "text" },
       { key: "account_name", label: "Publication Name", placeholder: "My Ghost Blog", type: "text" },
       { key: "email", label: "Account Email", placeholder: "you@example.com", type: "text" },
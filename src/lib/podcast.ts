@@ -0,0 +1,73 @@
+import { invoke } from "@tauri-apps/api/core";
+
+export type EpisodeType = "full" | "trailer" | "bonus";
+
+/** `PodcastEpisode` from src-tauri/src/commands/podcast.rs */
+export interface PodcastEpisode {
+  document_id: string;
+  audio_attachment_id: string;
+  season: number | null;
+  episode_number: number | null;
+  episode_type: EpisodeType;
+  explicit: boolean;
+  duration_secs: number | null;
+  /** Hosted audio URL, set once published */
+  audio_url: string | null;
+  summary: string | null;
+  created_at: string;
+  updated_at: string;
+}
+
+export interface EpisodeInput {
+  audio_attachment_id: string;
+  season?: number | null;
+  episode_number?: number | null;
+  episode_type?: EpisodeType;
+  explicit?: boolean;
+  duration_secs?: number | null;
+  audio_url?: string | null;
+}
+
+export interface EpisodeEnclosure {
+  url: string;
+  length: number;
+  mime_type: string;
+  /** RSS `<item>` with iTunes tags */
+  item_xml: string;
+}
+
+export function fetchPodcastEpisode(documentId: string): Promise<PodcastEpisode | null> {
+  return invoke<PodcastEpisode | null>("get_podcast_episode", { documentId });
+}
+
+export function savePodcastEpisode(documentId: string, episode: EpisodeInput): Promise<PodcastEpisode> {
+  return invoke<PodcastEpisode>("set_podcast_episode", { documentId, episode });
+}
+
+export function removePodcastEpisode(documentId: string): Promise<void> {
+  return invoke("remove_podcast_episode", { documentId });
+}
+
+export function fetchEpisodeEnclosure(documentId: string): Promise<EpisodeEnclosure> {
+  return invoke<EpisodeEnclosure>("get_episode_enclosure", { documentId });
+}
+
+/** Upload the audio and publish the show notes; resolves to the post id. */
+export function publishPodcastEpisode(
+  documentId: string,
+  target: { platform: string; accountId: string; publicationId: string },
+  status: "draft" | "published",
+): Promise<string> {
+  return invoke<string>("publish_podcast_episode", {
+    documentId,
+    platform: target.platform,
+    accountId: target.accountId,
+    publicationId: target.publicationId,
+    status,
+  });
+}
+
+/** AI-written directory description, stored on the episode. */
+export function summarizePodcastEpisode(documentId: string, providerId: string): Promise<string> {
+  return invoke<string>("summarize_podcast_episode", { documentId, providerId });
+}
@@ -0,0 +1,39 @@
+import { invoke } from "@tauri-apps/api/core";
+
+/** Platforms a document can have a publish profile for */
+export type ProfilePlatform = "ghost" | "beehiiv" | "kit" | "substack";
+
+/**
+ * `PublishProfile` from src-tauri/src/commands/publish_profiles.rs. Ghost
+ * takes tags, a slug and a canonical URL; Beehiiv only tags; Kit and
+ * Substack none of them.
+ */
+export interface PublishProfile {
+  document_id: string;
+  platform: ProfilePlatform;
+  /** Ghost tags or Beehiiv content tags */
+  tags: string[];
+  slug: string | null;
+  /** Where the issue was first published */
+  canonical_url: string | null;
+  /** Instead of `canonical_url`: the document's latest published URL there */
+  canonical_platform: ProfilePlatform | null;
+  updated_at: string;
+}
+
+export function listPublishProfiles(documentId: string): Promise<PublishProfile[]> {
+  return invoke<PublishProfile[]>("list_publish_profiles", { documentId });
+}
+
+/**
+ * Create or replace a document's profile for one platform; it is applied
+ * every time the document is published there. Returns it as normalized
+ * (tags de-duplicated, slug lowercased and hyphenated).
+ */
+export function savePublishProfile(profile: Omit<PublishProfile, "updated_at">): Promise<PublishProfile> {
+  return invoke<PublishProfile>("save_publish_profile", { profile });
+}
+
+export function deletePublishProfile(documentId: string, platform: ProfilePlatform): Promise<void> {
+  return invoke("delete_publish_profile", { documentId, platform });
+}
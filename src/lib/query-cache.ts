@@ -0,0 +1,25 @@
+import { invoke } from "@tauri-apps/api/core";
+
+/**
+ * `CacheSettings` from src-tauri/src/query_cache.rs. Dashboard aggregates
+ * (audience, revenue, analytics) are reused until a table they read is
+ * written or their TTL passes.
+ */
+export interface CacheSettings {
+  enabled: boolean;
+  default_ttl_secs: number;
+  /** Per-command TTLs in seconds, e.g. `{ get_revenue_stats: 60 }`; 0 turns caching off */
+  ttls: Record<string, number>;
+}
+
+export function getCacheSettings(): Promise<CacheSettings> {
+  return invoke<CacheSettings>("get_cache_settings");
+}
+
+export function saveCacheSettings(settings: CacheSettings): Promise<void> {
+  return invoke("save_cache_settings", { settings });
+}
+
+export function clearQueryCache(): Promise<void> {
+  return invoke("clear_query_cache");
+}
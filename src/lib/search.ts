@@ -0,0 +1,73 @@
+import { invoke } from "@tauri-apps/api/core";
+
+/** `DocumentSearchHit` from src-tauri/src/commands/export.rs */
+export interface DocumentSearchHit {
+  document: {
+    id: string;
+    title: string;
+    created_at: string;
+    updated_at: string;
+    word_count: number;
+    project_id: string | null;
+    status: string;
+    character_count: number;
+  };
+  /** Matched passage as escaped HTML, matches wrapped in `<mark>` */
+  snippet: string;
+}
+
+/** Full-text search over document titles and bodies, best matches first */
+export function searchDocuments(
+  query: string,
+  options?: { projectId?: string; limit?: number }
+): Promise<DocumentSearchHit[]> {
+  return invoke<DocumentSearchHit[]>("search_documents", {
+    query,
+    projectId: options?.projectId ?? null,
+    limit: options?.limit ?? null,
+  });
+}
+
+/** `FindScope` from src-tauri/src/commands/replace.rs; every field is optional */
+export interface FindScope {
+  project_id?: string | null;
+  /** Only these documents, when not empty */
+  document_ids?: string[];
+  include_archived?: boolean;
+  match_case?: boolean;
+  whole_word?: boolean;
+}
+
+/** `DocumentMatches` from src-tauri/src/commands/replace.rs */
+export interface DocumentMatches {
+  document_id: string;
+  title: string;
+  matches: number;
+  /** The first few matches with plain-text context */
+  previews: { before: string; matched: string; after: string }[];
+}
+
+/** `ReplaceResult` from src-tauri/src/commands/replace.rs */
+export interface ReplaceResult {
+  dry_run: boolean;
+  replacements: number;
+  documents: DocumentMatches[];
+}
+
+/** Exact-text matches across documents, for find and replace */
+export function findInDocuments(query: string, scope?: FindScope): Promise<DocumentMatches[]> {
+  return invoke<DocumentMatches[]>("find_in_documents", { query, scope: scope ?? null });
+}
+
+/**
+ * Replace `query` everywhere in scope. Run with `dryRun` first to preview;
+ * the real run takes a safety snapshot and versions each changed document.
+ */
+export function replaceInDocuments(
+  query: string,
+  replacement: string,
+  scope: FindScope | undefined,
+  dryRun: boolean,
+): Promise<ReplaceResult> {
+  return invoke<ReplaceResult>("replace_in_documents", { query, replacement, scope: scope ?? null, dryRun });
+}
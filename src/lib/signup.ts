@@ -0,0 +1,66 @@
+import { invoke } from "@tauri-apps/api/core";
+
+/** `SignupFormConfig` from src-tauri/src/commands/signup.rs */
+export interface SignupFormConfig {
+  title: string;
+  description: string | null;
+  button_label: string;
+  /** Tag given to confirmed subscribers */
+  source_tag: string;
+  /** Project whose brand kit styles the page */
+  project_id: string | null;
+  confirm_subject: string;
+}
+
+export interface PendingSignup {
+  email: string;
+  name: string | null;
+  source_tag: string;
+  created_at: string;
+  confirmed_at: string | null;
+}
+
+/** `ListenerSettings` from src-tauri/src/listener.rs */
+export interface ListenerSettings {
+  enabled: boolean;
+  port: number;
+  bind_address: string;
+  /** Externally reachable base URL, e.g. behind a tunnel */
+  public_url: string | null;
+}
+
+export interface ListenerInfo {
+  settings: ListenerSettings;
+  status: { running: boolean; port: number | null; base_url: string };
+}
+
+export function fetchSignupFormConfig(): Promise<SignupFormConfig> {
+  return invoke<SignupFormConfig>("get_signup_form_config");
+}
+
+export function saveSignupFormConfig(config: SignupFormConfig): Promise<void> {
+  return invoke("save_signup_form_config", { config });
+}
+
+/** Standalone form page HTML; pass `config` to preview unsaved changes */
+export function generateSignupForm(config?: SignupFormConfig): Promise<string> {
+  return invoke<string>("generate_signup_form", { config: config ?? null });
+}
+
+/** Write the form to `folder`/index.html; resolves to the file path */
+export function exportSignupForm(folder: string): Promise<string> {
+  return invoke<string>("export_signup_form", { folder });
+}
+
+export function fetchPendingSignups(includeConfirmed = false): Promise<PendingSignup[]> {
+  return invoke<PendingSignup[]>("list_pending_signups", { includeConfirmed });
+}
+
+export function fetchListenerInfo(): Promise<ListenerInfo> {
+  return invoke<ListenerInfo>("get_listener_info");
+}
+
+/** Save and start, restart or stop the listener to match */
+export function saveListenerSettings(settings: ListenerSettings): Promise<ListenerInfo> {
+  return invoke<ListenerInfo>("save_listener_settings", { settings });
+}
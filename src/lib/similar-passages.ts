@@ -0,0 +1,47 @@
+import { invoke } from "@tauri-apps/api/core";
+
+/** `SimilarPassage` from src-tauri/src/commands/similarity.rs */
+export interface SimilarPassage {
+  paragraph_index: number;
+  excerpt: string;
+  match_document_id: string;
+  match_title: string;
+  match_published_at: string | null;
+  match_paragraph_index: number;
+  match_excerpt: string;
+  /** Estimated similarity, 0-1 */
+  similarity: number;
+}
+
+/** Paragraphs that closely repeat something already published. */
+export function findSimilarPassages(documentId: string, threshold?: number): Promise<SimilarPassage[]> {
+  return invoke<SimilarPassage[]>("find_similar_passages", {
+    documentId,
+    threshold: threshold ?? null,
+  });
+}
+
+/** `RelatedDocument` from src-tauri/src/commands/similarity.rs */
+export interface RelatedDocument {
+  document_id: string;
+  title: string;
+  published_at: string | null;
+  url: string | null;
+  /** Cosine similarity, 0-1 */
+  score: number;
+  shared_terms: string[];
+}
+
+export interface RelatedSuggestions {
+  documents: RelatedDocument[];
+  /** "Further reading" block for the suggestions with a URL */
+  html: string;
+}
+
+/** Previously published issues related to this one, best first. */
+export function suggestRelatedDocuments(documentId: string, limit?: number): Promise<RelatedSuggestions> {
+  return invoke<RelatedSuggestions>("suggest_related_documents", {
+    documentId,
+    limit: limit ?? null,
+  });
+}
@@ -0,0 +1,34 @@
+import { invoke } from "@tauri-apps/api/core";
+
+export type SiteTheme = "classic" | "dark" | "editorial";
+
+/** `StaticSite` from src-tauri/src/commands/site.rs */
+export interface StaticSite {
+  out_dir: string;
+  posts: number;
+  /** Local images copied into assets/ */
+  images: number;
+}
+
+/**
+ * Write a project's published issues to `outDir` as a static website
+ * (index, post pages, feed.xml, sitemap.xml). `baseUrl` is where the site
+ * will be hosted; without it the feed and sitemap links are relative.
+ * Runs on the export queue, so `jobId` can be followed and cancelled like
+ * an archive export.
+ */
+export function generateStaticSite(
+  projectId: string,
+  theme: SiteTheme,
+  outDir: string,
+  baseUrl?: string,
+  jobId?: string,
+): Promise<StaticSite> {
+  return invoke<StaticSite>("generate_static_site", {
+    projectId,
+    theme,
+    outDir,
+    baseUrl: baseUrl ?? null,
+    jobId: jobId ?? null,
+  });
+}
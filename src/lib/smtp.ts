@@ -0,0 +1,95 @@
+import { invoke } from "@tauri-apps/api/core";
+
+/** `SmtpSettings` from src-tauri/src/commands/smtp.rs */
+export interface SmtpSettings {
+  host: string;
+  port: number;
+  security: "starttls" | "tls" | "none";
+  username: string;
+  from_email: string;
+  from_name: string | null;
+  reply_to: string | null;
+  messages_per_minute: number;
+  /** Unsubscribe page, given the token as `?token=` or in place of `{token}` */
+  unsubscribe_url: string | null;
+}
+
+export interface SmtpSend {
+  id: string;
+  document_id: string;
+  subject: string;
+  status: "sending" | "sent" | "partial" | "failed";
+  total: number;
+  sent: number;
+  failed: number;
+  started_at: string;
+  finished_at: string | null;
+}
+
+export interface SmtpLogEntry {
+  email: string;
+  status: "sent" | "failed";
+  error: string | null;
+  sent_at: string;
+}
+
+/** Payload of the "smtp:progress" event */
+export interface SmtpProgress {
+  send_id: string;
+  total: number;
+  sent: number;
+  failed: number;
+  done: boolean;
+}
+
+export interface SendOptions {
+  subject?: string;
+  /** Only subscribers with this tag */
+  tag?: string;
+  blockIds?: string[];
+}
+
+export function fetchSmtpSettings(): Promise<SmtpSettings> {
+  return invoke<SmtpSettings>("get_smtp_settings");
+}
+
+/** `password` is kept in the credential store, not settings */
+export function saveSmtpSettings(settings: SmtpSettings, password?: string): Promise<void> {
+  return invoke("save_smtp_settings", { settings, password: password ?? null });
+}
+
+export function testSmtpConnection(): Promise<boolean> {
+  return invoke<boolean>("test_smtp_connection");
+}
+
+export function sendSmtpTest(documentId: string, to: string, options?: SendOptions): Promise<void> {
+  return invoke("send_smtp_test", {
+    documentId,
+    to,
+    subject: options?.subject ?? null,
+    blockIds: options?.blockIds ?? null,
+  });
+}
+
+/** Start sending; resolves to the send id, progress follows on "smtp:progress" */
+export function sendIssueSmtp(documentId: string, options?: SendOptions): Promise<string> {
+  return invoke<string>("send_issue_smtp", {
+    documentId,
+    subject: options?.subject ?? null,
+    tag: options?.tag ?? null,
+    blockIds: options?.blockIds ?? null,
+  });
+}
+
+export function fetchSmtpSends(documentId?: string): Promise<SmtpSend[]> {
+  return invoke<SmtpSend[]>("list_smtp_sends", { documentId: documentId ?? null });
+}
+
+export function fetchSmtpSendLog(sendId: string): Promise<SmtpLogEntry[]> {
+  return invoke<SmtpLogEntry[]>("get_smtp_send_log", { sendId });
+}
+
+/** Suppress the address behind an unsubscribe token; resolves to it */
+export function processUnsubscribe(token: string): Promise<string> {
+  return invoke<string>("process_unsubscribe", { token });
+}
@@ -0,0 +1,47 @@
+import { invoke } from "@tauri-apps/api/core";
+
+/** `Snippet` from src-tauri/src/commands/snippets.rs */
+export interface Snippet {
+  id: string;
+  name: string;
+  /** Typed in the editor to expand the snippet, e.g. "/signoff" */
+  shortcut: string | null;
+  html: string;
+  /** `{{placeholder}}` keys in the HTML, in order of first use */
+  placeholders: string[];
+  created_at: string;
+  updated_at: string;
+}
+
+/** `RenderedSnippet` from src-tauri/src/commands/snippets.rs */
+export interface RenderedSnippet {
+  html: string;
+  /** Placeholders with no value; they are left out of the HTML */
+  unresolved: string[];
+}
+
+/** Create a snippet, or overwrite the one with `id` */
+export function saveSnippet(name: string, html: string, shortcut?: string, id?: string): Promise<Snippet> {
+  return invoke<Snippet>("save_snippet", { id: id ?? null, name, html, shortcut: shortcut ?? null });
+}
+
+export function listSnippets(): Promise<Snippet[]> {
+  return invoke<Snippet[]>("list_snippets");
+}
+
+export function deleteSnippet(id: string): Promise<void> {
+  return invoke("delete_snippet", { id });
+}
+
+/**
+ * The snippet's HTML with its placeholders filled in, ready to insert.
+ * With `documentId` the document's title, date, brand and sponsor values
+ * are available as well.
+ */
+export function renderSnippet(
+  id: string,
+  values?: Record<string, string>,
+  documentId?: string,
+): Promise<RenderedSnippet> {
+  return invoke<RenderedSnippet>("render_snippet", { id, values: values ?? null, documentId: documentId ?? null });
+}
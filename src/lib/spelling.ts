@@ -0,0 +1,41 @@
+import { invoke } from "@tauri-apps/api/core";
+
+/** `SpellingIssue` from src-tauri/src/commands/spelling.rs; offsets index the JS string */
+export interface SpellingIssue {
+  start: number;
+  end: number;
+  word: string;
+  suggestions: string[];
+}
+
+/** `DictionaryInfo` from src-tauri/src/commands/spelling.rs */
+export interface DictionaryInfo {
+  language: string;
+  user_words: number;
+}
+
+/** `language` defaults to the spellcheck.language setting, then en_US */
+export function checkSpelling(text: string, language?: string): Promise<SpellingIssue[]> {
+  return invoke<SpellingIssue[]>("check_spelling", { text, language: language ?? null });
+}
+
+/** Install a Hunspell .aff/.dic pair, e.g. from LibreOffice's dictionaries */
+export function installDictionary(language: string, affPath: string, dicPath: string): Promise<DictionaryInfo> {
+  return invoke<DictionaryInfo>("install_dictionary", { language, affPath, dicPath });
+}
+
+export function listDictionaries(): Promise<DictionaryInfo[]> {
+  return invoke<DictionaryInfo[]>("list_dictionaries");
+}
+
+export function addToDictionary(word: string, language?: string): Promise<void> {
+  return invoke("add_to_dictionary", { word, language: language ?? null });
+}
+
+export function removeFromDictionary(word: string, language?: string): Promise<void> {
+  return invoke("remove_from_dictionary", { word, language: language ?? null });
+}
+
+export function listUserDictionary(language?: string): Promise<string[]> {
+  return invoke<string[]>("list_user_dictionary", { language: language ?? null });
+}
@@ -0,0 +1,130 @@
+import { invoke } from "@tauri-apps/api/core";
+
+/** Backend setting keys from src-tauri/src/commands/storage.rs */
+export const SNAPSHOT_INTERVAL_SETTING = "storage.snapshot_interval_minutes";
+export const MAX_VERSIONS_SETTING = "storage.max_versions";
+export const MAX_DB_MB_SETTING = "storage.max_db_mb";
+export const RETENTION_SETTING = "storage.retention";
+
+/** `StorageBreakdown` from src-tauri/src/commands/storage.rs */
+export interface StorageBreakdown {
+  database_bytes: number;
+  documents_bytes: number;
+  versions_bytes: number;
+  version_count: number;
+  images_bytes: number;
+  image_count: number;
+  attachments_bytes: number;
+  attachment_count: number;
+  cache_bytes: number;
+  log_bytes: number;
+  budget_bytes: number | null;
+  over_budget: boolean;
+}
+
+export type PruneTarget = "versions" | "activity" | "caches" | "images";
+
+export interface PruneResult {
+  target: PruneTarget;
+  removed: number;
+  bytes_freed: number;
+}
+
+export interface StorageSettings {
+  snapshotIntervalMinutes: number;
+  maxVersions: number;
+  /** null means no budget */
+  maxDbMb: number | null;
+}
+
+export function fetchStorageBreakdown(): Promise<StorageBreakdown> {
+  return invoke<StorageBreakdown>("get_storage_breakdown");
+}
+
+/**
+ * Free one category. `keep` is versions per document for "versions" and
+ * days of history for "activity"; the backend defaults apply otherwise.
+ */
+export function pruneStorage(target: PruneTarget, keep?: number): Promise<PruneResult> {
+  return invoke<PruneResult>("prune_storage", { target, keep: keep ?? null });
+}
+
+export async function fetchStorageSettings(): Promise<StorageSettings> {
+  const all = await invoke<Record<string, unknown>>("get_app_settings");
+  return {
+    snapshotIntervalMinutes: Number(all[SNAPSHOT_INTERVAL_SETTING] ?? 0),
+    maxVersions: Number(all[MAX_VERSIONS_SETTING] ?? 50),
+    maxDbMb: all[MAX_DB_MB_SETTING] ? Number(all[MAX_DB_MB_SETTING]) : null,
+  };
+}
+
+export function saveStorageSetting(key: string, value: number | null): Promise<void> {
+  return invoke("set_app_setting", { key, value });
+}
+
+export function formatBytes(bytes: number): string {
+  if (bytes < 1024) return `${bytes} B`;
+  if (bytes < 1024 * 1024) return `${(bytes / 1024).toFixed(1)} KB`;
+  return `${(bytes / (1024 * 1024)).toFixed(1)} MB`;
+}
+
+/** `RetentionPolicy` from src-tauri/src/commands/storage.rs; unset rules are off */
+export interface RetentionPolicy {
+  /** Drop subscribers unseen this long who are no longer active anywhere */
+  inactive_subscriber_months?: number | null;
+  /** Drop bounce and complaint events older than this */
+  subscriber_event_months?: number | null;
+  /** Thin older analytics snapshots to one per account per week */
+  weekly_snapshots_after_months?: number | null;
+  /** Drop analytics snapshots older than this */
+  max_snapshot_months?: number | null;
+}
+
+export interface RetentionResult {
+  subscribers: number;
+  subscriber_events: number;
+  snapshots_thinned: number;
+  snapshots_expired: number;
+}
+
+export function fetchRetentionPolicy(): Promise<RetentionPolicy> {
+  return invoke<RetentionPolicy>("get_retention_policy");
+}
+
+export function saveRetentionPolicy(policy: RetentionPolicy): Promise<void> {
+  return invoke("save_retention_policy", { policy });
+}
+
+/** Counts what `policy` (default: the saved one) would remove right now. */
+export function previewRetention(policy?: RetentionPolicy): Promise<RetentionResult> {
+  return invoke<RetentionResult>("preview_retention", { policy: policy ?? null });
+}
+
+/** Runs the saved policy now; it otherwise runs once a day. */
+export function applyRetentionNow(): Promise<RetentionResult> {
+  return invoke<RetentionResult>("apply_retention_now");
+}
+
+export const MAX_SAFETY_SNAPSHOTS_SETTING = "storage.max_safety_snapshots";
+
+/** `SafetySnapshot` from src-tauri/src/commands/snapshots.rs */
+export interface SafetySnapshot {
+  id: string;
+  /** "migration", "archive_import", "backfill", "restore", "subscriber_sync" or "rollback" */
+  reason: string;
+  created_at: string;
+  size_bytes: number;
+}
+
+/** Database copies taken before risky operations, newest first. */
+export function listSafetySnapshots(): Promise<SafetySnapshot[]> {
+  return invoke<SafetySnapshot[]>("list_safety_snapshots");
+}
+
+/**
+ * Puts the whole database back to `id`. The current state is snapshotted
+ * first; "database:rolled_back" fires once the app is on the restored copy.
+ */
+export function rollbackToSnapshot(id: string): Promise<SafetySnapshot> {
+  return invoke<SafetySnapshot>("rollback_to_snapshot", { id });
+}
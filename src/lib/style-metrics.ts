@@ -0,0 +1,33 @@
+import { invoke } from "@tauri-apps/api/core";
+
+/** `StyleMetrics` from src-tauri/src/commands/style.rs */
+export interface StyleMetrics {
+  document_id: string;
+  /** null for the current text */
+  version: number | null;
+  words: number;
+  sentences: number;
+  syllables: number;
+  flesch_kincaid_grade: number;
+  reading_ease: number;
+  passive_sentences: number;
+  /** 0-1 */
+  passive_ratio: number;
+  adverbs: number;
+  /** 0-1 */
+  adverb_density: number;
+  avg_sentence_length: number;
+  longest_sentence: number;
+  sentence_lengths: { label: string; sentences: number }[];
+}
+
+/**
+ * Saved-text style metrics for a document, or for one of its versions.
+ * The Insights tab's live scores come from readability.ts instead.
+ */
+export function fetchStyleMetrics(documentId: string, version?: number): Promise<StyleMetrics> {
+  return invoke<StyleMetrics>("get_style_metrics", {
+    documentId,
+    version: version ?? null,
+  });
+}
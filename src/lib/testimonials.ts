@@ -0,0 +1,87 @@
+import { invoke } from "@tauri-apps/api/core";
+
+export type TestimonialSource = "manual" | "email" | "twitter" | "mastodon" | "other";
+
+/** `Testimonial` from src-tauri/src/commands/testimonials.rs */
+export interface Testimonial {
+  id: string;
+  quote: string;
+  author_name: string;
+  author_handle: string | null;
+  source: TestimonialSource;
+  source_url: string | null;
+  /** The issue it is about */
+  document_id: string | null;
+  /** 1 to 5, set by the writer */
+  rating: number | null;
+  featured: boolean;
+  /** Only approved testimonials are offered by `getBestTestimonials` */
+  approved: boolean;
+  received_at: string;
+  created_at: string;
+  updated_at: string;
+}
+
+/** `TestimonialInput` from src-tauri/src/commands/testimonials.rs; `id` updates an existing one */
+export interface TestimonialInput {
+  id?: string;
+  quote: string;
+  author_name: string;
+  author_handle?: string;
+  source?: TestimonialSource;
+  source_url?: string;
+  document_id?: string;
+  rating?: number;
+  featured?: boolean;
+  approved?: boolean;
+  /** RFC 3339; now when unset */
+  received_at?: string;
+}
+
+/** `MentionImport` from src-tauri/src/commands/testimonials.rs */
+export interface MentionImport {
+  imported: number;
+  /** Already imported before */
+  skipped: number;
+  /** Tagged with an issue from a link to it */
+  tagged: number;
+}
+
+export function saveTestimonial(input: TestimonialInput): Promise<Testimonial> {
+  return invoke<Testimonial>("save_testimonial", { input });
+}
+
+/** Newest first; `approved: false` lists imported mentions still to review */
+export function listTestimonials(documentId?: string, approved?: boolean): Promise<Testimonial[]> {
+  return invoke<Testimonial[]>("list_testimonials", { documentId: documentId ?? null, approved: approved ?? null });
+}
+
+export function deleteTestimonial(id: string): Promise<void> {
+  return invoke("delete_testimonial", { id });
+}
+
+/**
+ * Import recent mentions from a connected Twitter or Mastodon account as
+ * unapproved testimonials. Mentions linking to a published issue are tagged
+ * with it; the rest with `documentId`, if given.
+ */
+export function importSocialMentions(
+  platform: "twitter" | "mastodon",
+  accountId: string,
+  documentId?: string,
+): Promise<MentionImport> {
+  return invoke<MentionImport>("import_social_mentions", { platform, accountId, documentId: documentId ?? null });
+}
+
+/** Approved testimonials for a landing page or sponsor deck, best first */
+export function getBestTestimonials(
+  limit?: number,
+  documentId?: string,
+  projectId?: string,
+): Promise<Testimonial[]> {
+  return invoke<Testimonial[]>("get_best_testimonials", {
+    limit: limit ?? null,
+    documentId: documentId ?? null,
+    projectId: projectId ?? null,
+  });
+}
@@ -0,0 +1,26 @@
+import { invoke } from "@tauri-apps/api/core";
+
+/** `UserTemplate` from src-tauri/src/commands/export.rs */
+export interface UserTemplate {
+  id: string;
+  name: string;
+  category: string;
+  width: number;
+  height: number;
+  /** SVG data URI drawn by the backend from `elements_json` */
+  thumbnail: string;
+  elements_json: string;
+  usage_count: number;
+  is_builtin: boolean;
+  created_at: string;
+  updated_at: string;
+}
+
+export function listUserTemplates(): Promise<UserTemplate[]> {
+  return invoke<UserTemplate[]>("list_user_templates");
+}
+
+/** Redraws every stored thumbnail; resolves to how many were updated. */
+export function regenerateThumbnails(): Promise<number> {
+  return invoke<number>("regenerate_thumbnails");
+}
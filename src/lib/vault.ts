@@ -0,0 +1,45 @@
+import { invoke } from "@tauri-apps/api/core";
+import { listen, type UnlistenFn } from "@tauri-apps/api/event";
+
+/** `VaultSyncConfig` from src-tauri/src/commands/vault.rs */
+export interface VaultSyncConfig {
+  enabled: boolean;
+  /** Absolute path of the folder to mirror into */
+  folder: string | null;
+  /** Only mirror this project's documents, and import new files into it */
+  project_id: string | null;
+  /** Workspace synced; set on save from the project or the active workspace */
+  workspace_id?: string | null;
+}
+
+/** `VaultSyncReport` from src-tauri/src/commands/vault.rs */
+export interface VaultSyncReport {
+  written: number;
+  updated: number;
+  imported: number;
+  removed: number;
+  conflicts: number;
+}
+
+export function fetchVaultSyncConfig(): Promise<VaultSyncConfig> {
+  return invoke<VaultSyncConfig>("get_vault_sync_config");
+}
+
+/** Changing the folder starts that folder's mirror afresh */
+export function saveVaultSyncConfig(config: VaultSyncConfig): Promise<void> {
+  return invoke("save_vault_sync_config", { config });
+}
+
+export function syncVaultNow(): Promise<VaultSyncReport> {
+  return invoke<VaultSyncReport>("sync_vault_now");
+}
+
+/** Mirrored file path (relative to the folder) by document id */
+export function listVaultFiles(): Promise<Record<string, string>> {
+  return invoke<Record<string, string>>("list_vault_files");
+}
+
+/** Fires after any sync pass that changed something */
+export function onVaultSynced(handler: (report: VaultSyncReport) => void): Promise<UnlistenFn> {
+  return listen<VaultSyncReport>("vault:synced", (event) => handler(event.payload));
+}
@@ -0,0 +1,30 @@
+import { invoke } from "@tauri-apps/api/core";
+
+/**
+ * `PublishWebhook` from src-tauri/src/commands/webhooks.rs. POST to `url`
+ * with an `X-Signature-256: sha256=<hex HMAC-SHA256 of the body>` header
+ * to release the document's held posts.
+ */
+export interface PublishWebhook {
+  id: string;
+  document_id: string;
+  url: string;
+  /** Only set when the webhook is created */
+  secret: string | null;
+  created_at: string;
+  triggered_at: string | null;
+}
+
+/** Hold the document's pending scheduled posts until the webhook is called */
+export function createPublishWebhook(documentId: string): Promise<PublishWebhook> {
+  return invoke<PublishWebhook>("create_publish_webhook", { documentId });
+}
+
+export function listPublishWebhooks(documentId?: string): Promise<PublishWebhook[]> {
+  return invoke<PublishWebhook[]>("list_publish_webhooks", { documentId: documentId ?? null });
+}
+
+/** Remove a webhook; posts it still held publish at their scheduled time */
+export function deletePublishWebhook(id: string): Promise<void> {
+  return invoke("delete_publish_webhook", { id });
+}
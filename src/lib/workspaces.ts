@@ -0,0 +1,30 @@
+import { invoke } from "@tauri-apps/api/core";
+
+/** Emitted with the new workspace's id after `switchWorkspace` */
+export const WORKSPACE_CHANGED = "workspace:changed";
+
+/** `Workspace` from src-tauri/src/commands/workspaces.rs */
+export interface Workspace {
+  id: string;
+  name: string;
+  color: string;
+  created_at: string;
+  updated_at: string;
+  /** Whether lists and dashboards currently show this workspace */
+  active: boolean;
+  document_count: number;
+  subscriber_count: number;
+}
+
+export function listWorkspaces(): Promise<Workspace[]> {
+  return invoke<Workspace[]>("list_workspaces");
+}
+
+/** Adds an empty workspace; it isn't switched to. */
+export function createWorkspace(name: string, color?: string): Promise<Workspace> {
+  return invoke<Workspace>("create_workspace", { name, color: color ?? null });
+}
+
+export function switchWorkspace(id: string): Promise<Workspace> {
+  return invoke<Workspace>("switch_workspace", { id });
+}
@@ -1,6 +1,7 @@
 import { create } from "zustand";
 import { invoke } from "@tauri-apps/api/core";
 import type { PlatformId } from "@/lib/platforms";
+import { errorMessage } from "@/lib/error-handler";
 
 export interface Account {
   platform: PlatformId;
@@ -9,6 +10,7 @@ export interface Account {
   email: string;
   isConnected: boolean;
   publications: Publication[];
+  capabilities?: PlatformCapabilities;
 }
 
 export interface Publication {
@@ -16,12 +18,36 @@ export interface Publication {
   name: string;
   url: string;
   platform: string;
-  subscriberCount: number | null;
+  subscriber_count: number | null;
   description: string | null;
 }
 
+export interface PlatformCapabilities {
+  platform: string;
+  credential_type: string;
+  can_publish: boolean;
+  drafts_only: boolean;
+  can_read_posts: boolean;
+  can_read_subscribers: boolean;
+  can_read_analytics: boolean;
+  read_only: boolean;
+  notes: string[];
+}
+
+/** One metric from `get_platform_usage`; limits are null when unset */
+export interface PlatformUsage {
+  platform: string;
+  account_id: string;
+  metric: "api_calls" | "posts" | "emails_sent";
+  today: number;
+  this_month: number;
+  day_limit: number | null;
+  month_limit: number | null;
+}
+
 interface AccountsState {
   accounts: Account[];
+  usage: PlatformUsage[];
   isLoading: boolean;
   error: string | null;
 
@@ -39,10 +65,16 @@ interface AccountsState {
     platform: PlatformId,
     accountId: string,
   ) => Promise<void>;
+  loadCapabilities: (
+    platform: PlatformId,
+    accountId: string,
+  ) => Promise<PlatformCapabilities | null>;
+  loadUsage: (platform?: PlatformId) => Promise<void>;
 }
 
 export const useAccountsStore = create<AccountsState>((set, get) => ({
   accounts: [],
+  usage: [],
   isLoading: false,
   error: null,
 
@@ -69,6 +101,24 @@ export const useAccountsStore = create<AccountsState>((set, get) => ({
 
       set({ accounts, isLoading: false });
 
+      // Cached publications come back without a network round trip
+      for (const acc of accounts) {
+        invoke<Publication[]>("get_publications", {
+          platform: acc.platform,
+          accountId: acc.accountId,
+        })
+          .then((publications) =>
+            set((state) => ({
+              accounts: state.accounts.map((a) =>
+                a.platform === acc.platform && a.accountId === acc.accountId
+                  ? { ...a, publications }
+                  : a,
+              ),
+            })),
+          )
+          .catch(() => {});
+      }
+
       // Test connections in background
       for (const acc of accounts) {
         get()
@@ -76,7 +126,7 @@ export const useAccountsStore = create<AccountsState>((set, get) => ({
           .catch(() => {});
       }
     } catch (e) {
-      set({ error: String(e), isLoading: false });
+      set({ error: errorMessage(e), isLoading: false });
     }
   },
 
@@ -91,6 +141,7 @@ export const useAccountsStore = create<AccountsState>((set, get) => ({
         apiKey = JSON.stringify({
           subdomain: fields.subdomain || "",
           cookie: fields.cookie || null,
+          cookie_expires_at: fields.cookie_expires_at || null,
         });
       }
       // For Ghost, build JSON config with api_url + api_key
@@ -135,7 +186,7 @@ export const useAccountsStore = create<AccountsState>((set, get) => ({
       // Test connection
       await get().testConnection(platform, accountId);
     } catch (e) {
-      set({ error: String(e), isLoading: false });
+      set({ error: errorMessage(e), isLoading: false });
     }
   },
 
@@ -148,7 +199,7 @@ export const useAccountsStore = create<AccountsState>((set, get) => ({
         ),
       }));
     } catch (e) {
-      set({ error: String(e) });
+      set({ error: errorMessage(e) });
     }
   },
 
@@ -180,7 +231,7 @@ export const useAccountsStore = create<AccountsState>((set, get) => ({
 
   refreshPublications: async (platform, accountId) => {
     try {
-      const pubs = await invoke<Publication[]>("get_publications", {
+      const pubs = await invoke<Publication[]>("refresh_publications", {
         platform,
         accountId,
       });
@@ -192,7 +243,38 @@ export const useAccountsStore = create<AccountsState>((set, get) => ({
         ),
       }));
     } catch (e) {
-      set({ error: String(e) });
+      set({ error: errorMessage(e) });
+    }
+  },
+
+  loadCapabilities: async (platform, accountId) => {
+    try {
+      const capabilities = await invoke<PlatformCapabilities>(
+        "get_platform_capabilities",
+        { platform, accountId },
+      );
+      set((state) => ({
+        accounts: state.accounts.map((a) =>
+          a.platform === platform && a.accountId === accountId
+            ? { ...a, capabilities }
+            : a,
+        ),
+      }));
+      return capabilities;
+    } catch (e) {
+      set({ error: errorMessage(e) });
+      return null;
+    }
+  },
+
+  loadUsage: async (platform) => {
+    try {
+      const usage = await invoke<PlatformUsage[]>("get_platform_usage", {
+        platform: platform ?? null,
+      });
+      set({ usage });
+    } catch (e) {
+      set({ error: errorMessage(e) });
     }
   },
 }));
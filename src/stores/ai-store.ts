@@ -1,5 +1,6 @@
 import { create } from "zustand";
 import { invoke } from "@tauri-apps/api/core";
+import { errorMessage } from "@/lib/error-handler";
 
 // ─── Types ───
 
@@ -199,7 +200,7 @@ export const useAiStore = create<AiState>((set, get) => ({
         activeProviderId: active?.id || (providers.length > 0 ? providers[0].id : null),
       });
     } catch (e) {
-      set({ error: String(e) });
+      set({ error: errorMessage(e) });
     }
   },
 
@@ -210,7 +211,7 @@ export const useAiStore = create<AiState>((set, get) => ({
       await get().loadProviders();
       set({ isLoading: false });
     } catch (e) {
-      set({ error: String(e), isLoading: false });
+      set({ error: errorMessage(e), isLoading: false });
       throw e;
     }
   },
@@ -220,7 +221,7 @@ export const useAiStore = create<AiState>((set, get) => ({
       await invoke("delete_ai_provider", { providerId: id });
       await get().loadProviders();
     } catch (e) {
-      set({ error: String(e) });
+      set({ error: errorMessage(e) });
     }
   },
 
@@ -252,7 +253,7 @@ export const useAiStore = create<AiState>((set, get) => ({
       set({ isStreaming: false });
       return response;
     } catch (e) {
-      set({ isStreaming: false, error: String(e) });
+      set({ isStreaming: false, error: errorMessage(e) });
       throw e;
     }
   },
@@ -319,7 +320,7 @@ export const useAiStore = create<AiState>((set, get) => ({
       });
     } catch (e) {
       set({ isStreaming: false });
-      onError(String(e));
+      onError(errorMessage(e));
     }
 
     // Return cleanup function
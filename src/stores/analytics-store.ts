@@ -1,6 +1,8 @@
 import { create } from "zustand";
 import { invoke } from "@tauri-apps/api/core";
 import type { PlatformId } from "@/lib/platforms";
+import { errorMessage } from "@/lib/error-handler";
+import { getFocusStats, type FocusStats } from "@/lib/focus";
 
 export interface AnalyticsData {
   total_subscribers: number;
@@ -20,8 +22,88 @@ export interface PostPerformance {
   platform: string;
 }
 
+export interface MetricDelta {
+  current: number;
+  previous: number;
+  change: number;
+  /** null when the previous period was zero */
+  change_pct: number | null;
+}
+
+export interface AnalyticsComparison {
+  range: { from: string; to: string };
+  previous_range: { from: string; to: string };
+  subscribers: MetricDelta;
+  open_rate: MetricDelta;
+  click_rate: MetricDelta;
+  revenue_cents: MetricDelta;
+  posts_published: MetricDelta;
+}
+
+export interface HeatmapDay {
+  date: string;
+  posts_published: number;
+  words_written: number;
+}
+
+export interface PublishingHeatmap {
+  year: number;
+  days: HeatmapDay[];
+  total_posts: number;
+  total_words: number;
+  max_posts: number;
+  max_words: number;
+  longest_streak: number;
+}
+
+/** `WritingDay` from src-tauri/src/commands/analytics.rs */
+export interface WritingDay {
+  date: string;
+  /** Net words; negative on a day spent cutting */
+  words: number;
+  documents: number;
+}
+
+/** `WritingStats` from src-tauri/src/commands/analytics.rs */
+export interface WritingStats {
+  days: WritingDay[];
+  total_words: number;
+  daily_average: number;
+  current_streak: number;
+  longest_streak: number;
+  busiest_days: WritingDay[];
+  busiest_weekday: string | null;
+}
+
+export interface DigestItem {
+  document_id: string;
+  title: string;
+  published_at: string;
+  url: string | null;
+  excerpt: string;
+  /** Opens + clicks from the latest snapshot, when matched */
+  engagement: number | null;
+}
+
+/** `Digest` from src-tauri/src/commands/analytics.rs */
+export interface Digest {
+  /** The new draft document */
+  document_id: string;
+  title: string;
+  from: string;
+  to: string;
+  items: DigestItem[];
+}
+
+/** "week" / "month" = last full week or month, or a "YYYY-MM" month */
+export type DigestPeriod = "week" | "month" | string;
+
 interface AnalyticsState {
   data: AnalyticsData | null;
+  comparison: AnalyticsComparison | null;
+  heatmap: PublishingHeatmap | null;
+  writingStats: WritingStats | null;
+  focusStats: FocusStats | null;
   isLoading: boolean;
   error: string | null;
   selectedPlatform: PlatformId | "all";
@@ -34,6 +116,15 @@ interface AnalyticsState {
     accountId: string,
     publicationId?: string,
   ) => Promise<void>;
+  /** Current date range vs the same-length period before it */
+  fetchComparison: () => Promise<void>;
+  fetchHeatmap: (year: number) => Promise<void>;
+  /** Words written per day over the current date range */
+  fetchWritingStats: () => Promise<void>;
+  /** Focus sessions started in the current date range */
+  fetchFocusStats: () => Promise<void>;
+  /** Draft an "in case you missed it" recap of the period's issues */
+  buildDigest: (period: DigestPeriod, projectId?: string) => Promise<Digest>;
   fetchAllAnalytics: (
     accounts: {
       platform: PlatformId;
@@ -43,8 +134,12 @@ interface AnalyticsState {
   ) => Promise<void>;
 }
 
-export const useAnalyticsStore = create<AnalyticsState>((set) => ({
+export const useAnalyticsStore = create<AnalyticsState>((set, get) => ({
   data: null,
+  comparison: null,
+  heatmap: null,
+  writingStats: null,
+  focusStats: null,
   isLoading: false,
   error: null,
   selectedPlatform: "all",
@@ -66,10 +161,57 @@ export const useAnalyticsStore = create<AnalyticsState>((set) => ({
       });
       set({ data: result, isLoading: false });
     } catch (e) {
-      set({ error: String(e), isLoading: false });
+      set({ error: errorMessage(e), isLoading: false });
     }
   },
 
+  fetchComparison: async () => {
+    const { dateRange, selectedPlatform } = get();
+    try {
+      const comparison = await invoke<AnalyticsComparison>(
+        "get_analytics_comparison",
+        {
+          range: dateRange,
+          previousRange: null,
+          platform: selectedPlatform === "all" ? null : selectedPlatform,
+        },
+      );
+      set({ comparison });
+    } catch (e) {
+      set({ error: errorMessage(e) });
+    }
+  },
+
+  fetchHeatmap: async (year) => {
+    try {
+      const heatmap = await invoke<PublishingHeatmap>("get_publishing_heatmap", { year });
+      set({ heatmap });
+    } catch (e) {
+      set({ error: errorMessage(e) });
+    }
+  },
+
+  fetchWritingStats: async () => {
+    try {
+      const writingStats = await invoke<WritingStats>("get_writing_stats", { range: get().dateRange });
+      set({ writingStats });
+    } catch (e) {
+      set({ error: errorMessage(e) });
+    }
+  },
+
+  fetchFocusStats: async () => {
+    try {
+      const focusStats = await getFocusStats(get().dateRange);
+      set({ focusStats });
+    } catch (e) {
+      set({ error: errorMessage(e) });
+    }
+  },
+
+  buildDigest: (period, projectId) =>
+    invoke<Digest>("build_digest", { period, project: projectId ?? null }),
+
   fetchAllAnalytics: async (accounts) => {
     set({ isLoading: true, error: null });
     try {
@@ -124,7 +266,7 @@ export const useAnalyticsStore = create<AnalyticsState>((set) => ({
         isLoading: false,
       });
     } catch (e) {
-      set({ error: String(e), isLoading: false });
+      set({ error: errorMessage(e), isLoading: false });
     }
   },
 }));
@@ -0,0 +1,105 @@
+import { create } from "zustand";
+import { invoke } from "@tauri-apps/api/core";
+import { errorMessage } from "@/lib/error-handler";
+
+/** `Attachment` from src-tauri/src/commands/attachments.rs */
+export interface Attachment {
+  id: string;
+  document_id: string;
+  filename: string;
+  mime_type: string;
+  size: number;
+  path: string;
+  created_at: string;
+  /** Document holding the transcript, once transcribed */
+  transcript_document_id: string | null;
+}
+
+export type TranscriptionProvider = "openai" | "whisper_cpp";
+
+/** `TranscriptResult` from src-tauri/src/commands/transcription.rs */
+export interface TranscriptResult {
+  attachment_id: string;
+  document_id: string;
+  title: string;
+  provider: TranscriptionProvider;
+  language: string | null;
+  segments: { start_secs: number; end_secs: number; text: string }[];
+}
+
+interface AttachmentState {
+  /** Attachments of `documentId` */
+  attachments: Attachment[];
+  documentId: string | null;
+  isLoading: boolean;
+  error: string | null;
+
+  loadAttachments: (documentId: string) => Promise<void>;
+  attachFile: (documentId: string, filePath: string) => Promise<Attachment>;
+  deleteAttachment: (id: string) => Promise<void>;
+  /** Transcribe into a new draft document; resolves to its result */
+  transcribe: (id: string, provider: TranscriptionProvider, language?: string) => Promise<TranscriptResult>;
+}
+
+export const useAttachmentStore = create<AttachmentState>((set) => ({
+  attachments: [],
+  documentId: null,
+  isLoading: false,
+  error: null,
+
+  loadAttachments: async (documentId: string) => {
+    set({ isLoading: true, error: null, documentId });
+    try {
+      const attachments = await invoke<Attachment[]>("list_attachments", { documentId });
+      set({ attachments, isLoading: false });
+    } catch (e) {
+      set({ error: errorMessage(e), isLoading: false });
+    }
+  },
+
+  attachFile: async (documentId: string, filePath: string) => {
+    set({ isLoading: true, error: null });
+    try {
+      const attachment = await invoke<Attachment>("attach_file", { documentId, filePath });
+      set((state) => ({
+        attachments: state.documentId === documentId ? [...state.attachments, attachment] : state.attachments,
+        isLoading: false,
+      }));
+      return attachment;
+    } catch (e) {
+      set({ error: errorMessage(e), isLoading: false });
+      throw e;
+    }
+  },
+
+  deleteAttachment: async (id: string) => {
+    try {
+      await invoke("delete_attachment", { attachmentId: id });
+      set((state) => ({
+        attachments: state.attachments.filter((a) => a.id !== id),
+      }));
+    } catch (e) {
+      set({ error: errorMessage(e) });
+      throw e;
+    }
+  },
+
+  transcribe: async (id: string, provider: TranscriptionProvider, language?: string) => {
+    try {
+      const result = await invoke<TranscriptResult>("transcribe_audio", {
+        attachmentId: id,
+        provider,
+        language: language ?? null,
+      });
+      set((state) => ({
+        attachments: state.attachments.map((a) =>
+          a.id === id ? { ...a, transcript_document_id: result.document_id } : a
+        ),
+      }));
+      return result;
+    } catch (e) {
+      set({ error: errorMessage(e) });
+      throw e;
+    }
+  },
+}));
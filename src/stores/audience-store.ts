@@ -29,6 +29,23 @@ export interface Segment {
   color: string;
 }
 
+export interface BreakdownCount {
+  key: string;
+  count: number;
+  /** Fraction of the whole audience */
+  share: number;
+}
+
+export interface AudienceDemographics {
+  total: number;
+  /** Keyed "personal" | "corporate" | "education" | "government" */
+  domainTypes: BreakdownCount[];
+  topDomains: { domain: string; kind: string; count: number }[];
+  /** ISO country codes, from platforms that report them */
+  countries: BreakdownCount[];
+  unknownCountry: number;
+}
+
 interface AudienceState {
   subscribers: UnifiedSubscriber[];
   total: number;
@@ -36,6 +53,7 @@ interface AudienceState {
   perPage: number;
   stats: AudienceStats | null;
   segments: Segment[];
+  demographics: AudienceDemographics | null;
   isLoading: boolean;
   isSyncing: boolean;
   selectedSubscriber: UnifiedSubscriber | null;
@@ -48,6 +66,7 @@ interface AudienceState {
   fetchSubscribers: (page?: number) => Promise<void>;
   fetchStats: () => Promise<void>;
   fetchSegments: () => Promise<void>;
+  fetchDemographics: () => Promise<void>;
   syncAll: (accounts: { platform: string; accountId: string; publicationId?: string }[]) => Promise<void>;
   tagSubscribers: (ids: string[], tag: string) => Promise<void>;
   untagSubscribers: (ids: string[], tag: string) => Promise<void>;
@@ -60,6 +79,7 @@ export const useAudienceStore = create<AudienceState>((set, get) => ({
   perPage: 50,
   stats: null,
   segments: [],
+  demographics: null,
   isLoading: false,
   isSyncing: false,
   selectedSubscriber: null,
@@ -140,6 +160,23 @@ export const useAudienceStore = create<AudienceState>((set, get) => ({
     }
   },
 
+  fetchDemographics: async () => {
+    try {
+      const raw = await invoke<any>("get_audience_demographics");
+      set({
+        demographics: {
+          total: raw.total,
+          domainTypes: raw.domain_types,
+          topDomains: raw.top_domains,
+          countries: raw.countries,
+          unknownCountry: raw.unknown_country,
+        },
+      });
+    } catch {
+      // Ignore
+    }
+  },
+
   syncAll: async (accounts) => {
     set({ isSyncing: true });
     for (const acc of accounts) {
@@ -157,6 +194,7 @@ export const useAudienceStore = create<AudienceState>((set, get) => ({
     get().fetchSubscribers(1);
     get().fetchStats();
     get().fetchSegments();
+    get().fetchDemographics();
   },
 
   tagSubscribers: async (ids, tag) => {
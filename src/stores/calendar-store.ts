@@ -9,9 +9,12 @@ export interface ScheduledPost {
   publicationId: string | null;
   title: string;
   scheduledAt: string;
-  status: "pending" | "publishing" | "published" | "failed" | "cancelled";
+  status: "pending" | "publishing" | "published" | "failed" | "held" | "awaiting_approval" | "cancelled" | "unpublished" | "unpublish_failed";
   errorMessage: string | null;
   publishedUrl: string | null;
+  /** When the post is taken down again (Ghost, Beehiiv and Kit) */
+  unpublishAt: string | null;
+  unpublishedAt: string | null;
   createdAt: string;
   updatedAt: string;
 }
@@ -20,18 +23,51 @@ export interface CalendarEvent {
   id: string;
   title: string;
   date: string;
-  eventType: "scheduled" | "published" | "draft";
+  eventType: "scheduled" | "published" | "draft" | "projected" | "idea";
   platform: string | null;
   status: string;
   documentId: string | null;
 }
 
+export interface SchedulerPauseState {
+  paused: boolean;
+  paused_platforms: string[];
+}
+
+export type FailurePeriod = "24h" | "7d" | "30d" | "all";
+
+/** Which failed posts to retry; unset fields match everything */
+export interface FailureFilter {
+  platform?: string;
+  account_id?: string;
+  /** An error kind from the summary: "auth", "rate_limited", "network", ... */
+  error_kind?: string;
+  period?: FailurePeriod;
+  post_ids?: string[];
+}
+
+export interface FailureGroup {
+  platform: string;
+  error_kind: string;
+  count: number;
+  post_ids: string[];
+  latest_error: string | null;
+  latest_at: string;
+}
+
+export interface FailureSummary {
+  period: FailurePeriod;
+  total: number;
+  groups: FailureGroup[];
+}
+
 interface CalendarState {
   currentDate: Date;
   viewMode: "month" | "week";
   selectedDate: Date | null;
   events: CalendarEvent[];
   isLoading: boolean;
+  pause: SchedulerPauseState;
 
   navigate: (direction: -1 | 0 | 1) => void;
   setViewMode: (mode: "month" | "week") => void;
@@ -46,9 +82,20 @@ interface CalendarState {
     publicationId?: string;
     title: string;
     scheduledAt: string;
+    unpublishAt?: string;
   }) => Promise<void>;
   reschedulePost: (id: string, newDate: string) => Promise<void>;
+  /** Set or clear (null) when a post is taken down */
+  setUnpublishAt: (id: string, unpublishAt: string | null) => Promise<void>;
   cancelPost: (id: string) => Promise<void>;
+  fetchPauseState: () => Promise<void>;
+  /** Pause everything, or one platform when given */
+  pauseScheduler: (platform?: string) => Promise<void>;
+  resumeScheduler: (platform?: string) => Promise<void>;
+  /** Failed posts grouped by platform and error kind (default last 7 days) */
+  getFailureSummary: (period?: FailurePeriod) => Promise<FailureSummary>;
+  /** Requeue matching failed posts; resolves to their ids */
+  retryFailedPosts: (filter: FailureFilter) => Promise<string[]>;
 }
 
 export const useCalendarStore = create<CalendarState>((set, get) => ({
@@ -57,6 +104,7 @@ export const useCalendarStore = create<CalendarState>((set, get) => ({
   selectedDate: null,
   events: [],
   isLoading: false,
+  pause: { paused: false, paused_platforms: [] },
 
   navigate: (direction) => {
     const curr = get().currentDate;
@@ -129,6 +177,7 @@ export const useCalendarStore = create<CalendarState>((set, get) => ({
       publicationId: params.publicationId,
       title: params.title,
       scheduledAt: params.scheduledAt,
+      unpublishAt: params.unpublishAt ?? null,
     });
     const d = get().currentDate;
     get().fetchEvents(d.getFullYear(), d.getMonth() + 1);
@@ -140,9 +189,43 @@ export const useCalendarStore = create<CalendarState>((set, get) => ({
     get().fetchEvents(d.getFullYear(), d.getMonth() + 1);
   },
 
+  setUnpublishAt: async (id, unpublishAt) => {
+    await invoke("set_unpublish_at", { id, unpublishAt });
+    const d = get().currentDate;
+    get().fetchEvents(d.getFullYear(), d.getMonth() + 1);
+  },
+
   cancelPost: async (id) => {
     await invoke("cancel_scheduled_post", { id });
     const d = get().currentDate;
     get().fetchEvents(d.getFullYear(), d.getMonth() + 1);
   },
+
+  fetchPauseState: async () => {
+    try {
+      const pause = await invoke<SchedulerPauseState>("get_scheduler_pause_state");
+      set({ pause });
+    } catch {
+      // Ignore
+    }
+  },
+
+  pauseScheduler: async (platform) => {
+    const pause = await invoke<SchedulerPauseState>("pause_scheduler", { platform: platform ?? null });
+    set({ pause });
+  },
+
+  resumeScheduler: async (platform) => {
+    const pause = await invoke<SchedulerPauseState>("resume_scheduler", { platform: platform ?? null });
+    set({ pause });
+  },
+
+  getFailureSummary: (period) => invoke<FailureSummary>("get_failure_summary", { period: period ?? null }),
+
+  retryFailedPosts: async (filter) => {
+    const ids = await invoke<string[]>("retry_failed_posts", { filter });
+    const d = get().currentDate;
+    get().fetchEvents(d.getFullYear(), d.getMonth() + 1);
+    return ids;
+  },
 }));
@@ -0,0 +1,115 @@
+import { create } from "zustand";
+import { invoke } from "@tauri-apps/api/core";
+import { errorMessage } from "@/lib/error-handler";
+import type { SponsorStage } from "@/stores/sponsors-store";
+
+export type ContactRole = "sponsor" | "editor" | "guest";
+
+/** `Contact` from src-tauri/src/commands/contacts.rs */
+export interface Contact {
+  id: string;
+  name: string;
+  company: string | null;
+  email: string | null;
+  role: ContactRole;
+  notes: string;
+  created_at: string;
+  updated_at: string;
+}
+
+export interface SponsorshipDeal {
+  id: string;
+  contact_id: string;
+  title: string;
+  amount_cents: number;
+  currency: string;
+  status: SponsorStage;
+  document_id: string | null;
+  /** YYYY-MM-DD */
+  run_date: string | null;
+  notes: string;
+  created_at: string;
+  updated_at: string;
+}
+
+export interface ContactDetail {
+  contact: Contact;
+  deals: SponsorshipDeal[];
+  /** [currency, cents] per currency */
+  paid_cents: [string, number][];
+}
+
+export interface ContactInput {
+  name?: string;
+  company?: string | null;
+  email?: string | null;
+  role?: ContactRole;
+  notes?: string;
+}
+
+interface ContactsState {
+  contacts: Contact[];
+  isLoading: boolean;
+  error: string | null;
+
+  loadContacts: (filter?: { role?: ContactRole; query?: string }) => Promise<void>;
+  createContact: (input: ContactInput & { name: string }) => Promise<Contact>;
+  updateContact: (id: string, input: ContactInput) => Promise<Contact>;
+  deleteContact: (id: string) => Promise<void>;
+  getContact: (id: string) => Promise<ContactDetail>;
+}
+
+export const useContactsStore = create<ContactsState>((set) => ({
+  contacts: [],
+  isLoading: false,
+  error: null,
+
+  loadContacts: async (filter) => {
+    set({ isLoading: true, error: null });
+    try {
+      const contacts = await invoke<Contact[]>("list_contacts", {
+        role: filter?.role ?? null,
+        query: filter?.query ?? null,
+      });
+      set({ contacts, isLoading: false });
+    } catch (e) {
+      set({ error: errorMessage(e), isLoading: false });
+    }
+  },
+
+  createContact: async (input) => {
+    const contact = await invoke<Contact>("create_contact", {
+      name: input.name,
+      company: input.company ?? null,
+      email: input.email ?? null,
+      role: input.role ?? null,
+      notes: input.notes ?? null,
+    });
+    set((state) => ({
+      contacts: [...state.contacts, contact].sort((a, b) => a.name.localeCompare(b.name)),
+    }));
+    return contact;
+  },
+
+  updateContact: async (id, input) => {
+    const contact = await invoke<Contact>("update_contact", {
+      id,
+      name: input.name ?? null,
+      company: input.company ?? null,
+      email: input.email ?? null,
+      role: input.role ?? null,
+      notes: input.notes ?? null,
+    });
+    set((state) => ({
+      contacts: state.contacts.map((c) => (c.id === id ? contact : c)),
+    }));
+    return contact;
+  },
+
+  deleteContact: async (id) => {
+    await invoke("delete_contact", { id });
+    set((state) => ({ contacts: state.contacts.filter((c) => c.id !== id) }));
+  },
+
+  getContact: (id) => invoke<ContactDetail>("get_contact", { id }),
+}));
@@ -15,6 +15,19 @@ export interface Document {
   projectId: string | null;
   status: DocumentStatus;
   tags: string[];
+  /** Backend edit version last saved or loaded, sent as `expectedVersion` */
+  version?: number;
+}
+
+/** Document as returned by the `load_document` command */
+export interface StoredDocument {
+  id: string;
+  title: string;
+  content: string;
+  html_content: string;
+  created_at: string;
+  updated_at: string;
+  document_version: number;
 }
 
 export type SaveStatus = "idle" | "saving" | "saved" | "error";
@@ -42,6 +55,9 @@ interface EditorState {
   markClean: () => void;
   setSaveStatus: (status: SaveStatus) => void;
   setLastSavedAt: (time: string) => void;
+  setDocumentVersion: (version: number) => void;
+  /** Replace the current document with the stored copy (other window's save) */
+  openStoredDocument: (stored: StoredDocument) => void;
   setShowImportDialog: (show: boolean) => void;
 }
 
@@ -262,5 +278,42 @@ export const useEditorStore = create<EditorState>((set, get) => ({
   markClean: () => set({ isDirty: false }),
   setSaveStatus: (status) => set({ saveStatus: status }),
   setLastSavedAt: (time) => set({ lastSavedAt: time }),
+  setDocumentVersion: (version) =>
+    set((state) => ({ currentDocument: { ...state.currentDocument, version } })),
+  openStoredDocument: (stored) =>
+    set((state) => {
+      const existing =
+        state.currentDocument.id === stored.id
+          ? state.currentDocument
+          : state.documents.find((d) => d.id === stored.id);
+      let content: JSONContent | null = null;
+      try {
+        content = JSON.parse(stored.content);
+      } catch {
+        // Older saves stored HTML only
+      }
+      const doc: Document = {
+        ...(existing ?? createEmptyDocument()),
+        id: stored.id,
+        title: stored.title,
+        content,
+        htmlContent: stored.html_content,
+        createdAt: stored.created_at,
+        updatedAt: stored.updated_at,
+        version: stored.document_version,
+      };
+      return {
+        currentDocument: doc,
+        documents:
+          state.currentDocument.id === stored.id
+            ? state.documents
+            : [
+                ...state.documents.filter((d) => d.id !== stored.id),
+                state.currentDocument,
+              ],
+        isDirty: false,
+        saveStatus: "idle",
+      };
+    }),
   setShowImportDialog: (show) => set({ showImportDialog: show }),
 }));
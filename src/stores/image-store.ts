@@ -1,6 +1,7 @@
 import { create } from "zustand";
 import { invoke } from "@tauri-apps/api/core";
 import { convertFileSrc } from "@tauri-apps/api/core";
+import { errorMessage } from "@/lib/error-handler";
 
 export interface ImageEntry {
   id: string;
@@ -32,7 +33,7 @@ export const useImageStore = create<ImageState>((set, get) => ({
       const images = await invoke<ImageEntry[]>("list_images");
       set({ images, isLoading: false });
     } catch (e) {
-      set({ error: String(e), isLoading: false });
+      set({ error: errorMessage(e), isLoading: false });
     }
   },
 
@@ -46,7 +47,7 @@ export const useImageStore = create<ImageState>((set, get) => ({
       }));
       return entry;
     } catch (e) {
-      set({ error: String(e), isLoading: false });
+      set({ error: errorMessage(e), isLoading: false });
       throw e;
     }
   },
@@ -58,7 +59,7 @@ export const useImageStore = create<ImageState>((set, get) => ({
         images: state.images.filter((img) => img.id !== id),
       }));
     } catch (e) {
-      set({ error: String(e) });
+      set({ error: errorMessage(e) });
       throw e;
     }
   },
@@ -9,10 +9,64 @@ export interface Project {
   icon: string;
   sortOrder: number;
   documentCount: number;
+  defaults: ProjectDefaults;
+  archivedAt: string | null;
   createdAt: string;
   updatedAt: string;
 }
 
+/** `ProjectDefaults` from src-tauri/src/commands/export.rs, applied when scheduling */
+export interface ProjectDefaults {
+  targets: { platform: string; account_id: string; publication_id: string | null }[];
+  tags: string[];
+  brand: {
+    logo_url: string | null;
+    accent_color: string | null;
+    font_family: string | null;
+    /** Content blocks merged into every issue */
+    block_ids: string[];
+  };
+  seo: {
+    /** Appended to the post title for the meta title */
+    title_suffix: string | null;
+    meta_description: string | null;
+    og_image: string | null;
+  };
+}
+
+export interface ProjectDetail {
+  project: Project;
+  total_words: number;
+  published_count: number;
+  scheduled_count: number;
+  last_published_at: string | null;
+  avg_open_rate: number | null;
+  avg_click_rate: number | null;
+}
+
+const EMPTY_DEFAULTS: ProjectDefaults = {
+  targets: [],
+  tags: [],
+  brand: { logo_url: null, accent_color: null, font_family: null, block_ids: [] },
+  seo: { title_suffix: null, meta_description: null, og_image: null },
+};
+
+function toProject(raw: any): Project {
+  return {
+    id: raw.id,
+    name: raw.name,
+    description: raw.description,
+    color: raw.color,
+    icon: raw.icon,
+    sortOrder: raw.sort_order,
+    documentCount: raw.document_count,
+    defaults: raw.defaults ?? EMPTY_DEFAULTS,
+    archivedAt: raw.archived_at ?? null,
+    createdAt: raw.created_at,
+    updatedAt: raw.updated_at,
+  };
+}
+
 type FilterMode = "all" | "recent" | "drafts" | "scheduled" | "published" | string; // string = projectId
 
 interface ProjectsState {
@@ -21,9 +75,14 @@ interface ProjectsState {
   isLoading: boolean;
 
   setActiveFilter: (filter: FilterMode) => void;
-  fetchProjects: () => Promise<void>;
+  fetchProjects: (includeArchived?: boolean) => Promise<void>;
   createProject: (name: string, color?: string, icon?: string) => Promise<Project>;
-  updateProject: (id: string, updates: Partial<Pick<Project, "name" | "color" | "icon">>) => Promise<void>;
+  updateProject: (id: string, updates: Partial<Pick<Project, "name" | "color" | "icon" | "defaults">>) => Promise<void>;
+  getProjectDetail: (id: string) => Promise<ProjectDetail>;
+  /** Hide (or with `false`, restore) a project; its documents stay */
+  archiveProject: (id: string, archived?: boolean) => Promise<void>;
+  /** New project copying the template's look, defaults, presets and blocks */
+  createFromTemplate: (templateId: string, name: string) => Promise<Project>;
   deleteProject: (id: string) => Promise<void>;
   moveDocument: (documentId: string, projectId: string | null) => Promise<void>;
 }
@@ -35,23 +94,11 @@ export const useProjectsStore = create<ProjectsState>((set, get) => ({
 
   setActiveFilter: (filter) => set({ activeFilter: filter }),
 
-  fetchProjects: async () => {
+  fetchProjects: async (includeArchived) => {
     set({ isLoading: true });
     try {
-      const raw = await invoke<any[]>("list_projects");
-      set({
-        projects: raw.map((p) => ({
-          id: p.id,
-          name: p.name,
-          description: p.description,
-          color: p.color,
-          icon: p.icon,
-          sortOrder: p.sort_order,
-          documentCount: p.document_count,
-          createdAt: p.created_at,
-          updatedAt: p.updated_at,
-        })),
-      });
+      const raw = await invoke<any[]>("list_projects", { includeArchived: includeArchived ?? false });
+      set({ projects: raw.map(toProject) });
     } catch {
       // Ignore — may not be in Tauri
     } finally {
@@ -61,17 +108,7 @@ export const useProjectsStore = create<ProjectsState>((set, get) => ({
 
   createProject: async (name, color, icon) => {
     const raw = await invoke<any>("create_project", { name, color, icon });
-    const project: Project = {
-      id: raw.id,
-      name: raw.name,
-      description: raw.description,
-      color: raw.color,
-      icon: raw.icon,
-      sortOrder: raw.sort_order,
-      documentCount: raw.document_count,
-      createdAt: raw.created_at,
-      updatedAt: raw.updated_at,
-    };
+    const project = toProject(raw);
     set((s) => ({ projects: [...s.projects, project] }));
     return project;
   },
@@ -85,6 +122,27 @@ export const useProjectsStore = create<ProjectsState>((set, get) => ({
     }));
   },
 
+  getProjectDetail: async (id) => {
+    const raw = await invoke<any>("get_project_detail", { id });
+    return { ...raw, project: toProject(raw.project) };
+  },
+
+  archiveProject: async (id, archived = true) => {
+    const project = toProject(await invoke<any>("archive_project", { id, archived }));
+    set((s) => ({
+      projects: archived
+        ? s.projects.filter((p) => p.id !== id)
+        : s.projects.map((p) => (p.id === id ? project : p)),
+      activeFilter: archived && s.activeFilter === id ? "all" : s.activeFilter,
+    }));
+  },
+
+  createFromTemplate: async (templateId, name) => {
+    const project = toProject(await invoke<any>("create_project_from_template", { templateId, name }));
+    set((s) => ({ projects: [...s.projects, project] }));
+    return project;
+  },
+
   deleteProject: async (id) => {
     await invoke("delete_project", { id });
     set((s) => ({
@@ -1,7 +1,8 @@
 import { create } from "zustand";
 import { invoke } from "@tauri-apps/api/core";
 import type { PlatformId } from "@/lib/platforms";
-import { handleTauriError, withRetry } from "@/lib/error-handler";
+import { handleTauriError, isAppError, withRetry } from "@/lib/error-handler";
+import { useEditorStore } from "@/stores/editor-store";
 
 export interface PublishTarget {
   platform: PlatformId;
@@ -13,7 +14,7 @@ export interface PublishTarget {
   previewText: string;
   status: "draft" | "published";
   scheduledAt?: string | null;
-  publishStatus: "idle" | "publishing" | "success" | "error";
+  publishStatus: "idle" | "publishing" | "success" | "error" | "queued";
   resultMessage: string;
 }
 
@@ -114,6 +115,7 @@ export const usePublishStore = create<PublishState>((set, get) => ({
                 subtitle: target.subtitle || null,
                 preview_text: target.previewText || null,
                 status: target.status,
+                document_id: useEditorStore.getState().currentDocument.id,
               },
             }),
             1, // 1 retry
@@ -128,11 +130,13 @@ export const usePublishStore = create<PublishState>((set, get) => ({
           }));
         }
       } catch (e) {
+        // Offline: the backend queued the publish and will send it on reconnect
+        const queued = isAppError(e) && e.kind === "queued";
         const friendlyMsg = handleTauriError(e, `Publishing to ${target.accountName}`);
         set((state) => ({
           targets: state.targets.map((t) =>
             t.accountId === target.accountId
-              ? { ...t, publishStatus: "error", resultMessage: friendlyMsg }
+              ? { ...t, publishStatus: queued ? "queued" : "error", resultMessage: friendlyMsg }
               : t
           ),
         }));
@@ -166,6 +170,7 @@ export const usePublishStore = create<PublishState>((set, get) => ({
             subtitle: target.subtitle || null,
             preview_text: target.previewText || null,
             status: target.status,
+            document_id: useEditorStore.getState().currentDocument.id,
           },
         }),
         1,
@@ -179,11 +184,12 @@ export const usePublishStore = create<PublishState>((set, get) => ({
         ),
       }));
     } catch (e) {
+      const queued = isAppError(e) && e.kind === "queued";
       const friendlyMsg = handleTauriError(e, `Retry to ${target.accountName}`);
       set((state) => ({
         targets: state.targets.map((t) =>
           t.accountId === accountId
-            ? { ...t, publishStatus: "error", resultMessage: friendlyMsg }
+            ? { ...t, publishStatus: queued ? "queued" : "error", resultMessage: friendlyMsg }
             : t
         ),
       }));